@@ -3,12 +3,16 @@
 //! Compares: git2, gitoxide (gix), git CLI, and TypeScript
 //!
 //! Run with: cargo bench --features gitoxide
-//! Results are saved in target/criterion/ for comparison
+//! Results are saved in target/criterion/ for comparison; `gr dev bench-compare`
+//! checks a run against a saved baseline
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use gitgrip::cli::commands::sync::plan_sync;
 use gitgrip::core::manifest::{Manifest, RepoConfig};
 use gitgrip::core::repo::RepoInfo;
 use gitgrip::core::state::StateFile;
+use gitgrip::git::status::{get_ahead_behind, get_all_repo_status, get_repo_status};
+use gitgrip::git::STATUS_CACHE;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
@@ -116,6 +120,11 @@ fn bench_url_parse(c: &mut Criterion) {
         copyfile: None,
         linkfile: None,
         platform: None,
+        sparse: None,
+        depends_on: None,
+        revision: None,
+        env: None,
+        workspace: false,
     };
     let workspace = PathBuf::from("/home/user/workspace");
 
@@ -133,6 +142,11 @@ fn bench_url_parse_azure(c: &mut Criterion) {
         copyfile: None,
         linkfile: None,
         platform: None,
+        sparse: None,
+        depends_on: None,
+        revision: None,
+        env: None,
+        workspace: false,
     };
     let workspace = PathBuf::from("/home/user/workspace");
 
@@ -893,6 +907,229 @@ workspace:
     });
 }
 
+/// Benchmark status across a large synthetic workspace (gr status on 50 repos)
+fn bench_workspace_status(c: &mut Criterion) {
+    let temp = tempfile::TempDir::new().unwrap();
+    let workspace = temp.path().to_path_buf();
+
+    let mut yaml = String::from("repos:\n");
+    for i in 0..50 {
+        let name = format!("repo-{:02}", i);
+        let repo_path = workspace.join(&name);
+        fs::create_dir_all(&repo_path).unwrap();
+        for args in [
+            vec!["init", "-q", "-b", "main"],
+            vec!["config", "user.name", "Bench User"],
+            vec!["config", "user.email", "bench@example.com"],
+        ] {
+            Command::new("git")
+                .args(&args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+        }
+        fs::write(repo_path.join("README.md"), format!("# {}", name)).unwrap();
+        Command::new("git")
+            .args(["add", "README.md"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-q", "-m", "Initial commit"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        // Leave every other repo dirty
+        if i % 2 == 0 {
+            fs::write(repo_path.join("scratch.txt"), "wip").unwrap();
+        }
+
+        yaml.push_str(&format!(
+            "  {name}:\n    url: git@github.com:org/{name}.git\n    path: {name}\n"
+        ));
+    }
+
+    let manifest = Manifest::parse(&yaml).unwrap();
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, &workspace))
+        .collect();
+
+    let mut group = c.benchmark_group("workspace_status_50_repos");
+    group.sample_size(10);
+
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            STATUS_CACHE.clear();
+            let statuses: Vec<_> = repos.iter().map(get_repo_status).collect();
+            black_box(statuses)
+        })
+    });
+
+    group.bench_function("worker_pool", |b| {
+        b.iter(|| {
+            STATUS_CACHE.clear();
+            black_box(get_all_repo_status(&repos))
+        })
+    });
+
+    group.finish();
+    drop(temp);
+}
+
+/// Ahead/behind on a deep history: cold walk vs. the commit-pair cache
+fn bench_ahead_behind_cache(c: &mut Criterion) {
+    let temp = tempfile::TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp.path()).unwrap();
+    let sig = git2::Signature::now("Bench User", "bench@example.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+
+    // 5000 commits on HEAD; `base` stays at the first one
+    let mut parent = repo
+        .commit(Some("HEAD"), &sig, &sig, "Commit 0", &tree, &[])
+        .unwrap();
+    repo.branch("base", &repo.find_commit(parent).unwrap(), false)
+        .unwrap();
+    for i in 1..5000 {
+        let parent_commit = repo.find_commit(parent).unwrap();
+        parent = repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                &format!("Commit {}", i),
+                &tree,
+                &[&parent_commit],
+            )
+            .unwrap();
+    }
+
+    let path = temp.path().to_path_buf();
+    let mut group = c.benchmark_group("ahead_behind_deep_history");
+
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            STATUS_CACHE.clear();
+            black_box(get_ahead_behind(&path, "base"))
+        })
+    });
+
+    group.bench_function("cached", |b| {
+        STATUS_CACHE.clear();
+        get_ahead_behind(&path, "base");
+        b.iter(|| black_box(get_ahead_behind(&path, "base")))
+    });
+
+    group.finish();
+}
+
+/// Workspace sizes for the scale scenarios
+const SCENARIO_SIZES: [usize; 3] = [10, 50, 200];
+
+/// A synthetic workspace of `count` repos with a manifest the `gr` binary can
+/// load; every third repo has an uncommitted file
+fn setup_scenario_workspace(count: usize) -> (tempfile::TempDir, String) {
+    let temp = tempfile::TempDir::new().unwrap();
+    let workspace = temp.path();
+    let signature = git2::Signature::now("Bench User", "bench@example.com").unwrap();
+
+    let mut yaml = String::from("repos:\n");
+    for i in 0..count {
+        let name = format!("repo-{:03}", i);
+        let repo_path = workspace.join(&name);
+        let repo = git2::Repository::init(&repo_path).unwrap();
+        fs::write(repo_path.join("README.md"), format!("# {}", name)).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+        if i % 3 == 0 {
+            fs::write(repo_path.join("scratch.txt"), "wip").unwrap();
+        }
+
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        yaml.push_str(&format!(
+            "  {name}:\n    url: git@github.com:org/{name}.git\n    path: {name}\n    default_branch: {branch}\n"
+        ));
+    }
+
+    let manifests = workspace.join(".gitgrip").join("manifests");
+    fs::create_dir_all(&manifests).unwrap();
+    fs::write(manifests.join("manifest.yaml"), &yaml).unwrap();
+    (temp, yaml)
+}
+
+/// Run the `gr` binary in `workspace`, discarding its output
+fn run_gr(workspace: &std::path::Path, args: &[&str]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_gr"))
+        .args(args)
+        .current_dir(workspace)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "gr {:?} failed", args);
+}
+
+/// End-to-end scenarios on 10, 50, and 200 synthetic repos: `gr status`,
+/// intercepted `gr forall`, and sync planning
+///
+/// Compare runs with `gr dev bench-compare`.
+fn bench_scale_scenarios(c: &mut Criterion) {
+    let workspaces: Vec<_> = SCENARIO_SIZES
+        .iter()
+        .map(|&count| (count, setup_scenario_workspace(count)))
+        .collect();
+
+    let mut group = c.benchmark_group("scale_status");
+    group.sample_size(10);
+    for (count, (temp, _)) in &workspaces {
+        group.bench_with_input(BenchmarkId::new("gr_status", count), temp, |b, temp| {
+            b.iter(|| run_gr(temp.path(), &["status"]))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("scale_forall");
+    group.sample_size(10);
+    for (count, (temp, _)) in &workspaces {
+        group.bench_with_input(
+            BenchmarkId::new("intercepted_git_status", count),
+            temp,
+            |b, temp| b.iter(|| run_gr(temp.path(), &["forall", "-c", "git status --porcelain"])),
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("scale_sync_plan");
+    for (count, (temp, yaml)) in &workspaces {
+        let workspace = temp.path().to_path_buf();
+        group.bench_with_input(BenchmarkId::new("plan", count), yaml, |b, yaml| {
+            b.iter(|| {
+                let manifest = Manifest::parse(black_box(yaml)).unwrap();
+                let repos: Vec<RepoInfo> = manifest
+                    .repos
+                    .iter()
+                    .filter_map(|(name, config)| RepoInfo::from_config(name, config, &workspace))
+                    .collect();
+                black_box(plan_sync(&repos))
+            })
+        });
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_manifest_parse,
@@ -911,6 +1148,10 @@ criterion_group!(
     bench_forall_command,
     bench_multi_repo_status,
     bench_manifest_and_repos,
+    bench_workspace_status,
+    bench_ahead_behind_cache,
+    // Multi-repo scale scenarios
+    bench_scale_scenarios,
 );
 
 criterion_main!(benches);
@@ -0,0 +1,38 @@
+/*
+ * gitgrip C API
+ *
+ * Build the shared library with:
+ *   cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * All strings are NUL-terminated UTF-8. Functions returning `char *` return a
+ * JSON envelope, either {"ok": <value>} or {"error": "<message>"}, which must
+ * be released with gitgrip_string_free().
+ */
+
+#ifndef GITGRIP_H
+#define GITGRIP_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Parse and validate manifest YAML; "ok" holds the manifest as JSON. */
+char *gitgrip_manifest_parse(const char *yaml);
+
+/* Status of every repo in the workspace containing `path`. */
+char *gitgrip_status(const char *path);
+
+/* Clone missing repos and pull existing ones in the workspace containing `path`. */
+char *gitgrip_sync(const char *path);
+
+/* Library version. Static; do not free. */
+const char *gitgrip_version(void);
+
+/* Release a string returned by this library. Accepts NULL. */
+void gitgrip_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GITGRIP_H */
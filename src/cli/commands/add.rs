@@ -1,10 +1,12 @@
 //! Add command implementation
 
+use crate::cli::i18n::msg;
 use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
 use crate::git::cache::invalidate_status_cache;
 use crate::git::{get_workdir, open_repo, path_exists};
+use crate::util::profile::ProfiledCommand;
 use git2::Repository;
 use std::path::PathBuf;
 use std::process::Command;
@@ -15,7 +17,7 @@ pub fn run_add(
     manifest: &Manifest,
     files: &[String],
 ) -> anyhow::Result<()> {
-    Output::header("Checking repositories for changes to stage...");
+    Output::header(&msg!("add.checking"));
     println!();
 
     let repos: Vec<RepoInfo> = manifest
@@ -36,7 +38,7 @@ pub fn run_add(
             Ok(git_repo) => {
                 let staged = stage_files(&git_repo, &repo.absolute_path, files)?;
                 if staged > 0 {
-                    Output::success(&format!("{}: staged {} file(s)", repo.name, staged));
+                    Output::success(&msg!("add.staged", repo = repo.name, count = staged));
                     total_staged += staged;
                     repos_with_changes += 1;
                     invalidate_status_cache(&repo.absolute_path);
@@ -49,11 +51,15 @@ pub fn run_add(
     println!();
     if total_staged > 0 {
         println!(
-            "Staged {} file(s) in {} repository(s).",
-            total_staged, repos_with_changes
+            "{}",
+            msg!(
+                "add.summary",
+                files = total_staged,
+                repos = repos_with_changes
+            )
         );
     } else {
-        println!("No changes to stage.");
+        println!("{}", msg!("add.none"));
     }
 
     Ok(())
@@ -67,7 +73,7 @@ fn stage_files(repo: &Repository, _repo_path: &PathBuf, files: &[String]) -> any
     let before_output = Command::new("git")
         .args(["status", "--porcelain"])
         .current_dir(repo_dir)
-        .output()?;
+        .timed_output()?;
     let before_count = String::from_utf8_lossy(&before_output.stdout)
         .lines()
         .filter(|l| !l.starts_with("??") || files.contains(&".".to_string()))
@@ -91,7 +97,7 @@ fn stage_files(repo: &Repository, _repo_path: &PathBuf, files: &[String]) -> any
     let output = Command::new("git")
         .args(&args)
         .current_dir(repo_dir)
-        .output()?;
+        .timed_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -102,7 +108,7 @@ fn stage_files(repo: &Repository, _repo_path: &PathBuf, files: &[String]) -> any
     let after_output = Command::new("git")
         .args(["diff", "--cached", "--name-only"])
         .current_dir(repo_dir)
-        .output()?;
+        .timed_output()?;
 
     let staged_count = String::from_utf8_lossy(&after_output.stdout)
         .lines()
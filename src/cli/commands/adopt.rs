@@ -0,0 +1,169 @@
+//! Adopt command implementation
+//!
+//! Brings an existing checkout inside the workspace under management by
+//! adding it to the manifest with the URL and default branch of its remote.
+
+use crate::cli::commands::repo::{append_repo_to_manifest, extract_repo_name};
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::resolve_repo_path;
+use crate::git::branch::remote_branch_exists;
+use crate::git::remote::get_remote_url;
+use crate::git::{get_current_branch, open_repo};
+use crate::util::profile::ProfiledCommand;
+use git2::Repository;
+use std::path::Path;
+use std::process::Command;
+
+/// Run the adopt command
+pub fn run_adopt(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    path: &Path,
+    name: Option<&str>,
+) -> anyhow::Result<()> {
+    let abs_path = path
+        .canonicalize()
+        .map_err(|_| anyhow::anyhow!("{} does not exist", path.display()))?;
+    let root = workspace_root.canonicalize()?;
+    let rel_path = abs_path
+        .strip_prefix(&root)
+        .map_err(|_| anyhow::anyhow!("{} is outside the workspace", path.display()))?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if rel_path.is_empty() || rel_path.starts_with(".gitgrip") {
+        anyhow::bail!("{} cannot be adopted", path.display());
+    }
+    if !abs_path.join(".git").exists() {
+        anyhow::bail!("{} is not a git repository", path.display());
+    }
+    if let Some((existing, _)) = manifest
+        .repos
+        .iter()
+        .find(|(_, config)| resolve_repo_path(&root, &config.path) == abs_path)
+    {
+        anyhow::bail!("{} is already managed as '{}'", rel_path, existing);
+    }
+
+    let git_repo = open_repo(&abs_path)?;
+    let (remote, url) = find_remote(&git_repo)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} has no remote; push it somewhere first so others can clone it",
+            rel_path
+        )
+    })?;
+
+    let repo_name = match name {
+        Some(n) => n.to_string(),
+        None => extract_repo_name(&url).unwrap_or_else(|| {
+            abs_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| rel_path.clone())
+        }),
+    };
+    if manifest.repos.contains_key(&repo_name) {
+        anyhow::bail!(
+            "A repository named '{}' already exists in the manifest; pass --name",
+            repo_name
+        );
+    }
+
+    let default_branch = infer_default_branch(&git_repo, &remote);
+
+    Output::header(&format!("Adopting {}", Output::repo_name(&repo_name)));
+    println!();
+    Output::kv("Path", &rel_path);
+    Output::kv("URL", &url);
+    Output::kv("Default branch", &default_branch);
+
+    append_repo_to_manifest(workspace_root, &repo_name, &url, &rel_path, &default_branch)?;
+
+    println!();
+    Output::success(&format!("Added '{}' to the manifest", repo_name));
+    if remote != "origin" {
+        Output::warning(&format!(
+            "The URL came from remote '{}'; gr expects it to be named 'origin'",
+            remote
+        ));
+    }
+
+    Ok(())
+}
+
+/// `origin` if present, otherwise the first configured remote
+fn find_remote(repo: &Repository) -> anyhow::Result<Option<(String, String)>> {
+    if let Some(url) = get_remote_url(repo, "origin")? {
+        return Ok(Some(("origin".to_string(), url)));
+    }
+
+    let remotes = repo.remotes()?;
+    for remote in remotes.iter().flatten() {
+        if let Some(url) = get_remote_url(repo, remote)? {
+            return Ok(Some((remote.to_string(), url)));
+        }
+    }
+    Ok(None)
+}
+
+/// The remote's HEAD branch, falling back to main/master or the current branch
+fn infer_default_branch(repo: &Repository, remote: &str) -> String {
+    let workdir = crate::git::get_workdir(repo);
+    let head = Command::new("git")
+        .args([
+            "symbolic-ref",
+            "--short",
+            &format!("refs/remotes/{}/HEAD", remote),
+        ])
+        .current_dir(workdir)
+        .timed_output();
+    if let Ok(output) = head {
+        if output.status.success() {
+            let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if let Some(branch) = head.strip_prefix(&format!("{}/", remote)) {
+                return branch.to_string();
+            }
+        }
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|b| remote_branch_exists(repo, b, remote))
+        .map(|b| b.to_string())
+        .or_else(|| get_current_branch(repo).ok())
+        .unwrap_or_else(|| "main".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::git;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_infer_default_branch_from_remote_head() {
+        let temp = TempDir::new().unwrap();
+        let upstream = temp.path().join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        git(&upstream, &["init", "-b", "develop"]);
+        git(&upstream, &["config", "user.name", "Test User"]);
+        git(&upstream, &["config", "user.email", "test@example.com"]);
+        git(
+            &upstream,
+            &["commit", "--allow-empty", "-m", "Initial commit"],
+        );
+
+        git(temp.path(), &["clone", "--quiet", "upstream", "checkout"]);
+        let checkout = temp.path().join("checkout");
+        git(&checkout, &["checkout", "-b", "feat/x"]);
+
+        let repo = open_repo(&checkout).unwrap();
+        assert_eq!(infer_default_branch(&repo, "origin"), "develop");
+        let (remote, url) = find_remote(&repo).unwrap().unwrap();
+        assert_eq!(remote, "origin");
+        assert!(url.ends_with("upstream"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Amend command implementation
+//!
+//! Folds staged changes into the last commit of each repo and force-pushes
+//! the result, so open PRs are updated without an extra fixup commit. Pushes
+//! use a lease on the remote tip recorded before amending, so commits pushed
+//! by someone else in the meantime are never overwritten.
+
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::cli::commands::commit::has_staged_changes;
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::cache::invalidate_status_cache;
+use crate::git::remote::get_remote_tracking_oid;
+use crate::git::{get_current_branch, get_signature, get_workdir, open_repo, path_exists};
+use crate::util::profile::ProfiledCommand;
+use git2::Repository;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Run the amend command
+///
+/// With `message`, the amended commit is reworded; otherwise its message is
+/// kept. With `push = false`, the amend stays local. Protected branches are
+/// skipped unless `allow_protected`.
+pub fn run_amend(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    message: Option<&str>,
+    push: bool,
+    sign: bool,
+    allow_protected: bool,
+) -> anyhow::Result<()> {
+    Output::header("Amending commits...");
+    println!();
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    let mut success_count = 0;
+    let mut skip_count = 0;
+    let mut error_count = 0;
+
+    for repo in &repos {
+        if !path_exists(&repo.absolute_path) {
+            continue;
+        }
+
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                error_count += 1;
+                continue;
+            }
+        };
+
+        if !has_staged_changes(&git_repo)? {
+            skip_count += 1;
+            continue;
+        }
+
+        let branch = match get_current_branch(&git_repo) {
+            Ok(b) => b,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                error_count += 1;
+                continue;
+            }
+        };
+
+        // Rewriting a protected branch would force-push over shared history
+        if !allow_protected
+            && manifest
+                .settings
+                .is_protected(&branch, &repo.default_branch)
+        {
+            Output::error(&format!(
+                "{}: refusing to amend protected branch '{}'",
+                repo.name, branch
+            ));
+            error_count += 1;
+            continue;
+        }
+
+        if let Err(e) = get_signature(&git_repo) {
+            Output::error(&format!("{}: {}", repo.name, e));
+            error_count += 1;
+            continue;
+        }
+
+        // Record the remote tip before amending; the push is only allowed if
+        // the remote branch is still there
+        let expected = get_remote_tracking_oid(&git_repo, "origin", &branch);
+
+        let commit_id = match amend_commit(&git_repo, message, sign) {
+            Ok(id) => id,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                error_count += 1;
+                continue;
+            }
+        };
+        invalidate_status_cache(&repo.absolute_path);
+        let short_id = &commit_id[..7.min(commit_id.len())];
+
+        if !push {
+            Output::success(&format!("{}: amended ({})", repo.name, short_id));
+            success_count += 1;
+            continue;
+        }
+
+        let spinner = Output::spinner(&format!("Pushing {}...", repo.name));
+        let mode = match expected {
+            Some(expected) => PushMode::ForceWithLease(expected),
+            // Never pushed: there is nothing to overwrite
+            None => PushMode::Normal { set_upstream: true },
+        };
+        let result = push_audited(workspace_root, &repo.name, &git_repo, &branch, mode);
+        match result {
+            Ok(()) => {
+                spinner.finish_with_message(format!(
+                    "{}: amended ({}) and pushed",
+                    repo.name, short_id
+                ));
+                success_count += 1;
+            }
+            Err(e) => {
+                spinner.finish_with_message(format!(
+                    "{}: amended ({}) but push failed - {}",
+                    repo.name, short_id, e
+                ));
+                error_count += 1;
+            }
+        }
+    }
+
+    println!();
+    if success_count == 0 && error_count == 0 {
+        println!("No staged changes to amend.");
+        return Ok(());
+    }
+
+    let action = if push {
+        "Amended and pushed"
+    } else {
+        "Amended"
+    };
+    let skipped = if skip_count > 0 {
+        format!(", {} repo(s) had no staged changes", skip_count)
+    } else {
+        String::new()
+    };
+    if error_count == 0 {
+        Output::success(&format!("{} {} repo(s){}.", action, success_count, skipped));
+    } else {
+        Output::warning(&format!(
+            "{} {} repo(s), {} failed{}.",
+            action, success_count, error_count, skipped
+        ));
+    }
+
+    Ok(())
+}
+
+/// Amend HEAD with the staged changes using git CLI, returning the new commit id
+fn amend_commit(repo: &Repository, message: Option<&str>, sign: bool) -> anyhow::Result<String> {
+    let repo_path = get_workdir(repo);
+
+    let mut args = vec!["commit", "--amend"];
+    match message {
+        Some(message) => args.extend(["-m", message]),
+        None => args.push("--no-edit"),
+    }
+    if !sign {
+        args.push("--no-gpg-sign");
+    }
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(repo_path)
+        .timed_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("failed to sign") {
+            anyhow::bail!(
+                "git commit --amend failed: could not sign the commit - check that your signing key is available, or use --no-sign\n{}",
+                stderr
+            );
+        }
+        anyhow::bail!("git commit --amend failed: {}", stderr);
+    }
+
+    let hash_output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_path)
+        .timed_output()?;
+
+    Ok(String::from_utf8_lossy(&hash_output.stdout)
+        .trim()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_amend_commit_keeps_or_replaces_message() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::write(path.join("a.txt"), "one").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Add a"]);
+
+        let repo = open_repo(path).unwrap();
+        std::fs::write(path.join("b.txt"), "two").unwrap();
+        git(path, &["add", "."]);
+        amend_commit(&repo, None, false).unwrap();
+        assert_eq!(git(path, &["log", "--format=%s"]), "Add a");
+        assert_eq!(
+            git(path, &["show", "--format=", "--name-only"]),
+            "a.txt\nb.txt"
+        );
+
+        amend_commit(&repo, Some("Add a and b"), false).unwrap();
+        assert_eq!(git(path, &["log", "--format=%s"]), "Add a and b");
+    }
+}
@@ -0,0 +1,276 @@
+//! Archive command implementation
+//!
+//! Exports the sources of every repo as a single tarball, laid out by manifest
+//! path. Each repo's tree comes from `git archive`, so `.git` directories,
+//! untracked files and `export-ignore` paths are left out.
+
+use crate::cli::commands::foreach_changed::WorkspaceSnapshot;
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::{open_repo, path_exists};
+use crate::util::profile::ProfiledCommand;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const BLOCK: usize = 512;
+
+/// Run the archive command
+///
+/// Each repo is exported at `rev`, or at its commit in the `lock` snapshot
+/// (from `gr foreach-changed --save`) when one is given. Repos where that
+/// commit doesn't exist are skipped with a warning. `output` ending in `.tar`
+/// is written uncompressed; anything else is gzipped.
+pub fn run_archive(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    output: &Path,
+    rev: &str,
+    lock: Option<&Path>,
+) -> anyhow::Result<()> {
+    let snapshot = lock.map(WorkspaceSnapshot::load).transpose()?;
+
+    Output::header(&format!("Archiving workspace to {}", output.display()));
+    println!();
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    // Build next to the output and rename at the end, so a failure never
+    // leaves a truncated tarball behind
+    let partial = partial_path(output);
+    let gzip = !output.extension().is_some_and(|ext| ext == "tar");
+    let archived = match write_archive(&partial, gzip, &repos, rev, snapshot.as_ref()) {
+        Ok(archived) => archived,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial, output)?;
+
+    println!();
+    Output::success(&format!(
+        "Archived {} repo(s) to {}",
+        archived,
+        output.display()
+    ));
+    Ok(())
+}
+
+/// Write every repo's tree to `path`, returning how many were archived
+fn write_archive(
+    path: &Path,
+    gzip: bool,
+    repos: &[RepoInfo],
+    rev: &str,
+    snapshot: Option<&WorkspaceSnapshot>,
+) -> anyhow::Result<usize> {
+    let file = File::create(path)?;
+    let mut writer: Box<dyn Write> = if gzip {
+        Box::new(GzEncoder::new(file, Compression::default()))
+    } else {
+        Box::new(file)
+    };
+
+    let mut archived = 0;
+    for repo in repos {
+        if !path_exists(&repo.absolute_path) {
+            Output::warning(&format!("{}: not cloned, skipping", repo.name));
+            continue;
+        }
+
+        let rev = match snapshot {
+            Some(snapshot) => match snapshot.repos.get(&repo.name) {
+                Some(sha) => sha.as_str(),
+                None => {
+                    Output::warning(&format!(
+                        "{}: not in the lock snapshot, skipping",
+                        repo.name
+                    ));
+                    continue;
+                }
+            },
+            None => rev,
+        };
+        if !has_commit(&repo.absolute_path, rev) {
+            Output::warning(&format!("{}: '{}' not found, skipping", repo.name, rev));
+            continue;
+        }
+
+        let prefix = format!("{}/", archive_prefix(&repo.path));
+        let tar = git_archive(&repo.absolute_path, &prefix, rev)?;
+        writer.write_all(strip_end_of_archive(&tar))?;
+        Output::success(&format!("{}: {} @ {}", repo.name, prefix, rev));
+        archived += 1;
+    }
+
+    // Two zero blocks mark the end of the combined archive
+    writer.write_all(&[0u8; BLOCK * 2])?;
+    writer.flush()?;
+    Ok(archived)
+}
+
+/// Hidden sibling of `output` that the archive is built in
+fn partial_path(output: &Path) -> PathBuf {
+    let name = output
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    output.with_file_name(format!(".{}.{}.partial", name, std::process::id()))
+}
+
+/// Whether `rev` names a commit in the repo
+fn has_commit(repo_path: &Path, rev: &str) -> bool {
+    open_repo(repo_path)
+        .ok()
+        .is_some_and(|repo| repo.revparse_single(&format!("{}^{{commit}}", rev)).is_ok())
+}
+
+/// Tar path prefix for a manifest path (`./libs/core` -> `libs/core`)
+fn archive_prefix(path: &str) -> String {
+    path.split(['/', '\\'])
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn git_archive(repo_path: &Path, prefix: &str, rev: &str) -> anyhow::Result<Vec<u8>> {
+    let prefix_arg = format!("--prefix={}", prefix);
+    let output = Command::new("git")
+        .args(["archive", "--format=tar", &prefix_arg, rev])
+        .current_dir(repo_path)
+        .timed_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git archive failed in {}: {}", prefix, stderr.trim());
+    }
+
+    Ok(output.stdout)
+}
+
+/// Trim the end-of-archive marker (and record padding) so tars can be concatenated
+fn strip_end_of_archive(tar: &[u8]) -> &[u8] {
+    let mut offset = 0;
+    while offset + BLOCK <= tar.len() {
+        let header = &tar[offset..offset + BLOCK];
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let data_blocks = entry_size(header).div_ceil(BLOCK);
+        offset += BLOCK * (1 + data_blocks);
+    }
+    &tar[..offset.min(tar.len())]
+}
+
+/// Size field of a ustar header (octal, NUL/space terminated)
+fn entry_size(header: &[u8]) -> usize {
+    let field = String::from_utf8_lossy(&header[124..136]);
+    usize::from_str_radix(field.trim_matches(|c: char| c == '\0' || c == ' '), 8).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn setup_workspace() -> (TempDir, Manifest) {
+        let temp = TempDir::new().unwrap();
+        for name in ["app", "lib"] {
+            let path = temp.path().join(name);
+            init_repo(&path);
+            std::fs::write(path.join("README.md"), format!("{} v1\n", name)).unwrap();
+            git(&path, &["add", "."]);
+            git(&path, &["commit", "-q", "-m", "Initial commit"]);
+        }
+        git(&temp.path().join("app"), &["tag", "v1"]);
+
+        let manifest = Manifest::parse(
+            "repos:\n  app:\n    url: git@github.com:acme/app.git\n    path: app\n  lib:\n    url: git@github.com:acme/lib.git\n    path: lib\n",
+        )
+        .unwrap();
+        (temp, manifest)
+    }
+
+    fn contains(haystack: &[u8], needle: &str) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes())
+    }
+
+    #[test]
+    fn test_archive_prefix() {
+        assert_eq!(archive_prefix("./libs/core"), "libs/core");
+        assert_eq!(archive_prefix("app"), "app");
+        assert_eq!(archive_prefix("libs\\core"), "libs/core");
+    }
+
+    #[test]
+    fn test_strip_end_of_archive() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::write(path.join("README.md"), "hello\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-m", "Initial commit"]);
+
+        let tar = git_archive(path, "app/", "HEAD").unwrap();
+        let stripped = strip_end_of_archive(&tar);
+        assert!(stripped.len() < tar.len());
+        assert_eq!(stripped.len() % BLOCK, 0);
+        assert!(tar[stripped.len()..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_archive_skips_repo_without_rev() {
+        let (temp, manifest) = setup_workspace();
+        let root = temp.path().to_path_buf();
+        let output = root.join("out.tar");
+
+        run_archive(&root, &manifest, &output, "v1", None).unwrap();
+
+        let tar = std::fs::read(&output).unwrap();
+        assert!(contains(&tar, "app/README.md"));
+        assert!(!contains(&tar, "lib/README.md"));
+        assert!(!root.read_dir().unwrap().any(|entry| entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .ends_with(".partial")));
+    }
+
+    #[test]
+    fn test_archive_from_lock_snapshot() {
+        let (temp, manifest) = setup_workspace();
+        let root = temp.path().to_path_buf();
+        let app = root.join("app");
+        let locked = git(&app, &["rev-parse", "HEAD"]);
+        std::fs::write(app.join("README.md"), "app v2\n").unwrap();
+        git(&app, &["commit", "-q", "-am", "Bump"]);
+
+        let lock = root.join("lock.json");
+        WorkspaceSnapshot {
+            repos: BTreeMap::from([("app".to_string(), locked)]),
+        }
+        .save(&lock)
+        .unwrap();
+        let output = root.join("out.tar");
+
+        run_archive(&root, &manifest, &output, "HEAD", Some(&lock)).unwrap();
+
+        let tar = std::fs::read(&output).unwrap();
+        assert!(contains(&tar, "app v1"));
+        assert!(!contains(&tar, "app v2"));
+        assert!(!contains(&tar, "lib/README.md"));
+    }
+}
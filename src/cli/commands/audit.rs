@@ -0,0 +1,229 @@
+//! Audit command implementation
+//!
+//! `gr audit show` lists the mutating operations recorded in
+//! `.gitgrip/audit.log`, newest last. Commands push and delete remote
+//! branches through [`push_audited`] and [`delete_remote_audited`], which
+//! record each change as it succeeds.
+
+use crate::cli::output::{Output, Table};
+use crate::core::audit::{parse_since, read_log, record, AuditAction, AuditEntry};
+use crate::git::remote::{
+    delete_remote_branch, delete_remote_branch_at, force_push_branch, force_push_with_lease,
+    get_remote_tracking_oid, push_branch,
+};
+use crate::git::GitError;
+use chrono::{Local, Utc};
+use git2::{Oid, Repository};
+use std::path::Path;
+
+/// How [`push_audited`] updates the remote branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PushMode {
+    /// Fast-forward only, optionally setting the upstream
+    Normal { set_upstream: bool },
+    /// Overwrite whatever the remote has
+    Force,
+    /// Overwrite only while the remote is still at this commit
+    ForceWithLease(Oid),
+}
+
+/// Run the audit show command
+pub fn run_audit_show(
+    workspace_root: &Path,
+    since: Option<&str>,
+    repos_filter: &[String],
+) -> anyhow::Result<()> {
+    let since = since
+        .map(|s| parse_since(s, Utc::now()))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let entries: Vec<AuditEntry> = read_log(workspace_root)?
+        .into_iter()
+        .filter(|e| since.map_or(true, |since| e.timestamp >= since))
+        .filter(|e| repos_filter.is_empty() || repos_filter.contains(&e.repo))
+        .collect();
+
+    if entries.is_empty() {
+        Output::info("No recorded operations.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(vec![
+        "Time", "User", "Action", "Repo", "Ref", "Change", "Command",
+    ]);
+    for entry in &entries {
+        let time = entry
+            .timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        table.add_row(vec![
+            &time,
+            &entry.user,
+            &entry.action.to_string(),
+            &entry.repo,
+            entry.ref_name.as_deref().unwrap_or("-"),
+            &describe_change(entry),
+            &entry.command,
+        ]);
+    }
+    table.print();
+    println!();
+    println!("{} operation(s)", entries.len());
+
+    Ok(())
+}
+
+/// Append `entry` to the audit log, warning rather than failing the operation
+pub(crate) fn audit(workspace_root: &Path, entry: AuditEntry) {
+    if let Err(e) = record(workspace_root, &entry) {
+        Output::warning(&format!("Could not write the audit log: {}", e));
+    }
+}
+
+/// Push `branch` to origin and record it in the audit log
+pub(crate) fn push_audited(
+    workspace_root: &Path,
+    repo_name: &str,
+    git_repo: &Repository,
+    branch: &str,
+    mode: PushMode,
+) -> Result<(), GitError> {
+    let before = get_remote_tracking_oid(git_repo, "origin", branch);
+    let action = match mode {
+        PushMode::Normal { set_upstream } => {
+            push_branch(git_repo, branch, "origin", set_upstream)?;
+            AuditAction::Push
+        }
+        PushMode::Force => {
+            force_push_branch(git_repo, branch, "origin")?;
+            AuditAction::ForcePush
+        }
+        PushMode::ForceWithLease(expected) => {
+            force_push_with_lease(git_repo, branch, "origin", expected)?;
+            AuditAction::ForcePush
+        }
+    };
+
+    let after = git_repo
+        .refname_to_id(&format!("refs/heads/{}", branch))
+        .ok();
+    audit(
+        workspace_root,
+        AuditEntry::new(action, repo_name).with_ref(
+            branch,
+            before.map(|oid| oid.to_string()),
+            after.map(|oid| oid.to_string()),
+        ),
+    );
+    Ok(())
+}
+
+/// Delete `branch` on origin and record it in the audit log
+///
+/// With `expected`, the branch is only deleted while it still points there.
+pub(crate) fn delete_remote_audited(
+    workspace_root: &Path,
+    repo_name: &str,
+    git_repo: &Repository,
+    branch: &str,
+    expected: Option<Oid>,
+) -> Result<(), GitError> {
+    let before = match expected {
+        Some(expected) => {
+            delete_remote_branch_at(git_repo, branch, "origin", expected)?;
+            Some(expected)
+        }
+        None => {
+            let before = get_remote_tracking_oid(git_repo, "origin", branch);
+            delete_remote_branch(git_repo, branch, "origin")?;
+            before
+        }
+    };
+
+    audit(
+        workspace_root,
+        AuditEntry::new(AuditAction::BranchDelete, repo_name).with_ref(
+            &format!("origin/{}", branch),
+            before.map(|oid| oid.to_string()),
+            None,
+        ),
+    );
+    Ok(())
+}
+
+fn describe_change(entry: &AuditEntry) -> String {
+    let short = |sha: &String| sha[..7.min(sha.len())].to_string();
+    let refs = match (&entry.before, &entry.after) {
+        (Some(before), Some(after)) => format!("{} -> {}", short(before), short(after)),
+        (None, Some(after)) => format!("new at {}", short(after)),
+        (Some(before), None) => format!("was {}", short(before)),
+        (None, None) => String::new(),
+    };
+    match entry.pr {
+        Some(pr) if refs.is_empty() => format!("PR #{}", pr),
+        Some(pr) => format!("PR #{}, {}", pr, refs),
+        None => refs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::audit::read_log;
+    use crate::git::open_repo;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_describe_change() {
+        let entry = AuditEntry::new(AuditAction::Push, "app").with_ref(
+            "main",
+            Some("0123456789".to_string()),
+            Some("abcdef0123".to_string()),
+        );
+        assert_eq!(describe_change(&entry), "0123456 -> abcdef0");
+
+        let entry = AuditEntry::new(AuditAction::BranchDelete, "app").with_ref(
+            "feat",
+            Some("0123456789".to_string()),
+            None,
+        );
+        assert_eq!(describe_change(&entry), "was 0123456");
+
+        let entry = AuditEntry::new(AuditAction::PrCreate, "app").with_pr(12);
+        assert_eq!(describe_change(&entry), "PR #12");
+    }
+
+    #[test]
+    fn test_push_and_delete_are_recorded() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let remote = root.join("remote.git");
+        git(root, &["init", "-q", "--bare", remote.to_str().unwrap()]);
+        let path = root.join("app");
+        init_repo(&path);
+        git(
+            &path,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+        git(&path, &["commit", "-q", "--allow-empty", "-m", "Initial"]);
+        git(&path, &["checkout", "-q", "-b", "feat"]);
+        let tip = git(&path, &["rev-parse", "HEAD"]);
+
+        let git_repo = open_repo(&path).unwrap();
+        let push = PushMode::Normal { set_upstream: true };
+        push_audited(root, "app", &git_repo, "feat", push).unwrap();
+        delete_remote_audited(root, "app", &git_repo, "feat", None).unwrap();
+
+        let entries = read_log(root).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, AuditAction::Push);
+        assert_eq!(entries[0].ref_name.as_deref(), Some("feat"));
+        assert_eq!(entries[0].after.as_deref(), Some(tip.as_str()));
+        assert_eq!(entries[1].action, AuditAction::BranchDelete);
+        assert_eq!(entries[1].ref_name.as_deref(), Some("origin/feat"));
+        assert_eq!(entries[1].before.as_deref(), Some(tip.as_str()));
+    }
+}
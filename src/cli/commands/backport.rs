@@ -0,0 +1,298 @@
+//! Backport command implementation
+//!
+//! Cherry-picks the commits of a PR group's branch onto a release branch in
+//! every affected repo, pushes the backport branches, and opens linked PRs.
+
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::{LinkedPR, StateFile};
+use crate::git::branch::{branch_exists, checkout_branch, create_and_checkout_branch_from};
+use crate::git::cherry_pick::{branch_commits, cherry_pick_commits};
+use crate::git::remote::fetch_remote;
+use crate::git::status::{blocking_operation, get_status_info};
+use crate::git::{open_repo, path_exists};
+use crate::platform::traits::LinkedPRRef;
+use crate::platform::{detect_platform, get_platform_adapter, HostingPlatform};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Run the backport command
+///
+/// `source` is the branch shared by the PR group being backported.
+pub async fn run_backport(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    source: &str,
+    target: &str,
+    title: Option<&str>,
+    draft: bool,
+    sign: bool,
+) -> anyhow::Result<()> {
+    Output::header(&format!(
+        "Backporting {} to {}",
+        Output::branch_name(source),
+        Output::branch_name(target)
+    ));
+    println!();
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let state = StateFile::load(&state_path).unwrap_or_default();
+    let original_links = state
+        .get_pr_for_branch(source)
+        .and_then(|pr| state.get_linked_prs(pr).cloned())
+        .unwrap_or_default();
+
+    let backport_branch = backport_branch_name(source, target);
+    let pr_title = title
+        .map(|t| t.to_string())
+        .unwrap_or_else(|| format!("[Backport {}] {}", target, source));
+
+    let mut ready: Vec<&RepoInfo> = Vec::new();
+    let mut conflicted: Vec<String> = Vec::new();
+
+    for repo in &repos {
+        if !path_exists(&repo.absolute_path) {
+            continue;
+        }
+
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                continue;
+            }
+        };
+
+        // An unfinished cherry-pick must be completed before anything is pushed
+        if let Some(reason) = blocking_operation(&git_repo) {
+            Output::warning(&format!("{}: {}", repo.name, reason));
+            conflicted.push(repo.name.clone());
+            continue;
+        }
+
+        if let Err(e) = fetch_remote(&git_repo, "origin") {
+            Output::warning(&format!("{}: fetch failed - {}", repo.name, e));
+        }
+
+        // Re-running after resolving conflicts picks up the existing branch
+        if branch_exists(&git_repo, &backport_branch) {
+            match checkout_branch(&git_repo, &backport_branch) {
+                Ok(()) => {
+                    Output::info(&format!(
+                        "{}: using existing {}",
+                        repo.name, backport_branch
+                    ));
+                    ready.push(repo);
+                }
+                Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+            }
+            continue;
+        }
+
+        let source_ref = if branch_exists(&git_repo, source) {
+            source.to_string()
+        } else {
+            format!("origin/{}", source)
+        };
+        let commits = match branch_commits(
+            &git_repo,
+            &source_ref,
+            &format!("origin/{}", repo.default_branch),
+        ) {
+            Ok(c) if !c.is_empty() => c,
+            // Repo not touched by this PR group
+            _ => continue,
+        };
+
+        if let Ok(status) = get_status_info(&git_repo) {
+            if !status.staged.is_empty() || !status.modified.is_empty() {
+                Output::warning(&format!("{}: has uncommitted changes, skipping", repo.name));
+                continue;
+            }
+        }
+
+        let target_ref = format!("origin/{}", target);
+        if let Err(e) = create_and_checkout_branch_from(&git_repo, &backport_branch, &target_ref) {
+            Output::error(&format!(
+                "{}: cannot branch from {} - {}",
+                repo.name,
+                target_ref,
+                e.to_string().trim()
+            ));
+            continue;
+        }
+
+        match cherry_pick_commits(&git_repo, &commits, true, sign) {
+            Ok(outcome) => match outcome.conflict {
+                Some((_, paths)) => {
+                    Output::warning(&format!(
+                        "{}: conflicts in {} file(s)",
+                        repo.name,
+                        paths.len()
+                    ));
+                    conflicted.push(repo.name.clone());
+                }
+                None => {
+                    Output::success(&format!(
+                        "{}: picked {} commit(s)",
+                        repo.name, outcome.picked
+                    ));
+                    ready.push(repo);
+                }
+            },
+            Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    if !conflicted.is_empty() {
+        println!();
+        Output::warning(&format!(
+            "{} repo(s) have conflicts: {}",
+            conflicted.len(),
+            conflicted.join(", ")
+        ));
+        println!("Resolve them, run 'gr cherry-pick --continue', then re-run this backport.");
+        return Ok(());
+    }
+
+    if ready.is_empty() {
+        Output::info(&format!("No repos have commits from '{}'.", source));
+        return Ok(());
+    }
+
+    // Push and open PRs
+    println!();
+    let mut created: Vec<(&RepoInfo, Arc<dyn HostingPlatform>, u64, String)> = Vec::new();
+
+    for repo in &ready {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let spinner = Output::spinner(&format!("Pushing {}...", repo.name));
+        let push = PushMode::Normal { set_upstream: true };
+        if let Err(e) = push_audited(
+            workspace_root,
+            &repo.name,
+            &git_repo,
+            &backport_branch,
+            push,
+        ) {
+            spinner.finish_with_message(format!("{}: push failed - {}", repo.name, e));
+            continue;
+        }
+
+        let platform = get_platform_adapter(detect_platform(&repo.url), None);
+
+        if let Ok(Some(pr)) = platform
+            .find_pr_by_branch(&repo.owner, &repo.repo, &backport_branch)
+            .await
+        {
+            spinner.finish_with_message(format!("{}: PR #{} already open", repo.name, pr.number));
+            created.push((repo, platform, pr.number, pr.url));
+            continue;
+        }
+
+        let body = backport_body(&original_links, &repo.name, source, target);
+
+        match platform
+            .create_pull_request(
+                &repo.owner,
+                &repo.repo,
+                &backport_branch,
+                target,
+                &pr_title,
+                Some(&body),
+                draft,
+            )
+            .await
+        {
+            Ok(pr) => {
+                spinner.finish_with_message(format!(
+                    "{}: created PR #{} - {}",
+                    repo.name, pr.number, pr.url
+                ));
+                created.push((repo, platform, pr.number, pr.url));
+            }
+            Err(e) => spinner.finish_with_message(format!("{}: failed - {}", repo.name, e)),
+        }
+    }
+
+    // Cross-link the backport PRs with each other
+    if created.len() > 1 {
+        let links: Vec<LinkedPRRef> = created
+            .iter()
+            .map(|(repo, _, number, _)| LinkedPRRef {
+                repo_name: repo.name.clone(),
+                number: *number,
+            })
+            .collect();
+
+        for (repo, platform, number, _) in &created {
+            let body = format!(
+                "{}\n\n{}",
+                backport_body(&original_links, &repo.name, source, target),
+                platform.generate_linked_pr_comment(&links)
+            );
+            if let Err(e) = platform
+                .update_pull_request_body(&repo.owner, &repo.repo, *number, &body)
+                .await
+            {
+                Output::warning(&format!("{}: failed to link PRs - {}", repo.name, e));
+            }
+        }
+    }
+
+    println!();
+    if created.is_empty() {
+        Output::warning("No backport PRs were created.");
+    } else {
+        Output::success(&format!("Backport PR(s) for {}:", target));
+        for (repo, _, number, url) in &created {
+            println!("  {}: #{} - {}", repo.name, number, url);
+        }
+    }
+
+    Ok(())
+}
+
+/// Name of the branch holding a backport of `source` onto `target`
+fn backport_branch_name(source: &str, target: &str) -> String {
+    format!("backport/{}/{}", target, source)
+}
+
+/// PR description referencing the original PR (or branch) being backported
+fn backport_body(originals: &[LinkedPR], repo_name: &str, source: &str, target: &str) -> String {
+    let original = originals
+        .iter()
+        .find(|link| link.repo_name == repo_name)
+        .map(|link| link.url.clone())
+        .unwrap_or_else(|| format!("branch `{}`", source));
+    format!("Backport of {} to `{}`.", original, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backport_branch_name() {
+        assert_eq!(
+            backport_branch_name("fix/login", "release/1.x"),
+            "backport/release/1.x/fix/login"
+        );
+    }
+
+    #[test]
+    fn test_backport_body_without_original_pr() {
+        assert_eq!(
+            backport_body(&[], "api", "fix/login", "release/1.x"),
+            "Backport of branch `fix/login` to `release/1.x`."
+        );
+    }
+}
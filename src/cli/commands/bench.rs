@@ -6,6 +6,7 @@ use anyhow::Result;
 use clap::Args;
 use colored::Colorize;
 
+use crate::cli::output::Output;
 use crate::core::manifest::{Manifest, RepoConfig};
 use crate::core::repo::RepoInfo;
 use crate::core::state::StateFile;
@@ -25,7 +26,7 @@ pub struct BenchArgs {
     pub iterations: usize,
 
     /// Number of warmup iterations
-    #[arg(short, long, default_value = "2")]
+    #[arg(long, default_value = "2")]
     pub warmup: usize,
 
     /// Output as JSON
@@ -211,6 +212,11 @@ fn run_benchmark_operation(name: &str) -> Result<()> {
                 copyfile: None,
                 linkfile: None,
                 platform: None,
+                sparse: None,
+                depends_on: None,
+                revision: None,
+                env: None,
+                workspace: false,
             };
             let workspace = std::path::PathBuf::from("/workspace");
             let _ = RepoInfo::from_config("repo", &config, &workspace);
@@ -256,13 +262,23 @@ fn run_single_benchmark(name: &str, iterations: usize, warmup: usize) -> Result<
 
 /// Format benchmark results as a table
 fn format_results(results: &[BenchmarkResult]) -> String {
+    let bar = format!(" {} ", Output::label("│", "|"));
+    let cross = Output::label("┼", "+");
     let mut lines = vec![
         "Benchmark Results".to_string(),
-        "═════════════════".to_string(),
+        Output::label("═", "=").repeat(17),
         String::new(),
         // Header
-        "Operation        │ Iter │      Min │      Max │      Avg │      P95".to_string(),
-        "─────────────────┼──────┼──────────┼──────────┼──────────┼──────────".to_string(),
+        [
+            "Operation       ",
+            "Iter",
+            "     Min",
+            "     Max",
+            "     Avg",
+            "     P95",
+        ]
+        .join(&bar),
+        [17, 6, 10, 10, 10, 10].map(Output::rule).join(cross),
     ];
 
     // Rows
@@ -273,10 +289,7 @@ fn format_results(results: &[BenchmarkResult]) -> String {
         let max = format!("{:>8}", format_duration(result.max));
         let avg = format!("{:>8}", format_duration(result.avg));
         let p95 = format!("{:>8}", format_duration(result.p95));
-        lines.push(format!(
-            "{} │ {} │ {} │ {} │ {} │ {}",
-            name, iter, min, max, avg, p95
-        ));
+        lines.push([name, iter, min, max, avg, p95].join(&bar));
     }
 
     lines.join("\n")
@@ -341,7 +354,7 @@ pub async fn run(args: BenchArgs) -> Result<()> {
 
     // Output results
     if args.json {
-        println!("{}", serde_json::to_string_pretty(&results)?);
+        Output::json(&results)?;
     } else {
         println!("{}", format_results(&results));
     }
@@ -0,0 +1,228 @@
+//! Bisect command implementation
+//!
+//! Drives `git bisect` in one repository while pinning every other repo to
+//! its last commit at or before the timestamp of the commit under test.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::{BisectState, StateFile};
+use crate::git::status::get_repo_status;
+use crate::git::{path_exists, run_git};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Start bisecting `repo_name`, optionally marking the initial bad/good commits
+pub fn run_bisect_start(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    repo_name: &str,
+    bad: Option<&str>,
+    good: &[String],
+) -> anyhow::Result<()> {
+    let state_path = state_path(workspace_root);
+    let mut state = StateFile::load(&state_path)?;
+    if let Some(bisect) = &state.bisect {
+        anyhow::bail!(
+            "A bisect of '{}' is already in progress. Run 'gr bisect reset' first.",
+            bisect.repo
+        );
+    }
+
+    let repos = load_repos(workspace_root, manifest);
+    let target = repos
+        .iter()
+        .find(|r| r.name == repo_name)
+        .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found in manifest", repo_name))?;
+
+    let dirty: Vec<String> = repos
+        .iter()
+        .map(get_repo_status)
+        .filter(|s| s.exists && (s.staged > 0 || s.modified > 0))
+        .map(|s| s.name)
+        .collect();
+    if !dirty.is_empty() {
+        anyhow::bail!(
+            "Commit or stash changes before bisecting: {}",
+            dirty.join(", ")
+        );
+    }
+
+    let mut original_refs = HashMap::new();
+    for repo in &repos {
+        if repo.name != target.name && path_exists(&repo.absolute_path) {
+            original_refs.insert(repo.name.clone(), current_ref(&repo.absolute_path)?);
+        }
+    }
+
+    Output::header(&format!("Bisecting {}", Output::repo_name(&target.name)));
+    println!();
+
+    let mut args = vec!["bisect", "start"];
+    if let Some(bad) = bad {
+        args.push(bad);
+        args.extend(good.iter().map(|g| g.as_str()));
+    }
+    print_lines(&run_git(&target.absolute_path, &args)?);
+
+    state.bisect = Some(BisectState {
+        repo: target.name.clone(),
+        original_refs,
+    });
+    state.save(&state_path)?;
+
+    pin_other_repos(&repos, state.bisect.as_ref().unwrap())
+}
+
+/// Mark the current commit (or `rev`) as good, bad, or skipped
+pub fn run_bisect_mark(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    verdict: &str,
+    rev: Option<&str>,
+) -> anyhow::Result<()> {
+    let state = StateFile::load(state_path(workspace_root))?;
+    let bisect = state
+        .bisect
+        .ok_or_else(|| anyhow::anyhow!("No bisect in progress. Run 'gr bisect start' first."))?;
+
+    let repos = load_repos(workspace_root, manifest);
+    let target = repos
+        .iter()
+        .find(|r| r.name == bisect.repo)
+        .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found in manifest", bisect.repo))?;
+
+    let mut args = vec!["bisect", verdict];
+    if let Some(rev) = rev {
+        args.push(rev);
+    }
+    print_lines(&run_git(&target.absolute_path, &args)?);
+
+    pin_other_repos(&repos, &bisect)
+}
+
+/// End the bisect and restore every repo to its original branch
+pub fn run_bisect_reset(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
+    let state_path = state_path(workspace_root);
+    let mut state = StateFile::load(&state_path)?;
+    let bisect = match state.bisect.take() {
+        Some(b) => b,
+        None => {
+            Output::info("No bisect in progress.");
+            return Ok(());
+        }
+    };
+
+    let repos = load_repos(workspace_root, manifest);
+    for repo in &repos {
+        if !path_exists(&repo.absolute_path) {
+            continue;
+        }
+
+        let result = if repo.name == bisect.repo {
+            run_git(&repo.absolute_path, &["bisect", "reset"])
+        } else if let Some(original) = bisect.original_refs.get(&repo.name) {
+            run_git(&repo.absolute_path, &["checkout", "--quiet", original])
+        } else {
+            continue;
+        };
+
+        match result {
+            Ok(_) => Output::success(&format!("{}: restored", repo.name)),
+            Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    state.save(&state_path)?;
+    Ok(())
+}
+
+/// Check out each non-bisected repo at the commit closest to the bisect step
+fn pin_other_repos(repos: &[RepoInfo], bisect: &BisectState) -> anyhow::Result<()> {
+    let target = repos
+        .iter()
+        .find(|r| r.name == bisect.repo)
+        .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found in manifest", bisect.repo))?;
+
+    let timestamp = run_git(
+        &target.absolute_path,
+        &["log", "-1", "--format=%ct", "HEAD"],
+    )?;
+    let timestamp = timestamp.trim();
+
+    println!();
+    for repo in repos {
+        let Some(original) = bisect.original_refs.get(&repo.name) else {
+            continue;
+        };
+
+        let before = format!("--before={}", timestamp);
+        let sha = match run_git(&repo.absolute_path, &["rev-list", "-1", &before, original]) {
+            Ok(out) if !out.trim().is_empty() => out.trim().to_string(),
+            _ => {
+                Output::warning(&format!("{}: no commit before this step", repo.name));
+                continue;
+            }
+        };
+
+        match run_git(
+            &repo.absolute_path,
+            &["checkout", "--quiet", "--detach", &sha],
+        ) {
+            Ok(_) => Output::info(&format!("{}: pinned to {}", repo.name, &sha[..7])),
+            Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    Ok(())
+}
+
+fn state_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".gitgrip").join("state.json")
+}
+
+fn load_repos(workspace_root: &PathBuf, manifest: &Manifest) -> Vec<RepoInfo> {
+    manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect()
+}
+
+/// Current branch name, or the commit SHA when HEAD is detached
+fn current_ref(repo_path: &Path) -> anyhow::Result<String> {
+    let branch = run_git(repo_path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    if branch.trim() != "HEAD" {
+        return Ok(branch.trim().to_string());
+    }
+    Ok(run_git(repo_path, &["rev-parse", "HEAD"])?
+        .trim()
+        .to_string())
+}
+
+fn print_lines(output: &str) {
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        println!("  {}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::init_repo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_current_ref_branch_and_detached() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        run_git(path, &["commit", "--allow-empty", "-m", "Initial commit"]).unwrap();
+
+        assert_eq!(current_ref(path).unwrap(), "main");
+
+        run_git(path, &["checkout", "--quiet", "--detach"]).unwrap();
+        let sha = run_git(path, &["rev-parse", "HEAD"]).unwrap();
+        assert_eq!(current_ref(path).unwrap(), sha.trim());
+    }
+}
@@ -1,21 +1,40 @@
 //! Branch command implementation
 
+use crate::cli::commands::audit::{audit, delete_remote_audited, push_audited, PushMode};
+use crate::cli::commands::checkout::{
+    confirm_switch, find_unsaved_work, stash_changes, SwitchMode,
+};
 use crate::cli::output::Output;
+use crate::core::audit::{AuditAction, AuditEntry};
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
+use crate::core::state::StateFile;
+use crate::core::ticket::{branch_name, DEFAULT_BRANCH_FORMAT};
 use crate::git::{
-    branch::{branch_exists, create_and_checkout_branch, delete_local_branch, list_local_branches},
+    branch::{
+        branch_exists, checkout_branch, create_and_checkout_branch,
+        create_and_checkout_branch_from, delete_local_branch, list_local_branches,
+        remote_branch_exists, rename_local_branch,
+    },
     get_current_branch, open_repo,
+    remote::{fetch_remote, get_remote_tracking_oid, get_remote_url},
+    status::blocking_operation,
 };
-use std::path::PathBuf;
+use crate::platform::{detect_platform, get_platform_adapter};
+use crate::util::profile::ProfiledCommand;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Run the branch command
+///
+/// Deleting a protected branch is refused unless `allow_protected`.
 pub fn run_branch(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     name: Option<&str>,
     delete: bool,
     repos_filter: Option<&[String]>,
+    allow_protected: bool,
 ) -> anyhow::Result<()> {
     let repos: Vec<RepoInfo> = manifest
         .repos
@@ -30,6 +49,25 @@ pub fn run_branch(
 
     match name {
         Some(branch_name) if delete => {
+            manifest.settings.ensure_unprotected(
+                "delete",
+                repos
+                    .iter()
+                    .filter(|repo| {
+                        repo.exists()
+                            && open_repo(&repo.absolute_path)
+                                .is_ok_and(|r| branch_exists(&r, branch_name))
+                    })
+                    .map(|repo| {
+                        (
+                            repo.name.as_str(),
+                            branch_name,
+                            repo.default_branch.as_str(),
+                        )
+                    }),
+                allow_protected,
+            )?;
+
             // Delete branch
             Output::header(&format!("Deleting branch '{}'", branch_name));
             println!();
@@ -47,8 +85,22 @@ pub fn run_branch(
                             continue;
                         }
 
+                        let tip = git_repo
+                            .refname_to_id(&format!("refs/heads/{}", branch_name))
+                            .ok();
                         match delete_local_branch(&git_repo, branch_name, false) {
-                            Ok(()) => Output::success(&format!("{}: deleted", repo.name)),
+                            Ok(()) => {
+                                audit(
+                                    workspace_root,
+                                    AuditEntry::new(AuditAction::BranchDelete, &repo.name)
+                                        .with_ref(
+                                            branch_name,
+                                            tip.map(|oid| oid.to_string()),
+                                            None,
+                                        ),
+                                );
+                                Output::success(&format!("{}: deleted", repo.name))
+                            }
                             Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
                         }
                     }
@@ -127,3 +179,441 @@ pub fn run_branch(
 
     Ok(())
 }
+
+/// Run the branch start command
+///
+/// Fetches each repo, creates `name` from the fresh default branch, checks it
+/// out, and fast-forwards the local default branch. Nothing is switched until
+/// every repo has been checked and fetched, and if any repo then fails to
+/// switch, the repos already switched are put back. The repos are recorded in
+/// state so `gr pr create` opens PRs for exactly this set, along with the
+/// `ticket`, if any, so it can reference the ticket.
+pub fn run_branch_start(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    name: &str,
+    repos_filter: Option<&[String]>,
+    force: bool,
+    ticket: Option<&str>,
+) -> anyhow::Result<()> {
+    if let Some(filter) = repos_filter {
+        for repo_name in filter {
+            if !manifest.repos.contains_key(repo_name) {
+                anyhow::bail!("Unknown repo '{}'", repo_name);
+            }
+        }
+    }
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| {
+            repos_filter
+                .map(|filter| filter.iter().any(|f| f == &r.name))
+                .unwrap_or(true)
+        })
+        .filter(|r| {
+            if !r.exists() {
+                Output::warning(&format!("{}: not cloned, skipping", r.name));
+            }
+            r.exists()
+        })
+        .collect();
+    if repos.is_empty() {
+        anyhow::bail!("No cloned repos to start '{}' in", name);
+    }
+
+    // Check every repo before touching any of them
+    for repo in &repos {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        if let Some(reason) = blocking_operation(&git_repo) {
+            anyhow::bail!("{}: blocked - {}", repo.name, reason);
+        }
+        if branch_exists(&git_repo, name) {
+            anyhow::bail!(
+                "{}: branch '{}' already exists; use `gr checkout {}`",
+                repo.name,
+                name,
+                name
+            );
+        }
+    }
+    let refs: Vec<&RepoInfo> = repos.iter().collect();
+    let unsaved = find_unsaved_work(&refs, name);
+    let Some(mode) = confirm_switch(&unsaved, name, force)? else {
+        Output::info("Branch start aborted.");
+        return Ok(());
+    };
+
+    Output::header(&format!(
+        "Starting branch '{}' in {} repos...",
+        name,
+        repos.len()
+    ));
+    println!();
+
+    // Fetch everything first so a network failure leaves the workspace untouched
+    let mut bases = Vec::new();
+    for repo in &repos {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let has_remote = get_remote_url(&git_repo, "origin")?.is_some();
+        if has_remote {
+            let spinner = Output::spinner(&format!("Fetching {}...", repo.name));
+            let fetched = fetch_remote(&git_repo, "origin");
+            spinner.finish_and_clear();
+            if let Err(e) = fetched {
+                anyhow::bail!("{}: fetch failed - {}", repo.name, e.to_string().trim());
+            }
+        }
+        let tracking = get_remote_tracking_oid(&git_repo, "origin", &repo.default_branch);
+        let base = if has_remote && tracking.is_some() {
+            format!("origin/{}", repo.default_branch)
+        } else {
+            repo.default_branch.clone()
+        };
+        bases.push(base);
+    }
+
+    let mut started: Vec<(&RepoInfo, String)> = Vec::new();
+    for (repo, base) in repos.iter().zip(&bases) {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let original = get_current_branch(&git_repo)?;
+
+        let dirty = unsaved.iter().any(|w| w.repo == repo.name && w.dirty);
+        if mode == SwitchMode::Stash && dirty {
+            stash_changes(&repo.absolute_path, &format!("gr: starting {}", name))?;
+            Output::info(&format!("{}: stashed uncommitted changes", repo.name));
+        }
+
+        if let Err(e) = create_and_checkout_branch_from(&git_repo, name, base) {
+            Output::error(&format!("{}: {}", repo.name, e.to_string().trim()));
+            roll_back(&started, name);
+            anyhow::bail!(
+                "Could not start '{}'; repos already switched were restored",
+                name
+            );
+        }
+        started.push((repo, original));
+
+        if base.starts_with("origin/") {
+            fast_forward_default(&repo.absolute_path, &repo.default_branch);
+        }
+        Output::success(&format!("{}: {} from {}", repo.name, name, base));
+    }
+
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    state.set_branch_repos(name, repos.iter().map(|r| r.name.clone()).collect());
+    if let Some(ticket) = ticket {
+        state.set_branch_ticket(name, ticket);
+    }
+    state.save(&state_path)?;
+
+    println!();
+    println!(
+        "Started {} in {} repo(s).",
+        Output::branch_name(name),
+        repos.len()
+    );
+
+    Ok(())
+}
+
+/// The branch name for `ticket` under `settings.tickets.branch_format`, with
+/// `description` as its slug
+pub fn ticket_branch_name(manifest: &Manifest, ticket: &str, description: Option<&str>) -> String {
+    let format = manifest
+        .settings
+        .tickets
+        .branch_format
+        .as_deref()
+        .unwrap_or(DEFAULT_BRANCH_FORMAT);
+    branch_name(format, ticket, description)
+}
+
+/// Put repos back on their original branches and delete the new one
+fn roll_back(started: &[(&RepoInfo, String)], name: &str) {
+    for (repo, original) in started {
+        let Ok(git_repo) = open_repo(&repo.absolute_path) else {
+            continue;
+        };
+        let restored = checkout_branch(&git_repo, original)
+            .and_then(|()| delete_local_branch(&git_repo, name, true));
+        if let Err(e) = restored {
+            Output::warning(&format!(
+                "{}: could not roll back to {} - {}",
+                repo.name, original, e
+            ));
+        }
+    }
+}
+
+/// Fast-forward the (not checked out) local default branch to its remote
+///
+/// A local default branch with commits of its own is left alone.
+fn fast_forward_default(repo_path: &Path, default_branch: &str) {
+    let refspec = format!("refs/remotes/origin/{0}:refs/heads/{0}", default_branch);
+    let _ = Command::new("git")
+        .args(["fetch", "--quiet", ".", &refspec])
+        .current_dir(repo_path)
+        .timed_output();
+}
+
+/// Run the branch rename command
+///
+/// Renames `old` to `new` in every repo that has it. Pushed branches are
+/// renamed on the remote through the platform API when it supports that,
+/// which keeps open PRs attached; otherwise the new branch is pushed and the
+/// old one deleted, unless a PR still uses it. Renaming a protected branch is
+/// refused unless `allow_protected`; the default branch is never renamed.
+pub async fn run_branch_rename(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    old: &str,
+    new: &str,
+    allow_protected: bool,
+) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| r.exists() && open_repo(&r.absolute_path).is_ok_and(|g| branch_exists(&g, old)))
+        .collect();
+    if repos.is_empty() {
+        anyhow::bail!("Branch '{}' doesn't exist in any repo", old);
+    }
+    for repo in &repos {
+        if old == repo.default_branch {
+            anyhow::bail!("{}: refusing to rename the default branch", repo.name);
+        }
+        if branch_exists(&open_repo(&repo.absolute_path)?, new) {
+            anyhow::bail!("{}: branch '{}' already exists", repo.name, new);
+        }
+    }
+    manifest.settings.ensure_unprotected(
+        "rename",
+        repos
+            .iter()
+            .map(|repo| (repo.name.as_str(), old, repo.default_branch.as_str())),
+        allow_protected,
+    )?;
+
+    Output::header(&format!(
+        "Renaming '{}' to '{}' in {} repos...",
+        old,
+        new,
+        repos.len()
+    ));
+    println!();
+
+    let mut error_count = 0;
+    for repo in &repos {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let pushed = remote_branch_exists(&git_repo, old, "origin");
+
+        if let Err(e) = rename_local_branch(&git_repo, old, new) {
+            Output::error(&format!("{}: {}", repo.name, e.to_string().trim()));
+            error_count += 1;
+            continue;
+        }
+        if !pushed {
+            Output::success(&format!("{}: renamed (local only)", repo.name));
+            continue;
+        }
+
+        let platform = get_platform_adapter(detect_platform(&repo.url), None);
+        if platform
+            .rename_branch(&repo.owner, &repo.repo, old, new)
+            .await
+            .is_ok()
+        {
+            // Pick up the renamed remote branch and drop the stale tracking ref
+            let _ = Command::new("git")
+                .args(["fetch", "--prune", "origin"])
+                .current_dir(&repo.absolute_path)
+                .timed_output();
+            let _ = Command::new("git")
+                .args(["branch", &format!("--set-upstream-to=origin/{}", new), new])
+                .current_dir(&repo.absolute_path)
+                .timed_output();
+            Output::success(&format!("{}: renamed locally and on the remote", repo.name));
+            continue;
+        }
+
+        let push = PushMode::Normal { set_upstream: true };
+        if let Err(e) = push_audited(workspace_root, &repo.name, &git_repo, new, push) {
+            Output::error(&format!(
+                "{}: renamed locally but push failed - {}",
+                repo.name,
+                e.to_string().trim()
+            ));
+            error_count += 1;
+            continue;
+        }
+        match platform
+            .find_pr_by_branch(&repo.owner, &repo.repo, old)
+            .await
+        {
+            Ok(Some(pr)) => Output::warning(&format!(
+                "{}: pushed {}, but PR #{} still uses {}; kept the old remote branch",
+                repo.name, new, pr.number, old
+            )),
+            _ => match delete_remote_audited(workspace_root, &repo.name, &git_repo, old, None) {
+                Ok(()) => Output::success(&format!(
+                    "{}: pushed {} and deleted the old remote branch",
+                    repo.name, new
+                )),
+                Err(e) => Output::warning(&format!(
+                    "{}: pushed {}, but could not delete {} - {}",
+                    repo.name,
+                    new,
+                    old,
+                    e.to_string().trim()
+                )),
+            },
+        }
+    }
+
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    state.rename_branch(old, new);
+    state.save(&state_path)?;
+
+    println!();
+    if error_count == 0 {
+        Output::success(&format!(
+            "Renamed {} to {} in {} repo(s).",
+            old,
+            Output::branch_name(new),
+            repos.len()
+        ));
+    } else {
+        Output::warning(&format!(
+            "Renamed in {} repo(s), {} failed.",
+            repos.len() - error_count,
+            error_count
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+
+    #[test]
+    fn test_branch_start_from_fresh_default() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+
+        // An upstream with a commit the workspace clone hasn't seen yet
+        let upstream = root.join("upstream");
+        init_repo(&upstream);
+        git(
+            &upstream,
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        );
+        git(&root, &["clone", "-q", "upstream", "api"]);
+        git(&upstream, &["commit", "-q", "--allow-empty", "-m", "Newer"]);
+
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+"#,
+        )
+        .unwrap();
+
+        run_branch_start(&root, &manifest, "feat/x", None, false, None).unwrap();
+
+        let api = root.join("api");
+        assert_eq!(git(&api, &["branch", "--show-current"]), "feat/x");
+        assert_eq!(git(&api, &["log", "-1", "--format=%s"]), "Newer");
+        assert_eq!(git(&api, &["log", "-1", "--format=%s", "main"]), "Newer");
+
+        let state = StateFile::load(root.join(".gitgrip").join("state.json")).unwrap();
+        assert_eq!(
+            state.get_branch_repos("feat/x"),
+            Some(&vec!["api".to_string()])
+        );
+
+        // Starting the same branch again is refused before anything changes
+        assert!(run_branch_start(&root, &manifest, "feat/x", None, false, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_branch_rename_local_only() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let api = root.join("api");
+        init_repo(&api);
+        git(&api, &["commit", "-q", "--allow-empty", "-m", "Initial"]);
+        git(&api, &["checkout", "-q", "-b", "feat/old"]);
+
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+"#,
+        )
+        .unwrap();
+        let state_path = root.join(".gitgrip").join("state.json");
+        let mut state = StateFile::default();
+        state.set_branch_repos("feat/old", vec!["api".to_string()]);
+        state.save(&state_path).unwrap();
+
+        run_branch_rename(&root, &manifest, "feat/old", "feat/new", false)
+            .await
+            .unwrap();
+
+        assert_eq!(git(&api, &["branch", "--show-current"]), "feat/new");
+        let state = StateFile::load(&state_path).unwrap();
+        assert!(state.get_branch_repos("feat/old").is_none());
+        assert!(state.get_branch_repos("feat/new").is_some());
+
+        // The default branch is never renamed
+        assert!(run_branch_rename(&root, &manifest, "main", "trunk", true)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_branch_rename_protected() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let api = root.join("api");
+        init_repo(&api);
+        git(&api, &["commit", "-q", "--allow-empty", "-m", "Initial"]);
+        git(&api, &["branch", "release/1.x"]);
+
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+settings:
+  protected_branches: ["release/*"]
+"#,
+        )
+        .unwrap();
+
+        let err = run_branch_rename(&root, &manifest, "release/1.x", "release/one", false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("protected"), "{}", err);
+        assert!(git(&api, &["branch", "--list", "release/1.x"]).contains("release/1.x"));
+
+        run_branch_rename(&root, &manifest, "release/1.x", "release/one", true)
+            .await
+            .unwrap();
+        assert!(git(&api, &["branch", "--list", "release/one"]).contains("release/one"));
+    }
+}
@@ -0,0 +1,511 @@
+//! Branch audit command implementation
+//!
+//! Lists branches on each repo's `origin` that are safe to clean up: merged
+//! into the default branch (or recorded as a merged PR group, which catches
+//! squash merges), abandoned (no commits for `stale_days`), or last committed
+//! to by someone who has left. The default branch, protected branches, and
+//! branches of open PR groups are never listed. `--delete` removes the
+//! listed branches after confirmation, skipping any that moved since the audit.
+
+use crate::cli::commands::audit::delete_remote_audited;
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::StateFile;
+use crate::git::{open_repo, path_exists};
+use crate::util::pool::{max_workers, parallel_map};
+use crate::util::profile::ProfiledCommand;
+use chrono::{DateTime, TimeZone, Utc};
+use dialoguer::Confirm;
+use git2::{BranchType, Oid, Repository};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Days without commits before a branch counts as abandoned
+const DEFAULT_STALE_DAYS: u64 = 90;
+
+/// Settings for `gr branch audit`
+#[derive(Debug, Clone, Default)]
+pub struct BranchAuditOptions {
+    /// Overrides `settings.branch_audit.stale_days`
+    pub days: Option<u64>,
+    /// Added to `settings.branch_audit.departed`
+    pub departed: Vec<String>,
+    /// Only report these findings (`merged`, `abandoned`, `departed`)
+    pub only: Vec<String>,
+    /// Use remote-tracking branches as they are instead of fetching first
+    pub no_fetch: bool,
+    /// Delete the listed branches from the remote
+    pub delete: bool,
+    /// Delete without asking
+    pub yes: bool,
+    /// Output JSON
+    pub json: bool,
+}
+
+/// Why a branch is listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Finding {
+    Merged,
+    Abandoned,
+    Departed,
+}
+
+impl Finding {
+    fn parse(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "merged" => Ok(Finding::Merged),
+            "abandoned" => Ok(Finding::Abandoned),
+            "departed" => Ok(Finding::Departed),
+            other => anyhow::bail!(
+                "Unknown finding '{}' (expected merged, abandoned, or departed)",
+                other
+            ),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Finding::Merged => "merged",
+            Finding::Abandoned => "abandoned",
+            Finding::Departed => "departed",
+        }
+    }
+}
+
+/// A remote branch worth cleaning up
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditedBranch {
+    pub repo: String,
+    pub branch: String,
+    #[serde(serialize_with = "serialize_oid")]
+    pub tip: Oid,
+    /// Author of the tip commit
+    pub author: String,
+    pub email: String,
+    pub last_commit: DateTime<Utc>,
+    pub findings: Vec<Finding>,
+}
+
+fn serialize_oid<S: serde::Serializer>(oid: &Oid, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&oid.to_string())
+}
+
+/// What makes a branch worth listing
+struct Criteria<'a> {
+    now: DateTime<Utc>,
+    stale_days: u64,
+    /// Lowercased names and emails
+    departed: Vec<String>,
+    only: &'a [Finding],
+    /// Branches of merged PR groups, as `(repo, branch)`
+    merged_groups: HashSet<(String, String)>,
+    /// Branches of PR groups still open, as `(repo, branch)`
+    open_groups: HashSet<(String, String)>,
+}
+
+/// Run `gr branch audit`
+pub fn run_branch_audit(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &BranchAuditOptions,
+) -> anyhow::Result<()> {
+    let only = options
+        .only
+        .iter()
+        .flat_map(|names| names.split(','))
+        .map(|name| Finding::parse(name.trim()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let settings = &manifest.settings.branch_audit;
+    let state =
+        StateFile::load(workspace_root.join(".gitgrip").join("state.json")).unwrap_or_default();
+    let mut merged_groups = HashSet::new();
+    let mut open_groups = HashSet::new();
+    for group in &state.pr_groups {
+        let groups = if group.merged_at.is_some() {
+            &mut merged_groups
+        } else {
+            &mut open_groups
+        };
+        groups.extend(
+            group
+                .prs
+                .iter()
+                .map(|pr| (pr.repo_name.clone(), group.branch.clone())),
+        );
+    }
+    let criteria = Criteria {
+        now: Utc::now(),
+        stale_days: options
+            .days
+            .or(settings.stale_days)
+            .unwrap_or(DEFAULT_STALE_DAYS),
+        departed: settings
+            .departed
+            .iter()
+            .chain(&options.departed)
+            .map(|who| who.trim().to_lowercase())
+            .collect(),
+        only: &only,
+        merged_groups,
+        open_groups,
+    };
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    let spinner = (!options.json).then(|| Output::spinner("Auditing remote branches..."));
+    let results = parallel_map(&repos, max_workers(), |repo| {
+        if !options.no_fetch {
+            fetch_prune(repo)?;
+        }
+        let git_repo = open_repo(&repo.absolute_path).map_err(|e| e.to_string())?;
+        audit_repo(&git_repo, repo, manifest, &criteria).map_err(|e| e.message().to_string())
+    });
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
+
+    let mut branches = Vec::new();
+    for (repo, result) in repos.iter().zip(results) {
+        match result {
+            Ok(found) => branches.extend(found),
+            Err(e) => Output::warning(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    if options.json {
+        Output::json(&branches)?;
+        return Ok(());
+    }
+
+    Output::header(&format!(
+        "Remote branch audit (abandoned after {} days)",
+        criteria.stale_days
+    ));
+    println!();
+    if branches.is_empty() {
+        Output::success("No remote branches to clean up.");
+        return Ok(());
+    }
+    let mut table = Table::new(vec!["Repo", "Branch", "Last commit", "Author", "Findings"]);
+    for branch in &branches {
+        let findings: Vec<&str> = branch.findings.iter().map(|f| f.name()).collect();
+        table.add_row(vec![
+            &branch.repo,
+            &branch.branch,
+            &format_age(criteria.now, branch.last_commit),
+            &branch.author,
+            &findings.join(", "),
+        ]);
+    }
+    table.print();
+    println!();
+
+    let repo_count = branches
+        .iter()
+        .map(|b| b.repo.as_str())
+        .collect::<HashSet<_>>()
+        .len();
+    if !options.delete {
+        Output::info(&format!(
+            "{} branch(es) in {} repo(s); pass --delete to remove them from the remote",
+            branches.len(),
+            repo_count
+        ));
+        return Ok(());
+    }
+
+    let proceed = options.yes
+        || Confirm::with_theme(&*Output::theme())
+            .with_prompt(format!(
+                "Delete {} remote branch(es) in {} repo(s)?",
+                branches.len(),
+                repo_count
+            ))
+            .default(false)
+            .interact()?;
+    if !proceed {
+        Output::info("Nothing deleted.");
+        return Ok(());
+    }
+
+    let mut deleted = 0;
+    let mut failed = 0;
+    for branch in &branches {
+        let Some(repo) = repos.iter().find(|r| r.name == branch.repo) else {
+            continue;
+        };
+        let result = open_repo(&repo.absolute_path).and_then(|git_repo| {
+            delete_remote_audited(
+                workspace_root,
+                &repo.name,
+                &git_repo,
+                &branch.branch,
+                Some(branch.tip),
+            )
+        });
+        match result {
+            Ok(()) => {
+                Output::success(&format!("{}: deleted {}", repo.name, branch.branch));
+                deleted += 1;
+            }
+            Err(e) => {
+                Output::error(&format!("{}: {}: {}", repo.name, branch.branch, e));
+                failed += 1;
+            }
+        }
+    }
+
+    println!();
+    if failed == 0 {
+        Output::success(&format!("Deleted {} remote branch(es).", deleted));
+        Ok(())
+    } else {
+        anyhow::bail!("Deleted {} remote branch(es), {} failed", deleted, failed)
+    }
+}
+
+/// Bring remote-tracking branches up to date, dropping deleted ones
+fn fetch_prune(repo: &RepoInfo) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["fetch", "--prune", "--quiet", "origin"])
+        .current_dir(&repo.absolute_path)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "fetch failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Branches on `origin` that meet the criteria, oldest first
+fn audit_repo(
+    git_repo: &Repository,
+    repo: &RepoInfo,
+    manifest: &Manifest,
+    criteria: &Criteria,
+) -> Result<Vec<AuditedBranch>, git2::Error> {
+    let default_tip = git_repo
+        .refname_to_id(&format!("refs/remotes/origin/{}", repo.default_branch))
+        .or_else(|_| git_repo.refname_to_id(&format!("refs/heads/{}", repo.default_branch)))
+        .ok();
+
+    let mut found = Vec::new();
+    for entry in git_repo.branches(Some(BranchType::Remote))? {
+        let (branch, _) = entry?;
+        let reference = branch.get();
+        if reference.symbolic_target().is_some() {
+            // origin/HEAD
+            continue;
+        }
+        let Some(name) = reference
+            .shorthand()
+            .and_then(|name| name.strip_prefix("origin/"))
+        else {
+            continue;
+        };
+        let key = (repo.name.clone(), name.to_string());
+        if manifest.settings.is_protected(name, &repo.default_branch)
+            || criteria.open_groups.contains(&key)
+        {
+            continue;
+        }
+        let commit = reference.peel_to_commit()?;
+        let tip = commit.id();
+
+        let merged = criteria.merged_groups.contains(&key)
+            || default_tip.is_some_and(|default_tip| {
+                default_tip == tip
+                    || git_repo
+                        .graph_descendant_of(default_tip, tip)
+                        .unwrap_or(false)
+            });
+        let author = commit.author();
+        let last_commit = Utc
+            .timestamp_opt(commit.time().seconds(), 0)
+            .single()
+            .unwrap_or(criteria.now);
+        let findings = findings(
+            criteria,
+            merged,
+            last_commit,
+            author.name().unwrap_or_default(),
+            author.email().unwrap_or_default(),
+        );
+        if findings.is_empty() {
+            continue;
+        }
+        found.push(AuditedBranch {
+            repo: repo.name.clone(),
+            branch: name.to_string(),
+            tip,
+            author: author.name().unwrap_or_default().to_string(),
+            email: author.email().unwrap_or_default().to_string(),
+            last_commit,
+            findings,
+        });
+    }
+    found.sort_by_key(|b| b.last_commit);
+    Ok(found)
+}
+
+/// Which criteria a branch meets, limited to `criteria.only` when given
+fn findings(
+    criteria: &Criteria,
+    merged: bool,
+    last_commit: DateTime<Utc>,
+    name: &str,
+    email: &str,
+) -> Vec<Finding> {
+    let age = criteria.now.signed_duration_since(last_commit).num_days();
+    let departed = criteria
+        .departed
+        .iter()
+        .any(|who| *who == name.to_lowercase() || *who == email.to_lowercase());
+    [
+        (Finding::Merged, merged),
+        (Finding::Abandoned, age >= criteria.stale_days as i64),
+        (Finding::Departed, departed),
+    ]
+    .into_iter()
+    .filter(|(finding, applies)| {
+        *applies && (criteria.only.is_empty() || criteria.only.contains(finding))
+    })
+    .map(|(finding, _)| finding)
+    .collect()
+}
+
+/// `today`, `3 days ago`, `14 months ago`
+fn format_age(now: DateTime<Utc>, then: DateTime<Utc>) -> String {
+    match now.signed_duration_since(then).num_days() {
+        days if days < 1 => "today".to_string(),
+        1 => "1 day ago".to_string(),
+        days if days < 60 => format!("{} days ago", days),
+        days => format!("{} months ago", days / 30),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Run git with the author and committer dates pinned to `date`
+    fn git_at(path: &Path, args: &[&str], date: &str) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_audit_repo() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        let recent = Utc::now().to_rfc3339();
+        let old = "2020-01-01T00:00:00Z";
+        init_repo(path);
+        git(path, &["config", "user.email", "dana@example.com"]);
+        git_at(path, &["commit", "-q", "--allow-empty", "-m", "Init"], old);
+        git_at(
+            path,
+            &["update-ref", "refs/remotes/origin/done", "HEAD"],
+            old,
+        );
+        git_at(path, &["checkout", "-q", "-b", "stale"], old);
+        git_at(path, &["commit", "-q", "--allow-empty", "-m", "Old"], old);
+        git_at(
+            path,
+            &["update-ref", "refs/remotes/origin/stale", "HEAD"],
+            old,
+        );
+        git_at(path, &["checkout", "-q", "-b", "active", "main"], &recent);
+        git_at(
+            path,
+            &["commit", "-q", "--allow-empty", "-m", "New"],
+            &recent,
+        );
+        git_at(
+            path,
+            &["update-ref", "refs/remotes/origin/active", "HEAD"],
+            &recent,
+        );
+        git_at(path, &["checkout", "-q", "main"], &recent);
+        git_at(
+            path,
+            &["commit", "-q", "--allow-empty", "-m", "Tip"],
+            &recent,
+        );
+        git_at(
+            path,
+            &["update-ref", "refs/remotes/origin/main", "HEAD"],
+            &recent,
+        );
+        git_at(
+            path,
+            &["update-ref", "refs/remotes/origin/open", "refs/heads/stale"],
+            &recent,
+        );
+
+        let manifest: Manifest = serde_yaml::from_str(
+            "repos:\n  api:\n    url: https://github.com/acme/api.git\n    path: api\n",
+        )
+        .unwrap();
+        let repo = RepoInfo::from_config(
+            "api",
+            &manifest.repos["api"],
+            &PathBuf::from(path.parent().unwrap()),
+        )
+        .unwrap();
+        let key = |branch: &str| ("api".to_string(), branch.to_string());
+        let mut criteria = Criteria {
+            now: Utc::now(),
+            stale_days: 90,
+            departed: vec!["dana@example.com".to_string()],
+            only: &[],
+            merged_groups: HashSet::from([key("active")]),
+            open_groups: HashSet::from([key("open")]),
+        };
+
+        let git_repo = Repository::open(path).unwrap();
+        let found = audit_repo(&git_repo, &repo, &manifest, &criteria).unwrap();
+        let summary: Vec<(&str, Vec<Finding>)> = found
+            .iter()
+            .map(|b| (b.branch.as_str(), b.findings.clone()))
+            .collect();
+        use Finding::*;
+        assert_eq!(
+            summary,
+            vec![
+                ("done", vec![Merged, Abandoned, Departed]),
+                ("stale", vec![Abandoned, Departed]),
+                ("active", vec![Merged, Departed]),
+            ]
+        );
+
+        criteria.departed.clear();
+        criteria.only = &[Abandoned];
+        let found = audit_repo(&git_repo, &repo, &manifest, &criteria).unwrap();
+        let names: Vec<&str> = found.iter().map(|b| b.branch.as_str()).collect();
+        assert_eq!(names, vec!["done", "stale"]);
+        assert!(Finding::parse("gone").is_err());
+    }
+}
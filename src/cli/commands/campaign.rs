@@ -0,0 +1,470 @@
+//! Campaign command implementation
+//!
+//! A campaign runs a script in every repo on a shared branch cut from the
+//! default branch and, wherever the script changed files, commits the
+//! result, pushes it, and opens a PR with a shared title and body. Campaign membership is kept in the state
+//! file so `gr campaign status` can report merge progress later.
+
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::{CampaignPR, StateFile};
+use crate::git::branch::{branch_exists, create_and_checkout_branch_from, delete_local_branch};
+use crate::git::remote::fetch_remote;
+use crate::git::status::get_status_info;
+use crate::git::{open_repo, path_exists, run_git};
+use crate::platform::types::PRState;
+use crate::platform::{detect_platform, get_platform_adapter};
+use crate::util::profile::ProfiledCommand;
+use crate::util::shell_command;
+use git2::Repository;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Settings for `gr campaign run`
+#[derive(Debug, Clone)]
+pub struct CampaignOptions {
+    /// Branch created in each changed repo (identifies the campaign)
+    pub branch: String,
+    /// PR title, also used as the commit message
+    pub title: String,
+    /// PR body
+    pub body: Option<String>,
+    /// Open PRs as drafts
+    pub draft: bool,
+}
+
+/// PR counts from a campaign run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CampaignRunSummary {
+    pub opened: usize,
+    /// Repos where the script, commit, push, or PR creation failed
+    pub failed: usize,
+}
+
+impl std::fmt::Display for CampaignRunSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} PR(s) opened, {} failed", self.opened, self.failed)
+    }
+}
+
+/// Run `script` in every repo and open a PR wherever it changed files
+///
+/// `script` is executed directly if it names a file, otherwise through the
+/// manifest's shell. It runs with the repo as working directory and the same
+/// `REPO_*` environment variables as `gr forall`. If it fails, whatever it
+/// left behind is stashed.
+pub async fn run_campaign_run(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    script: &str,
+    options: &CampaignOptions,
+) -> anyhow::Result<CampaignRunSummary> {
+    Output::header(&format!(
+        "Running campaign {}",
+        Output::branch_name(&options.branch)
+    ));
+    println!();
+
+    // Resolve a script file before changing into each repo
+    let script_path = Path::new(script)
+        .is_file()
+        .then(|| Path::new(script).canonicalize())
+        .transpose()?;
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    let campaign = state.campaigns.entry(options.branch.clone()).or_default();
+    campaign.title = options.title.clone();
+    campaign.body = options.body.clone();
+
+    let mut opened = 0;
+    let mut failed = 0;
+    for repo in &repos {
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                failed += 1;
+                continue;
+            }
+        };
+
+        if branch_exists(&git_repo, &options.branch) {
+            Output::info(&format!(
+                "{}: branch {} already exists, skipping",
+                repo.name, options.branch
+            ));
+            continue;
+        }
+
+        if let Ok(status) = get_status_info(&git_repo) {
+            if !status.staged.is_empty() || !status.modified.is_empty() {
+                Output::warning(&format!("{}: has uncommitted changes, skipping", repo.name));
+                continue;
+            }
+        }
+
+        if let Err(e) = fetch_remote(&git_repo, "origin") {
+            Output::warning(&format!("{}: fetch failed - {}", repo.name, e));
+        }
+        let base = default_base(&git_repo, repo);
+        if let Err(e) = create_and_checkout_branch_from(&git_repo, &options.branch, &base) {
+            Output::error(&format!(
+                "{}: cannot branch from {} - {}",
+                repo.name,
+                base,
+                e.to_string().trim()
+            ));
+            failed += 1;
+            continue;
+        }
+
+        let changed = match run_script(repo, manifest, script, script_path.as_deref()) {
+            Ok(changed) => changed,
+            Err(e) => {
+                Output::error(&format!("{}: script failed - {}", repo.name, e));
+                failed += 1;
+                match abandon_branch(&git_repo, &options.branch) {
+                    Ok(true) => Output::warning(&format!(
+                        "{}: partial changes stashed; see 'git stash list'",
+                        repo.name
+                    )),
+                    Ok(false) => {}
+                    Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+                }
+                continue;
+            }
+        };
+        if !changed {
+            if let Err(e) = abandon_branch(&git_repo, &options.branch) {
+                Output::error(&format!("{}: {}", repo.name, e));
+            }
+            continue;
+        }
+
+        if let Err(e) = commit_changes(&repo.absolute_path, options) {
+            Output::error(&format!("{}: {}", repo.name, e));
+            failed += 1;
+            continue;
+        }
+
+        let spinner = Output::spinner(&format!("Pushing {}...", repo.name));
+        let push = PushMode::Normal { set_upstream: true };
+        if let Err(e) = push_audited(workspace_root, &repo.name, &git_repo, &options.branch, push) {
+            spinner.finish_with_message(format!("{}: push failed - {}", repo.name, e));
+            failed += 1;
+            continue;
+        }
+
+        let platform = get_platform_adapter(detect_platform(&repo.url), None);
+        let pr = match platform
+            .find_pr_by_branch(&repo.owner, &repo.repo, &options.branch)
+            .await
+        {
+            Ok(Some(pr)) => Ok((pr.number, pr.url)),
+            _ => platform
+                .create_pull_request(
+                    &repo.owner,
+                    &repo.repo,
+                    &options.branch,
+                    &repo.default_branch,
+                    &options.title,
+                    options.body.as_deref(),
+                    options.draft,
+                )
+                .await
+                .map(|pr| (pr.number, pr.url)),
+        };
+
+        match pr {
+            Ok((number, url)) => {
+                spinner.finish_with_message(format!("{}: PR #{} - {}", repo.name, number, url));
+                campaign.prs.retain(|p| p.repo_name != repo.name);
+                campaign.prs.push(CampaignPR {
+                    repo_name: repo.name.clone(),
+                    owner: repo.owner.clone(),
+                    repo: repo.repo.clone(),
+                    number,
+                    url,
+                    state: PRState::Open,
+                });
+                opened += 1;
+            }
+            Err(e) => {
+                spinner.finish_with_message(format!("{}: failed - {}", repo.name, e));
+                failed += 1;
+            }
+        }
+    }
+
+    let total = campaign.prs.len();
+    if total == 0 {
+        state.campaigns.remove(&options.branch);
+    }
+    state.save(&state_path)?;
+
+    println!();
+    if opened == 0 {
+        Output::warning("The script changed no repos; no PRs were opened.");
+    } else {
+        Output::success(&format!(
+            "Opened {} PR(s); campaign now spans {} repo(s). Track it with 'gr campaign status {}'.",
+            opened, total, options.branch
+        ));
+    }
+
+    Ok(CampaignRunSummary { opened, failed })
+}
+
+/// Refresh and show the PR states of one campaign, or of all campaigns
+pub async fn run_campaign_status(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    branch: Option<&str>,
+) -> anyhow::Result<()> {
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+
+    let mut branches: Vec<String> = match branch {
+        Some(b) if state.campaigns.contains_key(b) => vec![b.to_string()],
+        Some(b) => anyhow::bail!("No campaign for branch '{}'", b),
+        None => state.campaigns.keys().cloned().collect(),
+    };
+    branches.sort();
+
+    if branches.is_empty() {
+        Output::info("No campaigns. Start one with 'gr campaign run <script> --branch <name>'.");
+        return Ok(());
+    }
+
+    for branch in &branches {
+        let Some(campaign) = state.campaigns.get_mut(branch) else {
+            continue;
+        };
+
+        for pr in campaign.prs.iter_mut() {
+            // Repos removed from the manifest keep their last known state
+            let Some(config) = manifest.repos.get(&pr.repo_name) else {
+                continue;
+            };
+            let platform = get_platform_adapter(detect_platform(&config.url), None);
+            match platform
+                .get_pull_request(&pr.owner, &pr.repo, pr.number)
+                .await
+            {
+                Ok(details) => {
+                    pr.state = if details.merged {
+                        PRState::Merged
+                    } else {
+                        details.state
+                    };
+                }
+                Err(e) => Output::warning(&format!("{}: {}", pr.repo_name, e)),
+            }
+        }
+
+        Output::header(&format!(
+            "{} ({})",
+            campaign.title,
+            Output::branch_name(branch)
+        ));
+        println!();
+
+        let mut table = Table::new(vec!["Repo", "PR", "State"]);
+        for pr in &campaign.prs {
+            table.add_row(vec![
+                &Output::repo_name(&pr.repo_name),
+                &format!("#{}", pr.number),
+                &Output::status(&pr.state.to_string()),
+            ]);
+        }
+        table.print();
+        println!();
+        println!("  {}", progress_summary(&campaign.prs));
+        println!();
+    }
+
+    state.save(&state_path)?;
+    Ok(())
+}
+
+/// `origin/<default>` when it exists, else the local default branch
+fn default_base(git_repo: &Repository, repo: &RepoInfo) -> String {
+    let remote_base = format!("origin/{}", repo.default_branch);
+    if git_repo
+        .find_reference(&format!("refs/remotes/{}", remote_base))
+        .is_ok()
+    {
+        remote_base
+    } else {
+        repo.default_branch.clone()
+    }
+}
+
+/// Run the campaign script in a repo; returns whether it left changes behind
+fn run_script(
+    repo: &RepoInfo,
+    manifest: &Manifest,
+    script: &str,
+    script_path: Option<&Path>,
+) -> anyhow::Result<bool> {
+    let mut command = match script_path {
+        Some(path) => Command::new(path),
+        None => shell_command(manifest.shell(), script),
+    };
+
+    let output = command
+        .current_dir(&repo.absolute_path)
+        .env("REPO_NAME", &repo.name)
+        .env("REPO_PATH", &repo.absolute_path)
+        .env("REPO_URL", &repo.url)
+        .env("REPO_BRANCH", &repo.default_branch)
+        .timed_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.trim());
+    }
+
+    Ok(!run_git(&repo.absolute_path, &["status", "--porcelain"])?
+        .trim()
+        .is_empty())
+}
+
+/// Go back to the previous branch and delete the campaign branch
+///
+/// Changes the script left are stashed first; returns whether there were any.
+fn abandon_branch(git_repo: &Repository, branch: &str) -> anyhow::Result<bool> {
+    let repo_path = crate::git::get_workdir(git_repo);
+    let dirty = !run_git(repo_path, &["status", "--porcelain"])?
+        .trim()
+        .is_empty();
+    if dirty {
+        let message = format!("gr campaign {}: script failed", branch);
+        run_git(
+            repo_path,
+            &["stash", "push", "--include-untracked", "-m", &message],
+        )?;
+    }
+    run_git(repo_path, &["checkout", "-q", "-"])?;
+    delete_local_branch(git_repo, branch, true)?;
+    Ok(dirty)
+}
+
+/// Commit everything the script changed on the (checked out) campaign branch
+fn commit_changes(repo_path: &Path, options: &CampaignOptions) -> anyhow::Result<()> {
+    run_git(repo_path, &["add", "-A"])?;
+
+    let mut args = vec!["commit", "-m", &options.title];
+    if let Some(body) = &options.body {
+        args.extend(["-m", body]);
+    }
+    run_git(repo_path, &args)?;
+    Ok(())
+}
+
+/// One-line merge progress, e.g. `3/5 merged, 1 open, 1 closed`
+fn progress_summary(prs: &[CampaignPR]) -> String {
+    let count = |state: PRState| prs.iter().filter(|p| p.state == state).count();
+    format!(
+        "{}/{} merged, {} open, {} closed",
+        count(PRState::Merged),
+        prs.len(),
+        count(PRState::Open),
+        count(PRState::Closed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::manifest::RepoConfig;
+    use crate::git::testing::init_repo;
+    use tempfile::TempDir;
+
+    fn pr(repo: &str, state: PRState) -> CampaignPR {
+        CampaignPR {
+            repo_name: repo.to_string(),
+            owner: "acme".to_string(),
+            repo: repo.to_string(),
+            number: 1,
+            url: String::new(),
+            state,
+        }
+    }
+
+    #[test]
+    fn test_progress_summary() {
+        let prs = vec![
+            pr("api", PRState::Merged),
+            pr("web", PRState::Open),
+            pr("cli", PRState::Merged),
+        ];
+        assert_eq!(progress_summary(&prs), "2/3 merged, 1 open, 0 closed");
+    }
+
+    #[test]
+    fn test_run_script_detects_changes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let path = root.join("app");
+        std::fs::create_dir(&path).unwrap();
+        run_git(&path, &["init", "-b", "main"]).unwrap();
+
+        let config: RepoConfig =
+            serde_yaml::from_str("url: git@github.com:acme/app.git\npath: app\n").unwrap();
+        let repo = RepoInfo::from_config("app", &config, &root).unwrap();
+
+        let manifest = Manifest::parse(
+            "repos:\n  app:\n    url: git@github.com:acme/app.git\n    path: app\n",
+        )
+        .unwrap();
+
+        assert!(!run_script(&repo, &manifest, "true", None).unwrap());
+        assert!(run_script(&repo, &manifest, "echo \"$REPO_NAME\" > name.txt", None).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(path.join("name.txt")).unwrap(),
+            "app\n"
+        );
+        assert!(run_script(&repo, &manifest, "exit 3", None).is_err());
+    }
+
+    #[test]
+    fn test_abandon_branch_stashes_partial_changes() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::write(path.join("a.txt"), "a\n").unwrap();
+        run_git(path, &["add", "."]).unwrap();
+        run_git(path, &["commit", "-q", "-m", "Initial commit"]).unwrap();
+        run_git(path, &["checkout", "-q", "-b", "feature"]).unwrap();
+
+        let git_repo = open_repo(path).unwrap();
+        create_and_checkout_branch_from(&git_repo, "campaign/x", "main").unwrap();
+        std::fs::write(path.join("a.txt"), "half done\n").unwrap();
+        std::fs::write(path.join("new.txt"), "new\n").unwrap();
+
+        assert!(abandon_branch(&git_repo, "campaign/x").unwrap());
+        assert_eq!(std::fs::read_to_string(path.join("a.txt")).unwrap(), "a\n");
+        assert!(!path.join("new.txt").exists());
+        assert_eq!(
+            run_git(path, &["rev-parse", "--abbrev-ref", "HEAD"])
+                .unwrap()
+                .trim(),
+            "feature"
+        );
+        assert!(!branch_exists(&git_repo, "campaign/x"));
+        assert!(run_git(path, &["stash", "list"])
+            .unwrap()
+            .contains("campaign/x"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Changelog command implementation
+//!
+//! Aggregates the PR groups merged since a tag (or date) into one workspace
+//! changelog. Groups are recorded in the state file by `gr pr create` and
+//! `gr pr merge`; each becomes one entry linking all of its PRs, under a
+//! section for its first label, or for the conventional commit type of its
+//! title (`feat:`, `fix:`, ...).
+
+use crate::cli::output::Output;
+use crate::core::audit::parse_since;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::{PRGroup, StateFile};
+use crate::git::open_repo;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Settings for `gr changelog`
+#[derive(Debug, Clone, Default)]
+pub struct ChangelogOptions {
+    /// Tag, age, or date to start from; every merged group if unset
+    pub since: Option<String>,
+    /// Output JSON
+    pub json: bool,
+}
+
+/// Conventional commit types and their section titles, in section order
+const TYPES: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build"),
+    ("ci", "CI"),
+    ("chore", "Chores"),
+];
+
+/// Section for groups with neither a label nor a known type
+const OTHER: &str = "Other";
+
+#[derive(Debug, Serialize)]
+struct Section<'a> {
+    title: String,
+    groups: Vec<&'a PRGroup>,
+}
+
+/// Run the changelog command
+pub fn run_changelog(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &ChangelogOptions,
+) -> anyhow::Result<()> {
+    let since = match options.since {
+        Some(ref since) => Some(resolve_since(workspace_root, manifest, since)?),
+        None => None,
+    };
+
+    let state =
+        StateFile::load(workspace_root.join(".gitgrip").join("state.json")).unwrap_or_default();
+    let mut groups: Vec<(DateTime<Utc>, &PRGroup)> = state
+        .pr_groups
+        .iter()
+        .filter_map(|group| {
+            let merged = DateTime::parse_from_rfc3339(group.merged_at.as_deref()?).ok()?;
+            Some((merged.with_timezone(&Utc), group))
+        })
+        .filter(|(merged, _)| since.map_or(true, |since| *merged > since))
+        .collect();
+    groups.sort_by_key(|(merged, _)| *merged);
+    let sections = group_sections(groups.into_iter().map(|(_, group)| group));
+
+    if options.json {
+        Output::json(&sections)?;
+    } else {
+        print!("{}", render_markdown(&sections, options.since.as_deref()));
+    }
+    Ok(())
+}
+
+/// When `since` was: the newest commit it tags in any repo, else an age or date
+fn resolve_since(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    since: &str,
+) -> anyhow::Result<DateTime<Utc>> {
+    let tagged = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| repo.exists())
+        .filter_map(|repo| {
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            let commit = git_repo
+                .revparse_single(&format!("refs/tags/{}", since))
+                .ok()?
+                .peel_to_commit()
+                .ok()?;
+            Utc.timestamp_opt(commit.time().seconds(), 0).single()
+        })
+        .max();
+    match tagged {
+        Some(time) => Ok(time),
+        None => parse_since(since, Utc::now()).map_err(|_| {
+            anyhow::anyhow!(
+                "No repo has a tag '{}', and it isn't an age or date either",
+                since
+            )
+        }),
+    }
+}
+
+/// The section a group goes in
+fn section(group: &PRGroup) -> String {
+    if let Some(label) = group.labels.first() {
+        return label.clone();
+    }
+    let (kind, _) = split_type(&group.title);
+    TYPES
+        .iter()
+        .find(|(name, _)| Some(*name) == kind)
+        .map_or(OTHER, |(_, title)| title)
+        .to_string()
+}
+
+/// Split `feat(scope)!: Add x` into its type and the rest; a leading ticket
+/// (`PROJ-42: `) stays with the rest
+fn split_type(title: &str) -> (Option<&str>, String) {
+    let (ticket, rest) = match title.split_once(": ") {
+        Some((ticket, rest)) if is_ticket(ticket) => (Some(ticket), rest),
+        _ => (None, title),
+    };
+    let Some((prefix, description)) = rest.split_once(':') else {
+        return (None, title.to_string());
+    };
+    let kind = prefix
+        .trim_end_matches('!')
+        .split('(')
+        .next()
+        .unwrap_or_default();
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_lowercase()) {
+        return (None, title.to_string());
+    }
+    let description = description.trim();
+    let rest = match ticket {
+        Some(ticket) => format!("{}: {}", ticket, description),
+        None => description.to_string(),
+    };
+    (Some(kind), rest)
+}
+
+/// `PROJ-42`
+fn is_ticket(text: &str) -> bool {
+    text.split_once('-').is_some_and(|(project, number)| {
+        !project.is_empty()
+            && project
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// Sections in order: conventional types, then labels alphabetically, then
+/// everything else; groups keep their merge order
+fn group_sections<'a>(groups: impl Iterator<Item = &'a PRGroup>) -> Vec<Section<'a>> {
+    let mut sections: Vec<Section> = Vec::new();
+    for group in groups {
+        let title = section(group);
+        match sections.iter_mut().find(|s| s.title == title) {
+            Some(section) => section.groups.push(group),
+            None => sections.push(Section {
+                title,
+                groups: vec![group],
+            }),
+        }
+    }
+    sections.sort_by_key(|s| {
+        let rank = match TYPES.iter().position(|(_, title)| *title == s.title) {
+            Some(position) => position,
+            None if s.title == OTHER => TYPES.len() + 1,
+            None => TYPES.len(),
+        };
+        (rank, s.title.clone())
+    });
+    sections
+}
+
+fn render_markdown(sections: &[Section], since: Option<&str>) -> String {
+    let mut out = match since {
+        Some(since) => format!("## Changelog (since {})\n", since),
+        None => "## Changelog\n".to_string(),
+    };
+    if sections.is_empty() {
+        out.push_str("\n_No merged PR groups._\n");
+        return out;
+    }
+
+    for section in sections {
+        out.push_str(&format!("\n### {}\n\n", section.title));
+        for group in &section.groups {
+            let links: Vec<String> = group
+                .prs
+                .iter()
+                .map(|pr| format!("[{}#{}]({})", pr.repo_name, pr.number, pr.url))
+                .collect();
+            let (_, title) = split_type(&group.title);
+            out.push_str(&format!("- {} ({})\n", title, links.join(", ")));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::GroupPR;
+
+    fn group(title: &str, labels: &[&str], repos: &[&str]) -> PRGroup {
+        PRGroup {
+            branch: "feat/x".to_string(),
+            title: title.to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            prs: repos
+                .iter()
+                .enumerate()
+                .map(|(i, repo)| GroupPR {
+                    repo_name: repo.to_string(),
+                    number: i as u64 + 1,
+                    url: format!("https://github.com/acme/{}/pull/{}", repo, i + 1),
+                    merged: true,
+                })
+                .collect(),
+            merged_at: Some("2026-05-01T00:00:00Z".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        assert_eq!(
+            split_type("PROJ-42: fix(auth)!: Expire sessions"),
+            (Some("fix"), "PROJ-42: Expire sessions".to_string())
+        );
+        assert_eq!(split_type("Bump deps"), (None, "Bump deps".to_string()));
+        assert_eq!(
+            split_type("Note: Read this"),
+            (None, "Note: Read this".to_string())
+        );
+
+        let groups = [
+            group("Tidy up", &[], &["docs"]),
+            group("Rotate keys", &["security"], &["api"]),
+            group("fix: Expire sessions", &[], &["api"]),
+            group("feat: Add login", &[], &["app", "api"]),
+        ];
+        let sections = group_sections(groups.iter());
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["Features", "Bug Fixes", "security", "Other"]);
+
+        let out = render_markdown(&sections, Some("v1.2.0"));
+        assert!(out.starts_with("## Changelog (since v1.2.0)\n"));
+        assert!(out.contains(
+            "### Features\n\n- Add login ([app#1](https://github.com/acme/app/pull/1), \
+             [api#2](https://github.com/acme/api/pull/2))\n"
+        ));
+        assert!(out.contains("### Other\n\n- Tidy up ([docs#1]"));
+
+        assert!(render_markdown(&[], None).contains("_No merged PR groups._"));
+    }
+}
@@ -1,57 +1,262 @@
 //! Checkout command implementation
+//!
+//! Also home to the guard shared by commands that switch branches: before a
+//! repo is moved off a branch with unpushed commits or uncommitted changes,
+//! the user has to confirm (or pass `--force`).
 
+use crate::cli::i18n::msg;
 use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
 use crate::git::{
     branch::{branch_exists, checkout_branch},
-    open_repo,
+    get_current_branch, open_repo,
+    revision::{checkout_revision, resolve_revision},
+    status::{blocking_operation, get_unpushed_count, has_uncommitted_changes},
 };
-use std::path::PathBuf;
+use crate::util::profile::ProfiledCommand;
+use dialoguer::Select;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Work a branch switch would move away from in one repo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnsavedWork {
+    pub repo: String,
+    pub branch: String,
+    /// Commits on `branch` that aren't on the remote yet
+    pub unpushed: usize,
+    /// Whether the working tree has uncommitted changes
+    pub dirty: bool,
+}
+
+impl UnsavedWork {
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.unpushed > 0 {
+            parts.push(msg!("checkout.unpushed", count = self.unpushed));
+        }
+        if self.dirty {
+            parts.push(msg!("checkout.uncommitted"));
+        }
+        msg!(
+            "checkout.unsaved_work",
+            repo = self.repo,
+            branch = self.branch,
+            work = parts.join(", ")
+        )
+    }
+}
+
+/// How to treat uncommitted changes when switching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SwitchMode {
+    /// Let git carry them over to the new branch
+    Carry,
+    /// Stash them first
+    Stash,
+}
+
+/// Find repos with work that switching to `target` would move away from
+pub(crate) fn find_unsaved_work(repos: &[&RepoInfo], target: &str) -> Vec<UnsavedWork> {
+    repos
+        .iter()
+        .filter(|repo| repo.exists())
+        .filter_map(|repo| {
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            let branch = get_current_branch(&git_repo).ok()?;
+            if branch == target {
+                return None;
+            }
+            let unpushed = get_unpushed_count(&repo.absolute_path, &branch, &repo.default_branch);
+            let dirty = has_uncommitted_changes(&git_repo).unwrap_or(false);
+            (unpushed > 0 || dirty).then(|| UnsavedWork {
+                repo: repo.name.clone(),
+                branch,
+                unpushed,
+                dirty,
+            })
+        })
+        .collect()
+}
+
+/// Ask how to proceed when switching to `target` would leave work behind
+///
+/// Returns `None` if the user aborts. With `force`, or nothing unsaved, the
+/// switch goes ahead carrying any changes. Without a terminal to ask on, this
+/// fails instead of switching.
+pub(crate) fn confirm_switch(
+    unsaved: &[UnsavedWork],
+    target: &str,
+    force: bool,
+) -> anyhow::Result<Option<SwitchMode>> {
+    if unsaved.is_empty() || force {
+        return Ok(Some(SwitchMode::Carry));
+    }
+
+    Output::warning(&msg!(
+        "checkout.unsaved",
+        branch = target,
+        count = unsaved.len()
+    ));
+    for work in unsaved {
+        Output::list_item(&work.describe());
+    }
+    println!();
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Refusing to switch branches with unpushed or uncommitted work; push or commit it first, or pass --force"
+        );
+    }
+
+    let mut choices = Vec::new();
+    if unsaved.iter().any(|w| w.dirty) {
+        choices.push((
+            msg!("checkout.choice_carry", branch = target),
+            Some(SwitchMode::Carry),
+        ));
+        choices.push((msg!("checkout.choice_stash"), Some(SwitchMode::Stash)));
+    } else {
+        choices.push((msg!("checkout.choice_switch"), Some(SwitchMode::Carry)));
+    }
+    choices.push((msg!("checkout.choice_abort"), None));
+
+    let labels: Vec<&str> = choices.iter().map(|(label, _)| label.as_str()).collect();
+    let selection = Select::with_theme(&*Output::theme())
+        .with_prompt(msg!("checkout.prompt"))
+        .items(&labels)
+        .default(labels.len() - 1)
+        .interact()?;
+    Ok(choices[selection].1)
+}
+
+/// Stash uncommitted changes, including untracked files
+pub(crate) fn stash_changes(repo_path: &Path, message: &str) -> anyhow::Result<()> {
+    let output = Command::new("git")
+        .args(["stash", "push", "--include-untracked", "-m", message])
+        .current_dir(repo_path)
+        .timed_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git stash failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// The manifest `revision:` a repo goes to instead of `branch_name`
+///
+/// Checking out a repo's default branch returns a pinned repo to its pin.
+fn pinned_revision<'a>(
+    manifest: &'a Manifest,
+    repo: &RepoInfo,
+    branch_name: &str,
+) -> Option<&'a str> {
+    if branch_name != repo.default_branch {
+        return None;
+    }
+    manifest
+        .repos
+        .get(&repo.name)
+        .and_then(|config| config.revision.as_deref())
+}
 
 /// Run the checkout command
+///
+/// Repos with unpushed commits or uncommitted changes are only switched after
+/// confirmation, or with `force`. Checking out the default branch moves repos
+/// with a manifest `revision:` to that revision instead.
 pub fn run_checkout(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     branch_name: &str,
+    force: bool,
 ) -> anyhow::Result<()> {
-    Output::header(&format!(
-        "Checking out '{}' in {} repos...",
-        branch_name,
-        manifest.repos.len()
-    ));
-    println!();
-
     let repos: Vec<RepoInfo> = manifest
         .repos
         .iter()
         .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
         .collect();
 
+    let moving: Vec<&RepoInfo> = repos
+        .iter()
+        .filter(|repo| {
+            repo.exists()
+                && (pinned_revision(manifest, repo, branch_name).is_some()
+                    || open_repo(&repo.absolute_path).is_ok_and(|r| branch_exists(&r, branch_name)))
+        })
+        .collect();
+    let unsaved = find_unsaved_work(&moving, branch_name);
+    let Some(mode) = confirm_switch(&unsaved, branch_name, force)? else {
+        Output::info(&msg!("checkout.aborted"));
+        return Ok(());
+    };
+
+    Output::header(&msg!(
+        "checkout.checking_out",
+        branch = branch_name,
+        count = manifest.repos.len()
+    ));
+    println!();
+
     let mut success_count = 0;
     let mut _skip_count = 0;
 
     for repo in &repos {
         if !repo.exists() {
-            Output::warning(&format!("{}: not cloned", repo.name));
+            Output::warning(&msg!("checkout.not_cloned", repo = repo.name));
             _skip_count += 1;
             continue;
         }
 
         match open_repo(&repo.absolute_path) {
             Ok(git_repo) => {
-                if !branch_exists(&git_repo, branch_name) {
-                    Output::info(&format!("{}: branch doesn't exist, skipping", repo.name));
+                if let Some(reason) = blocking_operation(&git_repo) {
+                    Output::error(&msg!("checkout.blocked", repo = repo.name, reason = reason));
+                    _skip_count += 1;
+                    continue;
+                }
+
+                let pinned = pinned_revision(manifest, repo, branch_name);
+                if pinned.is_none() && !branch_exists(&git_repo, branch_name) {
+                    Output::info(&msg!("checkout.no_branch", repo = repo.name));
                     _skip_count += 1;
                     continue;
                 }
 
-                match checkout_branch(&git_repo, branch_name) {
-                    Ok(()) => {
+                let dirty = unsaved.iter().any(|w| w.repo == repo.name && w.dirty);
+                if mode == SwitchMode::Stash && dirty {
+                    let message = format!("gr: switching to {}", branch_name);
+                    if let Err(e) = stash_changes(&repo.absolute_path, &message) {
+                        Output::error(&format!("{}: {}", repo.name, e));
+                        _skip_count += 1;
+                        continue;
+                    }
+                    Output::info(&msg!("checkout.stashed", repo = repo.name));
+                }
+
+                let result = match pinned {
+                    Some(revision) => resolve_revision(&git_repo, revision, "origin")
+                        .and_then(|target| checkout_revision(&git_repo, &target, "origin")),
+                    None => checkout_branch(&git_repo, branch_name),
+                };
+                match (result, pinned) {
+                    (Ok(()), Some(revision)) => {
+                        Output::success(&msg!(
+                            "checkout.pinned",
+                            repo = repo.name,
+                            revision = revision
+                        ));
+                        success_count += 1;
+                    }
+                    (Ok(()), None) => {
                         Output::success(&repo.name);
                         success_count += 1;
                     }
-                    Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+                    (Err(e), _) => Output::error(&format!("{}: {}", repo.name, e)),
                 }
             }
             Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
@@ -60,11 +265,69 @@ pub fn run_checkout(
 
     println!();
     println!(
-        "Switched {}/{} repos to {}",
-        success_count,
-        repos.len(),
-        Output::branch_name(branch_name)
+        "{}",
+        msg!(
+            "checkout.summary",
+            count = success_count,
+            total = repos.len(),
+            branch = Output::branch_name(branch_name)
+        )
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+
+    #[test]
+    fn test_find_unsaved_work() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+  web:
+    url: git@github.com:acme/web.git
+    path: web
+"#,
+        )
+        .unwrap();
+        for name in ["api", "web"] {
+            let path = root.join(name);
+            init_repo(&path);
+            git(&path, &["commit", "-q", "--allow-empty", "-m", "Initial"]);
+            git(&path, &["branch", "feat"]);
+        }
+        let api = root.join("api");
+        git(&api, &["checkout", "-q", "-b", "wip"]);
+        git(&api, &["commit", "-q", "--allow-empty", "-m", "Local only"]);
+        std::fs::write(root.join("web").join("notes.txt"), "draft").unwrap();
+
+        let repos: Vec<RepoInfo> = manifest
+            .repos
+            .iter()
+            .filter_map(|(name, config)| RepoInfo::from_config(name, config, &root))
+            .collect();
+        let refs: Vec<&RepoInfo> = repos.iter().collect();
+
+        let mut unsaved = find_unsaved_work(&refs, "feat");
+        unsaved.sort_by(|a, b| a.repo.cmp(&b.repo));
+        assert_eq!(unsaved.len(), 2);
+        assert_eq!(unsaved[0].describe(), "api on wip: 1 unpushed commit(s)");
+        assert_eq!(unsaved[1].describe(), "web on main: uncommitted changes");
+
+        // Already on the target branch: nothing is left behind
+        let api: Vec<&RepoInfo> = refs.iter().copied().filter(|r| r.name == "api").collect();
+        assert!(find_unsaved_work(&api, "wip").is_empty());
+        assert_eq!(
+            confirm_switch(&unsaved, "feat", true).unwrap(),
+            Some(SwitchMode::Carry)
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Cherry-pick command implementation
+//!
+//! Cherry-picks commits onto the current branch in each repo where they exist.
+
+use crate::cli::commands::resolve::ResolveAction;
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::cherry_pick::{
+    abort_cherry_pick, cherry_pick_commits, commits_between, continue_cherry_pick, has_pending,
+    resolve_commits, CherryPickOutcome,
+};
+use crate::git::status::get_status_info;
+use crate::git::{open_repo, path_exists};
+use std::path::PathBuf;
+
+/// Run the cherry-pick command
+///
+/// `commits` may contain single revisions or `A..B` ranges; `from_branch`
+/// picks every commit on that branch that is not on the current branch.
+/// `resume` continues or aborts a pick that stopped on conflicts.
+pub fn run_cherry_pick(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    commits: &[String],
+    from_branch: Option<&str>,
+    record_origin: bool,
+    sign: bool,
+    resume: Option<ResolveAction>,
+) -> anyhow::Result<()> {
+    match resume {
+        Some(ResolveAction::Abort) => return run_cherry_pick_abort(workspace_root, manifest),
+        Some(ResolveAction::Continue) => return run_cherry_pick_continue(workspace_root, manifest),
+        None => {}
+    }
+    if commits.is_empty() && from_branch.is_none() {
+        anyhow::bail!("Specify commits to pick or --from <branch>");
+    }
+
+    Output::header("Cherry-picking");
+    println!();
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    let mut picked_repos = 0;
+    let mut conflicted: Vec<String> = Vec::new();
+
+    for repo in &repos {
+        if !path_exists(&repo.absolute_path) {
+            continue;
+        }
+
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                continue;
+            }
+        };
+
+        let to_pick = match from_branch {
+            Some(branch) => commits_between(&git_repo, "HEAD", branch),
+            None => resolve_commits(&git_repo, commits),
+        };
+        let to_pick = match to_pick {
+            Ok(c) if !c.is_empty() => c,
+            // Source commits don't exist in this repo
+            _ => continue,
+        };
+
+        if let Ok(status) = get_status_info(&git_repo) {
+            if !status.staged.is_empty() || !status.modified.is_empty() {
+                Output::warning(&format!("{}: has uncommitted changes, skipping", repo.name));
+                continue;
+            }
+        }
+
+        match cherry_pick_commits(&git_repo, &to_pick, record_origin, sign) {
+            Ok(outcome) => {
+                if report_outcome(&repo.name, &outcome) {
+                    conflicted.push(repo.name.clone());
+                } else {
+                    picked_repos += 1;
+                }
+            }
+            Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    println!();
+    print_summary(picked_repos, &conflicted);
+    Ok(())
+}
+
+fn run_cherry_pick_continue(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
+    Output::header("Continuing cherry-pick");
+    println!();
+
+    let mut picked_repos = 0;
+    let mut conflicted: Vec<String> = Vec::new();
+
+    for (name, config) in &manifest.repos {
+        let Some(repo) = RepoInfo::from_config(name, config, workspace_root) else {
+            continue;
+        };
+        let Ok(git_repo) = open_repo(&repo.absolute_path) else {
+            continue;
+        };
+        if !has_pending(&git_repo) {
+            continue;
+        }
+
+        match continue_cherry_pick(&git_repo) {
+            Ok(outcome) => {
+                if report_outcome(&repo.name, &outcome) {
+                    conflicted.push(repo.name.clone());
+                } else {
+                    picked_repos += 1;
+                }
+            }
+            Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    println!();
+    print_summary(picked_repos, &conflicted);
+    Ok(())
+}
+
+fn run_cherry_pick_abort(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
+    Output::header("Aborting cherry-pick");
+    println!();
+
+    for (name, config) in &manifest.repos {
+        let Some(repo) = RepoInfo::from_config(name, config, workspace_root) else {
+            continue;
+        };
+        let Ok(git_repo) = open_repo(&repo.absolute_path) else {
+            continue;
+        };
+        if !has_pending(&git_repo) {
+            continue;
+        }
+
+        match abort_cherry_pick(&git_repo) {
+            Ok(()) => Output::success(&format!("{}: cherry-pick aborted", repo.name)),
+            Err(e) => Output::error(&format!("{}: failed to abort - {}", repo.name, e)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the per-repo result; returns true if the repo stopped on conflicts
+fn report_outcome(name: &str, outcome: &CherryPickOutcome) -> bool {
+    match &outcome.conflict {
+        Some((oid, paths)) => {
+            Output::warning(&format!(
+                "{}: conflict picking {} ({} applied)",
+                name,
+                &oid.to_string()[..7],
+                outcome.picked
+            ));
+            for path in paths {
+                println!("    {}", path);
+            }
+            true
+        }
+        None => {
+            let mut msg = format!("{}: picked {} commit(s)", name, outcome.picked);
+            if outcome.empty > 0 {
+                msg.push_str(&format!(", {} already applied", outcome.empty));
+            }
+            Output::success(&msg);
+            false
+        }
+    }
+}
+
+fn print_summary(picked_repos: usize, conflicted: &[String]) {
+    if conflicted.is_empty() {
+        if picked_repos == 0 {
+            Output::info("No repos contained the requested commits.");
+        } else {
+            Output::success(&format!("Cherry-picked into {} repo(s)", picked_repos));
+        }
+    } else {
+        Output::warning(&format!(
+            "{} repo(s) have conflicts: {}",
+            conflicted.len(),
+            conflicted.join(", ")
+        ));
+        println!();
+        println!("To continue after resolving conflicts: gr cherry-pick --continue");
+        println!("To abort the cherry-pick: gr cherry-pick --abort");
+    }
+}
@@ -0,0 +1,330 @@
+//! CI artifacts command implementation
+//!
+//! Downloads artifacts from the newest successful CI run on a branch of each
+//! repo into `<output>/<repo>/<artifact>.zip`, checks each download against
+//! the digest (or size) the platform reports, and writes a `SHA256SUMS` file
+//! covering the whole bundle.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::{get_current_branch, open_repo};
+use crate::platform::traits::PlatformError;
+use crate::platform::{detect_platform, get_platform_adapter, CiArtifact};
+use crate::util::concurrent_map;
+use crate::util::disk::format_bytes;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Settings for `gr ci artifacts`
+#[derive(Debug, Clone, Default)]
+pub struct CiArtifactsOptions {
+    /// Artifact names to download; empty means all
+    pub names: Vec<String>,
+    /// Only these repos; empty means every repo with a matching artifact
+    pub repos: Vec<String>,
+    /// Branch to take the run from instead of each repo's current branch
+    pub branch: Option<String>,
+    /// Only runs of this workflow (GitHub)
+    pub workflow: Option<String>,
+    /// Directory to download into
+    pub output: PathBuf,
+    /// Output JSON
+    pub json: bool,
+}
+
+/// One artifact download
+#[derive(Debug, Serialize)]
+struct Download {
+    repo: String,
+    branch: String,
+    artifact: String,
+    run_id: Option<u64>,
+    /// Path relative to the output directory
+    path: Option<String>,
+    size: Option<u64>,
+    sha256: Option<String>,
+    /// Whether the platform reported a digest that the download matched
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Run the ci artifacts command
+pub async fn run_ci_artifacts(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &CiArtifactsOptions,
+) -> anyhow::Result<()> {
+    if let Some(unknown) = options
+        .repos
+        .iter()
+        .find(|r| !manifest.repos.contains_key(*r))
+    {
+        anyhow::bail!("Unknown repo '{}'", unknown);
+    }
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| options.repos.is_empty() || options.repos.contains(&r.name))
+        .filter(|r| r.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let targets: Vec<(RepoInfo, String)> = repos
+        .into_iter()
+        .map(|repo| {
+            let branch = options.branch.clone().unwrap_or_else(|| {
+                open_repo(&repo.absolute_path)
+                    .ok()
+                    .and_then(|r| get_current_branch(&r).ok())
+                    .unwrap_or_else(|| repo.default_branch.clone())
+            });
+            (repo, branch)
+        })
+        .collect();
+
+    let spinner = Output::spinner(&format!(
+        "Finding artifacts in {} repo(s)...",
+        targets.len()
+    ));
+    let listings = concurrent_map(
+        &targets,
+        manifest.settings.api_concurrency,
+        |(repo, branch)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            platform
+                .list_ci_artifacts(&repo.owner, &repo.repo, branch, options.workflow.as_deref())
+                .await
+        },
+    )
+    .await;
+    spinner.finish_and_clear();
+
+    let mut downloads = Vec::new();
+    let mut wanted: Vec<(RepoInfo, String, CiArtifact)> = Vec::new();
+    for ((repo, branch), listing) in targets.iter().zip(listings) {
+        let failed = |artifact: &str, error: String| Download {
+            repo: repo.name.clone(),
+            branch: branch.clone(),
+            artifact: artifact.to_string(),
+            run_id: None,
+            path: None,
+            size: None,
+            sha256: None,
+            verified: false,
+            error: Some(error),
+        };
+        let artifacts: Vec<CiArtifact> = match listing {
+            Ok(artifacts) => artifacts
+                .into_iter()
+                .filter(|a| options.names.is_empty() || options.names.contains(&a.name))
+                .collect(),
+            // Without --repo, repos without Actions or CI are skipped
+            Err(PlatformError::NotFound(_)) if options.repos.is_empty() => continue,
+            Err(e) => {
+                downloads.push(failed("-", e.to_string()));
+                continue;
+            }
+        };
+        // Without --repo, repos that have none of the artifacts are skipped
+        if artifacts.is_empty() && options.repos.is_empty() {
+            continue;
+        }
+        for name in &options.names {
+            if !artifacts.iter().any(|a| &a.name == name) {
+                downloads.push(failed(
+                    name,
+                    format!("not in the latest successful run on {}", branch),
+                ));
+            }
+        }
+        if artifacts.is_empty() && options.names.is_empty() {
+            downloads.push(failed(
+                "-",
+                format!("no artifacts in the latest successful run on {}", branch),
+            ));
+        }
+        wanted.extend(
+            artifacts
+                .into_iter()
+                .map(|artifact| (repo.clone(), branch.clone(), artifact)),
+        );
+    }
+    if wanted.is_empty() && downloads.is_empty() {
+        anyhow::bail!("No repo has matching artifacts");
+    }
+
+    if !wanted.is_empty() {
+        std::fs::create_dir_all(&options.output)?;
+    }
+    let spinner = Output::spinner(&format!("Downloading {} artifact(s)...", wanted.len()));
+    let fetched = concurrent_map(
+        &wanted,
+        manifest.settings.api_concurrency,
+        |(repo, branch, artifact)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            let mut download = Download {
+                repo: repo.name.clone(),
+                branch: branch.clone(),
+                artifact: artifact.name.clone(),
+                run_id: Some(artifact.run_id),
+                path: None,
+                size: None,
+                sha256: None,
+                verified: false,
+                error: None,
+            };
+            let result = match platform
+                .download_ci_artifact(&repo.owner, &repo.repo, artifact.id)
+                .await
+            {
+                Ok(bytes) => verify(artifact, &bytes).and_then(|sha256| {
+                    let path = artifact_path(&repo.name, &artifact.name);
+                    save(&options.output.join(&path), &bytes)?;
+                    download.path = Some(path);
+                    download.size = Some(bytes.len() as u64);
+                    download.verified = artifact.sha256.is_some();
+                    download.sha256 = Some(sha256);
+                    Ok(())
+                }),
+                Err(e) => Err(e.to_string()),
+            };
+            download.error = result.err();
+            download
+        },
+    )
+    .await;
+    spinner.finish_and_clear();
+    downloads.extend(fetched);
+    downloads.sort_by(|a, b| (&a.repo, &a.artifact).cmp(&(&b.repo, &b.artifact)));
+
+    // In `sha256sum` format, so the bundle can be checked with `sha256sum -c`
+    let sums: String = downloads
+        .iter()
+        .filter_map(|d| Some(format!("{}  {}\n", d.sha256.as_ref()?, d.path.as_ref()?)))
+        .collect();
+    if !sums.is_empty() {
+        std::fs::write(options.output.join("SHA256SUMS"), sums)?;
+    }
+
+    if options.json {
+        Output::json(&downloads)?;
+    } else {
+        let mut table = Table::new(vec![
+            "Repo", "Artifact", "Run", "Size", "SHA-256", "Verified",
+        ]);
+        for download in &downloads {
+            let run = download
+                .run_id
+                .map(|id| format!("#{}", id))
+                .unwrap_or_else(|| "-".to_string());
+            let (size, sha256, verified) = match &download.error {
+                None => (
+                    download.size.map(format_bytes).unwrap_or_default(),
+                    download
+                        .sha256
+                        .as_deref()
+                        .map(|sha| sha[..12].to_string())
+                        .unwrap_or_default(),
+                    if download.verified {
+                        Output::mark(true)
+                    } else {
+                        "no digest"
+                    }
+                    .to_string(),
+                ),
+                Some(error) => (
+                    "-".to_string(),
+                    "-".to_string(),
+                    format!("{} {}", Output::mark(false), error),
+                ),
+            };
+            table.add_row(vec![
+                &download.repo,
+                &download.artifact,
+                &run,
+                &size,
+                &sha256,
+                &verified,
+            ]);
+        }
+        table.print();
+        println!();
+    }
+
+    let failed = downloads.iter().filter(|d| d.error.is_some()).count();
+    if failed > 0 {
+        anyhow::bail!("{} artifact download(s) failed", failed);
+    }
+    if !options.json {
+        Output::success(&format!(
+            "Downloaded {} artifact(s) to {}",
+            downloads.len(),
+            options.output.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Check a download against the digest the platform reported, or failing
+/// that its size, returning the download's hex SHA-256
+fn verify(artifact: &CiArtifact, bytes: &[u8]) -> Result<String, String> {
+    let sha256 = format!("{:x}", Sha256::digest(bytes));
+    match (&artifact.sha256, artifact.size) {
+        (Some(expected), _) if !expected.eq_ignore_ascii_case(&sha256) => Err(format!(
+            "checksum mismatch (expected {}, got {})",
+            expected, sha256
+        )),
+        (None, Some(size)) if size != bytes.len() as u64 => Err(format!(
+            "size mismatch (expected {} bytes, got {})",
+            size,
+            bytes.len()
+        )),
+        _ => Ok(sha256),
+    }
+}
+
+/// Where an artifact goes, relative to the output directory
+fn artifact_path(repo: &str, artifact: &str) -> String {
+    format!("{}/{}.zip", repo, artifact.replace(['/', '\\'], "_"))
+}
+
+fn save(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let mut artifact = CiArtifact {
+            id: 1,
+            name: "dist".to_string(),
+            run_id: 2,
+            size: Some(5),
+            sha256: None,
+        };
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert_eq!(verify(&artifact, b"hello").unwrap(), hello);
+        assert!(verify(&artifact, b"hello!")
+            .unwrap_err()
+            .starts_with("size mismatch"));
+
+        artifact.sha256 = Some(hello.to_uppercase());
+        assert!(verify(&artifact, b"hello").is_ok());
+        artifact.sha256 = Some("00".repeat(32));
+        assert!(verify(&artifact, b"hello")
+            .unwrap_err()
+            .starts_with("checksum mismatch"));
+        assert_eq!(artifact_path("api", "build/linux"), "api/build_linux.zip");
+    }
+}
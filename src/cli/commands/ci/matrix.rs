@@ -0,0 +1,134 @@
+//! CI matrix command implementation
+//!
+//! Emits a GitHub Actions `strategy.matrix` of the repos changed relative to
+//! their default branch, so pipelines only build what a change affects.
+
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::scope::repo_changed_files;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// One matrix entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatrixEntry {
+    /// Repository name (from manifest)
+    pub name: String,
+    /// Path relative to the workspace root
+    pub path: String,
+    /// Files changed since the default branch, including uncommitted ones
+    pub changed_files: Vec<String>,
+}
+
+/// A matrix in the shape `strategy.matrix` accepts via `fromJSON`
+#[derive(Debug, Clone, Serialize)]
+pub struct Matrix {
+    pub include: Vec<MatrixEntry>,
+}
+
+/// Run the ci matrix command
+///
+/// With `all`, every cloned repo is included whether or not it changed.
+/// With `github_output`, `matrix=<json>` and `changed=<true|false>` are also
+/// appended to the file named by `$GITHUB_OUTPUT`.
+pub fn run_ci_matrix(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    all: bool,
+    github_output: bool,
+) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| repo.exists())
+        .collect();
+
+    let matrix = build_matrix(&repos, all);
+    let json = serde_json::to_string(&matrix)?;
+    println!("{}", json);
+
+    if github_output {
+        let path = std::env::var_os("GITHUB_OUTPUT")
+            .ok_or_else(|| anyhow::anyhow!("--github-output requires $GITHUB_OUTPUT to be set"))?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "matrix={}", json)?;
+        writeln!(file, "changed={}", !matrix.include.is_empty())?;
+    }
+
+    Ok(())
+}
+
+fn build_matrix(repos: &[RepoInfo], all: bool) -> Matrix {
+    let mut include: Vec<MatrixEntry> = repos
+        .iter()
+        .map(|repo| MatrixEntry {
+            name: repo.name.clone(),
+            path: repo.path.trim_start_matches("./").to_string(),
+            changed_files: repo_changed_files(repo),
+        })
+        .filter(|entry| all || !entry.changed_files.is_empty())
+        .collect();
+    include.sort_by(|a, b| a.name.cmp(&b.name));
+    Matrix { include }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_matrix_includes_only_changed_repos() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: ./api
+  web:
+    url: git@github.com:acme/web.git
+    path: web
+"#,
+        )
+        .unwrap();
+
+        for name in ["api", "web"] {
+            let path = root.join(name);
+            init_repo(&path);
+            git(
+                &path,
+                &["commit", "-q", "--allow-empty", "-m", "Initial commit"],
+            );
+        }
+        let api = root.join("api");
+        git(&api, &["checkout", "-q", "-b", "feat/x"]);
+        std::fs::write(api.join("lib.rs"), "// new").unwrap();
+        git(&api, &["add", "."]);
+        git(&api, &["commit", "-q", "-m", "Add lib"]);
+
+        let repos: Vec<RepoInfo> = manifest
+            .repos
+            .iter()
+            .filter_map(|(name, config)| RepoInfo::from_config(name, config, &root))
+            .collect();
+
+        let matrix = build_matrix(&repos, false);
+        assert_eq!(
+            matrix.include,
+            vec![MatrixEntry {
+                name: "api".to_string(),
+                path: "api".to_string(),
+                changed_files: vec!["lib.rs".to_string()],
+            }]
+        );
+        assert_eq!(build_matrix(&repos, true).include.len(), 2);
+    }
+}
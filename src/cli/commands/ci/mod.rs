@@ -0,0 +1,11 @@
+//! CI command implementations
+//!
+//! Subcommands for the CI pipelines of the workspace's repos.
+
+mod artifacts;
+mod matrix;
+mod run;
+
+pub use artifacts::{run_ci_artifacts, CiArtifactsOptions};
+pub use matrix::{run_ci_matrix, Matrix, MatrixEntry};
+pub use run::{parse_input, run_ci_run, CiRunOptions};
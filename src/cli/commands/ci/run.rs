@@ -0,0 +1,254 @@
+//! CI run command implementation
+//!
+//! Starts a workflow in several repos at once (a GitHub `workflow_dispatch`,
+//! or a GitLab pipeline with the inputs as variables), lists the runs it
+//! spawned, and with `--watch` waits for them to finish.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::{get_current_branch, open_repo};
+use crate::platform::traits::PlatformError;
+use crate::platform::{detect_platform, get_platform_adapter, CheckState, WorkflowRun};
+use crate::util::concurrent_map;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How often `--watch` polls the runs
+const WATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Settings for `gr ci run`
+#[derive(Debug, Clone, Default)]
+pub struct CiRunOptions {
+    /// Workflow file name or ID (ignored on GitLab)
+    pub workflow: String,
+    /// Only these repos; empty means every repo that has the workflow
+    pub repos: Vec<String>,
+    /// Ref to run on instead of each repo's current branch
+    pub git_ref: Option<String>,
+    /// Workflow inputs (GitLab pipeline variables)
+    pub inputs: Vec<(String, String)>,
+    /// Wait for the runs to finish
+    pub watch: bool,
+    /// Output JSON
+    pub json: bool,
+}
+
+/// What dispatching the workflow in one repo did
+#[derive(Debug, Serialize)]
+struct Dispatch {
+    repo: String,
+    #[serde(rename = "ref")]
+    git_ref: String,
+    run: Option<WorkflowRun>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Parse a `--input key=value` argument
+pub fn parse_input(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected key=value, got '{}'", arg)),
+    }
+}
+
+/// Run the ci run command
+pub async fn run_ci_run(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &CiRunOptions,
+) -> anyhow::Result<()> {
+    if let Some(unknown) = options
+        .repos
+        .iter()
+        .find(|r| !manifest.repos.contains_key(*r))
+    {
+        anyhow::bail!("Unknown repo '{}'", unknown);
+    }
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| options.repos.is_empty() || options.repos.contains(&r.name))
+        .filter(|r| r.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let targets: Vec<(RepoInfo, String)> = repos
+        .into_iter()
+        .map(|repo| {
+            let git_ref = options.git_ref.clone().unwrap_or_else(|| {
+                open_repo(&repo.absolute_path)
+                    .ok()
+                    .and_then(|r| get_current_branch(&r).ok())
+                    .unwrap_or_else(|| repo.default_branch.clone())
+            });
+            (repo, git_ref)
+        })
+        .collect();
+
+    let spinner = Output::spinner(&format!(
+        "Starting {} in {} repo(s)...",
+        options.workflow,
+        targets.len()
+    ));
+    let results = concurrent_map(
+        &targets,
+        manifest.settings.api_concurrency,
+        |(repo, git_ref)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            platform
+                .dispatch_workflow(
+                    &repo.owner,
+                    &repo.repo,
+                    &options.workflow,
+                    git_ref,
+                    &options.inputs,
+                )
+                .await
+        },
+    )
+    .await;
+    spinner.finish_and_clear();
+
+    let mut dispatches = Vec::new();
+    for ((repo, git_ref), result) in targets.iter().zip(results) {
+        let (run, error) = match result {
+            Ok(run) => (Some(run), None),
+            // Without --repo, repos that don't have the workflow are skipped
+            Err(PlatformError::NotFound(_)) if options.repos.is_empty() => continue,
+            Err(e) => (None, Some(e.to_string())),
+        };
+        dispatches.push(Dispatch {
+            repo: repo.name.clone(),
+            git_ref: git_ref.clone(),
+            run,
+            error,
+        });
+    }
+    if dispatches.is_empty() {
+        anyhow::bail!("No repo has a workflow named '{}'", options.workflow);
+    }
+
+    if options.watch {
+        watch_runs(&targets, &mut dispatches, manifest.settings.api_concurrency).await;
+    }
+
+    if options.json {
+        Output::json(&dispatches)?;
+    } else {
+        let mut table = Table::new(vec!["Repo", "Ref", "Run", "State", "URL"]);
+        for dispatch in &dispatches {
+            let (id, state, url) = match (&dispatch.run, &dispatch.error) {
+                (Some(run), _) => (
+                    format!("#{}", run.id),
+                    run.state.to_string(),
+                    run.url.clone().unwrap_or_default(),
+                ),
+                (None, error) => (
+                    "-".to_string(),
+                    "not started".to_string(),
+                    error.clone().unwrap_or_default(),
+                ),
+            };
+            table.add_row(vec![&dispatch.repo, &dispatch.git_ref, &id, &state, &url]);
+        }
+        table.print();
+        println!();
+    }
+
+    let not_started = dispatches.iter().filter(|d| d.run.is_none()).count();
+    let failed = dispatches
+        .iter()
+        .filter(|d| matches!(&d.run, Some(run) if run.state == CheckState::Failure))
+        .count();
+    if not_started + failed > 0 {
+        anyhow::bail!(
+            "{} run(s) failed, {} repo(s) could not start {}",
+            failed,
+            not_started,
+            options.workflow
+        );
+    }
+    if !options.json {
+        if options.watch {
+            Output::success(&format!("All {} run(s) succeeded", dispatches.len()));
+        } else {
+            Output::success(&format!(
+                "Started {} in {} repo(s); add --watch to wait for them",
+                options.workflow,
+                dispatches.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Poll the started runs until none is pending
+///
+/// A run whose state can't be read keeps its last known state.
+async fn watch_runs(
+    targets: &[(RepoInfo, String)],
+    dispatches: &mut [Dispatch],
+    concurrency: usize,
+) {
+    let spinner = Output::spinner("Waiting for runs...");
+    loop {
+        let pending: Vec<(RepoInfo, u64)> = dispatches
+            .iter()
+            .filter_map(|d| {
+                let run = d
+                    .run
+                    .as_ref()
+                    .filter(|run| run.state == CheckState::Pending)?;
+                let (repo, _) = targets.iter().find(|(repo, _)| repo.name == d.repo)?;
+                Some((repo.clone(), run.id))
+            })
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+        spinner.set_message(format!("Waiting for {} run(s)...", pending.len()));
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        let updates = concurrent_map(&pending, concurrency, |(repo, id)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            platform
+                .get_workflow_run(&repo.owner, &repo.repo, *id)
+                .await
+        })
+        .await;
+        for ((repo, _), update) in pending.iter().zip(updates) {
+            if let (Some(dispatch), Ok(run)) =
+                (dispatches.iter_mut().find(|d| d.repo == repo.name), update)
+            {
+                dispatch.run = Some(run);
+            }
+        }
+    }
+    spinner.finish_and_clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(
+            parse_input("env=staging").unwrap(),
+            ("env".to_string(), "staging".to_string())
+        );
+        assert_eq!(
+            parse_input("args=--a=b").unwrap(),
+            ("args".to_string(), "--a=b".to_string())
+        );
+        assert!(parse_input("staging").is_err());
+        assert!(parse_input("=x").is_err());
+    }
+}
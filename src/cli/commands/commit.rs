@@ -1,23 +1,37 @@
 //! Commit command implementation
 
+use crate::cli::i18n::msg;
 use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
 use crate::git::cache::invalidate_status_cache;
-use crate::git::{get_workdir, open_repo, path_exists};
+use crate::git::{
+    get_current_branch, get_signature, get_workdir, open_repo, parse_identity, path_exists,
+};
+use crate::util::profile::ProfiledCommand;
 use git2::Repository;
 use std::path::PathBuf;
 use std::process::Command;
 
 /// Run the commit command
+///
+/// Commits are signed according to git config (`commit.gpgsign`); pass
+/// `sign = false` to force unsigned commits. `author` overrides the commit
+/// author (`Name <email>`); the committer always comes from git config.
+/// Nothing is committed if any repo with staged changes is on a protected
+/// branch, unless `allow_protected`.
 pub fn run_commit(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     message: &str,
     amend: bool,
+    sign: bool,
+    author: Option<&str>,
+    allow_protected: bool,
 ) -> anyhow::Result<()> {
-    Output::header("Committing changes...");
-    println!();
+    if let Some(author) = author {
+        parse_identity(author)?;
+    }
 
     let repos: Vec<RepoInfo> = manifest
         .repos
@@ -25,6 +39,32 @@ pub fn run_commit(
         .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
         .collect();
 
+    let targets: Vec<(&RepoInfo, String)> = repos
+        .iter()
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .filter_map(|repo| {
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            if !has_staged_changes(&git_repo).unwrap_or(false) {
+                return None;
+            }
+            Some((repo, get_current_branch(&git_repo).ok()?))
+        })
+        .collect();
+    manifest.settings.ensure_unprotected(
+        "commit to",
+        targets.iter().map(|(repo, branch)| {
+            (
+                repo.name.as_str(),
+                branch.as_str(),
+                repo.default_branch.as_str(),
+            )
+        }),
+        allow_protected,
+    )?;
+
+    Output::header(&msg!("commit.committing"));
+    println!();
+
     let mut success_count = 0;
     let mut skip_count = 0;
 
@@ -41,13 +81,26 @@ pub fn run_commit(
                     continue;
                 }
 
-                match create_commit(&git_repo, message, amend) {
+                if let Err(e) = get_signature(&git_repo) {
+                    Output::error(&format!("{}: {}", repo.name, e));
+                    continue;
+                }
+
+                match create_commit(&git_repo, message, amend, sign, author) {
                     Ok(commit_id) => {
                         let short_id = &commit_id[..7.min(commit_id.len())];
                         if amend {
-                            Output::success(&format!("{}: amended ({})", repo.name, short_id));
+                            Output::success(&msg!(
+                                "commit.amended",
+                                repo = repo.name,
+                                id = short_id
+                            ));
                         } else {
-                            Output::success(&format!("{}: committed ({})", repo.name, short_id));
+                            Output::success(&msg!(
+                                "commit.committed",
+                                repo = repo.name,
+                                id = short_id
+                            ));
                         }
                         success_count += 1;
                         invalidate_status_cache(&repo.absolute_path);
@@ -61,30 +114,33 @@ pub fn run_commit(
 
     println!();
     if success_count > 0 {
-        println!(
-            "Created {} commit(s){}.",
-            success_count,
-            if skip_count > 0 {
-                format!(", {} repo(s) had no staged changes", skip_count)
-            } else {
-                String::new()
-            }
-        );
+        if skip_count > 0 {
+            println!(
+                "{}",
+                msg!(
+                    "commit.summary_skipped",
+                    count = success_count,
+                    skipped = skip_count
+                )
+            );
+        } else {
+            println!("{}", msg!("commit.summary", count = success_count));
+        }
     } else {
-        println!("No changes to commit.");
+        println!("{}", msg!("commit.none"));
     }
 
     Ok(())
 }
 
 /// Check if a repository has staged changes using git CLI
-fn has_staged_changes(repo: &Repository) -> anyhow::Result<bool> {
+pub(crate) fn has_staged_changes(repo: &Repository) -> anyhow::Result<bool> {
     let repo_path = get_workdir(repo);
 
     let output = Command::new("git")
         .args(["diff", "--cached", "--quiet"])
         .current_dir(repo_path)
-        .output()?;
+        .timed_output()?;
 
     // Exit code 0 means no diff (no staged changes)
     // Exit code 1 means there are changes
@@ -92,21 +148,40 @@ fn has_staged_changes(repo: &Repository) -> anyhow::Result<bool> {
 }
 
 /// Create a commit in the repository using git CLI
-fn create_commit(repo: &Repository, message: &str, amend: bool) -> anyhow::Result<String> {
+fn create_commit(
+    repo: &Repository,
+    message: &str,
+    amend: bool,
+    sign: bool,
+    author: Option<&str>,
+) -> anyhow::Result<String> {
     let repo_path = get_workdir(repo);
 
+    let author_arg = author.map(|a| format!("--author={}", a));
     let mut args = vec!["commit", "-m", message];
+    if let Some(author_arg) = &author_arg {
+        args.push(author_arg);
+    }
     if amend {
         args.push("--amend");
     }
+    if !sign {
+        args.push("--no-gpg-sign");
+    }
 
     let output = Command::new("git")
         .args(&args)
         .current_dir(repo_path)
-        .output()?;
+        .timed_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("gpg failed to sign") || stderr.contains("failed to sign") {
+            anyhow::bail!(
+                "git commit failed: could not sign the commit - check that your signing key is available, or use --no-sign\n{}",
+                stderr
+            );
+        }
         anyhow::bail!("git commit failed: {}", stderr);
     }
 
@@ -114,7 +189,7 @@ fn create_commit(repo: &Repository, message: &str, amend: bool) -> anyhow::Resul
     let hash_output = Command::new("git")
         .args(["rev-parse", "HEAD"])
         .current_dir(repo_path)
-        .output()?;
+        .timed_output()?;
 
     let commit_id = String::from_utf8_lossy(&hash_output.stdout)
         .trim()
@@ -192,7 +267,7 @@ mod tests {
             .output()
             .unwrap();
 
-        let commit_id = create_commit(&repo, "Test commit", false).unwrap();
+        let commit_id = create_commit(&repo, "Test commit", false, true, None).unwrap();
         assert!(!commit_id.is_empty());
 
         // Verify commit was created
@@ -205,6 +280,35 @@ mod tests {
         assert_eq!(message, "Test commit");
     }
 
+    #[test]
+    fn test_create_commit_with_author() {
+        let (temp_dir, repo) = setup_test_repo();
+
+        fs::write(temp_dir.path().join("test.txt"), "content").unwrap();
+        StdCommand::new("git")
+            .args(["add", "test.txt"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+
+        create_commit(
+            &repo,
+            "Authored commit",
+            false,
+            true,
+            Some("Other Person <other@example.com>"),
+        )
+        .unwrap();
+
+        let output = StdCommand::new("git")
+            .args(["log", "-1", "--format=%an <%ae>|%cn"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let log = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        assert_eq!(log, "Other Person <other@example.com>|Test User");
+    }
+
     #[test]
     fn test_amend_commit() {
         let (temp_dir, repo) = setup_test_repo();
@@ -219,7 +323,7 @@ mod tests {
             .output()
             .unwrap();
 
-        create_commit(&repo, "Initial commit", false).unwrap();
+        create_commit(&repo, "Initial commit", false, true, None).unwrap();
 
         // Modify and stage
         fs::write(&file_path, "amended").unwrap();
@@ -231,7 +335,7 @@ mod tests {
             .unwrap();
 
         // Amend
-        create_commit(&repo, "Amended commit", true).unwrap();
+        create_commit(&repo, "Amended commit", true, true, None).unwrap();
 
         // Verify only one commit exists
         let output = StdCommand::new("git")
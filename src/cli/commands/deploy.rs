@@ -0,0 +1,265 @@
+//! Deploy command implementation
+//!
+//! Shows what each repo has live in its deployment environments (GitHub
+//! Deployments or GitLab environments) and how the deployed commit compares
+//! to the local HEAD and, with `--lock`, to a workspace snapshot.
+
+use crate::cli::commands::foreach_changed::WorkspaceSnapshot;
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::open_repo;
+use crate::platform::{detect_platform, get_platform_adapter, Deployment};
+use crate::util::concurrent_map;
+use git2::{Oid, Repository};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Settings for `gr deploy status`
+#[derive(Debug, Clone, Default)]
+pub struct DeployStatusOptions {
+    /// Only these environments; empty means all
+    pub environments: Vec<String>,
+    /// Snapshot file (from `gr foreach-changed --save`) to compare against
+    pub lock: Option<PathBuf>,
+    /// Output JSON
+    pub json: bool,
+}
+
+/// How a deployed commit relates to a local one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Drift {
+    /// The local commit compared against
+    sha: String,
+    /// Deployed commits the local one lacks; `None` if the deployed commit
+    /// isn't in the local clone
+    ahead: Option<usize>,
+    /// Local commits not deployed yet
+    behind: Option<usize>,
+}
+
+impl Drift {
+    fn between(repo: &Repository, deployed: &str, local: Oid) -> Self {
+        let counts = Oid::from_str(deployed)
+            .ok()
+            .and_then(|deployed| repo.graph_ahead_behind(deployed, local).ok());
+        Self {
+            sha: local.to_string(),
+            ahead: counts.map(|(ahead, _)| ahead),
+            behind: counts.map(|(_, behind)| behind),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match (self.ahead, self.behind) {
+            (Some(0), Some(0)) => Output::mark(true).to_string(),
+            (Some(0), Some(behind)) => format!("{} behind", behind),
+            (Some(ahead), Some(0)) => format!("{} ahead", ahead),
+            (Some(ahead), Some(behind)) => format!("{} ahead, {} behind", ahead, behind),
+            _ => "? (fetch first)".to_string(),
+        }
+    }
+
+    fn is_behind(&self) -> bool {
+        self.behind.is_some_and(|behind| behind > 0)
+    }
+}
+
+/// One environment of one repo
+#[derive(Debug, Serialize)]
+struct EnvironmentStatus {
+    repo: String,
+    #[serde(flatten)]
+    deployment: Deployment,
+    head: Option<Drift>,
+    lock: Option<Drift>,
+    /// The deployment lags the lock (or HEAD, without `--lock`)
+    out_of_date: bool,
+}
+
+/// Run the deploy status command
+pub async fn run_deploy_status(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &DeployStatusOptions,
+) -> anyhow::Result<()> {
+    let snapshot = options
+        .lock
+        .as_deref()
+        .map(WorkspaceSnapshot::load)
+        .transpose()?;
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| r.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let spinner = Output::spinner(&format!(
+        "Fetching deployments for {} repo(s)...",
+        repos.len()
+    ));
+    let results = concurrent_map(
+        &repos,
+        manifest.settings.api_concurrency,
+        |repo| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            platform.list_deployments(&repo.owner, &repo.repo).await
+        },
+    )
+    .await;
+    spinner.finish_and_clear();
+
+    let mut statuses = Vec::new();
+    let mut failures = Vec::new();
+    for (repo, result) in repos.iter().zip(results) {
+        let deployments = match result {
+            Ok(deployments) => deployments,
+            Err(e) => {
+                failures.push(format!("{}: {}", repo.name, e));
+                continue;
+            }
+        };
+        let git_repo = open_repo(&repo.absolute_path).ok();
+        let locked = snapshot
+            .as_ref()
+            .and_then(|s| s.repos.get(&repo.name))
+            .and_then(|sha| Oid::from_str(sha).ok());
+        for deployment in deployments {
+            if !options.environments.is_empty()
+                && !options.environments.contains(&deployment.environment)
+            {
+                continue;
+            }
+            statuses.push(environment_status(
+                repo,
+                git_repo.as_ref(),
+                locked,
+                deployment,
+            ));
+        }
+    }
+
+    if options.json {
+        Output::json(&statuses)?;
+    } else if statuses.is_empty() {
+        Output::info("No deployments found.");
+    } else {
+        let mut headers = vec!["Repo", "Environment", "Deployed", "State", "vs HEAD"];
+        if snapshot.is_some() {
+            headers.push("vs lock");
+        }
+        let mut table = Table::new(headers);
+        for status in &statuses {
+            let deployed = format!(
+                "{} ({})",
+                short_sha(&status.deployment.sha),
+                status.deployment.git_ref
+            );
+            let head = status
+                .head
+                .as_ref()
+                .map(Drift::describe)
+                .unwrap_or_else(|| "-".to_string());
+            let mut row = vec![
+                status.repo.clone(),
+                status.deployment.environment.clone(),
+                deployed,
+                status.deployment.state.clone(),
+                head,
+            ];
+            if snapshot.is_some() {
+                row.push(
+                    status
+                        .lock
+                        .as_ref()
+                        .map(Drift::describe)
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+            }
+            table.add_row(row.iter().map(String::as_str).collect());
+        }
+        table.print();
+        println!();
+
+        let stale: Vec<String> = statuses
+            .iter()
+            .filter(|s| s.out_of_date)
+            .map(|s| format!("{} ({})", s.repo, s.deployment.environment))
+            .collect();
+        if !stale.is_empty() {
+            let against = if snapshot.is_some() { "lock" } else { "HEAD" };
+            Output::warning(&format!("Out of date vs {}: {}", against, stale.join(", ")));
+        }
+    }
+
+    for failure in &failures {
+        Output::warning(&format!("Could not list deployments for {}", failure));
+    }
+    Ok(())
+}
+
+fn environment_status(
+    repo: &RepoInfo,
+    git_repo: Option<&Repository>,
+    locked: Option<Oid>,
+    deployment: Deployment,
+) -> EnvironmentStatus {
+    let head = git_repo.and_then(|git_repo| {
+        let head = git_repo.head().ok()?.peel_to_commit().ok()?.id();
+        Some(Drift::between(git_repo, &deployment.sha, head))
+    });
+    let lock = git_repo
+        .zip(locked)
+        .map(|(git_repo, locked)| Drift::between(git_repo, &deployment.sha, locked));
+    let out_of_date = match (&lock, locked) {
+        (Some(lock), _) => lock.is_behind(),
+        // A lock that names the repo but can't be compared says nothing
+        (None, Some(_)) => false,
+        (None, None) => head.as_ref().is_some_and(Drift::is_behind),
+    };
+    EnvironmentStatus {
+        repo: repo.name.clone(),
+        deployment,
+        head,
+        lock,
+        out_of_date,
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_drift_between() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        git(path, &["commit", "-q", "--allow-empty", "-m", "first"]);
+        let repo = open_repo(path).unwrap();
+        let deployed = repo.head().unwrap().target().unwrap();
+        git(path, &["commit", "-q", "--allow-empty", "-m", "second"]);
+        let head = repo.head().unwrap().target().unwrap();
+
+        let drift = Drift::between(&repo, &deployed.to_string(), head);
+        assert_eq!((drift.ahead, drift.behind), (Some(0), Some(1)));
+        assert!(drift.is_behind());
+        assert_eq!(drift.describe(), "1 behind");
+
+        let current = Drift::between(&repo, &head.to_string(), head);
+        assert_eq!(current.describe(), "✓");
+
+        let unknown = Drift::between(&repo, &"a".repeat(40), head);
+        assert!(!unknown.is_behind());
+        assert_eq!(unknown.describe(), "? (fetch first)");
+    }
+}
@@ -0,0 +1,221 @@
+//! Dev command implementation
+//!
+//! Helpers for working on gitgrip itself. `gr dev bench-compare` runs the
+//! criterion benchmarks and fails if any got slower than a saved baseline by
+//! more than a threshold.
+
+use crate::cli::output::{Output, Table};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Settings for `gr dev bench-compare`
+#[derive(Debug, Clone)]
+pub struct BenchCompareOptions {
+    /// Name of the saved criterion baseline
+    pub baseline: String,
+    /// Save a new baseline instead of comparing
+    pub save: bool,
+    /// Largest allowed slowdown, in percent
+    pub threshold: f64,
+    /// Only run benchmarks matching this criterion filter
+    pub filter: Option<String>,
+    /// Compare the existing results without running the benchmarks
+    pub no_run: bool,
+}
+
+/// One benchmark's mean time in the baseline and the latest run
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    id: String,
+    baseline_ns: f64,
+    current_ns: f64,
+}
+
+impl Comparison {
+    /// Change from the baseline, in percent (positive is slower)
+    fn change(&self) -> f64 {
+        (self.current_ns - self.baseline_ns) / self.baseline_ns * 100.0
+    }
+}
+
+/// Run the dev bench-compare command
+pub fn run_bench_compare(options: &BenchCompareOptions) -> anyhow::Result<()> {
+    if options.save {
+        cargo_bench(&["--save-baseline", &options.baseline], options)?;
+        Output::success(&format!("Saved baseline '{}'", options.baseline));
+        return Ok(());
+    }
+    if !options.no_run {
+        cargo_bench(&[], options)?;
+    }
+
+    let dir = criterion_dir();
+    let comparisons = compare(&dir, &options.baseline)?;
+    if comparisons.is_empty() {
+        anyhow::bail!(
+            "No results for baseline '{}' in {}; save one first with 'gr dev bench-compare --save {}'",
+            options.baseline,
+            dir.display(),
+            options.baseline
+        );
+    }
+
+    let mut table = Table::new(vec!["Benchmark", "Baseline", "Current", "Change"]);
+    let mut regressed = 0;
+    for comparison in &comparisons {
+        let change = comparison.change();
+        let mark = if change > options.threshold {
+            regressed += 1;
+            " REGRESSED"
+        } else {
+            ""
+        };
+        table.add_row(vec![
+            &comparison.id,
+            &format_time(comparison.baseline_ns),
+            &format_time(comparison.current_ns),
+            &format!("{:+.1}%{}", change, mark),
+        ]);
+    }
+    table.print();
+    println!();
+
+    if regressed > 0 {
+        anyhow::bail!(
+            "{} benchmark(s) regressed by more than {}% against '{}'",
+            regressed,
+            options.threshold,
+            options.baseline
+        );
+    }
+    Output::success(&format!(
+        "{} benchmark(s) within {}% of '{}'",
+        comparisons.len(),
+        options.threshold,
+        options.baseline
+    ));
+
+    Ok(())
+}
+
+fn cargo_bench(criterion_args: &[&str], options: &BenchCompareOptions) -> anyhow::Result<()> {
+    let mut command = Command::new("cargo");
+    command
+        .args(["bench", "--bench", "benchmarks", "--"])
+        .args(criterion_args);
+    if let Some(filter) = &options.filter {
+        command.arg(filter);
+    }
+    let status = command.status()?;
+    if !status.success() {
+        anyhow::bail!("cargo bench failed with exit code: {:?}", status.code());
+    }
+    Ok(())
+}
+
+/// Where criterion keeps its results
+fn criterion_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target"))
+        .join("criterion")
+}
+
+/// Every benchmark under `dir` with results in both `baseline` and the latest run
+fn compare(dir: &Path, baseline: &str) -> std::io::Result<Vec<Comparison>> {
+    let mut comparisons = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        if !current.is_dir() {
+            continue;
+        }
+        let base = mean_ns(&current.join(baseline).join("estimates.json"));
+        let new = mean_ns(&current.join("new").join("estimates.json"));
+        if let (Some(baseline_ns), Some(current_ns)) = (base, new) {
+            comparisons.push(Comparison {
+                id: benchmark_id(dir, &current),
+                baseline_ns,
+                current_ns,
+            });
+            continue;
+        }
+        for entry in std::fs::read_dir(&current)?.flatten() {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                pending.push(entry.path());
+            }
+        }
+    }
+    comparisons.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(comparisons)
+}
+
+/// The mean estimate, in nanoseconds, from a criterion `estimates.json`
+fn mean_ns(path: &Path) -> Option<f64> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let estimates: serde_json::Value = serde_json::from_str(&content).ok()?;
+    estimates["mean"]["point_estimate"].as_f64()
+}
+
+/// Criterion's own name for the benchmark, or its path under `root`
+fn benchmark_id(root: &Path, dir: &Path) -> String {
+    std::fs::read_to_string(dir.join("new").join("benchmark.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|info| info["full_id"].as_str().map(str::to_string))
+        .unwrap_or_else(|| {
+            dir.strip_prefix(root)
+                .unwrap_or(dir)
+                .to_string_lossy()
+                .to_string()
+        })
+}
+
+fn format_time(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{:.0} ns", ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_estimate(dir: &Path, mean: f64) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("estimates.json"),
+            format!("{{\"mean\":{{\"point_estimate\":{}}}}}", mean),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_compare_against_baseline() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let status = root.join("scale_status").join("gr_status").join("50");
+        write_estimate(&status.join("main"), 2_000_000.0);
+        write_estimate(&status.join("new"), 2_500_000.0);
+        std::fs::write(
+            status.join("new").join("benchmark.json"),
+            "{\"full_id\":\"scale_status/gr_status/50\"}",
+        )
+        .unwrap();
+        // No baseline yet: not compared
+        write_estimate(&root.join("manifest_parse").join("new"), 900.0);
+
+        let comparisons = compare(root, "main").unwrap();
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].id, "scale_status/gr_status/50");
+        assert!((comparisons[0].change() - 25.0).abs() < 1e-9);
+        assert_eq!(format_time(comparisons[0].current_ns), "2.50 ms");
+        assert!(compare(root, "other").unwrap().is_empty());
+    }
+}
@@ -1,14 +1,22 @@
 //! Diff command implementation
 
+use crate::cli::diff_render::DiffRenderer;
+use crate::cli::i18n::msg;
 use crate::cli::output::Output;
-use crate::core::manifest::Manifest;
+use crate::core::manifest::{DiffSettings, Manifest};
 use crate::core::repo::RepoInfo;
 use crate::git::{open_repo, path_exists};
 use git2::{DiffOptions, Repository};
 use std::path::PathBuf;
 
-/// Run the diff command
-pub fn run_diff(workspace_root: &PathBuf, manifest: &Manifest, staged: bool) -> anyhow::Result<()> {
+/// Run the diff command, rendering each repo's diff with `display`
+pub fn run_diff(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    staged: bool,
+    display: &DiffSettings,
+) -> anyhow::Result<()> {
+    let renderer = DiffRenderer::new(display);
     let repos: Vec<RepoInfo> = manifest
         .repos
         .iter()
@@ -29,8 +37,8 @@ pub fn run_diff(workspace_root: &PathBuf, manifest: &Manifest, staged: bool) ->
                     if has_changes {
                         println!();
                     }
-                    Output::header(&format!("diff: {}", repo.name));
-                    println!("{}", diff_output);
+                    Output::header(&msg!("diff.repo", repo = repo.name));
+                    print!("{}", renderer.render(&diff_output));
                     has_changes = true;
                 }
             }
@@ -39,7 +47,7 @@ pub fn run_diff(workspace_root: &PathBuf, manifest: &Manifest, staged: bool) ->
     }
 
     if !has_changes {
-        println!("No changes.");
+        println!("{}", msg!("diff.none"));
     }
 
     Ok(())
@@ -60,29 +68,14 @@ fn get_diff(repo: &Repository, staged: bool) -> anyhow::Result<String> {
     };
 
     diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let prefix = match line.origin() {
-            '+' => "+",
-            '-' => "-",
-            ' ' => " ",
-            '>' => ">",
-            '<' => "<",
-            'F' => "", // File header
-            'H' => "", // Hunk header
-            'B' => "", // Binary
-            _ => "",
-        };
-
-        // Color the output
-        let content = std::str::from_utf8(line.content()).unwrap_or("");
-        let colored_line = match line.origin() {
-            '+' => format!("\x1b[32m{}{}\x1b[0m", prefix, content.trim_end()),
-            '-' => format!("\x1b[31m{}{}\x1b[0m", prefix, content.trim_end()),
-            '@' => format!("\x1b[36m{}\x1b[0m", content.trim_end()),
-            _ => format!("{}{}", prefix, content.trim_end()),
-        };
-
-        output.push_str(&colored_line);
-        output.push('\n');
+        // File and hunk headers carry their own text; other lines get their origin
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            output.push(line.origin());
+        }
+        output.push_str(&String::from_utf8_lossy(line.content()));
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
         true
     })?;
 
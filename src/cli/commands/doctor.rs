@@ -0,0 +1,43 @@
+//! Doctor command implementation
+//!
+//! Checks that the workspace on disk matches the manifest.
+
+use crate::cli::commands::status::print_workspace_drift;
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use std::path::PathBuf;
+
+/// Run the doctor command
+pub fn run_doctor(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
+    Output::header("Checking workspace");
+    println!();
+
+    let mut not_cloned: Vec<String> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| !repo.exists())
+        .map(|repo| repo.name)
+        .collect();
+    not_cloned.sort();
+
+    if !not_cloned.is_empty() {
+        Output::warning(&format!(
+            "{} repo(s) not cloned: {}",
+            not_cloned.len(),
+            not_cloned.join(", ")
+        ));
+        Output::info("Run 'gr sync' to clone them");
+    }
+
+    let problems = not_cloned.len() + print_workspace_drift(workspace_root, manifest);
+
+    println!();
+    if problems == 0 {
+        Output::success("No problems found.");
+    } else {
+        Output::warning(&format!("{} problem(s) found.", problems));
+    }
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! Du command implementation
+//!
+//! Reports per-repo disk usage split into git objects and working tree, and
+//! suggests remedies for repos that are unusually large.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::path_exists;
+use crate::util::disk::{dir_size, format_bytes};
+use crate::util::profile::ProfiledCommand;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Packfiles larger than this are flagged
+const HUGE_PACK_BYTES: u64 = 1024 * 1024 * 1024;
+/// Same threshold git uses for `gc.auto`
+const LOOSE_OBJECT_LIMIT: u64 = 6700;
+/// Working trees larger than this get a sparse-checkout suggestion
+const LARGE_WORKTREE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, serde::Serialize)]
+struct RepoUsage {
+    repo: String,
+    git_bytes: u64,
+    worktree_bytes: u64,
+    loose_objects: u64,
+    largest_pack_bytes: u64,
+    suggestions: Vec<String>,
+}
+
+/// Run the du command
+pub fn run_du(workspace_root: &PathBuf, manifest: &Manifest, json: bool) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    let usages: Vec<RepoUsage> = repos
+        .iter()
+        .map(|repo| {
+            let sparse = manifest
+                .repos
+                .get(&repo.name)
+                .is_some_and(|c| c.sparse.is_some());
+            measure_repo(&repo.name, &repo.absolute_path, sparse)
+        })
+        .collect();
+
+    if json {
+        Output::json(&usages)?;
+        return Ok(());
+    }
+
+    Output::header("Disk Usage");
+    println!();
+
+    let mut table = Table::new(vec!["Repo", ".git", "Working tree", "Loose objects"]);
+    for usage in &usages {
+        table.add_row(vec![
+            &Output::repo_name(&usage.repo),
+            &format_bytes(usage.git_bytes),
+            &format_bytes(usage.worktree_bytes),
+            &usage.loose_objects.to_string(),
+        ]);
+    }
+    table.print();
+
+    let git_total: u64 = usages.iter().map(|u| u.git_bytes).sum();
+    let worktree_total: u64 = usages.iter().map(|u| u.worktree_bytes).sum();
+    println!();
+    println!(
+        "  Total: {} ({} git, {} working tree)",
+        format_bytes(git_total + worktree_total),
+        format_bytes(git_total),
+        format_bytes(worktree_total)
+    );
+
+    let flagged: Vec<&RepoUsage> = usages
+        .iter()
+        .filter(|u| !u.suggestions.is_empty())
+        .collect();
+    if !flagged.is_empty() {
+        println!();
+        for usage in flagged {
+            for suggestion in &usage.suggestions {
+                Output::warning(&format!("{}: {}", usage.repo, suggestion));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn measure_repo(name: &str, repo_path: &Path, sparse: bool) -> RepoUsage {
+    let git_dir = repo_path.join(".git");
+    let git_bytes = dir_size(&git_dir);
+    let worktree_bytes = dir_size(repo_path).saturating_sub(git_bytes);
+
+    let loose_objects = count_loose_objects(repo_path);
+    let largest_pack_bytes = largest_pack(&git_dir.join("objects").join("pack"));
+
+    let mut usage = RepoUsage {
+        repo: name.to_string(),
+        git_bytes,
+        worktree_bytes,
+        loose_objects,
+        largest_pack_bytes,
+        suggestions: Vec::new(),
+    };
+    usage.suggestions = suggestions(&usage, sparse);
+    usage
+}
+
+/// Remedies for a repo that crosses one of the size thresholds
+fn suggestions(usage: &RepoUsage, sparse: bool) -> Vec<String> {
+    let mut suggestions = Vec::new();
+
+    if usage.loose_objects > LOOSE_OBJECT_LIMIT {
+        suggestions.push(format!(
+            "{} loose objects; run 'gr gc' to pack them",
+            usage.loose_objects
+        ));
+    }
+    if usage.largest_pack_bytes > HUGE_PACK_BYTES {
+        suggestions.push(format!(
+            "packfile of {}; consider a partial clone (--filter=blob:none) or 'gr gc --aggressive'",
+            format_bytes(usage.largest_pack_bytes)
+        ));
+    }
+    if usage.worktree_bytes > LARGE_WORKTREE_BYTES && !sparse {
+        suggestions.push(format!(
+            "working tree is {}; consider a 'sparse:' list in the manifest",
+            format_bytes(usage.worktree_bytes)
+        ));
+    }
+
+    suggestions
+}
+
+/// Loose object count from `git count-objects -v`
+fn count_loose_objects(repo_path: &Path) -> u64 {
+    let output = Command::new("git")
+        .args(["count-objects", "-v"])
+        .current_dir(repo_path)
+        .timed_output();
+
+    match output {
+        Ok(out) if out.status.success() => {
+            parse_count_objects(&String::from_utf8_lossy(&out.stdout), "count")
+        }
+        _ => 0,
+    }
+}
+
+fn parse_count_objects(output: &str, key: &str) -> u64 {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, v)| v.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn largest_pack(pack_dir: &Path) -> u64 {
+    std::fs::read_dir(pack_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "pack"))
+                .filter_map(|e| e.metadata().ok().map(|m| m.len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(loose_objects: u64, largest_pack_bytes: u64, worktree_bytes: u64) -> RepoUsage {
+        RepoUsage {
+            repo: "app".to_string(),
+            git_bytes: 0,
+            worktree_bytes,
+            loose_objects,
+            largest_pack_bytes,
+            suggestions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_count_objects() {
+        let output = "count: 348\nsize: 1668\nin-pack: 12\npacks: 1\n";
+        assert_eq!(parse_count_objects(output, "count"), 348);
+        assert_eq!(parse_count_objects(output, "in-pack"), 12);
+        assert_eq!(parse_count_objects(output, "missing"), 0);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        assert!(suggestions(&usage(10, 0, 0), false).is_empty());
+
+        let flagged = suggestions(
+            &usage(10_000, 2 * HUGE_PACK_BYTES, 2 * LARGE_WORKTREE_BYTES),
+            false,
+        );
+        assert_eq!(flagged.len(), 3);
+        assert!(flagged[0].contains("gr gc"));
+        assert!(flagged[1].contains("partial clone"));
+        assert!(flagged[2].contains("sparse"));
+
+        // Already sparse: no sparse suggestion
+        let sparse = suggestions(&usage(0, 0, 2 * LARGE_WORKTREE_BYTES), true);
+        assert!(sparse.is_empty());
+    }
+}
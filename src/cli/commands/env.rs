@@ -1,35 +1,234 @@
 //! Env command implementation
 //!
-//! Displays workspace environment variables.
+//! Resolves the workspace environment (built-ins, `workspace.env`, profile
+//! overrides, and with `--repo` that repo's variables) and prints it for
+//! people, as shell exports to `eval`, or as a dotenv file.
 
 use crate::cli::output::Output;
-use crate::core::manifest::Manifest;
+use crate::core::manifest::{Manifest, ShellKind};
+use crate::core::repo::RepoInfo;
+use crate::git::{get_current_branch, open_repo};
+use crate::util::shell::quote_arg;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+/// Settings for `gr env`
+#[derive(Debug, Clone, Default)]
+pub struct EnvOptions {
+    /// Include this repo's `REPO_*` variables and `env`
+    pub repo: Option<String>,
+    /// Layer this profile's env over the workspace's
+    pub profile: Option<String>,
+    /// Print as exports for this shell
+    pub shell: Option<String>,
+    /// Print in dotenv format
+    pub dotenv: bool,
+}
+
+/// Output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnvFormat {
+    /// `export KEY='value'` (sh, bash, zsh)
+    Posix,
+    /// `set -gx KEY 'value'`
+    Fish,
+    /// `$env:KEY = 'value'`
+    PowerShell,
+    /// `KEY="value"`
+    Dotenv,
+}
+
+impl EnvFormat {
+    fn parse_shell(shell: &str) -> anyhow::Result<Self> {
+        match shell {
+            "sh" | "bash" | "zsh" => Ok(Self::Posix),
+            "fish" => Ok(Self::Fish),
+            "powershell" | "pwsh" => Ok(Self::PowerShell),
+            other => anyhow::bail!(
+                "Unknown shell '{}' (expected sh, bash, zsh, fish, or powershell)",
+                other
+            ),
+        }
+    }
+}
+
 /// Run the env command
-pub fn run_env(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
-    Output::header("Workspace Environment");
-    println!();
-
-    // Built-in environment variables
-    println!("  GITGRIP_WORKSPACE={}", workspace_root.display());
-    println!(
-        "  GITGRIP_MANIFEST={}",
-        workspace_root
-            .join(".gitgrip/manifests/manifest.yaml")
-            .display()
-    );
-
-    // Workspace-defined environment variables
-    if let Some(ref workspace) = manifest.workspace {
-        if let Some(ref env_vars) = workspace.env {
+pub fn run_env(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &EnvOptions,
+) -> anyhow::Result<()> {
+    let mut manifest = manifest.clone();
+    if let Some(ref profile) = options.profile {
+        manifest.apply_profile(profile)?;
+    }
+    let format = match (&options.shell, options.dotenv) {
+        (Some(shell), _) => Some(EnvFormat::parse_shell(shell)?),
+        (None, true) => Some(EnvFormat::Dotenv),
+        (None, false) => None,
+    };
+
+    let builtins = builtin_env(workspace_root);
+    let workspace = manifest
+        .workspace
+        .as_ref()
+        .and_then(|w| w.env.clone())
+        .unwrap_or_default();
+    let repo = options
+        .repo
+        .as_deref()
+        .map(|name| repo_env(workspace_root, &manifest, name))
+        .transpose()?;
+
+    let Some(format) = format else {
+        Output::header("Workspace Environment");
+        println!();
+        for (key, value) in &builtins {
+            println!("  {}={}", key, value);
+        }
+        if !workspace.is_empty() {
             println!();
             println!("Workspace variables:");
-            for (key, value) in env_vars {
+            for (key, value) in workspace.iter().collect::<BTreeMap<_, _>>() {
                 println!("  {}={}", key, value);
             }
         }
-    }
+        if let (Some(name), Some(repo)) = (&options.repo, &repo) {
+            println!();
+            println!("Repo variables ({}):", name);
+            for (key, value) in repo {
+                println!("  {}={}", key, value);
+            }
+        }
+        return Ok(());
+    };
 
+    // Later layers win: built-ins, workspace (with profile), repo
+    let mut merged = builtins;
+    merged.extend(workspace);
+    merged.extend(repo.unwrap_or_default());
+    print!("{}", render(&merged, format)?);
     Ok(())
 }
+
+/// Variables every workspace has. The root isn't `GITGRIP_WORKSPACE`, which
+/// names the workspace `gr` targets, so exporting it can't redirect `gr`
+fn builtin_env(workspace_root: &PathBuf) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        (
+            "GITGRIP_WORKSPACE_ROOT".to_string(),
+            workspace_root.display().to_string(),
+        ),
+        (
+            "GITGRIP_MANIFEST".to_string(),
+            workspace_root
+                .join(".gitgrip/manifests/manifest.yaml")
+                .display()
+                .to_string(),
+        ),
+    ])
+}
+
+/// The `REPO_*` variables `gr forall` sets, plus the repo's own `env`
+fn repo_env(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    name: &str,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let config = manifest
+        .repos
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown repo '{}'", name))?;
+    let mut env = BTreeMap::new();
+    if let Some(repo) = RepoInfo::from_config(name, config, workspace_root) {
+        let branch = open_repo(&repo.absolute_path)
+            .ok()
+            .and_then(|r| get_current_branch(&r).ok())
+            .unwrap_or_else(|| repo.default_branch.clone());
+        env.insert("REPO_NAME".to_string(), repo.name.clone());
+        env.insert(
+            "REPO_PATH".to_string(),
+            repo.absolute_path.display().to_string(),
+        );
+        env.insert("REPO_URL".to_string(), repo.url.clone());
+        env.insert("REPO_BRANCH".to_string(), branch);
+    }
+    env.extend(config.env.clone().unwrap_or_default());
+    Ok(env)
+}
+
+fn render(env: &BTreeMap<String, String>, format: EnvFormat) -> anyhow::Result<String> {
+    let mut out = String::new();
+    for (key, value) in env {
+        if key.is_empty()
+            || key.starts_with(|c: char| c.is_ascii_digit())
+            || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            anyhow::bail!("'{}' is not a valid environment variable name", key);
+        }
+        let line = match format {
+            EnvFormat::Posix => format!("export {}={}", key, quote_arg(ShellKind::Sh, value)),
+            EnvFormat::Fish => format!(
+                "set -gx {} '{}'",
+                key,
+                value.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+            EnvFormat::PowerShell => {
+                format!("$env:{} = {}", key, quote_arg(ShellKind::Pwsh, value))
+            }
+            EnvFormat::Dotenv => format!("{}={}", key, dotenv_value(value)),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// A dotenv value, double-quoted with escapes unless it's a plain word
+fn dotenv_value(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./:@,+".contains(c))
+    {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let env = BTreeMap::from([
+            ("A".to_string(), "plain".to_string()),
+            ("B".to_string(), "it's $HOME".to_string()),
+        ]);
+        assert_eq!(
+            render(&env, EnvFormat::Posix).unwrap(),
+            "export A='plain'\nexport B='it'\\''s $HOME'\n"
+        );
+        assert_eq!(
+            render(&env, EnvFormat::Fish).unwrap(),
+            "set -gx A 'plain'\nset -gx B 'it\\'s $HOME'\n"
+        );
+        assert_eq!(
+            render(&env, EnvFormat::PowerShell).unwrap(),
+            "$env:A = 'plain'\n$env:B = 'it''s $HOME'\n"
+        );
+        assert_eq!(
+            render(&env, EnvFormat::Dotenv).unwrap(),
+            "A=plain\nB=\"it's \\$HOME\"\n"
+        );
+
+        let bad = BTreeMap::from([("NOT-VALID".to_string(), "x".to_string())]);
+        assert!(render(&bad, EnvFormat::Posix).is_err());
+        assert!(EnvFormat::parse_shell("tcsh").is_err());
+    }
+}
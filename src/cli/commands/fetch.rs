@@ -0,0 +1,241 @@
+//! Fetch command implementation
+//!
+//! Refreshes remote data in every repo without touching working trees, so
+//! ahead/behind counts in `gr status` can be trusted. `--cron` prints a
+//! systemd timer or launchd agent that runs `gr fetch --quiet` on a schedule.
+
+use crate::cli::i18n::msg;
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::remote::{fetch_remote, get_remote_url};
+use crate::git::{open_repo, path_exists};
+use crate::util::pool::{max_workers, parallel_map};
+use std::path::{Path, PathBuf};
+
+/// Scheduler to emit units for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheduler {
+    Systemd,
+    Launchd,
+}
+
+impl Scheduler {
+    fn parse(scheduler: &str) -> anyhow::Result<Self> {
+        match scheduler {
+            "systemd" => Ok(Scheduler::Systemd),
+            "launchd" => Ok(Scheduler::Launchd),
+            other => anyhow::bail!(
+                "Unknown scheduler '{}' (expected systemd or launchd)",
+                other
+            ),
+        }
+    }
+}
+
+/// Run the fetch command
+///
+/// With `quiet`, only failures are printed (to stderr), for scheduled runs.
+pub fn run_fetch(workspace_root: &PathBuf, manifest: &Manifest, quiet: bool) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    if !quiet {
+        Output::header(&msg!("fetch.fetching", count = repos.len()));
+        println!();
+    }
+
+    let results = parallel_map(&repos, max_workers(), |repo| {
+        let git_repo = open_repo(&repo.absolute_path).map_err(|e| e.to_string())?;
+        match get_remote_url(&git_repo, "origin") {
+            Ok(Some(_)) => fetch_remote(&git_repo, "origin")
+                .map(|()| true)
+                .map_err(|e| e.to_string().trim().to_string()),
+            // Nothing to fetch from
+            _ => Ok(false),
+        }
+    });
+
+    let mut fetched = 0;
+    let mut failed = 0;
+    for (repo, result) in repos.iter().zip(&results) {
+        match result {
+            Ok(true) => {
+                fetched += 1;
+                if !quiet {
+                    Output::success(&repo.name);
+                }
+            }
+            Ok(false) => {
+                if !quiet {
+                    Output::info(&msg!("fetch.no_origin", repo = repo.name));
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                if quiet {
+                    eprintln!("{}: {}", repo.name, e);
+                } else {
+                    Output::error(&format!("{}: {}", repo.name, e));
+                }
+            }
+        }
+    }
+
+    if !quiet {
+        println!();
+        println!(
+            "{}",
+            msg!("fetch.summary", fetched = fetched, failed = failed)
+        );
+    }
+    if failed > 0 {
+        anyhow::bail!("{} repo(s) failed to fetch", failed);
+    }
+
+    Ok(())
+}
+
+/// Print units that run `gr fetch --quiet` in this workspace every `interval_minutes`
+pub fn run_fetch_cron(
+    workspace_root: &Path,
+    scheduler: &str,
+    interval_minutes: u64,
+) -> anyhow::Result<()> {
+    let scheduler = Scheduler::parse(scheduler)?;
+    if interval_minutes == 0 {
+        anyhow::bail!("--interval must be at least 1 minute");
+    }
+    let exe = std::env::current_exe()?;
+    let name = unit_name(workspace_root);
+
+    let units = match scheduler {
+        Scheduler::Systemd => render_systemd(&name, &exe, workspace_root, interval_minutes),
+        Scheduler::Launchd => render_launchd(&name, &exe, workspace_root, interval_minutes),
+    };
+    print!("{}", units);
+
+    Ok(())
+}
+
+/// A unit name unique to the workspace, e.g. `gitgrip-fetch-my-workspace`
+fn unit_name(workspace_root: &Path) -> String {
+    let dir = workspace_root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let slug: String = dir
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "gitgrip-fetch".to_string()
+    } else {
+        format!("gitgrip-fetch-{}", slug)
+    }
+}
+
+fn render_systemd(name: &str, exe: &Path, workspace_root: &Path, interval_minutes: u64) -> String {
+    format!(
+        "# Save as ~/.config/systemd/user/{name}.service
+[Unit]
+Description=gitgrip: fetch remotes in {root}
+
+[Service]
+Type=oneshot
+WorkingDirectory={root}
+ExecStart={exe} fetch --quiet
+
+# Save as ~/.config/systemd/user/{name}.timer, then run:
+#   systemctl --user enable --now {name}.timer
+[Unit]
+Description=gitgrip: fetch remotes in {root} every {interval} minutes
+
+[Timer]
+OnBootSec=5min
+OnUnitActiveSec={interval}min
+Persistent=true
+
+[Install]
+WantedBy=timers.target
+",
+        name = name,
+        root = workspace_root.display(),
+        exe = exe.display(),
+        interval = interval_minutes,
+    )
+}
+
+fn render_launchd(name: &str, exe: &Path, workspace_root: &Path, interval_minutes: u64) -> String {
+    let label = format!("com.gitgrip.{}", name);
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Save as ~/Library/LaunchAgents/{label}.plist, then run:
+     launchctl load ~/Library/LaunchAgents/{label}.plist -->
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{label}</string>
+  <key>ProgramArguments</key>
+  <array>
+    <string>{exe}</string>
+    <string>fetch</string>
+    <string>--quiet</string>
+  </array>
+  <key>WorkingDirectory</key>
+  <string>{root}</string>
+  <key>StartInterval</key>
+  <integer>{seconds}</integer>
+  <key>RunAtLoad</key>
+  <true/>
+</dict>
+</plist>
+"#,
+        label = label,
+        exe = exe.display(),
+        root = workspace_root.display(),
+        seconds = interval_minutes * 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_name() {
+        assert_eq!(
+            unit_name(Path::new("/home/me/My Workspace")),
+            "gitgrip-fetch-my-workspace"
+        );
+        assert_eq!(unit_name(Path::new("/")), "gitgrip-fetch");
+    }
+
+    #[test]
+    fn test_render_units() {
+        let exe = Path::new("/usr/local/bin/gr");
+        let root = Path::new("/work/ws");
+
+        let systemd = render_systemd("gitgrip-fetch-ws", exe, root, 30);
+        assert!(systemd.contains("ExecStart=/usr/local/bin/gr fetch --quiet\n"));
+        assert!(systemd.contains("WorkingDirectory=/work/ws\n"));
+        assert!(systemd.contains("OnUnitActiveSec=30min\n"));
+
+        let launchd = render_launchd("gitgrip-fetch-ws", exe, root, 30);
+        assert!(launchd.contains("<string>com.gitgrip.gitgrip-fetch-ws</string>"));
+        assert!(launchd.contains("<integer>1800</integer>"));
+        assert!(Scheduler::parse("cron").is_err());
+    }
+}
@@ -11,13 +11,16 @@
 //! - Piped commands (git status | grep modified)
 //! - Redirected commands (git log > file.txt)
 
-use crate::cli::output::Output;
-use crate::core::manifest::Manifest;
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::{Manifest, ShellKind};
 use crate::core::repo::RepoInfo;
 use crate::git::path_exists;
+use crate::util::process::{is_cancelled, output_with_timeout, Outcome};
+use crate::util::shell_command;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
 
 /// Diff output format
 #[derive(Debug, Clone)]
@@ -280,34 +283,57 @@ fn execute_git_command(repo_path: &PathBuf, cmd: &GitCommand) -> Result<String,
     }
 }
 
+/// Run `command` in a repo on the git2 fast path, as `gr forall` would
+///
+/// Returns `None` if the command isn't one that forall intercepts (forall
+/// would run it through the shell). Used to check the fast path against the
+/// git CLI.
+pub fn run_intercepted(repo_path: &PathBuf, command: &str) -> Option<Result<String, String>> {
+    try_parse_git_command(command).map(|cmd| execute_git_command(repo_path, &cmd))
+}
+
 fn execute_status(repo: &git2::Repository, porcelain: bool) -> Result<String, String> {
+    // Same as git's defaults: untracked directories collapsed, ignored files hidden
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .include_ignored(false)
+        .renames_head_to_index(true);
     let statuses = repo
-        .statuses(None)
+        .statuses(Some(&mut opts))
         .map_err(|e| format!("Failed to get status: {}", e))?;
 
     if porcelain {
         let mut output = String::new();
+        // Like git, untracked paths come after all tracked changes
+        let mut untracked = String::new();
         for entry in statuses.iter() {
             let status = entry.status();
             let path = entry.path().unwrap_or("?");
 
+            if status.is_wt_new() {
+                untracked.push_str(&format!("?? {}\n", path));
+                // A staged deletion of the same path is still listed
+                if !status.is_index_deleted() {
+                    continue;
+                }
+            }
+
             let index_status = if status.is_index_new() {
                 'A'
+            } else if status.is_index_renamed() {
+                'R'
             } else if status.is_index_modified() {
                 'M'
             } else if status.is_index_deleted() {
                 'D'
-            } else if status.is_index_renamed() {
-                'R'
             } else if status.is_index_typechange() {
                 'T'
             } else {
                 ' '
             };
 
-            let wt_status = if status.is_wt_new() {
-                '?'
-            } else if status.is_wt_modified() {
+            let wt_status = if status.is_wt_modified() {
                 'M'
             } else if status.is_wt_deleted() {
                 'D'
@@ -319,8 +345,20 @@ fn execute_status(repo: &git2::Repository, porcelain: bool) -> Result<String, St
                 ' '
             };
 
-            output.push_str(&format!("{}{} {}\n", index_status, wt_status, path));
+            // For a staged rename, `path` is the old name
+            let renamed_to = entry
+                .head_to_index()
+                .filter(|_| status.is_index_renamed())
+                .and_then(|delta| delta.new_file().path().map(|p| p.display().to_string()));
+            match renamed_to {
+                Some(to) => output.push_str(&format!(
+                    "{}{} {} -> {}\n",
+                    index_status, wt_status, path, to
+                )),
+                None => output.push_str(&format!("{}{} {}\n", index_status, wt_status, path)),
+            }
         }
+        output.push_str(&untracked);
         Ok(output)
     } else {
         if statuses.is_empty() {
@@ -468,7 +506,7 @@ fn execute_diff(
     staged: bool,
     format: &DiffFormat,
 ) -> Result<String, String> {
-    let diff = if staged {
+    let mut diff = if staged {
         let head = repo
             .head()
             .map_err(|e| format!("Failed to get HEAD: {}", e))?;
@@ -480,6 +518,9 @@ fn execute_diff(
         repo.diff_index_to_workdir(None, None)
     }
     .map_err(|e| format!("Failed to get diff: {}", e))?;
+    // git detects renames by default (diff.renames)
+    diff.find_similar(None)
+        .map_err(|e| format!("Failed to detect renames: {}", e))?;
 
     match format {
         DiffFormat::Stat => {
@@ -695,12 +736,11 @@ fn execute_piped_command(
     repo_path: &PathBuf,
     git_cmd: &GitCommand,
     pipe_to: &str,
+    shell: ShellKind,
 ) -> Result<String, String> {
     let git_output = execute_git_command(repo_path, git_cmd)?;
 
-    let mut child = Command::new("sh")
-        .arg("-c")
-        .arg(pipe_to)
+    let mut child = shell_command(shell, pipe_to)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -753,7 +793,31 @@ fn execute_redirected_command(
     Ok(format!("Output written to {}\n", file))
 }
 
+/// Why a repo's command didn't succeed
+#[derive(Debug)]
+enum RunError {
+    Failed(String),
+    /// Killed after `--timeout`, with what it printed until then
+    TimedOut(String),
+    /// Killed or never started because of Ctrl-C
+    Cancelled,
+}
+
+impl RunError {
+    fn label(&self) -> &'static str {
+        match self {
+            RunError::Failed(_) => "failed",
+            RunError::TimedOut(_) => "timed out",
+            RunError::Cancelled => "cancelled",
+        }
+    }
+}
+
 /// Run the forall command
+///
+/// Shell commands that run longer than `timeout` are killed along with any
+/// processes they started. Ctrl-C kills running commands, skips the rest,
+/// and prints the results so far.
 pub fn run_forall(
     workspace_root: &PathBuf,
     manifest: &Manifest,
@@ -761,6 +825,7 @@ pub fn run_forall(
     parallel: bool,
     changed_only: bool,
     no_intercept: bool,
+    timeout: Option<Duration>,
 ) -> anyhow::Result<()> {
     let repos: Vec<RepoInfo> = manifest
         .repos
@@ -775,10 +840,16 @@ pub fn run_forall(
         parse_command(command)
     };
 
+    let shell = manifest.shell();
+
     if parallel {
-        run_parallel(&repos, command, changed_only, &parsed)?;
+        run_parallel(&repos, command, changed_only, &parsed, shell, timeout)?;
     } else {
-        run_sequential(&repos, command, changed_only, &parsed)?;
+        run_sequential(&repos, command, changed_only, &parsed, shell, timeout)?;
+    }
+
+    if is_cancelled() {
+        anyhow::bail!("Cancelled");
     }
 
     Ok(())
@@ -790,43 +861,130 @@ fn execute_parsed_command(
     command: &str,
     parsed: &ParsedCommand,
     repo: &RepoInfo,
-) -> Result<String, String> {
+    shell: ShellKind,
+    timeout: Option<Duration>,
+) -> Result<String, RunError> {
     match parsed {
-        ParsedCommand::Git(git_cmd) => execute_git_command(repo_path, git_cmd),
+        ParsedCommand::Git(git_cmd) => {
+            execute_git_command(repo_path, git_cmd).map_err(RunError::Failed)
+        }
         ParsedCommand::Piped { git_cmd, pipe_to } => {
-            execute_piped_command(repo_path, git_cmd, pipe_to)
+            execute_piped_command(repo_path, git_cmd, pipe_to, shell).map_err(RunError::Failed)
         }
         ParsedCommand::Redirect {
             git_cmd,
             file,
             append,
-        } => execute_redirected_command(repo_path, git_cmd, file, *append),
-        ParsedCommand::Shell(_) => {
-            // Run as shell command
-            let output = Command::new("sh")
-                .arg("-c")
-                .arg(command)
-                .current_dir(repo_path)
-                .env("REPO_NAME", &repo.name)
-                .env("REPO_PATH", repo_path)
-                .env("REPO_URL", &repo.url)
-                .env("REPO_BRANCH", &repo.default_branch)
-                .output()
-                .map_err(|e| e.to_string())?;
-
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                Ok(format!("{}{}", stdout, stderr))
+        } => {
+            execute_redirected_command(repo_path, git_cmd, file, *append).map_err(RunError::Failed)
+        }
+        ParsedCommand::Shell(_) => run_shell(
+            shell,
+            command,
+            repo_path,
+            &repo.name,
+            &repo.url,
+            &repo.default_branch,
+            timeout,
+        ),
+    }
+}
+
+/// Run `command` through the shell in a repo, with the `REPO_*` variables set
+fn run_shell(
+    shell: ShellKind,
+    command: &str,
+    repo_path: &Path,
+    repo_name: &str,
+    repo_url: &str,
+    repo_branch: &str,
+    timeout: Option<Duration>,
+) -> Result<String, RunError> {
+    let mut cmd = shell_command(shell, command);
+    cmd.current_dir(repo_path)
+        .env("REPO_NAME", repo_name)
+        .env("REPO_PATH", repo_path)
+        .env("REPO_URL", repo_url)
+        .env("REPO_BRANCH", repo_branch);
+    let (outcome, stdout, stderr) =
+        output_with_timeout(&mut cmd, timeout).map_err(|e| RunError::Failed(e.to_string()))?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&stdout),
+        String::from_utf8_lossy(&stderr)
+    );
+
+    match outcome {
+        Outcome::Exited(status) if status.success() => Ok(text),
+        Outcome::Exited(status) => Err(RunError::Failed(format!(
+            "Exit code: {:?}\n{}",
+            status.code(),
+            text
+        ))),
+        Outcome::TimedOut => Err(RunError::TimedOut(text)),
+        Outcome::Cancelled => Err(RunError::Cancelled),
+    }
+}
+
+/// Print one repo's result; `timeout` is only used to describe a timeout
+fn print_result(result: &Result<String, RunError>, timeout: Option<Duration>) {
+    match result {
+        Ok(output) => print!("{}", output),
+        Err(RunError::Failed(e)) => Output::error(e),
+        Err(RunError::TimedOut(partial)) => {
+            print!("{}", partial);
+            Output::error(&format!(
+                "Timed out after {}s; killed",
+                timeout.unwrap_or_default().as_secs_f64()
+            ));
+        }
+        Err(RunError::Cancelled) => Output::warning("Cancelled"),
+    }
+}
+
+/// Print the per-repo results table and totals
+fn print_summary(results: &[(String, Result<String, RunError>, Duration)], skip_count: usize) {
+    let mut table = Table::new(vec!["Repo", "Result", "Time"]);
+    for (repo_name, result, elapsed) in results {
+        let label = match result {
+            Ok(_) => "ok",
+            Err(e) => e.label(),
+        };
+        table.add_row(vec![
+            repo_name,
+            label,
+            &format!("{:.1}s", elapsed.as_secs_f64()),
+        ]);
+    }
+    table.print();
+    println!();
+
+    let success_count = results.iter().filter(|(_, r, _)| r.is_ok()).count();
+    let cancel_count = results
+        .iter()
+        .filter(|(_, r, _)| matches!(r, Err(RunError::Cancelled)))
+        .count();
+    let error_count = results.len() - success_count - cancel_count;
+    if cancel_count > 0 {
+        Output::warning(&format!(
+            "{} succeeded, {} failed, {} skipped, {} cancelled",
+            success_count, error_count, skip_count, cancel_count
+        ));
+    } else if error_count == 0 {
+        Output::success(&format!(
+            "Command completed in {} repo(s){}",
+            success_count,
+            if skip_count > 0 {
+                format!(", {} skipped", skip_count)
             } else {
-                Err(format!(
-                    "Exit code: {:?}\n{}{}",
-                    output.status.code(),
-                    String::from_utf8_lossy(&output.stdout),
-                    String::from_utf8_lossy(&output.stderr)
-                ))
+                String::new()
             }
-        }
+        ));
+    } else {
+        Output::warning(&format!(
+            "{} succeeded, {} failed, {} skipped",
+            success_count, error_count, skip_count
+        ));
     }
 }
 
@@ -835,9 +993,10 @@ fn run_sequential(
     command: &str,
     changed_only: bool,
     parsed: &ParsedCommand,
+    shell: ShellKind,
+    timeout: Option<Duration>,
 ) -> anyhow::Result<()> {
-    let mut success_count = 0;
-    let mut error_count = 0;
+    let mut results = Vec::new();
     let mut skip_count = 0;
 
     for repo in repos {
@@ -852,38 +1011,22 @@ fn run_sequential(
             continue;
         }
 
+        if is_cancelled() {
+            results.push((repo.name.clone(), Err(RunError::Cancelled), Duration::ZERO));
+            continue;
+        }
+
         Output::header(&format!("{}:", repo.name));
 
-        match execute_parsed_command(&repo.absolute_path, command, parsed, repo) {
-            Ok(output) => {
-                print!("{}", output);
-                success_count += 1;
-            }
-            Err(e) => {
-                Output::error(&e);
-                error_count += 1;
-            }
-        }
+        let started = Instant::now();
+        let result =
+            execute_parsed_command(&repo.absolute_path, command, parsed, repo, shell, timeout);
+        print_result(&result, timeout);
         println!();
+        results.push((repo.name.clone(), result, started.elapsed()));
     }
 
-    // Summary
-    if error_count == 0 {
-        Output::success(&format!(
-            "Command completed in {} repo(s){}",
-            success_count,
-            if skip_count > 0 {
-                format!(", {} skipped", skip_count)
-            } else {
-                String::new()
-            }
-        ));
-    } else {
-        Output::warning(&format!(
-            "{} succeeded, {} failed, {} skipped",
-            success_count, error_count, skip_count
-        ));
-    }
+    print_summary(&results, skip_count);
 
     Ok(())
 }
@@ -932,22 +1075,27 @@ fn run_parallel(
     command: &str,
     changed_only: bool,
     parsed: &ParsedCommand,
+    shell: ShellKind,
+    timeout: Option<Duration>,
 ) -> anyhow::Result<()> {
     use std::sync::{Arc, Mutex};
     use std::thread;
 
     let results = Arc::new(Mutex::new(Vec::new()));
     let mut handles = vec![];
+    let mut skip_count = 0;
 
     // Clone the parsed command for threads
     let cloneable_cmd = CloneableParsedCommand::from(parsed);
 
     for repo in repos {
         if !path_exists(&repo.absolute_path) {
+            skip_count += 1;
             continue;
         }
 
         if changed_only && !has_changes(&repo.absolute_path).unwrap_or(false) {
+            skip_count += 1;
             continue;
         }
 
@@ -960,50 +1108,38 @@ fn run_parallel(
         let parsed_cmd = cloneable_cmd.clone();
 
         let handle = thread::spawn(move || {
-            let result = match &parsed_cmd {
-                CloneableParsedCommand::Git(git_cmd) => execute_git_command(&repo_path, git_cmd),
-                CloneableParsedCommand::Piped { git_cmd, pipe_to } => {
-                    execute_piped_command(&repo_path, git_cmd, pipe_to)
-                }
-                CloneableParsedCommand::Redirect {
-                    git_cmd,
-                    file,
-                    append,
-                } => execute_redirected_command(&repo_path, git_cmd, file, *append),
-                CloneableParsedCommand::Shell(_) => {
-                    // Run as shell command
-                    let output = Command::new("sh")
-                        .arg("-c")
-                        .arg(&cmd)
-                        .current_dir(&repo_path)
-                        .env("REPO_NAME", &repo_name)
-                        .env("REPO_PATH", &repo_path)
-                        .env("REPO_URL", &repo_url)
-                        .env("REPO_BRANCH", &repo_branch)
-                        .output();
-
-                    match output {
-                        Ok(out) => {
-                            let stdout = String::from_utf8_lossy(&out.stdout).to_string();
-                            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-                            if out.status.success() {
-                                Ok(format!("{}{}", stdout, stderr))
-                            } else {
-                                Err(format!(
-                                    "Exit code: {:?}\n{}{}",
-                                    out.status.code(),
-                                    stdout,
-                                    stderr
-                                ))
-                            }
-                        }
-                        Err(e) => Err(e.to_string()),
+            let started = Instant::now();
+            let result = if is_cancelled() {
+                Err(RunError::Cancelled)
+            } else {
+                match &parsed_cmd {
+                    CloneableParsedCommand::Git(git_cmd) => {
+                        execute_git_command(&repo_path, git_cmd).map_err(RunError::Failed)
+                    }
+                    CloneableParsedCommand::Piped { git_cmd, pipe_to } => {
+                        execute_piped_command(&repo_path, git_cmd, pipe_to, shell)
+                            .map_err(RunError::Failed)
                     }
+                    CloneableParsedCommand::Redirect {
+                        git_cmd,
+                        file,
+                        append,
+                    } => execute_redirected_command(&repo_path, git_cmd, file, *append)
+                        .map_err(RunError::Failed),
+                    CloneableParsedCommand::Shell(_) => run_shell(
+                        shell,
+                        &cmd,
+                        &repo_path,
+                        &repo_name,
+                        &repo_url,
+                        &repo_branch,
+                        timeout,
+                    ),
                 }
             };
 
             let mut results = results.lock().unwrap();
-            results.push((repo_name, result));
+            results.push((repo_name, result, started.elapsed()));
         });
 
         handles.push(handle);
@@ -1016,32 +1152,12 @@ fn run_parallel(
 
     // Print results
     let results = results.lock().unwrap();
-    let mut success_count = 0;
-    let mut error_count = 0;
-
-    for (repo_name, output) in results.iter() {
+    for (repo_name, result, _) in results.iter() {
         Output::header(&format!("{}:", repo_name));
-        match output {
-            Ok(output) => {
-                print!("{}", output);
-                success_count += 1;
-            }
-            Err(e) => {
-                Output::error(&e.to_string());
-                error_count += 1;
-            }
-        }
+        print_result(result, timeout);
         println!();
     }
-
-    if error_count == 0 {
-        Output::success(&format!("Command completed in {} repo(s)", success_count));
-    } else {
-        Output::warning(&format!(
-            "{} succeeded, {} failed",
-            success_count, error_count
-        ));
-    }
+    print_summary(&results, skip_count);
 
     Ok(())
 }
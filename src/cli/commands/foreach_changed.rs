@@ -0,0 +1,343 @@
+//! Foreach-changed command implementation
+//!
+//! Runs a command only in the repos that changed between two points, for
+//! "build only what changed" scripts. Each end is a snapshot file (each
+//! repo's commit, written by `--save`), a git revision resolved in every
+//! repo, or the current checkout.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::{Manifest, ShellKind};
+use crate::core::repo::RepoInfo;
+use crate::git::open_repo;
+use crate::util::process::{is_cancelled, spawn_in_group, wait_with_timeout, Outcome};
+use crate::util::{quote_arg, shell_command};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Placeholder replaced by the repo's changed files, quoted for the shell
+pub const CHANGED_FILES_PLACEHOLDER: &str = "{changed_files}";
+
+/// Each repo's commit at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    /// Commit SHA per repo name
+    pub repos: BTreeMap<String, String>,
+}
+
+impl WorkspaceSnapshot {
+    /// HEAD of every cloned repo
+    pub fn capture(repos: &[RepoInfo]) -> Self {
+        let repos = repos
+            .iter()
+            .filter_map(|repo| {
+                let git_repo = open_repo(&repo.absolute_path).ok()?;
+                let head = git_repo.head().ok()?.peel_to_commit().ok()?;
+                Some((repo.name.clone(), head.id().to_string()))
+            })
+            .collect();
+        Self { repos }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid snapshot {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+/// One end of the comparison
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Endpoint {
+    Snapshot(WorkspaceSnapshot),
+    /// A revision resolved in each repo (e.g. `origin/main`)
+    Revision(String),
+    /// Each repo's HEAD
+    Head,
+}
+
+impl Endpoint {
+    /// A snapshot if `arg` names an existing file, otherwise a revision
+    fn parse(arg: Option<&str>) -> anyhow::Result<Self> {
+        match arg {
+            None => Ok(Self::Head),
+            Some(arg) if Path::new(arg).is_file() => {
+                Ok(Self::Snapshot(WorkspaceSnapshot::load(Path::new(arg))?))
+            }
+            Some(arg) => Ok(Self::Revision(arg.to_string())),
+        }
+    }
+
+    /// The commit this endpoint names in `repo`, or `None` if the repo isn't
+    /// in the snapshot
+    fn resolve(&self, repo: &Repository, name: &str) -> Result<Option<Oid>, String> {
+        let spec = match self {
+            Self::Snapshot(snapshot) => match snapshot.repos.get(name) {
+                Some(sha) => sha.clone(),
+                None => return Ok(None),
+            },
+            Self::Revision(revision) => revision.clone(),
+            Self::Head => "HEAD".to_string(),
+        };
+        repo.revparse_single(&format!("{}^{{commit}}", spec))
+            .map(|object| Some(object.id()))
+            .map_err(|_| format!("'{}' not found (fetch first?)", spec))
+    }
+}
+
+/// Settings for `gr foreach-changed`
+#[derive(Debug, Clone, Default)]
+pub struct ForeachChangedOptions {
+    /// Snapshot file or revision to compare from
+    pub from: Option<String>,
+    /// Snapshot file or revision to compare to (default: each repo's HEAD)
+    pub to: Option<String>,
+    /// Command to run in each changed repo
+    pub command: Option<String>,
+    /// Write a snapshot of the current workspace here instead
+    pub save: Option<PathBuf>,
+    /// Only list the changed repos and files
+    pub dry_run: bool,
+    /// Keep going after a repo's command fails
+    pub continue_on_error: bool,
+}
+
+/// Run the foreach-changed command
+pub fn run_foreach_changed(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &ForeachChangedOptions,
+) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    if let Some(ref path) = options.save {
+        let snapshot = WorkspaceSnapshot::capture(&repos);
+        snapshot.save(path)?;
+        Output::success(&format!(
+            "Saved snapshot of {} repo(s) to {}",
+            snapshot.repos.len(),
+            path.display()
+        ));
+        return Ok(());
+    }
+
+    let from = Endpoint::parse(options.from.as_deref())?;
+    let to = Endpoint::parse(options.to.as_deref())?;
+
+    let mut changed = Vec::new();
+    let mut failed = 0;
+    for repo in &repos {
+        if !repo.exists() {
+            continue;
+        }
+        match repo_changes(&repo.absolute_path, &repo.name, &from, &to) {
+            Ok(files) if files.is_empty() => {}
+            Ok(files) => changed.push((repo, files)),
+            Err(e) => {
+                Output::warning(&format!("{}: {}", repo.name, e));
+                failed += 1;
+            }
+        }
+    }
+
+    if changed.is_empty() {
+        if failed == 0 {
+            Output::info("No repos changed.");
+        }
+        return finish(failed, 0);
+    }
+
+    if options.dry_run || options.command.is_none() {
+        let mut table = Table::new(vec!["Repo", "Files", "Changed"]);
+        for (repo, files) in &changed {
+            table.add_row(vec![
+                &repo.name,
+                &files.len().to_string(),
+                &summarize(files),
+            ]);
+        }
+        table.print();
+        return finish(failed, 0);
+    }
+
+    let command = options.command.as_deref().unwrap_or_default();
+    let shell = manifest.shell();
+    let mut errors = 0;
+    for (repo, files) in &changed {
+        if is_cancelled() {
+            Output::warning("Cancelled; remaining repos skipped");
+            break;
+        }
+        Output::header(&format!("{} ({} changed file(s)):", repo.name, files.len()));
+        let result = run_in_repo(repo, command, files, shell);
+        println!();
+        if let Err(e) = result {
+            Output::error(&format!("{}: {}", repo.name, e));
+            errors += 1;
+            if !options.continue_on_error {
+                break;
+            }
+        }
+    }
+
+    finish(failed, errors)
+}
+
+fn finish(unresolved: usize, errors: usize) -> anyhow::Result<()> {
+    if errors > 0 {
+        anyhow::bail!("Command failed in {} repo(s)", errors);
+    }
+    if unresolved > 0 {
+        anyhow::bail!("Could not compare {} repo(s)", unresolved);
+    }
+    Ok(())
+}
+
+/// Files that differ in a repo between `from` and `to`
+///
+/// A repo missing from the `from` snapshot is new, so all its files count.
+fn repo_changes(
+    repo_path: &Path,
+    name: &str,
+    from: &Endpoint,
+    to: &Endpoint,
+) -> Result<Vec<String>, String> {
+    let repo = open_repo(repo_path).map_err(|e| e.to_string())?;
+    let Some(to_oid) = to.resolve(&repo, name)? else {
+        return Ok(Vec::new());
+    };
+    let from_oid = from.resolve(&repo, name)?;
+    changed_files(&repo, from_oid, to_oid).map_err(|e| e.to_string())
+}
+
+fn changed_files(
+    repo: &Repository,
+    from: Option<Oid>,
+    to: Oid,
+) -> Result<Vec<String>, git2::Error> {
+    let new_tree = repo.find_commit(to)?.tree()?;
+    let old_tree = match from {
+        Some(from) => Some(repo.find_commit(from)?.tree()?),
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+
+    let mut files: Vec<String> = diff
+        .deltas()
+        .filter_map(|delta| {
+            delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|path| path.to_string_lossy().to_string())
+        })
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Run `command` in a repo with `{changed_files}` filled in and the
+/// `REPO_*` and `CHANGED_FILES` variables set
+fn run_in_repo(
+    repo: &RepoInfo,
+    command: &str,
+    files: &[String],
+    shell: ShellKind,
+) -> anyhow::Result<()> {
+    let quoted: Vec<String> = files.iter().map(|f| quote_arg(shell, f)).collect();
+    let command = command.replace(CHANGED_FILES_PLACEHOLDER, &quoted.join(" "));
+
+    let mut cmd = shell_command(shell, &command);
+    cmd.current_dir(&repo.absolute_path)
+        .env("REPO_NAME", &repo.name)
+        .env("REPO_PATH", &repo.absolute_path)
+        .env("REPO_URL", &repo.url)
+        .env("REPO_BRANCH", &repo.default_branch)
+        .env("CHANGED_FILES", files.join("\n"));
+    let mut child = spawn_in_group(&mut cmd)?;
+    match wait_with_timeout(&mut child, None)? {
+        Outcome::Exited(status) if status.success() => Ok(()),
+        Outcome::Exited(status) => anyhow::bail!("exit code: {:?}", status.code()),
+        Outcome::TimedOut | Outcome::Cancelled => anyhow::bail!("cancelled"),
+    }
+}
+
+/// The first few changed files, for the dry-run table
+fn summarize(files: &[String]) -> String {
+    const SHOWN: usize = 3;
+    let mut summary = files
+        .iter()
+        .take(SHOWN)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if files.len() > SHOWN {
+        summary.push_str(&format!(", +{} more", files.len() - SHOWN));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repo_changes_between_snapshot_and_head() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("api");
+        init_repo(&path);
+        std::fs::write(path.join("a.txt"), "a").unwrap();
+        std::fs::write(path.join("b.txt"), "b").unwrap();
+        git(&path, &["add", "."]);
+        git(&path, &["commit", "-q", "-m", "Initial"]);
+
+        let repo = open_repo(&path).unwrap();
+        let head = repo.head().unwrap().target().unwrap().to_string();
+        let snapshot = Endpoint::Snapshot(WorkspaceSnapshot {
+            repos: BTreeMap::from([("api".to_string(), head)]),
+        });
+        assert!(repo_changes(&path, "api", &snapshot, &Endpoint::Head)
+            .unwrap()
+            .is_empty());
+
+        std::fs::write(path.join("b.txt"), "changed").unwrap();
+        std::fs::write(path.join("c d.txt"), "new").unwrap();
+        std::fs::remove_file(path.join("a.txt")).unwrap();
+        git(&path, &["add", "-A"]);
+        git(&path, &["commit", "-q", "-m", "Change"]);
+
+        let files = repo_changes(&path, "api", &snapshot, &Endpoint::Head).unwrap();
+        assert_eq!(files, vec!["a.txt", "b.txt", "c d.txt"]);
+
+        // Not in the snapshot: a new repo, so everything counts
+        let files = repo_changes(&path, "web", &snapshot, &Endpoint::Head).unwrap();
+        assert_eq!(files, vec!["b.txt", "c d.txt"]);
+
+        let revision = Endpoint::Revision("HEAD~1".to_string());
+        assert_eq!(
+            repo_changes(&path, "api", &revision, &Endpoint::Head).unwrap(),
+            vec!["a.txt", "b.txt", "c d.txt"]
+        );
+        assert!(repo_changes(
+            &path,
+            "api",
+            &Endpoint::Revision("v9".into()),
+            &Endpoint::Head
+        )
+        .unwrap_err()
+        .contains("not found"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Gc command implementation
+//!
+//! Runs repository maintenance across repos in parallel and reports how much
+//! space each repo's `.git` directory reclaimed.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::{path_exists, run_git};
+use crate::util::disk::{dir_size, format_bytes};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// Maintenance settings for `gr gc`
+#[derive(Debug, Clone)]
+pub struct GcOptions {
+    /// Run `git gc --aggressive`
+    pub aggressive: bool,
+    /// Prune unreachable objects older than this (git date, e.g. `2.weeks.ago`)
+    pub prune: String,
+    /// Expire reflog entries older than this
+    pub reflog_expire: String,
+    /// Register repos for background `git maintenance`
+    pub maintenance: bool,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        Self {
+            aggressive: false,
+            prune: "2.weeks.ago".to_string(),
+            reflog_expire: "90.days.ago".to_string(),
+            maintenance: false,
+        }
+    }
+}
+
+/// Result of running maintenance in one repo
+struct GcResult {
+    name: String,
+    before: u64,
+    after: u64,
+    errors: Vec<String>,
+}
+
+/// Run the gc command
+pub fn run_gc(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &GcOptions,
+) -> anyhow::Result<()> {
+    Output::header("Running maintenance");
+    println!();
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    let spinner = Output::spinner(&format!("Maintaining {} repo(s)...", repos.len()));
+
+    let handles: Vec<_> = repos
+        .iter()
+        .map(|repo| {
+            let name = repo.name.clone();
+            let path = repo.absolute_path.clone();
+            let options = options.clone();
+            thread::spawn(move || gc_repo(name, &path, &options))
+        })
+        .collect();
+
+    let mut results: Vec<GcResult> = handles
+        .into_iter()
+        .filter_map(|handle| handle.join().ok())
+        .collect();
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+
+    spinner.finish_and_clear();
+
+    let mut table = Table::new(vec!["Repo", "Before", "After", "Saved"]);
+    let mut total_saved = 0;
+    for result in &results {
+        let saved = result.before.saturating_sub(result.after);
+        total_saved += saved;
+        table.add_row(vec![
+            &Output::repo_name(&result.name),
+            &format_bytes(result.before),
+            &format_bytes(result.after),
+            &format_bytes(saved),
+        ]);
+    }
+    table.print();
+
+    println!();
+    let failed: Vec<&GcResult> = results.iter().filter(|r| !r.errors.is_empty()).collect();
+    for result in &failed {
+        for error in &result.errors {
+            Output::warning(&format!("{}: {}", result.name, error));
+        }
+    }
+
+    if failed.is_empty() {
+        Output::success(&format!(
+            "Maintained {} repo(s), reclaimed {}",
+            results.len(),
+            format_bytes(total_saved)
+        ));
+    } else {
+        Output::warning(&format!(
+            "Reclaimed {}; {} repo(s) had errors",
+            format_bytes(total_saved),
+            failed.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn gc_repo(name: String, repo_path: &Path, options: &GcOptions) -> GcResult {
+    let git_dir = repo_path.join(".git");
+    let before = dir_size(&git_dir);
+
+    let mut errors = Vec::new();
+    for args in gc_steps(options) {
+        let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        // Repos without an origin remote have nothing to prune
+        if args[..2] == ["remote", "prune"] && !has_remote(repo_path, "origin") {
+            continue;
+        }
+        if let Err(e) = run_git(repo_path, &args) {
+            errors.push(e.to_string());
+        }
+    }
+
+    GcResult {
+        name,
+        before,
+        after: dir_size(&git_dir),
+        errors,
+    }
+}
+
+/// Git invocations performed for each repo, in order
+fn gc_steps(options: &GcOptions) -> Vec<Vec<String>> {
+    let mut steps = vec![
+        vec![
+            "reflog".to_string(),
+            "expire".to_string(),
+            format!("--expire={}", options.reflog_expire),
+            "--all".to_string(),
+        ],
+        vec![
+            "remote".to_string(),
+            "prune".to_string(),
+            "origin".to_string(),
+        ],
+        vec![
+            "pack-refs".to_string(),
+            "--all".to_string(),
+            "--prune".to_string(),
+        ],
+    ];
+
+    let mut gc = vec![
+        "gc".to_string(),
+        "--quiet".to_string(),
+        format!("--prune={}", options.prune),
+    ];
+    if options.aggressive {
+        gc.push("--aggressive".to_string());
+    }
+    steps.push(gc);
+
+    if options.maintenance {
+        steps.push(vec!["maintenance".to_string(), "register".to_string()]);
+    }
+
+    steps
+}
+
+fn has_remote(repo_path: &Path, remote: &str) -> bool {
+    run_git(repo_path, &["remote"])
+        .map(|out| out.lines().any(|l| l.trim() == remote))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::init_repo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gc_steps() {
+        let options = GcOptions {
+            aggressive: true,
+            maintenance: true,
+            ..Default::default()
+        };
+        let steps = gc_steps(&options);
+        assert_eq!(steps[0][2], "--expire=90.days.ago");
+        assert_eq!(
+            steps[3],
+            vec!["gc", "--quiet", "--prune=2.weeks.ago", "--aggressive"]
+        );
+        assert_eq!(steps.last().unwrap(), &vec!["maintenance", "register"]);
+    }
+
+    #[test]
+    fn test_gc_repo_without_remote() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        run_git(path, &["commit", "--allow-empty", "-m", "Initial commit"]).unwrap();
+
+        let result = gc_repo("test".to_string(), path, &GcOptions::default());
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert!(result.before > 0);
+    }
+}
@@ -0,0 +1,235 @@
+//! Graph command implementation
+//!
+//! Exports the workspace as a DOT or Mermaid graph: one node per repo,
+//! annotated with its current branch and linked PR, and an edge for each
+//! `depends_on` entry in the manifest.
+
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::StateFile;
+use crate::git::{get_current_branch, open_repo, path_exists};
+use std::path::PathBuf;
+
+/// Graph output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl GraphFormat {
+    fn parse(format: &str) -> anyhow::Result<Self> {
+        match format {
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            other => anyhow::bail!("Unknown graph format '{}' (expected dot or mermaid)", other),
+        }
+    }
+}
+
+/// A repo in the graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GraphNode {
+    name: String,
+    /// Current branch, or `None` if the repo is not cloned
+    branch: Option<String>,
+    /// Whether `branch` differs from the repo's default branch
+    on_feature_branch: bool,
+    /// Linked PR for the current branch, as `(number, state)`
+    pr: Option<(u64, String)>,
+    depends_on: Vec<String>,
+}
+
+impl GraphNode {
+    fn annotation(&self) -> String {
+        let mut parts = Vec::new();
+        match &self.branch {
+            Some(branch) => parts.push(branch.clone()),
+            None => parts.push("not cloned".to_string()),
+        }
+        if let Some((number, state)) = &self.pr {
+            parts.push(format!("PR #{} ({})", number, state));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Run the graph command
+pub fn run_graph(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    format: &str,
+) -> anyhow::Result<()> {
+    let format = GraphFormat::parse(format)?;
+
+    let state =
+        StateFile::load(workspace_root.join(".gitgrip").join("state.json")).unwrap_or_default();
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let nodes: Vec<GraphNode> = repos
+        .iter()
+        .map(|repo| {
+            let branch = if path_exists(&repo.absolute_path) {
+                open_repo(&repo.absolute_path)
+                    .and_then(|r| get_current_branch(&r))
+                    .ok()
+            } else {
+                None
+            };
+            let on_feature_branch = branch.as_deref().is_some_and(|b| b != repo.default_branch);
+            let pr = branch
+                .as_deref()
+                .filter(|_| on_feature_branch)
+                .and_then(|b| state.get_pr_for_branch(b))
+                .and_then(|manifest_pr| state.get_linked_prs(manifest_pr))
+                .and_then(|links| links.iter().find(|l| l.repo_name == repo.name))
+                .map(|link| (link.number, link.state.to_string()));
+
+            let mut depends_on = manifest
+                .repos
+                .get(&repo.name)
+                .and_then(|config| config.depends_on.clone())
+                .unwrap_or_default();
+            // Scoping with --paths can drop a dependency from the manifest
+            depends_on.retain(|dep| manifest.repos.contains_key(dep));
+
+            GraphNode {
+                name: repo.name.clone(),
+                branch,
+                on_feature_branch,
+                pr,
+                depends_on,
+            }
+        })
+        .collect();
+
+    let rendered = match format {
+        GraphFormat::Dot => render_dot(&nodes),
+        GraphFormat::Mermaid => render_mermaid(&nodes),
+    };
+    print!("{}", rendered);
+
+    Ok(())
+}
+
+fn render_dot(nodes: &[GraphNode]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+
+    let mut out = String::from("digraph workspace {\n  rankdir=LR;\n  node [shape=box];\n");
+    for node in nodes {
+        let style = if node.on_feature_branch {
+            ", style=bold"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\\n{}\"{}];\n",
+            escape(&node.name),
+            escape(&node.name),
+            escape(&node.annotation()),
+            style
+        ));
+    }
+    for node in nodes {
+        for dep in &node.depends_on {
+            out.push_str(&format!(
+                "  \"{}\" -> \"{}\";\n",
+                escape(&node.name),
+                escape(dep)
+            ));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_mermaid(nodes: &[GraphNode]) -> String {
+    // Mermaid node IDs must be plain identifiers; the repo name goes in the label
+    let id = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    };
+    let label = |s: &str| s.replace('"', "#quot;");
+
+    let mut out = String::from("graph LR\n");
+    for node in nodes {
+        out.push_str(&format!(
+            "  {}[\"{}<br/>{}\"]\n",
+            id(&node.name),
+            label(&node.name),
+            label(&node.annotation())
+        ));
+    }
+    for node in nodes {
+        for dep in &node.depends_on {
+            out.push_str(&format!("  {} --> {}\n", id(&node.name), id(dep)));
+        }
+    }
+    let featured: Vec<String> = nodes
+        .iter()
+        .filter(|n| n.on_feature_branch)
+        .map(|n| id(&n.name))
+        .collect();
+    if !featured.is_empty() {
+        out.push_str("  classDef feature stroke-width:3px\n");
+        out.push_str(&format!("  class {} feature\n", featured.join(",")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes() -> Vec<GraphNode> {
+        vec![
+            GraphNode {
+                name: "web-app".to_string(),
+                branch: Some("feat/login".to_string()),
+                on_feature_branch: true,
+                pr: Some((42, "open".to_string())),
+                depends_on: vec!["lib".to_string()],
+            },
+            GraphNode {
+                name: "lib".to_string(),
+                branch: None,
+                on_feature_branch: false,
+                pr: None,
+                depends_on: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let dot = render_dot(&nodes());
+        assert!(dot.starts_with("digraph workspace {"));
+        assert!(dot.contains(
+            "  \"web-app\" [label=\"web-app\\nfeat/login · PR #42 (open)\", style=bold];\n"
+        ));
+        assert!(dot.contains("  \"lib\" [label=\"lib\\nnot cloned\"];\n"));
+        assert!(dot.contains("  \"web-app\" -> \"lib\";\n"));
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let mermaid = render_mermaid(&nodes());
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("  web_app[\"web-app<br/>feat/login · PR #42 (open)\"]\n"));
+        assert!(mermaid.contains("  web_app --> lib\n"));
+        assert!(mermaid.contains("  class web_app feature\n"));
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        assert!(GraphFormat::parse("svg").is_err());
+        assert_eq!(GraphFormat::parse("dot").unwrap(), GraphFormat::Dot);
+    }
+}
@@ -0,0 +1,238 @@
+//! Grep command implementation
+//!
+//! Searches tracked files in every repo in parallel with `git grep`, so
+//! ignored and untracked files are skipped, and groups matches by repo.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::path_exists;
+use crate::util::profile::ProfiledCommand;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+/// Search settings for `gr grep`
+#[derive(Debug, Clone, Default)]
+pub struct GrepOptions {
+    /// Case-insensitive match
+    pub ignore_case: bool,
+    /// Treat the pattern as a literal string
+    pub fixed_strings: bool,
+    /// Only list files containing a match
+    pub files_with_matches: bool,
+    /// Output JSON
+    pub json: bool,
+}
+
+/// A single match (`line` and `text` are empty with `files_with_matches`)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct GrepMatch {
+    repo: String,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+/// Run the grep command
+pub fn run_grep(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    pattern: &str,
+    pathspecs: &[String],
+    options: &GrepOptions,
+) -> anyhow::Result<()> {
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let handles: Vec<_> = repos
+        .iter()
+        .map(|repo| {
+            let name = repo.name.clone();
+            let path = repo.absolute_path.clone();
+            let pattern = pattern.to_string();
+            let pathspecs = pathspecs.to_vec();
+            let options = options.clone();
+            thread::spawn(move || {
+                let result = git_grep(&path, &pattern, &pathspecs, &options);
+                (name, result)
+            })
+        })
+        .collect();
+
+    // Handles are joined in repo order so output is stable
+    let mut matches: Vec<GrepMatch> = Vec::new();
+    let mut errors = 0;
+    for handle in handles {
+        let Ok((name, result)) = handle.join() else {
+            continue;
+        };
+        match result {
+            Ok(output) => matches.extend(parse_output(&name, &output, options)),
+            Err(e) => {
+                if !options.json {
+                    Output::error(&format!("{}: {}", name, e));
+                }
+                errors += 1;
+            }
+        }
+    }
+
+    if options.json {
+        Output::json(&matches)?;
+    } else {
+        print_grouped(&matches, options);
+    }
+
+    if errors > 0 {
+        anyhow::bail!("grep failed in {} repo(s)", errors);
+    }
+    Ok(())
+}
+
+fn git_grep(
+    repo_path: &Path,
+    pattern: &str,
+    pathspecs: &[String],
+    options: &GrepOptions,
+) -> Result<String, String> {
+    let mut args = vec!["grep", "-z", "-I", "--no-color"];
+    args.push(if options.fixed_strings { "-F" } else { "-E" });
+    if options.ignore_case {
+        args.push("-i");
+    }
+    args.push(if options.files_with_matches {
+        "-l"
+    } else {
+        "-n"
+    });
+    args.push("-e");
+    args.push(pattern);
+    if !pathspecs.is_empty() {
+        args.push("--");
+        args.extend(pathspecs.iter().map(|p| p.as_str()));
+    }
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    // Exit code 1 just means no matches
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+/// Parse `git grep -z` output (`path\0line\0text` or `path\0` with `-l`)
+fn parse_output(repo: &str, output: &str, options: &GrepOptions) -> Vec<GrepMatch> {
+    if options.files_with_matches {
+        return output
+            .split('\0')
+            .map(|p| p.trim_start_matches('\n'))
+            .filter(|p| !p.is_empty())
+            .map(|path| GrepMatch {
+                repo: repo.to_string(),
+                path: path.to_string(),
+                line: None,
+                text: None,
+            })
+            .collect();
+    }
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\0');
+            let path = parts.next()?;
+            let line_no = parts.next()?.parse().ok()?;
+            let text = parts.next().unwrap_or("");
+            Some(GrepMatch {
+                repo: repo.to_string(),
+                path: path.to_string(),
+                line: Some(line_no),
+                text: Some(text.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn print_grouped(matches: &[GrepMatch], options: &GrepOptions) {
+    if matches.is_empty() {
+        Output::info("No matches found.");
+        return;
+    }
+
+    let mut current_repo: Option<&str> = None;
+    for m in matches {
+        if current_repo != Some(m.repo.as_str()) {
+            if current_repo.is_some() {
+                println!();
+            }
+            println!("{}", Output::repo_name(&m.repo));
+            current_repo = Some(&m.repo);
+        }
+
+        match (m.line, &m.text) {
+            (Some(line), Some(text)) if !options.files_with_matches => {
+                println!(
+                    "  {}:{}: {}",
+                    m.path.magenta(),
+                    line.to_string().green(),
+                    text
+                )
+            }
+            _ => println!("  {}", m.path.magenta()),
+        }
+    }
+
+    let repo_count = {
+        let mut repos: Vec<&str> = matches.iter().map(|m| m.repo.as_str()).collect();
+        repos.dedup();
+        repos.len()
+    };
+    println!();
+    let noun = if options.files_with_matches {
+        "file(s)"
+    } else {
+        "match(es)"
+    };
+    println!("  {} {} in {} repo(s)", matches.len(), noun, repo_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_matches() {
+        let output = "src/main.rs\x0012\x00fn main() {\nREADME.md\x003\x00a: b\n";
+        let matches = parse_output("app", output, &GrepOptions::default());
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].path, "src/main.rs");
+        assert_eq!(matches[0].line, Some(12));
+        assert_eq!(matches[1].text.as_deref(), Some("a: b"));
+    }
+
+    #[test]
+    fn test_parse_files_with_matches() {
+        let options = GrepOptions {
+            files_with_matches: true,
+            ..Default::default()
+        };
+        let matches = parse_output("app", "src/a.rs\x00src/b.rs\x00", &options);
+        let paths: Vec<&str> = matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/a.rs", "src/b.rs"]);
+        assert!(matches[0].line.is_none());
+    }
+}
@@ -7,9 +7,11 @@
 
 use crate::cli::output::Output;
 use crate::core::manifest::{Manifest, PlatformType, RepoConfig};
+use crate::core::state::StateFile;
 use crate::git::clone_repo;
 use crate::platform;
-use dialoguer::{theme::ColorfulTheme, Editor, Select};
+use crate::util::profile::ProfiledCommand;
+use dialoguer::{Editor, Select};
 use git2::Repository;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -40,6 +42,7 @@ pub async fn run_init(
     create_manifest: bool,
     manifest_name: Option<&str>,
     private: bool,
+    profile: Option<&str>,
 ) -> anyhow::Result<()> {
     if from_dirs {
         run_init_from_dirs(
@@ -52,12 +55,16 @@ pub async fn run_init(
         )
         .await
     } else {
-        run_init_from_url(url, path)
+        run_init_from_url(url, path, profile)
     }
 }
 
 /// Initialize workspace from a manifest URL (original behavior)
-fn run_init_from_url(url: Option<&str>, path: Option<&str>) -> anyhow::Result<()> {
+fn run_init_from_url(
+    url: Option<&str>,
+    path: Option<&str>,
+    profile: Option<&str>,
+) -> anyhow::Result<()> {
     let manifest_url = match url {
         Some(u) => u.to_string(),
         None => {
@@ -91,7 +98,7 @@ fn run_init_from_url(url: Option<&str>, path: Option<&str>) -> anyhow::Result<()
 
     // Clone manifest repository
     let spinner = Output::spinner("Cloning manifest repository...");
-    match clone_repo(&manifest_url, &manifests_dir, None) {
+    match clone_repo(&manifest_url, &manifests_dir, None, None) {
         Ok(_) => {
             spinner.finish_with_message("Manifest cloned successfully");
         }
@@ -110,16 +117,37 @@ fn run_init_from_url(url: Option<&str>, path: Option<&str>) -> anyhow::Result<()
         anyhow::bail!("No manifest.yaml found in repository");
     }
 
+    // Check the profile before recording it, so a typo fails here and not
+    // on the first command in the workspace
+    if let Some(profile) = profile {
+        let checked = Manifest::load_for_workspace(&manifest_path, &target_dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut manifest| Ok(manifest.apply_profile(profile)?));
+        if let Err(e) = checked {
+            let _ = std::fs::remove_dir_all(&target_dir);
+            return Err(e);
+        }
+    }
+
     // Create state file
-    let state_path = gitgrip_dir.join("state.json");
-    std::fs::write(&state_path, "{}")?;
+    let state = StateFile {
+        profile: profile.map(str::to_string),
+        ..Default::default()
+    };
+    state.save(gitgrip_dir.join("state.json"))?;
 
     println!();
     Output::success("Workspace initialized successfully!");
     println!();
     println!("Next steps:");
     println!("  cd {:?}", target_dir);
-    println!("  gr sync    # Clone all repositories");
+    match profile {
+        Some(profile) => println!(
+            "  gr sync    # Clone the '{}' profile's repositories",
+            profile
+        ),
+        None => println!("  gr sync    # Clone all repositories"),
+    }
 
     Ok(())
 }
@@ -171,7 +199,13 @@ async fn run_init_from_dirs(
     println!();
     for repo in &discovered {
         let url_display = repo.url.as_deref().unwrap_or("(no remote)");
-        Output::list_item(&format!("{} → {} ({})", repo.name, repo.path, url_display));
+        Output::list_item(&format!(
+            "{} {} {} ({})",
+            repo.name,
+            Output::arrow(),
+            repo.path,
+            url_display
+        ));
     }
     println!();
 
@@ -239,7 +273,7 @@ async fn run_init_from_dirs(
                     let output = Command::new("git")
                         .args(["remote", "add", "origin", &clone_url])
                         .current_dir(&manifests_dir)
-                        .output()?;
+                        .timed_output()?;
 
                     if output.status.success() {
                         Output::success("Added remote 'origin' to manifest repo");
@@ -249,7 +283,7 @@ async fn run_init_from_dirs(
                         let push_output = Command::new("git")
                             .args(["push", "-u", "origin", "main"])
                             .current_dir(&manifests_dir)
-                            .output()?;
+                            .timed_output()?;
 
                         if push_output.status.success() {
                             Output::success("Pushed initial commit to remote");
@@ -258,7 +292,7 @@ async fn run_init_from_dirs(
                             let push_output = Command::new("git")
                                 .args(["push", "-u", "origin", "master"])
                                 .current_dir(&manifests_dir)
-                                .output()?;
+                                .timed_output()?;
 
                             if push_output.status.success() {
                                 Output::success("Pushed initial commit to remote");
@@ -494,6 +528,11 @@ fn generate_manifest(repos: &[DiscoveredRepo]) -> Manifest {
                 copyfile: None,
                 linkfile: None,
                 platform: None,
+                sparse: None,
+                depends_on: None,
+                revision: None,
+                env: None,
+                workspace: false,
             },
         );
     }
@@ -504,6 +543,8 @@ fn generate_manifest(repos: &[DiscoveredRepo]) -> Manifest {
         repos: repo_configs,
         settings: Default::default(),
         workspace: None,
+        profiles: HashMap::new(),
+        versions: HashMap::new(),
     }
 }
 
@@ -518,7 +559,7 @@ fn run_interactive_init(
     _workspace_root: &Path,
     discovered: &mut Vec<DiscoveredRepo>,
 ) -> anyhow::Result<Option<Manifest>> {
-    let theme = ColorfulTheme::default();
+    let theme = Output::theme();
 
     loop {
         // Show options
@@ -528,7 +569,7 @@ fn run_interactive_init(
             "Cancel",
         ];
 
-        let selection = Select::with_theme(&theme)
+        let selection = Select::with_theme(&*theme)
             .with_prompt("What would you like to do?")
             .items(&options)
             .default(0)
@@ -542,14 +583,14 @@ fn run_interactive_init(
 
                 println!();
                 println!("Generated manifest.yaml:");
-                println!("─────────────────────────────────────────");
+                println!("{}", Output::rule(41));
                 println!("{}", yaml);
-                println!("─────────────────────────────────────────");
+                println!("{}", Output::rule(41));
                 println!();
 
                 let edit_options = vec!["Use this manifest", "Edit in editor", "Go back"];
 
-                let edit_selection = Select::with_theme(&theme)
+                let edit_selection = Select::with_theme(&*theme)
                     .with_prompt("Review the manifest")
                     .items(&edit_options)
                     .default(0)
@@ -593,7 +634,7 @@ fn run_interactive_init(
                 println!();
                 println!("Selected repositories:");
                 for repo in discovered.iter() {
-                    Output::list_item(&format!("{} → {}", repo.name, repo.path));
+                    Output::list_item(&format!("{} {} {}", repo.name, Output::arrow(), repo.path));
                 }
                 println!();
             }
@@ -605,16 +646,16 @@ fn run_interactive_init(
 
 /// Interactive editing of the repository list
 fn run_edit_repo_list(repos: &mut Vec<DiscoveredRepo>) -> anyhow::Result<()> {
-    let theme = ColorfulTheme::default();
+    let theme = Output::theme();
 
     loop {
         let mut options: Vec<String> = repos
             .iter()
-            .map(|r| format!("[✓] {} ({})", r.name, r.path))
+            .map(|r| format!("[{}] {} ({})", Output::mark(true), r.name, r.path))
             .collect();
         options.push("Done editing".to_string());
 
-        let selection = Select::with_theme(&theme)
+        let selection = Select::with_theme(&*theme)
             .with_prompt("Toggle repositories (select to remove)")
             .items(&options)
             .default(options.len() - 1)
@@ -644,7 +685,7 @@ fn init_manifest_repo(manifests_dir: &Path) -> anyhow::Result<()> {
     let output = Command::new("git")
         .args(["init"])
         .current_dir(manifests_dir)
-        .output()?;
+        .timed_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -655,7 +696,7 @@ fn init_manifest_repo(manifests_dir: &Path) -> anyhow::Result<()> {
     let output = Command::new("git")
         .args(["add", "manifest.yaml"])
         .current_dir(manifests_dir)
-        .output()?;
+        .timed_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -670,7 +711,7 @@ fn init_manifest_repo(manifests_dir: &Path) -> anyhow::Result<()> {
             "Initial manifest\n\nGenerated by gr init --from-dirs",
         ])
         .current_dir(manifests_dir)
-        .output()?;
+        .timed_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
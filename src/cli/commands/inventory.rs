@@ -0,0 +1,517 @@
+//! Inventory command implementation
+//!
+//! Lists the dependencies declared in each repo's Cargo.toml, package.json,
+//! go.mod, and requirements.txt files, and the licenses found in license
+//! files and package metadata, as JSON or CSV. Everything is read from the
+//! HEAD commit, so results are cached per repo in
+//! `.gitgrip/inventory-cache.json` until HEAD moves.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::{open_repo, path_exists};
+use crate::util::{max_workers, parallel_map};
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Directories of vendored or generated code, not the repo's own manifests
+const SKIPPED_DIRS: [&str; 4] = ["node_modules", "vendor", "target", "third_party"];
+
+/// Settings for `gr inventory`
+#[derive(Debug, Clone, Default)]
+pub struct InventoryOptions {
+    /// Output CSV instead of JSON
+    pub csv: bool,
+    /// Write to this file instead of stdout
+    pub output: Option<PathBuf>,
+    /// Rescan every repo, ignoring the cache
+    pub no_cache: bool,
+}
+
+/// A declared dependency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Dependency {
+    /// `cargo`, `npm`, `go`, or `pypi`
+    ecosystem: String,
+    name: String,
+    /// Version requirement as written; empty if none
+    version: String,
+    /// `normal`, `dev`, `build`, `peer`, `optional`, or `indirect`
+    scope: String,
+    /// Manifest that declares it
+    path: String,
+}
+
+/// A license found in a license file or package metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct License {
+    /// SPDX identifier, or `unknown` for license files that aren't recognized
+    license: String,
+    path: String,
+}
+
+/// Everything found in one repo at one commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RepoInventory {
+    repo: String,
+    head: String,
+    licenses: Vec<License>,
+    dependencies: Vec<Dependency>,
+}
+
+/// Run the inventory command
+pub fn run_inventory(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &InventoryOptions,
+) -> anyhow::Result<()> {
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let cache_path = workspace_root.join(".gitgrip").join("inventory-cache.json");
+    let cache: BTreeMap<String, RepoInventory> = if options.no_cache {
+        BTreeMap::new()
+    } else {
+        std::fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    };
+
+    let results = parallel_map(&repos, max_workers(), |repo| {
+        let git_repo = open_repo(&repo.absolute_path).map_err(|e| e.to_string())?;
+        let head = git_repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| e.to_string())?
+            .id()
+            .to_string();
+        match cache.get(&repo.name) {
+            Some(cached) if cached.head == head => Ok(cached.clone()),
+            _ => scan_repo(&git_repo, &repo.name, head).map_err(|e| e.to_string()),
+        }
+    });
+
+    let mut inventories = Vec::new();
+    for (repo, result) in repos.iter().zip(results) {
+        match result {
+            Ok(inventory) => inventories.push(inventory),
+            Err(e) => Output::warning(&format!("{}: {}", repo.name, e)),
+        }
+    }
+
+    // Repos that failed this time keep their cached entry
+    let mut updated = cache;
+    for inventory in &inventories {
+        updated.insert(inventory.repo.clone(), inventory.clone());
+    }
+    if let Err(e) = std::fs::write(&cache_path, serde_json::to_string(&updated)?) {
+        Output::warning(&format!("Could not write {}: {}", cache_path.display(), e));
+    }
+
+    let rendered = if options.csv {
+        to_csv(&inventories)
+    } else {
+        serde_json::to_string_pretty(&inventories)? + "\n"
+    };
+    match &options.output {
+        Some(path) => {
+            std::fs::write(path, rendered)?;
+            let dependencies: usize = inventories.iter().map(|i| i.dependencies.len()).sum();
+            Output::success(&format!(
+                "Wrote {} dependencies from {} repo(s) to {}",
+                dependencies,
+                inventories.len(),
+                path.display()
+            ));
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Find and parse the manifests and license files in HEAD's tree
+fn scan_repo(repo: &Repository, name: &str, head: String) -> anyhow::Result<RepoInventory> {
+    let tree = repo.head()?.peel_to_tree()?;
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        let name = entry.name().unwrap_or_default();
+        match entry.kind() {
+            Some(ObjectType::Tree) if SKIPPED_DIRS.contains(&name) => TreeWalkResult::Skip,
+            Some(ObjectType::Blob) if is_manifest(name) || is_license_file(name) => {
+                files.push((format!("{}{}", dir, name), entry.id()));
+                TreeWalkResult::Ok
+            }
+            _ => TreeWalkResult::Ok,
+        }
+    })?;
+
+    let mut inventory = RepoInventory {
+        repo: name.to_string(),
+        head,
+        licenses: Vec::new(),
+        dependencies: Vec::new(),
+    };
+    for (path, id) in files {
+        let blob = repo.find_blob(id)?;
+        let content = String::from_utf8_lossy(blob.content());
+        let file = path.rsplit('/').next().unwrap_or(&path);
+        if is_license_file(file) {
+            inventory.licenses.push(License {
+                license: detect_license(&content).unwrap_or("unknown").to_string(),
+                path,
+            });
+            continue;
+        }
+        let (dependencies, declared) = parse_manifest(file, &content, &path);
+        inventory.dependencies.extend(dependencies);
+        inventory
+            .licenses
+            .extend(declared.map(|license| License { license, path }));
+    }
+    Ok(inventory)
+}
+
+fn is_manifest(file: &str) -> bool {
+    matches!(
+        file,
+        "Cargo.toml" | "package.json" | "go.mod" | "requirements.txt"
+    )
+}
+
+fn is_license_file(file: &str) -> bool {
+    let upper = file.to_ascii_uppercase();
+    ["LICENSE", "LICENCE", "COPYING"]
+        .iter()
+        .any(|prefix| upper.starts_with(prefix))
+}
+
+/// Dependencies in a manifest, plus the license it declares
+fn parse_manifest(file: &str, content: &str, path: &str) -> (Vec<Dependency>, Option<String>) {
+    let dependency = |ecosystem: &str, name: &str, version: &str, scope: &str| Dependency {
+        ecosystem: ecosystem.to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+        scope: scope.to_string(),
+        path: path.to_string(),
+    };
+    let mut dependencies = Vec::new();
+    let mut declared = None;
+
+    match file {
+        "Cargo.toml" => {
+            let mut section = String::new();
+            for line in content.lines().map(str::trim) {
+                if line.starts_with('[') {
+                    section = line.trim_matches(|c| c == '[' || c == ']').to_string();
+                    continue;
+                }
+                let Some((key, value)) = line.split_once('=') else {
+                    continue;
+                };
+                let (key, value) = (key.trim(), value.trim());
+                if section == "package" && key == "license" {
+                    declared = Some(value.trim_matches('"').to_string());
+                }
+                // `[dependencies.serde]` tables name the dependency in the header
+                if let Some((table, name)) = section.rsplit_once('.') {
+                    if let Some(scope) = cargo_scope(table) {
+                        if key == "version" {
+                            dependencies.push(dependency(
+                                "cargo",
+                                name,
+                                value.trim_matches('"'),
+                                scope,
+                            ));
+                        }
+                        continue;
+                    }
+                }
+                let Some(scope) = cargo_scope(&section) else {
+                    continue;
+                };
+                // `serde.workspace = true` inherits the workspace's version
+                let (name, version) = match key.strip_suffix(".workspace") {
+                    Some(name) => (name, "workspace".to_string()),
+                    None if value.starts_with('{') => (
+                        key,
+                        inline_table_value(value, "version").unwrap_or_default(),
+                    ),
+                    None => (key, value.trim_matches('"').to_string()),
+                };
+                dependencies.push(dependency("cargo", name, &version, scope));
+            }
+        }
+        "package.json" => {
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+                return (dependencies, declared);
+            };
+            declared = json["license"].as_str().map(str::to_string);
+            for (field, scope) in [
+                ("dependencies", "normal"),
+                ("devDependencies", "dev"),
+                ("peerDependencies", "peer"),
+                ("optionalDependencies", "optional"),
+            ] {
+                for (name, version) in json[field].as_object().into_iter().flatten() {
+                    dependencies.push(dependency(
+                        "npm",
+                        name,
+                        version.as_str().unwrap_or_default(),
+                        scope,
+                    ));
+                }
+            }
+        }
+        "go.mod" => {
+            let mut in_block = false;
+            for line in content.lines().map(str::trim) {
+                let spec = if in_block {
+                    if line == ")" {
+                        in_block = false;
+                        continue;
+                    }
+                    line
+                } else if line == "require (" {
+                    in_block = true;
+                    continue;
+                } else if let Some(spec) = line.strip_prefix("require ") {
+                    spec
+                } else {
+                    continue;
+                };
+                let scope = if spec.contains("// indirect") {
+                    "indirect"
+                } else {
+                    "normal"
+                };
+                let mut parts = spec.split_whitespace();
+                if let (Some(name), Some(version)) = (parts.next(), parts.next()) {
+                    if !name.starts_with("//") {
+                        dependencies.push(dependency("go", name, version, scope));
+                    }
+                }
+            }
+        }
+        "requirements.txt" => {
+            for line in content.lines() {
+                let line = line.split('#').next().unwrap_or("").trim();
+                if line.is_empty() || line.starts_with('-') {
+                    continue;
+                }
+                let split = line
+                    .find(|c: char| "=<>!~;[ @".contains(c))
+                    .unwrap_or(line.len());
+                let (name, rest) = line.split_at(split);
+                // Drop extras such as `[socks]` and environment markers
+                let rest = match rest.strip_prefix('[') {
+                    Some(rest) => rest.split_once(']').map_or("", |(_, rest)| rest),
+                    None => rest,
+                };
+                let version = rest.split(';').next().unwrap_or("").trim();
+                dependencies.push(dependency("pypi", name, version, "normal"));
+            }
+        }
+        _ => {}
+    }
+    (dependencies, declared)
+}
+
+/// Scope of a Cargo.toml dependency section, or `None` if it isn't one
+fn cargo_scope(section: &str) -> Option<&'static str> {
+    // Also covers `target.'cfg(unix)'.dependencies` and `workspace.dependencies`
+    let table = section.rsplit('.').next().unwrap_or(section);
+    match table {
+        "dependencies" => Some("normal"),
+        "dev-dependencies" => Some("dev"),
+        "build-dependencies" => Some("build"),
+        _ => None,
+    }
+}
+
+/// A string value from a TOML inline table like `{ version = "1", features = [...] }`
+fn inline_table_value(table: &str, key: &str) -> Option<String> {
+    let start = table
+        .find(&format!("{} ", key))
+        .or_else(|| table.find(&format!("{}=", key)))?;
+    let rest = table[start + key.len()..].trim_start().strip_prefix('=')?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    Some(rest.split('"').next()?.to_string())
+}
+
+/// SPDX identifier of a license file's text, from its telltale phrases
+fn detect_license(text: &str) -> Option<&'static str> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let has = |phrase: &str| text.contains(phrase);
+    let license = if has("Apache License") && has("Version 2.0") {
+        "Apache-2.0"
+    } else if has("GNU LESSER GENERAL PUBLIC LICENSE") {
+        if has("Version 3") {
+            "LGPL-3.0"
+        } else {
+            "LGPL-2.1"
+        }
+    } else if has("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        "AGPL-3.0"
+    } else if has("GNU GENERAL PUBLIC LICENSE") {
+        if has("Version 3") {
+            "GPL-3.0"
+        } else {
+            "GPL-2.0"
+        }
+    } else if has("Mozilla Public License") && has("2.0") {
+        "MPL-2.0"
+    } else if has("Permission is hereby granted, free of charge") {
+        "MIT"
+    } else if has("Permission to use, copy, modify, and/or distribute this software") {
+        "ISC"
+    } else if has("Redistribution and use in source and binary forms") {
+        if has("Neither the name") {
+            "BSD-3-Clause"
+        } else {
+            "BSD-2-Clause"
+        }
+    } else if has("This is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else {
+        return None;
+    };
+    Some(license)
+}
+
+/// One row per license and dependency
+fn to_csv(inventories: &[RepoInventory]) -> String {
+    let mut out = String::from("repo,head,record,ecosystem,name,version,scope,path\n");
+    for inventory in inventories {
+        let mut row = |fields: [&str; 6]| {
+            let cells: Vec<String> = [inventory.repo.as_str(), inventory.head.as_str()]
+                .into_iter()
+                .chain(fields)
+                .map(csv_field)
+                .collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        };
+        for license in &inventory.licenses {
+            row(["license", "", &license.license, "", "", &license.path]);
+        }
+        for dep in &inventory.dependencies {
+            row([
+                "dependency",
+                &dep.ecosystem,
+                &dep.name,
+                &dep.version,
+                &dep.scope,
+                &dep.path,
+            ]);
+        }
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deps(file: &str, content: &str) -> Vec<(String, String, String)> {
+        parse_manifest(file, content, file)
+            .0
+            .into_iter()
+            .map(|d| (d.name, d.version, d.scope))
+            .collect()
+    }
+
+    fn dep(name: &str, version: &str, scope: &str) -> (String, String, String) {
+        (name.to_string(), version.to_string(), scope.to_string())
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let cargo = r#"
+[package]
+name = "app"
+license = "MIT OR Apache-2.0"
+
+[dependencies]
+anyhow = "1"
+serde = { version = "1.0", features = ["derive"] }
+tokio.workspace = true
+
+[dev-dependencies.tempfile]
+version = "3"
+"#;
+        assert_eq!(
+            deps("Cargo.toml", cargo),
+            vec![
+                dep("anyhow", "1", "normal"),
+                dep("serde", "1.0", "normal"),
+                dep("tokio", "workspace", "normal"),
+                dep("tempfile", "3", "dev"),
+            ]
+        );
+        assert_eq!(
+            parse_manifest("Cargo.toml", cargo, "Cargo.toml")
+                .1
+                .as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+
+        let package = r#"{"license": "ISC", "dependencies": {"react": "^18.2.0"},
+            "devDependencies": {"vite": "5.0.0"}}"#;
+        assert_eq!(
+            deps("package.json", package),
+            vec![
+                dep("react", "^18.2.0", "normal"),
+                dep("vite", "5.0.0", "dev")
+            ]
+        );
+
+        let go = "module example.com/app\n\nrequire github.com/pkg/errors v0.9.1\n\
+                  require (\n\tgolang.org/x/sys v0.15.0 // indirect\n)\n";
+        assert_eq!(
+            deps("go.mod", go),
+            vec![
+                dep("github.com/pkg/errors", "v0.9.1", "normal"),
+                dep("golang.org/x/sys", "v0.15.0", "indirect"),
+            ]
+        );
+
+        let requirements = "# web\nflask>=2.0  # server\n-r dev.txt\nrequests[socks]==2.31.0\n";
+        assert_eq!(
+            deps("requirements.txt", requirements),
+            vec![
+                dep("flask", ">=2.0", "normal"),
+                dep("requests", "==2.31.0", "normal")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_license() {
+        assert_eq!(
+            detect_license("MIT License\n\nPermission is hereby granted, free of\ncharge, ..."),
+            Some("MIT")
+        );
+        assert_eq!(
+            detect_license("Apache License\n Version 2.0, January 2004"),
+            Some("Apache-2.0")
+        );
+        assert_eq!(detect_license("All rights reserved."), None);
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+}
@@ -6,7 +6,8 @@ use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
 use crate::git::path_exists;
-use std::path::PathBuf;
+use crate::git::sparse::{get_sparse_patterns, sparse_drifted};
+use std::path::{Path, PathBuf};
 
 /// Run the link command
 pub fn run_link(
@@ -55,13 +56,13 @@ fn show_link_status(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Re
 
                 let status = if source.exists() && dest.exists() {
                     valid_links += 1;
-                    "✓"
+                    Output::mark(true).to_string()
                 } else if !source.exists() {
                     broken_links += 1;
-                    "✗ (source missing)"
+                    format!("{} (source missing)", Output::mark(false))
                 } else {
                     broken_links += 1;
-                    "✗ (dest missing)"
+                    format!("{} (dest missing)", Output::mark(false))
                 };
 
                 println!("  [copy] {} -> {} {}", copyfile.src, copyfile.dest, status);
@@ -79,16 +80,16 @@ fn show_link_status(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Re
 
                 let status = if source.exists() && dest.exists() && dest.is_symlink() {
                     valid_links += 1;
-                    "✓"
+                    Output::mark(true).to_string()
                 } else if !source.exists() {
                     broken_links += 1;
-                    "✗ (source missing)"
+                    format!("{} (source missing)", Output::mark(false))
                 } else if !dest.exists() {
                     broken_links += 1;
-                    "✗ (link missing)"
+                    format!("{} (link missing)", Output::mark(false))
                 } else {
                     broken_links += 1;
-                    "✗ (not a symlink)"
+                    format!("{} (not a symlink)", Output::mark(false))
                 };
 
                 println!("  [link] {} -> {} {}", linkfile.src, linkfile.dest, status);
@@ -96,6 +97,8 @@ fn show_link_status(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Re
         }
     }
 
+    let sparse_drift = show_sparse_status(&repos, manifest);
+
     println!();
     if total_links == 0 {
         println!("No file links defined in manifest.");
@@ -109,10 +112,58 @@ fn show_link_status(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Re
         println!();
         println!("Run 'gr link --apply' to fix broken links.");
     }
+    if sparse_drift > 0 {
+        Output::warning(&format!(
+            "{} repo(s) have drifted sparse checkout; run 'gr sync' to reapply",
+            sparse_drift
+        ));
+    }
 
     Ok(())
 }
 
+/// Print sparse checkout state for repos with a `sparse:` list; returns the drift count
+fn show_sparse_status(repos: &[RepoInfo], manifest: &Manifest) -> usize {
+    let mut drifted = 0;
+
+    for repo in repos {
+        let Some(expected) = manifest
+            .repos
+            .get(&repo.name)
+            .and_then(|c| c.sparse.as_ref())
+        else {
+            continue;
+        };
+        if !path_exists(&repo.absolute_path) {
+            continue;
+        }
+
+        let current = get_sparse_patterns(&repo.absolute_path).ok().flatten();
+        let status = if sparse_drifted(current.as_deref(), expected) {
+            drifted += 1;
+            match current {
+                Some(current) => format!(
+                    "{} (checked out: {})",
+                    Output::mark(false),
+                    current.join(", ")
+                ),
+                None => format!("{} (sparse checkout disabled)", Output::mark(false)),
+            }
+        } else {
+            Output::mark(true).to_string()
+        };
+
+        println!(
+            "  [sparse] {}: {} {}",
+            repo.name,
+            expected.join(", "),
+            status
+        );
+    }
+
+    drifted
+}
+
 fn apply_links(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
     Output::header("Applying File Links");
     println!();
@@ -184,59 +235,14 @@ fn apply_links(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<
                     std::fs::create_dir_all(parent)?;
                 }
 
-                // Remove existing link/file if present
-                if dest.exists() || dest.is_symlink() {
-                    let _ = std::fs::remove_file(&dest);
-                }
-
-                #[cfg(unix)]
-                {
-                    match std::os::unix::fs::symlink(&source, &dest) {
-                        Ok(_) => {
-                            Output::success(&format!(
-                                "[link] {} -> {}",
-                                linkfile.src, linkfile.dest
-                            ));
-                            applied += 1;
-                        }
-                        Err(e) => {
-                            Output::error(&format!("Failed to create symlink: {}", e));
-                            errors += 1;
-                        }
+                match create_symlink(&source, &dest) {
+                    Ok(()) => {
+                        Output::success(&format!("[link] {} -> {}", linkfile.src, linkfile.dest));
+                        applied += 1;
                     }
-                }
-
-                #[cfg(windows)]
-                {
-                    // On Windows, use junction for directories, symlink for files
-                    if source.is_dir() {
-                        match std::os::windows::fs::symlink_dir(&source, &dest) {
-                            Ok(_) => {
-                                Output::success(&format!(
-                                    "[link] {} -> {}",
-                                    linkfile.src, linkfile.dest
-                                ));
-                                applied += 1;
-                            }
-                            Err(e) => {
-                                Output::error(&format!("Failed to create symlink: {}", e));
-                                errors += 1;
-                            }
-                        }
-                    } else {
-                        match std::os::windows::fs::symlink_file(&source, &dest) {
-                            Ok(_) => {
-                                Output::success(&format!(
-                                    "[link] {} -> {}",
-                                    linkfile.src, linkfile.dest
-                                ));
-                                applied += 1;
-                            }
-                            Err(e) => {
-                                Output::error(&format!("Failed to create symlink: {}", e));
-                                errors += 1;
-                            }
-                        }
+                    Err(e) => {
+                        Output::error(&format!("Failed to create symlink: {}", e));
+                        errors += 1;
                     }
                 }
             }
@@ -253,6 +259,29 @@ fn apply_links(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<
     Ok(())
 }
 
+/// Point `dest` at `source`, replacing whatever is at `dest`
+///
+/// On Windows, directories get a directory symlink and files a file symlink.
+pub fn create_symlink(source: &Path, dest: &Path) -> std::io::Result<()> {
+    if dest.exists() || dest.is_symlink() {
+        let _ = std::fs::remove_file(dest);
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(source, dest)
+    }
+
+    #[cfg(windows)]
+    {
+        if source.is_dir() {
+            std::os::windows::fs::symlink_dir(source, dest)
+        } else {
+            std::os::windows::fs::symlink_file(source, dest)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -276,6 +305,11 @@ mod tests {
                 copyfile: copyfiles,
                 linkfile: linkfiles,
                 platform: None,
+                sparse: None,
+                depends_on: None,
+                revision: None,
+                env: None,
+                workspace: false,
             },
         );
 
@@ -286,8 +320,17 @@ mod tests {
             settings: ManifestSettings {
                 pr_prefix: "[cross-repo]".to_string(),
                 merge_strategy: MergeStrategy::default(),
+                api_concurrency: 8,
+                pr_size_limit: 400,
+                pr_body_style: Default::default(),
+                notifications: Default::default(),
+                protected_branches: Vec::new(),
+                auto_optimize: false,
+                ..Default::default()
             },
             workspace: None,
+            profiles: HashMap::new(),
+            versions: HashMap::new(),
         }
     }
 
@@ -0,0 +1,94 @@
+//! Locks command implementation
+//!
+//! Shows and clears the per-repo lock files mutating commands hold under
+//! `.gitgrip/locks/`, for recovering from a process that was killed while
+//! holding them.
+
+use crate::cli::output::{Output, Table};
+use crate::core::lock::{list_locks, LockInfo};
+use chrono::Local;
+use std::path::Path;
+
+/// Run the locks list command
+pub fn run_locks_list(workspace_root: &Path) -> anyhow::Result<()> {
+    let locks = list_locks(workspace_root)?;
+    if locks.is_empty() {
+        Output::info("No repos are locked.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(vec!["Repo", "PID", "Since", "State", "Command"]);
+    for (path, info) in &locks {
+        match info {
+            Some(info) => {
+                let since = info
+                    .acquired
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                table.add_row(vec![
+                    &info.repo,
+                    &info.pid.to_string(),
+                    &since,
+                    state(info),
+                    &info.command,
+                ]);
+            }
+            None => table.add_row(vec![&file_stem(path), "-", "-", "unreadable", "-"]),
+        }
+    }
+    table.print();
+
+    Ok(())
+}
+
+/// Run the locks clear command
+///
+/// Removes locks whose process has exited (and unreadable lock files); with
+/// `force`, also those held by running processes.
+pub fn run_locks_clear(workspace_root: &Path, repos: &[String], force: bool) -> anyhow::Result<()> {
+    let mut cleared = 0;
+    let mut held = Vec::new();
+    for (path, info) in list_locks(workspace_root)? {
+        let repo = info
+            .as_ref()
+            .map(|info| info.repo.clone())
+            .unwrap_or_else(|| file_stem(&path));
+        if !repos.is_empty() && !repos.contains(&repo) {
+            continue;
+        }
+        if !force && info.as_ref().is_some_and(|info| !info.is_stale()) {
+            held.push(repo);
+            continue;
+        }
+        std::fs::remove_file(&path)?;
+        Output::success(&format!("{}: lock cleared", repo));
+        cleared += 1;
+    }
+
+    if !held.is_empty() {
+        Output::warning(&format!(
+            "Still held by running processes: {} (use --force to clear anyway)",
+            held.join(", ")
+        ));
+    }
+    if cleared == 0 && held.is_empty() {
+        Output::info("No locks to clear.");
+    }
+
+    Ok(())
+}
+
+fn state(info: &LockInfo) -> &'static str {
+    if info.is_stale() {
+        "stale"
+    } else {
+        "running"
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,210 @@
+//! Manifest command implementation
+//!
+//! `gr manifest lint` runs the extended manifest checks and prints each
+//! finding with its line and column in manifest.yaml. `gr manifest diff`
+//! summarizes what changed between two revisions of the manifest repo.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::manifest_diff::{diff_manifests, ManifestChange};
+use crate::core::manifest_lint::{lint_manifest, Severity};
+use crate::util::profile::ProfiledCommand;
+use std::path::Path;
+use std::process::Command;
+
+/// Run the manifest lint command
+///
+/// Works on manifests that fail to load, so it can explain why. With
+/// `network`, repo URLs are checked for reachability too. Fails if any
+/// errors were found; warnings alone don't fail.
+pub fn run_manifest_lint(
+    workspace_root: &Path,
+    manifest_path: &Path,
+    network: bool,
+) -> anyhow::Result<()> {
+    let source = std::fs::read_to_string(manifest_path)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(workspace_root);
+
+    let spinner = network.then(|| Output::spinner("Checking repo URLs..."));
+    let diagnostics = lint_manifest(&source, manifest_dir, workspace_root, network);
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
+
+    let file = manifest_path
+        .strip_prefix(workspace_root)
+        .unwrap_or(manifest_path)
+        .display()
+        .to_string();
+    for diagnostic in &diagnostics {
+        let line = diagnostic.render(&file);
+        match diagnostic.severity {
+            Severity::Error => Output::error(&line),
+            Severity::Warning => Output::warning(&line),
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if !diagnostics.is_empty() {
+        println!();
+    }
+    if errors > 0 {
+        anyhow::bail!(
+            "Manifest has {} error(s) and {} warning(s)",
+            errors,
+            warnings
+        );
+    }
+    if warnings > 0 {
+        Output::warning(&format!("Manifest is valid, with {} warning(s)", warnings));
+    } else {
+        Output::success("Manifest is valid.");
+    }
+
+    Ok(())
+}
+
+/// Run the manifest diff command
+///
+/// Compares `from` (default `HEAD`) with `to`, or with the manifest file on
+/// disk when `to` is `None`, so uncommitted edits show up.
+pub fn run_manifest_diff(
+    manifest_path: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> anyhow::Result<()> {
+    let from = from.unwrap_or("HEAD");
+    let old = read_manifest_at(manifest_path, Some(from))?;
+    let new = read_manifest_at(manifest_path, to)?;
+    let changes = diff_manifests(&old, &new);
+
+    Output::header(&format!(
+        "Manifest changes ({} -> {})",
+        from,
+        to.unwrap_or("working copy")
+    ));
+    println!();
+
+    if changes.is_empty() {
+        println!("No changes to repos or scripts.");
+        return Ok(());
+    }
+    for change in &changes {
+        match change.symbol() {
+            '+' => Output::diff_add(&change.to_string()),
+            '-' => Output::diff_del(&change.to_string()),
+            symbol => println!("{} {}", symbol, change),
+        }
+    }
+    println!();
+    println!("{} change(s)", changes.len());
+
+    Ok(())
+}
+
+/// Changes from manifest repo revision `rev` to the manifest file on disk
+pub(crate) fn manifest_changes_since(
+    manifest_path: &Path,
+    rev: &str,
+) -> anyhow::Result<Vec<ManifestChange>> {
+    let old = read_manifest_at(manifest_path, Some(rev))?;
+    let new = read_manifest_at(manifest_path, None)?;
+    Ok(diff_manifests(&old, &new))
+}
+
+/// The commit checked out in the manifest repo holding `manifest_path`
+pub(crate) fn manifest_head(manifest_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(manifest_path.parent()?)
+        .timed_output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse the manifest at `rev` of the manifest repo, or on disk if `None`
+///
+/// Old revisions are parsed without validation, since the rules may have
+/// changed since they were committed.
+fn read_manifest_at(manifest_path: &Path, rev: Option<&str>) -> anyhow::Result<Manifest> {
+    let Some(rev) = rev else {
+        let source = std::fs::read_to_string(manifest_path)?;
+        return Ok(serde_yaml::from_str(&source)?);
+    };
+
+    let file_name = manifest_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "manifest.yaml".to_string());
+    let dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .args(["show", &format!("{}:./{}", rev, file_name)])
+        .current_dir(dir)
+        .timed_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Cannot read the manifest at '{}': {}",
+            rev,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    serde_yaml::from_slice(&output.stdout)
+        .map_err(|e| anyhow::anyhow!("Failed to parse the manifest at '{}': {}", rev, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_changes_since() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        let manifest_path = dir.join("manifest.yaml");
+        std::fs::write(
+            &manifest_path,
+            "repos:\n  app:\n    url: git@github.com:org/app.git\n    path: app\n",
+        )
+        .unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Add app"]);
+        let head = manifest_head(&manifest_path).unwrap();
+
+        // Reformatting alone is not a change
+        std::fs::write(
+            &manifest_path,
+            "repos:\n  app: { url: \"git@github.com:org/app.git\", path: app }\n",
+        )
+        .unwrap();
+        assert!(manifest_changes_since(&manifest_path, &head)
+            .unwrap()
+            .is_empty());
+
+        std::fs::write(
+            &manifest_path,
+            "repos:\n  app:\n    url: git@github.com:org/app.git\n    path: apps/app\n",
+        )
+        .unwrap();
+        let changes = manifest_changes_since(&manifest_path, &head).unwrap();
+        assert_eq!(
+            changes,
+            vec![ManifestChange::PathChanged {
+                repo: "app".to_string(),
+                old: "app".to_string(),
+                new: "apps/app".to_string(),
+            }]
+        );
+        assert!(manifest_changes_since(&manifest_path, "no-such-rev").is_err());
+    }
+}
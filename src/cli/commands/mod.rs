@@ -3,20 +3,60 @@
 //! Each command is implemented in its own module.
 
 pub mod add;
+pub mod adopt;
+pub mod amend;
+pub mod archive;
+pub mod audit;
+pub mod backport;
 pub mod bench;
+pub mod bisect;
 pub mod branch;
+pub mod branch_audit;
+pub mod campaign;
+pub mod changelog;
 pub mod checkout;
+pub mod cherry_pick;
+pub mod ci;
 pub mod commit;
+pub mod deploy;
+pub mod dev;
 pub mod diff;
+pub mod doctor;
+pub mod du;
 pub mod env;
+pub mod fetch;
 pub mod forall;
+pub mod foreach_changed;
+pub mod gc;
+pub mod graph;
+pub mod grep;
 pub mod init;
+pub mod inventory;
 pub mod link;
+pub mod locks;
+pub mod manifest;
+pub mod new;
+pub mod optimize;
+pub mod owners;
 pub mod pr;
+pub mod prune;
 pub mod push;
 pub mod rebase;
 pub mod repo;
+pub mod report;
+pub mod reset;
+pub mod resolve;
+pub mod revert;
 pub mod run;
+pub mod sed;
+pub mod serve;
+pub mod standup;
+pub mod stats;
 pub mod status;
+pub mod switch_url;
 pub mod sync;
 pub mod tree;
+pub mod undo;
+pub mod verify;
+pub mod version;
+pub mod workspace;
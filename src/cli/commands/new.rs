@@ -0,0 +1,250 @@
+//! New command implementation
+//!
+//! Scaffolds a new repo from a template, creates it on the hosting platform,
+//! pushes the initial commit, protects the default branch, and adds it to
+//! the manifest.
+
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::cli::commands::repo::append_repo_to_manifest;
+use crate::cli::output::Output;
+use crate::core::manifest::{Manifest, PlatformType};
+use crate::core::repo::RepoInfo;
+use crate::git::remote::set_upstream_branch;
+use crate::git::{open_repo, run_git};
+use crate::platform::get_platform_adapter;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Settings for `gr new`
+#[derive(Debug, Clone)]
+pub struct NewOptions {
+    /// Template: a local directory or any URL `git clone` accepts
+    pub template: String,
+    /// Owner/namespace for the remote repo (default: most common in manifest)
+    pub owner: Option<String>,
+    /// Local path (default: the repo name)
+    pub path: Option<String>,
+    /// Default branch
+    pub branch: String,
+    /// Repo description
+    pub description: Option<String>,
+    /// Create a public repo
+    pub public: bool,
+    /// Skip default branch protection
+    pub no_protect: bool,
+}
+
+/// Run the new command
+pub async fn run_new(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    name: &str,
+    options: &NewOptions,
+) -> anyhow::Result<()> {
+    Output::header(&format!("Creating {} from {}", name, options.template));
+    println!();
+
+    if manifest.repos.contains_key(name) {
+        anyhow::bail!("Repository '{}' already exists in manifest", name);
+    }
+
+    let path = options.path.clone().unwrap_or_else(|| name.to_string());
+    let dest = workspace_root.join(&path);
+    if dest.exists() {
+        anyhow::bail!("{} already exists", dest.display());
+    }
+
+    let (owner, platform_type) = match (&options.owner, infer_owner(workspace_root, manifest)) {
+        (Some(owner), inferred) => (
+            owner.clone(),
+            inferred.map(|(_, p)| p).unwrap_or(PlatformType::GitHub),
+        ),
+        (None, Some(inferred)) => inferred,
+        (None, None) => anyhow::bail!("Could not infer an owner from the manifest; pass --owner"),
+    };
+
+    // Scaffold locally first so a bad template never leaves a remote behind
+    let spinner = Output::spinner("Copying template...");
+    if let Err(e) = scaffold(&options.template, &dest, &options.branch) {
+        let _ = std::fs::remove_dir_all(&dest);
+        spinner.finish_with_message(format!("Template failed - {}", e));
+        return Err(e);
+    }
+    spinner.finish_with_message("Created initial commit");
+
+    let platform = get_platform_adapter(platform_type, None);
+    let spinner = Output::spinner(&format!("Creating {}/{}...", owner, name));
+    let url = match platform
+        .create_repository(
+            &owner,
+            name,
+            options.description.as_deref(),
+            !options.public,
+        )
+        .await
+    {
+        Ok(url) => {
+            spinner.finish_with_message(format!("Created {}", url));
+            url
+        }
+        Err(e) => {
+            spinner.finish_with_message("Failed to create remote repository");
+            anyhow::bail!(
+                "{}\nThe scaffolded repo is at {}; remove it before retrying.",
+                e,
+                dest.display()
+            );
+        }
+    };
+
+    run_git(&dest, &["remote", "add", "origin", &url])?;
+    let git_repo = open_repo(&dest)?;
+
+    // The platform may have initialized the repo with a README commit
+    let spinner = Output::spinner("Pushing initial commit...");
+    push_audited(
+        workspace_root,
+        name,
+        &git_repo,
+        &options.branch,
+        PushMode::Force,
+    )?;
+    set_upstream_branch(&git_repo, "origin")?;
+    spinner.finish_with_message(format!("Pushed {}", options.branch));
+
+    if !options.no_protect {
+        match platform.protect_branch(&owner, name, &options.branch).await {
+            Ok(()) => Output::success(&format!("Protected {}", options.branch)),
+            Err(e) => Output::warning(&format!("Branch protection not applied: {}", e)),
+        }
+    }
+
+    append_repo_to_manifest(workspace_root, name, &url, &path, &options.branch)?;
+
+    println!();
+    Output::success(&format!("Added '{}' to the manifest at {}", name, path));
+    Ok(())
+}
+
+/// Copy the template into `dest` and commit it as a fresh history
+fn scaffold(template: &str, dest: &Path, branch: &str) -> anyhow::Result<()> {
+    copy_template(template, dest)?;
+
+    run_git(dest, &["init", "--quiet", "-b", branch])?;
+    run_git(dest, &["add", "-A"])?;
+    run_git(
+        dest,
+        &[
+            "commit",
+            "--quiet",
+            "-m",
+            &format!("Initial commit from template {}", template),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Copy a template's files (without its git history) into `dest`
+fn copy_template(template: &str, dest: &Path) -> anyhow::Result<()> {
+    let local = Path::new(template);
+    if local.is_dir() && !local.join(".git").exists() {
+        copy_dir(local, dest)?;
+    } else {
+        let dest_str = dest.to_string_lossy();
+        run_git(
+            dest.parent().unwrap_or(dest),
+            &["clone", "--depth", "1", "--quiet", template, &dest_str],
+        )?;
+        std::fs::remove_dir_all(dest.join(".git"))?;
+    }
+    Ok(())
+}
+
+fn copy_dir(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dest)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// The owner and platform shared by most repos in the manifest
+fn infer_owner(workspace_root: &PathBuf, manifest: &Manifest) -> Option<(String, PlatformType)> {
+    let mut counts: HashMap<(String, PlatformType), usize> = HashMap::new();
+    for repo in manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+    {
+        *counts.entry((repo.owner, repo.platform_type)).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.0.cmp(&a.0)))
+        .map(|(key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::init_repo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_infer_owner_picks_most_common() {
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+  web:
+    url: git@github.com:acme/web.git
+    path: web
+  fork:
+    url: git@github.com:someone/fork.git
+    path: fork
+"#,
+        )
+        .unwrap();
+
+        let (owner, platform) = infer_owner(&PathBuf::from("/ws"), &manifest).unwrap();
+        assert_eq!(owner, "acme");
+        assert_eq!(platform, PlatformType::GitHub);
+    }
+
+    #[test]
+    fn test_copy_template_from_directory() {
+        let temp = TempDir::new().unwrap();
+        let template = temp.path().join("template");
+        std::fs::create_dir_all(template.join("src")).unwrap();
+        std::fs::write(template.join("src/lib.rs"), "// lib").unwrap();
+
+        let dest = temp.path().join("svc");
+        copy_template(&template.to_string_lossy(), &dest).unwrap();
+        assert!(dest.join("src/lib.rs").exists());
+    }
+
+    #[test]
+    fn test_copy_template_from_git_repo_drops_history() {
+        let temp = TempDir::new().unwrap();
+        let template = temp.path().join("template");
+        init_repo(&template);
+        std::fs::write(template.join("README.md"), "# template").unwrap();
+        run_git(&template, &["add", "."]).unwrap();
+        run_git(&template, &["commit", "-m", "Initial commit"]).unwrap();
+
+        let dest = temp.path().join("svc");
+        let url = format!("file://{}", template.display());
+        copy_template(&url, &dest).unwrap();
+        assert!(dest.join("README.md").exists());
+        assert!(!dest.join(".git").exists());
+    }
+}
@@ -0,0 +1,223 @@
+//! Optimize command implementation
+//!
+//! Turns on git's own speedups in every repo: a commit-graph with changed-path
+//! Bloom filters for `log` and ahead/behind counts, the untracked cache for
+//! `status`, the built-in fsmonitor where git supports it, and
+//! `feature.manyFiles` in large repos. `status` and `log` are timed before and
+//! after so the gain is visible.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::incremental::INCREMENTAL_MIN_FILES;
+use crate::git::{open_repo, run_git, GitError};
+use crate::util::profile::ProfiledCommand;
+use crate::util::{max_workers, parallel_map};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Git release with the built-in fsmonitor daemon (macOS and Windows only)
+const FSMONITOR_MIN_VERSION: (u32, u32) = (2, 36);
+
+/// Settings for `gr optimize`
+#[derive(Debug, Clone, Default)]
+pub struct OptimizeOptions {
+    /// Skip timing `status` and `log` before and after
+    pub no_measure: bool,
+}
+
+/// Timings of the commands optimizing speeds up
+#[derive(Debug, Clone, Copy)]
+struct Timings {
+    status: Duration,
+    log: Duration,
+}
+
+/// Run the optimize command
+pub fn run_optimize(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &OptimizeOptions,
+) -> anyhow::Result<()> {
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| repo.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let fsmonitor = fsmonitor_supported();
+    let spinner = Output::spinner(&format!("Optimizing {} repo(s)...", repos.len()));
+    // Timing runs in parallel would measure each other; apply settings in
+    // parallel only when not measuring
+    let workers = if options.no_measure { max_workers() } else { 1 };
+    let results = parallel_map(&repos, workers, |repo| {
+        let before = (!options.no_measure).then(|| measure(&repo.absolute_path));
+        let applied = optimize_repo(&repo.absolute_path, fsmonitor);
+        let after = (!options.no_measure).then(|| measure(&repo.absolute_path));
+        (applied, before, after)
+    });
+    spinner.finish_and_clear();
+
+    let mut headers = vec!["Repo", "Enabled"];
+    if !options.no_measure {
+        headers.extend(["status", "log"]);
+    }
+    let mut table = Table::new(headers);
+    let mut failed = 0;
+    for (repo, (applied, before, after)) in repos.iter().zip(&results) {
+        let enabled = match applied {
+            Ok(steps) => steps.join(", "),
+            Err(e) => {
+                Output::warning(&format!("{}: {}", repo.name, e));
+                failed += 1;
+                "failed".to_string()
+            }
+        };
+        let mut row = vec![repo.name.clone(), enabled];
+        if let (Some(before), Some(after)) = (before, after) {
+            row.push(speedup(before.status, after.status));
+            row.push(speedup(before.log, after.log));
+        }
+        table.add_row(row.iter().map(String::as_str).collect());
+    }
+    table.print();
+
+    println!();
+    if !fsmonitor {
+        Output::info("fsmonitor skipped: it needs git 2.36+ on macOS or Windows");
+    }
+    if failed > 0 {
+        anyhow::bail!("Could not optimize {} repo(s)", failed);
+    }
+    Output::success(&format!("Optimized {} repo(s).", repos.len()));
+    Ok(())
+}
+
+/// Enable the speedups in one repo, returning what was turned on
+///
+/// Safe to run repeatedly: the commit-graph is rewritten incrementally and
+/// the config values are simply set again.
+pub fn optimize_repo(path: &Path, fsmonitor: bool) -> Result<Vec<&'static str>, GitError> {
+    let mut applied = Vec::new();
+
+    run_git(
+        path,
+        &[
+            "commit-graph",
+            "write",
+            "--reachable",
+            "--changed-paths",
+            "--split",
+        ],
+    )?;
+    applied.push("commit-graph");
+
+    run_git(path, &["config", "core.untrackedCache", "true"])?;
+    applied.push("untracked cache");
+
+    if fsmonitor {
+        run_git(path, &["config", "core.fsmonitor", "true"])?;
+        applied.push("fsmonitor");
+    }
+
+    let tracked = open_repo(path)
+        .and_then(|repo| Ok(repo.index()?.len()))
+        .unwrap_or(0);
+    if tracked >= INCREMENTAL_MIN_FILES {
+        run_git(path, &["config", "feature.manyFiles", "true"])?;
+        applied.push("manyFiles");
+    }
+
+    Ok(applied)
+}
+
+/// Whether this git has a built-in fsmonitor on this platform
+pub fn fsmonitor_supported() -> bool {
+    if !cfg!(any(target_os = "macos", target_os = "windows")) {
+        return false;
+    }
+    let Ok(output) = Command::new("git").arg("--version").timed_output() else {
+        return false;
+    };
+    parse_git_version(&String::from_utf8_lossy(&output.stdout))
+        .is_some_and(|version| version >= FSMONITOR_MIN_VERSION)
+}
+
+/// Major and minor version from `git --version` output
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.split_whitespace().nth(2)?;
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??))
+}
+
+/// Best of two runs each of `git status` and `git log`, so the first run's
+/// cold cache doesn't count
+fn measure(path: &Path) -> Timings {
+    let time = |args: &[&str]| {
+        (0..2)
+            .map(|_| {
+                let start = Instant::now();
+                let _ = Command::new("git")
+                    .args(args)
+                    .current_dir(path)
+                    .timed_output();
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or_default()
+    };
+    Timings {
+        status: time(&["status", "--porcelain"]),
+        log: time(&["log", "--oneline", "-n", "1000", "--", "."]),
+    }
+}
+
+/// `120ms -> 40ms (3.0x)`
+fn speedup(before: Duration, after: Duration) -> String {
+    let ratio = before.as_secs_f64() / after.as_secs_f64().max(0.001);
+    format!(
+        "{}ms -> {}ms ({:.1}x)",
+        before.as_millis(),
+        after.as_millis(),
+        ratio
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(parse_git_version("git version 2.39.2\n"), Some((2, 39)));
+        assert_eq!(
+            parse_git_version("git version 2.45.1.windows.1"),
+            Some((2, 45))
+        );
+        assert_eq!(parse_git_version("not git"), None);
+    }
+
+    #[test]
+    fn test_optimize_repo() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.name", "Test User"],
+            &["config", "user.email", "test@example.com"],
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        ] {
+            run_git(path, args).unwrap();
+        }
+
+        let applied = optimize_repo(path, false).unwrap();
+        assert_eq!(applied, vec!["commit-graph", "untracked cache"]);
+        assert!(path.join(".git/objects/info/commit-graphs").is_dir());
+        // A second run only updates what's there
+        assert_eq!(optimize_repo(path, false).unwrap(), applied);
+    }
+}
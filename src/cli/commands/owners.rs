@@ -0,0 +1,156 @@
+//! Owners command implementation
+//!
+//! Shows which CODEOWNERS own a path, or the changes on a branch, per repo.
+
+use crate::cli::output::Output;
+use crate::core::codeowners::CodeOwners;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::branch::{branch_exists, get_branch_changed_files};
+use crate::git::{get_current_branch, open_repo, path_exists};
+use std::path::{Path, PathBuf};
+
+/// Run the owners command
+///
+/// `target` may be a path inside a repo or a branch name; without a target,
+/// each repo's current branch is used.
+pub fn run_owners(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    target: Option<&str>,
+) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    if let Some(target) = target {
+        if let Some((repo, rel_path)) = find_repo_for_path(&repos, workspace_root, target) {
+            return show_path_owners(repo, &rel_path);
+        }
+    }
+
+    Output::header(&match target {
+        Some(branch) => format!("Owners of changes on {}", Output::branch_name(branch)),
+        None => "Owners of changes on current branches".to_string(),
+    });
+    println!();
+
+    let mut shown = 0;
+    for repo in &repos {
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+
+        let branch = match target {
+            Some(branch) if branch_exists(&git_repo, branch) => branch.to_string(),
+            Some(_) => continue,
+            None => match get_current_branch(&git_repo) {
+                Ok(b) if b != repo.default_branch => b,
+                _ => continue,
+            },
+        };
+
+        let changed = get_branch_changed_files(&git_repo, &repo.default_branch, &branch)?;
+        if changed.is_empty() {
+            continue;
+        }
+
+        println!("{}", Output::repo_name(&repo.name));
+        match CodeOwners::load(&repo.absolute_path) {
+            Some(codeowners) => {
+                for (owners, files) in codeowners.group_by_owners(&changed) {
+                    let label = if owners.is_empty() {
+                        "(no owner)".to_string()
+                    } else {
+                        owners.join(" ")
+                    };
+                    println!("  {} - {} file(s)", label, files.len());
+                    for file in files {
+                        println!("      {}", file);
+                    }
+                }
+            }
+            None => println!("  no CODEOWNERS file ({} changed file(s))", changed.len()),
+        }
+        println!();
+        shown += 1;
+    }
+
+    if shown == 0 {
+        Output::info("No changes found.");
+    }
+
+    Ok(())
+}
+
+fn show_path_owners(repo: &RepoInfo, rel_path: &str) -> anyhow::Result<()> {
+    let display = if rel_path.is_empty() { "." } else { rel_path };
+    Output::header(&format!(
+        "Owners of {} in {}",
+        display,
+        Output::repo_name(&repo.name)
+    ));
+    println!();
+
+    match CodeOwners::load(&repo.absolute_path) {
+        Some(codeowners) => match codeowners.owners_for(rel_path) {
+            [] => println!("  (no owner)"),
+            owners => println!("  {}", owners.join(" ")),
+        },
+        None => Output::info(&format!("{} has no CODEOWNERS file", repo.name)),
+    }
+
+    Ok(())
+}
+
+/// Find the repo containing `target` (relative to cwd or the workspace root)
+fn find_repo_for_path<'a>(
+    repos: &'a [RepoInfo],
+    workspace_root: &Path,
+    target: &str,
+) -> Option<(&'a RepoInfo, String)> {
+    let candidates = [
+        std::env::current_dir().ok().map(|cwd| cwd.join(target)),
+        Some(workspace_root.join(target)),
+    ];
+
+    let path = candidates
+        .into_iter()
+        .flatten()
+        .find(|p| p.exists())?
+        .canonicalize()
+        .ok()?;
+
+    // Prefer the most deeply nested repo when repo paths overlap
+    repos
+        .iter()
+        .filter_map(|repo| {
+            let root = repo.absolute_path.canonicalize().ok()?;
+            let rel = path.strip_prefix(&root).ok()?;
+            Some((repo, root.components().count(), relative_path(rel)))
+        })
+        .max_by_key(|(_, depth, _)| *depth)
+        .map(|(repo, _, rel)| (repo, rel))
+}
+
+fn relative_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(Path::new("services/api")), "services/api");
+        assert_eq!(relative_path(Path::new("")), "");
+    }
+}
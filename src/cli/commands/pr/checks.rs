@@ -1,18 +1,61 @@
 //! PR checks command implementation
+//!
+//! Lists each PR's check results and, with `--logs`, prints the tail of CI
+//! job logs so failures can be read without opening each repo's CI page.
+//! `--rerun-failed` restarts failed CI jobs and `--watch` waits for pending
+//! checks to finish before reporting.
 
+use super::feature_branches;
 use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
-use crate::git::{get_current_branch, open_repo, path_exists};
-use crate::platform::{detect_platform, get_platform_adapter, CheckState};
+use crate::platform::traits::PlatformError;
+use crate::platform::{detect_platform, get_platform_adapter, CheckState, CiJob, HostingPlatform};
+use crate::util::concurrent_map;
+use crate::util::redact::redact;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// How often `--watch` polls check status
+const WATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Settings for `gr pr checks`
+#[derive(Debug, Clone, Default)]
+pub struct ChecksOptions {
+    /// Output JSON
+    pub json: bool,
+    /// Print job logs: of jobs with this name, or of failing jobs when empty
+    pub logs: Option<String>,
+    /// Only show failing checks (and fetch logs of failing jobs)
+    pub failed_only: bool,
+    /// Lines printed from the end of each log
+    pub tail: usize,
+    /// Re-run failed CI jobs before reporting
+    pub rerun_failed: bool,
+    /// Wait until no checks are pending before reporting
+    pub watch: bool,
+}
+
+/// The end of one CI job's log
+#[derive(Debug, serde::Serialize)]
+struct JobLog {
+    name: String,
+    state: CheckState,
+    url: Option<String>,
+    /// Last lines of the log
+    lines: Vec<String>,
+    /// Why the log couldn't be fetched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
 /// Run the PR checks command
 pub async fn run_pr_checks(
     workspace_root: &PathBuf,
     manifest: &Manifest,
-    json_output: bool,
+    options: &ChecksOptions,
 ) -> anyhow::Result<()> {
+    let json_output = options.json;
     if !json_output {
         Output::header("CI/CD Check Status");
         println!();
@@ -36,6 +79,10 @@ pub async fn run_pr_checks(
         pr_number: Option<u64>,
         overall_state: String,
         checks: Vec<CheckInfo>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        logs: Vec<JobLog>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        logs_error: Option<String>,
     }
 
     let mut all_checks: Vec<RepoChecks> = Vec::new();
@@ -43,47 +90,50 @@ pub async fn run_pr_checks(
     let mut total_failed = 0;
     let mut total_pending = 0;
 
-    for repo in &repos {
-        if !path_exists(&repo.absolute_path) {
-            continue;
-        }
-
-        let git_repo = match open_repo(&repo.absolute_path) {
-            Ok(r) => r,
-            Err(_) => continue,
-        };
-
-        let branch = match get_current_branch(&git_repo) {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-
-        // Skip if on default branch
-        if branch == repo.default_branch {
-            continue;
+    let branches = feature_branches(repos).await;
+    let concurrency = manifest.settings.api_concurrency;
+    let mut restarted = 0;
+    if options.rerun_failed {
+        restarted = rerun_failed(&branches, concurrency, json_output).await;
+    }
+    if options.watch {
+        // Give restarted runs time to show up as pending
+        if restarted > 0 {
+            tokio::time::sleep(WATCH_INTERVAL).await;
         }
+        wait_for_checks(&branches, concurrency).await;
+    }
 
-        let platform_type = detect_platform(&repo.url);
-        let platform = get_platform_adapter(platform_type, None);
+    let lookups = concurrent_map(
+        &branches,
+        manifest.settings.api_concurrency,
+        |(repo, branch)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            // The PR number is optional, for display
+            let (pr, checks, logs) = tokio::join!(
+                platform.find_pr_by_branch(&repo.owner, &repo.repo, branch),
+                platform.get_status_checks(&repo.owner, &repo.repo, branch),
+                job_logs(platform.as_ref(), repo, branch, options),
+            );
+            (pr.ok().flatten().map(|pr| pr.number), checks, logs)
+        },
+    )
+    .await;
 
-        // Find PR number (optional, for display)
-        let pr_number = match platform
-            .find_pr_by_branch(&repo.owner, &repo.repo, &branch)
-            .await
-        {
-            Ok(Some(pr)) => Some(pr.number),
-            _ => None,
+    for ((repo, _), (pr_number, checks, logs)) in branches.iter().zip(lookups) {
+        let (logs, logs_error) = match logs {
+            Ok(logs) => (logs, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
         };
-
-        // Get status checks for the branch
-        match platform
-            .get_status_checks(&repo.owner, &repo.repo, &branch)
-            .await
-        {
+        match checks {
             Ok(status_result) => {
                 let check_infos: Vec<CheckInfo> = status_result
                     .statuses
                     .iter()
+                    .filter(|s| {
+                        !options.failed_only
+                            || matches!(s.state.to_lowercase().as_str(), "failure" | "error")
+                    })
                     .map(|s| {
                         let state = s.state.to_lowercase();
                         match state.as_str() {
@@ -101,9 +151,9 @@ pub async fn run_pr_checks(
                 if !json_output {
                     // Print status with indicator
                     let overall = match status_result.state {
-                        CheckState::Success => "✓",
-                        CheckState::Failure => "✗",
-                        CheckState::Pending => "●",
+                        CheckState::Success => Output::mark(true),
+                        CheckState::Failure => Output::mark(false),
+                        CheckState::Pending => Output::pending_mark(),
                     };
 
                     let pr_str = pr_number.map(|n| format!(" #{}", n)).unwrap_or_default();
@@ -111,13 +161,17 @@ pub async fn run_pr_checks(
 
                     for check in &check_infos {
                         let indicator = match check.state.as_str() {
-                            "success" => "  ✓",
-                            "failure" | "error" => "  ✗",
-                            _ => "  ●",
+                            "success" => Output::mark(true),
+                            "failure" | "error" => Output::mark(false),
+                            _ => Output::pending_mark(),
                         };
-                        println!("  {} {} {}", indicator, check.context, check.state);
+                        println!("    {} {} {}", indicator, check.context, check.state);
                     }
                     println!();
+                    if let Some(e) = &logs_error {
+                        Output::warning(&format!("{}: could not list CI jobs: {}", repo.name, e));
+                    }
+                    print_logs(&repo.name, &logs);
                 }
 
                 all_checks.push(RepoChecks {
@@ -125,6 +179,8 @@ pub async fn run_pr_checks(
                     pr_number,
                     overall_state: format!("{:?}", status_result.state).to_lowercase(),
                     checks: check_infos,
+                    logs,
+                    logs_error,
                 });
             }
             Err(e) => {
@@ -136,7 +192,7 @@ pub async fn run_pr_checks(
     }
 
     if json_output {
-        println!("{}", serde_json::to_string_pretty(&all_checks)?);
+        Output::json(&all_checks)?;
         return Ok(());
     }
 
@@ -158,3 +214,175 @@ pub async fn run_pr_checks(
 
     Ok(())
 }
+
+/// Re-run failed CI jobs in every repo, returning how many runs restarted
+async fn rerun_failed(branches: &[(RepoInfo, String)], concurrency: usize, quiet: bool) -> usize {
+    let results = concurrent_map(branches, concurrency, |(repo, branch)| async move {
+        let platform = get_platform_adapter(detect_platform(&repo.url), None);
+        platform
+            .rerun_failed_ci_jobs(&repo.owner, &repo.repo, branch)
+            .await
+    })
+    .await;
+
+    let mut restarted = 0;
+    for ((repo, _), result) in branches.iter().zip(results) {
+        match result {
+            Ok(0) => {}
+            Ok(runs) => {
+                restarted += runs;
+                if !quiet {
+                    Output::success(&format!("{}: re-running {} failed run(s)", repo.name, runs));
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    Output::warning(&format!("{}: could not re-run: {}", repo.name, e));
+                }
+            }
+        }
+    }
+    if !quiet {
+        if restarted == 0 {
+            Output::info("No failed runs to re-run.");
+        }
+        println!();
+    }
+    restarted
+}
+
+/// Poll until no repo's checks are pending
+///
+/// Repos whose status can't be read don't hold up the wait.
+async fn wait_for_checks(branches: &[(RepoInfo, String)], concurrency: usize) {
+    let spinner = Output::spinner("Waiting for checks...");
+    loop {
+        let states = concurrent_map(branches, concurrency, |(repo, branch)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            platform
+                .get_status_checks(&repo.owner, &repo.repo, branch)
+                .await
+                .map(|status| status.state)
+        })
+        .await;
+        let pending = states
+            .iter()
+            .filter(|state| matches!(state, Ok(CheckState::Pending)))
+            .count();
+        if pending == 0 {
+            break;
+        }
+        spinner.set_message(format!("Waiting for checks in {} repo(s)...", pending));
+        tokio::time::sleep(WATCH_INTERVAL).await;
+    }
+    spinner.finish_and_clear();
+}
+
+/// Fetch the logs `options.logs` asks for, if any
+async fn job_logs(
+    platform: &dyn HostingPlatform,
+    repo: &RepoInfo,
+    branch: &str,
+    options: &ChecksOptions,
+) -> Result<Vec<JobLog>, PlatformError> {
+    let Some(wanted) = options.logs.as_deref() else {
+        return Ok(Vec::new());
+    };
+    let jobs = platform
+        .list_ci_jobs(&repo.owner, &repo.repo, branch)
+        .await?;
+
+    let selected: Vec<CiJob> = jobs
+        .into_iter()
+        .filter(|job| {
+            let failed = job.state == CheckState::Failure;
+            if wanted.is_empty() {
+                failed
+            } else {
+                job.name == wanted && (failed || !options.failed_only)
+            }
+        })
+        .collect();
+    let logs = futures::future::join_all(
+        selected
+            .iter()
+            .map(|job| platform.get_ci_job_log(&repo.owner, &repo.repo, job.id)),
+    )
+    .await;
+
+    Ok(selected
+        .into_iter()
+        .zip(logs)
+        .map(|(job, log)| {
+            let (lines, error) = match log {
+                Ok(log) => (log_tail(&log, options.tail), None),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            JobLog {
+                name: job.name,
+                state: job.state,
+                url: job.url,
+                lines,
+                error,
+            }
+        })
+        .collect())
+}
+
+fn print_logs(repo_name: &str, logs: &[JobLog]) {
+    for log in logs {
+        let url = log
+            .url
+            .as_deref()
+            .map(|url| format!(" ({})", url))
+            .unwrap_or_default();
+        Output::header(&format!("{}: {} log{}", repo_name, log.name, url));
+        if let Some(e) = &log.error {
+            Output::warning(&format!("Could not fetch log: {}", e));
+        }
+        for line in &log.lines {
+            println!("  {}", redact(line));
+        }
+        println!();
+    }
+}
+
+/// Last `count` lines of a job log, without the timestamp GitHub puts on
+/// each line or carriage-return progress output
+fn log_tail(log: &str, count: usize) -> Vec<String> {
+    let lines: Vec<&str> = log.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| {
+            let line = line.rsplit('\r').next().unwrap_or(line);
+            match line.split_once(' ') {
+                Some((stamp, rest)) if is_timestamp(stamp) => rest.to_string(),
+                _ => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// `2024-05-01T12:00:00.1234567Z`
+fn is_timestamp(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() >= 20 && bytes[4] == b'-' && bytes[10] == b'T' && word.ends_with('Z')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_tail() {
+        let log = "2024-05-01T12:00:00.1234567Z Run cargo test\n\
+                   2024-05-01T12:00:01.1234567Z test a ... ok\n\
+                   2024-05-01T12:00:02.1234567Z 10%\r50%\r100%\n\
+                   error: test failed";
+        assert_eq!(
+            log_tail(log, 3),
+            vec!["test a ... ok", "100%", "error: test failed"]
+        );
+        assert_eq!(log_tail("one\ntwo", 10), vec!["one", "two"]);
+    }
+}
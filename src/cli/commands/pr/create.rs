@@ -1,21 +1,61 @@
 //! PR create command implementation
 
+use super::split::{changed_paths, create_split_branch, group_files, SplitBy, SplitGroup};
+use super::{is_oversized, size_table};
+use crate::cli::commands::audit::{audit, push_audited, PushMode};
+use crate::cli::commands::push::check_outgoing;
 use crate::cli::output::Output;
-use crate::core::manifest::{Manifest, PlatformType};
+use crate::core::audit::{AuditAction, AuditEntry};
+use crate::core::codeowners::{split_reviewers, CodeOwners};
+use crate::core::manifest::{Manifest, PlatformType, PrBodyStyle};
 use crate::core::repo::RepoInfo;
-use crate::core::state::StateFile;
+use crate::core::state::{GroupPR, PRGroup, StateFile};
+use crate::core::ticket::{body_with_ticket, branch_ticket, ticket_url, title_with_ticket};
+use crate::git::branch::{
+    get_branch_changed_files, get_branch_commit_messages, get_branch_diff_stat,
+    remote_branch_exists, CommitMessage,
+};
+use crate::git::remote::get_remote_tracking_oid;
 use crate::git::{get_current_branch, open_repo, path_exists};
+use crate::platform::traits::PlatformError;
 use crate::platform::{detect_platform, get_platform_adapter};
+use crate::util::concurrent_map;
 use git2::Repository;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// One PR to open: a repo's branch, or one split branch of it
+struct Target<'a> {
+    repo: &'a RepoInfo,
+    /// Branch the PR is opened from
+    head: String,
+    /// Repo name, plus the group for split PRs
+    label: String,
+    /// The files of a split PR, and its part number and count
+    part: Option<(SplitGroup, usize, usize)>,
+}
 
 /// Run the PR create command
+///
+/// With `auto_reviewers`, each PR requests review from the CODEOWNERS of the
+/// files it changes. With `split`, repos whose changes span several groups get
+/// one branch and PR per group instead of one PR. With `push_first` and
+/// `verify`, the branches get the pre-push checks of `gr push` first. Split
+/// branch commits are signed per repo config unless `sign` is false, and
+/// protected split branch names are refused unless `allow_protected`. The PRs
+/// are recorded as a group with `labels` for `gr changelog`.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_pr_create(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     title: Option<&str>,
     draft: bool,
     push_first: bool,
+    auto_reviewers: bool,
+    split: Option<SplitBy>,
+    verify: bool,
+    labels: &[String],
+    sign: bool,
+    allow_protected: bool,
 ) -> anyhow::Result<()> {
     Output::header("Creating pull requests...");
     println!();
@@ -75,28 +115,71 @@ pub async fn run_pr_create(
         }
     };
 
-    // Get title from argument or use branch name as fallback
-    let pr_title = title.map(|s| s.to_string()).unwrap_or_else(|| {
-        // Convert branch name to title: feat/my-feature -> My feature
-        let title = branch
-            .trim_start_matches("feat/")
-            .trim_start_matches("fix/")
-            .trim_start_matches("chore/")
-            .replace(['-', '_'], " ");
-        let mut chars = title.chars();
-        match chars.next() {
-            None => title,
-            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-        }
-    });
+    // A branch from `gr branch start` only gets PRs in the repos it was started in
+    let state =
+        StateFile::load(workspace_root.join(".gitgrip").join("state.json")).unwrap_or_default();
+    if let Some(started) = state.get_branch_repos(&branch) {
+        repos_with_changes.retain(|repo| {
+            let included = started.contains(&repo.name);
+            if !included {
+                Output::warning(&format!(
+                    "{}: has commits on {} but the branch wasn't started there; skipping",
+                    repo.name, branch
+                ));
+            }
+            included
+        });
+        for name in started {
+            if !repos_with_changes.iter().any(|repo| &repo.name == name) {
+                Output::info(&format!("{}: no commits on {} yet", name, branch));
+            }
+        }
+        if repos_with_changes.is_empty() {
+            println!("No repositories have changes to create PRs for.");
+            return Ok(());
+        }
+    }
+
+    let commits: Vec<Vec<CommitMessage>> = repos_with_changes
+        .iter()
+        .map(|repo| {
+            open_repo(&repo.absolute_path)
+                .and_then(|r| get_branch_commit_messages(&r, &repo.default_branch, &branch))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    // Title from the argument, else the one commit subject, else the branch name
+    let pr_title = title
+        .map(|s| s.to_string())
+        .or_else(|| single_subject(&commits))
+        .unwrap_or_else(|| title_from_branch(&branch));
+
+    // A branch's ticket goes in the title and is linked from the body
+    let ticket = branch_ticket(&state, &manifest.settings.tickets, &branch);
+    let ticket_link = ticket
+        .as_deref()
+        .and_then(|ticket| ticket_url(&manifest.settings.tickets, ticket));
+    let pr_title = match ticket {
+        Some(ref ticket) => title_with_ticket(&pr_title, ticket),
+        None => pr_title,
+    };
 
     // Push if requested
     if push_first {
+        if verify {
+            let targets: Vec<(&RepoInfo, String)> = repos_with_changes
+                .iter()
+                .map(|repo| (*repo, branch.clone()))
+                .collect();
+            check_outgoing(workspace_root, manifest, &targets)?;
+        }
         Output::info("Pushing branches first...");
         for repo in &repos_with_changes {
             if let Ok(git_repo) = open_repo(&repo.absolute_path) {
                 let spinner = Output::spinner(&format!("Pushing {}...", repo.name));
-                match crate::git::remote::push_branch(&git_repo, &branch, "origin", true) {
+                let push = PushMode::Normal { set_upstream: true };
+                match push_audited(workspace_root, &repo.name, &git_repo, &branch, push) {
                     Ok(()) => spinner.finish_with_message(format!("{}: pushed", repo.name)),
                     Err(e) => {
                         spinner.finish_with_message(format!("{}: push failed - {}", repo.name, e))
@@ -107,37 +190,172 @@ pub async fn run_pr_create(
         println!();
     }
 
+    let mut targets: Vec<Target> = Vec::new();
+    for repo in &repos_with_changes {
+        match split {
+            Some(split) => targets.extend(split_repo(
+                workspace_root,
+                manifest,
+                repo,
+                &branch,
+                &pr_title,
+                split,
+                sign,
+                allow_protected,
+            )),
+            None => targets.push(Target {
+                repo,
+                head: branch.clone(),
+                label: repo.name.clone(),
+                part: None,
+            }),
+        }
+    }
+
+    // Size summary shared by every PR in the group
+    let limit = manifest.settings.pr_size_limit;
+    let sizes: Vec<(&str, _)> = targets
+        .iter()
+        .filter_map(|target| {
+            let repo = target.repo;
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            let stat = get_branch_diff_stat(&git_repo, &repo.default_branch, &target.head).ok()?;
+            Some((target.label.as_str(), stat))
+        })
+        .collect();
+    for (name, stat) in &sizes {
+        if is_oversized(stat, limit) {
+            Output::warning(&format!(
+                "{}: {} is over the {}-line size limit; consider splitting it",
+                name, stat, limit
+            ));
+        }
+    }
+    let size_section =
+        (!sizes.is_empty()).then(|| format!("### Size\n\n{}", size_table(&sizes, limit)));
+
+    // CODEOWNERS lookups read git, so do them before going to the network
+    let work: Vec<(&Target, String, Option<Reviewers>, Option<String>)> = targets
+        .iter()
+        .map(|target| {
+            let repo = target.repo;
+            let reviewers = auto_reviewers
+                .then(|| code_owner_reviewers(repo, &target.head))
+                .flatten();
+            let (title, body) = match &target.part {
+                Some((group, part, parts)) => (
+                    format!("{} ({})", pr_title, group.key),
+                    Some(split_body(
+                        &branch,
+                        group,
+                        *part,
+                        *parts,
+                        size_section.as_deref(),
+                    )),
+                ),
+                None => {
+                    let index = repos_with_changes
+                        .iter()
+                        .position(|r| r.name == repo.name)
+                        .unwrap_or_default();
+                    (
+                        pr_title.clone(),
+                        pr_body(
+                            manifest.settings.pr_body_style,
+                            repo,
+                            &commits[index],
+                            size_section.as_deref(),
+                        ),
+                    )
+                }
+            };
+            let body = match ticket {
+                Some(ref ticket) => Some(body_with_ticket(
+                    body.as_deref(),
+                    ticket,
+                    ticket_link.as_deref(),
+                )),
+                None => body,
+            };
+            (target, title, reviewers, body)
+        })
+        .collect();
+
     // Create PRs for each repo
-    let mut created_prs: Vec<(String, u64, String)> = Vec::new(); // (repo_name, pr_number, url)
+    let spinner = Output::spinner(&format!("Creating {} PR(s)...", work.len()));
+    let results = concurrent_map(
+        &work,
+        manifest.settings.api_concurrency,
+        |(target, title, reviewers, body)| {
+            let repo = target.repo;
+            async move {
+                let platform = get_platform_adapter(detect_platform(&repo.url), None);
+                let pr = platform
+                    .create_pull_request(
+                        &repo.owner,
+                        &repo.repo,
+                        &target.head,
+                        &repo.default_branch,
+                        title,
+                        body.as_deref(),
+                        draft,
+                    )
+                    .await?;
 
-    for repo in &repos_with_changes {
-        let platform_type = detect_platform(&repo.url);
-        let platform = get_platform_adapter(platform_type, None);
+                let requested = match reviewers {
+                    Some(reviewers) => Some(
+                        platform
+                            .request_reviewers(
+                                &repo.owner,
+                                &repo.repo,
+                                pr.number,
+                                &reviewers.users,
+                                &reviewers.teams,
+                            )
+                            .await,
+                    ),
+                    None => None,
+                };
+                Ok::<_, PlatformError>((pr, requested))
+            }
+        },
+    )
+    .await;
+    spinner.finish_and_clear();
 
-        let spinner = Output::spinner(&format!("Creating PR for {}...", repo.name));
+    let mut created_prs: Vec<(String, u64, String)> = Vec::new(); // (label, pr_number, url)
+    let mut split_prs: Vec<(String, u64)> = Vec::new(); // (split branch, pr_number)
 
-        match platform
-            .create_pull_request(
-                &repo.owner,
-                &repo.repo,
-                &branch,
-                &repo.default_branch,
-                &pr_title,
-                None,
-                draft,
-            )
-            .await
-        {
-            Ok(pr) => {
-                spinner.finish_with_message(format!(
+    for ((target, _, reviewers, _), result) in work.iter().zip(results) {
+        let label = &target.label;
+        match result {
+            Ok((pr, requested)) => {
+                Output::success(&format!(
                     "{}: created PR #{} - {}",
-                    repo.name, pr.number, pr.url
+                    label, pr.number, pr.url
                 ));
-                created_prs.push((repo.name.clone(), pr.number, pr.url.clone()));
-            }
-            Err(e) => {
-                spinner.finish_with_message(format!("{}: failed - {}", repo.name, e));
+                audit(
+                    workspace_root,
+                    AuditEntry::new(AuditAction::PrCreate, &target.repo.name)
+                        .with_ref(&target.head, None, None)
+                        .with_pr(pr.number),
+                );
+                created_prs.push((label.clone(), pr.number, pr.url));
+                if target.part.is_some() {
+                    split_prs.push((target.head.clone(), pr.number));
+                }
+
+                match (reviewers, requested) {
+                    (Some(reviewers), Some(Ok(()))) => Output::info(&format!(
+                        "{}: requested review from {}",
+                        label,
+                        reviewers.describe()
+                    )),
+                    (_, Some(Err(e))) => Output::warning(&format!("{}: {}", label, e)),
+                    _ => {}
+                }
             }
+            Err(e) => Output::error(&format!("{}: failed - {}", label, e)),
         }
     }
 
@@ -155,6 +373,24 @@ pub async fn run_pr_create(
         if let Some((_, first_pr_number, _)) = created_prs.first() {
             state.set_pr_for_branch(&branch, *first_pr_number);
         }
+        for (head, number) in &split_prs {
+            state.set_pr_for_branch(head, *number);
+        }
+        state.record_pr_group(PRGroup {
+            branch: branch.clone(),
+            title: pr_title.clone(),
+            labels: labels.to_vec(),
+            prs: created_prs
+                .iter()
+                .map(|(label, number, url)| GroupPR {
+                    repo_name: label.clone(),
+                    number: *number,
+                    url: url.clone(),
+                    merged: false,
+                })
+                .collect(),
+            merged_at: None,
+        });
 
         let state_json = serde_json::to_string_pretty(&state)?;
         std::fs::write(&state_path, state_json)?;
@@ -174,6 +410,289 @@ pub async fn run_pr_create(
     Ok(())
 }
 
+/// PR targets for one repo under `split`: a branch per group, pushed, or the
+/// branch itself when its changes fall in a single group
+///
+/// Split branches are pushed with a lease on the last fetched remote tip, so
+/// someone else's update to one is never overwritten.
+#[allow(clippy::too_many_arguments)]
+fn split_repo<'a>(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    repo: &'a RepoInfo,
+    branch: &str,
+    title: &str,
+    split: SplitBy,
+    sign: bool,
+    allow_protected: bool,
+) -> Vec<Target<'a>> {
+    let unsplit = || {
+        vec![Target {
+            repo,
+            head: branch.to_string(),
+            label: repo.name.clone(),
+            part: None,
+        }]
+    };
+    let git_repo = match open_repo(&repo.absolute_path) {
+        Ok(git_repo) => git_repo,
+        Err(e) => {
+            Output::warning(&format!("{}: not split - {}", repo.name, e));
+            return unsplit();
+        }
+    };
+    let targets = match split_branches(
+        &git_repo,
+        manifest,
+        repo,
+        branch,
+        title,
+        split,
+        sign,
+        allow_protected,
+    ) {
+        Ok(targets) if targets.len() >= 2 => targets,
+        Ok(_) => return unsplit(),
+        Err(e) => {
+            Output::warning(&format!("{}: not split - {}", repo.name, e));
+            return unsplit();
+        }
+    };
+
+    Output::info(&format!(
+        "{}: splitting into {} PRs",
+        repo.name,
+        targets.len()
+    ));
+    targets
+        .into_iter()
+        .filter(|target| {
+            let spinner = Output::spinner(&format!("Pushing {}...", target.head));
+            let mode = match get_remote_tracking_oid(&git_repo, "origin", &target.head) {
+                Some(expected) => PushMode::ForceWithLease(expected),
+                None => PushMode::Normal {
+                    set_upstream: false,
+                },
+            };
+            match push_audited(workspace_root, &repo.name, &git_repo, &target.head, mode) {
+                Ok(()) => {
+                    spinner.finish_with_message(format!("{}: pushed {}", repo.name, target.head));
+                    true
+                }
+                Err(e) => {
+                    spinner.finish_with_message(format!(
+                        "{}: push of {} failed - {}",
+                        repo.name, target.head, e
+                    ));
+                    false
+                }
+            }
+        })
+        .collect()
+}
+
+/// Create the split branches for one repo, named `<branch>-<group>`
+#[allow(clippy::too_many_arguments)]
+fn split_branches<'a>(
+    git_repo: &Repository,
+    manifest: &Manifest,
+    repo: &'a RepoInfo,
+    branch: &str,
+    title: &str,
+    split: SplitBy,
+    sign: bool,
+    allow_protected: bool,
+) -> anyhow::Result<Vec<Target<'a>>> {
+    let head = git_repo
+        .revparse_single(&format!("refs/heads/{}", branch))?
+        .peel_to_commit()?
+        .id();
+    let base_ref = if remote_branch_exists(git_repo, &repo.default_branch, "origin") {
+        format!("origin/{}", repo.default_branch)
+    } else {
+        repo.default_branch.clone()
+    };
+    let base_tip = git_repo.revparse_single(&base_ref)?.peel_to_commit()?.id();
+    let base = git_repo.merge_base(base_tip, head)?;
+
+    let files = changed_paths(git_repo, base, head)?;
+    let codeowners = CodeOwners::load(&repo.absolute_path);
+    let groups = group_files(&files, split, codeowners.as_ref());
+    if groups.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<String> = groups
+        .iter()
+        .map(|group| format!("{}-{}", branch, group.slug))
+        .collect();
+    manifest.settings.ensure_unprotected(
+        "push",
+        names.iter().map(|name| {
+            (
+                repo.name.as_str(),
+                name.as_str(),
+                repo.default_branch.as_str(),
+            )
+        }),
+        allow_protected,
+    )?;
+
+    let parts = groups.len();
+    let mut targets = Vec::new();
+    for (index, (group, name)) in groups.into_iter().zip(names).enumerate() {
+        let message = format!("{} ({})", title, group.key);
+        create_split_branch(git_repo, base, head, &group.files, &name, &message, sign)?;
+        targets.push(Target {
+            repo,
+            head: name,
+            label: format!("{} ({})", repo.name, group.key),
+            part: Some((group, index + 1, parts)),
+        });
+    }
+    Ok(targets)
+}
+
+/// Description of one split PR: where it came from and the files it holds
+fn split_body(
+    branch: &str,
+    group: &SplitGroup,
+    part: usize,
+    parts: usize,
+    size_section: Option<&str>,
+) -> String {
+    let mut body = format!(
+        "Part {} of {} of `{}`, split by area: `{}`. The parts are independent and can merge in any order.\n\n### Files\n\n",
+        part, parts, branch, group.key
+    );
+    for file in &group.files {
+        body.push_str(&format!("- `{}`\n", file));
+    }
+    if let Some(size) = size_section {
+        body.push('\n');
+        body.push_str(size);
+    }
+    body
+}
+
+/// The shared commit subject, if the branch holds exactly one distinct commit message
+fn single_subject(commits: &[Vec<CommitMessage>]) -> Option<String> {
+    let mut subjects = commits.iter().flatten().map(|c| c.subject.as_str());
+    let first = subjects.next()?;
+    let per_repo_single = commits.iter().all(|c| c.len() <= 1);
+    (per_repo_single && subjects.all(|s| s == first)).then(|| first.to_string())
+}
+
+/// Convert a branch name to a title: feat/my-feature -> My feature
+fn title_from_branch(branch: &str) -> String {
+    let title = branch
+        .trim_start_matches("feat/")
+        .trim_start_matches("fix/")
+        .trim_start_matches("chore/")
+        .replace(['-', '_'], " ");
+    let mut chars = title.chars();
+    match chars.next() {
+        None => title,
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+/// Pull request template locations, in the order GitHub checks them
+const PR_TEMPLATES: &[&str] = &[
+    ".github/pull_request_template.md",
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+];
+
+/// Build a PR description in the configured style
+///
+/// The template style falls back to the commit list when the repo has no template.
+fn pr_body(
+    style: PrBodyStyle,
+    repo: &RepoInfo,
+    commits: &[CommitMessage],
+    size_section: Option<&str>,
+) -> Option<String> {
+    let main = match style {
+        PrBodyStyle::Blank => return None,
+        PrBodyStyle::Template => PR_TEMPLATES
+            .iter()
+            .find_map(|path| std::fs::read_to_string(repo.absolute_path.join(path)).ok())
+            .unwrap_or_else(|| commit_list(commits)),
+        PrBodyStyle::Commits => commit_list(commits),
+    };
+
+    let sections: Vec<&str> = [main.trim_end(), size_section.unwrap_or_default()]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!sections.is_empty()).then(|| sections.join("\n\n"))
+}
+
+/// Markdown list of commits, with bodies indented under their subjects
+fn commit_list(commits: &[CommitMessage]) -> String {
+    if commits.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("### Commits\n\n");
+    for commit in commits {
+        out.push_str(&format!("- {} ({})\n", commit.subject, commit.sha));
+        for line in commit.body.lines() {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("  {}\n", line));
+            }
+        }
+    }
+    out
+}
+
+/// Users and teams to request review from
+struct Reviewers {
+    users: Vec<String>,
+    teams: Vec<String>,
+}
+
+impl Reviewers {
+    fn describe(&self) -> String {
+        self.users
+            .iter()
+            .map(|u| format!("@{}", u))
+            .chain(self.teams.iter().map(|t| format!("team {}", t)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The CODEOWNERS of the files changed on `branch`, if there are any
+fn code_owner_reviewers(repo: &RepoInfo, branch: &str) -> Option<Reviewers> {
+    let codeowners = CodeOwners::load(&repo.absolute_path)?;
+    let changed = match open_repo(&repo.absolute_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|r| Ok(get_branch_changed_files(&r, &repo.default_branch, branch)?))
+    {
+        Ok(files) => files,
+        Err(e) => {
+            Output::warning(&format!("{}: cannot list changed files - {}", repo.name, e));
+            return None;
+        }
+    };
+
+    let owners: Vec<String> = changed
+        .iter()
+        .flat_map(|path| codeowners.owners_for(path).to_vec())
+        .collect();
+    let (users, teams) = split_reviewers(&owners);
+    if users.is_empty() && teams.is_empty() {
+        return None;
+    }
+    Some(Reviewers { users, teams })
+}
+
 /// Check if a branch has commits ahead of another branch
 fn has_commits_ahead(repo: &Repository, branch: &str, base: &str) -> anyhow::Result<bool> {
     let local_ref = format!("refs/heads/{}", branch);
@@ -202,7 +721,7 @@ fn has_commits_ahead(repo: &Repository, branch: &str, base: &str) -> anyhow::Res
         .target()
         .ok_or_else(|| anyhow::anyhow!("No base target"))?;
 
-    let (ahead, _behind) = repo.graph_ahead_behind(local_oid, base_oid)?;
+    let (ahead, _behind) = crate::git::cached_graph_ahead_behind(repo, local_oid, base_oid)?;
     Ok(ahead > 0)
 }
 
@@ -217,3 +736,73 @@ pub fn get_token_for_platform(platform: &PlatformType) -> Option<String> {
         PlatformType::AzureDevOps => std::env::var("AZURE_DEVOPS_TOKEN").ok(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::manifest::RepoConfig;
+
+    fn commit(subject: &str, body: &str) -> CommitMessage {
+        CommitMessage {
+            sha: "abc1234".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_single_subject() {
+        let one = vec![vec![commit("Add login", "")], vec![commit("Add login", "")]];
+        assert_eq!(single_subject(&one), Some("Add login".to_string()));
+
+        let two = vec![vec![commit("Add login", ""), commit("Fix typo", "")]];
+        assert_eq!(single_subject(&two), None);
+        assert_eq!(title_from_branch("feat/add-login"), "Add login");
+    }
+
+    #[test]
+    fn test_pr_body_styles() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = RepoConfig {
+            url: "git@github.com:acme/api.git".to_string(),
+            path: "api".to_string(),
+            default_branch: "main".to_string(),
+            copyfile: None,
+            linkfile: None,
+            platform: None,
+            sparse: None,
+            depends_on: None,
+            revision: None,
+            env: None,
+            workspace: false,
+        };
+        let repo = RepoInfo::from_config("api", &config, &temp.path().to_path_buf()).unwrap();
+        let commits = vec![commit("Add login", "Wires up the form.\n\nCloses #3")];
+
+        let body = pr_body(PrBodyStyle::Commits, &repo, &commits, Some("### Size")).unwrap();
+        assert_eq!(
+            body,
+            "### Commits\n\n- Add login (abc1234)\n  Wires up the form.\n\n  Closes #3\n\n### Size"
+        );
+
+        // No template in the repo: fall back to the commit list
+        let fallback = pr_body(PrBodyStyle::Template, &repo, &commits, None).unwrap();
+        assert!(fallback.starts_with("### Commits"));
+
+        std::fs::create_dir_all(repo.absolute_path.join(".github")).unwrap();
+        std::fs::write(
+            repo.absolute_path.join(".github/pull_request_template.md"),
+            "## Why\n",
+        )
+        .unwrap();
+        assert_eq!(
+            pr_body(PrBodyStyle::Template, &repo, &commits, None).unwrap(),
+            "## Why"
+        );
+
+        assert_eq!(
+            pr_body(PrBodyStyle::Blank, &repo, &commits, Some("x")),
+            None
+        );
+    }
+}
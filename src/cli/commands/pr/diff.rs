@@ -1,18 +1,21 @@
 //! PR diff command implementation
 
+use crate::cli::diff_render::DiffRenderer;
 use crate::cli::output::Output;
-use crate::core::manifest::Manifest;
+use crate::core::manifest::{DiffSettings, Manifest};
 use crate::core::repo::RepoInfo;
 use crate::git::{get_current_branch, open_repo, path_exists};
 use crate::platform::{detect_platform, get_platform_adapter};
 use std::path::PathBuf;
 
-/// Run the PR diff command
+/// Run the PR diff command, rendering diffs with `display`
 pub async fn run_pr_diff(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     stat_only: bool,
+    display: &DiffSettings,
 ) -> anyhow::Result<()> {
+    let renderer = DiffRenderer::new(display);
     if !stat_only {
         Output::header("PR Diff");
         println!();
@@ -74,7 +77,7 @@ pub async fn run_pr_diff(
                             total_deletions += stats.deletions;
                         } else {
                             println!("=== {} #{} ===", repo.name, pr.number);
-                            println!("{}", diff);
+                            print!("{}", renderer.render(&diff));
                             println!();
                         }
                     }
@@ -1,20 +1,77 @@
 //! PR merge command implementation
 
+use super::train::{commits_behind, group_dependencies, merge_order, resync_branch};
+use super::{branch_tickets, feature_branches, fetch_readiness};
+use crate::cli::commands::audit::audit;
 use crate::cli::output::Output;
+use crate::core::audit::{AuditAction, AuditEntry};
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
-use crate::git::{get_current_branch, open_repo, path_exists};
-use crate::platform::{detect_platform, get_platform_adapter, CheckState, MergeMethod};
-use std::path::PathBuf;
+use crate::core::state::{GroupPR, StateFile};
+use crate::git::{fetch_remote, open_repo, remote_branch_exists, trial_merge_sequence, TrialMerge};
+use crate::platform::tracker::get_tracker;
+use crate::platform::traits::PlatformError;
+use crate::platform::{
+    detect_platform, get_platform_adapter, BranchProtection, HostingPlatform, MergeMethod,
+    MergeQueueStatus, StatusCheck,
+};
+use crate::util::concurrent_map;
+use chrono::{SecondsFormat, Utc};
+use dialoguer::Confirm;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Settings for `gr pr merge`
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// `merge`, `squash`, or `rebase`; merge if unset
+    pub method: Option<String>,
+    /// Merge without readiness checks
+    pub force: bool,
+    /// Bypass approvals and checks with admin rights, after confirming
+    pub admin: bool,
+    /// Trial-merge every PR into its live base first and stop on conflicts
+    pub predict_conflicts: bool,
+    /// Print the merge order and stop
+    pub plan: bool,
+    /// Bring each branch up to date with its base right before it merges
+    pub resync: bool,
+}
+
+/// An open PR found for a repo's feature branch
+struct PRToMerge {
+    repo_name: String,
+    path: PathBuf,
+    owner: String,
+    repo: String,
+    branch: String,
+    base: String,
+    pr_number: u64,
+    url: String,
+    platform: Arc<dyn HostingPlatform>,
+    approved: bool,
+    checks_pass: bool,
+    mergeable: bool,
+    merge_queue: MergeQueueStatus,
+    ticket: Option<String>,
+    /// Commits on the base the branch lacks, as of the last fetch
+    behind: Option<usize>,
+    /// Repos in the group whose PRs must merge first
+    after: Vec<String>,
+}
+
 /// Run the PR merge command
+///
+/// With `admin`, approval and check requirements are bypassed using the
+/// token's admin rights, after confirming each bypassed protection.
 pub async fn run_pr_merge(
     workspace_root: &PathBuf,
     manifest: &Manifest,
-    method: Option<&str>,
-    force: bool,
+    options: &MergeOptions,
 ) -> anyhow::Result<()> {
+    let force = options.force;
+    let admin = options.admin;
+
     Output::header("Merging pull requests...");
     println!();
 
@@ -24,90 +81,63 @@ pub async fn run_pr_merge(
         .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
         .collect();
 
-    let merge_method = match method {
+    let merge_method = match options.method.as_deref() {
         Some("squash") => MergeMethod::Squash,
         Some("rebase") => MergeMethod::Rebase,
         _ => MergeMethod::Merge,
     };
 
-    // Collect PRs to merge
-    struct PRToMerge {
-        repo_name: String,
-        owner: String,
-        repo: String,
-        pr_number: u64,
-        platform: Arc<dyn crate::platform::HostingPlatform>,
-        approved: bool,
-        checks_pass: bool,
-        mergeable: bool,
-    }
+    let branches = feature_branches(repos).await;
+    let tickets = branch_tickets(workspace_root, manifest, &branches);
+    let lookups = concurrent_map(
+        &branches,
+        manifest.settings.api_concurrency,
+        |(repo, branch)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            let pr = platform
+                .find_pr_by_branch(&repo.owner, &repo.repo, branch)
+                .await?;
+            let details = match &pr {
+                Some(pr) => {
+                    let (readiness, queue) = tokio::join!(
+                        fetch_readiness(platform.as_ref(), repo, branch, pr.number),
+                        platform.get_merge_queue_status(&repo.owner, &repo.repo, pr.number),
+                    );
+                    // If the queue can't be read, fall back to merging directly
+                    Some((readiness, queue.unwrap_or_default()))
+                }
+                None => None,
+            };
+            Ok::<_, PlatformError>((platform, pr.zip(details)))
+        },
+    )
+    .await;
 
     let mut prs_to_merge: Vec<PRToMerge> = Vec::new();
 
-    for repo in &repos {
-        if !path_exists(&repo.absolute_path) {
-            continue;
-        }
-
-        let git_repo = match open_repo(&repo.absolute_path) {
-            Ok(r) => r,
-            Err(_) => continue,
-        };
-
-        let branch = match get_current_branch(&git_repo) {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-
-        // Skip if on default branch
-        if branch == repo.default_branch {
-            continue;
-        }
-
-        let platform_type = detect_platform(&repo.url);
-        let platform = get_platform_adapter(platform_type, None);
-
-        match platform
-            .find_pr_by_branch(&repo.owner, &repo.repo, &branch)
-            .await
-        {
-            Ok(Some(pr)) => {
-                // Get PR details
-                let (approved, mergeable) = match platform
-                    .get_pull_request(&repo.owner, &repo.repo, pr.number)
-                    .await
-                {
-                    Ok(full_pr) => {
-                        let is_approved = platform
-                            .is_pull_request_approved(&repo.owner, &repo.repo, pr.number)
-                            .await
-                            .unwrap_or(false);
-                        (is_approved, full_pr.mergeable.unwrap_or(false))
-                    }
-                    Err(_) => (false, false),
-                };
-
-                // Get status checks
-                let checks_pass = match platform
-                    .get_status_checks(&repo.owner, &repo.repo, &branch)
-                    .await
-                {
-                    Ok(status) => status.state == CheckState::Success,
-                    Err(_) => false,
-                };
-
+    for (((repo, branch), lookup), ticket) in branches.iter().zip(lookups).zip(tickets) {
+        match lookup {
+            Ok((platform, Some((pr, (readiness, merge_queue))))) => {
                 prs_to_merge.push(PRToMerge {
                     repo_name: repo.name.clone(),
+                    path: repo.absolute_path.clone(),
                     owner: repo.owner.clone(),
                     repo: repo.repo.clone(),
+                    branch: branch.clone(),
+                    base: repo.default_branch.clone(),
                     pr_number: pr.number,
+                    url: pr.url.clone(),
                     platform,
-                    approved,
-                    checks_pass,
-                    mergeable,
+                    approved: readiness.approved,
+                    checks_pass: readiness.checks_pass,
+                    mergeable: readiness.mergeable,
+                    merge_queue,
+                    ticket,
+                    behind: None,
+                    after: Vec::new(),
                 });
             }
-            Ok(None) => {
+            Ok((_, None)) => {
                 Output::info(&format!("{}: no open PR for this branch", repo.name));
             }
             Err(e) => {
@@ -121,8 +151,29 @@ pub async fn run_pr_merge(
         return Ok(());
     }
 
-    // Check readiness if not forcing
-    if !force {
+    let prs_to_merge = plan_merge(manifest, prs_to_merge).await;
+    if options.plan {
+        print_plan(&prs_to_merge);
+        if options.predict_conflicts {
+            println!();
+            predict_conflicts(&prs_to_merge).await;
+        }
+        return Ok(());
+    }
+
+    if options.predict_conflicts && !predict_conflicts(&prs_to_merge).await && !force {
+        println!();
+        println!("Use --force to merge anyway.");
+        return Ok(());
+    }
+
+    if admin {
+        if !confirm_admin_bypass(&prs_to_merge, manifest.settings.api_concurrency).await? {
+            Output::info("Nothing merged.");
+            return Ok(());
+        }
+    } else if !force {
+        // Check readiness if not forcing
         let mut issues = Vec::new();
         for pr in &prs_to_merge {
             if !pr.approved {
@@ -156,11 +207,84 @@ pub async fn run_pr_merge(
         }
     }
 
-    // Merge PRs
+    // Merge PRs, or add them to the merge queue where the base branch has one
     let mut success_count = 0;
+    let mut queued_count = 0;
     let mut error_count = 0;
+    // Each PR's ticket, and whether the PR is now merged
+    let mut outcomes: Vec<(Option<String>, bool)> = Vec::new();
 
     for pr in prs_to_merge {
+        // Admin merges go around the queue
+        if pr.merge_queue.enabled && !admin {
+            let spinner = Output::spinner(&format!(
+                "Queueing {} PR #{}...",
+                pr.repo_name, pr.pr_number
+            ));
+            let entry = match pr.merge_queue.entry {
+                Some(entry) => Ok(entry),
+                None => {
+                    pr.platform
+                        .enqueue_pull_request(&pr.owner, &pr.repo, pr.pr_number)
+                        .await
+                }
+            };
+            match entry {
+                Ok(entry) => {
+                    spinner.finish_with_message(format!(
+                        "{}: PR #{} in merge queue {}",
+                        pr.repo_name, pr.pr_number, entry
+                    ));
+                    queued_count += 1;
+                    outcomes.push((pr.ticket.clone(), false));
+                }
+                Err(e) => {
+                    spinner.finish_with_message(format!("{}: failed - {}", pr.repo_name, e));
+                    error_count += 1;
+                    outcomes.push((pr.ticket.clone(), false));
+
+                    if manifest.settings.merge_strategy
+                        == crate::core::manifest::MergeStrategy::AllOrNothing
+                    {
+                        Output::error("Stopping due to all-or-nothing merge strategy.");
+                        return Err(e.into());
+                    }
+                }
+            }
+            continue;
+        }
+
+        if options.resync {
+            let spinner = Output::spinner(&format!("Updating {}...", pr.repo_name));
+            let (root, name) = (workspace_root.clone(), pr.repo_name.clone());
+            let (path, branch, base) = (pr.path.clone(), pr.branch.clone(), pr.base.clone());
+            let resynced = tokio::task::spawn_blocking(move || {
+                resync_branch(&root, &name, &path, &branch, &base)
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+            match resynced {
+                Ok(true) => spinner.finish_with_message(format!(
+                    "{}: merged {} into {} and pushed",
+                    pr.repo_name, pr.base, pr.branch
+                )),
+                Ok(false) => spinner.finish_and_clear(),
+                Err(e) => {
+                    spinner.finish_with_message(format!("{}: failed - {}", pr.repo_name, e));
+                    error_count += 1;
+                    outcomes.push((pr.ticket.clone(), false));
+                    if manifest.settings.merge_strategy
+                        == crate::core::manifest::MergeStrategy::AllOrNothing
+                    {
+                        Output::error("Stopping due to all-or-nothing merge strategy.");
+                        return Err(e);
+                    }
+                    continue;
+                }
+            }
+        }
+
         let spinner = Output::spinner(&format!("Merging {} PR #{}...", pr.repo_name, pr.pr_number));
 
         match pr
@@ -175,7 +299,15 @@ pub async fn run_pr_merge(
             .await
         {
             Ok(merged) => {
+                outcomes.push((pr.ticket.clone(), true));
+                record_group_merge(workspace_root, &pr).await;
                 if merged {
+                    audit(
+                        workspace_root,
+                        AuditEntry::new(AuditAction::PrMerge, &pr.repo_name)
+                            .with_ref(&pr.branch, None, None)
+                            .with_pr(pr.pr_number),
+                    );
                     spinner.finish_with_message(format!(
                         "{}: merged PR #{}",
                         pr.repo_name, pr.pr_number
@@ -192,6 +324,7 @@ pub async fn run_pr_merge(
             Err(e) => {
                 spinner.finish_with_message(format!("{}: failed - {}", pr.repo_name, e));
                 error_count += 1;
+                outcomes.push((pr.ticket.clone(), false));
 
                 // Check for all-or-nothing merge strategy
                 if manifest.settings.merge_strategy
@@ -206,11 +339,437 @@ pub async fn run_pr_merge(
 
     // Summary
     println!();
-    if error_count == 0 {
+    if error_count == 0 && queued_count == 0 {
         Output::success(&format!("Successfully merged {} PR(s).", success_count));
+    } else if error_count == 0 {
+        Output::success(&format!(
+            "Merged {} PR(s), queued {}. Track the queue with 'gr pr status'.",
+            success_count, queued_count
+        ));
     } else {
-        Output::warning(&format!("{} merged, {} failed", success_count, error_count));
+        Output::warning(&format!(
+            "{} merged, {} queued, {} failed",
+            success_count, queued_count, error_count
+        ));
     }
 
+    transition_merged_tickets(manifest, &outcomes).await;
     Ok(())
 }
+
+/// Put the PRs in merge order (see `train`), noting how far behind its base
+/// each branch is and which PRs must merge before it
+async fn plan_merge(manifest: &Manifest, mut prs: Vec<PRToMerge>) -> Vec<PRToMerge> {
+    let targets: Vec<(PathBuf, String, String)> = prs
+        .iter()
+        .map(|pr| (pr.path.clone(), pr.branch.clone(), pr.base.clone()))
+        .collect();
+    let behind = tokio::task::spawn_blocking(move || {
+        targets
+            .iter()
+            .map(|(path, branch, base)| commits_behind(path, branch, base))
+            .collect()
+    })
+    .await
+    .unwrap_or_else(|_| vec![None; prs.len()]);
+
+    let names: Vec<String> = prs.iter().map(|pr| pr.repo_name.clone()).collect();
+    let group: Vec<&str> = names.iter().map(String::as_str).collect();
+    let deps: Vec<Vec<String>> = names
+        .iter()
+        .map(|name| group_dependencies(manifest, name, &group))
+        .collect();
+    let order = merge_order(&names, &behind, &deps);
+
+    for ((pr, behind), deps) in prs.iter_mut().zip(behind).zip(deps) {
+        pr.behind = behind;
+        pr.after = deps;
+    }
+    let mut slots: Vec<Option<PRToMerge>> = prs.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+fn print_plan(prs: &[PRToMerge]) {
+    Output::header("Merge plan");
+    println!();
+    for (step, pr) in prs.iter().enumerate() {
+        let mut notes = vec![match pr.behind {
+            Some(0) => format!("up to date with {}", pr.base),
+            Some(n) => format!("{} commit(s) behind {}", n, pr.base),
+            None => format!("unknown distance from {}", pr.base),
+        }];
+        if !pr.after.is_empty() {
+            notes.push(format!("after {}", pr.after.join(", ")));
+        }
+        if pr.merge_queue.enabled {
+            notes.push("via merge queue".to_string());
+        }
+        Output::numbered_item(
+            step + 1,
+            &format!(
+                "{} PR #{} ({}): {}",
+                Output::repo_name(&pr.repo_name),
+                pr.pr_number,
+                Output::branch_name(&pr.branch),
+                notes.join("; ")
+            ),
+        );
+    }
+}
+
+/// Trial-merge each PR into its freshly fetched base in merge order and
+/// report the outcome; false if any PR would conflict
+///
+/// PRs into the same repo and base are merged one after another, catching
+/// conflicts the platform's mergeable flag can't see until an earlier PR lands.
+async fn predict_conflicts(prs: &[PRToMerge]) -> bool {
+    // (checkout, base, PR indices) per repo and base, in merge order
+    let mut groups: Vec<(PathBuf, String, Vec<usize>)> = Vec::new();
+    for (index, pr) in prs.iter().enumerate() {
+        let same = |other: &PRToMerge| (&other.owner, &other.repo) == (&pr.owner, &pr.repo);
+        match groups
+            .iter_mut()
+            .find(|(_, base, indices)| *base == pr.base && same(&prs[indices[0]]))
+        {
+            Some((_, _, indices)) => indices.push(index),
+            None => groups.push((pr.path.clone(), pr.base.clone(), vec![index])),
+        }
+    }
+    let branches: Vec<String> = prs.iter().map(|pr| pr.branch.clone()).collect();
+
+    let spinner = Output::spinner("Fetching and trial-merging...");
+    let outcomes = tokio::task::spawn_blocking(move || {
+        let mut outcomes: Vec<Option<Result<TrialMerge, String>>> = vec![None; branches.len()];
+        for (path, base, indices) in groups {
+            let result = open_repo(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|repo| {
+                    // A failed fetch leaves the last fetched state, which is still worth checking
+                    let _ = fetch_remote(&repo, "origin");
+                    let on_origin = |branch: &str| {
+                        if remote_branch_exists(&repo, branch, "origin") {
+                            format!("origin/{}", branch)
+                        } else {
+                            branch.to_string()
+                        }
+                    };
+                    let heads: Vec<String> =
+                        indices.iter().map(|&i| on_origin(&branches[i])).collect();
+                    let heads: Vec<&str> = heads.iter().map(String::as_str).collect();
+                    trial_merge_sequence(&repo, &on_origin(&base), &heads)
+                        .map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(results) => {
+                    for (index, outcome) in indices.into_iter().zip(results) {
+                        outcomes[index] = Some(Ok(outcome));
+                    }
+                }
+                Err(e) => {
+                    for index in indices {
+                        outcomes[index] = Some(Err(e.clone()));
+                    }
+                }
+            }
+        }
+        outcomes
+    })
+    .await
+    .unwrap_or_default();
+    spinner.finish_and_clear();
+
+    Output::header("Predicted merge results");
+    let mut clean = true;
+    for (step, (pr, outcome)) in prs.iter().zip(outcomes).enumerate() {
+        let label = format!(
+            "{}/{} {} PR #{}",
+            step + 1,
+            prs.len(),
+            pr.repo_name,
+            pr.pr_number
+        );
+        match outcome {
+            Some(Ok(TrialMerge::Clean)) => {
+                Output::success(&format!("{}: merges cleanly into {}", label, pr.base))
+            }
+            Some(Ok(TrialMerge::Conflicts(paths))) => {
+                clean = false;
+                let note = if pr.mergeable {
+                    " (reported mergeable, but an earlier merge in this group changes that)"
+                } else {
+                    ""
+                };
+                Output::error(&format!(
+                    "{}: conflicts in {}{}",
+                    label,
+                    paths.join(", "),
+                    note
+                ));
+            }
+            Some(Err(e)) => Output::warning(&format!("{}: could not trial-merge - {}", label, e)),
+            None => Output::warning(&format!("{}: could not trial-merge", label)),
+        }
+    }
+    clean
+}
+
+/// Record a merged PR in its PR group for `gr changelog`; a branch without
+/// a group from `gr pr create` gets one titled after the PR
+async fn record_group_merge(workspace_root: &Path, pr: &PRToMerge) {
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let recorded = StateFile::load(&state_path)
+        .map(|state| {
+            state
+                .pr_groups
+                .iter()
+                .any(|g| g.branch == pr.branch && g.merged_at.is_none())
+        })
+        .unwrap_or(false);
+    let title = if recorded {
+        String::new()
+    } else {
+        match pr
+            .platform
+            .get_pull_request(&pr.owner, &pr.repo, pr.pr_number)
+            .await
+        {
+            Ok(details) => details.title,
+            Err(_) => pr.branch.clone(),
+        }
+    };
+
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    state.record_group_merge(
+        &pr.branch,
+        GroupPR {
+            repo_name: pr.repo_name.clone(),
+            number: pr.pr_number,
+            url: pr.url.clone(),
+            merged: true,
+        },
+        &title,
+        &Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+    );
+    if let Err(e) = state.save(&state_path) {
+        Output::warning(&format!("Could not record the merge in state: {}", e));
+    }
+}
+
+/// Move tickets whose PRs all merged to `settings.tickets.tracker.on_merge`
+async fn transition_merged_tickets(manifest: &Manifest, outcomes: &[(Option<String>, bool)]) {
+    let Some(ref settings) = manifest.settings.tickets.tracker else {
+        return;
+    };
+    let Some(ref state) = settings.on_merge else {
+        return;
+    };
+    let mut tickets: Vec<&String> = outcomes
+        .iter()
+        .filter_map(|(ticket, _)| ticket.as_ref())
+        .filter(|ticket| {
+            outcomes
+                .iter()
+                .filter(|(other, _)| other.as_ref() == Some(ticket))
+                .all(|(_, merged)| *merged)
+        })
+        .collect();
+    tickets.sort();
+    tickets.dedup();
+
+    let tracker = get_tracker(settings);
+    for ticket in tickets {
+        match tracker.transition_issue(ticket, state).await {
+            Ok(true) => Output::success(&format!("{}: moved to {}", ticket, state)),
+            Ok(false) => Output::info(&format!("{}: already {}", ticket, state)),
+            Err(e) => Output::warning(&format!("{}: could not move to {} - {}", ticket, state, e)),
+        }
+    }
+}
+
+/// Show every protection an admin merge would bypass and ask to proceed
+///
+/// Unmergeable PRs (conflicts) can't be bypassed and abort the merge.
+async fn confirm_admin_bypass(prs: &[PRToMerge], api_concurrency: usize) -> anyhow::Result<bool> {
+    let conflicted: Vec<String> = prs
+        .iter()
+        .filter(|pr| !pr.mergeable)
+        .map(|pr| format!("{} PR #{}", pr.repo_name, pr.pr_number))
+        .collect();
+    if !conflicted.is_empty() {
+        anyhow::bail!(
+            "Not mergeable (conflicts?), even as admin: {}",
+            conflicted.join(", ")
+        );
+    }
+
+    let details = concurrent_map(prs, api_concurrency, |pr| async move {
+        let (protection, checks) = tokio::join!(
+            pr.platform
+                .get_branch_protection(&pr.owner, &pr.repo, &pr.base),
+            pr.platform
+                .get_status_checks(&pr.owner, &pr.repo, &pr.branch),
+        );
+        (
+            protection.ok().flatten(),
+            checks.map(|c| c.statuses).unwrap_or_default(),
+        )
+    })
+    .await;
+
+    Output::warning("Admin merge will bypass these protections:");
+    for (pr, (protection, checks)) in prs.iter().zip(&details) {
+        println!(
+            "  {} PR #{} into {}:",
+            Output::repo_name(&pr.repo_name),
+            pr.pr_number,
+            Output::branch_name(&pr.base)
+        );
+        let bypasses = describe_bypasses(pr, protection.as_ref(), checks);
+        if bypasses.is_empty() {
+            println!("    (nothing to bypass)");
+        }
+        for bypass in &bypasses {
+            println!("    - {}", bypass);
+        }
+        if protection.as_ref().is_some_and(|p| p.enforce_admins) && !bypasses.is_empty() {
+            Output::warning(
+                "    protection applies to admins on this branch; the merge will likely be rejected",
+            );
+        }
+    }
+    println!();
+
+    Ok(Confirm::with_theme(&*Output::theme())
+        .with_prompt(format!("Merge {} PR(s) as admin?", prs.len()))
+        .default(false)
+        .interact()?)
+}
+
+/// The protections an admin merge of `pr` skips, one line each
+fn describe_bypasses(
+    pr: &PRToMerge,
+    protection: Option<&BranchProtection>,
+    checks: &[StatusCheck],
+) -> Vec<String> {
+    let mut bypasses = Vec::new();
+    let required =
+        |context: &str| protection.is_some_and(|p| p.required_checks.iter().any(|c| c == context));
+
+    if !pr.approved {
+        match protection {
+            Some(p) if p.required_approvals > 0 => bypasses.push(format!(
+                "{} required approval(s) missing",
+                p.required_approvals
+            )),
+            _ => bypasses.push("not approved".to_string()),
+        }
+    }
+
+    let (failing_required, failing_optional): (Vec<&StatusCheck>, Vec<&StatusCheck>) = checks
+        .iter()
+        .filter(|c| c.state != "success")
+        .partition(|c| required(&c.context));
+    let label = |c: &&StatusCheck| format!("{} ({})", c.context, c.state);
+    if !failing_required.is_empty() {
+        let names: Vec<String> = failing_required.iter().map(label).collect();
+        bypasses.push(format!("required checks not passing: {}", names.join(", ")));
+    }
+    if !failing_optional.is_empty() {
+        let names: Vec<String> = failing_optional.iter().map(label).collect();
+        bypasses.push(format!("optional checks not passing: {}", names.join(", ")));
+    }
+
+    // Required checks that never reported don't appear in the status list
+    if let Some(p) = protection {
+        let missing: Vec<&str> = p
+            .required_checks
+            .iter()
+            .filter(|c| !checks.iter().any(|s| &s.context == *c))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            bypasses.push(format!(
+                "required checks not reported: {}",
+                missing.join(", ")
+            ));
+        }
+    }
+    if checks.is_empty() && !pr.checks_pass && protection.is_none() {
+        bypasses.push("checks not passing".to_string());
+    }
+
+    if pr.merge_queue.enabled {
+        bypasses.push("merge queue (merging directly)".to_string());
+    }
+    bypasses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::github::GitHubAdapter;
+
+    fn pr() -> PRToMerge {
+        PRToMerge {
+            repo_name: "api".to_string(),
+            path: PathBuf::from("api"),
+            owner: "acme".to_string(),
+            repo: "api".to_string(),
+            branch: "feat/x".to_string(),
+            base: "main".to_string(),
+            pr_number: 7,
+            url: "https://github.com/acme/api/pull/7".to_string(),
+            platform: Arc::new(GitHubAdapter::new(None)),
+            approved: false,
+            checks_pass: false,
+            mergeable: true,
+            merge_queue: MergeQueueStatus {
+                enabled: true,
+                entry: None,
+            },
+            ticket: None,
+            behind: None,
+            after: Vec::new(),
+        }
+    }
+
+    fn check(context: &str, state: &str) -> StatusCheck {
+        StatusCheck {
+            context: context.to_string(),
+            state: state.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_describe_bypasses() {
+        let protection = BranchProtection {
+            required_checks: vec!["build".to_string(), "e2e".to_string()],
+            required_approvals: 2,
+            enforce_admins: false,
+        };
+        let checks = vec![
+            check("build", "failure"),
+            check("docs", "pending"),
+            check("lint", "success"),
+        ];
+        assert_eq!(
+            describe_bypasses(&pr(), Some(&protection), &checks),
+            vec![
+                "2 required approval(s) missing",
+                "required checks not passing: build (failure)",
+                "optional checks not passing: docs (pending)",
+                "required checks not reported: e2e",
+                "merge queue (merging directly)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe_bypasses_without_protection() {
+        let mut pr = pr();
+        pr.approved = true;
+        pr.checks_pass = true;
+        pr.merge_queue.enabled = false;
+        assert!(describe_bypasses(&pr, None, &[check("lint", "success")]).is_empty());
+    }
+}
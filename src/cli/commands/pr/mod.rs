@@ -1,15 +1,206 @@
 //! PR command implementations
 //!
 //! Subcommands for pull request operations.
+//!
+//! Git lookups run on blocking threads; platform API calls for the PR group
+//! run concurrently, capped by `settings.api_concurrency`.
 
 mod checks;
 mod create;
 mod diff;
 mod merge;
+mod ready_check;
+mod split;
 mod status;
+mod train;
 
-pub use checks::run_pr_checks;
+pub use checks::{run_pr_checks, ChecksOptions};
 pub use create::run_pr_create;
 pub use diff::run_pr_diff;
-pub use merge::run_pr_merge;
+pub use merge::{run_pr_merge, MergeOptions};
+pub use ready_check::run_pr_ready_check;
+pub use split::SplitBy;
 pub use status::run_pr_status;
+
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::state::StateFile;
+use crate::core::ticket::branch_ticket;
+use crate::git::branch::{get_branch_diff_stat, DiffStat};
+use crate::git::{get_current_branch, open_repo, path_exists};
+use crate::platform::tracker::{get_tracker, Issue};
+use crate::platform::{CheckState, HostingPlatform};
+use crate::util::{concurrent_map, max_workers, parallel_map};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Cloned repos that are on a feature branch, paired with that branch
+///
+/// Repos on their default branch (or whose branch can't be read) are left out.
+async fn feature_branches(repos: Vec<RepoInfo>) -> Vec<(RepoInfo, String)> {
+    tokio::task::spawn_blocking(move || {
+        let branches = parallel_map(&repos, max_workers(), |repo| {
+            if !path_exists(&repo.absolute_path) {
+                return None;
+            }
+            let branch = get_current_branch(&open_repo(&repo.absolute_path).ok()?).ok()?;
+            (branch != repo.default_branch).then_some(branch)
+        });
+        repos
+            .into_iter()
+            .zip(branches)
+            .filter_map(|(repo, branch)| Some((repo, branch?)))
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// The ticket of each branch, if it has one
+fn branch_tickets(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    branches: &[(RepoInfo, String)],
+) -> Vec<Option<String>> {
+    let state =
+        StateFile::load(workspace_root.join(".gitgrip").join("state.json")).unwrap_or_default();
+    branches
+        .iter()
+        .map(|(_, branch)| branch_ticket(&state, &manifest.settings.tickets, branch))
+        .collect()
+}
+
+/// Each distinct ticket as the configured tracker sees it, or why it couldn't
+/// be read; empty without a tracker
+async fn fetch_issues(
+    manifest: &Manifest,
+    tickets: &[Option<String>],
+) -> HashMap<String, Result<Issue, String>> {
+    let Some(ref settings) = manifest.settings.tickets.tracker else {
+        return HashMap::new();
+    };
+    let mut keys: Vec<String> = tickets.iter().flatten().cloned().collect();
+    keys.sort();
+    keys.dedup();
+    let tracker = get_tracker(settings);
+    let issues = concurrent_map(&keys, manifest.settings.api_concurrency, |key| {
+        let tracker = tracker.clone();
+        async move { tracker.get_issue(key).await.map_err(|e| e.to_string()) }
+    })
+    .await;
+    keys.into_iter().zip(issues).collect()
+}
+
+/// Whether an open PR is ready to merge
+#[derive(Debug, Clone, Copy, Default)]
+struct PRReadiness {
+    approved: bool,
+    checks_pass: bool,
+    mergeable: bool,
+}
+
+/// Fetch details, reviews, and checks for a PR concurrently
+async fn fetch_readiness(
+    platform: &dyn HostingPlatform,
+    repo: &RepoInfo,
+    branch: &str,
+    pr_number: u64,
+) -> PRReadiness {
+    let (details, approved, checks) = tokio::join!(
+        platform.get_pull_request(&repo.owner, &repo.repo, pr_number),
+        platform.is_pull_request_approved(&repo.owner, &repo.repo, pr_number),
+        platform.get_status_checks(&repo.owner, &repo.repo, branch),
+    );
+
+    // Approval only counts when the PR itself could be read
+    let (approved, mergeable) = match details {
+        Ok(pr) => (approved.unwrap_or(false), pr.mergeable.unwrap_or(false)),
+        Err(_) => (false, false),
+    };
+    PRReadiness {
+        approved,
+        checks_pass: matches!(checks, Ok(status) if status.state == CheckState::Success),
+        mergeable,
+    }
+}
+
+/// Local diff size of each repo's branch against its default branch
+///
+/// Repos whose diff can't be computed get `None`.
+async fn branch_sizes(branches: &[(RepoInfo, String)]) -> Vec<Option<DiffStat>> {
+    let branches = branches.to_vec();
+    tokio::task::spawn_blocking(move || {
+        parallel_map(&branches, max_workers(), |(repo, branch)| {
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            get_branch_diff_stat(&git_repo, &repo.default_branch, branch).ok()
+        })
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Whether `stat` exceeds `settings.pr_size_limit` (0 disables the check)
+fn is_oversized(stat: &DiffStat, limit: usize) -> bool {
+    limit > 0 && stat.lines_changed() > limit
+}
+
+/// Markdown table of PR sizes for a PR description
+fn size_table(sizes: &[(&str, DiffStat)], limit: usize) -> String {
+    let mut table = String::from(
+        "| Repo | Files | Additions | Deletions |\n|------|------:|----------:|----------:|\n",
+    );
+    let mut total = DiffStat::default();
+    for (repo, stat) in sizes {
+        let flag = if is_oversized(stat, limit) {
+            " ⚠️"
+        } else {
+            ""
+        };
+        table.push_str(&format!(
+            "| {}{} | {} | +{} | -{} |\n",
+            repo, flag, stat.files, stat.additions, stat.deletions
+        ));
+        total.files += stat.files;
+        total.additions += stat.additions;
+        total.deletions += stat.deletions;
+    }
+    if sizes.len() > 1 {
+        table.push_str(&format!(
+            "| **Total** | {} | +{} | -{} |\n",
+            total.files, total.additions, total.deletions
+        ));
+    }
+    if sizes.iter().any(|(_, stat)| is_oversized(stat, limit)) {
+        table.push_str(&format!(
+            "\n⚠️ Over {} changed lines; consider splitting for review.\n",
+            limit
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_table_flags_oversized() {
+        let small = DiffStat {
+            files: 1,
+            additions: 10,
+            deletions: 2,
+        };
+        let large = DiffStat {
+            files: 12,
+            additions: 450,
+            deletions: 80,
+        };
+        let table = size_table(&[("api", small), ("web", large)], 400);
+        assert!(table.contains("| api | 1 | +10 | -2 |\n"));
+        assert!(table.contains("| web ⚠️ | 12 | +450 | -80 |\n"));
+        assert!(table.contains("| **Total** | 13 | +460 | -82 |\n"));
+        assert!(table.contains("Over 400 changed lines"));
+
+        assert!(!is_oversized(&large, 0));
+    }
+}
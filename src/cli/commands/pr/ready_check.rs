@@ -0,0 +1,236 @@
+//! PR ready-check command implementation
+//!
+//! Judges the open PR of every repo on a feature branch against
+//! `settings.ready_check` and prints a verdict per repo. The command fails
+//! unless every PR is ready, so merge bots and pipelines can gate on its exit
+//! code and read the reasons from `--json`.
+
+use super::{feature_branches, fetch_readiness};
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::{Manifest, ReadyPolicy};
+use crate::core::repo::RepoInfo;
+use crate::git::branch::get_commits_between;
+use crate::git::open_repo;
+use crate::platform::traits::PlatformError;
+use crate::platform::{detect_platform, get_platform_adapter};
+use crate::util::{concurrent_map, max_workers, parallel_map};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Readiness of one repo's PR
+#[derive(Debug, Clone, Default, Serialize)]
+struct Verdict {
+    repo: String,
+    branch: String,
+    pr_number: Option<u64>,
+    url: Option<String>,
+    ready: bool,
+    approved: bool,
+    checks_pass: bool,
+    mergeable: bool,
+    /// Commits on the base branch missing from the PR branch, as last fetched
+    behind: Option<usize>,
+    /// `None` where the platform doesn't track thread resolution
+    unresolved_threads: Option<usize>,
+    /// Why the PR isn't ready; empty when it is
+    reasons: Vec<String>,
+}
+
+/// Verdicts for the whole workspace
+#[derive(Debug, Serialize)]
+struct Report {
+    ready: bool,
+    repos: Vec<Verdict>,
+}
+
+/// Run the PR ready-check command
+pub async fn run_pr_ready_check(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    json_output: bool,
+) -> anyhow::Result<()> {
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    let branches = feature_branches(repos).await;
+    let behind = commits_behind(&branches).await;
+    let lookups = concurrent_map(
+        &branches,
+        manifest.settings.api_concurrency,
+        |(repo, branch)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            match platform
+                .find_pr_by_branch(&repo.owner, &repo.repo, branch)
+                .await?
+            {
+                Some(pr) => {
+                    let (readiness, threads) = tokio::join!(
+                        fetch_readiness(platform.as_ref(), repo, branch, pr.number),
+                        platform.get_unresolved_review_threads(&repo.owner, &repo.repo, pr.number),
+                    );
+                    // A platform error leaves the thread count unknown
+                    Ok(Some((pr, readiness, threads.ok().flatten())))
+                }
+                None => Ok::<_, PlatformError>(None),
+            }
+        },
+    )
+    .await;
+
+    let policy = &manifest.settings.ready_check;
+    let mut verdicts = Vec::new();
+    for (((repo, branch), lookup), behind) in branches.iter().zip(lookups).zip(behind) {
+        let mut verdict = Verdict {
+            repo: repo.name.clone(),
+            branch: branch.clone(),
+            behind,
+            ..Default::default()
+        };
+        match lookup {
+            Ok(Some((pr, readiness, threads))) => {
+                verdict.pr_number = Some(pr.number);
+                verdict.url = Some(pr.url);
+                verdict.approved = readiness.approved;
+                verdict.checks_pass = readiness.checks_pass;
+                verdict.mergeable = readiness.mergeable;
+                verdict.unresolved_threads = threads;
+                verdict.reasons = unmet(policy, &verdict);
+            }
+            Ok(None) => verdict.reasons.push("no open PR".to_string()),
+            Err(e) => verdict.reasons.push(format!("lookup failed: {}", e)),
+        }
+        verdict.ready = verdict.reasons.is_empty();
+        verdicts.push(verdict);
+    }
+
+    // With nothing to merge there is nothing a gate should let through
+    let report = Report {
+        ready: !verdicts.is_empty() && verdicts.iter().all(|v| v.ready),
+        repos: verdicts,
+    };
+
+    if json_output {
+        Output::json(&report)?;
+    } else if report.repos.is_empty() {
+        Output::info("No repositories are on feature branches.");
+    } else {
+        let mut table = Table::new(vec!["Repo", "PR#", "Ready", "Reasons"]);
+        for verdict in &report.repos {
+            let pr = verdict
+                .pr_number
+                .map(|n| format!("#{}", n))
+                .unwrap_or_else(|| "-".to_string());
+            let ready = Output::mark(verdict.ready);
+            let reasons = verdict.reasons.join("; ");
+            table.add_row(vec![&verdict.repo, &pr, ready, &reasons]);
+        }
+        table.print();
+        println!();
+    }
+
+    if report.repos.is_empty() {
+        anyhow::bail!("Not ready to merge: no PRs to check");
+    }
+    if !report.ready {
+        let blocked = report.repos.iter().filter(|v| !v.ready).count();
+        anyhow::bail!("Not ready to merge ({} repo(s) blocked)", blocked);
+    }
+    if !json_output {
+        Output::success(&format!("All {} PR(s) ready to merge", report.repos.len()));
+    }
+    Ok(())
+}
+
+/// Policy requirements an open PR fails
+fn unmet(policy: &ReadyPolicy, verdict: &Verdict) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if policy.require_approval && !verdict.approved {
+        reasons.push("not approved".to_string());
+    }
+    if policy.require_checks && !verdict.checks_pass {
+        reasons.push("checks not passing".to_string());
+    }
+    if policy.require_mergeable && !verdict.mergeable {
+        reasons.push("not mergeable".to_string());
+    }
+    if let Some(max) = policy.max_behind {
+        match verdict.behind {
+            Some(behind) if behind > max => {
+                reasons.push(format!("{} commit(s) behind base (max {})", behind, max))
+            }
+            Some(_) => {}
+            None => reasons.push("can't tell how far behind base it is".to_string()),
+        }
+    }
+    if !policy.allow_unresolved_threads {
+        if let Some(threads @ 1..) = verdict.unresolved_threads {
+            reasons.push(format!("{} unresolved review thread(s)", threads));
+        }
+    }
+    reasons
+}
+
+/// Commits on `origin/<default>` missing from each branch, from the last fetch
+async fn commits_behind(branches: &[(RepoInfo, String)]) -> Vec<Option<usize>> {
+    let branches = branches.to_vec();
+    tokio::task::spawn_blocking(move || {
+        parallel_map(&branches, max_workers(), |(repo, branch)| {
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            let base = format!("origin/{}", repo.default_branch);
+            git_repo.revparse_single(&base).ok()?;
+            get_commits_between(&git_repo, branch, Some(&base))
+                .ok()
+                .map(|commits| commits.len())
+        })
+    })
+    .await
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unmet() {
+        let ready = Verdict {
+            approved: true,
+            checks_pass: true,
+            mergeable: true,
+            behind: Some(3),
+            unresolved_threads: Some(0),
+            ..Default::default()
+        };
+        let mut policy = ReadyPolicy::default();
+        assert!(unmet(&policy, &ready).is_empty());
+
+        let blocked = Verdict {
+            approved: false,
+            unresolved_threads: Some(2),
+            ..ready.clone()
+        };
+        assert_eq!(
+            unmet(&policy, &blocked),
+            vec!["not approved", "2 unresolved review thread(s)"]
+        );
+
+        policy.max_behind = Some(2);
+        policy.require_approval = false;
+        policy.allow_unresolved_threads = true;
+        assert_eq!(
+            unmet(&policy, &blocked),
+            vec!["3 commit(s) behind base (max 2)"]
+        );
+        // Unknown thread state doesn't block
+        let unknown = Verdict {
+            unresolved_threads: None,
+            ..ready
+        };
+        policy.allow_unresolved_threads = false;
+        policy.max_behind = None;
+        assert!(unmet(&policy, &unknown).is_empty());
+    }
+}
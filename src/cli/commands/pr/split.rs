@@ -0,0 +1,294 @@
+//! Splitting a branch into one PR per area
+//!
+//! With `--split-by-path` (or `--split-by-owner`), a repo whose branch
+//! touches several top-level directories (or CODEOWNERS owner sets) gets one
+//! branch and PR per group. Each split branch is a single commit on the
+//! merge base holding the branch's final version of that group's files, so
+//! the PRs are independent and can merge in any order.
+
+use crate::core::codeowners::CodeOwners;
+use crate::git::get_signature;
+use crate::git::signing::create_commit_with_signing;
+use crate::git::GitError;
+use git2::build::TreeUpdateBuilder;
+use git2::{FileMode, Oid, Repository};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How `gr pr create` partitions a repo's changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    /// First path component (files at the root form their own group)
+    Path,
+    /// CODEOWNERS owner set of each file
+    Owner,
+}
+
+/// Changed files that go into one split PR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitGroup {
+    /// Shown in the PR title, e.g. `docs` or `@acme/web`
+    pub key: String,
+    /// Appended to the branch name, e.g. `docs` or `acme-web`
+    pub slug: String,
+    /// Repo-relative paths
+    pub files: Vec<String>,
+}
+
+/// Partition `files` into groups, sorted by key
+pub fn group_files(
+    files: &[String],
+    split: SplitBy,
+    codeowners: Option<&CodeOwners>,
+) -> Vec<SplitGroup> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        let key = match split {
+            SplitBy::Path => match file.split_once('/') {
+                Some((dir, _)) => dir.to_string(),
+                None => "(root)".to_string(),
+            },
+            SplitBy::Owner => {
+                let owners = codeowners.map(|c| c.owners_for(file)).unwrap_or_default();
+                if owners.is_empty() {
+                    "(unowned)".to_string()
+                } else {
+                    owners.join(" ")
+                }
+            }
+        };
+        groups.entry(key).or_default().push(file.clone());
+    }
+
+    let mut slugs: Vec<String> = Vec::new();
+    groups
+        .into_iter()
+        .map(|(key, files)| {
+            let mut slug = slugify(&key);
+            if slugs.contains(&slug) {
+                slug = format!("{}-{}", slug, slugs.len() + 1);
+            }
+            slugs.push(slug.clone());
+            SplitGroup { key, slug, files }
+        })
+        .collect()
+}
+
+/// Paths that differ between two commits, without rename detection so a
+/// rename shows up as both its old and new path
+pub fn changed_paths(repo: &Repository, base: Oid, head: Oid) -> Result<Vec<String>, git2::Error> {
+    let base_tree = repo.find_commit(base)?.tree()?;
+    let head_tree = repo.find_commit(head)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)?;
+    let mut paths: Vec<String> = diff
+        .deltas()
+        .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+        .flatten()
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Point `refs/heads/<name>` at a commit on `base` that applies `head`'s
+/// version of `files` (deleting those `head` doesn't have)
+///
+/// An existing split branch from an earlier run is built on rather than
+/// replaced, so it only ever moves forward: the new commit goes on top of it,
+/// or it is left alone if it already matches. One that doesn't contain `base`
+/// is refused. The commit is signed when the repo config asks for it, unless
+/// `sign` is false.
+pub fn create_split_branch(
+    repo: &Repository,
+    base: Oid,
+    head: Oid,
+    files: &[String],
+    name: &str,
+    message: &str,
+    sign: bool,
+) -> Result<Oid, GitError> {
+    let refname = format!("refs/heads/{}", name);
+    let existing = repo.refname_to_id(&refname).ok();
+    let parent = match existing {
+        Some(tip) if tip == base || repo.graph_descendant_of(tip, base)? => {
+            repo.find_commit(tip)?
+        }
+        Some(_) => {
+            return Err(GitError::OperationFailed(format!(
+                "branch '{}' already exists and isn't based on {}; delete or rename it first",
+                name,
+                &base.to_string()[..7]
+            )))
+        }
+        None => repo.find_commit(base)?,
+    };
+    let head_tree = repo.find_commit(head)?.tree()?;
+
+    let mut update = TreeUpdateBuilder::new();
+    for file in files {
+        match head_tree.get_path(Path::new(file)) {
+            Ok(entry) => {
+                update.upsert(file.as_str(), entry.id(), file_mode(entry.filemode()));
+            }
+            Err(_) => {
+                update.remove(file.as_str());
+            }
+        }
+    }
+    let parent_tree = parent.tree()?;
+    let tree_id = update.create_updated(repo, &parent_tree)?;
+    if existing.is_some() && tree_id == parent_tree.id() {
+        return Ok(parent.id());
+    }
+    let tree = repo.find_tree(tree_id)?;
+
+    let signature = get_signature(repo)?;
+    let commit = create_commit_with_signing(
+        repo,
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &[&parent],
+        sign,
+    )?;
+    repo.reference(&refname, commit, true, "gr pr create: split branch")?;
+    Ok(commit)
+}
+
+fn file_mode(mode: i32) -> FileMode {
+    match mode {
+        0o100755 => FileMode::BlobExecutable,
+        0o120000 => FileMode::Link,
+        0o160000 => FileMode::Commit,
+        _ => FileMode::Blob,
+    }
+}
+
+/// `@acme/web-team` -> `acme-web-team`, `(root)` -> `root`
+fn slugify(key: &str) -> String {
+    let slug = key
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "misc".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_group_files() {
+        let files: Vec<String> = ["README.md", "api/a.rs", "api/b.rs", "web/app.ts"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        let groups = group_files(&files, SplitBy::Path, None);
+        let keys: Vec<(&str, &str, usize)> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.slug.as_str(), g.files.len()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![("(root)", "root", 1), ("api", "api", 2), ("web", "web", 1)]
+        );
+
+        let owners = CodeOwners::parse("/api/ @acme/backend\n/web/ @acme/frontend\n");
+        let groups = group_files(&files, SplitBy::Owner, Some(&owners));
+        let keys: Vec<&str> = groups.iter().map(|g| g.slug.as_str()).collect();
+        assert_eq!(keys, vec!["unowned", "acme-backend", "acme-frontend"]);
+    }
+
+    #[test]
+    fn test_create_split_branch() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::create_dir_all(path.join("api")).unwrap();
+        std::fs::create_dir_all(path.join("web")).unwrap();
+        std::fs::write(path.join("api/old.rs"), "old\n").unwrap();
+        std::fs::write(path.join("web/app.ts"), "v1\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Initial"]);
+        git(path, &["checkout", "-q", "-b", "feat"]);
+        git(path, &["mv", "api/old.rs", "api/new.rs"]);
+        std::fs::write(path.join("web/app.ts"), "v2\n").unwrap();
+        git(path, &["commit", "-q", "-am", "Change both"]);
+
+        let repo = Repository::open(path).unwrap();
+        let base = repo.revparse_single("main").unwrap().id();
+        let head = repo.revparse_single("feat").unwrap().id();
+        let files = changed_paths(&repo, base, head).unwrap();
+        assert_eq!(files, vec!["api/new.rs", "api/old.rs", "web/app.ts"]);
+
+        let groups = group_files(&files, SplitBy::Path, None);
+        create_split_branch(
+            &repo,
+            base,
+            head,
+            &groups[0].files,
+            "feat-api",
+            "api",
+            false,
+        )
+        .unwrap();
+        let changed = git(
+            path,
+            &["diff", "--name-status", "--no-renames", "main", "feat-api"],
+        );
+        assert_eq!(changed, "A\tapi/new.rs\nD\tapi/old.rs");
+        assert_eq!(git(path, &["rev-parse", "feat-api~1"]), base.to_string());
+    }
+
+    #[test]
+    fn test_create_split_branch_only_moves_forward() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::write(path.join("a.txt"), "v1\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Initial"]);
+        git(path, &["checkout", "-q", "-b", "feat"]);
+        std::fs::write(path.join("a.txt"), "v2\n").unwrap();
+        git(path, &["commit", "-q", "-am", "v2"]);
+
+        let repo = Repository::open(path).unwrap();
+        let base = repo.revparse_single("main").unwrap().id();
+        let files = vec!["a.txt".to_string()];
+        let split =
+            |head: Oid| create_split_branch(&repo, base, head, &files, "feat-a", "a", false);
+
+        let head = repo.revparse_single("feat").unwrap().id();
+        let first = split(head).unwrap();
+        // Re-running with nothing new leaves the branch alone
+        assert_eq!(split(head).unwrap(), first);
+
+        std::fs::write(path.join("a.txt"), "v3\n").unwrap();
+        git(path, &["commit", "-q", "-am", "v3"]);
+        let head = repo.revparse_single("feat").unwrap().id();
+        split(head).unwrap();
+        assert_eq!(git(path, &["rev-parse", "feat-a~1"]), first.to_string());
+        assert_eq!(git(path, &["show", "feat-a:a.txt"]), "v3");
+
+        // A split branch rewritten elsewhere is not clobbered
+        git(path, &["checkout", "-q", "--orphan", "other"]);
+        git(path, &["commit", "-q", "--allow-empty", "-m", "Unrelated"]);
+        git(path, &["branch", "-q", "-f", "feat-a", "other"]);
+        let other = git(path, &["rev-parse", "other"]);
+        assert!(split(head).is_err());
+        assert_eq!(git(path, &["rev-parse", "feat-a"]), other);
+    }
+}
@@ -1,10 +1,15 @@
 //! PR status command implementation
 
+use super::{
+    branch_sizes, branch_tickets, feature_branches, fetch_issues, fetch_readiness, is_oversized,
+};
 use crate::cli::output::{Output, Table};
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
-use crate::git::{get_current_branch, open_repo, path_exists};
-use crate::platform::{detect_platform, get_platform_adapter};
+use crate::git::branch::DiffStat;
+use crate::platform::traits::PlatformError;
+use crate::platform::{detect_platform, get_platform_adapter, MergeQueueEntry};
+use crate::util::concurrent_map;
 use std::path::PathBuf;
 
 /// Run the PR status command
@@ -34,71 +39,71 @@ pub async fn run_pr_status(
         checks_pass: bool,
         mergeable: bool,
         url: Option<String>,
+        merge_queue: Option<MergeQueueEntry>,
+        /// `None` where the platform doesn't report thread resolution
+        unresolved_threads: Option<usize>,
+        size: Option<DiffStat>,
+        oversized: bool,
+        ticket: Option<String>,
+        /// The ticket's state in the configured tracker
+        ticket_state: Option<String>,
     }
 
-    let mut statuses: Vec<PRStatusInfo> = Vec::new();
-
-    for repo in &repos {
-        if !path_exists(&repo.absolute_path) {
-            continue;
-        }
-
-        let git_repo = match open_repo(&repo.absolute_path) {
-            Ok(r) => r,
-            Err(_) => continue,
-        };
-
-        let branch = match get_current_branch(&git_repo) {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-
-        // Skip if on default branch
-        if branch == repo.default_branch {
-            continue;
-        }
+    let branches = feature_branches(repos).await;
+    let sizes = branch_sizes(&branches).await;
+    let limit = manifest.settings.pr_size_limit;
+    let tickets = branch_tickets(workspace_root, manifest, &branches);
+    let lookups = concurrent_map(
+        &branches,
+        manifest.settings.api_concurrency,
+        |(repo, branch)| async move {
+            let platform = get_platform_adapter(detect_platform(&repo.url), None);
+            match platform
+                .find_pr_by_branch(&repo.owner, &repo.repo, branch)
+                .await?
+            {
+                Some(pr) => {
+                    let (readiness, queue, threads) = tokio::join!(
+                        fetch_readiness(platform.as_ref(), repo, branch, pr.number),
+                        platform.get_merge_queue_status(&repo.owner, &repo.repo, pr.number),
+                        platform.get_unresolved_review_threads(&repo.owner, &repo.repo, pr.number),
+                    );
+                    let entry = queue.ok().and_then(|q| q.entry);
+                    Ok(Some((pr, readiness, entry, threads.ok().flatten())))
+                }
+                None => Ok::<_, PlatformError>(None),
+            }
+        },
+    );
+    let (lookups, issues) = tokio::join!(lookups, fetch_issues(manifest, &tickets));
 
-        let platform_type = detect_platform(&repo.url);
-        let platform = get_platform_adapter(platform_type, None);
-
-        match platform
-            .find_pr_by_branch(&repo.owner, &repo.repo, &branch)
-            .await
-        {
-            Ok(Some(pr)) => {
-                // Get PR details including approval and mergeable status
-                let (approved, mergeable) = match platform
-                    .get_pull_request(&repo.owner, &repo.repo, pr.number)
-                    .await
-                {
-                    Ok(full_pr) => {
-                        let is_approved = platform
-                            .is_pull_request_approved(&repo.owner, &repo.repo, pr.number)
-                            .await
-                            .unwrap_or(false);
-                        (is_approved, full_pr.mergeable.unwrap_or(false))
-                    }
-                    Err(_) => (false, false),
-                };
-
-                // Get status checks
-                let checks_pass = match platform
-                    .get_status_checks(&repo.owner, &repo.repo, &branch)
-                    .await
-                {
-                    Ok(status) => status.state == crate::platform::CheckState::Success,
-                    Err(_) => false,
-                };
+    let mut statuses: Vec<PRStatusInfo> = Vec::new();
 
+    for ((((repo, branch), lookup), size), ticket) in
+        branches.iter().zip(lookups).zip(sizes).zip(tickets)
+    {
+        let oversized = size.is_some_and(|stat| is_oversized(&stat, limit));
+        let ticket_state = ticket
+            .as_ref()
+            .and_then(|ticket| issues.get(ticket)?.as_ref().ok())
+            .map(|issue| issue.state.clone());
+        match lookup {
+            Ok(Some((pr, readiness, merge_queue, unresolved_threads))) => {
                 statuses.push(PRStatusInfo {
                     repo: repo.name.clone(),
                     branch: branch.clone(),
                     pr_number: Some(pr.number),
                     state: "open".to_string(),
-                    approved,
-                    checks_pass,
-                    mergeable,
-                    url: Some(pr.url.clone()),
+                    approved: readiness.approved,
+                    checks_pass: readiness.checks_pass,
+                    mergeable: readiness.mergeable,
+                    url: Some(pr.url),
+                    merge_queue,
+                    unresolved_threads,
+                    size,
+                    oversized,
+                    ticket,
+                    ticket_state,
                 });
             }
             Ok(None) => {
@@ -111,6 +116,12 @@ pub async fn run_pr_status(
                     checks_pass: false,
                     mergeable: false,
                     url: None,
+                    merge_queue: None,
+                    unresolved_threads: None,
+                    size,
+                    oversized,
+                    ticket,
+                    ticket_state,
                 });
             }
             Err(e) => {
@@ -122,7 +133,7 @@ pub async fn run_pr_status(
     }
 
     if json_output {
-        println!("{}", serde_json::to_string_pretty(&statuses)?);
+        Output::json(&statuses)?;
         return Ok(());
     }
 
@@ -132,32 +143,85 @@ pub async fn run_pr_status(
     }
 
     // Display table
-    let mut table = Table::new(vec![
+    // Only show the queue column when some PR is queued
+    let show_queue = statuses.iter().any(|s| s.merge_queue.is_some());
+    let show_ticket = statuses.iter().any(|s| s.ticket.is_some());
+    let mut headers = vec![
         "Repo",
         "PR#",
         "State",
         "Approved",
         "Checks",
         "Mergeable",
-    ]);
+        "Threads",
+        "Size",
+    ];
+    if show_queue {
+        headers.push("Queue");
+    }
+    if show_ticket {
+        headers.push("Ticket");
+    }
+    let mut table = Table::new(headers);
 
     for status in &statuses {
         let pr_num = status
             .pr_number
             .map(|n| format!("#{}", n))
             .unwrap_or_else(|| "-".to_string());
-        let approved = if status.approved { "✓" } else { "✗" };
-        let checks = if status.checks_pass { "✓" } else { "✗" };
-        let mergeable = if status.mergeable { "✓" } else { "✗" };
+        let approved = Output::mark(status.approved);
+        let checks = Output::mark(status.checks_pass);
+        let mergeable = Output::mark(status.mergeable);
+
+        let queue = status
+            .merge_queue
+            .as_ref()
+            .map(|entry| entry.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        let threads = match status.unresolved_threads {
+            Some(0) => Output::mark(true).to_string(),
+            Some(n) => format!("{} unresolved", n),
+            None => "-".to_string(),
+        };
 
-        table.add_row(vec![
-            &status.repo,
+        let size = match status.size {
+            Some(stat) => format!(
+                "+{} -{} ({}){}",
+                stat.additions,
+                stat.deletions,
+                stat.files,
+                if status.oversized {
+                    Output::label(" ⚠", " OVERSIZED")
+                } else {
+                    ""
+                }
+            ),
+            None => "-".to_string(),
+        };
+
+        let mut row = vec![
+            status.repo.as_str(),
             &pr_num,
             &status.state,
             approved,
             checks,
             mergeable,
-        ]);
+            &threads,
+            &size,
+        ];
+        let ticket = match (&status.ticket, &status.ticket_state) {
+            (Some(ticket), Some(state)) => format!("{} ({})", ticket, state),
+            (Some(ticket), None) => ticket.clone(),
+            (None, _) => "-".to_string(),
+        };
+        if show_queue {
+            row.push(&queue);
+        }
+        if show_ticket {
+            row.push(&ticket);
+        }
+        table.add_row(row);
     }
 
     table.print();
@@ -170,6 +234,39 @@ pub async fn run_pr_status(
         .filter(|s| s.pr_number.is_some() && s.approved && s.checks_pass && s.mergeable)
         .count();
 
+    let oversized: Vec<&str> = statuses
+        .iter()
+        .filter(|s| s.oversized)
+        .map(|s| s.repo.as_str())
+        .collect();
+    if !oversized.is_empty() {
+        Output::warning(&format!(
+            "Over the {}-line size limit: {} (consider splitting for review)",
+            limit,
+            oversized.join(", ")
+        ));
+    }
+
+    let unresolved: Vec<String> = statuses
+        .iter()
+        .filter_map(|s| match s.unresolved_threads {
+            Some(n) if n > 0 => Some(format!("{} ({})", s.repo, n)),
+            _ => None,
+        })
+        .collect();
+    if !unresolved.is_empty() {
+        Output::warning(&format!(
+            "Unresolved review threads (block merging where conversation resolution is required): {}",
+            unresolved.join(", ")
+        ));
+    }
+
+    for (ticket, issue) in &issues {
+        if let Err(e) = issue {
+            Output::warning(&format!("Could not read ticket {}: {}", ticket, e));
+        }
+    }
+
     if ready == with_prs && with_prs > 0 {
         Output::success(&format!("All {} PRs ready to merge!", with_prs));
     } else if with_prs > 0 {
@@ -0,0 +1,166 @@
+//! Merge train planning
+//!
+//! Orders a PR group for merging: a repo's PR goes after the PRs of repos it
+//! depends on (`depends_on`, followed through repos outside the group), and
+//! otherwise the PRs least behind their base go first, while their checks
+//! still reflect the base. Branches can be brought up to date with their
+//! base right before they merge.
+
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::core::manifest::Manifest;
+use crate::git::{fetch_remote, get_remote_tracking_oid, open_repo};
+use crate::util::profile::ProfiledCommand;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+/// Repos among `group` that `name` depends on, directly or through repos
+/// outside the group
+pub(super) fn group_dependencies(manifest: &Manifest, name: &str, group: &[&str]) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending = vec![name.to_string()];
+    while let Some(current) = pending.pop() {
+        let deps = manifest
+            .repos
+            .get(&current)
+            .and_then(|config| config.depends_on.clone())
+            .unwrap_or_default();
+        for dep in deps {
+            if !seen.insert(dep.clone()) {
+                continue;
+            }
+            if group.contains(&dep.as_str()) {
+                found.push(dep.clone());
+            }
+            pending.push(dep);
+        }
+    }
+    found.sort();
+    found
+}
+
+/// The merge order as indices into `names`: dependencies first, then the
+/// least stale (unknown staleness last), then by name
+pub(super) fn merge_order(
+    names: &[String],
+    behind: &[Option<usize>],
+    deps: &[Vec<String>],
+) -> Vec<usize> {
+    let mut order: Vec<usize> = Vec::new();
+    while order.len() < names.len() {
+        let placed: Vec<&str> = order.iter().map(|&i| names[i].as_str()).collect();
+        let next = (0..names.len())
+            .filter(|i| !order.contains(i))
+            .filter(|&i| deps[i].iter().all(|dep| placed.contains(&dep.as_str())))
+            .min_by_key(|&i| (behind[i].unwrap_or(usize::MAX), &names[i]));
+        match next {
+            Some(i) => order.push(i),
+            // Manifest validation rules out cycles; keep whatever is left as is
+            None => {
+                let rest: Vec<usize> = (0..names.len()).filter(|i| !order.contains(i)).collect();
+                order.extend(rest);
+            }
+        }
+    }
+    order
+}
+
+/// Commits on `base` (origin's, once fetched) that `branch` doesn't have
+pub(super) fn commits_behind(path: &Path, branch: &str, base: &str) -> Option<usize> {
+    let repo = open_repo(path).ok()?;
+    let branch_oid = repo
+        .revparse_single(&format!("refs/heads/{}", branch))
+        .ok()?
+        .id();
+    let base_oid = match get_remote_tracking_oid(&repo, "origin", base) {
+        Some(oid) => oid,
+        None => repo.revparse_single(base).ok()?.id(),
+    };
+    let (_, behind) = repo.graph_ahead_behind(branch_oid, base_oid).ok()?;
+    Some(behind)
+}
+
+/// Fetch, merge `origin/<base>` into the checked-out `branch` and push it;
+/// false if it was already up to date
+///
+/// A conflicting merge is aborted, leaving the branch as it was.
+pub(super) fn resync_branch(
+    workspace_root: &Path,
+    repo_name: &str,
+    path: &Path,
+    branch: &str,
+    base: &str,
+) -> anyhow::Result<bool> {
+    let repo = open_repo(path)?;
+    fetch_remote(&repo, "origin")?;
+    if commits_behind(path, branch, base) == Some(0) {
+        return Ok(false);
+    }
+
+    let upstream = format!("origin/{}", base);
+    let output = Command::new("git")
+        .args(["merge", "--no-edit", &upstream])
+        .current_dir(path)
+        .timed_output()?;
+    if !output.status.success() {
+        let _ = Command::new("git")
+            .args(["merge", "--abort"])
+            .current_dir(path)
+            .timed_output();
+        anyhow::bail!(
+            "merging {} into {} failed; update the branch by hand",
+            upstream,
+            branch
+        );
+    }
+    let push = PushMode::Normal {
+        set_upstream: false,
+    };
+    push_audited(workspace_root, repo_name, &repo, branch, push)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_order() {
+        let manifest = Manifest::parse(
+            r#"
+version: 1
+repos:
+  app:
+    url: git@github.com:acme/app.git
+    path: app
+    depends_on: [sdk]
+  sdk:
+    url: git@github.com:acme/sdk.git
+    path: sdk
+    depends_on: [core]
+  core:
+    url: git@github.com:acme/core.git
+    path: core
+  docs:
+    url: git@github.com:acme/docs.git
+    path: docs
+"#,
+        )
+        .unwrap();
+
+        // `sdk` has no PR, but `app` still follows `core` through it
+        let names: Vec<String> = ["app", "core", "docs"].map(String::from).to_vec();
+        let group: Vec<&str> = names.iter().map(String::as_str).collect();
+        let deps: Vec<Vec<String>> = names
+            .iter()
+            .map(|name| group_dependencies(&manifest, name, &group))
+            .collect();
+        assert_eq!(deps[0], vec!["core".to_string()]);
+
+        let order = merge_order(&names, &[Some(0), Some(5), None], &deps);
+        assert_eq!(order, vec![1, 0, 2]);
+        let order = merge_order(&names, &[Some(0), Some(5), Some(1)], &deps);
+        assert_eq!(order, vec![2, 1, 0]);
+    }
+}
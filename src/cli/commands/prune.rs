@@ -0,0 +1,273 @@
+//! Prune command implementation
+//!
+//! Removes checkouts of repos the workspace no longer manages: repos dropped
+//! from the manifest, or left behind by a profile switch or a path change.
+//! Sync records every repo it clones or pulls in the state file, so only
+//! checkouts gitgrip created are ever candidates; repos with work that isn't
+//! on a remote are kept unless forced.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::resolve_repo_path;
+use crate::core::state::StateFile;
+use crate::git::status::has_uncommitted_changes;
+use crate::git::{open_repo, path_exists};
+use chrono::Local;
+use git2::Repository;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Settings for `gr prune`
+#[derive(Debug, Clone, Default)]
+pub struct PruneOptions {
+    /// Only list what would be pruned
+    pub dry_run: bool,
+    /// Move checkouts to `.gitgrip/pruned/` instead of deleting them
+    pub archive: bool,
+    /// Prune repos with unpushed or uncommitted work too
+    pub force: bool,
+}
+
+/// A checkout the workspace no longer manages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    /// Repository name it was synced under
+    pub name: String,
+    /// Path relative to the workspace root
+    pub path: String,
+    /// Why it isn't safe to delete, if it isn't
+    pub unsafe_reason: Option<String>,
+}
+
+/// Find checkouts sync created that the manifest no longer has
+///
+/// Entries whose directory is already gone are dropped from `state`.
+pub fn find_orphans(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    state: &mut StateFile,
+) -> Vec<Orphan> {
+    let managed: HashSet<PathBuf> = manifest
+        .repos
+        .values()
+        .map(|config| resolve_repo_path(workspace_root, &config.path))
+        .collect();
+
+    state
+        .managed_repos
+        .retain(|_, path| path_exists(resolve_repo_path(workspace_root, path).join(".git")));
+
+    let mut orphans: Vec<Orphan> = state
+        .managed_repos
+        .iter()
+        .filter(|(_, path)| !managed.contains(&resolve_repo_path(workspace_root, path)))
+        .map(|(name, path)| Orphan {
+            name: name.clone(),
+            path: path.clone(),
+            unsafe_reason: unpushed_work(&resolve_repo_path(workspace_root, path)),
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+    orphans
+}
+
+/// Run the prune command
+pub fn run_prune(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    options: &PruneOptions,
+) -> anyhow::Result<()> {
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    let orphans = find_orphans(workspace_root, manifest, &mut state);
+
+    if orphans.is_empty() {
+        state.save(&state_path)?;
+        Output::info("No repos to prune.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(vec!["Repo", "Path", "Status"]);
+    for orphan in &orphans {
+        let status = orphan.unsafe_reason.as_deref().unwrap_or("fully pushed");
+        table.add_row(vec![&orphan.name, &orphan.path, status]);
+    }
+    table.print();
+    println!();
+
+    if options.dry_run {
+        Output::info(&format!(
+            "Dry run: {} repo(s) would be {}",
+            orphans
+                .iter()
+                .filter(|o| options.force || o.unsafe_reason.is_none())
+                .count(),
+            if options.archive {
+                "archived"
+            } else {
+                "deleted"
+            }
+        ));
+        return Ok(());
+    }
+
+    let mut pruned = 0;
+    let mut kept = 0;
+    for orphan in &orphans {
+        if let (Some(reason), false) = (&orphan.unsafe_reason, options.force) {
+            Output::warning(&format!("{}: kept - {}", orphan.name, reason));
+            kept += 1;
+            continue;
+        }
+
+        let path = resolve_repo_path(workspace_root, &orphan.path);
+        let result = if options.archive {
+            archive_checkout(workspace_root, &orphan.name, &path)
+                .map(|dest| format!("archived to {}", dest.display()))
+        } else {
+            std::fs::remove_dir_all(&path)
+                .map(|()| "deleted".to_string())
+                .map_err(Into::into)
+        };
+        match result {
+            Ok(message) => {
+                Output::success(&format!("{}: {}", orphan.name, message));
+                state.managed_repos.remove(&orphan.name);
+                remove_empty_parents(workspace_root, &path);
+                pruned += 1;
+            }
+            Err(e) => {
+                Output::error(&format!("{}: {}", orphan.name, e));
+                kept += 1;
+            }
+        }
+    }
+    state.save(&state_path)?;
+
+    println!();
+    if kept == 0 {
+        Output::success(&format!("Pruned {} repo(s).", pruned));
+    } else {
+        Output::warning(&format!("Pruned {} repo(s), kept {}.", pruned, kept));
+        Output::info("Push or discard their work, or pass --force (with --archive to keep a copy)");
+    }
+    Ok(())
+}
+
+/// Describe work in a checkout that only exists locally, if any
+fn unpushed_work(path: &Path) -> Option<String> {
+    let repo = match open_repo(path) {
+        Ok(repo) => repo,
+        Err(e) => return Some(e.to_string()),
+    };
+
+    let mut reasons = Vec::new();
+    if has_uncommitted_changes(&repo).unwrap_or(true) {
+        reasons.push("uncommitted changes".to_string());
+    }
+    match unpushed_commits(&repo) {
+        Ok(0) => {}
+        Ok(n) => reasons.push(format!("{} unpushed commit(s)", n)),
+        Err(e) => reasons.push(format!("could not check commits: {}", e.message())),
+    }
+    let stashes = repo.reflog("refs/stash").map(|log| log.len()).unwrap_or(0);
+    if stashes > 0 {
+        reasons.push(format!("{} stash(es)", stashes));
+    }
+
+    (!reasons.is_empty()).then(|| reasons.join(", "))
+}
+
+/// Commits on local branches or HEAD that no remote-tracking branch has
+fn unpushed_commits(repo: &Repository) -> Result<usize, git2::Error> {
+    let mut walk = repo.revwalk()?;
+    walk.push_glob("refs/heads/*")?;
+    if repo.head().is_ok() {
+        walk.push_head()?;
+    }
+    walk.hide_glob("refs/remotes/*")?;
+    Ok(walk.count())
+}
+
+/// Move a checkout under `.gitgrip/pruned/`, timestamped if the name is taken
+fn archive_checkout(workspace_root: &Path, name: &str, path: &Path) -> anyhow::Result<PathBuf> {
+    let dir = workspace_root.join(".gitgrip").join("pruned");
+    std::fs::create_dir_all(&dir)?;
+    let mut dest = dir.join(name);
+    if dest.exists() {
+        dest = dir.join(format!("{}-{}", name, Local::now().format("%Y%m%d-%H%M%S")));
+    }
+    std::fs::rename(path, &dest)?;
+    Ok(dest)
+}
+
+/// Drop directories a pruned checkout leaves empty, up to the workspace root
+fn remove_empty_parents(workspace_root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(d) = dir {
+        if d == workspace_root || std::fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_orphans() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let upstream = root.join("upstream");
+        init_repo(&upstream);
+        git(
+            &upstream,
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        );
+        for name in ["api", "old", "wip"] {
+            git(root, &["clone", "-q", upstream.to_str().unwrap(), name]);
+        }
+        let wip = root.join("wip");
+        git(&wip, &["config", "user.name", "Test User"]);
+        git(&wip, &["config", "user.email", "test@example.com"]);
+        git(&wip, &["commit", "-q", "--allow-empty", "-m", "Local work"]);
+
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: https://github.com/acme/api.git
+    path: api
+"#,
+        )
+        .unwrap();
+        let mut state = StateFile::default();
+        for name in ["api", "old", "wip", "gone"] {
+            state
+                .managed_repos
+                .insert(name.to_string(), name.to_string());
+        }
+
+        let orphans = find_orphans(root, &manifest, &mut state);
+        assert_eq!(
+            orphans,
+            vec![
+                Orphan {
+                    name: "old".to_string(),
+                    path: "old".to_string(),
+                    unsafe_reason: None,
+                },
+                Orphan {
+                    name: "wip".to_string(),
+                    path: "wip".to_string(),
+                    unsafe_reason: Some("1 unpushed commit(s)".to_string()),
+                },
+            ]
+        );
+        assert!(!state.managed_repos.contains_key("gone"));
+    }
+}
@@ -1,24 +1,44 @@
 //! Push command implementation
 
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::cli::i18n::msg;
 use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
-use crate::git::remote::{force_push_branch, push_branch};
-use crate::git::{get_current_branch, open_repo, path_exists};
-use git2::Repository;
-use std::path::PathBuf;
+use crate::core::secrets::SecretScanner;
+use crate::git::lfs::large_blobs;
+use crate::git::remote::get_remote_tracking_oid;
+use crate::git::{get_commits_between, get_current_branch, open_repo, path_exists};
+use crate::util::disk::{format_bytes, parse_bytes};
+use git2::{Oid, Repository};
+use std::path::{Path, PathBuf};
 
 /// Run the push command
+///
+/// With `force_with_lease`, rewritten branches are force pushed only if the
+/// remote branch is still where it was at the last fetch. With `dry_run`,
+/// nothing is pushed; each repo's outgoing commits are listed instead.
+/// Force pushes to protected branches are refused unless `allow_protected`.
+/// With `verify`, outgoing commits are first scanned for secrets and large
+/// files, as configured by `settings.secret_scan` and `settings.large_files`.
+#[allow(clippy::too_many_arguments)]
 pub fn run_push(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     set_upstream: bool,
     force: bool,
+    force_with_lease: bool,
+    dry_run: bool,
+    allow_protected: bool,
+    verify: bool,
 ) -> anyhow::Result<()> {
-    if force {
-        Output::header("Force pushing changes...");
+    let force = force || force_with_lease;
+    if dry_run {
+        Output::header(&msg!("push.preview"));
+    } else if force {
+        Output::header(&msg!("push.force_pushing"));
     } else {
-        Output::header("Pushing changes...");
+        Output::header(&msg!("push.pushing"));
     }
     println!();
 
@@ -28,6 +48,46 @@ pub fn run_push(
         .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
         .collect();
 
+    if force && !dry_run {
+        let targets: Vec<(&RepoInfo, String)> = repos
+            .iter()
+            .filter(|repo| path_exists(&repo.absolute_path))
+            .filter_map(|repo| {
+                let git_repo = open_repo(&repo.absolute_path).ok()?;
+                let branch = get_current_branch(&git_repo).ok()?;
+                has_commits_to_push(&git_repo, &branch)
+                    .unwrap_or(false)
+                    .then_some((repo, branch))
+            })
+            .collect();
+        manifest.settings.ensure_unprotected(
+            "force push",
+            targets.iter().map(|(repo, branch)| {
+                (
+                    repo.name.as_str(),
+                    branch.as_str(),
+                    repo.default_branch.as_str(),
+                )
+            }),
+            allow_protected,
+        )?;
+    }
+
+    if verify && !dry_run {
+        let targets: Vec<(&RepoInfo, String)> = repos
+            .iter()
+            .filter(|repo| path_exists(&repo.absolute_path))
+            .filter_map(|repo| {
+                let git_repo = open_repo(&repo.absolute_path).ok()?;
+                let branch = get_current_branch(&git_repo).ok()?;
+                has_commits_to_push(&git_repo, &branch)
+                    .unwrap_or(false)
+                    .then_some((repo, branch))
+            })
+            .collect();
+        check_outgoing(workspace_root, manifest, &targets)?;
+    }
+
     let mut success_count = 0;
     let mut skip_count = 0;
     let mut error_count = 0;
@@ -51,34 +111,63 @@ pub fn run_push(
 
                 // Check if there's anything to push
                 if !has_commits_to_push(&git_repo, &branch)? {
-                    Output::info(&format!("{}: nothing to push", repo.name));
+                    Output::info(&msg!("push.nothing", repo = repo.name));
                     skip_count += 1;
                     continue;
                 }
 
-                let action = if force { "Force pushing" } else { "Pushing" };
-                let spinner = Output::spinner(&format!("{} {}...", action, repo.name));
+                if dry_run {
+                    match plan_push(&git_repo, &branch, &repo.default_branch) {
+                        Ok(plan) => {
+                            print_plan(&repo.name, &branch, &plan, force);
+                            success_count += 1;
+                        }
+                        Err(e) => {
+                            Output::error(&format!("{}: {}", repo.name, e));
+                            error_count += 1;
+                        }
+                    }
+                    continue;
+                }
+
+                let spinner = Output::spinner(&if force {
+                    msg!("push.force_pushing_repo", repo = repo.name)
+                } else {
+                    msg!("push.pushing_repo", repo = repo.name)
+                });
 
-                let result = if force {
-                    force_push_branch(&git_repo, &branch, "origin")
+                let mode = if force_with_lease {
+                    // The remote-tracking ref is the tip we last saw; a branch
+                    // that was never pushed has nothing to overwrite
+                    match get_remote_tracking_oid(&git_repo, "origin", &branch) {
+                        Some(expected) => PushMode::ForceWithLease(expected),
+                        None => PushMode::Normal { set_upstream },
+                    }
+                } else if force {
+                    PushMode::Force
                 } else {
-                    push_branch(&git_repo, &branch, "origin", set_upstream)
+                    PushMode::Normal { set_upstream }
                 };
+                let result = push_audited(workspace_root, &repo.name, &git_repo, &branch, mode);
 
                 match result {
                     Ok(()) => {
-                        let msg = if force {
-                            format!("{}: force pushed", repo.name)
+                        let done = if force {
+                            msg!("push.force_pushed", repo = repo.name)
                         } else if set_upstream {
-                            format!("{}: pushed and set upstream", repo.name)
+                            msg!("push.pushed_upstream", repo = repo.name)
                         } else {
-                            format!("{}: pushed", repo.name)
+                            msg!("push.pushed", repo = repo.name)
                         };
-                        spinner.finish_with_message(msg);
+                        spinner.finish_with_message(done);
                         success_count += 1;
                     }
                     Err(e) => {
-                        spinner.finish_with_message(format!("{}: failed - {}", repo.name, e));
+                        spinner.finish_with_message(msg!(
+                            "push.failed",
+                            repo = repo.name,
+                            error = e
+                        ));
                         error_count += 1;
                     }
                 }
@@ -91,35 +180,257 @@ pub fn run_push(
     }
 
     println!();
-    let action = if force { "Force pushed" } else { "Pushed" };
+    if dry_run {
+        if error_count > 0 {
+            Output::warning(&msg!(
+                "push.dry_run_failed",
+                count = success_count,
+                failed = error_count
+            ));
+        } else {
+            println!("{}", msg!("push.dry_run", count = success_count));
+        }
+        return Ok(());
+    }
+
     if error_count == 0 {
         if success_count > 0 {
-            Output::success(&format!(
-                "{} {} repo(s){}.",
-                action,
-                success_count,
-                if skip_count > 0 {
-                    format!(", {} had nothing to push", skip_count)
-                } else {
-                    String::new()
-                }
-            ));
+            Output::success(&match (force, skip_count > 0) {
+                (true, true) => msg!(
+                    "push.summary_force_skipped",
+                    count = success_count,
+                    skipped = skip_count
+                ),
+                (true, false) => msg!("push.summary_force", count = success_count),
+                (false, true) => msg!(
+                    "push.summary_skipped",
+                    count = success_count,
+                    skipped = skip_count
+                ),
+                (false, false) => msg!("push.summary", count = success_count),
+            });
         } else {
-            println!("Nothing to push.");
+            println!("{}", msg!("push.none"));
         }
+    } else if force {
+        Output::warning(&msg!(
+            "push.summary_force_failed",
+            count = success_count,
+            failed = error_count,
+            skipped = skip_count
+        ));
     } else {
-        Output::warning(&format!(
-            "{} {}, {} failed, {} skipped",
-            success_count,
-            action.to_lowercase(),
-            error_count,
-            skip_count
+        Output::warning(&msg!(
+            "push.summary_failed",
+            count = success_count,
+            failed = error_count,
+            skipped = skip_count
         ));
     }
 
     Ok(())
 }
 
+/// How a push would update the remote branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PushKind {
+    /// The remote branch doesn't exist yet
+    NewBranch,
+    /// The remote branch is an ancestor of the local one
+    FastForward,
+    /// The branches diverged; the remote commits would be overwritten
+    Forced,
+}
+
+/// What pushing a branch would send
+#[derive(Debug)]
+struct PushPlan {
+    kind: PushKind,
+    /// Outgoing commits, newest first, as `(short sha, subject)`
+    commits: Vec<(String, String)>,
+    /// Remote commits a forced push would drop
+    overwritten: usize,
+}
+
+/// Pre-push checks on the outgoing commits of `targets` (`(repo, branch)`):
+/// the secret scan and the large file limit, each if configured
+pub fn check_outgoing(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    targets: &[(&RepoInfo, String)],
+) -> anyhow::Result<()> {
+    if manifest.settings.secret_scan.enabled {
+        check_secrets(workspace_root, manifest, targets)?;
+    }
+    if manifest.settings.large_files.max_size.is_some() {
+        check_large_files(manifest, targets)?;
+    }
+    Ok(())
+}
+
+/// Refuse to push if the outgoing commits of `targets` add likely credentials
+fn check_secrets(
+    workspace_root: &Path,
+    manifest: &Manifest,
+    targets: &[(&RepoInfo, String)],
+) -> anyhow::Result<()> {
+    let settings = &manifest.settings.secret_scan;
+    let scanner = SecretScanner::load(settings, workspace_root)?;
+
+    let mut count = 0;
+    for (repo, branch) in targets {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let base = outgoing_base(&git_repo, branch, &repo.default_branch);
+        for finding in scanner.scan_commits(&git_repo, &base, branch)? {
+            Output::error(&format!(
+                "{}: {}:{} ({}) {} {}",
+                repo.name,
+                finding.path,
+                finding.line,
+                finding.commit,
+                finding.rule,
+                finding.redacted()
+            ));
+            count += 1;
+        }
+    }
+    if count > 0 {
+        anyhow::bail!(
+            "Push blocked: {} likely secret(s) in outgoing commits. Remove them, \
+             add a pattern to {}, or pass --no-verify",
+            count,
+            settings.allowlist
+        );
+    }
+    Ok(())
+}
+
+/// Warn about, or with `settings.large_files.block` refuse, outgoing files
+/// over `settings.large_files.max_size`
+fn check_large_files(manifest: &Manifest, targets: &[(&RepoInfo, String)]) -> anyhow::Result<()> {
+    let settings = &manifest.settings.large_files;
+    let Some(max_size) = settings.max_size.as_deref() else {
+        return Ok(());
+    };
+    let limit = parse_bytes(max_size).map_err(|e| anyhow::anyhow!(e))?;
+
+    let mut count = 0;
+    for (repo, branch) in targets {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let base = outgoing_base(&git_repo, branch, &repo.default_branch);
+        for blob in large_blobs(&git_repo, &base, branch, limit)? {
+            let message = msg!(
+                "push.large_file",
+                repo = repo.name,
+                path = blob.path,
+                size = format_bytes(blob.size),
+                commit = blob.commit,
+                limit = format_bytes(limit)
+            );
+            if settings.block {
+                Output::error(&message);
+            } else {
+                Output::warning(&message);
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Ok(());
+    }
+    Output::info(&msg!("push.large_file_hint"));
+    if settings.block {
+        anyhow::bail!(
+            "Push blocked: {} file(s) over {}. Move them to Git LFS or pass --no-verify",
+            count,
+            format_bytes(limit)
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// Where the commits pushing `branch` would send start: its remote-tracking
+/// branch, or for a new branch, the default branch
+fn outgoing_base(repo: &Repository, branch: &str, default_branch: &str) -> String {
+    let remote_ref = format!("origin/{}", branch);
+    if repo
+        .find_reference(&format!("refs/remotes/{}", remote_ref))
+        .is_ok()
+    {
+        return remote_ref;
+    }
+    let base = format!("origin/{}", default_branch);
+    if repo
+        .find_reference(&format!("refs/remotes/{}", base))
+        .is_ok()
+    {
+        base
+    } else {
+        default_branch.to_string()
+    }
+}
+
+/// Work out what pushing `branch` to origin would do, from the remote-tracking refs
+fn plan_push(repo: &Repository, branch: &str, default_branch: &str) -> anyhow::Result<PushPlan> {
+    let base = outgoing_base(repo, branch, default_branch);
+    let (kind, overwritten) = if base == format!("origin/{}", branch) {
+        let overwritten = get_commits_between(repo, branch, Some(&base))?.len();
+        let kind = if overwritten == 0 {
+            PushKind::FastForward
+        } else {
+            PushKind::Forced
+        };
+        (kind, overwritten)
+    } else {
+        // A new branch sends whatever isn't on the default branch yet
+        (PushKind::NewBranch, 0)
+    };
+
+    let commits = get_commits_between(repo, &base, Some(branch))?
+        .iter()
+        .map(|sha| {
+            let subject = Oid::from_str(sha)
+                .and_then(|oid| repo.find_commit(oid))
+                .ok()
+                .and_then(|commit| commit.summary().map(str::to_string))
+                .unwrap_or_default();
+            (sha[..7.min(sha.len())].to_string(), subject)
+        })
+        .collect();
+
+    Ok(PushPlan {
+        kind,
+        commits,
+        overwritten,
+    })
+}
+
+fn print_plan(repo_name: &str, branch: &str, plan: &PushPlan, force: bool) {
+    let kind = match plan.kind {
+        PushKind::NewBranch => msg!("push.plan_new_branch"),
+        PushKind::FastForward => msg!("push.plan_fast_forward"),
+        PushKind::Forced => msg!("push.plan_forced", count = plan.overwritten),
+    };
+    println!(
+        "{}",
+        msg!(
+            "push.plan",
+            repo = Output::repo_name(repo_name),
+            arrow = Output::arrow(),
+            branch = Output::branch_name(&format!("origin/{}", branch)),
+            kind = kind,
+            count = plan.commits.len()
+        )
+    );
+    for (sha, subject) in &plan.commits {
+        Output::list_item(&format!("{} {}", sha, subject));
+    }
+    if plan.kind == PushKind::Forced && !force {
+        Output::warning(&msg!("push.plan_rejected", repo = repo_name));
+    }
+}
+
 /// Check if branch has commits that aren't on the remote
 fn has_commits_to_push(repo: &Repository, branch: &str) -> anyhow::Result<bool> {
     // Try to find the remote tracking branch
@@ -151,7 +462,7 @@ fn has_commits_to_push(repo: &Repository, branch: &str) -> anyhow::Result<bool>
     }
 
     // Check if local is ahead of remote
-    let (ahead, _behind) = repo.graph_ahead_behind(local_oid, remote_oid)?;
+    let (ahead, _behind) = crate::git::cached_graph_ahead_behind(repo, local_oid, remote_oid)?;
     Ok(ahead > 0)
 }
 
@@ -190,6 +501,48 @@ mod tests {
         (temp_dir, repo)
     }
 
+    #[test]
+    fn test_plan_push() {
+        let (temp_dir, repo) = setup_test_repo();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        let commit = |message: &str| git(&["commit", "-q", "--allow-empty", "-m", message]);
+
+        let bare = TempDir::new().unwrap();
+        let url = bare.path().to_string_lossy().to_string();
+        git(&["init", "-q", "--bare", &url]);
+        git(&["remote", "add", "origin", &url]);
+        git(&["push", "-q", "origin", "HEAD:refs/heads/main"]);
+        git(&["fetch", "-q", "origin"]);
+        git(&["checkout", "-q", "-b", "feat"]);
+        commit("First");
+        commit("Second");
+
+        let plan = plan_push(&repo, "feat", "main").unwrap();
+        assert_eq!(plan.kind, PushKind::NewBranch);
+        let subjects: Vec<&str> = plan.commits.iter().map(|(_, s)| s.as_str()).collect();
+        assert_eq!(subjects, vec!["Second", "First"]);
+
+        git(&["push", "-q", "origin", "feat"]);
+        commit("Third");
+        let plan = plan_push(&repo, "feat", "main").unwrap();
+        assert_eq!(plan.kind, PushKind::FastForward);
+        assert_eq!(plan.commits.len(), 1);
+
+        git(&["reset", "-q", "--hard", "HEAD~2"]);
+        commit("Rewritten");
+        let plan = plan_push(&repo, "feat", "main").unwrap();
+        assert_eq!(plan.kind, PushKind::Forced);
+        assert_eq!(plan.overwritten, 1);
+        assert_eq!(plan.commits[0].1, "Rewritten");
+    }
+
     #[test]
     fn test_has_commits_to_push_no_remote() {
         let (_temp_dir, repo) = setup_test_repo();
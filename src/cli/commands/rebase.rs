@@ -6,6 +6,7 @@ use crate::cli::output::Output;
 use crate::core::manifest::Manifest;
 use crate::core::repo::RepoInfo;
 use crate::git::{get_current_branch, open_repo, path_exists};
+use crate::util::profile::ProfiledCommand;
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -73,7 +74,7 @@ pub fn run_rebase(
         let output = Command::new("git")
             .args(["rebase", target])
             .current_dir(&repo.absolute_path)
-            .output()?;
+            .timed_output()?;
 
         if output.status.success() {
             spinner.finish_with_message(format!("{}: rebased", repo.name));
@@ -139,7 +140,7 @@ fn run_rebase_abort(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Re
             let output = Command::new("git")
                 .args(["rebase", "--abort"])
                 .current_dir(&repo.absolute_path)
-                .output()?;
+                .timed_output()?;
 
             if output.status.success() {
                 Output::success(&format!("{}: rebase aborted", repo.name));
@@ -175,7 +176,7 @@ fn run_rebase_continue(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow:
             let output = Command::new("git")
                 .args(["rebase", "--continue"])
                 .current_dir(&repo.absolute_path)
-                .output()?;
+                .timed_output()?;
 
             if output.status.success() {
                 Output::success(&format!("{}: rebase continued", repo.name));
@@ -1,12 +1,20 @@
 //! Repo command implementation
 //!
-//! Manages repositories in the workspace.
+//! Manages repositories in the workspace, and inspects one in detail with
+//! `gr repo info`.
 
 use crate::cli::output::{Output, Table};
-use crate::core::manifest::Manifest;
+use crate::core::manifest::{Manifest, RepoConfig};
 use crate::core::repo::RepoInfo;
-use crate::git::path_exists;
-use std::path::PathBuf;
+use crate::core::state::StateFile;
+use crate::git::{
+    get_current_branch, get_status_info, last_fetch_time, open_repo, path_exists, GitOperation,
+};
+use crate::platform::get_platform_adapter;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use git2::{BranchType, Repository};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 
 /// Run repo list command
 pub fn run_repo_list(workspace_root: &PathBuf, manifest: &Manifest) -> anyhow::Result<()> {
@@ -63,6 +71,23 @@ pub fn run_repo_add(
 
     let branch = default_branch.unwrap_or("main").to_string();
 
+    append_repo_to_manifest(workspace_root, &repo_name, url, &repo_path, &branch)?;
+
+    Output::success(&format!("Added repository '{}' to manifest", repo_name));
+    println!();
+    println!("Run 'gr sync' to clone the repository.");
+
+    Ok(())
+}
+
+/// Append a repo entry to the workspace manifest, keeping its formatting
+pub fn append_repo_to_manifest(
+    workspace_root: &Path,
+    name: &str,
+    url: &str,
+    path: &str,
+    default_branch: &str,
+) -> anyhow::Result<()> {
     // Load manifest
     let manifest_path = workspace_root.join(".gitgrip/manifests/manifest.yaml");
     let content = std::fs::read_to_string(&manifest_path)?;
@@ -74,7 +99,7 @@ pub fn run_repo_add(
     url: {}
     path: {}
     default_branch: {}"#,
-        repo_name, url, repo_path, branch
+        name, url, path, default_branch
     );
 
     // Check if repos section exists and append
@@ -103,10 +128,6 @@ pub fn run_repo_add(
 
     std::fs::write(&manifest_path, updated_content)?;
 
-    Output::success(&format!("Added repository '{}' to manifest", repo_name));
-    println!();
-    println!("Run 'gr sync' to clone the repository.");
-
     Ok(())
 }
 
@@ -174,8 +195,574 @@ pub fn run_repo_remove(
     Ok(())
 }
 
+/// Settings for `gr repo info`
+#[derive(Debug, Clone, Default)]
+pub struct RepoInfoOptions {
+    /// Recent commits to show
+    pub commits: usize,
+    /// Output JSON
+    pub json: bool,
+    /// Don't look up the current branch's PR on the hosting platform
+    pub no_prs: bool,
+}
+
+/// Everything known about one repo, for `gr repo info`
+#[derive(Debug, Serialize)]
+struct RepoDetails {
+    name: String,
+    manifest: RepoConfig,
+    platform: String,
+    owner: String,
+    repo: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    path: PathBuf,
+    cloned: bool,
+    remotes: Vec<RemoteDetails>,
+    /// Current branch, or the commit when detached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head: Option<String>,
+    branches: Vec<BranchDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<StatusDetails>,
+    commits: Vec<CommitDetails>,
+    prs: Vec<PRDetails>,
+    files: Vec<FileDetails>,
+    cache: Vec<CacheDetails>,
+}
+
+#[derive(Debug, Serialize)]
+struct RemoteDetails {
+    name: String,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BranchDetails {
+    name: String,
+    current: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upstream: Option<String>,
+    ahead: usize,
+    behind: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct StatusDetails {
+    staged: usize,
+    modified: usize,
+    untracked: usize,
+    conflicted: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    operation: Option<GitOperation>,
+    /// Last fetch (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fetched: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CommitDetails {
+    sha: String,
+    subject: String,
+    author: String,
+    date: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PRDetails {
+    number: u64,
+    url: String,
+    state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branch: Option<String>,
+    /// Where it was found: `linked`, `group`, or `platform`
+    source: String,
+}
+
+#[derive(Debug, Serialize)]
+struct FileDetails {
+    /// `copy` or `link`
+    kind: String,
+    src: String,
+    dest: String,
+    /// `ok`, `source missing`, `dest missing`, or `not a link`
+    status: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheDetails {
+    kind: String,
+    detail: String,
+}
+
+/// Run repo info command
+pub async fn run_repo_info(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    name: &str,
+    options: &RepoInfoOptions,
+) -> anyhow::Result<()> {
+    let config = manifest
+        .repos
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found in manifest", name))?;
+    let info = RepoInfo::from_config(name, config, workspace_root).ok_or_else(|| {
+        anyhow::anyhow!(
+            "'{}' is a nested workspace or has an unrecognized URL: {}",
+            name,
+            config.url
+        )
+    })?;
+    let state =
+        StateFile::load(workspace_root.join(".gitgrip").join("state.json")).unwrap_or_default();
+    let mut details = collect_details(workspace_root, config, &info, &state, options.commits);
+
+    // The current branch's PR, when state doesn't already know it
+    let branch = details
+        .branches
+        .iter()
+        .find(|b| b.current && b.name != info.default_branch)
+        .map(|b| b.name.clone());
+    if let (Some(branch), false) = (branch, options.no_prs) {
+        let platform = get_platform_adapter(info.platform_type, None);
+        match platform
+            .find_pr_by_branch(&info.owner, &info.repo, &branch)
+            .await
+        {
+            Ok(Some(pr)) if !details.prs.iter().any(|p| p.url == pr.url) => {
+                details.prs.push(PRDetails {
+                    number: pr.number,
+                    url: pr.url,
+                    state: "open".to_string(),
+                    branch: Some(branch),
+                    source: "platform".to_string(),
+                })
+            }
+            Ok(_) => {}
+            Err(e) if options.json => eprintln!("{}: could not look up PRs: {}", name, e),
+            Err(e) => Output::warning(&format!("Could not look up PRs: {}", e)),
+        }
+    }
+
+    if options.json {
+        Output::json(&details)?;
+    } else {
+        print_details(&details);
+    }
+    Ok(())
+}
+
+/// Gather what the manifest, checkout, and state say about a repo
+fn collect_details(
+    workspace_root: &Path,
+    config: &RepoConfig,
+    info: &RepoInfo,
+    state: &StateFile,
+    commits: usize,
+) -> RepoDetails {
+    let git_repo = open_repo(&info.absolute_path).ok();
+    let mut details = RepoDetails {
+        name: info.name.clone(),
+        manifest: config.clone(),
+        platform: info.platform_type.to_string(),
+        owner: info.owner.clone(),
+        repo: info.repo.clone(),
+        project: info.project.clone(),
+        path: info.absolute_path.clone(),
+        cloned: git_repo.is_some(),
+        remotes: Vec::new(),
+        head: None,
+        branches: Vec::new(),
+        status: None,
+        commits: Vec::new(),
+        prs: linked_prs(state, &info.name),
+        files: linked_files(workspace_root, config, &info.absolute_path),
+        cache: Vec::new(),
+    };
+
+    if let Some(ref repo) = git_repo {
+        details.remotes = repo
+            .remotes()
+            .map(|names| {
+                names
+                    .iter()
+                    .flatten()
+                    .filter_map(|name| {
+                        let remote = repo.find_remote(name).ok()?;
+                        Some(RemoteDetails {
+                            name: name.to_string(),
+                            url: remote.url()?.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        details.head = get_current_branch(repo).ok();
+        details.branches = branches(repo);
+        details.status = get_status_info(repo).ok().map(|status| StatusDetails {
+            staged: status.staged.len(),
+            modified: status.modified.len(),
+            untracked: status.untracked_count,
+            conflicted: status.conflicted.len(),
+            operation: status.operation,
+            fetched: last_fetch_time(repo)
+                .map(|time| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)),
+        });
+        details.commits = recent_commits(repo, commits);
+    }
+    details.cache = cache_entries(workspace_root, &info.name, git_repo.as_ref(), state);
+    details
+}
+
+/// Local branches with their upstream and how far apart they are
+fn branches(repo: &Repository) -> Vec<BranchDetails> {
+    let Ok(branches) = repo.branches(Some(BranchType::Local)) else {
+        return Vec::new();
+    };
+    let mut found: Vec<BranchDetails> = branches
+        .flatten()
+        .filter_map(|(branch, _)| {
+            let name = branch.name().ok()??.to_string();
+            let upstream = branch.upstream().ok();
+            let (ahead, behind) = match (branch.get().target(), upstream.as_ref()) {
+                (Some(local), Some(upstream)) => upstream
+                    .get()
+                    .target()
+                    .and_then(|remote| repo.graph_ahead_behind(local, remote).ok())
+                    .unwrap_or((0, 0)),
+                _ => (0, 0),
+            };
+            Some(BranchDetails {
+                name,
+                current: branch.is_head(),
+                upstream: upstream.and_then(|u| u.name().ok()?.map(str::to_string)),
+                ahead,
+                behind,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| (!a.current, &a.name).cmp(&(!b.current, &b.name)));
+    found
+}
+
+fn recent_commits(repo: &Repository, count: usize) -> Vec<CommitDetails> {
+    let Ok(mut walk) = repo.revwalk() else {
+        return Vec::new();
+    };
+    if walk.push_head().is_err() {
+        return Vec::new();
+    }
+    walk.flatten()
+        .take(count)
+        .filter_map(|oid| {
+            let commit = repo.find_commit(oid).ok()?;
+            let date = Utc
+                .timestamp_opt(commit.time().seconds(), 0)
+                .single()?
+                .format("%Y-%m-%d")
+                .to_string();
+            let author = commit.author().name().unwrap_or_default().to_string();
+            Some(CommitDetails {
+                sha: oid.to_string()[..7].to_string(),
+                subject: commit.summary().unwrap_or_default().to_string(),
+                author,
+                date,
+            })
+        })
+        .collect()
+}
+
+/// PRs the state file links to the repo: from `gr pr create` and PR groups
+fn linked_prs(state: &StateFile, name: &str) -> Vec<PRDetails> {
+    let mut prs: Vec<PRDetails> = Vec::new();
+    for (key, links) in &state.pr_links {
+        let branch = state
+            .branch_to_pr
+            .iter()
+            .find(|(_, number)| number.to_string() == *key)
+            .map(|(branch, _)| branch.clone());
+        for link in links.iter().filter(|link| link.repo_name == name) {
+            prs.push(PRDetails {
+                number: link.number,
+                url: link.url.clone(),
+                state: link.state.to_string(),
+                branch: branch.clone(),
+                source: "linked".to_string(),
+            });
+        }
+    }
+    // Split PRs are recorded as `<repo> (<group>)`
+    let split = format!("{} (", name);
+    for group in &state.pr_groups {
+        for pr in &group.prs {
+            if (pr.repo_name == name || pr.repo_name.starts_with(&split))
+                && !prs.iter().any(|p| p.url == pr.url)
+            {
+                prs.push(PRDetails {
+                    number: pr.number,
+                    url: pr.url.clone(),
+                    state: if pr.merged { "merged" } else { "open" }.to_string(),
+                    branch: Some(group.branch.clone()),
+                    source: "group".to_string(),
+                });
+            }
+        }
+    }
+    prs.sort_by_key(|pr| pr.number);
+    prs
+}
+
+/// The repo's copyfile and linkfile entries and whether they're in place
+fn linked_files(workspace_root: &Path, config: &RepoConfig, repo_path: &Path) -> Vec<FileDetails> {
+    let mut files = Vec::new();
+    for copy in config.copyfile.iter().flatten() {
+        let (source, dest) = (repo_path.join(&copy.src), workspace_root.join(&copy.dest));
+        let status = if !source.exists() {
+            "source missing"
+        } else if !dest.exists() {
+            "dest missing"
+        } else {
+            "ok"
+        };
+        files.push(FileDetails {
+            kind: "copy".to_string(),
+            src: copy.src.clone(),
+            dest: copy.dest.clone(),
+            status: status.to_string(),
+        });
+    }
+    for link in config.linkfile.iter().flatten() {
+        let (source, dest) = (repo_path.join(&link.src), workspace_root.join(&link.dest));
+        let status = if !source.exists() {
+            "source missing"
+        } else if !dest.exists() && !dest.is_symlink() {
+            "dest missing"
+        } else if !dest.is_symlink() {
+            "not a link"
+        } else {
+            "ok"
+        };
+        files.push(FileDetails {
+            kind: "link".to_string(),
+            src: link.src.clone(),
+            dest: link.dest.clone(),
+            status: status.to_string(),
+        });
+    }
+    files
+}
+
+/// What the workspace has cached or recorded about the repo
+fn cache_entries(
+    workspace_root: &Path,
+    name: &str,
+    repo: Option<&Repository>,
+    state: &StateFile,
+) -> Vec<CacheDetails> {
+    let mut entries = Vec::new();
+    let mut add = |kind: &str, detail: String| {
+        entries.push(CacheDetails {
+            kind: kind.to_string(),
+            detail,
+        })
+    };
+
+    if let Some(path) = state.managed_repos.get(name) {
+        add("sync", format!("managed checkout at {}", path));
+    }
+    let mut started: Vec<&String> = state
+        .branch_repos
+        .iter()
+        .filter(|(_, repos)| repos.iter().any(|r| r == name))
+        .map(|(branch, _)| branch)
+        .collect();
+    started.sort();
+    for branch in started {
+        let detail = match state.get_branch_ticket(branch) {
+            Some(ticket) => format!("{} (ticket {})", branch, ticket),
+            None => branch.clone(),
+        };
+        add("branch start", detail);
+    }
+
+    let inventory = std::fs::read_to_string(workspace_root.join(".gitgrip/inventory-cache.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|cache| cache.get(name)?.get("head")?.as_str().map(str::to_string));
+    if let Some(head) = inventory {
+        let current = repo
+            .and_then(|r| r.head().ok()?.target())
+            .map(|oid| oid.to_string());
+        let freshness = if current.as_deref() == Some(head.as_str()) {
+            "current"
+        } else {
+            "stale"
+        };
+        add(
+            "inventory",
+            format!("scanned at {} ({})", &head[..head.len().min(7)], freshness),
+        );
+    }
+
+    if let Some(repo) = repo {
+        let alternates = repo.path().join("objects").join("info").join("alternates");
+        for line in std::fs::read_to_string(alternates)
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+        {
+            add("objects", format!("borrowed from {}", line.trim()));
+        }
+    }
+    entries
+}
+
+fn print_details(details: &RepoDetails) {
+    Output::header(&format!("Repository {}", Output::repo_name(&details.name)));
+    Output::kv("URL", &details.manifest.url);
+    Output::kv(
+        "Path",
+        &format!(
+            "{} ({})",
+            details.manifest.path,
+            if details.cloned {
+                "cloned"
+            } else {
+                "not cloned"
+            }
+        ),
+    );
+    Output::kv("Default branch", &details.manifest.default_branch);
+    if let Some(ref revision) = details.manifest.revision {
+        Output::kv("Revision", revision);
+    }
+    if let Some(ref deps) = details.manifest.depends_on {
+        Output::kv("Depends on", &deps.join(", "));
+    }
+    if let Some(ref sparse) = details.manifest.sparse {
+        Output::kv("Sparse", &sparse.join(", "));
+    }
+    if let Some(ref env) = details.manifest.env {
+        let mut keys: Vec<&String> = env.keys().collect();
+        keys.sort();
+        Output::kv(
+            "Env",
+            &keys
+                .iter()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    let project = details
+        .project
+        .as_ref()
+        .map(|p| format!(" (project {})", p))
+        .unwrap_or_default();
+    Output::kv(
+        "Platform",
+        &format!(
+            "{} ({}/{}){}",
+            details.platform, details.owner, details.repo, project
+        ),
+    );
+
+    if !details.cloned {
+        println!();
+        Output::info("Not cloned; run 'gr sync' to clone it.");
+        return;
+    }
+
+    Output::header("Remotes");
+    for remote in &details.remotes {
+        Output::kv(&remote.name, &remote.url);
+    }
+
+    Output::header("Branches");
+    for branch in &details.branches {
+        let marker = if branch.current { "*" } else { " " };
+        let tracking = match branch.upstream {
+            Some(ref upstream) => format!(
+                " -> {} (ahead {}, behind {})",
+                upstream, branch.ahead, branch.behind
+            ),
+            None => " (no upstream)".to_string(),
+        };
+        println!(
+            "  {} {}{}",
+            marker,
+            Output::branch_name(&branch.name),
+            tracking
+        );
+    }
+    if let (Some(ref head), false) = (&details.head, details.branches.iter().any(|b| b.current)) {
+        Output::kv("HEAD", head);
+    }
+
+    if let Some(ref status) = details.status {
+        Output::header("Status");
+        Output::kv(
+            "Changes",
+            &format!(
+                "{} staged, {} modified, {} untracked, {} conflicted",
+                status.staged, status.modified, status.untracked, status.conflicted
+            ),
+        );
+        if let Some(operation) = status.operation {
+            Output::kv("In progress", &format!("{:?}", operation).to_lowercase());
+        }
+        Output::kv("Last fetch", status.fetched.as_deref().unwrap_or("never"));
+    }
+
+    if !details.commits.is_empty() {
+        Output::header("Recent commits");
+        for commit in &details.commits {
+            println!(
+                "  {} {} ({}, {})",
+                commit.sha, commit.subject, commit.author, commit.date
+            );
+        }
+    }
+
+    Output::header("Pull requests");
+    if details.prs.is_empty() {
+        Output::subheader("none known");
+    }
+    for pr in &details.prs {
+        let branch = pr
+            .branch
+            .as_ref()
+            .map(|b| format!(" on {}", Output::branch_name(b)))
+            .unwrap_or_default();
+        Output::list_item(&format!(
+            "#{} {}{} {}",
+            pr.number,
+            Output::status(&pr.state),
+            branch,
+            pr.url
+        ));
+    }
+
+    if !details.files.is_empty() {
+        Output::header("Copied and linked files");
+        for file in &details.files {
+            Output::list_item(&format!(
+                "[{}] {} -> {}: {}",
+                file.kind, file.src, file.dest, file.status
+            ));
+        }
+    }
+
+    if !details.cache.is_empty() {
+        Output::header("Cache and state");
+        for entry in &details.cache {
+            Output::kv(&entry.kind, &entry.detail);
+        }
+    }
+}
+
 /// Extract repository name from URL
-fn extract_repo_name(url: &str) -> Option<String> {
+pub fn extract_repo_name(url: &str) -> Option<String> {
     // Handle SSH URLs: git@github.com:owner/repo.git
     if url.starts_with("git@") {
         let parts: Vec<&str> = url.split('/').collect();
@@ -198,6 +785,77 @@ fn extract_repo_name(url: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::state::{GroupPR, PRGroup};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_details() {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path().to_path_buf();
+        let repo_dir = workspace.join("api");
+        let repo = Repository::init(&repo_dir).unwrap();
+        std::fs::write(repo_dir.join("README.md"), "# API").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Ana", "ana@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+        std::fs::write(repo_dir.join("notes.txt"), "wip").unwrap();
+
+        let manifest = Manifest::parse(
+            r#"
+version: 1
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+    copyfile:
+      - src: README.md
+        dest: API.md
+    linkfile:
+      - src: missing.txt
+        dest: missing.txt
+"#,
+        )
+        .unwrap();
+        let config = &manifest.repos["api"];
+        let info = RepoInfo::from_config("api", config, &workspace).unwrap();
+
+        let mut state = StateFile::default();
+        state
+            .managed_repos
+            .insert("api".to_string(), "api".to_string());
+        state.record_pr_group(PRGroup {
+            branch: "docs/readme".to_string(),
+            title: "Docs".to_string(),
+            prs: vec![GroupPR {
+                repo_name: "api (docs)".to_string(),
+                number: 4,
+                url: "https://github.com/acme/api/pull/4".to_string(),
+                merged: false,
+            }],
+            ..Default::default()
+        });
+
+        let details = collect_details(&workspace, config, &info, &state, 5);
+        assert!(details.cloned);
+        assert_eq!(
+            (details.owner.as_str(), details.repo.as_str()),
+            ("acme", "api")
+        );
+        assert_eq!(details.commits.len(), 1);
+        assert_eq!(details.commits[0].subject, "Initial");
+        assert_eq!(details.branches.len(), 1);
+        assert!(details.branches[0].current);
+        assert_eq!(details.status.as_ref().unwrap().untracked, 1);
+        assert_eq!(details.prs.len(), 1);
+        assert_eq!(details.prs[0].source, "group");
+        let statuses: Vec<&str> = details.files.iter().map(|f| f.status.as_str()).collect();
+        assert_eq!(statuses, vec!["dest missing", "source missing"]);
+        assert_eq!(details.cache[0].kind, "sync");
+    }
 
     #[test]
     fn test_extract_repo_name_ssh() {
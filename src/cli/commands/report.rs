@@ -0,0 +1,455 @@
+//! Report command implementation
+//!
+//! Renders a shareable Markdown or HTML snapshot of the workspace: branch,
+//! dirty files, and ahead/behind per repo, open PRs with their check status,
+//! and recent commits.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::status::{get_cached_status, get_repo_status, RepoStatus};
+use crate::platform::traits::PlatformError;
+use crate::platform::{detect_platform, get_platform_adapter, CheckState};
+use crate::util::profile::ProfiledCommand;
+use crate::util::{concurrent_map, max_workers, parallel_map};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Report output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    fn parse(format: &str) -> anyhow::Result<Self> {
+        match format {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            other => anyhow::bail!(
+                "Unknown report format '{}' (expected markdown or html)",
+                other
+            ),
+        }
+    }
+}
+
+/// Open PR for a repo's current branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PrSummary {
+    number: u64,
+    url: String,
+    /// Combined check state, or `None` if it couldn't be read
+    checks: Option<CheckState>,
+}
+
+/// One commit from `git log`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CommitSummary {
+    sha: String,
+    subject: String,
+    author: String,
+    when: String,
+}
+
+/// Everything the report shows for one repo
+#[derive(Debug, Clone)]
+struct RepoReport {
+    status: RepoStatus,
+    /// Dirty files as `<code> <path>`, e.g. `M src/lib.rs`
+    dirty: Vec<String>,
+    commits: Vec<CommitSummary>,
+    pr: Option<PrSummary>,
+}
+
+/// Run the report command
+///
+/// The report goes to `output` if given, otherwise to stdout. With
+/// `no_prs`, the hosting platform is not queried.
+pub async fn run_report(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    format: &str,
+    output: Option<&Path>,
+    commits: usize,
+    no_prs: bool,
+) -> anyhow::Result<()> {
+    let format = ReportFormat::parse(format)?;
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let spinner = output.map(|_| Output::spinner("Collecting workspace report..."));
+
+    let collected = repos.clone();
+    let mut reports: Vec<RepoReport> = tokio::task::spawn_blocking(move || {
+        parallel_map(&collected, max_workers(), |repo| {
+            let status = get_repo_status(repo);
+            let (dirty, commits) = if status.exists {
+                (
+                    dirty_files(&repo.absolute_path),
+                    recent_commits(&repo.absolute_path, commits),
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            RepoReport {
+                status,
+                dirty,
+                commits,
+                pr: None,
+            }
+        })
+    })
+    .await?;
+
+    if !no_prs {
+        let lookups = concurrent_map(&repos, manifest.settings.api_concurrency, |repo| {
+            let branch = reports
+                .iter()
+                .find(|r| r.status.name == repo.name)
+                .filter(|r| r.status.exists && r.status.branch != repo.default_branch)
+                .map(|r| r.status.branch.clone());
+            async move {
+                let Some(branch) = branch else {
+                    return Ok(None);
+                };
+                let platform = get_platform_adapter(detect_platform(&repo.url), None);
+                let Some(pr) = platform
+                    .find_pr_by_branch(&repo.owner, &repo.repo, &branch)
+                    .await?
+                else {
+                    return Ok(None);
+                };
+                let checks = platform
+                    .get_status_checks(&repo.owner, &repo.repo, &branch)
+                    .await
+                    .ok()
+                    .map(|c| c.state);
+                Ok::<_, PlatformError>(Some(PrSummary {
+                    number: pr.number,
+                    url: pr.url,
+                    checks,
+                }))
+            }
+        })
+        .await;
+
+        for (report, lookup) in reports.iter_mut().zip(lookups) {
+            match lookup {
+                Ok(pr) => report.pr = pr,
+                Err(e) => {
+                    let message = format!("{}: could not look up PR: {}", report.status.name, e);
+                    // Keep stdout clean when the report itself goes there
+                    if output.is_some() {
+                        Output::warning(&message);
+                    } else {
+                        eprintln!("{}", message);
+                    }
+                }
+            }
+        }
+    }
+
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let rendered = match format {
+        ReportFormat::Markdown => render_markdown(&reports, &generated),
+        ReportFormat::Html => render_html(&reports, &generated),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered)?;
+            if let Some(spinner) = spinner {
+                spinner.finish_and_clear();
+            }
+            Output::success(&format!("Wrote report to {}", path.display()));
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+fn dirty_files(path: &Path) -> Vec<String> {
+    match get_cached_status(&path.to_path_buf()) {
+        Ok(info) => info
+            .conflicted
+            .iter()
+            .map(|f| format!("U {}", f))
+            .chain(info.staged.iter().map(|f| format!("A {}", f)))
+            .chain(info.modified.iter().map(|f| format!("M {}", f)))
+            .chain(info.untracked.iter().map(|f| format!("? {}", f)))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn recent_commits(path: &Path, limit: usize) -> Vec<CommitSummary> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let output = Command::new("git")
+        .args([
+            "log",
+            &format!("-n{}", limit),
+            "--format=%h%x1f%s%x1f%an%x1f%ar",
+        ])
+        .current_dir(path)
+        .timed_output();
+    match output {
+        Ok(o) if o.status.success() => parse_log(&String::from_utf8_lossy(&o.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_log(stdout: &str) -> Vec<CommitSummary> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            Some(CommitSummary {
+                sha: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                when: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn sync_label(status: &RepoStatus) -> String {
+    format!("↑{} ↓{}", status.ahead, status.behind)
+}
+
+fn main_label(status: &RepoStatus) -> String {
+    format!("+{} -{}", status.ahead_main, status.behind_main)
+}
+
+fn checks_label(checks: Option<CheckState>) -> &'static str {
+    match checks {
+        Some(CheckState::Success) => "✓ passing",
+        Some(CheckState::Failure) => "✗ failing",
+        Some(CheckState::Pending) => "● pending",
+        None => "unknown",
+    }
+}
+
+fn render_markdown(reports: &[RepoReport], generated: &str) -> String {
+    // Table cells can't contain raw pipes
+    let cell = |s: &str| s.replace('|', "\\|");
+
+    let mut out = format!("# Workspace Report\n\n_Generated {}_\n\n", generated);
+    out.push_str("| Repo | Branch | Dirty | Upstream | vs default | PR | Checks |\n");
+    out.push_str("|------|--------|-------|----------|------------|----|--------|\n");
+    for report in reports {
+        let status = &report.status;
+        if !status.exists {
+            out.push_str(&format!(
+                "| {} | _not cloned_ | | | | | |\n",
+                cell(&status.name)
+            ));
+            continue;
+        }
+        let (pr, checks) = match &report.pr {
+            Some(pr) => (
+                format!("[#{}]({})", pr.number, pr.url),
+                checks_label(pr.checks),
+            ),
+            None => ("-".to_string(), "-"),
+        };
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} | {} | {} | {} |\n",
+            cell(&status.name),
+            cell(&status.branch),
+            report.dirty.len(),
+            sync_label(status),
+            main_label(status),
+            pr,
+            checks
+        ));
+    }
+
+    for report in reports.iter().filter(|r| r.status.exists) {
+        out.push_str(&format!("\n## {}\n\n", report.status.name));
+        if !report.dirty.is_empty() {
+            out.push_str("**Uncommitted changes**\n\n");
+            for file in &report.dirty {
+                out.push_str(&format!("- `{}`\n", file));
+            }
+            out.push('\n');
+        }
+        if report.commits.is_empty() {
+            out.push_str("_No commits_\n");
+        } else {
+            out.push_str("**Recent commits**\n\n");
+            for commit in &report.commits {
+                out.push_str(&format!(
+                    "- `{}` {} — {}, {}\n",
+                    commit.sha, commit.subject, commit.author, commit.when
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn render_html(reports: &[RepoReport], generated: &str) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Workspace Report</title>\n\
+         <style>body{font-family:sans-serif}table{border-collapse:collapse}\
+         td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}code{font-size:90%}</style>\n\
+         </head>\n<body>\n<h1>Workspace Report</h1>\n",
+    );
+    out.push_str(&format!(
+        "<p><em>Generated {}</em></p>\n",
+        escape(generated)
+    ));
+    out.push_str(
+        "<table>\n<tr><th>Repo</th><th>Branch</th><th>Dirty</th><th>Upstream</th>\
+         <th>vs default</th><th>PR</th><th>Checks</th></tr>\n",
+    );
+    for report in reports {
+        let status = &report.status;
+        if !status.exists {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td colspan=\"6\"><em>not cloned</em></td></tr>\n",
+                escape(&status.name)
+            ));
+            continue;
+        }
+        let (pr, checks) = match &report.pr {
+            Some(pr) => (
+                format!("<a href=\"{}\">#{}</a>", escape(&pr.url), pr.number),
+                checks_label(pr.checks),
+            ),
+            None => ("-".to_string(), "-"),
+        };
+        out.push_str(&format!(
+            "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&status.name),
+            escape(&status.branch),
+            report.dirty.len(),
+            sync_label(status),
+            main_label(status),
+            pr,
+            checks
+        ));
+    }
+    out.push_str("</table>\n");
+
+    for report in reports.iter().filter(|r| r.status.exists) {
+        out.push_str(&format!("<h2>{}</h2>\n", escape(&report.status.name)));
+        if !report.dirty.is_empty() {
+            out.push_str("<p><strong>Uncommitted changes</strong></p>\n<ul>\n");
+            for file in &report.dirty {
+                out.push_str(&format!("<li><code>{}</code></li>\n", escape(file)));
+            }
+            out.push_str("</ul>\n");
+        }
+        if report.commits.is_empty() {
+            out.push_str("<p><em>No commits</em></p>\n");
+        } else {
+            out.push_str("<p><strong>Recent commits</strong></p>\n<ul>\n");
+            for commit in &report.commits {
+                out.push_str(&format!(
+                    "<li><code>{}</code> {} — {}, {}</li>\n",
+                    escape(&commit.sha),
+                    escape(&commit.subject),
+                    escape(&commit.author),
+                    escape(&commit.when)
+                ));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Vec<RepoReport> {
+        let status = RepoStatus {
+            name: "api".to_string(),
+            branch: "feat/login".to_string(),
+            clean: false,
+            staged: 0,
+            modified: 1,
+            untracked: 0,
+            ahead: 2,
+            behind: 0,
+            ahead_main: 3,
+            behind_main: 1,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
+            exists: true,
+        };
+        let missing = RepoStatus {
+            name: "docs".to_string(),
+            branch: String::new(),
+            exists: false,
+            ..status.clone()
+        };
+        vec![
+            RepoReport {
+                status,
+                dirty: vec!["M src/<main>.rs".to_string()],
+                commits: parse_log("abc1234\x1fAdd login\x1fAda\x1f2 hours ago\n"),
+                pr: Some(PrSummary {
+                    number: 7,
+                    url: "https://github.com/acme/api/pull/7".to_string(),
+                    checks: Some(CheckState::Failure),
+                }),
+            },
+            RepoReport {
+                status: missing,
+                dirty: vec![],
+                commits: vec![],
+                pr: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render_markdown(&report(), "2024-01-01 09:00");
+        assert!(md.contains(
+            "| api | `feat/login` | 1 | ↑2 ↓0 | +3 -1 | [#7](https://github.com/acme/api/pull/7) | ✗ failing |\n"
+        ));
+        assert!(md.contains("| docs | _not cloned_ | | | | | |\n"));
+        assert!(md.contains("- `abc1234` Add login — Ada, 2 hours ago\n"));
+        assert!(!md.contains("## docs"));
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let html = render_html(&report(), "2024-01-01 09:00");
+        assert!(html.contains("<li><code>M src/&lt;main&gt;.rs</code></li>"));
+        assert!(html.contains("<a href=\"https://github.com/acme/api/pull/7\">#7</a>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn test_parse_log_skips_malformed_lines() {
+        let commits = parse_log("abc\x1fone\x1fAda\x1fnow\nbroken line\n");
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].subject, "one");
+    }
+}
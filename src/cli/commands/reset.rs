@@ -0,0 +1,320 @@
+//! Reset command implementation
+//!
+//! Moves the current branch in each repo to a target ref, by default its
+//! upstream. Every run first previews the commits each repo would drop. The
+//! old tips are kept in `ORIG_HEAD` and the audit log, so `gr undo` can put
+//! them back.
+
+use crate::cli::commands::audit::audit;
+use crate::cli::output::Output;
+use crate::core::audit::{AuditAction, AuditEntry};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::remote::{reset, ResetMode};
+use crate::git::status::get_status_info;
+use crate::git::{get_commits_between, get_current_branch, open_repo, path_exists};
+use dialoguer::Confirm;
+use git2::{Oid, Repository};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Settings for `gr reset`
+#[derive(Debug, Clone)]
+pub struct ResetOptions {
+    pub mode: ResetMode,
+    /// Ref to reset every repo to; each branch's upstream if `None`
+    pub target: Option<String>,
+    /// Per-repo overrides, as `repo=ref`
+    pub repo_targets: Vec<String>,
+    /// Skip the confirmation prompt (the preview is still shown)
+    pub yes: bool,
+    /// Only show the preview
+    pub dry_run: bool,
+    /// Allow moving protected branches
+    pub allow_protected: bool,
+}
+
+/// What resetting one repo would do
+struct ResetPlan<'a> {
+    repo: &'a RepoInfo,
+    branch: String,
+    target: String,
+    from: Oid,
+    to: Oid,
+    /// Commits no longer on the branch afterwards, as `(short sha, subject)`
+    discarded: Vec<(String, String)>,
+    /// Uncommitted changes a hard reset would throw away
+    dirty: usize,
+}
+
+/// Run the reset command
+pub fn run_reset(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    repos_filter: Option<&[String]>,
+    options: &ResetOptions,
+) -> anyhow::Result<()> {
+    let mut overrides: HashMap<&str, &str> = HashMap::new();
+    for spec in &options.repo_targets {
+        let (repo, target) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --target '{}' (expected repo=ref)", spec))?;
+        if !manifest.repos.contains_key(repo) {
+            anyhow::bail!("Unknown repo '{}'", repo);
+        }
+        overrides.insert(repo, target);
+    }
+    if let Some(filter) = repos_filter {
+        if let Some(unknown) = filter.iter().find(|r| !manifest.repos.contains_key(*r)) {
+            anyhow::bail!("Unknown repo '{}'", unknown);
+        }
+    }
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| repos_filter.map_or(true, |filter| filter.contains(&r.name)))
+        .filter(|r| path_exists(&r.absolute_path))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Output::header(&format!("Reset preview ({})", options.mode.flag()));
+    println!();
+
+    let mut plans: Vec<ResetPlan> = Vec::new();
+    for repo in &repos {
+        let target = overrides
+            .get(repo.name.as_str())
+            .map(|t| t.to_string())
+            .or_else(|| options.target.clone());
+        match plan_reset(repo, target.as_deref(), options.mode) {
+            Ok(Some(plan)) => {
+                print_plan(&plan, options.mode);
+                plans.push(plan);
+            }
+            Ok(None) => Output::info(&format!("{}: already at target", repo.name)),
+            Err(e) => Output::warning(&format!("{}: {}, skipping", repo.name, e)),
+        }
+    }
+    println!();
+
+    if plans.is_empty() {
+        println!("Nothing to reset.");
+        return Ok(());
+    }
+
+    if !options.dry_run {
+        manifest.settings.ensure_unprotected(
+            "reset",
+            plans
+                .iter()
+                .filter(|plan| plan.from != plan.to)
+                .map(|plan| {
+                    (
+                        plan.repo.name.as_str(),
+                        plan.branch.as_str(),
+                        plan.repo.default_branch.as_str(),
+                    )
+                }),
+            options.allow_protected,
+        )?;
+    }
+
+    let proceed = if options.dry_run {
+        false
+    } else if options.yes {
+        true
+    } else {
+        Confirm::with_theme(&*Output::theme())
+            .with_prompt(format!(
+                "Reset {} repo(s) with {}?",
+                plans.len(),
+                options.mode.flag()
+            ))
+            .default(false)
+            .interact()?
+    };
+    if !proceed {
+        Output::info("Nothing reset.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for plan in &plans {
+        let result = open_repo(&plan.repo.absolute_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|git_repo| Ok(reset(&git_repo, &plan.to.to_string(), options.mode)?));
+        match result {
+            Ok(()) => {
+                audit(
+                    workspace_root,
+                    AuditEntry {
+                        mode: Some(options.mode.name().to_string()),
+                        ..AuditEntry::new(AuditAction::Reset, &plan.repo.name).with_ref(
+                            &plan.branch,
+                            Some(plan.from.to_string()),
+                            Some(plan.to.to_string()),
+                        )
+                    },
+                );
+                Output::success(&format!(
+                    "{}: {} reset to {}",
+                    plan.repo.name, plan.branch, plan.target
+                ));
+            }
+            Err(e) => {
+                failed += 1;
+                Output::error(&format!("{}: {}", plan.repo.name, e));
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} repo(s) failed to reset", failed);
+    }
+    println!();
+    Output::info("Previous tips are in ORIG_HEAD; 'gr undo' restores them.");
+
+    Ok(())
+}
+
+/// Work out what resetting `repo` to `target` would do
+///
+/// `None` if it's already there and, for a hard reset, the tree is clean.
+fn plan_reset<'a>(
+    repo: &'a RepoInfo,
+    target: Option<&str>,
+    mode: ResetMode,
+) -> anyhow::Result<Option<ResetPlan<'a>>> {
+    let git_repo = open_repo(&repo.absolute_path)?;
+    let branch = get_current_branch(&git_repo)?;
+    let target = match target {
+        Some(target) => target.to_string(),
+        None => upstream_of(&git_repo, &branch)
+            .ok_or_else(|| anyhow::anyhow!("{} has no upstream; pass a ref", branch))?,
+    };
+
+    let from = git_repo.head()?.peel_to_commit()?.id();
+    let to = git_repo
+        .revparse_single(&target)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| anyhow::anyhow!("unknown ref '{}'", target))?
+        .id();
+    let dirty = if mode == ResetMode::Hard {
+        get_status_info(&git_repo)
+            .map(|s| s.staged.len() + s.modified.len())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    if from == to && dirty == 0 {
+        return Ok(None);
+    }
+
+    let discarded = get_commits_between(&git_repo, &to.to_string(), Some(&from.to_string()))?
+        .iter()
+        .map(|sha| {
+            let subject = Oid::from_str(sha)
+                .and_then(|oid| git_repo.find_commit(oid))
+                .ok()
+                .and_then(|commit| commit.summary().map(str::to_string))
+                .unwrap_or_default();
+            (sha[..7.min(sha.len())].to_string(), subject)
+        })
+        .collect();
+
+    Ok(Some(ResetPlan {
+        repo,
+        branch,
+        target,
+        from,
+        to,
+        discarded,
+        dirty,
+    }))
+}
+
+/// The remote-tracking branch `branch` follows, e.g. `origin/main`
+fn upstream_of(repo: &Repository, branch: &str) -> Option<String> {
+    let local = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+    let upstream = local.upstream().ok()?;
+    upstream.name().ok().flatten().map(str::to_string)
+}
+
+fn print_plan(plan: &ResetPlan, mode: ResetMode) {
+    let short = |oid: Oid| oid.to_string()[..7].to_string();
+    println!(
+        "{} ({}): {} -> {} ({}), discards {} commit(s)",
+        Output::repo_name(&plan.repo.name),
+        Output::branch_name(&plan.branch),
+        short(plan.from),
+        short(plan.to),
+        plan.target,
+        plan.discarded.len()
+    );
+    for (sha, subject) in &plan.discarded {
+        Output::list_item(&format!("{} {}", sha, subject));
+    }
+    if plan.dirty > 0 {
+        Output::warning(&format!(
+            "{}: {} uncommitted change(s) will be lost",
+            plan.repo.name, plan.dirty
+        ));
+    } else if mode == ResetMode::Soft && !plan.discarded.is_empty() {
+        Output::subheader("their changes stay staged");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_and_reset() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let path = root.join("app");
+        init_repo(&path);
+        git(&path, &["commit", "-q", "--allow-empty", "-m", "Base"]);
+        let base = git(&path, &["rev-parse", "HEAD"]);
+        git(&path, &["commit", "-q", "--allow-empty", "-m", "Oops one"]);
+        git(&path, &["commit", "-q", "--allow-empty", "-m", "Oops two"]);
+        let tip = git(&path, &["rev-parse", "HEAD"]);
+        std::fs::write(path.join("wip.txt"), "wip").unwrap();
+        git(&path, &["add", "wip.txt"]);
+
+        let manifest =
+            Manifest::parse("repos:\n  app:\n    url: git@github.com:org/app.git\n    path: app\n")
+                .unwrap();
+        let repo = RepoInfo::from_config("app", &manifest.repos["app"], &root).unwrap();
+
+        // No upstream and no ref
+        assert!(plan_reset(&repo, None, ResetMode::Hard).is_err());
+        assert!(plan_reset(&repo, Some("HEAD"), ResetMode::Mixed)
+            .unwrap()
+            .is_none());
+        // A hard reset in place still discards the dirty tree
+        let in_place = plan_reset(&repo, Some("HEAD"), ResetMode::Hard)
+            .unwrap()
+            .unwrap();
+        assert!(in_place.discarded.is_empty());
+        assert_eq!(in_place.dirty, 1);
+
+        let plan = plan_reset(&repo, Some("HEAD~2"), ResetMode::Hard)
+            .unwrap()
+            .unwrap();
+        let subjects: Vec<&str> = plan.discarded.iter().map(|(_, s)| s.as_str()).collect();
+        assert_eq!(subjects, vec!["Oops two", "Oops one"]);
+        assert_eq!(plan.dirty, 1);
+
+        let git_repo = open_repo(&path).unwrap();
+        reset(&git_repo, &plan.to.to_string(), ResetMode::Soft).unwrap();
+        assert_eq!(git(&path, &["rev-parse", "HEAD"]), base);
+        assert_eq!(git(&path, &["rev-parse", "ORIG_HEAD"]), tip);
+        // Soft keeps the work staged
+        assert!(path.join("wip.txt").exists());
+    }
+}
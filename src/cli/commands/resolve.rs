@@ -0,0 +1,187 @@
+//! Resolve command implementation
+//!
+//! Continues or aborts in-progress merge/rebase/cherry-pick/revert/bisect
+//! operations across repositories.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::cherry_pick::{abort_cherry_pick, continue_cherry_pick, has_pending};
+use crate::git::status::{get_status_info, GitOperation};
+use crate::git::{open_repo, path_exists};
+use crate::util::profile::ProfiledCommand;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// What to do with the in-progress operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveAction {
+    Continue,
+    Abort,
+}
+
+/// Run the resolve command
+pub fn run_resolve(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    action: ResolveAction,
+) -> anyhow::Result<()> {
+    Output::header(match action {
+        ResolveAction::Continue => "Continuing in-progress operations",
+        ResolveAction::Abort => "Aborting in-progress operations",
+    });
+    println!();
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+
+    let mut resolved_count = 0;
+    let mut pending: Vec<String> = Vec::new();
+
+    for repo in &repos {
+        if !path_exists(&repo.absolute_path) {
+            continue;
+        }
+
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+
+        let operation = match GitOperation::detect(&git_repo) {
+            Some(op) => op,
+            None => continue,
+        };
+
+        if action == ResolveAction::Continue {
+            if operation == GitOperation::Bisect {
+                Output::info(&format!(
+                    "{}: bisect in progress, nothing to continue",
+                    repo.name
+                ));
+                continue;
+            }
+
+            let conflicted = get_status_info(&git_repo)
+                .map(|s| s.conflicted.len())
+                .unwrap_or(0);
+            if conflicted > 0 {
+                Output::warning(&format!(
+                    "{}: {} conflicted file(s) - resolve and stage them first",
+                    repo.name, conflicted
+                ));
+                pending.push(repo.name.clone());
+                continue;
+            }
+        }
+
+        // Picks started by `gr cherry-pick` carry their own todo list
+        if operation == GitOperation::CherryPick && has_pending(&git_repo) {
+            let result = match action {
+                ResolveAction::Continue => {
+                    continue_cherry_pick(&git_repo).map(|outcome| outcome.conflict.is_none())
+                }
+                ResolveAction::Abort => abort_cherry_pick(&git_repo).map(|()| true),
+            };
+            match result {
+                Ok(true) => {
+                    Output::success(&format!("{}: cherry-pick resolved", repo.name));
+                    resolved_count += 1;
+                }
+                Ok(false) => {
+                    Output::warning(&format!("{}: new conflicts", repo.name));
+                    pending.push(repo.name.clone());
+                }
+                Err(e) => {
+                    Output::error(&format!("{}: {}", repo.name, e));
+                    pending.push(repo.name.clone());
+                }
+            }
+            continue;
+        }
+
+        let output = Command::new("git")
+            .args(resolve_args(operation, action))
+            .env("GIT_EDITOR", "true")
+            .current_dir(&repo.absolute_path)
+            .timed_output()?;
+
+        if output.status.success() && GitOperation::detect(&git_repo).is_none() {
+            Output::success(&format!(
+                "{}: {} {}",
+                repo.name,
+                operation.git_command(),
+                match action {
+                    ResolveAction::Continue => "completed",
+                    ResolveAction::Abort => "aborted",
+                }
+            ));
+            resolved_count += 1;
+        } else {
+            // A rebase may stop again on the next commit with new conflicts
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stdout.contains("CONFLICT") || stderr.contains("CONFLICT") {
+                Output::warning(&format!("{}: new conflicts", repo.name));
+            } else if output.status.success() {
+                Output::warning(&format!(
+                    "{}: {} still in progress",
+                    repo.name,
+                    operation.git_command()
+                ));
+            } else {
+                Output::error(&format!("{}: {}", repo.name, stderr.trim()));
+            }
+            pending.push(repo.name.clone());
+        }
+    }
+
+    println!();
+    if resolved_count == 0 && pending.is_empty() {
+        Output::info("No operations in progress.");
+    } else if pending.is_empty() {
+        Output::success(&format!("Resolved {} repo(s)", resolved_count));
+    } else {
+        Output::warning(&format!(
+            "{} resolved, {} need manual conflict resolution: {}",
+            resolved_count,
+            pending.len(),
+            pending.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Git arguments that continue or abort the given operation
+fn resolve_args(operation: GitOperation, action: ResolveAction) -> Vec<&'static str> {
+    match (operation, action) {
+        (GitOperation::Bisect, _) => vec!["bisect", "reset"],
+        (op, ResolveAction::Continue) => vec![op.git_command(), "--continue"],
+        (op, ResolveAction::Abort) => vec![op.git_command(), "--abort"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_args() {
+        assert_eq!(
+            resolve_args(GitOperation::Rebase, ResolveAction::Continue),
+            vec!["rebase", "--continue"]
+        );
+        assert_eq!(
+            resolve_args(GitOperation::CherryPick, ResolveAction::Abort),
+            vec!["cherry-pick", "--abort"]
+        );
+        assert_eq!(
+            resolve_args(GitOperation::Bisect, ResolveAction::Abort),
+            vec!["bisect", "reset"]
+        );
+    }
+}
@@ -0,0 +1,507 @@
+//! Revert command implementation
+//!
+//! The fast path for rolling back a cross-repo change: reverts the given
+//! commits in every repo whose default branch contains them, on a new branch
+//! from the default branch, and optionally pushes and opens linked revert PRs.
+
+use crate::cli::commands::audit::{push_audited, PushMode};
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::branch::{branch_exists, checkout_branch, create_and_checkout_branch_from};
+use crate::git::remote::fetch_remote;
+use crate::git::status::{blocking_operation, get_status_info};
+use crate::git::{get_workdir, open_repo, path_exists, run_git, GitError};
+use crate::platform::traits::LinkedPRRef;
+use crate::platform::{detect_platform, get_platform_adapter, HostingPlatform};
+use git2::{Oid, Repository, RepositoryState};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Settings for `gr revert`
+#[derive(Debug, Clone, Default)]
+pub struct RevertOptions {
+    /// Parent number to revert merge commits against (`git revert -m`)
+    pub mainline: Option<u32>,
+    /// Branch to create (default: `revert/<short sha>`)
+    pub branch: Option<String>,
+    /// Push the branches and open linked PRs
+    pub pr: bool,
+    /// PR title (default: `Revert "<subject>"`)
+    pub title: Option<String>,
+    /// Create PRs as drafts
+    pub draft: bool,
+}
+
+/// A repo where the revert was committed
+struct Reverted<'a> {
+    repo: &'a RepoInfo,
+    /// The reverted commits, as `(sha, subject)`
+    commits: Vec<(Oid, String)>,
+}
+
+/// A revert PR that was opened (or found already open)
+struct RevertPr<'a> {
+    repo: &'a RepoInfo,
+    platform: Arc<dyn HostingPlatform>,
+    number: u64,
+    url: String,
+    body: String,
+}
+
+/// Run the revert command
+///
+/// `commits` must be commit SHAs; each repo reverts the ones on its default
+/// branch, newest first.
+pub async fn run_revert(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    commits: &[String],
+    options: &RevertOptions,
+) -> anyhow::Result<()> {
+    let branch = options
+        .branch
+        .clone()
+        .unwrap_or_else(|| revert_branch_name(&commits[0]));
+    Output::header(&format!(
+        "Reverting {} on {}",
+        commits.join(", "),
+        Output::branch_name(&branch)
+    ));
+    println!();
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut reverted: Vec<Reverted> = Vec::new();
+    let mut conflicted: Vec<String> = Vec::new();
+
+    for repo in &repos {
+        let git_repo = match open_repo(&repo.absolute_path) {
+            Ok(r) => r,
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo.name, e));
+                continue;
+            }
+        };
+
+        // An unfinished revert must be completed before anything is pushed
+        if let Some(reason) = blocking_operation(&git_repo) {
+            Output::warning(&format!("{}: {}", repo.name, reason));
+            conflicted.push(repo.name.clone());
+            continue;
+        }
+
+        if let Err(e) = fetch_remote(&git_repo, "origin") {
+            Output::warning(&format!("{}: fetch failed - {}", repo.name, e));
+        }
+
+        let remote_base = format!("origin/{}", repo.default_branch);
+        let base = if git_repo
+            .find_reference(&format!("refs/remotes/{}", remote_base))
+            .is_ok()
+        {
+            remote_base
+        } else {
+            repo.default_branch.clone()
+        };
+
+        let found = find_commits(&git_repo, commits, &base);
+        if found.is_empty() {
+            // Repo not touched by this change
+            continue;
+        }
+
+        // Re-running after resolving conflicts picks up the existing branch
+        if branch_exists(&git_repo, &branch) {
+            match checkout_branch(&git_repo, &branch) {
+                Ok(()) => {
+                    Output::info(&format!("{}: using existing {}", repo.name, branch));
+                    reverted.push(Reverted {
+                        repo,
+                        commits: found,
+                    });
+                }
+                Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+            }
+            continue;
+        }
+
+        if let Ok(status) = get_status_info(&git_repo) {
+            if !status.staged.is_empty() || !status.modified.is_empty() {
+                Output::warning(&format!("{}: has uncommitted changes, skipping", repo.name));
+                continue;
+            }
+        }
+
+        if let Err(e) = create_and_checkout_branch_from(&git_repo, &branch, &base) {
+            Output::error(&format!(
+                "{}: cannot branch from {} - {}",
+                repo.name,
+                base,
+                e.to_string().trim()
+            ));
+            continue;
+        }
+
+        let oids: Vec<Oid> = found.iter().map(|(oid, _)| *oid).collect();
+        match revert_commits(&git_repo, &oids, options.mainline) {
+            Ok(()) => {
+                Output::success(&format!(
+                    "{}: reverted {} commit(s)",
+                    repo.name,
+                    found.len()
+                ));
+                reverted.push(Reverted {
+                    repo,
+                    commits: found,
+                });
+            }
+            Err(e) => {
+                let in_revert = matches!(
+                    git_repo.state(),
+                    RepositoryState::Revert | RepositoryState::RevertSequence
+                );
+                if in_revert {
+                    Output::warning(&format!("{}: conflicts while reverting", repo.name));
+                    conflicted.push(repo.name.clone());
+                } else {
+                    Output::error(&format!("{}: {}", repo.name, e));
+                }
+            }
+        }
+    }
+
+    if !conflicted.is_empty() {
+        println!();
+        Output::warning(&format!(
+            "{} repo(s) have conflicts: {}",
+            conflicted.len(),
+            conflicted.join(", ")
+        ));
+        println!("Resolve them, run 'gr resolve --continue', then re-run this revert.");
+        return Ok(());
+    }
+
+    if reverted.is_empty() {
+        Output::info("No repos contain these commits.");
+        return Ok(());
+    }
+
+    if !options.pr {
+        println!();
+        println!(
+            "Reverted in {} repo(s) on {}. Review, then push with 'gr push -u' or re-run with --pr.",
+            reverted.len(),
+            branch
+        );
+        return Ok(());
+    }
+
+    // Push and open PRs
+    println!();
+    let mut created: Vec<RevertPr> = Vec::new();
+
+    for item in &reverted {
+        let repo = item.repo;
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let spinner = Output::spinner(&format!("Pushing {}...", repo.name));
+        let push = PushMode::Normal { set_upstream: true };
+        if let Err(e) = push_audited(workspace_root, &repo.name, &git_repo, &branch, push) {
+            spinner.finish_with_message(format!("{}: push failed - {}", repo.name, e));
+            continue;
+        }
+
+        let platform = get_platform_adapter(detect_platform(&repo.url), None);
+        let body = revert_body(&item.commits);
+
+        if let Ok(Some(pr)) = platform
+            .find_pr_by_branch(&repo.owner, &repo.repo, &branch)
+            .await
+        {
+            spinner.finish_with_message(format!("{}: PR #{} already open", repo.name, pr.number));
+            created.push(RevertPr {
+                repo,
+                platform,
+                number: pr.number,
+                url: pr.url,
+                body,
+            });
+            continue;
+        }
+
+        let title = options
+            .title
+            .clone()
+            .unwrap_or_else(|| revert_title(&item.commits));
+        match platform
+            .create_pull_request(
+                &repo.owner,
+                &repo.repo,
+                &branch,
+                &repo.default_branch,
+                &title,
+                Some(&body),
+                options.draft,
+            )
+            .await
+        {
+            Ok(pr) => {
+                spinner.finish_with_message(format!(
+                    "{}: created PR #{} - {}",
+                    repo.name, pr.number, pr.url
+                ));
+                created.push(RevertPr {
+                    repo,
+                    platform,
+                    number: pr.number,
+                    url: pr.url,
+                    body,
+                });
+            }
+            Err(e) => spinner.finish_with_message(format!("{}: failed - {}", repo.name, e)),
+        }
+    }
+
+    // Cross-link the revert PRs with each other
+    if created.len() > 1 {
+        let links: Vec<LinkedPRRef> = created
+            .iter()
+            .map(|pr| LinkedPRRef {
+                repo_name: pr.repo.name.clone(),
+                number: pr.number,
+            })
+            .collect();
+
+        for pr in &created {
+            let body = format!(
+                "{}\n\n{}",
+                pr.body,
+                pr.platform.generate_linked_pr_comment(&links)
+            );
+            if let Err(e) = pr
+                .platform
+                .update_pull_request_body(&pr.repo.owner, &pr.repo.repo, pr.number, &body)
+                .await
+            {
+                Output::warning(&format!("{}: failed to link PRs - {}", pr.repo.name, e));
+            }
+        }
+    }
+
+    println!();
+    if created.is_empty() {
+        Output::warning("No revert PRs were created.");
+    } else {
+        Output::success("Revert PR(s):");
+        for pr in &created {
+            println!("  {}: #{} - {}", pr.repo.name, pr.number, pr.url);
+        }
+    }
+
+    Ok(())
+}
+
+/// Default branch name for reverting `commit`, e.g. `revert/1a2b3c4`
+fn revert_branch_name(commit: &str) -> String {
+    format!("revert/{}", &commit[..7.min(commit.len())])
+}
+
+/// The commits among `specs` that are on `base` in `repo`, newest first,
+/// with their subjects
+///
+/// Only commit SHAs are accepted: a branch name or `HEAD` would resolve in
+/// every repo, and a commit only on an unmerged branch isn't there to revert.
+fn find_commits(repo: &Repository, specs: &[String], base: &str) -> Vec<(Oid, String)> {
+    let Ok(base) = repo
+        .revparse_single(&format!("{}^{{commit}}", base))
+        .map(|object| object.id())
+    else {
+        return Vec::new();
+    };
+
+    let wanted: HashSet<Oid> = specs
+        .iter()
+        .filter(|spec| is_commit_sha(spec))
+        .filter_map(|spec| repo.revparse_single(&format!("{}^{{commit}}", spec)).ok())
+        .map(|object| object.id())
+        .filter(|oid| *oid == base || repo.graph_descendant_of(base, *oid).unwrap_or(false))
+        .collect();
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    // Walk back from the base so the newest commit is reverted first
+    let Ok(mut walk) = repo.revwalk() else {
+        return Vec::new();
+    };
+    let _ = walk.set_sorting(git2::Sort::TOPOLOGICAL);
+    if walk.push(base).is_err() {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    for oid in walk.flatten() {
+        if wanted.contains(&oid) {
+            let subject = repo
+                .find_commit(oid)
+                .ok()
+                .and_then(|commit| commit.summary().map(str::to_string))
+                .unwrap_or_default();
+            found.push((oid, subject));
+            if found.len() == wanted.len() {
+                break;
+            }
+        }
+    }
+    found
+}
+
+/// Whether `spec` looks like a full or abbreviated commit SHA
+fn is_commit_sha(spec: &str) -> bool {
+    (4..=40).contains(&spec.len()) && spec.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Revert `commits` in order, one revert commit each
+///
+/// `mainline` is only passed for merge commits: git requires it there and
+/// rejects it for ordinary commits, and the list may mix both.
+fn revert_commits(
+    repo: &Repository,
+    commits: &[Oid],
+    mainline: Option<u32>,
+) -> Result<(), GitError> {
+    let mainline = mainline.map(|parent| parent.to_string());
+    for oid in commits {
+        let sha = oid.to_string();
+        let mut args = vec!["revert", "--no-edit"];
+        if let Some(parent) = &mainline {
+            if repo.find_commit(*oid)?.parent_count() > 1 {
+                args.extend(["-m", parent.as_str()]);
+            }
+        }
+        args.push(&sha);
+        run_git(get_workdir(repo), &args)?;
+    }
+    Ok(())
+}
+
+fn revert_title(commits: &[(Oid, String)]) -> String {
+    match commits {
+        [(_, subject)] => format!("Revert \"{}\"", subject),
+        _ => format!("Revert {} commits", commits.len()),
+    }
+}
+
+fn revert_body(commits: &[(Oid, String)]) -> String {
+    let lines: Vec<String> = commits
+        .iter()
+        .map(|(oid, subject)| format!("- {} {}", oid, subject))
+        .collect();
+    format!("Reverts:\n\n{}", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_revert_branch_name() {
+        assert_eq!(revert_branch_name("1a2b3c4d5e6f"), "revert/1a2b3c4");
+        assert_eq!(revert_branch_name("v1.2"), "revert/v1.2");
+    }
+
+    #[test]
+    fn test_find_and_revert_commits() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::write(path.join("config.txt"), "timeout = 30\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Add config"]);
+        std::fs::write(path.join("config.txt"), "timeout = 1\n").unwrap();
+        git(path, &["commit", "-q", "-am", "Lower timeout"]);
+        let bad = git(path, &["rev-parse", "HEAD"]);
+
+        let repo = open_repo(path).unwrap();
+        let found = find_commits(&repo, &[bad[..8].to_string(), "0".repeat(40)], "main");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1, "Lower timeout");
+        assert_eq!(revert_title(&found), "Revert \"Lower timeout\"");
+
+        revert_commits(&repo, &[found[0].0], None).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.join("config.txt")).unwrap(),
+            "timeout = 30\n"
+        );
+        assert_eq!(
+            git(path, &["log", "-1", "--format=%s"]),
+            "Revert \"Lower timeout\""
+        );
+    }
+
+    #[test]
+    fn test_revert_mixed_merge_and_plain_commits() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        git(path, &["commit", "-q", "--allow-empty", "-m", "Initial"]);
+        git(path, &["checkout", "-q", "-b", "feat"]);
+        std::fs::write(path.join("feature.txt"), "on\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Add feature"]);
+        git(path, &["checkout", "-q", "main"]);
+        git(
+            path,
+            &["merge", "-q", "--no-ff", "-m", "Merge feat", "feat"],
+        );
+        let merge = git(path, &["rev-parse", "HEAD"]);
+        std::fs::write(path.join("config.txt"), "debug\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Enable debug"]);
+        let plain = git(path, &["rev-parse", "HEAD"]);
+
+        let repo = open_repo(path).unwrap();
+        let commits = [
+            Oid::from_str(&plain).unwrap(),
+            Oid::from_str(&merge).unwrap(),
+        ];
+        revert_commits(&repo, &commits, Some(1)).unwrap();
+        assert!(!path.join("config.txt").exists());
+        assert!(!path.join("feature.txt").exists());
+    }
+
+    #[test]
+    fn test_find_commits_only_on_base_newest_first() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        git(path, &["commit", "-q", "--allow-empty", "-m", "First"]);
+        let first = git(path, &["rev-parse", "HEAD"]);
+        git(path, &["commit", "-q", "--allow-empty", "-m", "Second"]);
+        let second = git(path, &["rev-parse", "HEAD"]);
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        git(path, &["commit", "-q", "--allow-empty", "-m", "Unmerged"]);
+        let unmerged = git(path, &["rev-parse", "HEAD"]);
+
+        let repo = open_repo(path).unwrap();
+        let specs = vec![
+            first,
+            second.clone(),
+            unmerged,
+            "HEAD".to_string(),
+            "main".to_string(),
+        ];
+        let found = find_commits(&repo, &specs, "main");
+        let subjects: Vec<&str> = found.iter().map(|(_, s)| s.as_str()).collect();
+        assert_eq!(subjects, vec!["Second", "First"]);
+        assert_eq!(found[0].0.to_string(), second);
+    }
+}
@@ -3,16 +3,22 @@
 //! Runs workspace scripts defined in manifest.
 
 use crate::cli::output::Output;
-use crate::core::manifest::Manifest;
+use crate::core::manifest::{Manifest, ShellKind};
+use crate::util::process::{spawn_in_group, wait_with_timeout, Outcome};
+use crate::util::shell_command;
 use std::path::PathBuf;
-use std::process::Command;
+use std::time::{Duration, Instant};
 
 /// Run the run command
+///
+/// With a `timeout`, each command (or step) that runs longer is killed along
+/// with any processes it started.
 pub fn run_run(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     script_name: Option<&str>,
     list: bool,
+    timeout: Option<Duration>,
 ) -> anyhow::Result<()> {
     let scripts = manifest.workspace.as_ref().and_then(|w| w.scripts.as_ref());
 
@@ -55,10 +61,13 @@ pub fn run_run(
     Output::header(&format!("Running script: {}", name));
     println!();
 
+    let shell = manifest.shell();
+    let started = Instant::now();
+
     // Execute the script
     if let Some(ref command) = script.command {
         // Single command script
-        run_command(workspace_root, command)?;
+        run_command(workspace_root, command, shell, timeout)?;
     } else if let Some(ref steps) = script.steps {
         // Multi-step script
         for (i, step) in steps.iter().enumerate() {
@@ -74,27 +83,47 @@ pub fn run_run(
                 .as_ref()
                 .map(|p| workspace_root.join(p))
                 .unwrap_or_else(|| workspace_root.clone());
-            run_command(&working_dir, &step.command)?;
+            run_command(&working_dir, &step.command, shell, timeout)?;
             println!();
         }
     } else {
         anyhow::bail!("Script '{}' has no command or steps defined", name);
     }
 
-    Output::success(&format!("Script '{}' completed", name));
+    Output::success(&format!(
+        "Script '{}' completed in {:.1}s",
+        name,
+        started.elapsed().as_secs_f64()
+    ));
     Ok(())
 }
 
-fn run_command(working_dir: &PathBuf, command: &str) -> anyhow::Result<()> {
-    let status = Command::new("sh")
-        .arg("-c")
-        .arg(command)
-        .current_dir(working_dir)
-        .status()?;
+fn run_command(
+    working_dir: &PathBuf,
+    command: &str,
+    shell: ShellKind,
+    timeout: Option<Duration>,
+) -> anyhow::Result<()> {
+    let mut cmd = shell_command(shell, command);
+    cmd.current_dir(working_dir);
+    // Only a separate process group can be killed as a whole on timeout, but
+    // it can't read the terminal (stdin is null); otherwise stay in the
+    // terminal's group so Ctrl-C and prompts reach the command
+    let mut child = if timeout.is_some() {
+        spawn_in_group(&mut cmd)?
+    } else {
+        cmd.spawn()?
+    };
 
-    if !status.success() {
-        anyhow::bail!("Command failed with exit code: {:?}", status.code());
+    match wait_with_timeout(&mut child, timeout)? {
+        Outcome::Exited(status) if status.success() => Ok(()),
+        Outcome::Exited(status) => {
+            anyhow::bail!("Command failed with exit code: {:?}", status.code())
+        }
+        Outcome::TimedOut => anyhow::bail!(
+            "Command timed out after {}s",
+            timeout.unwrap_or_default().as_secs_f64()
+        ),
+        Outcome::Cancelled => anyhow::bail!("Cancelled"),
     }
-
-    Ok(())
 }
@@ -0,0 +1,363 @@
+//! Sed command implementation
+//!
+//! Applies a regex replacement to tracked files across repos, previews the
+//! combined diff, and on confirmation commits the change on a new branch in
+//! each affected repo. Nothing is written to disk until the change is
+//! confirmed.
+
+use crate::cli::diff_render::DiffRenderer;
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::branch::create_and_checkout_branch;
+use crate::git::status::get_status_info;
+use crate::git::{get_current_branch, open_repo, path_exists, run_git};
+use dialoguer::Confirm;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Settings for `gr sed`
+#[derive(Debug, Clone)]
+pub struct SedOptions {
+    /// Branch created in each changed repo
+    pub branch: String,
+    /// Commit message shared by every repo
+    pub message: String,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    /// Only show the diff; leave files untouched
+    pub dry_run: bool,
+}
+
+/// A file's contents before and after the replacement
+struct FileRewrite {
+    file: String,
+    original: String,
+    replaced: String,
+}
+
+/// Files rewritten in one repo
+struct RepoEdit<'a> {
+    repo: &'a RepoInfo,
+    rewrites: Vec<FileRewrite>,
+}
+
+/// Run the sed command
+///
+/// `replacement` may reference capture groups as `$1` or `${name}`.
+pub fn run_sed(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    pattern: &str,
+    replacement: &str,
+    pathspecs: &[String],
+    options: &SedOptions,
+) -> anyhow::Result<()> {
+    let regex = Regex::new(pattern)?;
+
+    let repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .collect();
+
+    let mut edits: Vec<RepoEdit> = Vec::new();
+    for repo in &repos {
+        let files = tracked_files(&repo.absolute_path, pathspecs)?;
+        let rewrites: Vec<FileRewrite> = files
+            .into_iter()
+            .filter_map(|file| {
+                let full = repo.absolute_path.join(&file);
+                // A tracked symlink may point outside the repo; never write through it
+                if std::fs::symlink_metadata(&full)
+                    .ok()?
+                    .file_type()
+                    .is_symlink()
+                {
+                    return None;
+                }
+                let original = std::fs::read_to_string(&full).ok()?;
+                let replaced = regex.replace_all(&original, replacement).into_owned();
+                (replaced != original).then_some(FileRewrite {
+                    file,
+                    original,
+                    replaced,
+                })
+            })
+            .collect();
+        if rewrites.is_empty() {
+            continue;
+        }
+
+        // Never mix the replacement with unrelated local changes
+        let dirty = open_repo(&repo.absolute_path)
+            .ok()
+            .and_then(|r| get_status_info(&r).ok())
+            .map(|s| !s.staged.is_empty() || !s.modified.is_empty())
+            .unwrap_or(true);
+        if dirty {
+            Output::warning(&format!("{}: has uncommitted changes, skipping", repo.name));
+            continue;
+        }
+
+        edits.push(RepoEdit { repo, rewrites });
+    }
+
+    if edits.is_empty() {
+        Output::info("No matches found.");
+        return Ok(());
+    }
+
+    let renderer = DiffRenderer::new(&manifest.settings.diff);
+    for edit in &edits {
+        Output::header(&edit.repo.name);
+        for rewrite in &edit.rewrites {
+            print!("{}", renderer.render(&unified_diff(rewrite)?));
+        }
+        println!();
+    }
+
+    let file_count: usize = edits.iter().map(|e| e.rewrites.len()).sum();
+    let summary = format!("{} file(s) in {} repo(s)", file_count, edits.len());
+
+    let proceed = if options.dry_run {
+        false
+    } else if options.yes {
+        true
+    } else {
+        Confirm::with_theme(&*Output::theme())
+            .with_prompt(format!(
+                "Commit changes to {} on branch '{}'?",
+                summary, options.branch
+            ))
+            .default(false)
+            .interact()?
+    };
+
+    if !proceed {
+        Output::info(&format!("Left {} unchanged.", summary));
+        return Ok(());
+    }
+
+    println!();
+    let mut committed = 0;
+    for edit in &edits {
+        match commit_edit(edit, options) {
+            Ok(()) => {
+                Output::success(&format!(
+                    "{}: committed {} file(s) on {}",
+                    edit.repo.name,
+                    edit.rewrites.len(),
+                    options.branch
+                ));
+                committed += 1;
+            }
+            Err(e) => Output::error(&format!("{}: {}", edit.repo.name, e)),
+        }
+    }
+
+    println!();
+    Output::success(&format!(
+        "Committed in {}/{} repo(s). Push with 'gr push -u' and open PRs with 'gr pr create'.",
+        committed,
+        edits.len()
+    ));
+    Ok(())
+}
+
+/// Write the rewritten files and commit them, restoring the originals and the
+/// previously checked-out branch on failure
+fn commit_edit(edit: &RepoEdit, options: &SedOptions) -> anyhow::Result<()> {
+    let path = &edit.repo.absolute_path;
+    let git_repo = open_repo(path)?;
+    let original_branch = get_current_branch(&git_repo)?;
+    create_and_checkout_branch(&git_repo, &options.branch)?;
+
+    let result = write_and_commit(edit, options);
+    if result.is_err() {
+        for rewrite in &edit.rewrites {
+            let _ = std::fs::write(path.join(&rewrite.file), &rewrite.original);
+        }
+        let mut reset = vec!["reset", "-q", "--"];
+        reset.extend(edit.rewrites.iter().map(|r| r.file.as_str()));
+        let _ = run_git(path, &reset);
+        if run_git(path, &["checkout", "-q", &original_branch]).is_ok() {
+            let _ = run_git(path, &["branch", "-D", &options.branch]);
+        }
+    }
+    result
+}
+
+fn write_and_commit(edit: &RepoEdit, options: &SedOptions) -> anyhow::Result<()> {
+    let path = &edit.repo.absolute_path;
+    for rewrite in &edit.rewrites {
+        std::fs::write(path.join(&rewrite.file), &rewrite.replaced)?;
+    }
+
+    let mut add = vec!["add", "--"];
+    add.extend(edit.rewrites.iter().map(|r| r.file.as_str()));
+    run_git(path, &add)?;
+    run_git(path, &["commit", "-m", &options.message])?;
+    Ok(())
+}
+
+/// Unified diff of one rewrite, built in memory
+fn unified_diff(rewrite: &FileRewrite) -> anyhow::Result<String> {
+    let path = Path::new(&rewrite.file);
+    let mut patch = git2::Patch::from_buffers(
+        rewrite.original.as_bytes(),
+        Some(path),
+        rewrite.replaced.as_bytes(),
+        Some(path),
+        None,
+    )?;
+    Ok(String::from_utf8_lossy(&patch.to_buf()?).into_owned())
+}
+
+fn tracked_files(repo_path: &Path, pathspecs: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut args = vec!["ls-files", "-z", "--"];
+    args.extend(pathspecs.iter().map(|p| p.as_str()));
+    let output = run_git(repo_path, &args)?;
+    Ok(output
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(|f| f.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::init_repo;
+    use tempfile::TempDir;
+
+    fn setup_workspace() -> (TempDir, Manifest) {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("app");
+        init_repo(&repo);
+        std::fs::write(repo.join("lib.rs"), "use old_name::Client;\n").unwrap();
+        std::fs::write(repo.join("notes.txt"), "nothing here\n").unwrap();
+        run_git(&repo, &["add", "."]).unwrap();
+        run_git(&repo, &["commit", "-m", "Initial commit"]).unwrap();
+
+        let manifest = Manifest::parse(
+            "repos:\n  app:\n    url: git@github.com:acme/app.git\n    path: app\n",
+        )
+        .unwrap();
+        (temp, manifest)
+    }
+
+    fn options(dry_run: bool) -> SedOptions {
+        SedOptions {
+            branch: "refactor/rename".to_string(),
+            message: "Rename old_name to new_name".to_string(),
+            yes: true,
+            dry_run,
+        }
+    }
+
+    #[test]
+    fn test_sed_commits_on_branch() {
+        let (temp, manifest) = setup_workspace();
+        let root = temp.path().to_path_buf();
+        let repo = root.join("app");
+
+        run_sed(
+            &root,
+            &manifest,
+            r"old_(\w+)",
+            "new_$1",
+            &[],
+            &options(false),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(repo.join("lib.rs")).unwrap();
+        assert_eq!(content, "use new_name::Client;\n");
+        let branch = run_git(&repo, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap();
+        assert_eq!(branch.trim(), "refactor/rename");
+        let subject = run_git(&repo, &["log", "-1", "--format=%s"]).unwrap();
+        assert_eq!(subject.trim(), "Rename old_name to new_name");
+    }
+
+    #[test]
+    fn test_sed_dry_run_leaves_files() {
+        let (temp, manifest) = setup_workspace();
+        let root = temp.path().to_path_buf();
+        let repo = root.join("app");
+
+        run_sed(
+            &root,
+            &manifest,
+            "old_name",
+            "new_name",
+            &[],
+            &options(true),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(repo.join("lib.rs")).unwrap();
+        assert_eq!(content, "use old_name::Client;\n");
+        let status = run_git(&repo, &["status", "--porcelain"]).unwrap();
+        assert!(status.is_empty());
+    }
+
+    #[test]
+    fn test_sed_failed_commit_restores_branch() {
+        let (temp, manifest) = setup_workspace();
+        let root = temp.path().to_path_buf();
+        let repo = root.join("app");
+        let hook = repo.join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        run_sed(
+            &root,
+            &manifest,
+            "old_name",
+            "new_name",
+            &[],
+            &options(false),
+        )
+        .unwrap();
+
+        let branch = run_git(&repo, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap();
+        assert_eq!(branch.trim(), "main");
+        let content = std::fs::read_to_string(repo.join("lib.rs")).unwrap();
+        assert_eq!(content, "use old_name::Client;\n");
+        let branches = run_git(&repo, &["branch", "--list", "refactor/rename"]).unwrap();
+        assert!(branches.trim().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sed_skips_symlinks() {
+        let (temp, manifest) = setup_workspace();
+        let root = temp.path().to_path_buf();
+        let repo = root.join("app");
+        let outside = root.join("outside.txt");
+        std::fs::write(&outside, "old_name\n").unwrap();
+        std::os::unix::fs::symlink(&outside, repo.join("link.txt")).unwrap();
+        run_git(&repo, &["add", "link.txt"]).unwrap();
+        run_git(&repo, &["commit", "-m", "Add link"]).unwrap();
+
+        run_sed(
+            &root,
+            &manifest,
+            "old_name",
+            "new_name",
+            &[],
+            &options(false),
+        )
+        .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&outside).unwrap(), "old_name\n");
+        let content = std::fs::read_to_string(repo.join("lib.rs")).unwrap();
+        assert_eq!(content, "use new_name::Client;\n");
+    }
+}
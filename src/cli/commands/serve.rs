@@ -0,0 +1,511 @@
+//! Serve command implementation
+//!
+//! Runs a long-lived JSON-RPC 2.0 server over stdio or a unix socket so
+//! editor extensions can query and drive the workspace without spawning a
+//! process per request. Messages may be framed LSP-style (`Content-Length`
+//! headers) or sent one per line; each response uses the framing of its
+//! request.
+//!
+//! The manifest is re-read for every request, so edits are picked up without
+//! restarting the server.
+
+use crate::workspace::Workspace;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Largest message (Content-Length body or single line) accepted from a client
+const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Methods understood by the server, returned by `initialize`
+const METHODS: &[&str] = &[
+    "initialize",
+    "workspace/info",
+    "workspace/status",
+    "workspace/branches",
+    "workspace/sync",
+    "branch/create",
+    "branch/checkout",
+    "pr/status",
+    "shutdown",
+    "exit",
+];
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: Option<String>,
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchParams {
+    name: String,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// How a message was framed on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    ContentLength,
+    Line,
+}
+
+/// Run the serve command
+///
+/// Serves stdio unless `socket` is given, in which case it listens on that
+/// unix socket and serves each connection concurrently.
+pub async fn run_serve(workspace_root: &Path, socket: Option<&Path>) -> anyhow::Result<()> {
+    let root = workspace_root.to_path_buf();
+    match socket {
+        None => {
+            serve_stream(
+                &root,
+                BufReader::new(tokio::io::stdin()),
+                tokio::io::stdout(),
+            )
+            .await
+        }
+        Some(path) => serve_socket(root, path).await,
+    }
+}
+
+#[cfg(unix)]
+async fn serve_socket(root: PathBuf, path: &Path) -> anyhow::Result<()> {
+    use tokio::net::UnixListener;
+
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    // A socket left behind by a previous server would make bind fail, but
+    // anything else at that path, or a socket a live server still answers
+    // on, isn't ours to delete
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            anyhow::bail!("{} exists and is not a socket", path.display());
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            anyhow::bail!("another server is already listening on {}", path.display());
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    // The server can change branches in every repo; keep it to this user
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    eprintln!("gr serve: listening on {}", path.display());
+
+    // Connections share one thread; git work is moved to blocking threads
+    let connections = tokio::task::LocalSet::new();
+    connections
+        .run_until(async move {
+            loop {
+                let (stream, _) = listener.accept().await?;
+                let root = root.clone();
+                tokio::task::spawn_local(async move {
+                    let (reader, writer) = stream.into_split();
+                    if let Err(e) = serve_stream(&root, BufReader::new(reader), writer).await {
+                        eprintln!("gr serve: connection error - {}", e);
+                    }
+                });
+            }
+        })
+        .await
+}
+
+#[cfg(not(unix))]
+async fn serve_socket(_root: PathBuf, _path: &Path) -> anyhow::Result<()> {
+    anyhow::bail!("--socket requires unix domain sockets; use stdio on this platform")
+}
+
+/// Answer requests from `reader` on `writer` until EOF or `exit`
+async fn serve_stream<R, W>(root: &Path, mut reader: R, mut writer: W) -> anyhow::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    while let Some((body, framing)) = read_message(&mut reader).await? {
+        let (response, exit) = match serde_json::from_slice::<Request>(&body) {
+            Ok(request) => {
+                let exit = request.method == "exit";
+                (respond(root, request).await, exit)
+            }
+            Err(e) => {
+                let code = if serde_json::from_slice::<Value>(&body).is_ok() {
+                    INVALID_REQUEST
+                } else {
+                    PARSE_ERROR
+                };
+                (
+                    Some(error_response(
+                        Value::Null,
+                        RpcError::new(code, e.to_string()),
+                    )),
+                    false,
+                )
+            }
+        };
+
+        if let Some(response) = response {
+            write_message(&mut writer, &response, framing).await?;
+        }
+        if exit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Read one message, or `None` at EOF
+async fn read_message<R>(reader: &mut R) -> anyhow::Result<Option<(Vec<u8>, Framing)>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if read_line_capped(reader, &mut line).await? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let Some(length) = content_length(&line) else {
+        return Ok(Some((line.trim().as_bytes().to_vec(), Framing::Line)));
+    };
+
+    // Skip any remaining headers (e.g. Content-Type) up to the blank line
+    loop {
+        line.clear();
+        if read_line_capped(reader, &mut line).await? == 0 {
+            return Ok(None);
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+
+    if length > MAX_MESSAGE_SIZE {
+        anyhow::bail!(
+            "message of {} bytes exceeds the {} byte limit",
+            length,
+            MAX_MESSAGE_SIZE
+        );
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some((body, Framing::ContentLength)))
+}
+
+/// `read_line` that refuses lines longer than [`MAX_MESSAGE_SIZE`] instead
+/// of buffering them without bound
+async fn read_line_capped<R>(reader: &mut R, line: &mut String) -> anyhow::Result<usize>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let read = reader
+        .take(MAX_MESSAGE_SIZE as u64 + 1)
+        .read_line(line)
+        .await?;
+    if read > MAX_MESSAGE_SIZE {
+        anyhow::bail!("message exceeds the {} byte limit", MAX_MESSAGE_SIZE);
+    }
+    Ok(read)
+}
+
+fn content_length(header: &str) -> Option<usize> {
+    let (name, value) = header.split_once(':')?;
+    if !name.trim().eq_ignore_ascii_case("content-length") {
+        return None;
+    }
+    value.trim().parse().ok()
+}
+
+async fn write_message<W>(writer: &mut W, message: &Value, framing: Framing) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let body = message.to_string();
+    match framing {
+        Framing::ContentLength => {
+            writer
+                .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+                .await?;
+            writer.write_all(body.as_bytes()).await?;
+        }
+        Framing::Line => {
+            writer.write_all(body.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+        }
+    }
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Dispatch a request; notifications (no `id`) get no response
+async fn respond(root: &Path, request: Request) -> Option<Value> {
+    let id = request.id.clone();
+    let result = if request.jsonrpc.as_deref() != Some("2.0") {
+        Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""))
+    } else {
+        dispatch(root, &request.method, request.params).await
+    };
+
+    let id = id?;
+    Some(match result {
+        Ok(value) => json!({ "jsonrpc": "2.0", "id": id, "result": value }),
+        Err(e) => error_response(id, e),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+async fn dispatch(root: &Path, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(json!({
+            "serverInfo": { "name": "gitgrip", "version": env!("CARGO_PKG_VERSION") },
+            "methods": METHODS,
+        })),
+        "shutdown" | "exit" => Ok(Value::Null),
+        "pr/status" => {
+            let workspace = open(root)?;
+            to_value(workspace.pull_requests().await)
+        }
+        "workspace/info" | "workspace/status" | "workspace/branches" | "workspace/sync"
+        | "branch/create" | "branch/checkout" => {
+            // Git work blocks, so keep it off the async workers
+            let root = root.to_path_buf();
+            let method = method.to_string();
+            tokio::task::spawn_blocking(move || dispatch_blocking(&root, &method, params))
+                .await
+                .map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))?
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", method),
+        )),
+    }
+}
+
+fn dispatch_blocking(root: &Path, method: &str, params: Value) -> Result<Value, RpcError> {
+    let workspace = open(root)?;
+    match method {
+        "workspace/info" => Ok(json!({
+            "root": workspace.root(),
+            "repos": workspace
+                .repos()
+                .iter()
+                .map(|repo| json!({
+                    "name": repo.name,
+                    "path": repo.path,
+                    "url": repo.url,
+                    "defaultBranch": repo.default_branch,
+                    "cloned": repo.exists(),
+                }))
+                .collect::<Vec<_>>(),
+        })),
+        "workspace/status" => to_value(workspace.status()),
+        "workspace/branches" => to_value(workspace.branches()),
+        "workspace/sync" => to_value(workspace.sync()),
+        "branch/create" => to_value(workspace.create_branch(&branch_name(params)?)),
+        "branch/checkout" => to_value(workspace.checkout(&branch_name(params)?)),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", method),
+        )),
+    }
+}
+
+fn open(root: &Path) -> Result<Workspace, RpcError> {
+    Workspace::open(root).map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))
+}
+
+fn branch_name(params: Value) -> Result<String, RpcError> {
+    serde_json::from_value::<BranchParams>(params)
+        .map(|p| p.name)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_workspace() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let manifests = temp.path().join(".gitgrip").join("manifests");
+        std::fs::create_dir_all(&manifests).unwrap();
+        std::fs::write(
+            manifests.join("manifest.yaml"),
+            "repos:\n  app:\n    url: git@github.com:acme/app.git\n    path: app\n",
+        )
+        .unwrap();
+        temp
+    }
+
+    async fn exchange(root: &Path, input: &str) -> String {
+        let mut output = Vec::new();
+        serve_stream(root, input.as_bytes(), &mut output)
+            .await
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_line_framed_requests() {
+        let temp = setup_workspace();
+        let output = exchange(
+            temp.path(),
+            concat!(
+                r#"{"jsonrpc":"2.0","id":1,"method":"workspace/info"}"#,
+                "\n",
+                r#"{"jsonrpc":"2.0","method":"workspace/status"}"#,
+                "\n",
+                r#"{"jsonrpc":"2.0","id":2,"method":"nope"}"#,
+                "\n",
+                "not json\n",
+            ),
+        )
+        .await;
+
+        let responses: Vec<Value> = output
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        // The notification gets no response
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["result"]["repos"][0]["name"], "app");
+        assert_eq!(responses[0]["result"]["repos"][0]["cloned"], false);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_content_length_framing_and_exit() {
+        let temp = setup_workspace();
+        let body = r#"{"jsonrpc":"2.0","id":"a","method":"branch/create","params":{}}"#;
+        let exit = r#"{"jsonrpc":"2.0","id":"b","method":"exit"}"#;
+        let after = r#"{"jsonrpc":"2.0","id":"c","method":"initialize"}"#;
+        let input = format!(
+            "Content-Length: {}\r\n\r\n{}Content-Length: {}\r\nContent-Type: application/json\r\n\r\n{}{}\n",
+            body.len(),
+            body,
+            exit.len(),
+            exit,
+            after
+        );
+
+        let output = exchange(temp.path(), &input).await;
+        let mut messages = Vec::new();
+        let mut rest = output.as_str();
+        while let Some((header, tail)) = rest.split_once("\r\n\r\n") {
+            let length = content_length(header).unwrap();
+            messages.push(serde_json::from_str::<Value>(&tail[..length]).unwrap());
+            rest = &tail[length..];
+        }
+
+        // Nothing is answered after exit
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["id"], "a");
+        assert_eq!(messages[0]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(messages[1]["id"], "b");
+        assert!(rest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_is_rejected() {
+        let temp = setup_workspace();
+        let input = format!("Content-Length: {}\r\n\r\n{{}}", MAX_MESSAGE_SIZE + 1);
+        let mut output = Vec::new();
+        assert!(serve_stream(temp.path(), input.as_bytes(), &mut output)
+            .await
+            .is_err());
+        assert!(output.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_line_is_rejected() {
+        let temp = setup_workspace();
+        let input = "x".repeat(MAX_MESSAGE_SIZE + 1);
+        let mut output = Vec::new();
+        assert!(serve_stream(temp.path(), input.as_bytes(), &mut output)
+            .await
+            .is_err());
+        assert!(output.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket_in_use_is_left_alone() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("gr.sock");
+        let _live = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        assert!(serve_socket(temp.path().to_path_buf(), &path)
+            .await
+            .is_err());
+        assert!(std::os::unix::net::UnixStream::connect(&path).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("gr.sock");
+        let serve = serve_socket(temp.path().to_path_buf(), &path);
+        // The server runs until killed; give it long enough to bind
+        let _ = tokio::time::timeout(std::time::Duration::from_millis(200), serve).await;
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket_path_must_not_be_a_regular_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("notes.txt");
+        std::fs::write(&path, "keep me").unwrap();
+        assert!(serve_socket(temp.path().to_path_buf(), &path)
+            .await
+            .is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+    }
+}
@@ -0,0 +1,246 @@
+//! Standup command implementation
+//!
+//! Summarizes your work across repos since a point in time: commits on each
+//! local branch, and PRs you opened, had merged, or reviewed on the hosting
+//! platform, printed as Markdown to paste into chat.
+
+use crate::core::audit::parse_since;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::open_repo;
+use crate::platform::{detect_platform, get_platform_adapter, PRActivity, PRActivityKind};
+use crate::util::profile::ProfiledCommand;
+use crate::util::{concurrent_map, max_workers, parallel_map};
+use chrono::{DateTime, Local, Utc};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Settings for `gr standup`
+#[derive(Debug, Clone, Default)]
+pub struct StandupOptions {
+    /// Age (`1d`, `12h`) or date to summarize from
+    pub since: String,
+    /// Author pattern for commits; each repo's `user.email` if unset
+    pub author: Option<String>,
+    /// Only use local history
+    pub no_prs: bool,
+}
+
+/// Commits on one branch, as `(short sha, subject)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BranchCommits {
+    branch: String,
+    commits: Vec<(String, String)>,
+}
+
+/// Everything you did in one repo
+#[derive(Debug, Clone, Default)]
+struct RepoActivity {
+    repo: String,
+    branches: Vec<BranchCommits>,
+    prs: Vec<PRActivity>,
+}
+
+/// Run the standup command
+pub async fn run_standup(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &StandupOptions,
+) -> anyhow::Result<()> {
+    let since = parse_since(&options.since, Utc::now()).map_err(anyhow::Error::msg)?;
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| r.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let collected = repos.clone();
+    let author = options.author.clone();
+    let branches = tokio::task::spawn_blocking(move || {
+        parallel_map(&collected, max_workers(), |repo| {
+            local_commits(&repo.absolute_path, since, author.as_deref())
+        })
+    })
+    .await?;
+
+    let mut activity: Vec<RepoActivity> = repos
+        .iter()
+        .zip(branches)
+        .map(|(repo, branches)| RepoActivity {
+            repo: repo.name.clone(),
+            branches,
+            prs: Vec::new(),
+        })
+        .collect();
+
+    if !options.no_prs {
+        let lookups = concurrent_map(
+            &repos,
+            manifest.settings.api_concurrency,
+            |repo| async move {
+                let platform = get_platform_adapter(detect_platform(&repo.url), None);
+                platform
+                    .list_pr_activity(&repo.owner, &repo.repo, since)
+                    .await
+            },
+        )
+        .await;
+        for (entry, lookup) in activity.iter_mut().zip(lookups) {
+            match lookup {
+                Ok(mut prs) => {
+                    prs.sort_by(|a, b| (a.kind, &a.at).cmp(&(b.kind, &b.at)));
+                    entry.prs = prs;
+                }
+                // Keep stdout clean for pasting
+                Err(e) => eprintln!("{}: could not look up PRs: {}", entry.repo, e),
+            }
+        }
+    }
+
+    print!("{}", render_markdown(&activity, since));
+    Ok(())
+}
+
+/// Commits since `since` by `author` (or the repo's `user.email`) on each
+/// local branch
+fn local_commits(path: &Path, since: DateTime<Utc>, author: Option<&str>) -> Vec<BranchCommits> {
+    let author = match author {
+        Some(author) => author.to_string(),
+        None => match open_repo(path)
+            .ok()
+            .and_then(|repo| repo.config().ok()?.get_string("user.email").ok())
+        {
+            Some(email) => email,
+            None => return Vec::new(),
+        },
+    };
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--branches",
+            "--source",
+            "--no-merges",
+            "--fixed-strings",
+            &format!("--since={}", since.to_rfc3339()),
+            &format!("--author={}", author),
+            "--format=%h%x1f%S%x1f%s",
+        ])
+        .current_dir(path)
+        .timed_output();
+    match output {
+        Ok(o) if o.status.success() => group_by_branch(&String::from_utf8_lossy(&o.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// Group `git log --source` lines (`sha`, ref, subject) by branch, in the
+/// order branches first appear
+fn group_by_branch(stdout: &str) -> Vec<BranchCommits> {
+    let mut branches: Vec<BranchCommits> = Vec::new();
+    for line in stdout.lines() {
+        let mut fields = line.split('\x1f');
+        let (Some(sha), Some(source), Some(subject)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let branch = source.strip_prefix("refs/heads/").unwrap_or(source);
+        let commit = (sha.to_string(), subject.to_string());
+        match branches.iter_mut().find(|b| b.branch == branch) {
+            Some(entry) => entry.commits.push(commit),
+            None => branches.push(BranchCommits {
+                branch: branch.to_string(),
+                commits: vec![commit],
+            }),
+        }
+    }
+    branches
+}
+
+fn render_markdown(activity: &[RepoActivity], since: DateTime<Utc>) -> String {
+    let mut out = format!(
+        "## Standup (since {})\n",
+        since.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+    );
+    let active: Vec<&RepoActivity> = activity
+        .iter()
+        .filter(|a| !a.branches.is_empty() || !a.prs.is_empty())
+        .collect();
+    if active.is_empty() {
+        out.push_str("\n_No activity._\n");
+        return out;
+    }
+
+    for repo in active {
+        out.push_str(&format!("\n### {}\n\n", repo.repo));
+        for branch in &repo.branches {
+            let count = branch.commits.len();
+            out.push_str(&format!(
+                "- `{}`: {} commit{}\n",
+                branch.branch,
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+            for (sha, subject) in &branch.commits {
+                out.push_str(&format!("  - {} ({})\n", subject, sha));
+            }
+        }
+        for pr in &repo.prs {
+            let verb = match pr.kind {
+                PRActivityKind::Opened => "Opened",
+                PRActivityKind::Merged => "Merged",
+                PRActivityKind::Reviewed => "Reviewed",
+            };
+            out.push_str(&format!(
+                "- {} [#{} {}]({})\n",
+                verb, pr.number, pr.title, pr.url
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown() {
+        let branches = group_by_branch(
+            "a1\x1frefs/heads/feat/login\x1fAdd form\n\
+             b2\x1frefs/heads/main\x1fFix typo\n\
+             c3\x1frefs/heads/feat/login\x1fWire up API\n",
+        );
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].branch, "feat/login");
+        assert_eq!(branches[0].commits.len(), 2);
+
+        let activity = vec![
+            RepoActivity {
+                repo: "api".to_string(),
+                branches,
+                prs: vec![PRActivity {
+                    kind: PRActivityKind::Reviewed,
+                    number: 9,
+                    title: "Bump deps".to_string(),
+                    url: "https://example.com/9".to_string(),
+                    at: "2026-05-01T10:00:00Z".to_string(),
+                }],
+            },
+            RepoActivity {
+                repo: "web".to_string(),
+                ..Default::default()
+            },
+        ];
+        let out = render_markdown(&activity, Utc::now());
+        assert!(out.contains("### api\n\n- `feat/login`: 2 commits\n  - Add form (a1)\n"));
+        assert!(out.contains("- `main`: 1 commit\n"));
+        assert!(out.contains("- Reviewed [#9 Bump deps](https://example.com/9)\n"));
+        assert!(!out.contains("### web"));
+
+        assert!(render_markdown(&[], Utc::now()).contains("_No activity._"));
+    }
+}
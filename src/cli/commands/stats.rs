@@ -0,0 +1,359 @@
+//! Stats command implementation
+//!
+//! Contribution stats per repo and for the whole workspace over a window:
+//! commits and lines changed on each default branch, distinct authors, and
+//! how long merged PRs took from opening to merge.
+
+use crate::cli::output::{Output, Table};
+use crate::core::audit::parse_since;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::{get_remote_tracking_oid, open_repo};
+use crate::platform::{detect_platform, get_platform_adapter, MergedPR};
+use crate::util::profile::ProfiledCommand;
+use crate::util::{concurrent_map, max_workers, parallel_map};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Settings for `gr stats`
+#[derive(Debug, Clone, Default)]
+pub struct StatsOptions {
+    /// Start of the window: an age (`30d`) or date
+    pub since: String,
+    /// End of the window; now if unset
+    pub until: Option<String>,
+    /// Output JSON
+    pub json: bool,
+    /// Output CSV
+    pub csv: bool,
+    /// Skip PR cycle times
+    pub no_prs: bool,
+}
+
+/// History on a default branch within the window
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct History {
+    commits: usize,
+    additions: u64,
+    deletions: u64,
+    /// Lowercased author emails
+    authors: BTreeSet<String>,
+}
+
+/// Stats for one repo, or the workspace total
+#[derive(Debug, Clone, Serialize)]
+struct RepoStats {
+    repo: String,
+    commits: usize,
+    additions: u64,
+    deletions: u64,
+    contributors: usize,
+    /// Unknown with `--no-prs` or when the platform couldn't be queried
+    prs_merged: Option<usize>,
+    /// Median hours from opening to merge
+    median_cycle_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsReport {
+    since: String,
+    until: String,
+    repos: Vec<RepoStats>,
+    total: RepoStats,
+}
+
+/// Run the stats command
+pub async fn run_stats(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &StatsOptions,
+) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let since = parse_since(&options.since, now).map_err(anyhow::Error::msg)?;
+    let until = match options.until {
+        Some(ref until) => {
+            parse_since(until, now).map_err(|e| anyhow::anyhow!(e.replace("--since", "--until")))?
+        }
+        None => now,
+    };
+    if since >= until {
+        anyhow::bail!("--since must be before --until");
+    }
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|r| r.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let collected = repos.clone();
+    let histories = tokio::task::spawn_blocking(move || {
+        parallel_map(&collected, max_workers(), |repo| {
+            history(&repo.absolute_path, &repo.default_branch, since, until)
+        })
+    })
+    .await?;
+
+    let mut cycle_times: Vec<Option<Vec<f64>>> = vec![None; repos.len()];
+    if !options.no_prs {
+        let lookups = concurrent_map(
+            &repos,
+            manifest.settings.api_concurrency,
+            |repo| async move {
+                let platform = get_platform_adapter(detect_platform(&repo.url), None);
+                platform
+                    .list_merged_pull_requests(&repo.owner, &repo.repo, since)
+                    .await
+            },
+        )
+        .await;
+        for ((repo, lookup), slot) in repos.iter().zip(lookups).zip(cycle_times.iter_mut()) {
+            match lookup {
+                Ok(prs) => *slot = Some(cycle_hours(&prs, until)),
+                // Warnings go to stderr so JSON and CSV stay parseable
+                Err(e) => eprintln!("{}: could not list merged PRs: {}", repo.name, e),
+            }
+        }
+    }
+
+    let mut stats = Vec::new();
+    let mut all_authors = BTreeSet::new();
+    let mut all_cycles: Option<Vec<f64>> = None;
+    for ((repo, history), cycles) in repos.iter().zip(histories).zip(cycle_times) {
+        let history = match history {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("{}: {}", repo.name, e);
+                History::default()
+            }
+        };
+        if let Some(ref cycles) = cycles {
+            all_cycles.get_or_insert_with(Vec::new).extend(cycles);
+        }
+        stats.push(repo_stats(&repo.name, &history, cycles.as_deref()));
+        all_authors.extend(history.authors);
+    }
+    let total = RepoStats {
+        repo: "total".to_string(),
+        commits: stats.iter().map(|s| s.commits).sum(),
+        additions: stats.iter().map(|s| s.additions).sum(),
+        deletions: stats.iter().map(|s| s.deletions).sum(),
+        contributors: all_authors.len(),
+        prs_merged: all_cycles.as_ref().map(Vec::len),
+        median_cycle_hours: all_cycles.and_then(median),
+    };
+    let report = StatsReport {
+        since: since.to_rfc3339_opts(SecondsFormat::Secs, true),
+        until: until.to_rfc3339_opts(SecondsFormat::Secs, true),
+        repos: stats,
+        total,
+    };
+
+    if options.json {
+        Output::json(&report)?;
+    } else if options.csv {
+        print!("{}", to_csv(&report));
+    } else {
+        print_table(&report);
+    }
+    Ok(())
+}
+
+/// Non-merge commits on the default branch (the remote's, when fetched)
+/// between `since` and `until`
+fn history(
+    path: &Path,
+    default_branch: &str,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> anyhow::Result<History> {
+    let git_repo = open_repo(path)?;
+    let branch = match get_remote_tracking_oid(&git_repo, "origin", default_branch) {
+        Some(_) => format!("origin/{}", default_branch),
+        None => default_branch.to_string(),
+    };
+    let output = Command::new("git")
+        .args([
+            "log",
+            &branch,
+            "--no-merges",
+            &format!("--since={}", since.to_rfc3339()),
+            &format!("--until={}", until.to_rfc3339()),
+            "--format=%x1e%aE",
+            "--numstat",
+            "--",
+        ])
+        .current_dir(path)
+        .timed_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git log {} failed: {}",
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_history(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git log --format=%x1e%aE --numstat`; binary files count no lines
+fn parse_history(stdout: &str) -> History {
+    let mut history = History::default();
+    for record in stdout.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let mut lines = record.lines();
+        let email = lines.next().unwrap_or_default().trim().to_lowercase();
+        history.commits += 1;
+        if !email.is_empty() {
+            history.authors.insert(email);
+        }
+        for line in lines {
+            let mut fields = line.split('\t');
+            if let (Some(added), Some(deleted)) = (fields.next(), fields.next()) {
+                history.additions += added.parse::<u64>().unwrap_or(0);
+                history.deletions += deleted.parse::<u64>().unwrap_or(0);
+            }
+        }
+    }
+    history
+}
+
+/// Hours from opening to merge for PRs merged by `until`
+fn cycle_hours(prs: &[MergedPR], until: DateTime<Utc>) -> Vec<f64> {
+    prs.iter()
+        .filter_map(|pr| {
+            let created = DateTime::parse_from_rfc3339(&pr.created_at).ok()?;
+            let merged = DateTime::parse_from_rfc3339(&pr.merged_at).ok()?;
+            (merged <= until).then(|| (merged - created).num_seconds().max(0) as f64 / 3600.0)
+        })
+        .collect()
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+fn repo_stats(name: &str, history: &History, cycles: Option<&[f64]>) -> RepoStats {
+    RepoStats {
+        repo: name.to_string(),
+        commits: history.commits,
+        additions: history.additions,
+        deletions: history.deletions,
+        contributors: history.authors.len(),
+        prs_merged: cycles.map(<[f64]>::len),
+        median_cycle_hours: cycles.and_then(|c| median(c.to_vec())),
+    }
+}
+
+/// `5.5h` under two days, else `3.2d`
+fn format_hours(hours: f64) -> String {
+    if hours < 48.0 {
+        format!("{:.1}h", hours)
+    } else {
+        format!("{:.1}d", hours / 24.0)
+    }
+}
+
+fn print_table(report: &StatsReport) {
+    Output::header(&format!("Stats ({} to {})", report.since, report.until));
+    println!();
+
+    let mut table = Table::new(vec![
+        "Repo",
+        "Commits",
+        "Lines +/-",
+        "Contributors",
+        "PRs merged",
+        "Median cycle",
+    ]);
+    let unknown = || "-".to_string();
+    let mut add_row = |name: &str, stats: &RepoStats| {
+        table.add_row(vec![
+            name,
+            &stats.commits.to_string(),
+            &format!("+{} -{}", stats.additions, stats.deletions),
+            &stats.contributors.to_string(),
+            &stats.prs_merged.map_or_else(unknown, |n| n.to_string()),
+            &stats.median_cycle_hours.map_or_else(unknown, format_hours),
+        ]);
+    };
+    for stats in &report.repos {
+        add_row(&Output::repo_name(&stats.repo), stats);
+    }
+    add_row("Total", &report.total);
+    table.print();
+}
+
+/// One row per repo, then the total
+fn to_csv(report: &StatsReport) -> String {
+    let mut out = String::from(
+        "repo,commits,additions,deletions,contributors,prs_merged,median_cycle_hours\n",
+    );
+    for stats in report.repos.iter().chain([&report.total]) {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            stats.repo,
+            stats.commits,
+            stats.additions,
+            stats.deletions,
+            stats.contributors,
+            stats.prs_merged.map(|n| n.to_string()).unwrap_or_default(),
+            stats
+                .median_cycle_hours
+                .map(|h| format!("{:.2}", h))
+                .unwrap_or_default(),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_and_cycle_times() {
+        let history = parse_history(
+            "\x1eAna@Example.com\n\n3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n\
+             \x1eana@example.com\n\n10\t0\tREADME.md\n\
+             \x1ebo@example.com\n",
+        );
+        assert_eq!(history.commits, 3);
+        assert_eq!((history.additions, history.deletions), (13, 1));
+        assert_eq!(history.authors.len(), 2);
+
+        let until = DateTime::parse_from_rfc3339("2026-05-10T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let pr = |created: &str, merged: &str| MergedPR {
+            number: 1,
+            created_at: created.to_string(),
+            merged_at: merged.to_string(),
+        };
+        let cycles = cycle_hours(
+            &[
+                pr("2026-05-01T00:00:00Z", "2026-05-01T06:00:00Z"),
+                pr("2026-05-01T00:00:00Z", "2026-05-03T00:00:00Z"),
+                pr("2026-05-01T00:00:00Z", "2026-05-11T00:00:00Z"),
+            ],
+            until,
+        );
+        assert_eq!(cycles, vec![6.0, 48.0]);
+        assert_eq!(median(cycles), Some(27.0));
+        assert_eq!(median(Vec::new()), None);
+        assert_eq!(format_hours(27.0), "27.0h");
+        assert_eq!(format_hours(72.0), "3.0d");
+    }
+}
@@ -1,31 +1,68 @@
 //! Status command implementation
 
+use crate::cli::i18n::msg;
 use crate::cli::output::{Output, Table};
 use crate::core::manifest::Manifest;
+use crate::core::nested::nested_repos;
 use crate::core::repo::RepoInfo;
-use crate::git::status::{get_repo_status, RepoStatus};
+use crate::core::unmanaged::{find_remote_mismatches, find_unmanaged_repos};
+use crate::git::incremental::force_full_scan;
+use crate::git::open_repo;
+use crate::git::remote::{get_remote_url, last_fetch_time};
+use crate::git::status::{
+    get_all_repo_status_against, get_cached_status_limited, RepoStatus, RepoStatusInfo,
+    UNTRACKED_LIMIT,
+};
 use std::path::PathBuf;
 
+/// Remote data older than this is called out, since ahead/behind may be wrong
+const STALE_FETCH_HOURS: u64 = 24;
+
 /// Run the status command
+///
+/// `base` overrides the ref used for the ahead/behind column (default: each
+/// repo's default branch). With `all`, also reports repos in the workspace
+/// that the manifest doesn't manage. `full_status` disables incremental
+/// status for large repos. With `repos_filter`, only those repos are shown,
+/// followed by their changed files; untracked files are capped at
+/// `UNTRACKED_LIMIT` per repo unless `full`.
+#[allow(clippy::too_many_arguments)]
 pub fn run_status(
     workspace_root: &PathBuf,
     manifest: &Manifest,
     verbose: bool,
+    base: Option<&str>,
+    all: bool,
+    full_status: bool,
+    repos_filter: Option<&[String]>,
+    full: bool,
 ) -> anyhow::Result<()> {
-    Output::header("Repository Status");
-    println!();
-
-    // Get all repo info
-    let repos: Vec<RepoInfo> = manifest
+    // Get all repo info, with nested workspaces' repos as `<entry>/<repo>`
+    let mut repos: Vec<RepoInfo> = manifest
         .repos
         .iter()
         .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
         .collect();
+    repos.extend(nested_repos(workspace_root, manifest));
+
+    if let Some(filter) = repos_filter {
+        for repo_name in filter {
+            if !repos.iter().any(|r| &r.name == repo_name) {
+                anyhow::bail!("Unknown repo '{}'", repo_name);
+            }
+        }
+        repos.retain(|r| filter.iter().any(|f| f == &r.name));
+    }
+
+    force_full_scan(full_status);
+
+    Output::header(&msg!("status.title"));
+    println!();
 
     // Get status for all repos
-    let statuses: Vec<(RepoStatus, &RepoInfo)> = repos
-        .iter()
-        .map(|repo| (get_repo_status(repo), repo))
+    let statuses: Vec<(RepoStatus, &RepoInfo)> = get_all_repo_status_against(&repos, base)
+        .into_iter()
+        .zip(&repos)
         .collect();
 
     // Count stats
@@ -35,11 +72,26 @@ pub fn run_status(
     let ahead_count = statuses.iter().filter(|(s, _)| s.ahead_main > 0).count();
 
     // Display table
-    let mut table = Table::new(vec!["Repo", "Branch", "Status", "vs main"]);
+    let base_label = base.unwrap_or("main");
+    let (repo_header, branch_header, status_header) = (
+        msg!("status.column_repo"),
+        msg!("status.column_branch"),
+        msg!("status.column_status"),
+    );
+    let vs_header = msg!("status.column_vs", base = base_label);
+    let mut table = Table::new(vec![
+        &repo_header,
+        &branch_header,
+        &status_header,
+        &vs_header,
+    ]);
 
     for (status, repo) in &statuses {
         let status_str = format_status(status, verbose);
-        let main_str = format_main_comparison(status, &repo.default_branch);
+        let main_str = match base {
+            Some(_) => format_base_comparison(status),
+            None => format_main_comparison(status, &repo.default_branch),
+        };
         table.add_row(vec![
             &Output::repo_name(&status.name),
             &Output::branch_name(&status.branch),
@@ -52,19 +104,176 @@ pub fn run_status(
 
     // Summary
     println!();
-    let ahead_suffix = if ahead_count > 0 {
-        format!(" | {} ahead of main", ahead_count)
-    } else {
-        String::new()
-    };
-    println!(
-        "  {}/{} cloned | {} with changes{}",
-        cloned, total, with_changes, ahead_suffix
+    let summary = msg!(
+        "status.summary",
+        cloned = cloned,
+        total = total,
+        changed = with_changes
     );
+    if ahead_count > 0 {
+        let ahead = msg!(
+            "status.summary_ahead",
+            count = ahead_count,
+            base = base_label
+        );
+        println!("  {} | {}", summary, ahead);
+    } else {
+        println!("  {}", summary);
+    }
+
+    // Call out repos stuck mid-merge/rebase so they aren't mistaken for plain dirty repos
+    let in_progress: Vec<&RepoStatus> = statuses
+        .iter()
+        .filter(|(s, _)| s.operation.is_some())
+        .map(|(s, _)| s)
+        .collect();
+    if !in_progress.is_empty() {
+        println!();
+        for status in in_progress {
+            if let Some(operation) = status.operation {
+                Output::warning(&msg!(
+                    "status.in_progress",
+                    repo = status.name,
+                    operation = operation.git_command(),
+                    count = status.conflicted
+                ));
+            }
+        }
+        Output::info(&msg!("status.in_progress_hint"));
+    }
+
+    let lfs_missing: Vec<&RepoStatus> = statuses
+        .iter()
+        .filter(|(s, _)| s.lfs_missing > 0)
+        .map(|(s, _)| s)
+        .collect();
+    if !lfs_missing.is_empty() {
+        println!();
+        for status in lfs_missing {
+            Output::warning(&msg!(
+                "status.lfs_missing",
+                repo = status.name,
+                count = status.lfs_missing
+            ));
+        }
+        Output::info(&msg!("status.lfs_missing_hint"));
+    }
+
+    let stale: Vec<(&str, u64)> = statuses
+        .iter()
+        .filter(|(s, _)| s.exists)
+        .filter_map(|(_, repo)| {
+            let git_repo = open_repo(&repo.absolute_path).ok()?;
+            get_remote_url(&git_repo, "origin").ok()??;
+            let hours = last_fetch_time(&git_repo)?.elapsed().ok()?.as_secs() / 3600;
+            (hours >= STALE_FETCH_HOURS).then_some((repo.name.as_str(), hours))
+        })
+        .collect();
+    if !stale.is_empty() {
+        println!();
+        for (name, hours) in &stale {
+            Output::warning(&msg!(
+                "status.stale",
+                repo = name,
+                age = format_fetch_age(*hours)
+            ));
+        }
+        Output::info(&msg!("status.stale_hint"));
+    }
+
+    if repos_filter.is_some() {
+        let limit = if full { None } else { Some(UNTRACKED_LIMIT) };
+        for repo in repos.iter().filter(|r| r.exists()) {
+            match get_cached_status_limited(&repo.absolute_path, limit) {
+                Ok(info) => print_changed_files(&repo.name, &info),
+                Err(e) => Output::error(&format!("{}: {}", repo.name, e)),
+            }
+        }
+    }
+
+    if all {
+        print_workspace_drift(workspace_root, manifest);
+    }
 
     Ok(())
 }
 
+/// List a repo's changed files, noting untracked files left out by the cap
+fn print_changed_files(name: &str, info: &RepoStatusInfo) {
+    if info.is_clean {
+        return;
+    }
+
+    Output::header(&Output::repo_name(name));
+    let sections = [
+        (msg!("status.conflicted"), &info.conflicted),
+        (msg!("status.staged"), &info.staged),
+        (msg!("status.modified"), &info.modified),
+        (msg!("status.untracked"), &info.untracked),
+    ];
+    for (label, files) in sections {
+        if files.is_empty() {
+            continue;
+        }
+        Output::subheader(&format!("{}:", label));
+        for file in files {
+            Output::list_item(file);
+        }
+    }
+    if info.untracked_truncated {
+        Output::info(&msg!(
+            "status.untracked_truncated",
+            count = info.untracked_count - info.untracked.len()
+        ));
+    }
+}
+
+fn format_fetch_age(hours: u64) -> String {
+    if hours < 48 {
+        msg!("status.hours", count = hours)
+    } else {
+        msg!("status.days", count = hours / 24)
+    }
+}
+
+/// Report unmanaged repos and remote mismatches; returns how many were found
+pub fn print_workspace_drift(workspace_root: &PathBuf, manifest: &Manifest) -> usize {
+    let unmanaged = find_unmanaged_repos(workspace_root, manifest);
+    let mismatches = find_remote_mismatches(workspace_root, manifest);
+
+    if !unmanaged.is_empty() {
+        println!();
+        for repo in &unmanaged {
+            let origin = repo.url.clone().unwrap_or_else(|| msg!("status.no_origin"));
+            Output::warning(&msg!("status.unmanaged", path = repo.path, origin = origin));
+        }
+        Output::info(&msg!("status.unmanaged_hint"));
+    }
+
+    if !mismatches.is_empty() {
+        println!();
+        for m in &mismatches {
+            let actual = m.actual.clone().unwrap_or_else(|| msg!("status.no_remote"));
+            Output::warning(&msg!(
+                "status.mismatch",
+                repo = m.repo,
+                actual = actual,
+                expected = m.expected
+            ));
+            let verb = if m.actual.is_some() { "set-url" } else { "add" };
+            println!(
+                "    git -C {} remote {} origin {}   # {}",
+                m.path,
+                verb,
+                m.expected,
+                msg!("status.mismatch_hint")
+            );
+        }
+    }
+
+    unmanaged.len() + mismatches.len()
+}
+
 /// Format the vs main comparison column
 fn format_main_comparison(status: &RepoStatus, default_branch: &str) -> String {
     // On default branch - no comparison needed
@@ -72,16 +281,30 @@ fn format_main_comparison(status: &RepoStatus, default_branch: &str) -> String {
         return "-".to_string();
     }
 
+    format_base_comparison(status)
+}
+
+/// Format the ahead/behind column against an explicit base ref
+fn format_base_comparison(status: &RepoStatus) -> String {
+    if !status.exists {
+        return "-".to_string();
+    }
+
+    // The base doesn't exist in this repo, so there's nothing to compare
+    if status.base_missing {
+        return "?".to_string();
+    }
+
     if status.ahead_main == 0 && status.behind_main == 0 {
-        return "\u{2713}".to_string(); // checkmark
+        return Output::mark(true).to_string();
     }
 
     let mut parts = Vec::new();
     if status.ahead_main > 0 {
-        parts.push(format!("\u{2191}{}", status.ahead_main)); // up arrow
+        parts.push(Output::ahead(status.ahead_main));
     }
     if status.behind_main > 0 {
-        parts.push(format!("\u{2193}{}", status.behind_main)); // down arrow
+        parts.push(Output::behind(status.behind_main));
     }
     parts.join(" ")
 }
@@ -89,31 +312,41 @@ fn format_main_comparison(status: &RepoStatus, default_branch: &str) -> String {
 /// Format status for display
 fn format_status(status: &RepoStatus, verbose: bool) -> String {
     if !status.exists {
-        return "not cloned".to_string();
+        return msg!("status.not_cloned");
     }
 
-    if status.clean {
-        return "✓".to_string();
+    if status.clean && status.lfs_missing == 0 {
+        return Output::mark(true).to_string();
     }
 
     let mut parts = Vec::new();
 
+    if let Some(operation) = status.operation {
+        parts.push(operation.to_string());
+    }
+    if status.conflicted > 0 {
+        parts.push(Output::tally("!", "CONFLICTED", status.conflicted));
+    }
+
     if status.staged > 0 {
-        parts.push(format!("+{}", status.staged));
+        parts.push(Output::tally("+", "STAGED", status.staged));
     }
     if status.modified > 0 {
-        parts.push(format!("~{}", status.modified));
+        parts.push(Output::tally("~", "MODIFIED", status.modified));
     }
     if status.untracked > 0 {
-        parts.push(format!("?{}", status.untracked));
+        parts.push(Output::tally("?", "UNTRACKED", status.untracked));
+    }
+    if status.lfs_missing > 0 {
+        parts.push(Output::tally("LFS-", "LFS-MISSING", status.lfs_missing));
     }
 
     if verbose {
         if status.ahead > 0 {
-            parts.push(format!("↑{}", status.ahead));
+            parts.push(Output::ahead(status.ahead));
         }
         if status.behind > 0 {
-            parts.push(format!("↓{}", status.behind));
+            parts.push(Output::behind(status.behind));
         }
     }
 
@@ -123,6 +356,13 @@ fn format_status(status: &RepoStatus, verbose: bool) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::status::GitOperation;
+
+    #[test]
+    fn test_format_fetch_age() {
+        assert_eq!(format_fetch_age(30), "30 hours");
+        assert_eq!(format_fetch_age(80), "3 days");
+    }
 
     #[test]
     fn test_format_status_clean() {
@@ -137,6 +377,10 @@ mod tests {
             behind: 0,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_status(&status, false), "✓");
@@ -155,6 +399,10 @@ mod tests {
             behind: 0,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_status(&status, false), "+2 ~3 ?1");
@@ -173,11 +421,59 @@ mod tests {
             behind: 1,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_status(&status, true), "+1 ↑3 ↓1");
     }
 
+    #[test]
+    fn test_format_status_lfs_missing() {
+        let status = RepoStatus {
+            name: "assets".to_string(),
+            branch: "main".to_string(),
+            clean: true,
+            staged: 0,
+            modified: 0,
+            untracked: 0,
+            ahead: 0,
+            behind: 0,
+            ahead_main: 0,
+            behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 3,
+            exists: true,
+        };
+        assert_eq!(format_status(&status, false), "LFS-3");
+    }
+
+    #[test]
+    fn test_format_status_conflicted() {
+        let status = RepoStatus {
+            name: "test".to_string(),
+            branch: "feat".to_string(),
+            clean: false,
+            staged: 1,
+            modified: 0,
+            untracked: 0,
+            ahead: 0,
+            behind: 0,
+            ahead_main: 0,
+            behind_main: 0,
+            base_missing: false,
+            operation: Some(GitOperation::Rebase),
+            conflicted: 2,
+            lfs_missing: 0,
+            exists: true,
+        };
+        assert_eq!(format_status(&status, false), "REBASING !2 +1");
+    }
+
     #[test]
     fn test_format_main_comparison_on_main() {
         let status = RepoStatus {
@@ -191,6 +487,10 @@ mod tests {
             behind: 0,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_main_comparison(&status, "main"), "-");
@@ -209,6 +509,10 @@ mod tests {
             behind: 0,
             ahead_main: 5,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_main_comparison(&status, "main"), "↑5");
@@ -227,6 +531,10 @@ mod tests {
             behind: 0,
             ahead_main: 0,
             behind_main: 3,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_main_comparison(&status, "main"), "↓3");
@@ -245,11 +553,61 @@ mod tests {
             behind: 0,
             ahead_main: 2,
             behind_main: 5,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_main_comparison(&status, "main"), "↑2 ↓5");
     }
 
+    #[test]
+    fn test_format_base_comparison_on_default_branch() {
+        let status = RepoStatus {
+            name: "test".to_string(),
+            branch: "main".to_string(),
+            clean: true,
+            staged: 0,
+            modified: 0,
+            untracked: 0,
+            ahead: 0,
+            behind: 0,
+            ahead_main: 1,
+            behind_main: 4,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
+            exists: true,
+        };
+        // An explicit base compares even when on the default branch
+        assert_eq!(format_base_comparison(&status), "↑1 ↓4");
+    }
+
+    #[test]
+    fn test_format_base_comparison_missing_base() {
+        let status = RepoStatus {
+            name: "test".to_string(),
+            branch: "feat/test".to_string(),
+            clean: true,
+            staged: 0,
+            modified: 0,
+            untracked: 0,
+            ahead: 0,
+            behind: 0,
+            ahead_main: 0,
+            behind_main: 0,
+            base_missing: true,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
+            exists: true,
+        };
+        // Not "in sync" when the base ref doesn't exist in the repo
+        assert_eq!(format_base_comparison(&status), "?");
+    }
+
     #[test]
     fn test_format_main_comparison_in_sync() {
         let status = RepoStatus {
@@ -263,6 +621,10 @@ mod tests {
             behind: 0,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         };
         assert_eq!(format_main_comparison(&status, "main"), "✓");
@@ -0,0 +1,349 @@
+//! Switch-url command implementation
+//!
+//! Rewrites repo URLs in bulk when an organization changes hosts (GitHub to
+//! GitHub Enterprise, SSH to HTTPS, ...). Each checkout's `origin` and the
+//! manifest are updated together, then the new URLs are checked.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::manifest_lint::{check_url, YamlLocator};
+use crate::core::repo::RepoInfo;
+use crate::git::remote::{get_remote_url, set_remote_url};
+use crate::git::{open_repo, path_exists};
+use crate::util::pool::{max_workers, parallel_map};
+use dialoguer::Confirm;
+use regex::{NoExpand, Regex};
+use std::path::PathBuf;
+
+/// Settings for `gr switch-url`
+#[derive(Debug, Clone)]
+pub struct SwitchUrlOptions {
+    /// Match the pattern literally instead of as a regex
+    pub fixed_strings: bool,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    /// Only show the planned changes
+    pub dry_run: bool,
+    /// Check that every new URL is reachable afterwards
+    pub verify: bool,
+}
+
+/// A URL change for one repo
+struct UrlSwitch {
+    /// Repo name, or `manifest` for the manifest repo
+    name: String,
+    /// Manifest field holding the URL, e.g. `repos.app.url`
+    field: String,
+    /// Manifest URL before and after
+    manifest: Option<(String, String)>,
+    /// Checkout path, with its `origin` URL before and after
+    origin: Option<(PathBuf, String, String)>,
+}
+
+impl UrlSwitch {
+    /// The URL the repo is fetched from once the switch is applied
+    fn new_url(&self) -> &str {
+        self.origin
+            .as_ref()
+            .map(|(_, _, new)| new)
+            .or(self.manifest.as_ref().map(|(_, new)| new))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// Run the switch-url command
+///
+/// Unless `fixed_strings` is set, `replacement` may reference capture groups
+/// as `$1` or `${name}`.
+pub fn run_switch_url(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    pattern: &str,
+    replacement: &str,
+    options: &SwitchUrlOptions,
+) -> anyhow::Result<()> {
+    let regex = if options.fixed_strings {
+        Regex::new(&regex::escape(pattern))?
+    } else {
+        Regex::new(pattern)?
+    };
+    let rewrite = |url: &str| -> Option<String> {
+        let new = if options.fixed_strings {
+            regex.replace_all(url, NoExpand(replacement))
+        } else {
+            regex.replace_all(url, replacement)
+        };
+        (new != url).then(|| new.into_owned())
+    };
+
+    let manifests_dir = workspace_root.join(".gitgrip").join("manifests");
+    let manifest_path = manifests_dir.join("manifest.yaml");
+
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut targets: Vec<(String, String, String, PathBuf)> = repos
+        .into_iter()
+        .map(|repo| {
+            (
+                format!("repos.{}.url", repo.name),
+                repo.name,
+                repo.url,
+                repo.absolute_path,
+            )
+        })
+        .collect();
+    if let Some(ref manifest_repo) = manifest.manifest {
+        targets.push((
+            "manifest.url".to_string(),
+            "manifest".to_string(),
+            manifest_repo.url.clone(),
+            manifests_dir.clone(),
+        ));
+    }
+
+    let switches: Vec<UrlSwitch> = targets
+        .into_iter()
+        .filter_map(|(field, name, url, path)| {
+            let origin = path_exists(&path)
+                .then(|| open_repo(&path).ok())
+                .flatten()
+                .and_then(|repo| get_remote_url(&repo, "origin").ok().flatten())
+                .and_then(|old| rewrite(&old).map(|new| (path, old, new)));
+            let manifest = rewrite(&url).map(|new| (url, new));
+            (manifest.is_some() || origin.is_some()).then_some(UrlSwitch {
+                name,
+                field,
+                manifest,
+                origin,
+            })
+        })
+        .collect();
+
+    if switches.is_empty() {
+        Output::info("No URLs match.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(vec!["Repo", "Where", "Old URL", "New URL"]);
+    for switch in &switches {
+        if let Some((old, new)) = &switch.manifest {
+            table.add_row(vec![&switch.name, "manifest", old, new]);
+        }
+        if let Some((_, old, new)) = &switch.origin {
+            table.add_row(vec![&switch.name, "origin", old, new]);
+        }
+    }
+    table.print();
+    println!();
+
+    let proceed = if options.dry_run {
+        false
+    } else if options.yes {
+        true
+    } else {
+        Confirm::with_theme(&*Output::theme())
+            .with_prompt(format!("Switch URLs for {} repo(s)?", switches.len()))
+            .default(false)
+            .interact()?
+    };
+    if !proceed {
+        Output::info("No URLs changed.");
+        return Ok(());
+    }
+
+    let edits: Vec<(String, String, String)> = switches
+        .iter()
+        .filter_map(|s| {
+            let (old, new) = s.manifest.as_ref()?;
+            Some((s.field.clone(), old.clone(), new.clone()))
+        })
+        .collect();
+    if !edits.is_empty() {
+        let source = std::fs::read_to_string(&manifest_path)?;
+        let (updated, skipped) = rewrite_manifest_urls(&source, &edits);
+        if updated != source {
+            std::fs::write(&manifest_path, updated)?;
+            Output::success(&format!(
+                "manifest.yaml: updated {} URL(s)",
+                edits.len() - skipped.len()
+            ));
+        }
+        for field in skipped {
+            let new = edits
+                .iter()
+                .find(|(f, _, _)| *f == field)
+                .map(|(_, _, new)| new.as_str())
+                .unwrap_or_default();
+            Output::warning(&format!(
+                "manifest.yaml: {} uses variables or an unusual layout; set it to {} by hand",
+                field, new
+            ));
+        }
+    }
+
+    let mut failed = 0;
+    for switch in &switches {
+        let Some((path, _, new)) = &switch.origin else {
+            continue;
+        };
+        let result = open_repo(path)
+            .map_err(|e| e.to_string())
+            .and_then(|repo| set_remote_url(&repo, "origin", new).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => Output::success(&format!("{}: origin -> {}", switch.name, new)),
+            Err(e) => {
+                failed += 1;
+                Output::error(&format!("{}: {}", switch.name, e));
+            }
+        }
+    }
+
+    if options.verify {
+        println!();
+        let spinner = Output::spinner("Checking new URLs...");
+        let results = parallel_map(&switches, max_workers(), |s| check_url(s.new_url()));
+        spinner.finish_and_clear();
+        for (switch, result) in switches.iter().zip(&results) {
+            match result {
+                Ok(()) => Output::success(&format!("{}: reachable", switch.name)),
+                Err(e) => {
+                    failed += 1;
+                    Output::error(&format!(
+                        "{}: cannot reach {}: {}",
+                        switch.name,
+                        switch.new_url(),
+                        e
+                    ));
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} URL(s) failed to update or verify", failed);
+    }
+
+    Ok(())
+}
+
+/// Replace URL values in manifest `source`, keeping formatting and comments
+///
+/// Each edit is `(field, old, new)`. A field is only rewritten when its value
+/// is written out literally as `old`; values built from `${VAR}` templates or
+/// not on a `key: value` line are returned for the user to fix by hand.
+fn rewrite_manifest_urls(
+    source: &str,
+    edits: &[(String, String, String)],
+) -> (String, Vec<String>) {
+    let locator = YamlLocator::new(source);
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+    let mut skipped = Vec::new();
+
+    for (field, old, new) in edits {
+        let replaced = locator.exact(field).and_then(|location| {
+            let line = lines.get_mut(location.line - 1)?;
+            let start = location.column - 1;
+            let value = line.get(start..)?;
+            let (len, quote) = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => (value[1..].find(quote)? + 2, Some(quote)),
+                _ => (value.find(" #").unwrap_or(value.len()), None),
+            };
+            let raw = value[..len].trim_end();
+            let literal = match quote {
+                Some(_) => &raw[1..raw.len() - 1],
+                None => raw,
+            };
+            if literal != old {
+                return None;
+            }
+            let written = match quote {
+                Some(quote) => format!("{}{}{}", quote, new, quote),
+                None => new.clone(),
+            };
+            line.replace_range(start..start + raw.len(), &written);
+            Some(())
+        });
+        if replaced.is_none() {
+            skipped.push(field.clone());
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if source.ends_with('\n') {
+        updated.push('\n');
+    }
+    (updated, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(field: &str, old: &str, new: &str) -> (String, String, String) {
+        (field.to_string(), old.to_string(), new.to_string())
+    }
+
+    #[test]
+    fn test_rewrite_manifest_urls() {
+        let source = r#"manifest:
+  url: "git@github.com:org/manifest.git"
+repos:
+  app:
+    url: git@github.com:org/app.git  # main app
+    path: app
+  lib:
+    url: ${GIT_BASE}/lib.git
+    path: lib
+"#;
+        let (updated, skipped) = rewrite_manifest_urls(
+            source,
+            &[
+                edit(
+                    "manifest.url",
+                    "git@github.com:org/manifest.git",
+                    "https://ghe.example.com/org/manifest.git",
+                ),
+                edit(
+                    "repos.app.url",
+                    "git@github.com:org/app.git",
+                    "https://ghe.example.com/org/app.git",
+                ),
+                edit(
+                    "repos.lib.url",
+                    "git@github.com:org/lib.git",
+                    "https://ghe.example.com/org/lib.git",
+                ),
+            ],
+        );
+
+        assert_eq!(skipped, vec!["repos.lib.url".to_string()]);
+        assert_eq!(
+            updated,
+            r#"manifest:
+  url: "https://ghe.example.com/org/manifest.git"
+repos:
+  app:
+    url: https://ghe.example.com/org/app.git  # main app
+    path: app
+  lib:
+    url: ${GIT_BASE}/lib.git
+    path: lib
+"#
+        );
+        assert!(serde_yaml::from_str::<serde_yaml::Value>(&updated).is_ok());
+    }
+
+    #[test]
+    fn test_rewrite_missing_field() {
+        let source = "repos:\n  app: { url: a, path: app }\n";
+        let (updated, skipped) = rewrite_manifest_urls(source, &[edit("repos.app.url", "a", "b")]);
+        assert_eq!(updated, source);
+        assert_eq!(skipped, vec!["repos.app.url".to_string()]);
+    }
+}
@@ -1,101 +1,905 @@
 //! Sync command implementation
 
+use crate::cli::commands::link::create_symlink;
+use crate::cli::commands::manifest::{manifest_changes_since, manifest_head};
+use crate::cli::commands::optimize::{fsmonitor_supported, optimize_repo};
+use crate::cli::i18n::msg;
 use crate::cli::output::Output;
-use crate::core::manifest::Manifest;
-use crate::core::repo::RepoInfo;
-use crate::git::remote::safe_pull_latest;
-use crate::git::{clone_repo, open_repo, path_exists};
-use std::path::PathBuf;
+use crate::core::manifest::{Manifest, RepoConfig};
+use crate::core::nested::{nested_workspaces, nesting_depth, MAX_NESTING};
+use crate::core::repo::{resolve_repo_path, RepoInfo};
+use crate::core::state::{StateFile, SyncProgress};
+use crate::core::unmanaged::{find_unmanaged_repos, same_remote, UnmanagedRepo};
+use crate::git::lfs::{lfs_available, missing_lfs_objects, pull_lfs_objects, uses_lfs};
+use crate::git::reference::update_reference;
+use crate::git::remote::{get_remote_url, safe_pull_latest};
+use crate::git::revision::{checkout_revision, resolve_revision, sync_revision};
+use crate::git::sparse::{clone_sparse, get_sparse_patterns, set_sparse_patterns, sparse_drifted};
+use crate::git::status::{blocking_operation, get_status_info};
+use crate::git::{clone_repo, open_repo, path_exists, GitError};
+use crate::util::pool::{max_workers, parallel_map};
+use crate::util::process::is_cancelled;
+use crate::util::profile::ProfiledCommand;
+use crate::util::RetryOptions;
+use crate::Workspace;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Repo counts from a sync
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncSummary {
+    pub synced: usize,
+    pub failed: usize,
+}
+
+impl std::fmt::Display for SyncSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} synced, {} failed", self.synced, self.failed)
+    }
+}
+
+/// What sync does with a repo, decided before touching the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    /// Not on disk yet
+    Clone,
+    /// Fast-forward from origin
+    Pull,
+    /// A merge, rebase, or similar is in progress
+    Blocked(String),
+    /// The checkout can't be opened
+    Broken(String),
+}
+
+/// Decide what sync will do with each of `repos`
+pub fn plan_sync(repos: &[RepoInfo]) -> Vec<SyncAction> {
+    repos
+        .iter()
+        .map(|repo| {
+            if !path_exists(&repo.absolute_path) {
+                return SyncAction::Clone;
+            }
+            match open_repo(&repo.absolute_path) {
+                Ok(git_repo) => match blocking_operation(&git_repo) {
+                    Some(reason) => SyncAction::Blocked(reason),
+                    None => SyncAction::Pull,
+                },
+                Err(e) => SyncAction::Broken(e.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Default cap on clones running at once
+pub const DEFAULT_CLONE_JOBS: usize = 4;
+
+/// Retries for a failed clone: 2s, then 4s (plus jitter)
+fn clone_retry() -> RetryOptions {
+    RetryOptions {
+        max_retries: 2,
+        initial_delay_ms: 2000,
+        ..Default::default()
+    }
+}
+
+/// Settings for `gr sync`
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    /// Skip repos with local changes instead of failing them
+    pub force: bool,
+    /// Only sync repos an interrupted sync didn't finish
+    pub resume: bool,
+    /// Clones to run at once; pulls stay one at a time
+    pub clone_jobs: usize,
+    /// Directory of bare repos that clones borrow objects from
+    pub reference_cache: Option<PathBuf>,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            resume: false,
+            clone_jobs: DEFAULT_CLONE_JOBS,
+            reference_cache: None,
+        }
+    }
+}
 
 /// Run the sync command
-pub fn run_sync(workspace_root: &PathBuf, manifest: &Manifest, force: bool) -> anyhow::Result<()> {
-    Output::header(&format!("Syncing {} repositories...", manifest.repos.len()));
-    println!();
+///
+/// Missing repos are cloned first, several at a time, retrying failures with
+/// backoff. Finished repos are recorded in the state file as the sync goes,
+/// so `--continue` can pick up after an interruption or failed clones.
+pub fn run_sync(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &SyncOptions,
+) -> anyhow::Result<SyncSummary> {
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let manifest_path = workspace_root
+        .join(".gitgrip")
+        .join("manifests")
+        .join("manifest.yaml");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    let previous = state.sync_progress.take();
+    let mut progress = if options.resume {
+        previous.clone().ok_or_else(|| {
+            anyhow::anyhow!("No interrupted sync to continue; run 'gr sync' instead")
+        })?
+    } else {
+        SyncProgress::default()
+    };
+    progress.cloning.clear();
 
     let repos: Vec<RepoInfo> = manifest
         .repos
         .iter()
         .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| !progress.done.contains(&repo.name))
         .collect();
 
-    let mut success_count = 0;
-    let mut error_count = 0;
+    let profile = state
+        .profile
+        .as_ref()
+        .map(|profile| msg!("sync.profile", name = profile))
+        .unwrap_or_default();
+    if options.resume {
+        Output::header(&msg!(
+            "sync.continuing",
+            left = repos.len(),
+            total = manifest.repos.len(),
+            profile = profile
+        ));
+    } else {
+        Output::header(&msg!(
+            "sync.syncing",
+            count = manifest.repos.len(),
+            profile = profile
+        ));
+        warn_if_manifest_changed(&manifest_path, &state);
+    }
+    println!();
 
-    for repo in &repos {
-        let spinner = Output::spinner(&format!("Pulling {}...", repo.name));
+    if let Some(ref previous) = previous {
+        for repo in repos.iter().filter(|r| previous.cloning.contains(&r.name)) {
+            if remove_partial_clone(&repo.absolute_path) {
+                Output::info(&msg!("sync.removed_partial", repo = repo.name));
+            }
+        }
+    }
+    migrate_moved_repos(workspace_root, manifest, &repos);
 
-        if !path_exists(&repo.absolute_path) {
-            // Clone the repo
-            spinner.set_message(format!("Cloning {}...", repo.name));
+    let plan = plan_sync(&repos);
+    let (to_clone, to_pull): (Vec<_>, Vec<_>) = repos
+        .iter()
+        .zip(plan)
+        .partition(|(_, action)| *action == SyncAction::Clone);
+    let to_clone: Vec<&RepoInfo> = to_clone.into_iter().map(|(repo, _)| repo).collect();
 
-            match clone_repo(&repo.url, &repo.absolute_path, Some(&repo.default_branch)) {
-                Ok(_) => {
-                    spinner.finish_with_message(format!("{}: cloned", repo.name));
-                    success_count += 1;
-                }
-                Err(e) => {
-                    spinner.finish_with_message(format!("{}: clone failed - {}", repo.name, e));
-                    error_count += 1;
-                }
-            }
+    progress.cloning = to_clone.iter().map(|repo| repo.name.clone()).collect();
+    state.sync_progress = Some(progress);
+    save_state(&state, &state_path);
+
+    let (mut success_count, mut error_count) =
+        clone_missing(manifest, &to_clone, options, &mut state, &state_path);
+
+    for (repo, action) in to_pull {
+        let spinner = Output::spinner(&msg!("sync.pulling", repo = repo.name));
+        let config = manifest.repos.get(&repo.name);
+        let sparse = config.and_then(|config| config.sparse.as_deref());
+        let revision = config.and_then(|config| config.revision.as_deref());
+
+        if let SyncAction::Blocked(reason) = &action {
+            spinner.finish_with_message(msg!("sync.blocked", repo = repo.name, reason = reason));
+            error_count += 1;
             continue;
         }
 
         // Pull existing repo
         match open_repo(&repo.absolute_path) {
             Ok(git_repo) => {
-                let result = safe_pull_latest(&git_repo, &repo.default_branch, "origin");
+                let result = match revision {
+                    Some(revision) => sync_revision(&git_repo, &repo.default_branch, revision),
+                    None => safe_pull_latest(&git_repo, &repo.default_branch, "origin"),
+                };
 
+                let mut synced = false;
                 match result {
                     Ok(pull_result) => {
                         if pull_result.pulled {
-                            if pull_result.recovered {
-                                spinner.finish_with_message(format!(
-                                    "{}: {} (recovered)",
-                                    repo.name,
-                                    pull_result.message.unwrap_or_else(|| "pulled".to_string())
-                                ));
-                            } else {
-                                spinner.finish_with_message(format!("{}: pulled", repo.name));
+                            match pull_result.message {
+                                Some(message) if pull_result.recovered => spinner
+                                    .finish_with_message(msg!(
+                                        "sync.recovered",
+                                        repo = repo.name,
+                                        message = message
+                                    )),
+                                Some(msg) => {
+                                    spinner.finish_with_message(format!("{}: {}", repo.name, msg))
+                                }
+                                None => spinner
+                                    .finish_with_message(msg!("sync.pulled", repo = repo.name)),
                             }
-                            success_count += 1;
+                            synced = true;
                         } else if let Some(msg) = pull_result.message {
-                            if force {
-                                spinner.finish_with_message(format!(
-                                    "{}: skipped - {}",
-                                    repo.name, msg
+                            if options.force {
+                                spinner.finish_with_message(msg!(
+                                    "sync.skipped",
+                                    repo = repo.name,
+                                    reason = msg
                                 ));
                             } else {
                                 spinner.finish_with_message(format!("{}: {}", repo.name, msg));
                             }
-                            error_count += 1;
                         } else {
-                            spinner.finish_with_message(format!("{}: up to date", repo.name));
-                            success_count += 1;
+                            spinner.finish_with_message(msg!("sync.up_to_date", repo = repo.name));
+                            synced = true;
                         }
                     }
                     Err(e) => {
-                        spinner.finish_with_message(format!("{}: error - {}", repo.name, e));
-                        error_count += 1;
+                        spinner.finish_with_message(msg!(
+                            "sync.error",
+                            repo = repo.name,
+                            error = e
+                        ));
                     }
                 }
+
+                if let Some(patterns) = sparse {
+                    sync_sparse(&repo.name, &repo.absolute_path, patterns);
+                }
+                sync_lfs(&repo.name, &repo.absolute_path);
+
+                if synced {
+                    success_count += 1;
+                    mark_done(&mut state, &repo.name);
+                    save_state(&state, &state_path);
+                } else {
+                    error_count += 1;
+                }
             }
             Err(e) => {
-                spinner.finish_with_message(format!("{}: error - {}", repo.name, e));
+                spinner.finish_with_message(msg!("sync.error", repo = repo.name, error = e));
                 error_count += 1;
             }
         }
     }
 
+    if manifest.settings.auto_optimize {
+        optimize_synced(&repos);
+    }
+
+    let nested = sync_nested(workspace_root, manifest, options);
+    success_count += nested.synced;
+    error_count += nested.failed;
+
+    // Remember which checkouts the workspace manages, for `gr prune`
+    for (name, config) in &manifest.repos {
+        if resolve_repo_path(workspace_root, &config.path)
+            .join(".git")
+            .exists()
+        {
+            state
+                .managed_repos
+                .insert(name.clone(), config.path.clone());
+        }
+    }
+
+    // Remember which manifest this sync used, for the next change warning
+    if let Some(head) = manifest_head(&manifest_path) {
+        state.last_sync_manifest = Some(head);
+    }
+    if error_count == 0 {
+        state.sync_progress = None;
+    }
+    save_state(&state, &state_path);
+
     println!();
     if error_count == 0 {
-        Output::success(&format!(
-            "All {} repositories synced successfully.",
-            success_count
-        ));
+        Output::success(&msg!("sync.summary", count = success_count));
     } else {
-        Output::warning(&format!("{} synced, {} failed", success_count, error_count));
+        Output::warning(&msg!(
+            "sync.summary_failed",
+            synced = success_count,
+            failed = error_count
+        ));
+        Output::info(&msg!("sync.continue_hint"));
+    }
+
+    Ok(SyncSummary {
+        synced: success_count,
+        failed: error_count,
+    })
+}
+
+/// Clone `repos`, at most `clone_jobs` at a time, recording each finished clone
+///
+/// Returns the number cloned and the number that failed.
+fn clone_missing(
+    manifest: &Manifest,
+    repos: &[&RepoInfo],
+    options: &SyncOptions,
+    state: &mut StateFile,
+    state_path: &Path,
+) -> (usize, usize) {
+    if repos.is_empty() {
+        return (0, 0);
+    }
+
+    let bar = Output::progress_bar(repos.len() as u64, &msg!("sync.cloning"));
+    let shared = Mutex::new(std::mem::take(state));
+    let results = parallel_map(repos, options.clone_jobs, |repo| {
+        let config = manifest.repos.get(&repo.name);
+        let sparse = config.and_then(|config| config.sparse.as_deref());
+        let revision = config.and_then(|config| config.revision.as_deref());
+        let reference = options.reference_cache.as_deref().and_then(|cache| {
+            update_reference(cache, &repo.url)
+                .map_err(|e| {
+                    bar.suspend(|| {
+                        Output::warning(&format!(
+                            "{}: not using the reference cache - {}",
+                            repo.name, e
+                        ))
+                    })
+                })
+                .ok()
+        });
+        let result = clone_with_retry(repo, sparse, revision, reference.as_deref());
+
+        bar.suspend(|| match &result {
+            Ok(()) => match revision {
+                Some(revision) => Output::success(&msg!(
+                    "sync.cloned_at",
+                    repo = repo.name,
+                    revision = revision
+                )),
+                None => Output::success(&msg!("sync.cloned", repo = repo.name)),
+            },
+            Err(e) => Output::error(&msg!("sync.clone_failed", repo = repo.name, error = e)),
+        });
+        bar.inc(1);
+
+        if result.is_ok() {
+            let mut state = shared.lock().unwrap();
+            mark_done(&mut state, &repo.name);
+            save_state(&state, state_path);
+        }
+        result.is_ok()
+    });
+    bar.finish_and_clear();
+    *state = shared.into_inner().unwrap();
+
+    for (repo, _) in repos.iter().zip(&results).filter(|(_, ok)| **ok) {
+        sync_lfs(&repo.name, &repo.absolute_path);
+    }
+    let cloned = results.iter().filter(|ok| **ok).count();
+    (cloned, results.len() - cloned)
+}
+
+/// Clone a repo, retrying with exponential backoff
+///
+/// A failed attempt's directory is removed before the next, so retries and
+/// later syncs start clean.
+fn clone_with_retry(
+    repo: &RepoInfo,
+    sparse: Option<&[String]>,
+    revision: Option<&str>,
+    reference: Option<&Path>,
+) -> Result<(), GitError> {
+    let retry = clone_retry();
+    let mut attempt = 0;
+    loop {
+        let result = clone_once(repo, sparse, revision, reference);
+        let Err(e) = result else {
+            return Ok(());
+        };
+        if path_exists(&repo.absolute_path) {
+            let _ = std::fs::remove_dir_all(&repo.absolute_path);
+        }
+        if attempt >= retry.max_retries || is_cancelled() {
+            return Err(e);
+        }
+        std::thread::sleep(retry.calculate_delay(attempt));
+        attempt += 1;
+    }
+}
+
+fn clone_once(
+    repo: &RepoInfo,
+    sparse: Option<&[String]>,
+    revision: Option<&str>,
+    reference: Option<&Path>,
+) -> Result<(), GitError> {
+    match sparse {
+        Some(patterns) => clone_sparse(
+            &repo.url,
+            &repo.absolute_path,
+            Some(&repo.default_branch),
+            patterns,
+            reference,
+        )?,
+        None => {
+            clone_repo(
+                &repo.url,
+                &repo.absolute_path,
+                Some(&repo.default_branch),
+                reference,
+            )?;
+        }
+    }
+
+    if let Some(revision) = revision {
+        let git_repo = open_repo(&repo.absolute_path)?;
+        let target = resolve_revision(&git_repo, revision, "origin")?;
+        checkout_revision(&git_repo, &target, "origin")?;
+    }
+    Ok(())
+}
+
+/// Delete what's left of a clone that was cut off: a directory that isn't
+/// a repo, or a repo without a single commit
+fn remove_partial_clone(path: &Path) -> bool {
+    if !path_exists(path) {
+        return false;
+    }
+    let partial = match open_repo(path) {
+        Ok(repo) => repo.head().is_err(),
+        Err(_) => true,
+    };
+    partial && std::fs::remove_dir_all(path).is_ok()
+}
+
+fn mark_done(state: &mut StateFile, name: &str) {
+    let progress = state.sync_progress.get_or_insert_with(Default::default);
+    progress.cloning.retain(|repo| repo != name);
+    if !progress.done.iter().any(|repo| repo == name) {
+        progress.done.push(name.to_string());
+    }
+}
+
+fn save_state(state: &StateFile, path: &Path) {
+    if let Err(e) = state.save(path) {
+        Output::warning(&format!("Could not save workspace state: {}", e));
+    }
+}
+
+/// Record the manifest profile the workspace uses, or clear it with `None`
+///
+/// Repos outside the new profile stay on disk; the workspace just stops
+/// managing them.
+pub fn set_profile(
+    workspace_root: &Path,
+    manifest_path: &Path,
+    profile: Option<&str>,
+) -> anyhow::Result<()> {
+    if let Some(profile) = profile {
+        Manifest::load_for_workspace(manifest_path, workspace_root)?.apply_profile(profile)?;
+    }
+    let state_path = workspace_root.join(".gitgrip").join("state.json");
+    let mut state = StateFile::load(&state_path).unwrap_or_default();
+    if state.profile.as_deref() == profile {
+        return Ok(());
+    }
+    state.profile = profile.map(str::to_string);
+    // An interrupted sync of the old profile doesn't carry over
+    state.sync_progress = None;
+    state.save(&state_path)?;
+    match profile {
+        Some(profile) => Output::info(&format!("Switched to profile '{}'", profile)),
+        None => Output::info("Profile cleared; syncing every repository"),
+    }
+    Ok(())
+}
+
+/// Fast-forward the manifest repo holding `manifest_path`
+///
+/// Runs before the manifest is loaded, so the sync uses the updated repo
+/// list. Skipped with a warning when the manifest repo has local changes;
+/// failures are reported but never stop the sync.
+pub fn update_manifest_repo(manifest_path: &Path) {
+    let Some(dir) = manifest_path.parent() else {
+        return;
+    };
+    // Not a checkout of a manifest repo (e.g. created by `gr init --from-dirs`)
+    let Ok(repo) = open_repo(dir) else {
+        return;
+    };
+    if !matches!(get_remote_url(&repo, "origin"), Ok(Some(_))) {
+        return;
+    }
+
+    if let Ok(status) = get_status_info(&repo) {
+        if !status.staged.is_empty() || !status.modified.is_empty() {
+            Output::warning("Manifest repo has local changes; not updating it");
+            Output::info(
+                "Commit or stash them in .gitgrip/manifests, or pass --no-manifest-update",
+            );
+            println!();
+            return;
+        }
+    }
+
+    let before = manifest_head(manifest_path);
+    let spinner = Output::spinner("Updating manifest...");
+    let output = Command::new("git")
+        .args(["pull", "--ff-only", "--quiet"])
+        .current_dir(dir)
+        .timed_output();
+    match output {
+        Ok(output) if output.status.success() => {
+            if manifest_head(manifest_path) == before {
+                spinner.finish_with_message("manifest: up to date");
+            } else {
+                spinner.finish_with_message("manifest: pulled");
+            }
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            spinner.finish_with_message(format!(
+                "manifest: not updated - {}",
+                stderr.lines().next().unwrap_or("git pull failed").trim()
+            ));
+        }
+        Err(e) => spinner.finish_with_message(format!("manifest: not updated - {}", e)),
+    }
+    println!();
+}
+
+/// Warn when the manifest's repos or scripts changed since the last sync
+fn warn_if_manifest_changed(manifest_path: &Path, state: &StateFile) {
+    let Some(ref last) = state.last_sync_manifest else {
+        return;
+    };
+    let Ok(changes) = manifest_changes_since(manifest_path, last) else {
+        return;
+    };
+    if changes.is_empty() {
+        return;
+    }
+    let short = &last[..7.min(last.len())];
+    Output::warning(&format!(
+        "Manifest changed since last sync ({} change(s))",
+        changes.len()
+    ));
+    Output::info(&format!("Run 'gr manifest diff {}' to review", short));
+    println!();
+}
+
+/// Move checkouts whose manifest `path` changed to their new location
+///
+/// A repo missing on disk is matched to an unmanaged checkout in the
+/// workspace with the same remote URL. Moving it keeps local branches,
+/// stashes, and worktrees, where cloning would leave a stale duplicate.
+fn migrate_moved_repos(workspace_root: &Path, manifest: &Manifest, repos: &[RepoInfo]) {
+    let missing: Vec<&RepoInfo> = repos
+        .iter()
+        .filter(|repo| !path_exists(&repo.absolute_path))
+        .collect();
+    if missing.is_empty() {
+        return;
     }
 
+    let candidates = find_unmanaged_repos(workspace_root, manifest);
+    for repo in missing {
+        let matches: Vec<&UnmanagedRepo> = candidates
+            .iter()
+            .filter(|c| {
+                c.url
+                    .as_deref()
+                    .is_some_and(|url| same_remote(url, &repo.url))
+            })
+            .collect();
+        let old = match matches.as_slice() {
+            [] => continue,
+            [only] => only,
+            _ => {
+                let paths: Vec<&str> = matches.iter().map(|c| c.path.as_str()).collect();
+                Output::warning(&format!(
+                    "{}: several old checkouts match ({}); cloning fresh",
+                    repo.name,
+                    paths.join(", ")
+                ));
+                continue;
+            }
+        };
+
+        let old_path = resolve_repo_path(workspace_root, &old.path);
+        match move_checkout(workspace_root, &old_path, &repo.absolute_path) {
+            Ok(()) => {
+                Output::success(&format!(
+                    "{}: moved {} -> {}",
+                    repo.name, old.path, repo.path
+                ));
+                relink(workspace_root, manifest, repo);
+            }
+            Err(e) => Output::warning(&format!(
+                "{}: could not move {} - {}",
+                repo.name, old.path, e
+            )),
+        }
+    }
+}
+
+fn move_checkout(workspace_root: &Path, from: &Path, to: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(from, to)?;
+
+    // Worktrees (griptrees) record the main checkout's absolute path
+    let _ = Command::new("git")
+        .args(["worktree", "repair"])
+        .current_dir(to)
+        .timed_output();
+
+    // Drop directories the move left empty
+    let mut dir = from.parent();
+    while let Some(d) = dir {
+        if d == workspace_root || std::fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
     Ok(())
 }
+
+/// Re-point the repo's linkfiles at its new location
+fn relink(workspace_root: &Path, manifest: &Manifest, repo: &RepoInfo) {
+    let Some(linkfiles) = manifest
+        .repos
+        .get(&repo.name)
+        .and_then(|config| config.linkfile.as_ref())
+    else {
+        return;
+    };
+
+    for linkfile in linkfiles {
+        let source = repo.absolute_path.join(&linkfile.src);
+        let dest = workspace_root.join(&linkfile.dest);
+        if !source.exists() {
+            continue;
+        }
+        if let Err(e) = create_symlink(&source, &dest) {
+            Output::warning(&format!(
+                "{}: failed to relink {} - {}",
+                repo.name, linkfile.dest, e
+            ));
+        }
+    }
+}
+
+/// Re-apply the manifest's sparse checkout directories if they drifted
+fn sync_sparse(name: &str, repo_path: &Path, patterns: &[String]) {
+    let current = get_sparse_patterns(repo_path).ok().flatten();
+    if !sparse_drifted(current.as_deref(), patterns) {
+        return;
+    }
+
+    match set_sparse_patterns(repo_path, patterns) {
+        Ok(()) => Output::info(&format!("{}: sparse checkout updated", name)),
+        Err(e) => Output::warning(&format!("{}: sparse checkout failed - {}", name, e)),
+    }
+}
+
+/// Sync each nested workspace entry, cloning its manifest repo first
+fn sync_nested(workspace_root: &Path, manifest: &Manifest, options: &SyncOptions) -> SyncSummary {
+    let mut summary = SyncSummary::default();
+    let nested = nested_workspaces(workspace_root, manifest);
+    if nested.is_empty() {
+        return summary;
+    }
+    if nesting_depth(workspace_root) + 1 >= MAX_NESTING {
+        Output::warning(&format!(
+            "Not syncing nested workspaces more than {} levels deep; does a manifest include itself?",
+            MAX_NESTING
+        ));
+        summary.failed += nested.len();
+        return summary;
+    }
+
+    for (name, root) in nested {
+        let config = &manifest.repos[&name];
+        println!();
+        Output::header(&format!("Nested workspace {} ({}):", name, config.path));
+        match sync_nested_workspace(&root, config, options) {
+            Ok(child) => {
+                summary.synced += child.synced;
+                summary.failed += child.failed;
+            }
+            Err(e) => {
+                Output::error(&format!("{}: {}", name, e));
+                summary.failed += 1;
+            }
+        }
+    }
+    summary
+}
+
+fn sync_nested_workspace(
+    root: &Path,
+    config: &RepoConfig,
+    options: &SyncOptions,
+) -> anyhow::Result<SyncSummary> {
+    let manifests_dir = root.join(".gitgrip").join("manifests");
+    if !path_exists(manifests_dir.join(".git")) {
+        let spinner = Output::spinner("Cloning manifest...");
+        std::fs::create_dir_all(&manifests_dir)?;
+        if let Err(e) = clone_repo(
+            &config.url,
+            &manifests_dir,
+            Some(&config.default_branch),
+            None,
+        ) {
+            spinner.finish_with_message(format!("manifest: clone failed - {}", e));
+            let _ = std::fs::remove_dir_all(&manifests_dir);
+            return Err(e.into());
+        }
+        spinner.finish_with_message("manifest: cloned");
+    } else if !options.resume {
+        update_manifest_repo(&manifests_dir.join("manifest.yaml"));
+    }
+
+    let (root, manifest) = Workspace::open(root)?.into_parts();
+    if options.resume {
+        // Nothing left to continue in a nested workspace that finished
+        let state = StateFile::load(root.join(".gitgrip").join("state.json")).unwrap_or_default();
+        if state.sync_progress.is_none() {
+            return Ok(SyncSummary::default());
+        }
+    }
+    run_sync(&root, &manifest, options)
+}
+
+/// Apply `gr optimize` settings to every cloned repo (`settings.auto_optimize`)
+fn optimize_synced(repos: &[RepoInfo]) {
+    let cloned: Vec<&RepoInfo> = repos.iter().filter(|repo| repo.exists()).collect();
+    let fsmonitor = fsmonitor_supported();
+    let spinner = Output::spinner(&format!("Optimizing {} repo(s)...", cloned.len()));
+    let results = parallel_map(&cloned, max_workers(), |repo| {
+        optimize_repo(&repo.absolute_path, fsmonitor)
+    });
+    spinner.finish_and_clear();
+    for (repo, result) in cloned.iter().zip(results) {
+        if let Err(e) = result {
+            Output::warning(&format!("{}: optimize failed - {}", repo.name, e));
+        }
+    }
+}
+
+/// Fetch LFS objects that are still pointer files after a clone or pull
+fn sync_lfs(name: &str, repo_path: &Path) {
+    if !uses_lfs(repo_path) {
+        return;
+    }
+    if !lfs_available() {
+        Output::warning(&format!(
+            "{}: uses Git LFS but git-lfs is not installed; LFS files are pointers",
+            name
+        ));
+        return;
+    }
+    if missing_lfs_objects(repo_path) == 0 {
+        return;
+    }
+
+    let spinner = Output::spinner(&format!("Fetching LFS objects for {}...", name));
+    match pull_lfs_objects(repo_path) {
+        Ok(()) => spinner.finish_with_message(format!("{}: LFS objects fetched", name)),
+        Err(e) => spinner.finish_with_message(format!("{}: LFS pull failed - {}", name, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_migrate_moved_repo() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let old = root.join("old/app");
+        std::fs::create_dir_all(&old).unwrap();
+        git(&old, &["init", "-q", "-b", "main"]);
+        git(
+            &old,
+            &["remote", "add", "origin", "git@github.com:acme/app.git"],
+        );
+        git(&old, &["branch", "-q", "-m", "feat/local-work"]);
+        std::fs::write(old.join("Makefile"), "all:").unwrap();
+
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  app:
+    url: https://github.com/acme/app.git
+    path: services/app
+    linkfile:
+      - src: Makefile
+        dest: Makefile
+"#,
+        )
+        .unwrap();
+        let repos: Vec<RepoInfo> = manifest
+            .repos
+            .iter()
+            .filter_map(|(name, config)| RepoInfo::from_config(name, config, &root))
+            .collect();
+
+        migrate_moved_repos(&root, &manifest, &repos);
+
+        let new = root.join("services/app");
+        assert!(new.join(".git").exists());
+        assert!(!root.join("old").exists());
+        assert_eq!(
+            std::fs::read_link(root.join("Makefile")).unwrap(),
+            new.join("Makefile")
+        );
+        let head = std::fs::read_to_string(new.join(".git/HEAD")).unwrap();
+        assert!(head.contains("feat/local-work"));
+    }
+
+    #[test]
+    fn test_remove_partial_clone() {
+        let temp = TempDir::new().unwrap();
+
+        // Cut off before the first commit arrived
+        let partial = temp.path().join("partial");
+        std::fs::create_dir_all(&partial).unwrap();
+        git(&partial, &["init", "-q", "-b", "main"]);
+        assert!(remove_partial_clone(&partial));
+        assert!(!partial.exists());
+
+        let complete = temp.path().join("complete");
+        init_repo(&complete);
+        git(
+            &complete,
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        );
+        assert!(!remove_partial_clone(&complete));
+        assert!(complete.join(".git").exists());
+    }
+
+    #[test]
+    fn test_update_manifest_repo() {
+        let temp = TempDir::new().unwrap();
+        let upstream = temp.path().join("upstream");
+        init_repo(&upstream);
+        std::fs::write(upstream.join("manifest.yaml"), "repos: {}\n").unwrap();
+        git(&upstream, &["add", "."]);
+        git(&upstream, &["commit", "-q", "-m", "Initial manifest"]);
+
+        let manifests = temp.path().join(".gitgrip/manifests");
+        git(
+            temp.path(),
+            &[
+                "clone",
+                "-q",
+                upstream.to_str().unwrap(),
+                manifests.to_str().unwrap(),
+            ],
+        );
+        let manifest_path = manifests.join("manifest.yaml");
+
+        std::fs::write(upstream.join("manifest.yaml"), "repos:\n  app: {}\n").unwrap();
+        git(&upstream, &["commit", "-q", "-am", "Add app"]);
+        update_manifest_repo(&manifest_path);
+        assert_eq!(
+            std::fs::read_to_string(&manifest_path).unwrap(),
+            "repos:\n  app: {}\n"
+        );
+
+        // Local edits are left alone
+        std::fs::write(upstream.join("manifest.yaml"), "repos: {}\n").unwrap();
+        git(&upstream, &["commit", "-q", "-am", "Remove app"]);
+        std::fs::write(&manifest_path, "repos:\n  mine: {}\n").unwrap();
+        update_manifest_repo(&manifest_path);
+        assert_eq!(
+            std::fs::read_to_string(&manifest_path).unwrap(),
+            "repos:\n  mine: {}\n"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Undo command implementation
+//!
+//! Reverses the most recent operation in the audit log where git still has
+//! what's needed: deleted branches are recreated at the commit they pointed
+//! to, and resets are rewound to the previous tip. Operations that changed a
+//! remote (pushes, PR merges) are listed with the command that would reverse
+//! them, but not touched.
+
+use crate::cli::commands::audit::audit;
+use crate::cli::output::Output;
+use crate::core::audit::{last_undoable, read_log, AuditAction, AuditEntry};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::branch::branch_exists;
+use crate::git::remote::{reset, ResetMode};
+use crate::git::{get_current_branch, open_repo, path_exists};
+use chrono::Local;
+use dialoguer::Confirm;
+use git2::Oid;
+use std::path::PathBuf;
+
+/// A reversible step
+#[derive(Debug, PartialEq, Eq)]
+enum UndoStep {
+    /// Recreate `branch` at `commit`
+    RestoreBranch {
+        path: PathBuf,
+        branch: String,
+        commit: String,
+    },
+    /// Move the checked-out `branch` back to `commit`
+    ResetBranch {
+        path: PathBuf,
+        branch: String,
+        commit: String,
+        mode: ResetMode,
+    },
+}
+
+impl UndoStep {
+    fn describe(&self) -> String {
+        match self {
+            UndoStep::RestoreBranch { branch, commit, .. } => {
+                format!(
+                    "restore branch {} at {}",
+                    branch,
+                    &commit[..7.min(commit.len())]
+                )
+            }
+            UndoStep::ResetBranch { branch, commit, .. } => {
+                format!("move {} back to {}", branch, &commit[..7.min(commit.len())])
+            }
+        }
+    }
+
+    fn apply(&self) -> anyhow::Result<()> {
+        match self {
+            UndoStep::RestoreBranch {
+                path,
+                branch,
+                commit,
+            } => {
+                let repo = open_repo(path)?;
+                let commit = repo.find_commit(Oid::from_str(commit)?)?;
+                repo.branch(branch, &commit, false)?;
+                Ok(())
+            }
+            UndoStep::ResetBranch {
+                path, commit, mode, ..
+            } => Ok(reset(&open_repo(path)?, commit, *mode)?),
+        }
+    }
+}
+
+/// Run the undo command
+///
+/// Lists what can and can't be undone; with `dry_run`, stops there.
+pub fn run_undo(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let entries = read_log(workspace_root)?;
+    let group = last_undoable(&entries);
+    let Some(first) = group.first() else {
+        Output::info("Nothing to undo.");
+        return Ok(());
+    };
+
+    Output::header(&format!(
+        "Last operation: {} ({})",
+        first.command,
+        first
+            .timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+    ));
+    println!();
+
+    let mut steps: Vec<(&AuditEntry, UndoStep)> = Vec::new();
+    for entry in &group {
+        match plan_undo(workspace_root, manifest, entry) {
+            Ok(step) => {
+                Output::success(&format!("{}: can {}", entry.repo, step.describe()));
+                steps.push((entry, step));
+            }
+            Err(reason) => Output::warning(&format!(
+                "{}: cannot undo {} - {}",
+                entry.repo, entry.action, reason
+            )),
+        }
+    }
+    println!();
+
+    if steps.is_empty() {
+        Output::info("Nothing here can be undone automatically.");
+        return Ok(());
+    }
+
+    let proceed = if dry_run {
+        false
+    } else if yes {
+        true
+    } else {
+        Confirm::with_theme(&*Output::theme())
+            .with_prompt(format!("Undo {} step(s)?", steps.len()))
+            .default(false)
+            .interact()?
+    };
+    if !proceed {
+        Output::info("Nothing undone.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for (entry, step) in &steps {
+        match step.apply() {
+            Ok(()) => {
+                Output::success(&format!("{}: {}", entry.repo, step.describe()));
+                audit(
+                    workspace_root,
+                    AuditEntry {
+                        undoes: Some(entry.invocation.clone()),
+                        ..AuditEntry::new(AuditAction::Undo, &entry.repo).with_ref(
+                            entry.ref_name.as_deref().unwrap_or_default(),
+                            None,
+                            entry.before.clone(),
+                        )
+                    },
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                Output::error(&format!("{}: {}", entry.repo, e));
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} step(s) failed to undo", failed);
+    }
+
+    Ok(())
+}
+
+/// Work out how to reverse `entry`, or why it can't be
+fn plan_undo(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    entry: &AuditEntry,
+) -> Result<UndoStep, String> {
+    let branch = entry.ref_name.clone().unwrap_or_default();
+    match entry.action {
+        AuditAction::BranchDelete => {
+            let (path, git_repo, commit) = locate(workspace_root, manifest, entry)?;
+            if branch_exists(&git_repo, &branch) {
+                return Err(format!("branch {} exists again", branch));
+            }
+            Ok(UndoStep::RestoreBranch {
+                path,
+                branch,
+                commit,
+            })
+        }
+        AuditAction::Reset => {
+            let (path, git_repo, commit) = locate(workspace_root, manifest, entry)?;
+            if get_current_branch(&git_repo).ok().as_ref() != Some(&branch) {
+                return Err(format!("{} is no longer checked out", branch));
+            }
+            let head = git_repo
+                .head()
+                .ok()
+                .and_then(|head| head.target())
+                .map(|oid| oid.to_string());
+            if head.is_none() || head != entry.after {
+                return Err(format!("{} has moved since the reset", branch));
+            }
+            // Rewinding a hard reset must bring the files back, without
+            // discarding anything done since
+            let mode = match entry.mode.as_deref() {
+                Some("soft") => ResetMode::Soft,
+                Some("mixed") => ResetMode::Mixed,
+                _ => ResetMode::Keep,
+            };
+            Ok(UndoStep::ResetBranch {
+                path,
+                branch,
+                commit,
+                mode,
+            })
+        }
+        AuditAction::Push | AuditAction::ForcePush => Err(match (&entry.before, &entry.after) {
+            (Some(before), Some(after)) => format!(
+                "remote changes aren't undone automatically; to restore it run \
+                 `git push --force-with-lease={b}:{after} origin {before}:{b}`",
+                b = branch,
+                before = before,
+                after = after
+            ),
+            _ => format!(
+                "remote changes aren't undone automatically; to remove the new branch run \
+                 `git push origin --delete {}`",
+                branch
+            ),
+        }),
+        AuditAction::PrCreate => Err(format!(
+            "close PR #{} on the hosting platform",
+            entry.pr.unwrap_or_default()
+        )),
+        AuditAction::PrMerge => Err(format!(
+            "merged PR #{} can't be unmerged; revert the merge commit instead",
+            entry.pr.unwrap_or_default()
+        )),
+        AuditAction::Undo => Err("undo entries aren't undone".to_string()),
+    }
+}
+
+/// The checkout `entry` refers to, with the commit it recorded as `before`
+fn locate(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    entry: &AuditEntry,
+) -> Result<(PathBuf, git2::Repository, String), String> {
+    let repo = manifest
+        .repos
+        .get(&entry.repo)
+        .and_then(|config| RepoInfo::from_config(&entry.repo, config, workspace_root))
+        .filter(|repo| path_exists(&repo.absolute_path))
+        .ok_or("the repo is no longer in the workspace")?;
+    let commit = entry
+        .before
+        .clone()
+        .ok_or("the log doesn't say which commit it pointed to")?;
+    let git_repo = open_repo(&repo.absolute_path).map_err(|e| e.to_string())?;
+    let found = Oid::from_str(&commit)
+        .ok()
+        .is_some_and(|oid| git_repo.find_commit(oid).is_ok());
+    if !found {
+        return Err(format!("commit {} is gone (garbage collected?)", commit));
+    }
+    Ok((repo.absolute_path, git_repo, commit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_restore_deleted_branch() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let repo_path = root.join("app");
+        init_repo(&repo_path);
+        git(
+            &repo_path,
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        );
+        git(&repo_path, &["checkout", "-q", "-b", "feat"]);
+        git(&repo_path, &["commit", "-q", "--allow-empty", "-m", "Work"]);
+        let tip = git(&repo_path, &["rev-parse", "HEAD"]);
+        git(&repo_path, &["checkout", "-q", "main"]);
+        git(&repo_path, &["branch", "-q", "-D", "feat"]);
+
+        let manifest =
+            Manifest::parse("repos:\n  app:\n    url: git@github.com:org/app.git\n    path: app\n")
+                .unwrap();
+        let entry = AuditEntry::new(AuditAction::BranchDelete, "app").with_ref(
+            "feat",
+            Some(tip.clone()),
+            None,
+        );
+
+        let step = plan_undo(&root, &manifest, &entry).unwrap();
+        step.apply().unwrap();
+        assert_eq!(git(&repo_path, &["rev-parse", "feat"]), tip);
+        assert!(plan_undo(&root, &manifest, &entry)
+            .unwrap_err()
+            .contains("exists again"));
+
+        let push = AuditEntry::new(AuditAction::Push, "app").with_ref("feat", None, Some(tip));
+        assert!(plan_undo(&root, &manifest, &push)
+            .unwrap_err()
+            .contains("git push origin --delete feat"));
+
+        // A hard reset is rewound with the files brought back
+        std::fs::write(repo_path.join("file.txt"), "v1").unwrap();
+        git(&repo_path, &["add", "file.txt"]);
+        git(&repo_path, &["commit", "-q", "-m", "Add file"]);
+        let before = git(&repo_path, &["rev-parse", "HEAD"]);
+        git(&repo_path, &["reset", "-q", "--hard", "HEAD~1"]);
+        let after = git(&repo_path, &["rev-parse", "HEAD"]);
+        let entry = AuditEntry {
+            mode: Some("hard".to_string()),
+            ..AuditEntry::new(AuditAction::Reset, "app").with_ref(
+                "main",
+                Some(before.clone()),
+                Some(after),
+            )
+        };
+        let step = plan_undo(&root, &manifest, &entry).unwrap();
+        assert!(matches!(
+            step,
+            UndoStep::ResetBranch {
+                mode: ResetMode::Keep,
+                ..
+            }
+        ));
+        step.apply().unwrap();
+        assert_eq!(git(&repo_path, &["rev-parse", "HEAD"]), before);
+        assert!(repo_path.join("file.txt").exists());
+        assert!(plan_undo(&root, &manifest, &entry)
+            .unwrap_err()
+            .contains("has moved"));
+    }
+}
@@ -0,0 +1,300 @@
+//! Verify command implementation
+//!
+//! Checks each repo's object database and refs with `git fsck` (or, without
+//! a git binary, by reading every object through libgit2), and that pinned
+//! manifest revisions still resolve. Corrupted repos get recovery steps that
+//! re-clone without losing local branches.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::open_repo;
+use crate::git::revision::resolve_revision;
+use crate::util::profile::ProfiledCommand;
+use crate::util::{max_workers, parallel_map};
+use git2::Repository;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Settings for `gr verify`
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    /// Only check that objects are reachable, not their contents
+    pub quick: bool,
+}
+
+/// What was found wrong with one repo
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoVerification {
+    /// Missing or corrupt objects and broken refs
+    pub corruption: Vec<String>,
+    /// The manifest `revision:` that doesn't resolve, if any
+    pub missing_pin: Option<String>,
+}
+
+impl RepoVerification {
+    pub fn is_ok(&self) -> bool {
+        self.corruption.is_empty() && self.missing_pin.is_none()
+    }
+}
+
+/// Run the verify command
+pub fn run_verify(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &VerifyOptions,
+) -> anyhow::Result<()> {
+    let mut repos: Vec<RepoInfo> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| repo.exists())
+        .collect();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let spinner = Output::spinner(&format!("Verifying {} repositories...", repos.len()));
+    let results = parallel_map(&repos, max_workers(), |repo| {
+        let revision = manifest
+            .repos
+            .get(&repo.name)
+            .and_then(|config| config.revision.as_deref());
+        verify_repo(&repo.absolute_path, revision, options.quick)
+    });
+    spinner.finish_and_clear();
+
+    let mut table = Table::new(vec!["Repo", "Objects & refs", "Pinned revision"]);
+    for (repo, result) in repos.iter().zip(&results) {
+        let objects = match result.corruption.len() {
+            0 => "ok".to_string(),
+            n => format!("{} problem(s)", n),
+        };
+        let pin = match (&result.missing_pin, manifest.repos.get(&repo.name)) {
+            (Some(_), _) => "missing".to_string(),
+            (None, Some(config)) if config.revision.is_some() => "ok".to_string(),
+            _ => "-".to_string(),
+        };
+        table.add_row(vec![&repo.name, &objects, &pin]);
+    }
+    table.print();
+
+    let mut failed = 0;
+    for (repo, result) in repos.iter().zip(&results) {
+        if result.is_ok() {
+            continue;
+        }
+        failed += 1;
+        println!();
+        Output::error(&format!("{} ({})", repo.name, repo.path));
+        for problem in &result.corruption {
+            Output::list_item(problem);
+        }
+        if let Some(ref pin) = result.missing_pin {
+            Output::list_item(pin);
+            Output::info("Fetch, or fix the manifest's revision if it was rewritten upstream");
+        }
+        if !result.corruption.is_empty() {
+            print_recovery(repo);
+        }
+    }
+
+    println!();
+    if failed > 0 {
+        anyhow::bail!("{} of {} repo(s) failed verification", failed, repos.len());
+    }
+    Output::success(&format!("All {} repositories verified.", repos.len()));
+    Ok(())
+}
+
+/// Check one repo's objects, refs, and pinned revision
+pub fn verify_repo(path: &Path, revision: Option<&str>, quick: bool) -> RepoVerification {
+    let repo = match open_repo(path) {
+        Ok(repo) => repo,
+        Err(e) => {
+            return RepoVerification {
+                corruption: vec![e.to_string()],
+                missing_pin: None,
+            }
+        }
+    };
+
+    let corruption = match fsck_cli(path, quick) {
+        Some(problems) => problems,
+        None => fsck_odb(&repo),
+    };
+    let missing_pin = revision.and_then(|revision| {
+        resolve_revision(&repo, revision, "origin")
+            .err()
+            .map(|e| e.to_string())
+    });
+    RepoVerification {
+        corruption,
+        missing_pin,
+    }
+}
+
+/// Problems `git fsck` reports, or `None` if git couldn't be run
+fn fsck_cli(path: &Path, quick: bool) -> Option<Vec<String>> {
+    let mut args = vec!["fsck", "--no-dangling", "--no-progress"];
+    if quick {
+        args.push("--connectivity-only");
+    }
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(path)
+        .timed_output()
+        .ok()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut problems: Vec<String> = stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim)
+        .filter(|line| {
+            !line.is_empty() && !line.starts_with("notice:") && !line.starts_with("Checking")
+        })
+        .map(str::to_string)
+        .collect();
+    if problems.is_empty() && !output.status.success() {
+        problems.push(format!("git fsck failed ({})", output.status));
+    }
+    Some(problems)
+}
+
+/// Read every object, resolve every ref, and check that every tree entry
+/// reachable from a ref exists, through libgit2
+fn fsck_odb(repo: &Repository) -> Vec<String> {
+    let mut problems = Vec::new();
+    let odb = match repo.odb() {
+        Ok(odb) => odb,
+        Err(e) => return vec![format!("cannot open object database: {}", e.message())],
+    };
+
+    let mut oids = Vec::new();
+    if let Err(e) = odb.foreach(|oid| {
+        oids.push(*oid);
+        true
+    }) {
+        problems.push(format!("cannot list objects: {}", e.message()));
+    }
+    for oid in oids {
+        if let Err(e) = odb.read(oid) {
+            problems.push(format!("corrupt object {}: {}", oid, e.message()));
+        }
+    }
+
+    match repo.references() {
+        Ok(references) => {
+            for reference in references {
+                match reference {
+                    Ok(reference) => {
+                        if let Err(e) = reference.peel(git2::ObjectType::Any) {
+                            problems.push(format!(
+                                "{}: {}",
+                                reference.name().unwrap_or("(invalid ref name)"),
+                                e.message()
+                            ));
+                        }
+                    }
+                    Err(e) => problems.push(format!("unreadable ref: {}", e.message())),
+                }
+            }
+        }
+        Err(e) => problems.push(format!("cannot list refs: {}", e.message())),
+    }
+
+    // Connectivity: every blob and tree the history points at
+    let Ok(mut walk) = repo.revwalk() else {
+        return problems;
+    };
+    let _ = walk.push_glob("refs/*");
+    let mut seen_trees = HashSet::new();
+    for oid in walk {
+        let commit = match oid.and_then(|oid| repo.find_commit(oid)) {
+            Ok(commit) => commit,
+            Err(e) => {
+                problems.push(format!("unreadable commit: {}", e.message()));
+                continue;
+            }
+        };
+        let tree = match commit.tree() {
+            Ok(tree) => tree,
+            Err(_) => {
+                problems.push(format!("missing tree {}", commit.tree_id()));
+                continue;
+            }
+        };
+        if !seen_trees.insert(tree.id()) {
+            continue;
+        }
+        let _ = tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+            if entry.kind() == Some(git2::ObjectType::Commit) {
+                // Submodule commit, stored in another repo
+                return git2::TreeWalkResult::Ok;
+            }
+            if !odb.exists(entry.id()) {
+                problems.push(format!(
+                    "missing {} {}",
+                    entry.kind().map_or("object", |kind| kind.str()),
+                    entry.id()
+                ));
+                return git2::TreeWalkResult::Skip;
+            }
+            git2::TreeWalkResult::Ok
+        });
+    }
+    problems.sort();
+    problems.dedup();
+    problems
+}
+
+/// Steps to re-clone a repo while keeping what can be saved of its branches
+fn print_recovery(repo: &RepoInfo) {
+    Output::info("To recover, re-clone and fetch the branches that are still readable:");
+    println!("    mv {path} {path}.corrupt", path = repo.path);
+    println!("    gr sync");
+    println!(
+        "    git -C {path} fetch ../{name}.corrupt 'refs/heads/*:refs/heads/recovered/*'",
+        path = repo.path,
+        name = repo
+            .path
+            .rsplit('/')
+            .find(|part| !part.is_empty())
+            .unwrap_or(&repo.path)
+    );
+    Output::info(&format!(
+        "then check the recovered/* branches and delete {}.corrupt",
+        repo.path
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_verify_repo() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        std::fs::write(path.join("file.txt"), "hello\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Initial"]);
+        git(path, &["tag", "v1.0.0"]);
+
+        assert!(verify_repo(path, Some("v1.0.0"), false).is_ok());
+        let result = verify_repo(path, Some("v9.9.9"), false);
+        assert!(result.corruption.is_empty());
+        assert!(result.missing_pin.is_some());
+
+        let blob = git(path, &["rev-parse", "HEAD:file.txt"]);
+        std::fs::remove_file(path.join(".git/objects").join(&blob[..2]).join(&blob[2..])).unwrap();
+        let result = verify_repo(path, None, false);
+        assert!(result.corruption.iter().any(|p| p.contains(&blob)));
+        let problems = fsck_odb(&open_repo(path).unwrap());
+        assert!(problems.iter().any(|p| p.contains(&blob)), "{:?}", problems);
+    }
+}
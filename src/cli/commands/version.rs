@@ -0,0 +1,301 @@
+//! Version command implementation
+//!
+//! `gr version check` reads every file listed under the manifest's
+//! `versions` and reports where they disagree. `gr version bump` moves one
+//! version to its next major, minor, or patch release in all of its files
+//! and commits the change on a new branch in each repo.
+
+use crate::cli::output::Output;
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::core::version::{
+    bump_version, file_pattern, find_versions, replace_versions, version_regex, Bump,
+};
+use crate::git::branch::create_and_checkout_branch;
+use crate::git::status::get_status_info;
+use crate::git::{open_repo, path_exists, run_git};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings for `gr version bump`
+#[derive(Debug, Clone)]
+pub struct VersionBumpOptions {
+    /// `major`, `minor`, or `patch`
+    pub level: String,
+    /// Version to bump; may be omitted when the manifest defines one
+    pub name: Option<String>,
+    /// Branch created in each repo; `version/<name>-<version>` by default
+    pub branch: Option<String>,
+    /// Show the changes without writing anything
+    pub dry_run: bool,
+}
+
+/// One file of a version and what it holds
+#[derive(Debug, Clone, Serialize)]
+struct Location {
+    repo: String,
+    path: String,
+    #[serde(skip)]
+    absolute_path: Option<PathBuf>,
+    /// Every version the pattern matched
+    versions: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A version and whether its files agree
+#[derive(Debug, Serialize)]
+struct VersionReport {
+    name: String,
+    /// The shared version, when every file has the same one
+    version: Option<String>,
+    files: Vec<Location>,
+}
+
+/// Run `gr version check`
+pub fn run_version_check(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    json: bool,
+) -> anyhow::Result<()> {
+    if manifest.versions.is_empty() {
+        anyhow::bail!("No versions defined; add a 'versions:' section to the manifest");
+    }
+    let mut names: Vec<&String> = manifest.versions.keys().collect();
+    names.sort();
+    let reports: Vec<VersionReport> = names
+        .into_iter()
+        .map(|name| read_version(workspace_root, manifest, name))
+        .collect();
+    let inconsistent = reports.iter().filter(|r| r.version.is_none()).count();
+
+    if json {
+        Output::json(&reports)?;
+    } else {
+        Output::header("Versions");
+        println!();
+        for report in &reports {
+            match report.version {
+                Some(ref version) => Output::success(&format!(
+                    "{}: {} in {} file(s)",
+                    report.name,
+                    version,
+                    report.files.len()
+                )),
+                None => {
+                    Output::error(&format!("{}: files disagree", report.name));
+                    print_locations(&report.files);
+                }
+            }
+        }
+    }
+
+    if inconsistent > 0 {
+        anyhow::bail!("{} version(s) out of step", inconsistent);
+    }
+    Ok(())
+}
+
+/// Run `gr version bump`
+pub fn run_version_bump(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+    options: &VersionBumpOptions,
+) -> anyhow::Result<()> {
+    let bump = Bump::parse(&options.level).map_err(anyhow::Error::msg)?;
+    let name = match options.name {
+        Some(ref name) if manifest.versions.contains_key(name) => name.clone(),
+        Some(ref name) => anyhow::bail!("Unknown version '{}'", name),
+        None => {
+            let mut names: Vec<&String> = manifest.versions.keys().collect();
+            names.sort();
+            match names[..] {
+                [] => {
+                    anyhow::bail!("No versions defined; add a 'versions:' section to the manifest")
+                }
+                [only] => only.clone(),
+                _ => anyhow::bail!(
+                    "Several versions defined; pick one with --name ({})",
+                    names
+                        .iter()
+                        .map(|n| n.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }
+        }
+    };
+
+    let report = read_version(workspace_root, manifest, &name);
+    let Some(current) = report.version else {
+        Output::error(&format!("{}: files disagree", name));
+        print_locations(&report.files);
+        anyhow::bail!("Fix the versions of '{}' before bumping", name);
+    };
+    let next = bump_version(&current, bump).map_err(anyhow::Error::msg)?;
+    let branch = options
+        .branch
+        .clone()
+        .unwrap_or_else(|| format!("version/{}-{}", name, next));
+
+    // Rewritten files per repo; checked before anything is written
+    let mut edits: BTreeMap<String, (PathBuf, Vec<(String, String)>)> = BTreeMap::new();
+    for (file, location) in manifest.versions[&name].iter().zip(&report.files) {
+        let Some(ref path) = location.absolute_path else {
+            continue;
+        };
+        let regex =
+            version_regex(&file_pattern(file).unwrap_or_default()).map_err(anyhow::Error::msg)?;
+        let files = &mut edits
+            .entry(file.repo.clone())
+            .or_insert_with(|| (path.clone(), Vec::new()))
+            .1;
+        // A file listed twice (with different patterns) gets both rewrites
+        match files.iter_mut().find(|(p, _)| *p == file.path) {
+            Some((_, content)) => *content = replace_versions(content, &regex, &next),
+            None => {
+                let content = std::fs::read_to_string(path.join(&file.path))?;
+                files.push((file.path.clone(), replace_versions(&content, &regex, &next)));
+            }
+        }
+    }
+    for (repo, (path, _)) in &edits {
+        let dirty = open_repo(path)
+            .ok()
+            .and_then(|r| get_status_info(&r).ok())
+            .map(|s| !s.staged.is_empty() || !s.modified.is_empty())
+            .unwrap_or(true);
+        if dirty {
+            anyhow::bail!(
+                "{} has uncommitted changes; commit or stash them first",
+                repo
+            );
+        }
+    }
+
+    Output::header(&format!("{}: {} -> {} ({})", name, current, next, bump));
+    println!();
+    for (repo, (_, files)) in &edits {
+        for (file, _) in files {
+            println!("  {}/{}", Output::repo_name(repo), file);
+        }
+    }
+    if options.dry_run {
+        println!();
+        Output::info("Dry run; nothing written.");
+        return Ok(());
+    }
+
+    println!();
+    let message = format!("Bump {} to {}", name, next);
+    let mut failed = 0;
+    for (repo, (path, files)) in &edits {
+        match commit_bump(path, files, &branch, &message) {
+            Ok(()) => Output::success(&format!("{}: committed on {}", repo, branch)),
+            Err(e) => {
+                Output::error(&format!("{}: {}", repo, e));
+                failed += 1;
+            }
+        }
+    }
+
+    let after = read_version(workspace_root, manifest, &name);
+    println!();
+    if failed > 0 {
+        anyhow::bail!("Bump failed in {} repo(s)", failed);
+    }
+    match after.version {
+        Some(version) if version == next => Output::success(&format!(
+            "{} is {} everywhere. Push with 'gr push -u' and open PRs with 'gr pr create'.",
+            name, next
+        )),
+        _ => {
+            Output::warning(&format!("{}: files still disagree", name));
+            print_locations(&after.files);
+        }
+    }
+    Ok(())
+}
+
+/// Read every file of version `name`
+fn read_version(workspace_root: &PathBuf, manifest: &Manifest, name: &str) -> VersionReport {
+    let files: Vec<Location> = manifest.versions[name]
+        .iter()
+        .map(|file| {
+            let repo_path = manifest
+                .repos
+                .get(&file.repo)
+                .and_then(|config| RepoInfo::from_config(&file.repo, config, workspace_root))
+                .map(|repo| repo.absolute_path)
+                .filter(|path| path_exists(path));
+            let mut location = Location {
+                repo: file.repo.clone(),
+                path: file.path.clone(),
+                absolute_path: repo_path.clone(),
+                versions: Vec::new(),
+                error: None,
+            };
+            let read = repo_path
+                .ok_or_else(|| "repo not cloned".to_string())
+                .and_then(|path| read_file_versions(&path, file));
+            match read {
+                Ok(versions) => location.versions = versions,
+                Err(e) => location.error = Some(e),
+            }
+            location
+        })
+        .collect();
+
+    let first = files.first().and_then(|f| f.versions.first());
+    let consistent = files.iter().all(|f| {
+        f.error.is_none() && !f.versions.is_empty() && f.versions.iter().all(|v| Some(v) == first)
+    });
+    VersionReport {
+        name: name.to_string(),
+        version: first.filter(|_| consistent).cloned(),
+        files,
+    }
+}
+
+fn read_file_versions(
+    repo_path: &Path,
+    file: &crate::core::manifest::VersionFile,
+) -> Result<Vec<String>, String> {
+    let pattern = file_pattern(file).ok_or_else(|| "no pattern".to_string())?;
+    let regex = version_regex(&pattern)?;
+    let content = std::fs::read_to_string(repo_path.join(&file.path)).map_err(|e| e.to_string())?;
+    let versions = find_versions(&content, &regex);
+    if versions.is_empty() {
+        return Err("pattern not found".to_string());
+    }
+    Ok(versions)
+}
+
+fn print_locations(files: &[Location]) {
+    for file in files {
+        let found = match file.error {
+            Some(ref error) => error.clone(),
+            None => file.versions.join(", "),
+        };
+        println!("    {}/{}: {}", file.repo, file.path, found);
+    }
+}
+
+fn commit_bump(
+    repo_path: &Path,
+    files: &[(String, String)],
+    branch: &str,
+    message: &str,
+) -> anyhow::Result<()> {
+    let git_repo = open_repo(repo_path)?;
+    create_and_checkout_branch(&git_repo, branch)?;
+    for (file, content) in files {
+        std::fs::write(repo_path.join(file), content)?;
+    }
+    let mut add = vec!["add", "--"];
+    add.extend(files.iter().map(|(file, _)| file.as_str()));
+    run_git(repo_path, &add)?;
+    run_git(repo_path, &["commit", "-m", message])?;
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Workspace command implementation
+//!
+//! Lists, names, and switches between the workspaces in the user's registry,
+//! and runs a command in each of them.
+
+use crate::cli::output::{Output, Table};
+use crate::core::manifest::ShellKind;
+use crate::core::registry::WorkspaceRegistry;
+use crate::util::process::{is_cancelled, spawn_in_group, wait_with_timeout, Outcome};
+use crate::util::shell_command;
+use crate::Workspace;
+use std::path::Path;
+
+/// Run the workspace list command
+pub fn run_workspace_list() -> anyhow::Result<()> {
+    let registry = WorkspaceRegistry::load(WorkspaceRegistry::default_path()?)?;
+    if registry.workspaces.is_empty() {
+        Output::info("No workspaces registered yet; `gr sync` in a workspace adds it.");
+        return Ok(());
+    }
+
+    let current_root = std::env::current_dir()
+        .ok()
+        .and_then(|dir| Workspace::locate(dir).ok())
+        .map(|(root, _)| root.canonicalize().unwrap_or(root));
+
+    let mut table = Table::new(vec!["", "Name", "Path", "Repos"]);
+    for (name, path) in &registry.workspaces {
+        let marker = if current_root.as_deref() == Some(path.as_path()) {
+            "*"
+        } else if registry.current.as_deref() == Some(name.as_str()) {
+            ">"
+        } else {
+            ""
+        };
+        let repos = match Workspace::open(path) {
+            Ok(workspace) => workspace.manifest().repos.len().to_string(),
+            Err(_) if !path.exists() => "missing".to_string(),
+            Err(_) => "invalid".to_string(),
+        };
+        table.add_row(vec![marker, name, &path.display().to_string(), &repos]);
+    }
+    table.print();
+    println!();
+    Output::info("* current directory, > default from `gr workspace switch`");
+    Ok(())
+}
+
+/// Run the workspace add command: register the current workspace
+pub fn run_workspace_add(workspace_root: &Path, name: Option<&str>) -> anyhow::Result<()> {
+    let path = WorkspaceRegistry::default_path()?;
+    let mut registry = WorkspaceRegistry::load(&path)?;
+    let root = workspace_root.canonicalize()?;
+    let name = registry.register(&root, name)?;
+    registry.save(&path)?;
+    Output::success(&format!("Registered {} as '{}'", root.display(), name));
+    Ok(())
+}
+
+/// Run the workspace remove command
+pub fn run_workspace_remove(name: &str) -> anyhow::Result<()> {
+    let path = WorkspaceRegistry::default_path()?;
+    let mut registry = WorkspaceRegistry::load(&path)?;
+    let root = registry.remove(name)?;
+    registry.save(&path)?;
+    Output::success(&format!(
+        "Forgot '{}' ({} was left in place)",
+        name,
+        root.display()
+    ));
+    Ok(())
+}
+
+/// Run the workspace switch command
+///
+/// A process can't change its shell's directory, so this sets the workspace
+/// commands use when run outside any workspace, and prints where it is.
+pub fn run_workspace_switch(name: Option<&str>) -> anyhow::Result<()> {
+    let path = WorkspaceRegistry::default_path()?;
+    let mut registry = WorkspaceRegistry::load(&path)?;
+    match name {
+        Some(name) => {
+            let root = registry.resolve(name)?.to_path_buf();
+            registry.current = Some(name.to_string());
+            registry.save(&path)?;
+            Output::success(&format!("Switched to '{}' ({})", name, root.display()));
+            Output::info("gr commands run outside a workspace now act on it");
+        }
+        None => {
+            registry.current = None;
+            registry.save(&path)?;
+            Output::success("Cleared the default workspace");
+        }
+    }
+    Ok(())
+}
+
+/// Run the workspace run command: `command` in each registered workspace root
+pub fn run_workspace_run(
+    command: &str,
+    only: &[String],
+    continue_on_error: bool,
+) -> anyhow::Result<()> {
+    let registry = WorkspaceRegistry::load(WorkspaceRegistry::default_path()?)?;
+    for name in only {
+        registry.resolve(name)?;
+    }
+    let targets: Vec<(&String, &std::path::PathBuf)> = registry
+        .workspaces
+        .iter()
+        .filter(|(name, _)| only.is_empty() || only.contains(name))
+        .collect();
+
+    let mut failed = 0;
+    for (name, root) in targets {
+        if is_cancelled() {
+            Output::warning("Cancelled; remaining workspaces skipped");
+            break;
+        }
+        if !root.exists() {
+            Output::warning(&format!("{}: {} is missing, skipped", name, root.display()));
+            continue;
+        }
+        Output::header(&format!("{} ({}):", name, root.display()));
+        let mut cmd = shell_command(ShellKind::platform_default(), command);
+        cmd.current_dir(root).env("GITGRIP_WORKSPACE", name);
+        let result = spawn_in_group(&mut cmd)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut child| Ok(wait_with_timeout(&mut child, None)?));
+        println!();
+        let error = match result {
+            Ok(Outcome::Exited(status)) if status.success() => continue,
+            Ok(Outcome::Exited(status)) => format!("exit code: {:?}", status.code()),
+            Ok(Outcome::TimedOut | Outcome::Cancelled) => "cancelled".to_string(),
+            Err(e) => e.to_string(),
+        };
+        Output::error(&format!("{}: {}", name, error));
+        failed += 1;
+        if !continue_on_error {
+            break;
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("Command failed in {} workspace(s)", failed);
+    }
+    Ok(())
+}
@@ -0,0 +1,588 @@
+//! Diff rendering
+//!
+//! Renders unified diffs, from git or a hosting platform, for the terminal.
+//! Removed lines followed by added lines are paired up, and the words that
+//! changed between a pair are highlighted. The unified layout keeps every
+//! line as it is, so uncolored output (e.g. when piped) is still a valid
+//! patch; the side-by-side layout shows old and new next to each other with
+//! line numbers.
+
+use crate::core::manifest::{DiffSettings, DiffTheme, SideBySide};
+use colored::{Color, Colorize};
+
+/// Narrowest terminal that `side_by_side: auto` uses two columns for
+pub const SIDE_BY_SIDE_MIN_WIDTH: usize = 160;
+
+/// Lines with more tokens than this are compared whole, not word by word
+const MAX_WORD_TOKENS: usize = 200;
+
+/// Share of a pair's text that must be unchanged for word highlighting;
+/// below it the lines are unrelated and highlighting would be noise
+const MIN_SHARED: f64 = 0.3;
+
+/// Width of the line number columns in side-by-side layout
+const LINE_NUMBER_WIDTH: usize = 4;
+
+/// Colors for one theme
+struct Palette {
+    removed: Color,
+    added: Color,
+    /// Background of changed words in removed and added lines
+    removed_emph: Color,
+    added_emph: Color,
+    /// Text of changed words
+    emph_text: Color,
+    hunk: Color,
+    file: Color,
+}
+
+fn palette(theme: DiffTheme) -> Option<Palette> {
+    match theme {
+        DiffTheme::Dark => Some(Palette {
+            removed: Color::Red,
+            added: Color::Green,
+            removed_emph: Color::TrueColor {
+                r: 120,
+                g: 24,
+                b: 24,
+            },
+            added_emph: Color::TrueColor {
+                r: 24,
+                g: 100,
+                b: 32,
+            },
+            emph_text: Color::BrightWhite,
+            hunk: Color::Cyan,
+            file: Color::Yellow,
+        }),
+        DiffTheme::Light => Some(Palette {
+            removed: Color::Red,
+            added: Color::Green,
+            removed_emph: Color::TrueColor {
+                r: 255,
+                g: 192,
+                b: 192,
+            },
+            added_emph: Color::TrueColor {
+                r: 188,
+                g: 240,
+                b: 188,
+            },
+            emph_text: Color::Black,
+            hunk: Color::Blue,
+            file: Color::Blue,
+        }),
+        DiffTheme::Plain => None,
+    }
+}
+
+/// Which side of a diff a line is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Context,
+    Removed,
+    Added,
+}
+
+/// Text of a line, split into runs that did or didn't change
+type Segments = Vec<(String, bool)>;
+
+/// Renders unified diffs with a theme and layout
+pub struct DiffRenderer {
+    palette: Option<Palette>,
+    side_by_side: bool,
+    width: usize,
+}
+
+impl DiffRenderer {
+    /// A renderer for stdout; `auto` goes side by side on wide terminals
+    ///
+    /// Plain output mode gets the uncolored unified patch, since word
+    /// highlights are color alone and columns don't read aloud.
+    pub fn new(settings: &DiffSettings) -> Self {
+        let width = crate::cli::pager::terminal_width();
+        if crate::cli::output::Output::is_plain() {
+            return Self::with_width(DiffTheme::Plain, false, width.unwrap_or(0));
+        }
+        let side_by_side = match settings.side_by_side {
+            SideBySide::Always => true,
+            SideBySide::Never => false,
+            SideBySide::Auto => width.is_some_and(|w| w >= SIDE_BY_SIDE_MIN_WIDTH),
+        };
+        Self::with_width(
+            settings.theme,
+            side_by_side,
+            width.unwrap_or(SIDE_BY_SIDE_MIN_WIDTH),
+        )
+    }
+
+    /// A renderer for a fixed layout and width
+    pub fn with_width(theme: DiffTheme, side_by_side: bool, width: usize) -> Self {
+        Self {
+            palette: palette(theme),
+            side_by_side,
+            width,
+        }
+    }
+
+    /// Render a unified diff
+    pub fn render(&self, diff: &str) -> String {
+        let mut out = String::new();
+        let lines: Vec<&str> = diff.lines().collect();
+        let mut i = 0;
+        // Lines left in the current hunk, when its header gave counts
+        let mut hunk: Option<(usize, usize)> = None;
+        let (mut old_line, mut new_line) = (0, 0);
+        let mut titled = false;
+
+        while i < lines.len() {
+            let line = lines[i];
+            let in_hunk = hunk.is_some_and(|(old, new)| old + new > 0)
+                && line
+                    .chars()
+                    .next()
+                    .is_some_and(|c| matches!(c, ' ' | '-' | '+' | '\\'));
+            if !in_hunk {
+                hunk = None;
+                if let Some((old_start, old_count, new_start, new_count)) = parse_hunk_header(line)
+                {
+                    hunk = Some((old_count, new_count));
+                    (old_line, new_line) = (old_start, new_start);
+                    self.push_styled(&mut out, line, self.palette.as_ref().map(|p| p.hunk));
+                } else {
+                    self.header_line(&mut out, line, &mut titled);
+                }
+                i += 1;
+                continue;
+            }
+
+            match line.as_bytes()[0] {
+                b'\\' => {
+                    out.push_str(&self.dim(line));
+                    out.push('\n');
+                    i += 1;
+                }
+                b' ' => {
+                    self.context_line(&mut out, &line[1..], old_line, new_line);
+                    old_line += 1;
+                    new_line += 1;
+                    if let Some((old, new)) = hunk.as_mut() {
+                        *old = old.saturating_sub(1);
+                        *new = new.saturating_sub(1);
+                    }
+                    i += 1;
+                }
+                _ => {
+                    // A run of removed lines, then the added lines replacing them
+                    let mut removed = Vec::new();
+                    let mut added = Vec::new();
+                    while i < lines.len() && lines[i].starts_with('-') && hunk_left(hunk).0 > 0 {
+                        removed.push(&lines[i][1..]);
+                        take(&mut hunk, Side::Removed);
+                        i += 1;
+                    }
+                    while i < lines.len() && lines[i].starts_with('+') && hunk_left(hunk).1 > 0 {
+                        added.push(&lines[i][1..]);
+                        take(&mut hunk, Side::Added);
+                        i += 1;
+                    }
+                    if removed.is_empty() && added.is_empty() {
+                        // Counts ran out early; show the line as it is
+                        self.push_styled(&mut out, line, None);
+                        i += 1;
+                        continue;
+                    }
+                    self.change_block(&mut out, &removed, &added, old_line, new_line);
+                    old_line += removed.len();
+                    new_line += added.len();
+                }
+            }
+        }
+        out
+    }
+
+    fn push_styled(&self, out: &mut String, text: &str, color: Option<Color>) {
+        match color {
+            Some(color) => out.push_str(&text.color(color).to_string()),
+            None => out.push_str(text),
+        }
+        out.push('\n');
+    }
+
+    /// A line outside hunks: file headers and notes
+    fn header_line(&self, out: &mut String, line: &str, titled: &mut bool) {
+        let file = self.palette.as_ref().map(|p| p.file);
+        if !self.side_by_side {
+            if line.starts_with("diff ") || line.starts_with("--- ") || line.starts_with("+++ ") {
+                match file {
+                    Some(color) => out.push_str(&line.color(color).bold().to_string()),
+                    None => out.push_str(line),
+                }
+                out.push('\n');
+            } else {
+                out.push_str(&self.dim(line));
+                out.push('\n');
+            }
+            return;
+        }
+
+        // Side by side: one title per file, plus notes such as renames
+        let title = if let Some(paths) = line.strip_prefix("diff --git ") {
+            *titled = false;
+            paths.rsplit_once(" b/").map(|(_, path)| path.to_string())
+        } else if let Some(path) = line.strip_prefix("Index: ") {
+            *titled = false;
+            Some(path.to_string())
+        } else {
+            line.strip_prefix("+++ ")
+                .map(|path| path.strip_prefix("b/").unwrap_or(path).to_string())
+        };
+        match title {
+            Some(title) => {
+                if !*titled {
+                    *titled = true;
+                    out.push('\n');
+                    match file {
+                        Some(color) => out.push_str(&title.color(color).bold().to_string()),
+                        None => out.push_str(&title),
+                    }
+                    out.push('\n');
+                }
+            }
+            None if line.starts_with("index ")
+                || line.starts_with("--- ")
+                || line.starts_with("====") => {}
+            None => {
+                out.push_str(&self.dim(line));
+                out.push('\n');
+            }
+        }
+    }
+
+    /// Dimmed text, unless the theme is plain
+    fn dim(&self, text: &str) -> String {
+        match self.palette {
+            Some(_) => text.dimmed().to_string(),
+            None => text.to_string(),
+        }
+    }
+
+    fn context_line(&self, out: &mut String, text: &str, old_line: usize, new_line: usize) {
+        let segments = vec![(text.to_string(), false)];
+        if self.side_by_side {
+            self.row(
+                out,
+                Some((old_line, &segments, Side::Context)),
+                Some((new_line, &segments, Side::Context)),
+            );
+        } else {
+            out.push(' ');
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+
+    fn change_block(
+        &self,
+        out: &mut String,
+        removed: &[&str],
+        added: &[&str],
+        old_line: usize,
+        new_line: usize,
+    ) {
+        let whole = |text: &str| vec![(text.to_string(), false)];
+        let mut old: Vec<Segments> = removed.iter().map(|t| whole(t)).collect();
+        let mut new: Vec<Segments> = added.iter().map(|t| whole(t)).collect();
+        if self.palette.is_some() {
+            for k in 0..removed.len().min(added.len()) {
+                if let Some((a, b)) = word_diff(removed[k], added[k]) {
+                    old[k] = a;
+                    new[k] = b;
+                }
+            }
+        }
+
+        if self.side_by_side {
+            for k in 0..old.len().max(new.len()) {
+                self.row(
+                    out,
+                    old.get(k).map(|s| (old_line + k, s, Side::Removed)),
+                    new.get(k).map(|s| (new_line + k, s, Side::Added)),
+                );
+            }
+        } else {
+            for segments in &old {
+                out.push_str(&self.paint('-', segments, Side::Removed, None));
+                out.push('\n');
+            }
+            for segments in &new {
+                out.push_str(&self.paint('+', segments, Side::Added, None));
+                out.push('\n');
+            }
+        }
+    }
+
+    /// One side-by-side row; either side may be empty
+    fn row(
+        &self,
+        out: &mut String,
+        left: Option<(usize, &Segments, Side)>,
+        right: Option<(usize, &Segments, Side)>,
+    ) {
+        // number, space, marker on each side, and " │ " between them
+        let column = self.width.saturating_sub(2 * (LINE_NUMBER_WIDTH + 2) + 3) / 2;
+        let half = |cell: Option<(usize, &Segments, Side)>, pad: bool| match cell {
+            Some((number, segments, side)) => {
+                let marker = match side {
+                    Side::Context => ' ',
+                    Side::Removed => '-',
+                    Side::Added => '+',
+                };
+                let expanded: Segments = segments
+                    .iter()
+                    .map(|(text, changed)| (text.replace('\t', "    "), *changed))
+                    .collect();
+                format!(
+                    "{} {}",
+                    self.dim(&format!("{:>width$}", number, width = LINE_NUMBER_WIDTH)),
+                    self.paint(marker, &expanded, side, Some((column, pad)))
+                )
+            }
+            None if pad => " ".repeat(LINE_NUMBER_WIDTH + 2 + column),
+            None => String::new(),
+        };
+        out.push_str(&half(left, true));
+        out.push_str(&format!(" {} ", self.dim("│")));
+        out.push_str(half(right, false).trim_end());
+        out.push('\n');
+    }
+
+    /// A marker and text, colored for its side with changed words
+    /// emphasized; `fit` truncates to a width and optionally pads to it
+    fn paint(
+        &self,
+        marker: char,
+        segments: &Segments,
+        side: Side,
+        fit: Option<(usize, bool)>,
+    ) -> String {
+        let mut visible: Segments = Vec::new();
+        let mut used = 0;
+        let limit = fit.map(|(width, _)| width);
+        for (text, changed) in segments {
+            let count = text.chars().count();
+            match limit {
+                Some(limit) if used + count > limit => {
+                    let keep = limit.saturating_sub(used + 1);
+                    visible.push((text.chars().take(keep).collect::<String>() + "…", *changed));
+                    used = limit;
+                    break;
+                }
+                _ => {
+                    visible.push((text.clone(), *changed));
+                    used += count;
+                }
+            }
+        }
+        let padding = match fit {
+            Some((width, true)) => " ".repeat(width.saturating_sub(used)),
+            _ => String::new(),
+        };
+
+        let Some(ref palette) = self.palette else {
+            let text: String = visible.into_iter().map(|(text, _)| text).collect();
+            return format!("{}{}{}", marker, text, padding);
+        };
+        let (color, emph) = match side {
+            Side::Context => {
+                let text: String = visible.into_iter().map(|(text, _)| text).collect();
+                return format!("{}{}{}", marker, text, padding);
+            }
+            Side::Removed => (palette.removed, palette.removed_emph),
+            Side::Added => (palette.added, palette.added_emph),
+        };
+        let mut out = marker.to_string().color(color).to_string();
+        for (text, changed) in visible {
+            if changed {
+                out.push_str(&text.color(palette.emph_text).on_color(emph).to_string());
+            } else {
+                out.push_str(&text.color(color).to_string());
+            }
+        }
+        out + &padding
+    }
+}
+
+/// Lines (old, new) left in the current hunk; unbounded without counts
+fn hunk_left(hunk: Option<(usize, usize)>) -> (usize, usize) {
+    hunk.unwrap_or((usize::MAX, usize::MAX))
+}
+
+fn take(hunk: &mut Option<(usize, usize)>, side: Side) {
+    if let Some((old, new)) = hunk.as_mut() {
+        match side {
+            Side::Removed => *old = old.saturating_sub(1),
+            Side::Added => *new = new.saturating_sub(1),
+            Side::Context => {}
+        }
+    }
+}
+
+/// `@@ -12,3 +12,4 @@` as (old start, old count, new start, new count)
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize, usize)> {
+    let ranges = line.strip_prefix("@@ ")?.split(" @@").next()?;
+    let (old, new) = ranges.split_once(' ')?;
+    let range = |text: &str| -> Option<(usize, usize)> {
+        match text.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((text.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(old.strip_prefix('-')?)?;
+    let (new_start, new_count) = range(new.strip_prefix('+')?)?;
+    Some((old_start, old_count, new_start, new_count))
+}
+
+/// Words, runs of whitespace, and single punctuation characters
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut previous: Option<u8> = None;
+    for (index, c) in line.char_indices() {
+        let current = class(c);
+        if index > start && (previous != Some(current) || current == 2) {
+            tokens.push(&line[start..index]);
+            start = index;
+        }
+        previous = Some(current);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// Split a removed and an added line into changed and unchanged runs; None
+/// when the lines have too little in common to compare word by word
+fn word_diff(old: &str, new: &str) -> Option<(Segments, Segments)> {
+    let a = tokenize(old);
+    let b = tokenize(new);
+    if a.len() > MAX_WORD_TOKENS || b.len() > MAX_WORD_TOKENS {
+        return None;
+    }
+
+    // Longest common subsequence of tokens
+    let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let mut kept_a = vec![false; a.len()];
+    let mut kept_b = vec![false; b.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            kept_a[i] = true;
+            kept_b[j] = true;
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    let shared: usize = a
+        .iter()
+        .zip(&kept_a)
+        .filter(|(token, kept)| **kept && !token.trim().is_empty())
+        .map(|(token, _)| token.len())
+        .sum();
+    let total = old.trim().len() + new.trim().len();
+    if total > 0 && (2 * shared) as f64 / (total as f64) < MIN_SHARED {
+        return None;
+    }
+    Some((segments(&a, &kept_a), segments(&b, &kept_b)))
+}
+
+/// Merge tokens into runs; whitespace between two changes counts as changed
+fn segments(tokens: &[&str], kept: &[bool]) -> Segments {
+    let mut changed: Vec<bool> = kept.iter().map(|k| !k).collect();
+    for i in 1..tokens.len().saturating_sub(1) {
+        if !changed[i] && tokens[i].trim().is_empty() && changed[i - 1] && changed[i + 1] {
+            changed[i] = true;
+        }
+    }
+    let mut out: Segments = Vec::new();
+    for (token, changed) in tokens.iter().zip(changed) {
+        match out.last_mut() {
+            Some((text, last)) if *last == changed => text.push_str(token),
+            _ => out.push((token.to_string(), changed)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let total = add(1, 2);
++    let sum = add(1, 3);
+ }
+";
+
+    #[test]
+    fn test_render() {
+        let (old, new) =
+            word_diff("    let total = add(1, 2);", "    let sum = add(1, 3);").unwrap();
+        assert_eq!(
+            old,
+            vec![
+                ("    let ".to_string(), false),
+                ("total".to_string(), true),
+                (" = add(1, ".to_string(), false),
+                ("2".to_string(), true),
+                (");".to_string(), false),
+            ]
+        );
+        assert_eq!(new[1], ("sum".to_string(), true));
+        assert!(word_diff("alpha beta", "gamma delta").is_none());
+        assert_eq!(parse_hunk_header("@@ -7 +8,2 @@ fn x"), Some((7, 1, 8, 2)));
+
+        // Unified output is the patch itself once colors are off
+        let plain = DiffRenderer::with_width(DiffTheme::Plain, false, 80);
+        assert_eq!(plain.render(DIFF), DIFF);
+
+        let wide = DiffRenderer::with_width(DiffTheme::Plain, true, 80);
+        let out = wide.render(DIFF);
+        let rows: Vec<&str> = out.lines().collect();
+        assert_eq!(rows[1], "src/lib.rs");
+        assert!(rows
+            .contains(&"   2 -    let total = add(1, 2);       │    2 +    let sum = add(1, 3);"));
+        let narrow = DiffRenderer::with_width(DiffTheme::Plain, true, 50).render(DIFF);
+        assert!(narrow.contains("   2 -    let total = … │    2 +    let sum = ad…\n"));
+        assert!(!out.contains("index 1111111"));
+    }
+}
@@ -0,0 +1,256 @@
+//! Message catalog for user-facing text
+//!
+//! Messages live in YAML catalogs under `src/cli/locales/`, one per
+//! language, keyed by `<command>.<message>` and compiled into the binary.
+//! [`msg!`] looks a key up in the current locale, falling back to English,
+//! and fills in its `{name}` placeholders. The locale comes from
+//! `GITGRIP_LANG`, else `LC_ALL`, `LC_MESSAGES`, or `LANG`; a build can
+//! default to another language with `GITGRIP_DEFAULT_LANG` set at compile
+//! time. JSON output is never translated.
+//!
+//! Only the everyday commands (status, sync, diff, add, commit, push, fetch,
+//! checkout) go through the catalog so far; the rest still print English
+//! directly and move over one command at a time.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Catalogs compiled into the binary; `en` is the reference
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("locales/en.yaml")),
+    ("ru", include_str!("locales/ru.yaml")),
+];
+
+/// Locale used when the environment doesn't pick an available one
+const FALLBACK_LOCALE: &str = match option_env!("GITGRIP_DEFAULT_LANG") {
+    Some(lang) => lang,
+    None => "en",
+};
+
+/// Environment variables naming the locale, in priority order
+const LOCALE_VARS: &[&str] = &["GITGRIP_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+
+type Catalog = HashMap<String, String>;
+
+static CATALOG_MAP: Lazy<HashMap<&'static str, Catalog>> = Lazy::new(|| {
+    CATALOGS
+        .iter()
+        .map(|(lang, source)| (*lang, parse_catalog(source)))
+        .collect()
+});
+
+/// Unit tests see the build's default locale whatever the environment says
+static LOCALE: Lazy<&'static str> = Lazy::new(|| {
+    if cfg!(test) {
+        FALLBACK_LOCALE
+    } else {
+        pick_locale(|var| std::env::var(var).ok())
+    }
+});
+
+/// Look up a message with placeholders filled in
+///
+/// ```ignore
+/// Output::success(&msg!("add.staged", repo = repo.name, count = staged));
+/// ```
+macro_rules! msg {
+    ($key:literal) => {
+        $crate::cli::i18n::lookup($key, &[])
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::cli::i18n::lookup(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use msg;
+
+/// The current locale's message for `key`, with `{name}` placeholders
+/// replaced by `args`
+///
+/// Keys missing from the locale use English; keys missing from English are
+/// returned as is, so a typo shows up in the output rather than as a blank.
+pub fn lookup(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    render(*LOCALE, key, args)
+}
+
+fn render(locale: &str, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = [locale, "en"]
+        .iter()
+        .find_map(|lang| CATALOG_MAP.get(lang)?.get(key))
+        .map_or(key, String::as_str);
+    fill(template, args)
+}
+
+/// Replace `{name}` placeholders; unknown ones are left in place
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            let (_, value) = args.iter().find(|(arg, _)| *arg == name)?;
+            Some((value.to_string(), end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The first locale variable that is set decides; unavailable languages,
+/// and `C`/`POSIX`, fall back to the build's default
+fn pick_locale(var: impl Fn(&str) -> Option<String>) -> &'static str {
+    let Some(value) = LOCALE_VARS
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())
+    else {
+        return FALLBACK_LOCALE;
+    };
+    // `ru_RU.UTF-8` and `ru-RU` are both Russian
+    let lang = value
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    CATALOGS
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| *name == lang)
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+/// Flatten nested YAML sections into dotted keys
+fn parse_catalog(source: &str) -> Catalog {
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(source).expect("message catalogs are valid YAML");
+    let mut catalog = Catalog::new();
+    flatten("", &value, &mut catalog);
+    catalog
+}
+
+fn flatten(prefix: &str, value: &serde_yaml::Value, catalog: &mut Catalog) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map {
+                let key = key.as_str().unwrap_or_default();
+                let path = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, value, catalog);
+            }
+        }
+        serde_yaml::Value::String(text) => {
+            catalog.insert(prefix.to_string(), text.clone());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(template: &str) -> BTreeSet<&str> {
+        template
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogs() {
+        let en = &CATALOG_MAP["en"];
+        for (lang, _) in CATALOGS {
+            for (key, text) in &CATALOG_MAP[lang] {
+                let english = en
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{}: '{}' isn't in the English catalog", lang, key));
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(english),
+                    "{}: placeholders of '{}'",
+                    lang,
+                    key
+                );
+            }
+        }
+
+        // Every key used in the source has an English message
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut pending = vec![src];
+        while let Some(dir) = pending.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                if path.extension() != Some("rs".as_ref()) {
+                    continue;
+                }
+                let source = std::fs::read_to_string(&path).unwrap();
+                for part in source.split("msg!(\"").skip(1) {
+                    let key = part.split('"').next().unwrap();
+                    assert!(
+                        en.contains_key(key),
+                        "{}: no English message for '{}'",
+                        path.display(),
+                        key
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(
+            render("en", "add.staged", &[("repo", &"api"), ("count", &3)]),
+            "api: staged 3 file(s)"
+        );
+        assert_eq!(
+            render("ru", "add.staged", &[("repo", &"api"), ("count", &3)]),
+            "api: проиндексировано файлов: 3"
+        );
+        assert_eq!(render("fr", "diff.none", &[]), "No changes.");
+        assert_eq!(render("en", "no.such.key", &[]), "no.such.key");
+        assert_eq!(fill("{a} {b} {", &[("a", &1)]), "1 {b} {");
+
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(pick_locale(env(&[("LANG", "ru_RU.UTF-8")])), "ru");
+        assert_eq!(
+            pick_locale(env(&[("LC_ALL", "C"), ("LANG", "ru_RU.UTF-8")])),
+            "en"
+        );
+        assert_eq!(
+            pick_locale(env(&[("GITGRIP_LANG", "ru"), ("LC_ALL", "en_US")])),
+            "ru"
+        );
+        assert_eq!(pick_locale(env(&[("LANG", "")])), "en");
+    }
+}
@@ -3,6 +3,9 @@
 //! Command-line interface using clap.
 
 pub mod commands;
+pub mod diff_render;
+pub mod i18n;
 pub mod output;
+pub mod pager;
 
 pub use output::Output;
@@ -0,0 +1,214 @@
+//! Pager for long output
+//!
+//! On an interactive terminal, [`start`] runs a pager (`settings.pager.command`,
+//! `$PAGER`, else `less -FRX`) and points the process's stdout at it until
+//! the returned guard drops, so everything printed, including by child
+//! processes, scrolls in one pager. Colors and the terminal width are read
+//! before stdout stops being a terminal, so output looks the same paged.
+//!
+//! Output reaches the pager through a relay thread. If the pager is quit
+//! early, the relay discards the rest instead of letting writes fail, so a
+//! paged command that changes repos (`gr forall`) still runs to completion.
+
+use crate::core::manifest::PagerSettings;
+use once_cell::sync::OnceCell;
+use std::io::IsTerminal;
+
+/// Used when neither the manifest nor `$PAGER` names a pager
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Terminal width from before stdout was handed to the pager
+static PAGED_WIDTH: OnceCell<usize> = OnceCell::new();
+
+/// A running pager; stdout is restored and the pager waited for on drop
+pub struct Pager {
+    #[cfg(unix)]
+    child: std::process::Child,
+    /// Duplicate of the original stdout
+    #[cfg(unix)]
+    saved_stdout: std::os::unix::io::RawFd,
+    /// Copies stdout to the pager until stdout is restored
+    #[cfg(unix)]
+    relay: Option<std::thread::JoinHandle<()>>,
+}
+
+/// Page stdout if it is a terminal and paging isn't turned off
+///
+/// A pager that fails to start is reported and output goes straight to the
+/// terminal. Paging is only supported on Unix.
+pub fn start(settings: &PagerSettings, no_pager: bool) -> Option<Pager> {
+    if no_pager || settings.enabled == Some(false) || !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let env_pager = std::env::var("PAGER").ok();
+    let command = pager_command(settings.command.as_deref(), env_pager.as_deref())?;
+    match Pager::spawn(&command) {
+        Ok(pager) => Some(pager),
+        Err(e) => {
+            eprintln!("warning: couldn't start pager '{}': {}", command, e);
+            None
+        }
+    }
+}
+
+/// Width of the terminal output ends up on, if it is one
+pub fn terminal_width() -> Option<usize> {
+    PAGED_WIDTH.get().copied().or_else(|| {
+        console::Term::stdout()
+            .size_checked()
+            .map(|(_, columns)| columns as usize)
+    })
+}
+
+/// The pager to run; `None` when it is empty or `cat`
+fn pager_command(configured: Option<&str>, env_pager: Option<&str>) -> Option<String> {
+    let command = configured.or(env_pager).unwrap_or(DEFAULT_PAGER).trim();
+    if command.is_empty() || command == "cat" {
+        return None;
+    }
+    Some(command.to_string())
+}
+
+impl Pager {
+    #[cfg(unix)]
+    fn spawn(command: &str) -> std::io::Result<Self> {
+        use std::io::Write;
+        use std::os::unix::io::FromRawFd;
+        use std::process::{Command, Stdio};
+
+        let width = terminal_width();
+        let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command).stdin(Stdio::piped());
+        // A bare `less` from $PAGER still needs -R to show colors
+        if std::env::var_os("LESS").is_none() {
+            cmd.env("LESS", "FRX");
+        }
+        let mut child = cmd.spawn()?;
+        let stdin = child.stdin.take().expect("pager stdin is piped");
+
+        std::io::stdout().flush()?;
+        let mut fds = [0; 2];
+        let saved_stdout = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        let failed = saved_stdout < 0
+            || unsafe { libc::pipe(fds.as_mut_ptr()) } < 0
+            || unsafe { libc::fcntl(fds[0], libc::F_SETFD, libc::FD_CLOEXEC) } < 0
+            || unsafe { libc::dup2(fds[1], libc::STDOUT_FILENO) } < 0;
+        if failed {
+            let error = std::io::Error::last_os_error();
+            unsafe {
+                for fd in [saved_stdout, fds[0], fds[1]] {
+                    if fd > 0 {
+                        libc::close(fd);
+                    }
+                }
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(error);
+        }
+        // Stdout now holds the pipe's only write end; the relay sees EOF once it's restored
+        unsafe { libc::close(fds[1]) };
+        let output = unsafe { std::fs::File::from_raw_fd(fds[0]) };
+        let relay = std::thread::spawn(move || relay(output, stdin));
+
+        colored::control::set_override(colorize);
+        if let Some(width) = width {
+            let _ = PAGED_WIDTH.set(width);
+        }
+
+        Ok(Self {
+            child,
+            saved_stdout,
+            relay: Some(relay),
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn spawn(_command: &str) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "paging is only supported on Unix",
+        ))
+    }
+}
+
+/// Copy `output` into `pager` until EOF, discarding whatever arrives after
+/// the pager has gone away
+fn relay(mut output: impl std::io::Read, mut pager: impl std::io::Write) {
+    let mut buffer = [0; 8192];
+    let mut open = true;
+    loop {
+        match output.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => {
+                open = open
+                    && pager
+                        .write_all(&buffer[..n])
+                        .and_then(|_| pager.flush())
+                        .is_ok();
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Pager {
+    fn drop(&mut self) {
+        use std::io::Write;
+
+        let _ = std::io::stdout().flush();
+        unsafe {
+            libc::dup2(self.saved_stdout, libc::STDOUT_FILENO);
+            libc::close(self.saved_stdout);
+        }
+        if let Some(relay) = self.relay.take() {
+            let _ = relay.join();
+        }
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None, None).as_deref(), Some("less -FRX"));
+        assert_eq!(pager_command(None, Some("most")).as_deref(), Some("most"));
+        assert_eq!(
+            pager_command(Some("delta --paging=always"), Some("most")).as_deref(),
+            Some("delta --paging=always")
+        );
+        assert_eq!(pager_command(None, Some("cat")), None);
+        assert_eq!(pager_command(Some(""), Some("most")), None);
+    }
+
+    /// A pager that stops reading early
+    struct QuitAfter(usize);
+
+    impl std::io::Write for QuitAfter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.0 == 0 {
+                return Err(std::io::ErrorKind::BrokenPipe.into());
+            }
+            self.0 -= 1;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_relay_drains_output_after_pager_quits() {
+        let mut output = std::io::Cursor::new(vec![b'x'; 100_000]);
+        relay(&mut output, QuitAfter(1));
+        assert_eq!(output.position(), 100_000);
+    }
+}
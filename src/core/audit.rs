@@ -0,0 +1,311 @@
+//! Audit log of mutating operations
+//!
+//! Pushes, resets, branch deletions, and PR creation and merges are appended to
+//! `.gitgrip/audit.log`, one JSON object per line, recording who did what to
+//! which ref and the command line that did it. `gr audit show` reads it back,
+//! and `gr undo` uses it to reverse the most recent operation.
+
+use crate::util::redact::redact;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Kind of mutating operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditAction {
+    Push,
+    ForcePush,
+    Reset,
+    BranchDelete,
+    PrCreate,
+    PrMerge,
+    /// `gr undo` reversing an earlier invocation
+    Undo,
+}
+
+/// Identifies this process, so entries from one command can be grouped
+static INVOCATION: Lazy<String> =
+    Lazy::new(|| format!("{}-{}", Utc::now().timestamp_millis(), std::process::id()));
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuditAction::Push => "push",
+            AuditAction::ForcePush => "force-push",
+            AuditAction::Reset => "reset",
+            AuditAction::BranchDelete => "branch-delete",
+            AuditAction::PrCreate => "pr-create",
+            AuditAction::PrMerge => "pr-merge",
+            AuditAction::Undo => "undo",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// `Name <email>` from git config, or the login name
+    pub user: String,
+    pub action: AuditAction,
+    pub repo: String,
+    /// Branch or ref acted on
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub ref_name: Option<String>,
+    /// Commit the ref pointed to before the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Commit the ref points to after the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// PR number, for PR operations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr: Option<u64>,
+    /// Reset mode (`soft`, `mixed`, `hard`), for resets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Command line that made the change
+    pub command: String,
+    /// Shared by every entry written by one run of `gr`
+    #[serde(default)]
+    pub invocation: String,
+    /// For undo entries, the invocation that was reversed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<String>,
+}
+
+impl AuditEntry {
+    /// An entry for `action` on `repo`, stamped with the current time, user, and command line
+    pub fn new(action: AuditAction, repo: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            user: current_user(),
+            action,
+            repo: repo.to_string(),
+            ref_name: None,
+            before: None,
+            after: None,
+            pr: None,
+            mode: None,
+            command: command_line(),
+            invocation: INVOCATION.clone(),
+            undoes: None,
+        }
+    }
+
+    /// Set the ref and the commits it moved between
+    pub fn with_ref(
+        mut self,
+        ref_name: &str,
+        before: Option<String>,
+        after: Option<String>,
+    ) -> Self {
+        self.ref_name = Some(ref_name.to_string());
+        self.before = before;
+        self.after = after;
+        self
+    }
+
+    /// Set the PR number
+    pub fn with_pr(mut self, number: u64) -> Self {
+        self.pr = Some(number);
+        self
+    }
+}
+
+/// This process's command line, with credentials masked and the program
+/// reduced to its name
+pub(crate) fn command_line() -> String {
+    let mut args: Vec<String> = std::env::args().collect();
+    if let Some(program) = args.first_mut() {
+        if let Some(name) = Path::new(program.as_str()).file_name() {
+            *program = name.to_string_lossy().to_string();
+        }
+    }
+    redact(&args.join(" ")).into_owned()
+}
+
+/// Entries of the most recent invocation that hasn't been undone yet
+///
+/// Undo entries themselves are never returned, so repeated `gr undo` walks
+/// back through history.
+pub fn last_undoable(entries: &[AuditEntry]) -> Vec<&AuditEntry> {
+    let undone: HashSet<&str> = entries.iter().filter_map(|e| e.undoes.as_deref()).collect();
+    let latest = entries
+        .iter()
+        .rev()
+        .find(|e| e.action != AuditAction::Undo && !undone.contains(e.invocation.as_str()));
+    match latest {
+        Some(latest) => entries
+            .iter()
+            .filter(|e| e.invocation == latest.invocation && e.action != AuditAction::Undo)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Path of the audit log in a workspace
+pub fn audit_log_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".gitgrip").join("audit.log")
+}
+
+/// Append `entry` to the workspace's audit log
+pub fn record(workspace_root: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    let path = audit_log_path(workspace_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Read the audit log, oldest first, skipping lines that don't parse
+pub fn read_log(workspace_root: &Path) -> std::io::Result<Vec<AuditEntry>> {
+    let path = audit_log_path(workspace_root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Parse a `--since` value: an age like `30m`, `12h`, `7d`, `2w`, or a date
+/// (`2024-05-01`) or RFC 3339 timestamp
+pub fn parse_since(since: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let since = since.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(since) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let split = since
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(since.len());
+    let (count, unit) = since.split_at(split);
+    let count: i64 = count.parse().map_err(|_| {
+        format!(
+            "Invalid --since '{}' (use e.g. 12h, 7d, or 2024-05-01)",
+            since
+        )
+    })?;
+    let age = match unit {
+        "m" => Duration::minutes(count),
+        "h" => Duration::hours(count),
+        "d" => Duration::days(count),
+        "w" => Duration::weeks(count),
+        _ => {
+            return Err(format!(
+                "Invalid --since unit '{}' (expected m, h, d, or w)",
+                unit
+            ))
+        }
+    };
+    Ok(now - age)
+}
+
+fn current_user() -> String {
+    let config = git2::Config::open_default().ok();
+    let get = |key: &str| config.as_ref().and_then(|c| c.get_string(key).ok());
+    match (get("user.name"), get("user.email")) {
+        (Some(name), Some(email)) => format!("{} <{}>", name, email),
+        (Some(name), None) => name,
+        (None, Some(email)) => email,
+        (None, None) => std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_read() {
+        let temp = TempDir::new().unwrap();
+        let push = AuditEntry::new(AuditAction::ForcePush, "app").with_ref(
+            "feat/x",
+            Some("abc".to_string()),
+            Some("def".to_string()),
+        );
+        let merge = AuditEntry::new(AuditAction::PrMerge, "lib")
+            .with_ref("feat/x", None, None)
+            .with_pr(7);
+        record(temp.path(), &push).unwrap();
+        record(temp.path(), &merge).unwrap();
+
+        // A torn or foreign line doesn't hide the rest
+        let path = audit_log_path(temp.path());
+        let mut content = std::fs::read_to_string(&path).unwrap();
+        content.push_str("{\"truncated\n");
+        std::fs::write(&path, content).unwrap();
+
+        assert_eq!(read_log(temp.path()).unwrap(), vec![push, merge]);
+        let line = std::fs::read_to_string(&path).unwrap();
+        assert!(line.contains("\"action\":\"force-push\""));
+        assert!(line.contains("\"ref\":\"feat/x\""));
+    }
+
+    #[test]
+    fn test_last_undoable() {
+        let entry = |action, repo: &str, invocation: &str| AuditEntry {
+            invocation: invocation.to_string(),
+            ..AuditEntry::new(action, repo)
+        };
+        let mut entries = vec![
+            entry(AuditAction::Push, "app", "1"),
+            entry(AuditAction::BranchDelete, "app", "2"),
+            entry(AuditAction::BranchDelete, "lib", "2"),
+        ];
+        let repos = |group: Vec<&AuditEntry>| -> Vec<String> {
+            group.iter().map(|e| e.repo.clone()).collect()
+        };
+        assert_eq!(repos(last_undoable(&entries)), vec!["app", "lib"]);
+
+        entries.push(AuditEntry {
+            undoes: Some("2".to_string()),
+            ..entry(AuditAction::Undo, "app", "3")
+        });
+        let group = last_undoable(&entries);
+        assert_eq!(group.len(), 1);
+        assert_eq!(group[0].action, AuditAction::Push);
+
+        entries.push(AuditEntry {
+            undoes: Some("1".to_string()),
+            ..entry(AuditAction::Undo, "app", "4")
+        });
+        assert!(last_undoable(&entries).is_empty());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = DateTime::parse_from_rfc3339("2024-05-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_since("2h", now).unwrap(), now - Duration::hours(2));
+        assert_eq!(parse_since("7d", now).unwrap(), now - Duration::days(7));
+        assert_eq!(
+            parse_since("2024-05-01", now).unwrap().to_rfc3339(),
+            "2024-05-01T00:00:00+00:00"
+        );
+        assert!(parse_since("3y", now).is_err());
+        assert!(parse_since("soon", now).is_err());
+    }
+}
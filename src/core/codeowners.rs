@@ -0,0 +1,197 @@
+//! CODEOWNERS parsing
+//!
+//! Supports the GitHub/GitLab file locations and gitignore-style patterns.
+//! As on GitHub, the last matching rule determines a path's owners.
+
+use crate::util::glob::{GlobTail, PathGlob};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Locations searched for a CODEOWNERS file, in priority order
+const CODEOWNERS_PATHS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+#[derive(Debug, Clone)]
+struct Rule {
+    glob: PathGlob,
+    owners: Vec<String>,
+}
+
+/// Parsed CODEOWNERS rules for one repository
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Load the repo's CODEOWNERS file, if it has one
+    pub fn load(repo_path: &Path) -> Option<Self> {
+        CODEOWNERS_PATHS
+            .iter()
+            .find_map(|p| std::fs::read_to_string(repo_path.join(p)).ok())
+            .map(|content| Self::parse(&content))
+    }
+
+    /// Parse CODEOWNERS content; malformed lines are ignored
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(|line| line.split(" #").next().unwrap_or("").trim())
+            // Skip comments and GitLab section headers
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter(|line| !line.starts_with('[') && !line.starts_with("^["))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let glob = pattern_to_glob(pattern)?;
+                Some(Rule {
+                    glob,
+                    owners: parts.map(|o| o.to_string()).collect(),
+                })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Owners of a repo-relative path (empty if unowned)
+    pub fn owners_for(&self, path: &str) -> &[String] {
+        let path = path.trim_start_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.glob.matches(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Group paths by their owner set
+    pub fn group_by_owners<'a>(
+        &self,
+        paths: &'a [String],
+    ) -> BTreeMap<Vec<String>, Vec<&'a String>> {
+        let mut groups: BTreeMap<Vec<String>, Vec<&String>> = BTreeMap::new();
+        for path in paths {
+            groups
+                .entry(self.owners_for(path).to_vec())
+                .or_default()
+                .push(path);
+        }
+        groups
+    }
+}
+
+/// Split owners into reviewer logins and team slugs (`@org/team` -> `team`)
+///
+/// Email owners can't be requested as reviewers and are dropped.
+pub fn split_reviewers<'a>(
+    owners: impl IntoIterator<Item = &'a String>,
+) -> (Vec<String>, Vec<String>) {
+    let mut users = Vec::new();
+    let mut teams = Vec::new();
+    for owner in owners {
+        let Some(handle) = owner.strip_prefix('@') else {
+            continue;
+        };
+        let (list, name) = match handle.split_once('/') {
+            Some((_, team)) => (&mut teams, team),
+            None => (&mut users, handle),
+        };
+        if !list.iter().any(|existing: &String| existing == name) {
+            list.push(name.to_string());
+        }
+    }
+    (users, teams)
+}
+
+/// Translate a CODEOWNERS pattern into a glob over repo-relative paths
+fn pattern_to_glob(pattern: &str) -> Option<PathGlob> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // A slash anywhere but the end anchors the pattern to the repo root
+    let anchored = trimmed.contains('/');
+    let body = trimmed.trim_start_matches('/');
+    if body.is_empty() {
+        return None;
+    }
+
+    // `dir/` owns everything beneath it; `dir/*` only its direct children
+    let tail = if dir_only {
+        GlobTail::Beneath
+    } else if body.ends_with("/*") {
+        GlobTail::Exact
+    } else {
+        GlobTail::OrBeneath
+    };
+    PathGlob::with_options(body, anchored, tail).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Default owners
+*               @acme/core
+
+*.md            @docs-writer   # docs anywhere
+/services/api/  @acme/api-team @alice
+docs/*          docs@example.com
+**/migrations   @dba
+
+[Frontend]
+/web/           @acme/web
+"#;
+
+    fn owners(codeowners: &CodeOwners, path: &str) -> Vec<String> {
+        codeowners.owners_for(path).to_vec()
+    }
+
+    #[test]
+    fn test_last_match_wins() {
+        let co = CodeOwners::parse(SAMPLE);
+        assert_eq!(owners(&co, "src/main.rs"), vec!["@acme/core"]);
+        assert_eq!(
+            owners(&co, "services/api/README.md"),
+            vec!["@acme/api-team", "@alice"]
+        );
+        assert_eq!(
+            owners(&co, "services/worker/README.md"),
+            vec!["@docs-writer"]
+        );
+        assert_eq!(owners(&co, "web/app.ts"), vec!["@acme/web"]);
+    }
+
+    #[test]
+    fn test_pattern_semantics() {
+        let co = CodeOwners::parse(SAMPLE);
+        // docs/* matches direct children only
+        assert_eq!(owners(&co, "docs/guide.txt"), vec!["docs@example.com"]);
+        assert_eq!(owners(&co, "docs/api/guide.txt"), vec!["@acme/core"]);
+        // ** matches at any depth, including everything below the directory
+        assert_eq!(owners(&co, "db/migrations/001.sql"), vec!["@dba"]);
+        // /services/api/ doesn't match a similarly named directory elsewhere
+        assert_eq!(owners(&co, "old/services/api/x.rs"), vec!["@acme/core"]);
+    }
+
+    #[test]
+    fn test_unowned_path() {
+        let co = CodeOwners::parse("/docs/ @docs-writer\n");
+        assert!(co.owners_for("src/lib.rs").is_empty());
+    }
+
+    #[test]
+    fn test_split_reviewers() {
+        let owners: Vec<String> = ["@acme/api-team", "@alice", "bob@example.com", "@alice"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (users, teams) = split_reviewers(&owners);
+        assert_eq!(users, vec!["alice"]);
+        assert_eq!(teams, vec!["api-team"]);
+    }
+}
@@ -0,0 +1,282 @@
+//! Per-repo advisory locks
+//!
+//! Mutating commands hold `.gitgrip/locks/<repo>.lock` for every repo they may
+//! touch, so two `gr` processes can't rewrite the same index or refs at once.
+//! Each lock file records the process holding it. A lock left by a process
+//! that has exited is taken over; `gr locks clear` removes any that remain.
+
+use crate::core::audit::command_line;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use thiserror::Error;
+
+/// How often a waiting process re-checks a lock
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long an unreadable lock file may exist before it's treated as abandoned
+/// (it's normally only empty while its owner is writing it)
+const UNREADABLE_GRACE: Duration = Duration::from_secs(5);
+
+/// Errors that can occur when taking a lock
+#[derive(Error, Debug)]
+pub enum LockError {
+    #[error(
+        "Repo '{}' is locked by `{}` (pid {}, since {}). If that process is gone, run 'gr locks clear'",
+        .0.repo,
+        .0.command,
+        .0.pid,
+        .0.acquired.format("%Y-%m-%d %H:%M:%S UTC")
+    )]
+    Held(LockInfo),
+
+    #[error("Failed to take lock: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Contents of a lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub repo: String,
+    pub pid: u32,
+    /// Command line of the holding process
+    pub command: String,
+    pub acquired: DateTime<Utc>,
+}
+
+impl LockInfo {
+    fn current(repo: &str) -> Self {
+        Self {
+            repo: repo.to_string(),
+            pid: std::process::id(),
+            command: command_line(),
+            acquired: Utc::now(),
+        }
+    }
+
+    /// Whether the holding process has exited
+    pub fn is_stale(&self) -> bool {
+        !process_alive(self.pid)
+    }
+}
+
+/// A held lock, released when dropped
+#[derive(Debug)]
+pub struct RepoLock {
+    path: PathBuf,
+}
+
+impl Drop for RepoLock {
+    fn drop(&mut self) {
+        // Leave it alone if it was cleared and taken by someone else meanwhile
+        if read_lock(&self.path).is_some_and(|info| info.pid == std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Directory holding a workspace's lock files
+pub fn locks_dir(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".gitgrip").join("locks")
+}
+
+fn lock_path(workspace_root: &Path, repo: &str) -> PathBuf {
+    locks_dir(workspace_root).join(format!("{}.lock", repo.replace(['/', '\\'], "--")))
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Take the lock for `repo`, waiting up to `timeout` for its holder to finish
+///
+/// `on_wait` is called once, with the holder, if the lock is busy.
+pub fn acquire(
+    workspace_root: &Path,
+    repo: &str,
+    timeout: Duration,
+    mut on_wait: impl FnMut(&LockInfo),
+) -> Result<RepoLock, LockError> {
+    let path = lock_path(workspace_root, repo);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut waited = false;
+    loop {
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                let info = serde_json::to_string(&LockInfo::current(repo))
+                    .map_err(std::io::Error::from)?;
+                file.write_all(info.as_bytes())?;
+                return Ok(RepoLock { path });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let holder = match read_lock(&path) {
+            Some(holder) if holder.is_stale() => {
+                // Re-check right before removing, to narrow the window in
+                // which another process takes it over first
+                if read_lock(&path).as_ref() == Some(&holder) {
+                    let _ = std::fs::remove_file(&path);
+                }
+                continue;
+            }
+            Some(holder) => holder,
+            None => {
+                let abandoned = std::fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                    .is_some_and(|age| age > UNREADABLE_GRACE);
+                if abandoned {
+                    let _ = std::fs::remove_file(&path);
+                    continue;
+                }
+                LockInfo {
+                    repo: repo.to_string(),
+                    pid: 0,
+                    command: "unknown".to_string(),
+                    acquired: Utc::now(),
+                }
+            }
+        };
+
+        if Instant::now() >= deadline {
+            return Err(LockError::Held(holder));
+        }
+        if !waited {
+            on_wait(&holder);
+            waited = true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Take the locks for all of `repos`, in name order so that two processes
+/// locking overlapping sets can't deadlock
+pub fn acquire_all<'a>(
+    workspace_root: &Path,
+    repos: impl IntoIterator<Item = &'a str>,
+    timeout: Duration,
+    mut on_wait: impl FnMut(&LockInfo),
+) -> Result<Vec<RepoLock>, LockError> {
+    let mut repos: Vec<&str> = repos.into_iter().collect();
+    repos.sort_unstable();
+    repos.dedup();
+
+    let deadline = Instant::now() + timeout;
+    let mut locks = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        locks.push(acquire(workspace_root, repo, remaining, &mut on_wait)?);
+    }
+    Ok(locks)
+}
+
+/// All lock files in the workspace, with their holders
+pub fn list_locks(workspace_root: &Path) -> std::io::Result<Vec<(PathBuf, Option<LockInfo>)>> {
+    let dir = locks_dir(workspace_root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut locks: Vec<(PathBuf, Option<LockInfo>)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lock"))
+        .map(|path| {
+            let info = read_lock(&path);
+            (path, info)
+        })
+        .collect();
+    locks.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(locks)
+}
+
+fn process_alive(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Path::new(&format!("/proc/{}", pid)).exists()
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(true)
+    }
+    #[cfg(not(unix))]
+    {
+        // No cheap check; leave it to `gr locks clear --force`
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_acquire_release_and_wait() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        let lock = acquire(root, "app", Duration::ZERO, |_| {}).unwrap();
+        let locks = list_locks(root).unwrap();
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].1.as_ref().unwrap().pid, std::process::id());
+
+        // Held: times out, after reporting the holder once
+        let mut waits = 0;
+        let err = acquire(root, "app", Duration::from_millis(250), |holder| {
+            assert_eq!(holder.repo, "app");
+            waits += 1;
+        })
+        .unwrap_err();
+        assert!(matches!(err, LockError::Held(_)));
+        assert!(err.to_string().contains("gr locks clear"));
+        assert_eq!(waits, 1);
+
+        drop(lock);
+        assert!(list_locks(root).unwrap().is_empty());
+
+        let locks = acquire_all(root, ["lib", "app", "lib"], Duration::ZERO, |_| {}).unwrap();
+        assert_eq!(locks.len(), 2);
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let stale = LockInfo {
+            pid: u32::MAX,
+            ..LockInfo::current("app")
+        };
+        std::fs::create_dir_all(locks_dir(root)).unwrap();
+        std::fs::write(
+            lock_path(root, "app"),
+            serde_json::to_string(&stale).unwrap(),
+        )
+        .unwrap();
+
+        assert!(stale.is_stale());
+        let _lock = acquire(root, "app", Duration::ZERO, |_| {}).unwrap();
+        let (_, info) = &list_locks(root).unwrap()[0];
+        assert_eq!(info.as_ref().unwrap().pid, std::process::id());
+    }
+}
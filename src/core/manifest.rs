@@ -2,8 +2,13 @@
 //!
 //! The manifest file (manifest.yaml) defines the multi-repo workspace configuration.
 
+use crate::core::template::{TemplateContext, TemplateError};
+use crate::platform::types::MergeMethod;
+use crate::util::disk::parse_bytes;
+use crate::util::glob::PathGlob;
+use crate::util::process::parse_duration;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use thiserror::Error;
 
@@ -21,6 +26,12 @@ pub enum ManifestError {
 
     #[error("Path escapes workspace boundary: {0}")]
     PathTraversal(String),
+
+    #[error("Invalid template in {field}: {source}")]
+    Template {
+        field: String,
+        source: TemplateError,
+    },
 }
 
 /// Hosting platform type
@@ -93,6 +104,23 @@ pub struct RepoConfig {
     /// Optional platform override
     #[serde(skip_serializing_if = "Option::is_none")]
     pub platform: Option<PlatformConfig>,
+    /// Directories to check out (cone-mode sparse checkout); whole repo if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse: Option<Vec<String>>,
+    /// Names of other repos in the manifest that this repo depends on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+    /// Branch, tag, or commit that sync checks out instead of the default
+    /// branch (e.g. a release branch); tags and commits are checked out detached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// Environment variables for this repo, layered over `workspace.env`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+    /// This entry is a nested gitgrip workspace: `url` is its manifest repo,
+    /// cloned into `<path>/.gitgrip/manifests`, and sync recurses into it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub workspace: bool,
 }
 
 fn default_branch() -> String {
@@ -129,6 +157,19 @@ pub enum MergeStrategy {
     Independent,
 }
 
+/// How `gr pr create` fills in PR descriptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrBodyStyle {
+    /// The branch's commits, with their bodies, plus a size table
+    #[default]
+    Commits,
+    /// The repo's pull request template, plus a size table
+    Template,
+    /// No description
+    Blank,
+}
+
 /// Global manifest settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManifestSettings {
@@ -138,17 +179,475 @@ pub struct ManifestSettings {
     /// Merge strategy for linked PRs
     #[serde(default)]
     pub merge_strategy: MergeStrategy,
+    /// Maximum hosting platform API requests in flight at once
+    #[serde(default = "default_api_concurrency")]
+    pub api_concurrency: usize,
+    /// Lines changed (added + deleted) above which a PR is flagged as oversized; 0 disables
+    #[serde(default = "default_pr_size_limit")]
+    pub pr_size_limit: usize,
+    /// How `gr pr create` fills in PR descriptions
+    #[serde(default)]
+    pub pr_body_style: PrBodyStyle,
+    /// Where to report completion of long-running operations
+    #[serde(default, skip_serializing_if = "NotificationSettings::is_empty")]
+    pub notifications: NotificationSettings,
+    /// Branches (or globs such as `release/*`) local commands won't commit to,
+    /// force push, or delete without `--allow-protected`, in addition to each
+    /// repo's default branch, which is always protected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_branches: Vec<String>,
+    /// Run `gr optimize` on each repo after `gr sync` clones or pulls it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_optimize: bool,
+    /// Defaults for `gr status` flags
+    #[serde(default, skip_serializing_if = "StatusDefaults::is_empty")]
+    pub status: StatusDefaults,
+    /// Defaults for `gr sync` flags
+    #[serde(default, skip_serializing_if = "SyncDefaults::is_empty")]
+    pub sync: SyncDefaults,
+    /// Defaults for `gr forall` flags
+    #[serde(default, skip_serializing_if = "ForallDefaults::is_empty")]
+    pub forall: ForallDefaults,
+    /// Defaults for `gr pr` flags
+    #[serde(default, skip_serializing_if = "PrDefaults::is_empty")]
+    pub pr: PrDefaults,
+    /// What `gr pr ready-check` requires of each PR
+    #[serde(default, skip_serializing_if = "ReadyPolicy::is_default")]
+    pub ready_check: ReadyPolicy,
+    /// Scanning of outgoing commits for credentials before pushing
+    #[serde(default, skip_serializing_if = "SecretScanSettings::is_default")]
+    pub secret_scan: SecretScanSettings,
+    /// Size limit for files in outgoing commits
+    #[serde(default, skip_serializing_if = "LargeFileSettings::is_empty")]
+    pub large_files: LargeFileSettings,
+    /// Ticket-based branch names and PR links
+    #[serde(default, skip_serializing_if = "TicketSettings::is_empty")]
+    pub tickets: TicketSettings,
+    /// How `gr diff` and `gr pr diff` display diffs
+    #[serde(default, skip_serializing_if = "DiffSettings::is_default")]
+    pub diff: DiffSettings,
+    /// Pager for long output on interactive terminals
+    #[serde(default, skip_serializing_if = "PagerSettings::is_empty")]
+    pub pager: PagerSettings,
+    /// What `gr branch audit` flags
+    #[serde(default, skip_serializing_if = "BranchAuditSettings::is_empty")]
+    pub branch_audit: BranchAuditSettings,
+}
+
+impl ManifestSettings {
+    /// Whether `branch` is protected in a repo whose default branch is `default_branch`
+    ///
+    /// The default branch is always protected; `protected_branches` adds more.
+    pub fn is_protected(&self, branch: &str, default_branch: &str) -> bool {
+        if branch == default_branch {
+            return true;
+        }
+        // Anchor under a dummy segment so a bare name only matches itself
+        self.protected_branches.iter().any(|pattern| {
+            pattern == branch
+                || PathGlob::new(&format!("refs/{}", pattern))
+                    .is_ok_and(|glob| glob.matches(&format!("refs/{}", branch)))
+        })
+    }
+
+    /// Refuse to `action` any protected branch, unless `allow`
+    ///
+    /// `targets` are `(repo, branch, default_branch)`; every protected one is
+    /// named in the error.
+    pub fn ensure_unprotected<'a>(
+        &self,
+        action: &str,
+        targets: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+        allow: bool,
+    ) -> anyhow::Result<()> {
+        if allow {
+            return Ok(());
+        }
+        let hits: Vec<String> = targets
+            .into_iter()
+            .filter(|(_, branch, default_branch)| self.is_protected(branch, default_branch))
+            .map(|(repo, branch, _)| format!("{} ({})", repo, branch))
+            .collect();
+        if !hits.is_empty() {
+            anyhow::bail!(
+                "Refusing to {} protected branch: {}; pass --allow-protected to override",
+                action,
+                hits.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Completion notifications for sync and campaign runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Show a desktop notification (needs the `notifications` feature)
+    #[serde(default)]
+    pub desktop: bool,
+    /// URL to POST a JSON summary to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    /// Slack incoming webhook URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack: Option<String>,
+}
+
+impl NotificationSettings {
+    fn is_empty(&self) -> bool {
+        !self.desktop && self.webhook.is_none() && self.slack.is_none()
+    }
+}
+
+/// Default flags for `gr status`; flags on the command line win
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusDefaults {
+    /// `--verbose` / `--no-verbose`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verbose: Option<bool>,
+}
+
+impl StatusDefaults {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Default flags for `gr sync`; flags on the command line win
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncDefaults {
+    /// `--clone-jobs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clone_jobs: Option<usize>,
+    /// `--prune` / `--no-prune`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prune: Option<bool>,
+}
+
+impl SyncDefaults {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Default flags for `gr forall`; flags on the command line win
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForallDefaults {
+    /// `--parallel` / `--no-parallel`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel: Option<bool>,
+    /// `--timeout` (e.g. `10m`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
+impl ForallDefaults {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Default flags for `gr pr create` and `gr pr merge`; flags on the command
+/// line win
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrDefaults {
+    /// `gr pr create --draft` / `--no-draft`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    /// `gr pr create --push` / `--no-push`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push: Option<bool>,
+    /// `gr pr create --auto-reviewers` / `--no-auto-reviewers`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_reviewers: Option<bool>,
+    /// `gr pr merge --method`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_method: Option<MergeMethod>,
+}
+
+impl PrDefaults {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Conditions a PR must meet for `gr pr ready-check` to pass it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadyPolicy {
+    /// The PR must be approved
+    pub require_approval: bool,
+    /// Status checks must pass
+    pub require_checks: bool,
+    /// The PR must merge without conflicts
+    pub require_mergeable: bool,
+    /// Most commits the base branch may be ahead of the PR branch (unset: no limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_behind: Option<usize>,
+    /// Let PRs with unresolved review threads pass
+    pub allow_unresolved_threads: bool,
+}
+
+impl Default for ReadyPolicy {
+    fn default() -> Self {
+        Self {
+            require_approval: true,
+            require_checks: true,
+            require_mergeable: true,
+            max_behind: None,
+            allow_unresolved_threads: false,
+        }
+    }
+}
+
+impl ReadyPolicy {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Pre-push secret scanning; see `core::secrets`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretScanSettings {
+    /// Scan outgoing commits on `gr push` and `gr pr create --push`
+    pub enabled: bool,
+    /// Rules on top of the built-in ones
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<SecretRule>,
+    /// Use only `rules`, not the built-in ones
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_default_rules: bool,
+    /// Bits of entropy per character above which a long value assigned to a
+    /// secret-sounding name is flagged; 0 disables
+    pub entropy_threshold: f64,
+    /// File of allowed secret or path regexes, relative to the workspace root
+    pub allowlist: String,
+}
+
+impl Default for SecretScanSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: Vec::new(),
+            no_default_rules: false,
+            entropy_threshold: 4.0,
+            allowlist: ".gitgrip/secret-allowlist".to_string(),
+        }
+    }
+}
+
+impl SecretScanSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Pre-push check for files that belong in Git LFS
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LargeFileSettings {
+    /// Largest file an outgoing commit may add, e.g. `50MB`; unset disables
+    /// the check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<String>,
+    /// Refuse the push instead of warning
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub block: bool,
+}
+
+impl LargeFileSettings {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Color theme for rendered diffs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffTheme {
+    /// Colors for dark terminal backgrounds
+    #[default]
+    Dark,
+    /// Colors for light terminal backgrounds
+    Light,
+    /// No colors or highlighting
+    Plain,
+}
+
+impl std::str::FromStr for DiffTheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dark" => Ok(Self::Dark),
+            "light" => Ok(Self::Light),
+            "plain" => Ok(Self::Plain),
+            other => Err(format!(
+                "Unknown theme '{}' (expected dark, light, or plain)",
+                other
+            )),
+        }
+    }
+}
+
+/// When diffs show the old and new versions next to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SideBySide {
+    /// When the terminal is wide enough
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// How diffs are rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSettings {
+    #[serde(default)]
+    pub theme: DiffTheme,
+    #[serde(default)]
+    pub side_by_side: SideBySide,
+}
+
+impl DiffSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Pager for `gr diff`, `gr pr diff`, and `gr forall` output
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PagerSettings {
+    /// `false` never pages; paging is on by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Pager command run through `sh`; `$PAGER`, else `less -FRX`, if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl PagerSettings {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Remote branches `gr branch audit` reports besides merged ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchAuditSettings {
+    /// Days without commits after which a branch is abandoned; 90 if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_days: Option<u64>,
+    /// Names or emails of people who have left; their branches are flagged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub departed: Vec<String>,
+}
+
+impl BranchAuditSettings {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// How `gr branch start --ticket` names branches and PRs link tickets
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketSettings {
+    /// Branch name format with `{ticket}` and `{slug}` placeholders, e.g.
+    /// `feat/{ticket}-{slug}`; `{ticket}-{slug}` if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_format: Option<String>,
+    /// Ticket URL with a `{ticket}` placeholder, e.g.
+    /// `https://acme.atlassian.net/browse/{ticket}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Issue tracker to read ticket states from and update after merges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<TrackerSettings>,
+}
+
+/// Issue tracker kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    /// Jira REST API; token from `JIRA_API_TOKEN` (with `JIRA_EMAIL` on Jira Cloud)
+    Jira,
+    /// Linear GraphQL API; key from `LINEAR_API_KEY`
+    Linear,
+}
+
+/// Issue tracker connection and workflow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackerSettings {
+    #[serde(rename = "type")]
+    pub kind: TrackerKind,
+    /// Site URL, e.g. `https://acme.atlassian.net` (required for Jira)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// State to move a ticket to once `gr pr merge` merges all its PRs, e.g.
+    /// `Done`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_merge: Option<String>,
+}
+
+impl TicketSettings {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// A named secret pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretRule {
+    pub name: String,
+    /// Regex matched against each added line
+    pub pattern: String,
+}
+
+/// A boolean flag's value: `--flag` or `--no-flag` if given, else the
+/// manifest default, else off
+pub fn resolve_flag(on: bool, off: bool, default: Option<bool>) -> bool {
+    match (on, off) {
+        (true, _) => true,
+        (_, true) => false,
+        _ => default.unwrap_or(false),
+    }
 }
 
 fn default_pr_prefix() -> String {
     "[cross-repo]".to_string()
 }
 
+fn default_api_concurrency() -> usize {
+    8
+}
+
+fn default_pr_size_limit() -> usize {
+    400
+}
+
 impl Default for ManifestSettings {
     fn default() -> Self {
         Self {
             pr_prefix: default_pr_prefix(),
             merge_strategy: MergeStrategy::default(),
+            api_concurrency: default_api_concurrency(),
+            pr_size_limit: default_pr_size_limit(),
+            pr_body_style: PrBodyStyle::default(),
+            notifications: NotificationSettings::default(),
+            protected_branches: Vec::new(),
+            auto_optimize: false,
+            status: StatusDefaults::default(),
+            sync: SyncDefaults::default(),
+            forall: ForallDefaults::default(),
+            pr: PrDefaults::default(),
+            ready_check: ReadyPolicy::default(),
+            secret_scan: SecretScanSettings::default(),
+            large_files: LargeFileSettings::default(),
+            tickets: TicketSettings::default(),
+            diff: DiffSettings::default(),
+            pager: PagerSettings::default(),
+            branch_audit: BranchAuditSettings::default(),
         }
     }
 }
@@ -203,9 +702,39 @@ pub struct WorkspaceHooks {
     pub post_checkout: Option<Vec<HookCommand>>,
 }
 
+/// Shell used to run workspace commands (forall, scripts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellKind {
+    /// POSIX `sh -c`
+    Sh,
+    /// `bash -c`
+    Bash,
+    /// Windows `cmd /C`
+    Cmd,
+    /// Windows PowerShell (`powershell -Command`)
+    Powershell,
+    /// PowerShell Core (`pwsh -Command`)
+    Pwsh,
+}
+
+impl ShellKind {
+    /// Default shell for the current platform
+    pub fn platform_default() -> Self {
+        if cfg!(windows) {
+            ShellKind::Cmd
+        } else {
+            ShellKind::Sh
+        }
+    }
+}
+
 /// Workspace configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkspaceConfig {
+    /// Shell for running commands (defaults to sh, or cmd on Windows)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<ShellKind>,
     /// Environment variables
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
@@ -217,6 +746,41 @@ pub struct WorkspaceConfig {
     pub hooks: Option<WorkspaceHooks>,
 }
 
+/// A named subset of the workspace, chosen with `gr init --profile`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    /// Repo names (or globs such as `api-*`) the profile includes
+    pub repos: Vec<String>,
+    /// Environment variables layered over `workspace.env`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+    /// Scripts layered over `workspace.scripts`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scripts: Option<HashMap<String, WorkspaceScript>>,
+}
+
+impl ProfileConfig {
+    /// Whether the profile includes the repo called `name`
+    pub fn includes(&self, name: &str) -> bool {
+        self.repos.iter().any(|pattern| {
+            pattern == name || PathGlob::new(pattern).is_ok_and(|glob| glob.matches(name))
+        })
+    }
+}
+
+/// A file that carries one of the workspace's versions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionFile {
+    /// Repo the file is in
+    pub repo: String,
+    /// Path within the repo
+    pub path: String,
+    /// Regex locating the version, with `{version}` where it goes; defaults
+    /// exist for Cargo.toml, package.json, and pyproject.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
 /// The main manifest structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
@@ -234,6 +798,12 @@ pub struct Manifest {
     /// Workspace config (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub workspace: Option<WorkspaceConfig>,
+    /// Named subsets of the repos, for developers who only need some
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, ProfileConfig>,
+    /// Versions kept in step across repos, by name, with the files carrying each
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub versions: HashMap<String, Vec<VersionFile>>,
 }
 
 fn default_version() -> u32 {
@@ -241,19 +811,109 @@ fn default_version() -> u32 {
 }
 
 impl Manifest {
+    /// Shell configured for workspace commands, or the platform default
+    pub fn shell(&self) -> ShellKind {
+        self.workspace
+            .as_ref()
+            .and_then(|w| w.shell)
+            .unwrap_or_else(ShellKind::platform_default)
+    }
+
+    /// Narrow the manifest to profile `name`
+    ///
+    /// Repos outside the profile are dropped, and the profile's env and
+    /// scripts are layered over the workspace's.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), ManifestError> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            names.sort();
+            return Err(ManifestError::ValidationError(format!(
+                "Unknown profile '{}' (available: {})",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )));
+        };
+
+        self.repos.retain(|repo, _| profile.includes(repo));
+        if profile.env.is_some() || profile.scripts.is_some() {
+            let workspace = self.workspace.get_or_insert_with(Default::default);
+            if let Some(env) = profile.env {
+                workspace
+                    .env
+                    .get_or_insert_with(Default::default)
+                    .extend(env);
+            }
+            if let Some(scripts) = profile.scripts {
+                workspace
+                    .scripts
+                    .get_or_insert_with(Default::default)
+                    .extend(scripts);
+            }
+        }
+        Ok(())
+    }
+
     /// Load a manifest from a YAML file
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ManifestError> {
         let content = std::fs::read_to_string(path)?;
         Self::parse(&content)
     }
 
+    /// Load a manifest from a YAML file, with `${WORKSPACE_ROOT}` set to `workspace_root`
+    pub fn load_for_workspace<P: AsRef<Path>>(
+        path: P,
+        workspace_root: &Path,
+    ) -> Result<Self, ManifestError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_with(&content, &TemplateContext::new(Some(workspace_root)))
+    }
+
     /// Parse a manifest from a YAML string
     pub fn parse(yaml: &str) -> Result<Self, ManifestError> {
-        let manifest: Manifest = serde_yaml::from_str(yaml)?;
+        Self::parse_with(yaml, &TemplateContext::default())
+    }
+
+    /// Parse a manifest from a YAML string, expanding templates with `ctx`
+    ///
+    /// Templates are expanded before validation, so expanded paths are
+    /// still checked against the workspace boundary.
+    pub fn parse_with(yaml: &str, ctx: &TemplateContext) -> Result<Self, ManifestError> {
+        let mut manifest: Manifest = serde_yaml::from_str(yaml)?;
+        manifest.expand_templates(ctx)?;
         manifest.validate()?;
         Ok(manifest)
     }
 
+    /// Expand `${VAR}` templates in repo URLs and paths and the manifest repo URL
+    pub fn expand_templates(&mut self, ctx: &TemplateContext) -> Result<(), ManifestError> {
+        for (field, value) in self.template_fields_mut() {
+            *value = ctx
+                .expand(value)
+                .map_err(|source| ManifestError::Template { field, source })?;
+        }
+        Ok(())
+    }
+
+    /// Fields that may hold templates, as `(field path, value)` in a stable order
+    pub(crate) fn template_fields_mut(&mut self) -> Vec<(String, &mut String)> {
+        let mut repos: Vec<(&String, &mut RepoConfig)> = self.repos.iter_mut().collect();
+        repos.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut fields = Vec::new();
+        for (name, repo) in repos {
+            fields.push((format!("repos.{}.url", name), &mut repo.url));
+            fields.push((format!("repos.{}.path", name), &mut repo.path));
+        }
+        if let Some(ref mut manifest_config) = self.manifest {
+            fields.push(("manifest.url".to_string(), &mut manifest_config.url));
+        }
+        fields
+    }
+
     /// Validate the manifest
     pub fn validate(&self) -> Result<(), ManifestError> {
         // Must have at least one repo
@@ -263,10 +923,78 @@ impl Manifest {
             ));
         }
 
+        if self.settings.api_concurrency == 0 {
+            return Err(ManifestError::ValidationError(
+                "settings.api_concurrency must be at least 1".to_string(),
+            ));
+        }
+
+        let notifications = &self.settings.notifications;
+        for (key, url) in [
+            ("webhook", &notifications.webhook),
+            ("slack", &notifications.slack),
+        ] {
+            if let Some(url) = url {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    return Err(ManifestError::ValidationError(format!(
+                        "settings.notifications.{} must be an http(s) URL",
+                        key
+                    )));
+                }
+            }
+        }
+
+        if self.settings.sync.clone_jobs == Some(0) {
+            return Err(ManifestError::ValidationError(
+                "settings.sync.clone_jobs must be at least 1".to_string(),
+            ));
+        }
+        if let Some(ref timeout) = self.settings.forall.timeout {
+            parse_duration(timeout).map_err(|e| {
+                ManifestError::ValidationError(format!("settings.forall.timeout: {}", e))
+            })?;
+        }
+
+        if let Some(ref max_size) = self.settings.large_files.max_size {
+            parse_bytes(max_size).map_err(|e| {
+                ManifestError::ValidationError(format!("settings.large_files.max_size: {}", e))
+            })?;
+        }
+        if let Some(ref tracker) = self.settings.tickets.tracker {
+            match (tracker.kind, tracker.base_url.as_deref()) {
+                (_, Some(url)) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                    return Err(ManifestError::ValidationError(
+                        "settings.tickets.tracker.base_url must be an http(s) URL".to_string(),
+                    ));
+                }
+                (TrackerKind::Jira, None) => {
+                    return Err(ManifestError::ValidationError(
+                        "settings.tickets.tracker.base_url is required for Jira".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        if let Some(ref format) = self.settings.tickets.branch_format {
+            crate::core::ticket::validate_branch_format(format).map_err(|e| {
+                ManifestError::ValidationError(format!("settings.tickets.branch_format: {}", e))
+            })?;
+        }
+        for rule in &self.settings.secret_scan.rules {
+            regex::Regex::new(&rule.pattern).map_err(|e| {
+                ManifestError::ValidationError(format!(
+                    "settings.secret_scan.rules: invalid pattern for '{}': {}",
+                    rule.name, e
+                ))
+            })?;
+        }
+
         // Validate each repo config
         for (name, repo) in &self.repos {
             self.validate_repo_config(name, repo)?;
         }
+        self.validate_dependencies()?;
+        self.validate_versions()?;
 
         // Validate manifest repo config if present
         if let Some(ref manifest_config) = self.manifest {
@@ -282,6 +1010,10 @@ impl Manifest {
             self.validate_workspace_config(workspace)?;
         }
 
+        for (name, profile) in &self.profiles {
+            self.validate_profile(name, profile)?;
+        }
+
         Ok(())
     }
 
@@ -313,6 +1045,160 @@ impl Manifest {
         // Validate copyfile/linkfile configs
         self.validate_file_configs(name, &repo.copyfile, &repo.linkfile)?;
 
+        // Sparse directories must stay inside the repo
+        if let Some(ref sparse) = repo.sparse {
+            for pattern in sparse {
+                if pattern.trim().is_empty() {
+                    return Err(ManifestError::ValidationError(format!(
+                        "Repository '{}' has an empty sparse entry",
+                        name
+                    )));
+                }
+                if path_escapes_boundary(pattern) {
+                    return Err(ManifestError::PathTraversal(format!(
+                        "Repository '{}' sparse path escapes boundary: {}",
+                        name, pattern
+                    )));
+                }
+            }
+        }
+
+        if repo.workspace
+            && (repo.sparse.is_some()
+                || repo.revision.is_some()
+                || repo.copyfile.is_some()
+                || repo.linkfile.is_some())
+        {
+            return Err(ManifestError::ValidationError(format!(
+                "Repository '{}' is a nested workspace; sparse, revision, copyfile and linkfile don't apply",
+                name
+            )));
+        }
+
+        if let Some(ref revision) = repo.revision {
+            if revision.is_empty()
+                || revision.starts_with('-')
+                || revision.chars().any(char::is_whitespace)
+            {
+                return Err(ManifestError::ValidationError(format!(
+                    "Repository '{}' has an invalid revision: '{}'",
+                    name, revision
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_profile(&self, name: &str, profile: &ProfileConfig) -> Result<(), ManifestError> {
+        for pattern in &profile.repos {
+            let is_glob = pattern.contains(['*', '?', '[']);
+            if is_glob {
+                if PathGlob::new(pattern).is_err() {
+                    return Err(ManifestError::ValidationError(format!(
+                        "Profile '{}' has an invalid repo glob: '{}'",
+                        name, pattern
+                    )));
+                }
+            } else if !self.repos.contains_key(pattern) {
+                return Err(ManifestError::ValidationError(format!(
+                    "Profile '{}' includes unknown repository '{}'",
+                    name, pattern
+                )));
+            }
+        }
+        if !self.repos.keys().any(|repo| profile.includes(repo)) {
+            return Err(ManifestError::ValidationError(format!(
+                "Profile '{}' includes no repositories",
+                name
+            )));
+        }
+
+        let scripts = WorkspaceConfig {
+            scripts: profile.scripts.clone(),
+            ..Default::default()
+        };
+        self.validate_workspace_config(&scripts)
+    }
+
+    fn validate_versions(&self) -> Result<(), ManifestError> {
+        for (name, files) in &self.versions {
+            let invalid = |message: String| {
+                ManifestError::ValidationError(format!("versions.{}: {}", name, message))
+            };
+            if files.is_empty() {
+                return Err(invalid("lists no files".to_string()));
+            }
+            for file in files {
+                if !self.repos.contains_key(&file.repo) {
+                    return Err(invalid(format!("unknown repository '{}'", file.repo)));
+                }
+                if file.path.is_empty() || Path::new(&file.path).is_absolute() {
+                    return Err(invalid(format!(
+                        "'{}' must be a path within the repo",
+                        file.path
+                    )));
+                }
+                let pattern = crate::core::version::file_pattern(file)
+                    .ok_or_else(|| invalid(format!("{} needs a pattern", file.path)))?;
+                crate::core::version::version_regex(&pattern)
+                    .map_err(|e| invalid(format!("{}: {}", file.path, e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_dependencies(&self) -> Result<(), ManifestError> {
+        for (name, repo) in &self.repos {
+            for dep in repo.depends_on.iter().flatten() {
+                if dep == name {
+                    return Err(ManifestError::ValidationError(format!(
+                        "Repository '{}' cannot depend on itself",
+                        name
+                    )));
+                }
+                if !self.repos.contains_key(dep) {
+                    return Err(ManifestError::ValidationError(format!(
+                        "Repository '{}' depends on unknown repository '{}'",
+                        name, dep
+                    )));
+                }
+            }
+        }
+
+        // Depth-first search for a back edge, visiting repos in a stable order
+        fn visit<'a>(
+            manifest: &'a Manifest,
+            name: &'a str,
+            visiting: &mut Vec<&'a str>,
+            done: &mut HashSet<&'a str>,
+        ) -> Result<(), ManifestError> {
+            if done.contains(name) {
+                return Ok(());
+            }
+            if let Some(pos) = visiting.iter().position(|n| *n == name) {
+                let mut cycle = visiting[pos..].to_vec();
+                cycle.push(name);
+                return Err(ManifestError::ValidationError(format!(
+                    "Dependency cycle: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+            visiting.push(name);
+            for dep in manifest.repos[name].depends_on.iter().flatten() {
+                visit(manifest, dep, visiting, done)?;
+            }
+            visiting.pop();
+            done.insert(name);
+            Ok(())
+        }
+
+        let mut names: Vec<&str> = self.repos.keys().map(String::as_str).collect();
+        names.sort();
+        let mut done = HashSet::new();
+        for name in names {
+            visit(self, name, &mut Vec::new(), &mut done)?;
+        }
         Ok(())
     }
 
@@ -480,6 +1366,7 @@ workspace:
         assert!(manifest.manifest.is_some());
         assert_eq!(manifest.repos.len(), 1);
         assert_eq!(manifest.settings.pr_prefix, "[multi-repo]");
+        assert_eq!(manifest.settings.api_concurrency, 8);
     }
 
     #[test]
@@ -491,6 +1378,93 @@ repos: {}
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_zero_api_concurrency_fails() {
+        let yaml = r#"
+repos:
+  app:
+    url: git@github.com:user/app.git
+    path: app
+settings:
+  api_concurrency: 0
+"#;
+        assert!(Manifest::parse(yaml).is_err());
+    }
+
+    #[test]
+    fn test_notification_settings() {
+        let yaml = r#"
+repos:
+  app:
+    url: git@github.com:user/app.git
+    path: app
+settings:
+  notifications:
+    desktop: true
+    slack: https://hooks.slack.com/services/T000/B000/XXX
+"#;
+        let manifest = Manifest::parse(yaml).unwrap();
+        assert!(manifest.settings.notifications.desktop);
+        assert_eq!(manifest.settings.pr_body_style, PrBodyStyle::Commits);
+        assert!(manifest.settings.notifications.webhook.is_none());
+
+        let bad = yaml.replace("https://hooks", "hooks");
+        assert!(Manifest::parse(&bad).is_err());
+    }
+
+    #[test]
+    fn test_protected_branches() {
+        let mut settings = ManifestSettings::default();
+        assert!(settings.is_protected("main", "main"));
+        assert!(!settings.is_protected("feat/x", "main"));
+
+        settings.protected_branches = vec!["main".to_string(), "release/*".to_string()];
+        assert!(settings.is_protected("release/1.2", "trunk"));
+        assert!(!settings.is_protected("release/1.2/hotfix", "trunk"));
+        assert!(!settings.is_protected("feat/main", "trunk"));
+        // The default branch stays protected when it isn't listed
+        assert!(settings.is_protected("trunk", "trunk"));
+
+        let targets = [("api", "main", "main"), ("web", "feat/x", "main")];
+        let err = settings
+            .ensure_unprotected("commit to", targets, false)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Refusing to commit to protected branch: api (main); pass --allow-protected to override"
+        );
+        assert!(settings
+            .ensure_unprotected("commit to", targets, true)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_depends_on_validation() {
+        let ok = r#"
+repos:
+  app:
+    url: git@github.com:user/app.git
+    path: app
+    depends_on: [lib]
+  lib:
+    url: git@github.com:user/lib.git
+    path: lib
+"#;
+        let manifest = Manifest::parse(ok).unwrap();
+        assert_eq!(
+            manifest.repos["app"].depends_on,
+            Some(vec!["lib".to_string()])
+        );
+
+        let unknown = ok.replace("[lib]", "[missing]");
+        let err = Manifest::parse(&unknown).unwrap_err().to_string();
+        assert!(err.contains("unknown repository 'missing'"));
+
+        let cycle = format!("{}    depends_on: [app]\n", ok);
+        let err = Manifest::parse(&cycle).unwrap_err().to_string();
+        assert!(err.contains("app -> lib -> app"), "{}", err);
+    }
+
     #[test]
     fn test_path_traversal_fails() {
         let yaml = r#"
@@ -503,6 +1477,100 @@ repos:
         assert!(matches!(result, Err(ManifestError::PathTraversal(_))));
     }
 
+    #[test]
+    fn test_parse_sparse() {
+        let yaml = r#"
+repos:
+  monorepo:
+    url: git@github.com:user/monorepo.git
+    path: monorepo
+    sparse:
+      - services/api
+      - libs/common
+"#;
+        let manifest = Manifest::parse(yaml).unwrap();
+        assert_eq!(
+            manifest.repos["monorepo"].sparse,
+            Some(vec!["services/api".to_string(), "libs/common".to_string()])
+        );
+
+        let escaping = yaml.replace("libs/common", "../outside");
+        assert!(matches!(
+            Manifest::parse(&escaping),
+            Err(ManifestError::PathTraversal(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_revision() {
+        let yaml = r#"
+repos:
+  api:
+    url: git@github.com:user/api.git
+    path: api
+    revision: release/2.x
+  web:
+    url: git@github.com:user/web.git
+    path: web
+"#;
+        let manifest = Manifest::parse(yaml).unwrap();
+        assert_eq!(
+            manifest.repos["api"].revision.as_deref(),
+            Some("release/2.x")
+        );
+        assert_eq!(manifest.repos["web"].revision, None);
+
+        let invalid = yaml.replace("release/2.x", "\"--upload-pack=evil\"");
+        assert!(matches!(
+            Manifest::parse(&invalid),
+            Err(ManifestError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        let yaml = r#"
+repos:
+  api:
+    url: git@github.com:user/api.git
+    path: api
+  api-worker:
+    url: git@github.com:user/api-worker.git
+    path: api-worker
+  web:
+    url: git@github.com:user/web.git
+    path: web
+workspace:
+  env:
+    LOG_LEVEL: info
+profiles:
+  backend:
+    repos: ["api*"]
+    env:
+      LOG_LEVEL: debug
+    scripts:
+      up:
+        command: docker compose up
+"#;
+        let mut manifest = Manifest::parse(yaml).unwrap();
+        manifest.apply_profile("backend").unwrap();
+        let mut repos: Vec<&String> = manifest.repos.keys().collect();
+        repos.sort();
+        assert_eq!(repos, vec!["api", "api-worker"]);
+        let workspace = manifest.workspace.as_ref().unwrap();
+        assert_eq!(workspace.env.as_ref().unwrap()["LOG_LEVEL"], "debug");
+        assert!(workspace.scripts.as_ref().unwrap().contains_key("up"));
+
+        let err = Manifest::parse(yaml).unwrap().apply_profile("frontend");
+        assert!(err.unwrap_err().to_string().contains("available: backend"));
+
+        let unknown = yaml.replace("[\"api*\"]", "[api, mobile]");
+        assert!(matches!(
+            Manifest::parse(&unknown),
+            Err(ManifestError::ValidationError(_))
+        ));
+    }
+
     #[test]
     fn test_absolute_path_fails() {
         let yaml = r#"
@@ -534,6 +1602,32 @@ workspace:
         assert!(matches!(result, Err(ManifestError::ValidationError(_))));
     }
 
+    #[test]
+    fn test_parse_workspace_shell() {
+        let yaml = r#"
+repos:
+  app:
+    url: git@github.com:user/app.git
+    path: app
+workspace:
+  shell: pwsh
+"#;
+        let manifest = Manifest::parse(yaml).unwrap();
+        assert_eq!(manifest.shell(), ShellKind::Pwsh);
+    }
+
+    #[test]
+    fn test_default_shell() {
+        let yaml = r#"
+repos:
+  app:
+    url: git@github.com:user/app.git
+    path: app
+"#;
+        let manifest = Manifest::parse(yaml).unwrap();
+        assert_eq!(manifest.shell(), ShellKind::platform_default());
+    }
+
     #[test]
     fn test_path_escapes_boundary() {
         assert!(path_escapes_boundary(".."));
@@ -544,4 +1638,62 @@ workspace:
         assert!(!path_escapes_boundary("foo/bar"));
         assert!(!path_escapes_boundary("./foo"));
     }
+
+    #[test]
+    fn test_parse_with_templates() {
+        let yaml = r#"
+repos:
+  app:
+    url: ${GIT_BASE:-git@github.com:}${ORG}/app.git
+    path: ${ORG}/app
+"#;
+        let ctx = TemplateContext::from_vars([("ORG", "acme")]);
+        let manifest = Manifest::parse_with(yaml, &ctx).unwrap();
+        assert_eq!(manifest.repos["app"].url, "git@github.com:acme/app.git");
+        assert_eq!(manifest.repos["app"].path, "acme/app");
+
+        let err = Manifest::parse_with(yaml, &TemplateContext::from_vars([("X", "y")]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Invalid template in repos.app.url: undefined variable 'ORG'"));
+
+        // Expanded paths are still checked against the workspace boundary
+        let ctx = TemplateContext::from_vars([("ORG", "../escape")]);
+        assert!(matches!(
+            Manifest::parse_with(yaml, &ctx),
+            Err(ManifestError::PathTraversal(_))
+        ));
+    }
+
+    #[test]
+    fn test_command_defaults() {
+        let yaml = r#"
+repos:
+  app:
+    url: git@github.com:acme/app.git
+    path: app
+settings:
+  forall:
+    parallel: true
+    timeout: 10m
+  pr:
+    merge_method: squash
+"#;
+        let settings = Manifest::parse(yaml).unwrap().settings;
+        assert_eq!(settings.forall.parallel, Some(true));
+        assert_eq!(settings.pr.merge_method, Some(MergeMethod::Squash));
+        assert_eq!(settings.status.verbose, None);
+
+        // Flags win over the manifest; unset falls back to it
+        assert!(resolve_flag(false, false, settings.forall.parallel));
+        assert!(!resolve_flag(false, true, settings.forall.parallel));
+        assert!(resolve_flag(true, false, Some(false)));
+        assert!(!resolve_flag(false, false, None));
+
+        let bad = yaml.replace("10m", "soon");
+        assert!(Manifest::parse(&bad)
+            .unwrap_err()
+            .to_string()
+            .contains("settings.forall.timeout"));
+    }
 }
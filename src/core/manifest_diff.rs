@@ -0,0 +1,208 @@
+//! Semantic manifest comparison for `gr manifest diff`
+//!
+//! Compares two parsed manifests and reports what matters to a workspace:
+//! repos added or removed, and changes to a repo's URL, path, or default
+//! branch, or to workspace scripts. Formatting-only edits produce no changes.
+
+use crate::core::manifest::Manifest;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// One semantic difference between two manifests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestChange {
+    RepoAdded {
+        name: String,
+        url: String,
+    },
+    RepoRemoved {
+        name: String,
+    },
+    UrlChanged {
+        repo: String,
+        old: String,
+        new: String,
+    },
+    PathChanged {
+        repo: String,
+        old: String,
+        new: String,
+    },
+    BranchChanged {
+        repo: String,
+        old: String,
+        new: String,
+    },
+    ScriptAdded {
+        name: String,
+    },
+    ScriptRemoved {
+        name: String,
+    },
+    ScriptChanged {
+        name: String,
+    },
+}
+
+impl ManifestChange {
+    /// `+` for additions, `-` for removals, `~` for modifications
+    pub fn symbol(&self) -> char {
+        match self {
+            ManifestChange::RepoAdded { .. } | ManifestChange::ScriptAdded { .. } => '+',
+            ManifestChange::RepoRemoved { .. } | ManifestChange::ScriptRemoved { .. } => '-',
+            _ => '~',
+        }
+    }
+}
+
+impl fmt::Display for ManifestChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestChange::RepoAdded { name, url } => write!(f, "repo {} ({})", name, url),
+            ManifestChange::RepoRemoved { name } => write!(f, "repo {}", name),
+            ManifestChange::UrlChanged { repo, old, new } => {
+                write!(f, "{}: url {} -> {}", repo, old, new)
+            }
+            ManifestChange::PathChanged { repo, old, new } => {
+                write!(f, "{}: path {} -> {}", repo, old, new)
+            }
+            ManifestChange::BranchChanged { repo, old, new } => {
+                write!(f, "{}: default branch {} -> {}", repo, old, new)
+            }
+            ManifestChange::ScriptAdded { name } | ManifestChange::ScriptRemoved { name } => {
+                write!(f, "script {}", name)
+            }
+            ManifestChange::ScriptChanged { name } => write!(f, "script {} changed", name),
+        }
+    }
+}
+
+/// Semantic changes from `old` to `new`, repos first, each group in name order
+pub fn diff_manifests(old: &Manifest, new: &Manifest) -> Vec<ManifestChange> {
+    let mut changes = Vec::new();
+
+    let names: BTreeSet<&String> = old.repos.keys().chain(new.repos.keys()).collect();
+    for name in names {
+        match (old.repos.get(name), new.repos.get(name)) {
+            (None, Some(repo)) => changes.push(ManifestChange::RepoAdded {
+                name: name.clone(),
+                url: repo.url.clone(),
+            }),
+            (Some(_), None) => changes.push(ManifestChange::RepoRemoved { name: name.clone() }),
+            (Some(before), Some(after)) => {
+                if before.url != after.url {
+                    changes.push(ManifestChange::UrlChanged {
+                        repo: name.clone(),
+                        old: before.url.clone(),
+                        new: after.url.clone(),
+                    });
+                }
+                if before.path != after.path {
+                    changes.push(ManifestChange::PathChanged {
+                        repo: name.clone(),
+                        old: before.path.clone(),
+                        new: after.path.clone(),
+                    });
+                }
+                if before.default_branch != after.default_branch {
+                    changes.push(ManifestChange::BranchChanged {
+                        repo: name.clone(),
+                        old: before.default_branch.clone(),
+                        new: after.default_branch.clone(),
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    let old_scripts = old.workspace.as_ref().and_then(|w| w.scripts.as_ref());
+    let new_scripts = new.workspace.as_ref().and_then(|w| w.scripts.as_ref());
+    let names: BTreeSet<&String> = old_scripts
+        .into_iter()
+        .flat_map(|s| s.keys())
+        .chain(new_scripts.into_iter().flat_map(|s| s.keys()))
+        .collect();
+    for name in names {
+        let before = old_scripts.and_then(|s| s.get(name));
+        let after = new_scripts.and_then(|s| s.get(name));
+        match (before, after) {
+            (None, Some(_)) => changes.push(ManifestChange::ScriptAdded { name: name.clone() }),
+            (Some(_), None) => changes.push(ManifestChange::ScriptRemoved { name: name.clone() }),
+            (Some(before), Some(after)) => {
+                // Scripts have no PartialEq; compare their serialized form
+                if serde_yaml::to_value(before).ok() != serde_yaml::to_value(after).ok() {
+                    changes.push(ManifestChange::ScriptChanged { name: name.clone() });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_manifests() {
+        let old = Manifest::parse(
+            r#"
+repos:
+  app:
+    url: git@github.com:org/app.git
+    path: app
+  lib:
+    url: git@github.com:org/lib.git
+    path: lib
+workspace:
+  scripts:
+    build:
+      command: make
+    lint:
+      command: make lint
+"#,
+        )
+        .unwrap();
+        let new = Manifest::parse(
+            r#"
+repos:
+  app:
+    url: git@github.com:org/app-v2.git
+    path: apps/app
+    default_branch: develop
+  api:
+    url: git@github.com:org/api.git
+    path: api
+workspace:
+  scripts:
+    build:
+      command: make all
+    lint: { command: make lint }
+    test:
+      command: make test
+"#,
+        )
+        .unwrap();
+
+        let changes: Vec<String> = diff_manifests(&old, &new)
+            .iter()
+            .map(|c| format!("{} {}", c.symbol(), c))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                "+ repo api (git@github.com:org/api.git)",
+                "~ app: url git@github.com:org/app.git -> git@github.com:org/app-v2.git",
+                "~ app: path app -> apps/app",
+                "~ app: default branch main -> develop",
+                "- repo lib",
+                "~ script build changed",
+                "+ script test",
+            ]
+        );
+        assert!(diff_manifests(&new, &new).is_empty());
+    }
+}
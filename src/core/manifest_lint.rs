@@ -0,0 +1,679 @@
+//! Extended manifest checks for `gr manifest lint`
+//!
+//! [`Manifest::validate`] stops at the first problem and only sees the parsed
+//! values. Linting reports every problem it finds, each pointing at the line
+//! and column in manifest.yaml it comes from, and also checks things that
+//! need the workspace on disk (copyfile sources) or the network (repo URLs).
+
+use crate::core::manifest::{Manifest, PlatformType};
+use crate::core::template::TemplateContext;
+use crate::util::profile::ProfiledCommand;
+use crate::util::{max_workers, parallel_map};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+/// How serious a lint finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A 1-based position in the manifest source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A single lint finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Where in the manifest the problem is, if it can be pinned down
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Render as `file:line:col: severity: message`
+    pub fn render(&self, file: &str) -> String {
+        match self.location {
+            Some(location) => format!("{}:{}: {}: {}", file, location, self.severity, self.message),
+            None => format!("{}: {}: {}", file, self.severity, self.message),
+        }
+    }
+}
+
+/// Maps key paths such as `repos.app.copyfile[0].src` to source positions
+///
+/// serde_yaml only reports positions for parse errors, so this scans the
+/// block-style YAML used by manifests line by line. A key's position is that
+/// of its value when the value is on the same line, otherwise of the key.
+#[derive(Debug, Default)]
+pub struct YamlLocator {
+    positions: HashMap<String, Location>,
+}
+
+impl YamlLocator {
+    pub fn new(source: &str) -> Self {
+        let mut positions = HashMap::new();
+        // (indent, segment) for the mapping keys and sequence items enclosing the line
+        let mut stack: Vec<(usize, String)> = Vec::new();
+        let mut item_counts: HashMap<String, usize> = HashMap::new();
+        // Lines more indented than this belong to a block scalar
+        let mut block_indent: Option<usize> = None;
+
+        for (index, line) in source.lines().enumerate() {
+            let content = line.trim_start_matches(' ');
+            let indent = line.len() - content.len();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            if let Some(limit) = block_indent {
+                if indent > limit {
+                    continue;
+                }
+                block_indent = None;
+            }
+            if content.starts_with("---") || content.starts_with("...") {
+                stack.clear();
+                continue;
+            }
+
+            let mut column = indent;
+            let mut rest = content;
+
+            // Sequence items, possibly nested on one line (`- - a`)
+            while rest == "-" || rest.starts_with("- ") {
+                while stack
+                    .last()
+                    .is_some_and(|(i, seg)| *i > column || (*i == column && seg.starts_with('[')))
+                {
+                    stack.pop();
+                }
+                let parent = join_path(&stack);
+                let count = item_counts
+                    .entry(format!("{}@{}", parent, column))
+                    .or_default();
+                stack.push((column, format!("[{}]", count)));
+                *count += 1;
+
+                let after = rest[1..].trim_start_matches(' ');
+                column += rest.len() - after.len();
+                rest = after;
+            }
+
+            if rest.is_empty() {
+                continue;
+            }
+
+            match split_key(rest) {
+                Some((key, value_offset)) => {
+                    while stack.last().is_some_and(|(i, _)| *i >= column) {
+                        stack.pop();
+                    }
+                    stack.push((column, key));
+                    let value = rest[value_offset..].trim_start_matches(' ');
+                    let value_column = if value.is_empty() || value.starts_with('#') {
+                        column
+                    } else {
+                        column + rest.len() - value.len()
+                    };
+                    positions.insert(
+                        join_path(&stack),
+                        Location {
+                            line: index + 1,
+                            column: value_column + 1,
+                        },
+                    );
+                    if value.starts_with('|') || value.starts_with('>') {
+                        block_indent = Some(column);
+                    }
+                }
+                None => {
+                    // A scalar sequence item
+                    if stack.last().is_some_and(|(i, _)| *i < column) {
+                        positions.insert(
+                            join_path(&stack),
+                            Location {
+                                line: index + 1,
+                                column: column + 1,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        Self { positions }
+    }
+
+    /// Position of the value at exactly `path`, with no fallback
+    pub fn exact(&self, path: &str) -> Option<Location> {
+        self.positions.get(path).copied()
+    }
+
+    /// Position of the value at `path`, e.g. `repos.app.path`
+    ///
+    /// Falls back to the nearest enclosing key, for values inside flow-style
+    /// collections (`{ type: github }`) that the scanner doesn't descend into.
+    pub fn find(&self, path: &str) -> Option<Location> {
+        let mut path = path;
+        loop {
+            if let Some(location) = self.positions.get(path) {
+                return Some(*location);
+            }
+            path = &path[..path.rfind(['.', '['])?];
+        }
+    }
+}
+
+fn join_path(stack: &[(usize, String)]) -> String {
+    let mut path = String::new();
+    for (_, segment) in stack {
+        if !path.is_empty() && !segment.starts_with('[') {
+            path.push('.');
+        }
+        path.push_str(segment);
+    }
+    path
+}
+
+/// Split `key: value` into the unquoted key and the offset of the value
+fn split_key(text: &str) -> Option<(String, usize)> {
+    let (key, after) = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = text[1..].find(quote)? + 1;
+            (text[1..end].to_string(), end + 1)
+        }
+        '{' | '[' => return None,
+        _ => {
+            let end = text
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| text[i + 1..].is_empty() || text[i + 1..].starts_with(' '))?;
+            (text[..end].trim_end().to_string(), end)
+        }
+    };
+    let tail = &text[after..];
+    if tail == ":" || tail.starts_with(": ") {
+        Some((key, after + 1))
+    } else {
+        None
+    }
+}
+
+/// Whether `name` is a valid branch name, following `git check-ref-format --branch`
+pub fn is_valid_branch_name(name: &str) -> bool {
+    if name.is_empty() || name == "@" || name.starts_with('-') {
+        return false;
+    }
+    if name.contains("..") || name.contains("@{") || name.contains("//") {
+        return false;
+    }
+    if name.chars().any(|c| {
+        c.is_ascii_control() || matches!(c, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+    }) {
+        return false;
+    }
+    if name.starts_with('/') || name.ends_with('/') || name.ends_with('.') {
+        return false;
+    }
+    name.split('/')
+        .all(|component| !component.starts_with('.') && !component.ends_with(".lock"))
+}
+
+/// Lint manifest `source`
+///
+/// `manifest_dir` is the manifest repo checkout (for the manifest's own
+/// copyfile sources) and `workspace_root` is where repos are cloned. With
+/// `network`, every repo URL is checked with `git ls-remote`.
+pub fn lint_manifest(
+    source: &str,
+    manifest_dir: &Path,
+    workspace_root: &Path,
+    network: bool,
+) -> Vec<Diagnostic> {
+    let locator = YamlLocator::new(source);
+    let mut diagnostics = Vec::new();
+    let error = |location: Option<Location>, message: String| Diagnostic {
+        severity: Severity::Error,
+        location,
+        message,
+    };
+
+    let mut raw: Value = match serde_yaml::from_str(source) {
+        Ok(raw) => raw,
+        Err(e) => {
+            let location = e.location().map(|l| Location {
+                line: l.line(),
+                column: l.column(),
+            });
+            return vec![error(location, e.to_string())];
+        }
+    };
+
+    // Unknown platforms would fail the typed parse below; report each one and
+    // substitute a known platform so the remaining checks still run
+    let mut platform_fields: Vec<String> = vec!["manifest.platform.type".to_string()];
+    if let Some(repos) = raw.get("repos").and_then(Value::as_mapping) {
+        platform_fields.extend(
+            repos
+                .keys()
+                .filter_map(Value::as_str)
+                .map(|name| format!("repos.{}.platform.type", name)),
+        );
+    }
+    for field in &platform_fields {
+        let Some(value) = value_at_mut(&mut raw, field) else {
+            continue;
+        };
+        if serde_yaml::from_value::<PlatformType>(value.clone()).is_ok() {
+            continue;
+        }
+        diagnostics.push(error(
+            locator.find(field),
+            format!(
+                "unknown platform {} (expected github, gitlab, or azure-devops)",
+                describe_value(value)
+            ),
+        ));
+        *value = Value::String(PlatformType::default().to_string());
+    }
+
+    let mut manifest: Manifest = match serde_yaml::from_value(raw) {
+        Ok(manifest) => manifest,
+        Err(_) => {
+            // Re-parse the text to get the error's position
+            if let Err(e) = serde_yaml::from_str::<Manifest>(source) {
+                let location = e.location().map(|l| Location {
+                    line: l.line(),
+                    column: l.column(),
+                });
+                diagnostics.push(error(location, e.to_string()));
+            }
+            return diagnostics;
+        }
+    };
+
+    let ctx = TemplateContext::new(Some(workspace_root));
+    for (field, value) in manifest.template_fields_mut() {
+        match ctx.expand(value) {
+            Ok(expanded) => *value = expanded,
+            Err(e) => diagnostics.push(error(locator.find(&field), format!("{}: {}", field, e))),
+        }
+    }
+
+    if let Err(e) = manifest.validate() {
+        diagnostics.push(error(None, e.to_string()));
+    }
+
+    let mut names: Vec<&String> = manifest.repos.keys().collect();
+    names.sort();
+
+    // Duplicate paths
+    let mut seen_paths: HashMap<String, &str> = HashMap::new();
+    for name in &names {
+        let path = normalize_path(&manifest.repos[*name].path);
+        match seen_paths.get(&path) {
+            Some(first) => diagnostics.push(error(
+                locator.find(&format!("repos.{}.path", name)),
+                format!(
+                    "repo '{}' has the same path as '{}': {}",
+                    name, first, manifest.repos[*name].path
+                ),
+            )),
+            None => {
+                seen_paths.insert(path, name);
+            }
+        }
+    }
+
+    // Branch names
+    let mut branches: Vec<(String, &str)> = names
+        .iter()
+        .map(|name| {
+            (
+                format!("repos.{}.default_branch", name),
+                manifest.repos[*name].default_branch.as_str(),
+            )
+        })
+        .collect();
+    if let Some(ref manifest_config) = manifest.manifest {
+        branches.push((
+            "manifest.default_branch".to_string(),
+            &manifest_config.default_branch,
+        ));
+    }
+    for (field, branch) in branches {
+        if !is_valid_branch_name(branch) {
+            diagnostics.push(error(
+                locator.find(&field),
+                format!("invalid branch name '{}'", branch),
+            ));
+        }
+    }
+
+    // Copyfile and linkfile sources, for checkouts that exist
+    let mut file_sources: Vec<(String, &Path, &str)> = Vec::new();
+    let repo_dirs: HashMap<&str, std::path::PathBuf> = names
+        .iter()
+        .map(|name| {
+            (
+                name.as_str(),
+                workspace_root.join(&manifest.repos[*name].path),
+            )
+        })
+        .collect();
+    for name in &names {
+        let config = &manifest.repos[*name];
+        let dir = repo_dirs[name.as_str()].as_path();
+        for (i, file) in config.copyfile.iter().flatten().enumerate() {
+            file_sources.push((
+                format!("repos.{}.copyfile[{}].src", name, i),
+                dir,
+                &file.src,
+            ));
+        }
+        for (i, file) in config.linkfile.iter().flatten().enumerate() {
+            file_sources.push((
+                format!("repos.{}.linkfile[{}].src", name, i),
+                dir,
+                &file.src,
+            ));
+        }
+    }
+    if let Some(ref manifest_config) = manifest.manifest {
+        for (i, file) in manifest_config.copyfile.iter().flatten().enumerate() {
+            file_sources.push((
+                format!("manifest.copyfile[{}].src", i),
+                manifest_dir,
+                &file.src,
+            ));
+        }
+        for (i, file) in manifest_config.linkfile.iter().flatten().enumerate() {
+            file_sources.push((
+                format!("manifest.linkfile[{}].src", i),
+                manifest_dir,
+                &file.src,
+            ));
+        }
+    }
+    for (field, dir, src) in file_sources {
+        if dir.exists() && !src.is_empty() && !dir.join(src).exists() {
+            let kind = if field.contains(".copyfile[") {
+                "copyfile"
+            } else {
+                "linkfile"
+            };
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                location: locator.find(&field),
+                message: format!(
+                    "{} source '{}' does not exist in {}",
+                    kind,
+                    src,
+                    dir.display()
+                ),
+            });
+        }
+    }
+
+    // Script step names must be unique within a script
+    if let Some(scripts) = manifest.workspace.as_ref().and_then(|w| w.scripts.as_ref()) {
+        let mut script_names: Vec<&String> = scripts.keys().collect();
+        script_names.sort();
+        for script in script_names {
+            let mut seen = HashMap::new();
+            for (i, step) in scripts[script].steps.iter().flatten().enumerate() {
+                if let Some(first) = seen.insert(step.name.as_str(), i) {
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Warning,
+                        location: locator
+                            .find(&format!("workspace.scripts.{}.steps[{}].name", script, i)),
+                        message: format!(
+                            "script '{}' has more than one step named '{}' (steps {} and {})",
+                            script,
+                            step.name,
+                            first + 1,
+                            i + 1
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    if network {
+        let mut urls: Vec<(String, &str)> = names
+            .iter()
+            .map(|name| {
+                (
+                    format!("repos.{}.url", name),
+                    manifest.repos[*name].url.as_str(),
+                )
+            })
+            .collect();
+        if let Some(ref manifest_config) = manifest.manifest {
+            urls.push(("manifest.url".to_string(), &manifest_config.url));
+        }
+        let results = parallel_map(&urls, max_workers(), |(_, url)| check_url(url));
+        for ((field, url), result) in urls.iter().zip(results) {
+            if let Err(reason) = result {
+                diagnostics.push(error(
+                    locator.find(field),
+                    format!("cannot reach {}: {}", url, reason),
+                ));
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.location.is_some(), d.location));
+    diagnostics
+}
+
+/// Check that `url` answers `git ls-remote` without prompting for credentials
+pub fn check_url(url: &str) -> Result<(), String> {
+    let mut command = Command::new("git");
+    command
+        .args(["ls-remote", "--quiet", url, "HEAD"])
+        .env("GIT_TERMINAL_PROMPT", "0");
+    if std::env::var_os("GIT_SSH_COMMAND").is_none() {
+        command.env(
+            "GIT_SSH_COMMAND",
+            "ssh -o BatchMode=yes -o ConnectTimeout=10",
+        );
+    }
+    let output = command.timed_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("git ls-remote failed")
+        .trim_start_matches("fatal: ")
+        .to_string())
+}
+
+/// The value at a dotted mapping path, e.g. `repos.app.platform.type`
+fn value_at_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(value, |value, key| value.get_mut(key))
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{}'", s),
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Normalize a repo path so `./libs/a/` and `libs/a` compare equal
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MANIFEST: &str = r#"version: 1
+repos:
+  app:
+    url: git@github.com:user/app.git
+    path: ./app
+    default_branch: "feat..x"
+    copyfile:
+      - src: README.md
+        dest: APP_README.md
+      - src: missing.md
+        dest: OTHER.md
+  lib:
+    url: git@github.com:user/lib.git
+    path: app/
+    platform:
+      type: bitbucket
+workspace:
+  scripts:
+    build:
+      description: |
+        Build everything
+        name: not a key
+      steps:
+        - name: compile
+          command: make
+        - name: compile
+          command: make install
+"#;
+
+    #[test]
+    fn test_locator() {
+        let locator = YamlLocator::new(MANIFEST);
+        assert_eq!(
+            locator.find("repos.app.path"),
+            Some(Location {
+                line: 5,
+                column: 11
+            })
+        );
+        assert_eq!(
+            locator.find("repos.app.copyfile[1].src"),
+            Some(Location {
+                line: 10,
+                column: 14
+            })
+        );
+        assert_eq!(
+            locator.find("workspace.scripts.build.steps[1].name"),
+            Some(Location {
+                line: 26,
+                column: 17
+            })
+        );
+        assert_eq!(
+            locator.find("repos.lib"),
+            Some(Location {
+                line: 12,
+                column: 3
+            })
+        );
+        assert_eq!(
+            locator.find("workspace.scripts.build.name"),
+            Some(Location {
+                line: 19,
+                column: 5
+            })
+        );
+        assert_eq!(locator.find("settings.pr_prefix"), None);
+    }
+
+    #[test]
+    fn test_lint_reports_every_problem() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("app")).unwrap();
+        std::fs::write(temp.path().join("app/README.md"), "# App").unwrap();
+
+        let diagnostics = lint_manifest(MANIFEST, temp.path(), temp.path(), false);
+        let rendered: Vec<String> = diagnostics
+            .iter()
+            .map(|d| d.render("manifest.yaml"))
+            .collect();
+        assert_eq!(rendered.len(), 5, "{:#?}", rendered);
+        assert_eq!(
+            rendered[0],
+            "manifest.yaml:6:21: error: invalid branch name 'feat..x'"
+        );
+        assert!(rendered[1].starts_with(
+            "manifest.yaml:10:14: warning: copyfile source 'missing.md' does not exist"
+        ));
+        assert_eq!(
+            rendered[2],
+            "manifest.yaml:14:11: error: repo 'lib' has the same path as 'app': app/"
+        );
+        assert_eq!(
+            rendered[3],
+            "manifest.yaml:16:13: error: unknown platform 'bitbucket' (expected github, gitlab, or azure-devops)"
+        );
+        assert!(rendered[4].starts_with(
+            "manifest.yaml:26:17: warning: script 'build' has more than one step named 'compile'"
+        ));
+    }
+
+    #[test]
+    fn test_lint_parse_error_has_location() {
+        let diagnostics =
+            lint_manifest("repos:\n  app: [\n", Path::new("."), Path::new("."), false);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].location.is_some());
+    }
+
+    #[test]
+    fn test_branch_names() {
+        for valid in ["main", "release/1.x", "feat/a-b_c"] {
+            assert!(is_valid_branch_name(valid), "{}", valid);
+        }
+        for invalid in [
+            "",
+            "-x",
+            "a..b",
+            "a b",
+            "a/",
+            "a.lock",
+            "x/.hidden",
+            "a@{b",
+            "@",
+        ] {
+            assert!(!is_valid_branch_name(invalid), "{}", invalid);
+        }
+    }
+}
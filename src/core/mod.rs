@@ -1,9 +1,22 @@
 //! Core business logic for gitgrip
 
+pub mod audit;
+pub mod codeowners;
 pub mod griptree;
+pub mod lock;
 pub mod manifest;
+pub mod manifest_diff;
+pub mod manifest_lint;
+pub mod nested;
+pub mod registry;
 pub mod repo;
+pub mod scope;
+pub mod secrets;
 pub mod state;
+pub mod template;
+pub mod ticket;
+pub mod unmanaged;
+pub mod version;
 
 pub use manifest::Manifest;
 pub use repo::RepoInfo;
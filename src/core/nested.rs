@@ -0,0 +1,129 @@
+//! Nested workspaces
+//!
+//! A manifest entry with `workspace: true` is another gitgrip workspace
+//! rather than a repo: its URL is that workspace's manifest repo, cloned into
+//! `<path>/.gitgrip/manifests`. `gr sync` recurses into it, and `gr status`
+//! lists its repos as `<entry>/<repo>`. A nested workspace is a normal
+//! workspace too, so `gr` run inside it only sees its own repos.
+
+use crate::core::manifest::Manifest;
+use crate::core::repo::{resolve_repo_path, RepoInfo};
+use crate::workspace::Workspace;
+use std::path::{Path, PathBuf};
+
+/// How many workspaces deep nesting may go, to stop manifests that
+/// (directly or not) include themselves
+pub const MAX_NESTING: usize = 4;
+
+/// Name and root of each nested workspace entry, sorted by name
+pub fn nested_workspaces(workspace_root: &Path, manifest: &Manifest) -> Vec<(String, PathBuf)> {
+    let mut nested: Vec<(String, PathBuf)> = manifest
+        .repos
+        .iter()
+        .filter(|(_, config)| config.workspace)
+        .map(|(name, config)| {
+            (
+                name.clone(),
+                resolve_repo_path(workspace_root, &config.path),
+            )
+        })
+        .collect();
+    nested.sort();
+    nested
+}
+
+/// How many workspaces `root` is nested inside
+pub fn nesting_depth(root: &Path) -> usize {
+    root.ancestors()
+        .skip(1)
+        .filter(|dir| dir.join(".gitgrip").join("manifests").is_dir())
+        .count()
+}
+
+/// Repos of every nested workspace that has been synced, recursively, named
+/// `<entry>/<repo>` with paths relative to `workspace_root`
+pub fn nested_repos(workspace_root: &Path, manifest: &Manifest) -> Vec<RepoInfo> {
+    collect_nested(workspace_root, manifest, 0)
+}
+
+fn collect_nested(workspace_root: &Path, manifest: &Manifest, depth: usize) -> Vec<RepoInfo> {
+    if depth + 1 >= MAX_NESTING {
+        return Vec::new();
+    }
+    let mut repos = Vec::new();
+    for (name, root) in nested_workspaces(workspace_root, manifest) {
+        let Ok(workspace) = Workspace::open(&root) else {
+            continue;
+        };
+        let prefix = manifest.repos[&name].path.trim_end_matches('/').to_string();
+        let mut children = workspace.repos();
+        children.extend(collect_nested(
+            workspace.root(),
+            workspace.manifest(),
+            depth + 1,
+        ));
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        repos.extend(children.into_iter().map(|mut repo| {
+            repo.name = format!("{}/{}", name, repo.name);
+            repo.path = format!("{}/{}", prefix, repo.path.trim_start_matches("./"));
+            repo
+        }));
+    }
+    repos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nested_repos() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  app:
+    url: https://github.com/acme/app.git
+    path: app
+  platform:
+    url: https://github.com/acme/platform-manifest.git
+    path: teams/platform
+    workspace: true
+"#,
+        )
+        .unwrap();
+        assert!(RepoInfo::from_config(
+            "platform",
+            &manifest.repos["platform"],
+            &root.to_path_buf()
+        )
+        .is_none());
+        // Not synced yet
+        assert!(nested_repos(root, &manifest).is_empty());
+
+        let child = root.join("teams/platform");
+        let manifests = child.join(".gitgrip/manifests");
+        std::fs::create_dir_all(&manifests).unwrap();
+        std::fs::write(
+            manifests.join("manifest.yaml"),
+            r#"
+repos:
+  api:
+    url: https://github.com/acme/api.git
+    path: ./api
+"#,
+        )
+        .unwrap();
+
+        let repos = nested_repos(root, &manifest);
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "platform/api");
+        assert_eq!(repos[0].path, "teams/platform/api");
+        assert_eq!(repos[0].absolute_path, child.join("api"));
+        assert_eq!(nesting_depth(&child), 0);
+        std::fs::create_dir_all(root.join(".gitgrip/manifests")).unwrap();
+        assert_eq!(nesting_depth(&child), 1);
+    }
+}
@@ -0,0 +1,216 @@
+//! User-level registry of known workspaces
+//!
+//! Every workspace `gr init` creates or `gr sync` runs in is recorded by name
+//! in `~/.gitgrip/workspaces.json` (or `$GITGRIP_HOME/workspaces.json`), so
+//! `gr -w <name> ...` can run against a workspace from anywhere and
+//! `gr workspace run` can act on all of them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur with the workspace registry
+#[derive(Error, Debug)]
+pub enum RegistryError {
+    #[error("Failed to read workspace registry: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Failed to parse workspace registry: {0}")]
+    ParseError(#[from] serde_json::Error),
+
+    #[error("Unknown workspace '{name}' (known: {known})")]
+    NotFound { name: String, known: String },
+
+    #[error("Workspace name '{0}' is already used for {1}")]
+    NameTaken(String, String),
+
+    #[error("No home directory; set GITGRIP_HOME")]
+    NoHome,
+}
+
+/// Known workspaces, by name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceRegistry {
+    /// Map: workspace name -> absolute root path
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, PathBuf>,
+    /// Workspace set by `gr workspace switch`, used outside any workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+}
+
+impl WorkspaceRegistry {
+    /// Where the registry lives: `$GITGRIP_HOME`, else `~/.gitgrip`
+    pub fn default_path() -> Result<PathBuf, RegistryError> {
+        if let Some(home) = std::env::var_os("GITGRIP_HOME").filter(|v| !v.is_empty()) {
+            return Ok(PathBuf::from(home).join("workspaces.json"));
+        }
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .filter(|v| !v.is_empty())
+            .map(|home| PathBuf::from(home).join(".gitgrip").join("workspaces.json"))
+            .ok_or(RegistryError::NoHome)
+    }
+
+    /// Load the registry from a JSON file, empty if it doesn't exist
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RegistryError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the registry to a JSON file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RegistryError> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Name a workspace is registered under
+    pub fn name_of(&self, root: &Path) -> Option<&str> {
+        self.workspaces
+            .iter()
+            .find(|(_, path)| path.as_path() == root)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Record a workspace, returning its name
+    ///
+    /// Without a `name`, an already registered root keeps its name and a new
+    /// one is named after its directory (`api`, `api-2`, ...). An explicit
+    /// `name` renames the root's entry.
+    pub fn register(&mut self, root: &Path, name: Option<&str>) -> Result<String, RegistryError> {
+        let existing = self.name_of(root).map(str::to_string);
+        let name = match (name, existing) {
+            (None, Some(existing)) => return Ok(existing),
+            (Some(name), _) => {
+                match self.workspaces.get(name) {
+                    Some(path) if path.as_path() != root => {
+                        return Err(RegistryError::NameTaken(
+                            name.to_string(),
+                            path.display().to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+                name.to_string()
+            }
+            (None, None) => self.unused_name(root),
+        };
+
+        if let Some(old) = self.name_of(root).map(str::to_string) {
+            self.workspaces.remove(&old);
+            if self.current.as_deref() == Some(old.as_str()) {
+                self.current = Some(name.clone());
+            }
+        }
+        self.workspaces.insert(name.clone(), root.to_path_buf());
+        Ok(name)
+    }
+
+    /// Forget a workspace (its files are left alone)
+    pub fn remove(&mut self, name: &str) -> Result<PathBuf, RegistryError> {
+        let path = self
+            .workspaces
+            .remove(name)
+            .ok_or_else(|| self.not_found(name))?;
+        if self.current.as_deref() == Some(name) {
+            self.current = None;
+        }
+        Ok(path)
+    }
+
+    /// Root of the workspace called `name`
+    pub fn resolve(&self, name: &str) -> Result<&Path, RegistryError> {
+        self.workspaces
+            .get(name)
+            .map(PathBuf::as_path)
+            .ok_or_else(|| self.not_found(name))
+    }
+
+    fn not_found(&self, name: &str) -> RegistryError {
+        let known: Vec<&str> = self.workspaces.keys().map(String::as_str).collect();
+        RegistryError::NotFound {
+            name: name.to_string(),
+            known: if known.is_empty() {
+                "none".to_string()
+            } else {
+                known.join(", ")
+            },
+        }
+    }
+
+    fn unused_name(&self, root: &Path) -> String {
+        let base = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "workspace".to_string());
+        (1..)
+            .map(|n| {
+                if n == 1 {
+                    base.clone()
+                } else {
+                    format!("{}-{}", base, n)
+                }
+            })
+            .find(|name| !self.workspaces.contains_key(name))
+            .unwrap_or(base)
+    }
+}
+
+/// Record `root` in the user's registry, ignoring failures
+///
+/// Called by commands that create or sync a workspace; the registry is a
+/// convenience and never worth failing them over.
+pub fn remember_workspace(root: &Path) {
+    let Ok(path) = WorkspaceRegistry::default_path() else {
+        return;
+    };
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let Ok(mut registry) = WorkspaceRegistry::load(&path) else {
+        return;
+    };
+    if registry.name_of(&root).is_some() {
+        return;
+    }
+    if registry.register(&root, None).is_ok() {
+        let _ = registry.save(&path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_names() {
+        let mut registry = WorkspaceRegistry::default();
+        let work = Path::new("/src/work/app");
+        let oss = Path::new("/src/oss/app");
+
+        assert_eq!(registry.register(work, None).unwrap(), "app");
+        assert_eq!(registry.register(oss, None).unwrap(), "app-2");
+        assert_eq!(registry.register(work, None).unwrap(), "app");
+
+        registry.current = Some("app-2".to_string());
+        assert_eq!(registry.register(oss, Some("oss")).unwrap(), "oss");
+        assert_eq!(registry.current.as_deref(), Some("oss"));
+        assert_eq!(registry.resolve("oss").unwrap(), oss);
+        assert!(matches!(
+            registry.register(oss, Some("app")),
+            Err(RegistryError::NameTaken(..))
+        ));
+
+        registry.remove("oss").unwrap();
+        assert!(registry.current.is_none());
+        let err = registry.resolve("oss").unwrap_err().to_string();
+        assert_eq!(err, "Unknown workspace 'oss' (known: app)");
+    }
+}
@@ -1,6 +1,6 @@
 //! Repository information and operations
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::core::manifest::{PlatformType, RepoConfig};
 
@@ -29,10 +29,15 @@ pub struct RepoInfo {
 
 impl RepoInfo {
     /// Create RepoInfo from a manifest RepoConfig
+    ///
+    /// Returns `None` for nested workspace entries, which aren't repos
     pub fn from_config(name: &str, config: &RepoConfig, workspace_root: &PathBuf) -> Option<Self> {
+        if config.workspace {
+            return None;
+        }
         let parsed = parse_git_url(&config.url)?;
 
-        let absolute_path = workspace_root.join(&config.path);
+        let absolute_path = resolve_repo_path(workspace_root, &config.path);
 
         let platform_type = config
             .platform
@@ -59,6 +64,21 @@ impl RepoInfo {
     }
 }
 
+/// Resolve a manifest path against the workspace root
+///
+/// Splits on both `/` and `\` and drops `.` segments so the result uses native
+/// separators on every platform.
+pub fn resolve_repo_path(workspace_root: &Path, path: &str) -> PathBuf {
+    let mut resolved = workspace_root.to_path_buf();
+    for segment in path.split(['/', '\\']) {
+        if segment.is_empty() || segment == "." {
+            continue;
+        }
+        resolved.push(segment);
+    }
+    resolved
+}
+
 /// Parsed git URL components
 struct ParsedUrl {
     owner: String,
@@ -210,6 +230,20 @@ mod tests {
         assert_eq!(parsed.project, Some("project".to_string()));
     }
 
+    #[test]
+    fn test_resolve_repo_path() {
+        let root = PathBuf::from("ws");
+        assert_eq!(
+            resolve_repo_path(&root, "./libs/core"),
+            root.join("libs").join("core")
+        );
+        assert_eq!(
+            resolve_repo_path(&root, "libs\\core"),
+            root.join("libs").join("core")
+        );
+        assert_eq!(resolve_repo_path(&root, "app"), root.join("app"));
+    }
+
     #[test]
     fn test_detect_github() {
         assert_eq!(
@@ -0,0 +1,108 @@
+//! Path-scoped workspaces
+//!
+//! Narrows a manifest to the repos whose changes touch given path globs, so
+//! commands like `gr pr create --paths 'proto/**'` only act on repos affected
+//! by a change.
+
+use crate::core::manifest::Manifest;
+use crate::core::repo::RepoInfo;
+use crate::git::branch::get_branch_changed_files;
+use crate::git::status::get_changed_files;
+use crate::git::{open_repo, path_exists};
+use crate::util::glob::PathGlob;
+use std::path::PathBuf;
+
+/// Files changed in a repo: commits on HEAD since the default branch plus
+/// uncommitted changes
+pub fn repo_changed_files(repo: &RepoInfo) -> Vec<String> {
+    if !path_exists(&repo.absolute_path) {
+        return Vec::new();
+    }
+    let Ok(git_repo) = open_repo(&repo.absolute_path) else {
+        return Vec::new();
+    };
+
+    let mut files =
+        get_branch_changed_files(&git_repo, &repo.default_branch, "HEAD").unwrap_or_default();
+    for file in get_changed_files(&git_repo).unwrap_or_default() {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    files
+}
+
+/// Drop repos from the manifest whose changed files match none of `globs`
+///
+/// Returns the names of the repos that were kept.
+pub fn scope_to_paths(
+    workspace_root: &PathBuf,
+    manifest: &mut Manifest,
+    globs: &[String],
+) -> anyhow::Result<Vec<String>> {
+    let globs = globs
+        .iter()
+        .map(|g| PathGlob::new(g).map_err(|e| anyhow::anyhow!("Invalid glob '{}': {}", g, e)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    manifest.repos.retain(|name, config| {
+        RepoInfo::from_config(name, config, workspace_root)
+            .map(|repo| {
+                repo_changed_files(&repo)
+                    .iter()
+                    .any(|file| globs.iter().any(|g| g.matches(file)))
+            })
+            .unwrap_or(false)
+    });
+
+    let mut kept: Vec<String> = manifest.repos.keys().cloned().collect();
+    kept.sort();
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{self, git};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn init_repo(path: &Path) {
+        testing::init_repo(path);
+        git(path, &["commit", "--allow-empty", "-m", "Initial commit"]);
+    }
+
+    #[test]
+    fn test_scope_to_paths() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        init_repo(&root.join("api"));
+        init_repo(&root.join("web"));
+
+        // api changes a proto file on a branch; web only has an uncommitted README
+        let api = root.join("api");
+        git(&api, &["checkout", "-b", "feat/proto"]);
+        std::fs::create_dir(api.join("proto")).unwrap();
+        std::fs::write(api.join("proto/service.proto"), "syntax = \"proto3\";").unwrap();
+        git(&api, &["add", "."]);
+        git(&api, &["commit", "-m", "Add service"]);
+        std::fs::write(root.join("web/README.md"), "# web").unwrap();
+
+        let mut manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+  web:
+    url: git@github.com:acme/web.git
+    path: web
+"#,
+        )
+        .unwrap();
+
+        let kept = scope_to_paths(&root, &mut manifest, &["proto/**".to_string()]).unwrap();
+        assert_eq!(kept, vec!["api".to_string()]);
+        assert!(!manifest.repos.contains_key("web"));
+    }
+}
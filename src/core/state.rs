@@ -50,6 +50,87 @@ pub struct LinkedPR {
     pub check_details: Option<CheckStatusDetails>,
 }
 
+/// A multi-repo bisect in progress
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectState {
+    /// Repository being bisected
+    pub repo: String,
+    /// Map: repo name -> branch (or commit) checked out before bisecting
+    pub original_refs: HashMap<String, String>,
+}
+
+/// Progress of a `gr sync` that hasn't finished cleanly
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    /// Repos already cloned or pulled
+    #[serde(default)]
+    pub done: Vec<String>,
+    /// Repos being cloned; a leftover directory for one may be a partial clone
+    #[serde(default)]
+    pub cloning: Vec<String>,
+}
+
+/// A PR opened by a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignPR {
+    /// Repository name (from manifest)
+    pub repo_name: String,
+    /// Owner/namespace from git URL
+    pub owner: String,
+    /// Repo name from git URL
+    pub repo: String,
+    /// PR number
+    pub number: u64,
+    /// PR URL
+    pub url: String,
+    /// Last known PR state
+    pub state: PRState,
+}
+
+/// A batch change applied across repos by `gr campaign run`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignState {
+    /// Shared PR title (also the commit message)
+    pub title: String,
+    /// Shared PR body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// PRs opened by the campaign
+    #[serde(default)]
+    pub prs: Vec<CampaignPR>,
+}
+
+/// A PR of a PR group
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPR {
+    /// Repository name, plus the group for split PRs
+    pub repo_name: String,
+    pub number: u64,
+    pub url: String,
+    #[serde(default)]
+    pub merged: bool,
+}
+
+/// The PRs opened together from one branch, kept after merging for `gr changelog`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRGroup {
+    pub branch: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub prs: Vec<GroupPR>,
+    /// When the last of the PRs merged (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_at: Option<String>,
+}
+
 /// The persistent state file structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -63,6 +144,33 @@ pub struct StateFile {
     /// Map: manifest PR number -> linked PRs
     #[serde(default)]
     pub pr_links: HashMap<String, Vec<LinkedPR>>,
+    /// Multi-repo bisect in progress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bisect: Option<BisectState>,
+    /// Map: campaign branch -> campaign
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub campaigns: HashMap<String, CampaignState>,
+    /// Map: branch started with `gr branch start` -> repos it was started in
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub branch_repos: HashMap<String, Vec<String>>,
+    /// Map: branch started with `gr branch start --ticket` -> ticket ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub branch_tickets: HashMap<String, String>,
+    /// Manifest repo commit the workspace was last synced against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync_manifest: Option<String>,
+    /// Map: repo name -> path of every checkout sync has cloned or pulled
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub managed_repos: HashMap<String, String>,
+    /// Manifest profile the workspace is limited to (`gr init --profile`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Sync interrupted or partly failed, for `gr sync --continue`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_progress: Option<SyncProgress>,
+    /// PR groups opened by `gr pr create` or merged by `gr pr merge`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pr_groups: Vec<PRGroup>,
 }
 
 impl StateFile {
@@ -139,6 +247,86 @@ impl StateFile {
         if let Some(pr_number) = self.branch_to_pr.remove(branch) {
             self.pr_links.remove(&pr_number.to_string());
         }
+        self.branch_repos.remove(branch);
+        self.branch_tickets.remove(branch);
+    }
+
+    /// Move everything recorded for branch `old` to `new`
+    pub fn rename_branch(&mut self, old: &str, new: &str) {
+        if let Some(pr_number) = self.branch_to_pr.remove(old) {
+            self.branch_to_pr.insert(new.to_string(), pr_number);
+        }
+        if let Some(repos) = self.branch_repos.remove(old) {
+            self.branch_repos.insert(new.to_string(), repos);
+        }
+        if let Some(ticket) = self.branch_tickets.remove(old) {
+            self.branch_tickets.insert(new.to_string(), ticket);
+        }
+        if let Some(campaign) = self.campaigns.remove(old) {
+            self.campaigns.insert(new.to_string(), campaign);
+        }
+    }
+
+    /// Repos a branch was started in, if it was started with `gr branch start`
+    pub fn get_branch_repos(&self, branch: &str) -> Option<&Vec<String>> {
+        self.branch_repos.get(branch)
+    }
+
+    /// Record the repos a branch was started in
+    pub fn set_branch_repos(&mut self, branch: &str, repos: Vec<String>) {
+        self.branch_repos.insert(branch.to_string(), repos);
+    }
+
+    /// Ticket a branch was started with, if it was started with `--ticket`
+    pub fn get_branch_ticket(&self, branch: &str) -> Option<&String> {
+        self.branch_tickets.get(branch)
+    }
+
+    /// Record the ticket a branch was started with
+    pub fn set_branch_ticket(&mut self, branch: &str, ticket: &str) {
+        self.branch_tickets
+            .insert(branch.to_string(), ticket.to_string());
+    }
+
+    /// Record a newly opened PR group, replacing the unmerged one of its branch
+    pub fn record_pr_group(&mut self, group: PRGroup) {
+        match self
+            .pr_groups
+            .iter_mut()
+            .find(|g| g.branch == group.branch && g.merged_at.is_none())
+        {
+            Some(existing) => *existing = group,
+            None => self.pr_groups.push(group),
+        }
+    }
+
+    /// Mark a PR of the branch's unmerged group merged, starting a group
+    /// titled `title` if none was recorded; the group is merged at `at` once
+    /// all of its PRs are
+    pub fn record_group_merge(&mut self, branch: &str, pr: GroupPR, title: &str, at: &str) {
+        let index = match self
+            .pr_groups
+            .iter()
+            .position(|g| g.branch == branch && g.merged_at.is_none())
+        {
+            Some(index) => index,
+            None => {
+                self.pr_groups.push(PRGroup {
+                    branch: branch.to_string(),
+                    title: title.to_string(),
+                    ..Default::default()
+                });
+                self.pr_groups.len() - 1
+            }
+        };
+        let group = &mut self.pr_groups[index];
+        match group.prs.iter_mut().find(|p| p.url == pr.url) {
+            Some(existing) => existing.merged = true,
+            None => group.prs.push(GroupPR { merged: true, ..pr }),
+        }
+        if group.prs.iter().all(|p| p.merged) {
+            group.merged_at = Some(at.to_string());
+        }
     }
 
     /// Check if all linked PRs are ready to merge
@@ -210,6 +398,128 @@ mod tests {
         assert!(state.get_pr_for_branch("feat/test").is_none());
     }
 
+    #[test]
+    fn test_branch_repos() {
+        let mut state = StateFile::default();
+        assert!(!serde_json::to_string(&state)
+            .unwrap()
+            .contains("branchRepos"));
+
+        state.set_branch_repos("feat/login", vec!["api".to_string(), "web".to_string()]);
+        let parsed = StateFile::parse(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert_eq!(parsed.get_branch_repos("feat/login").unwrap().len(), 2);
+
+        state.set_pr_for_branch("feat/login", 12);
+        state.set_branch_ticket("feat/login", "PROJ-7");
+        state.rename_branch("feat/login", "feat/sign-in");
+        assert_eq!(state.get_pr_for_branch("feat/sign-in"), Some(12));
+        assert_eq!(state.get_branch_repos("feat/sign-in").unwrap().len(), 2);
+        assert_eq!(
+            state.get_branch_ticket("feat/sign-in").map(String::as_str),
+            Some("PROJ-7")
+        );
+
+        state.remove_branch("feat/sign-in");
+        assert!(state.get_branch_repos("feat/sign-in").is_none());
+        assert!(state.get_branch_ticket("feat/sign-in").is_none());
+    }
+
+    #[test]
+    fn test_bisect_state_roundtrip() {
+        let mut state = StateFile::default();
+        assert!(!serde_json::to_string(&state).unwrap().contains("bisect"));
+
+        let mut original_refs = HashMap::new();
+        original_refs.insert("lib".to_string(), "main".to_string());
+        state.bisect = Some(BisectState {
+            repo: "app".to_string(),
+            original_refs,
+        });
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains("originalRefs"));
+        let parsed = StateFile::parse(&json).unwrap();
+        assert_eq!(parsed.bisect.unwrap().repo, "app");
+    }
+
+    #[test]
+    fn test_campaign_state_roundtrip() {
+        let mut state = StateFile::default();
+        assert!(!serde_json::to_string(&state).unwrap().contains("campaigns"));
+
+        state.campaigns.insert(
+            "chore/bump-deps".to_string(),
+            CampaignState {
+                title: "Bump dependencies".to_string(),
+                body: None,
+                prs: vec![CampaignPR {
+                    repo_name: "app".to_string(),
+                    owner: "user".to_string(),
+                    repo: "app".to_string(),
+                    number: 7,
+                    url: "https://github.com/user/app/pull/7".to_string(),
+                    state: PRState::Merged,
+                }],
+            },
+        );
+
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed = StateFile::parse(&json).unwrap();
+        let campaign = &parsed.campaigns["chore/bump-deps"];
+        assert_eq!(campaign.prs[0].number, 7);
+        assert_eq!(campaign.prs[0].state, PRState::Merged);
+    }
+
+    #[test]
+    fn test_pr_group_merge() {
+        let pr = |repo: &str, number: u64| GroupPR {
+            repo_name: repo.to_string(),
+            number,
+            url: format!("https://github.com/user/{}/pull/{}", repo, number),
+            merged: false,
+        };
+        let mut state = StateFile::default();
+        state.record_pr_group(PRGroup {
+            branch: "feat/login".to_string(),
+            title: "feat: Add login".to_string(),
+            labels: vec!["auth".to_string()],
+            prs: vec![pr("app", 1), pr("api", 2)],
+            merged_at: None,
+        });
+
+        state.record_group_merge(
+            "feat/login",
+            pr("app", 1),
+            "ignored",
+            "2026-05-01T10:00:00Z",
+        );
+        assert!(state.pr_groups[0].merged_at.is_none());
+        state.record_group_merge(
+            "feat/login",
+            pr("api", 2),
+            "ignored",
+            "2026-05-01T11:00:00Z",
+        );
+        assert_eq!(
+            state.pr_groups[0].merged_at.as_deref(),
+            Some("2026-05-01T11:00:00Z")
+        );
+
+        // The branch name reused later starts a new group
+        state.record_group_merge(
+            "feat/login",
+            pr("app", 9),
+            "Fix login",
+            "2026-06-01T00:00:00Z",
+        );
+        assert_eq!(state.pr_groups.len(), 2);
+        assert_eq!(state.pr_groups[1].title, "Fix login");
+        assert!(state.pr_groups[1].merged_at.is_some());
+
+        let parsed = StateFile::parse(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert_eq!(parsed.pr_groups[0].labels, vec!["auth".to_string()]);
+    }
+
     #[test]
     fn test_all_linked_prs_ready() {
         let mut state = StateFile::default();
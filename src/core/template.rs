@@ -0,0 +1,178 @@
+//! `${VAR}` expansion in manifest values
+//!
+//! Repo URLs and paths may reference environment variables, so one manifest
+//! can serve users who clone over SSH or HTTPS, or from different org
+//! prefixes. Two names are always defined: `WORKSPACE_ROOT` and `USERNAME`.
+//!
+//! - `${NAME}` expands to the variable's value; an undefined variable is an error
+//! - `${NAME:-default}` expands to `default` when `NAME` is undefined or empty
+//! - `$$` is a literal `$`
+
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors from expanding a template
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("undefined variable '{0}' (set it, or give a default with ${{{0}:-value}})")]
+    Undefined(String),
+
+    #[error("invalid variable name '{0}'")]
+    InvalidName(String),
+
+    #[error("unterminated '${{'")]
+    Unterminated,
+}
+
+/// Variables available to manifest templates
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    builtins: HashMap<String, String>,
+    /// Whether to fall back to the process environment
+    use_env: bool,
+}
+
+impl TemplateContext {
+    /// Built-ins for a workspace at `workspace_root` (if known), plus the environment
+    pub fn new(workspace_root: Option<&Path>) -> Self {
+        let mut builtins = HashMap::new();
+        if let Some(root) = workspace_root {
+            builtins.insert(
+                "WORKSPACE_ROOT".to_string(),
+                root.to_string_lossy().to_string(),
+            );
+        }
+        if let Some(user) = std::env::var("USER")
+            .ok()
+            .or_else(|| std::env::var("USERNAME").ok())
+        {
+            builtins.insert("USERNAME".to_string(), user);
+        }
+        Self {
+            builtins,
+            use_env: true,
+        }
+    }
+
+    /// Only the given variables, ignoring the environment
+    pub fn from_vars<K: Into<String>, V: Into<String>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        Self {
+            builtins: vars
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            use_env: false,
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.builtins.get(name).cloned().or_else(|| {
+            if self.use_env {
+                std::env::var(name).ok()
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Expand every `${...}` reference in `input`
+    pub fn expand(&self, input: &str) -> Result<String, TemplateError> {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(pos) = rest.find('$') {
+            out.push_str(&rest[..pos]);
+            let after = &rest[pos + 1..];
+            if let Some(tail) = after.strip_prefix('$') {
+                out.push('$');
+                rest = tail;
+                continue;
+            }
+            let Some(body) = after.strip_prefix('{') else {
+                out.push('$');
+                rest = after;
+                continue;
+            };
+            let end = body.find('}').ok_or(TemplateError::Unterminated)?;
+            let (name, default) = match body[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&body[..end], None),
+            };
+            if !is_valid_name(name) {
+                return Err(TemplateError::InvalidName(name.to_string()));
+            }
+            match (self.lookup(name), default) {
+                (Some(value), Some(default)) if value.is_empty() => out.push_str(default),
+                (Some(value), _) => out.push_str(&value),
+                (None, Some(default)) => out.push_str(default),
+                (None, None) => return Err(TemplateError::Undefined(name.to_string())),
+            }
+            rest = &body[end + 1..];
+        }
+        out.push_str(rest);
+
+        Ok(out)
+    }
+}
+
+impl Default for TemplateContext {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let ctx = TemplateContext::from_vars([("ORG", "acme"), ("EMPTY", "")]);
+        assert_eq!(
+            ctx.expand("git@github.com:${ORG}/app.git").unwrap(),
+            "git@github.com:acme/app.git"
+        );
+        assert_eq!(
+            ctx.expand("${GIT_HOST:-https://github.com/}${ORG}/app")
+                .unwrap(),
+            "https://github.com/acme/app"
+        );
+        assert_eq!(ctx.expand("${EMPTY:-x}").unwrap(), "x");
+        assert_eq!(
+            ctx.expand("cost $5 and $${ORG}").unwrap(),
+            "cost $5 and ${ORG}"
+        );
+        assert_eq!(ctx.expand("plain").unwrap(), "plain");
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let ctx = TemplateContext::from_vars([("ORG", "acme")]);
+        assert_eq!(
+            ctx.expand("${NOPE}/app"),
+            Err(TemplateError::Undefined("NOPE".to_string()))
+        );
+        assert_eq!(ctx.expand("${ORG"), Err(TemplateError::Unterminated));
+        assert_eq!(
+            ctx.expand("${1BAD}"),
+            Err(TemplateError::InvalidName("1BAD".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_builtins() {
+        let ctx = TemplateContext::new(Some(Path::new("/work/ws")));
+        assert_eq!(ctx.expand("${WORKSPACE_ROOT}/x").unwrap(), "/work/ws/x");
+    }
+}
@@ -0,0 +1,190 @@
+//! Branch-to-ticket mapping
+//!
+//! `gr branch start --ticket` names branches after an issue-tracker ticket
+//! using `settings.tickets.branch_format`, and `gr pr create` puts the ticket
+//! in PR titles and links it from PR bodies.
+
+use crate::core::manifest::{TicketSettings, TrackerKind};
+use crate::core::state::StateFile;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Branch format used when a ticket is given but none is configured
+pub const DEFAULT_BRANCH_FORMAT: &str = "{ticket}-{slug}";
+
+/// Longest slug a branch name gets
+const MAX_SLUG_LEN: usize = 50;
+
+/// A Jira-style key such as `PROJ-123`
+static TICKET_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Z][A-Z0-9]+-[0-9]+\b").unwrap());
+
+/// Placeholders a branch format may use
+const PLACEHOLDERS: [&str; 2] = ["{ticket}", "{slug}"];
+
+/// Check that a branch format only uses known placeholders
+pub fn validate_branch_format(format: &str) -> Result<(), String> {
+    let mut rest = format.to_string();
+    for placeholder in PLACEHOLDERS {
+        rest = rest.replace(placeholder, "");
+    }
+    if let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map_or(rest.len(), |i| start + i + 1);
+        return Err(format!(
+            "unknown placeholder '{}' (expected {{ticket}} or {{slug}})",
+            &rest[start..end]
+        ));
+    }
+    if !format.contains("{ticket}") {
+        return Err("must contain {ticket}".to_string());
+    }
+    Ok(())
+}
+
+/// Lowercase words joined by `-`, cut to a branch-friendly length
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for word in text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if !slug.is_empty() && slug.len() + 1 + word.len() > MAX_SLUG_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    slug
+}
+
+/// The branch name for `ticket`, with an optional description for `{slug}`
+///
+/// Separators left dangling by an empty slug are dropped, so
+/// `feat/{ticket}-{slug}` gives `feat/PROJ-1` without a description.
+pub fn branch_name(format: &str, ticket: &str, description: Option<&str>) -> String {
+    let slug = description.map(slugify).unwrap_or_default();
+    let mut name = format.replace("{ticket}", ticket).replace("{slug}", &slug);
+    for (doubled, single) in [("--", "-"), ("__", "_"), ("//", "/"), ("-/", "/")] {
+        while name.contains(doubled) {
+            name = name.replace(doubled, single);
+        }
+    }
+    name.trim_matches(|c| matches!(c, '-' | '_' | '/' | '.'))
+        .to_string()
+}
+
+/// The first ticket ID in a branch name, for branches not started with `--ticket`
+pub fn find_ticket(branch: &str) -> Option<String> {
+    TICKET_ID.find(branch).map(|m| m.as_str().to_string())
+}
+
+/// The ticket a branch belongs to: the one it was started with, else, once
+/// `settings.tickets` is configured, an ID in its name
+pub fn branch_ticket(state: &StateFile, settings: &TicketSettings, branch: &str) -> Option<String> {
+    if let Some(ticket) = state.get_branch_ticket(branch) {
+        return Some(ticket.clone());
+    }
+    if settings.branch_format.is_none() && settings.url.is_none() && settings.tracker.is_none() {
+        return None;
+    }
+    find_ticket(branch)
+}
+
+/// The ticket's URL, from `settings.tickets.url` or else a Jira tracker's site
+pub fn ticket_url(settings: &TicketSettings, ticket: &str) -> Option<String> {
+    if let Some(ref url) = settings.url {
+        return Some(url.replace("{ticket}", ticket));
+    }
+    let tracker = settings.tracker.as_ref()?;
+    match (tracker.kind, tracker.base_url.as_deref()) {
+        (TrackerKind::Jira, Some(base_url)) => Some(format!(
+            "{}/browse/{}",
+            base_url.trim_end_matches('/'),
+            ticket
+        )),
+        _ => None,
+    }
+}
+
+/// A PR title with the ticket in front, unless it already mentions it
+pub fn title_with_ticket(title: &str, ticket: &str) -> String {
+    if title.contains(ticket) {
+        title.to_string()
+    } else {
+        format!("{}: {}", ticket, title)
+    }
+}
+
+/// A PR body that opens with a link to the ticket, unless it already mentions it
+pub fn body_with_ticket(body: Option<&str>, ticket: &str, url: Option<&str>) -> String {
+    let line = match url {
+        Some(url) => format!("Ticket: [{}]({})", ticket, url),
+        None => format!("Ticket: {}", ticket),
+    };
+    match body {
+        Some(body) if body.contains(ticket) => body.to_string(),
+        Some(body) if !body.trim().is_empty() => format!("{}\n\n{}", line, body),
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_name() {
+        assert_eq!(
+            branch_name(
+                "feat/{ticket}-{slug}",
+                "PROJ-12",
+                Some("Fix the login timeout!")
+            ),
+            "feat/PROJ-12-fix-the-login-timeout"
+        );
+        assert_eq!(
+            branch_name("feat/{ticket}-{slug}", "PROJ-12", None),
+            "feat/PROJ-12"
+        );
+        assert_eq!(branch_name("{slug}/{ticket}", "PROJ-12", None), "PROJ-12");
+        assert!(slugify(&"word ".repeat(30)).len() <= MAX_SLUG_LEN);
+        assert!(!slugify(&"word ".repeat(30)).ends_with('-'));
+
+        assert!(validate_branch_format("feat/{ticket}-{slug}").is_ok());
+        assert!(validate_branch_format("feat/{slug}").is_err());
+        assert!(validate_branch_format("{user}/{ticket}").is_err());
+
+        assert_eq!(
+            find_ticket("feat/PROJ-12-login"),
+            Some("PROJ-12".to_string())
+        );
+        assert_eq!(find_ticket("feat/login"), None);
+    }
+
+    #[test]
+    fn test_ticket_in_pr() {
+        assert_eq!(
+            title_with_ticket("Fix login", "PROJ-12"),
+            "PROJ-12: Fix login"
+        );
+        assert_eq!(
+            title_with_ticket("PROJ-12 Fix login", "PROJ-12"),
+            "PROJ-12 Fix login"
+        );
+
+        let url = "https://acme.atlassian.net/browse/PROJ-12";
+        assert_eq!(
+            body_with_ticket(Some("- Fix login"), "PROJ-12", Some(url)),
+            format!("Ticket: [PROJ-12]({})\n\n- Fix login", url)
+        );
+        assert_eq!(body_with_ticket(None, "PROJ-12", None), "Ticket: PROJ-12");
+        assert_eq!(
+            body_with_ticket(Some("Closes PROJ-12"), "PROJ-12", Some(url)),
+            "Closes PROJ-12"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Workspace drift detection
+//!
+//! Finds git repos under the workspace root that the manifest doesn't know
+//! about, and managed repos whose `origin` points somewhere other than the
+//! manifest URL.
+
+use crate::core::manifest::Manifest;
+use crate::core::repo::{resolve_repo_path, RepoInfo};
+use crate::git::open_repo;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// How deep below the workspace root to look for unmanaged repos
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Directories never worth descending into
+const SKIP_DIRS: &[&str] = &["node_modules", "target"];
+
+/// A git repo inside the workspace that isn't in the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmanagedRepo {
+    /// Path relative to the workspace root
+    pub path: String,
+    /// URL of its `origin` remote, if any
+    pub url: Option<String>,
+}
+
+/// A managed repo whose checkout has a different `origin` than the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteMismatch {
+    /// Repository name (from manifest)
+    pub repo: String,
+    /// Path relative to the workspace root
+    pub path: String,
+    /// URL in the manifest
+    pub expected: String,
+    /// URL of the checkout's `origin` (None if it has no origin)
+    pub actual: Option<String>,
+}
+
+/// Find git repos under the workspace root that aren't in the manifest
+pub fn find_unmanaged_repos(workspace_root: &Path, manifest: &Manifest) -> Vec<UnmanagedRepo> {
+    let managed: HashSet<PathBuf> = manifest
+        .repos
+        .values()
+        .map(|config| resolve_repo_path(workspace_root, &config.path))
+        .collect();
+
+    let mut found = Vec::new();
+    scan_dir(workspace_root, workspace_root, &managed, 0, &mut found);
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    found
+}
+
+fn scan_dir(
+    root: &Path,
+    dir: &Path,
+    managed: &HashSet<PathBuf>,
+    depth: usize,
+    found: &mut Vec<UnmanagedRepo>,
+) {
+    if depth >= MAX_SCAN_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || name.starts_with('.') || SKIP_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        // Nested repos inside managed repos (submodules, vendored checkouts) are theirs
+        if managed.contains(&path) {
+            continue;
+        }
+
+        if path.join(".git").exists() {
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            found.push(UnmanagedRepo {
+                path: rel,
+                url: origin_url(&path),
+            });
+        } else {
+            scan_dir(root, &path, managed, depth + 1, found);
+        }
+    }
+}
+
+/// Find cloned repos whose `origin` doesn't match the manifest URL
+pub fn find_remote_mismatches(
+    workspace_root: &PathBuf,
+    manifest: &Manifest,
+) -> Vec<RemoteMismatch> {
+    let mut mismatches: Vec<RemoteMismatch> = manifest
+        .repos
+        .iter()
+        .filter_map(|(name, config)| RepoInfo::from_config(name, config, workspace_root))
+        .filter(|repo| repo.exists())
+        .filter_map(|repo| {
+            let actual = origin_url(&repo.absolute_path);
+            match &actual {
+                Some(url) if same_remote(url, &repo.url) => None,
+                _ => Some(RemoteMismatch {
+                    repo: repo.name.clone(),
+                    path: repo.path.clone(),
+                    expected: repo.url.clone(),
+                    actual,
+                }),
+            }
+        })
+        .collect();
+    mismatches.sort_by(|a, b| a.repo.cmp(&b.repo));
+    mismatches
+}
+
+fn origin_url(path: &Path) -> Option<String> {
+    let repo = open_repo(path).ok()?;
+    let remote = repo.find_remote("origin").ok()?;
+    remote.url().map(|u| u.to_string())
+}
+
+/// Whether two remote URLs point at the same repo, treating the SSH and
+/// HTTPS forms (and a trailing `.git`) as equivalent
+pub fn same_remote(a: &str, b: &str) -> bool {
+    normalize_remote(a) == normalize_remote(b)
+}
+
+fn normalize_remote(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/').trim_end_matches(".git");
+    let url = url
+        .strip_prefix("ssh://")
+        .or_else(|| url.strip_prefix("https://"))
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    // Drop a user (git@) and turn scp-style host:path into host/path
+    let url = url.split_once('@').map(|(_, rest)| rest).unwrap_or(url);
+    url.replacen(':', "/", 1).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_same_remote() {
+        assert!(same_remote(
+            "git@github.com:acme/api.git",
+            "https://github.com/acme/api"
+        ));
+        assert!(same_remote(
+            "ssh://git@github.com/acme/api.git",
+            "git@github.com:acme/api.git"
+        ));
+        assert!(!same_remote(
+            "git@github.com:acme/api.git",
+            "git@github.com:fork/api.git"
+        ));
+    }
+
+    #[test]
+    fn test_find_unmanaged_and_mismatched() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        for path in ["api", "libs/extra", "web"] {
+            let dir = root.join(path);
+            std::fs::create_dir_all(&dir).unwrap();
+            Command::new("git")
+                .args(["init", "-q"])
+                .current_dir(&dir)
+                .output()
+                .unwrap();
+        }
+        Command::new("git")
+            .args(["remote", "add", "origin", "git@github.com:fork/api.git"])
+            .current_dir(root.join("api"))
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["remote", "add", "origin", "https://github.com/acme/web"])
+            .current_dir(root.join("web"))
+            .output()
+            .unwrap();
+
+        let manifest = Manifest::parse(
+            r#"
+repos:
+  api:
+    url: git@github.com:acme/api.git
+    path: api
+  web:
+    url: git@github.com:acme/web.git
+    path: ./web
+"#,
+        )
+        .unwrap();
+
+        let unmanaged = find_unmanaged_repos(&root, &manifest);
+        assert_eq!(
+            unmanaged,
+            vec![UnmanagedRepo {
+                path: "libs/extra".to_string(),
+                url: None,
+            }]
+        );
+
+        let mismatches = find_remote_mismatches(&root, &manifest);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].repo, "api");
+        assert_eq!(
+            mismatches[0].actual.as_deref(),
+            Some("git@github.com:fork/api.git")
+        );
+    }
+}
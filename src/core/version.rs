@@ -0,0 +1,157 @@
+//! Workspace version coordination
+//!
+//! The manifest's `versions` section lists, per version identifier, every
+//! file that carries that version. Each file has a regex with a `{version}`
+//! placeholder locating the version in it, with defaults for common package
+//! manifests. `gr version check` reads them all and `gr version bump`
+//! rewrites them together.
+
+use crate::core::manifest::VersionFile;
+use regex::Regex;
+use std::fmt;
+use std::path::Path;
+
+/// Placeholder a version pattern must contain
+pub const PLACEHOLDER: &str = "{version}";
+
+/// What `{version}` matches: `1.2.3` with an optional pre-release or build
+const SEMVER: &str =
+    r"(?P<version>[0-9]+\.[0-9]+\.[0-9]+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)";
+
+/// Which part of a version to bump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl Bump {
+    pub fn parse(level: &str) -> Result<Self, String> {
+        match level {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            other => Err(format!(
+                "Unknown bump '{}' (expected major, minor, or patch)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Patch => "patch",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The next version after `version`; pre-release and build suffixes are
+/// dropped, so bumping `2.0.0-rc.1` by patch gives `2.0.1`
+pub fn bump_version(version: &str, bump: Bump) -> Result<String, String> {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("'{}' is not a semantic version", version))?;
+    let [major, minor, patch] = parts[..] else {
+        return Err(format!("'{}' is not a semantic version", version));
+    };
+    Ok(match bump {
+        Bump::Major => format!("{}.0.0", major + 1),
+        Bump::Minor => format!("{}.{}.0", major, minor + 1),
+        Bump::Patch => format!("{}.{}.{}", major, minor, patch + 1),
+    })
+}
+
+/// The file's pattern, or the default for its file name
+pub fn file_pattern(file: &VersionFile) -> Option<String> {
+    if let Some(ref pattern) = file.pattern {
+        return Some(pattern.clone());
+    }
+    let name = Path::new(&file.path).file_name()?.to_str()?;
+    let pattern = match name {
+        "Cargo.toml" | "pyproject.toml" => r#"(?m)^version\s*=\s*"{version}""#,
+        "package.json" => r#""version"\s*:\s*"{version}""#,
+        _ => return None,
+    };
+    Some(pattern.to_string())
+}
+
+/// Compile a pattern, with `{version}` matching a semantic version
+pub fn version_regex(pattern: &str) -> Result<Regex, String> {
+    if pattern.matches(PLACEHOLDER).count() != 1 {
+        return Err(format!("pattern must contain {} once", PLACEHOLDER));
+    }
+    Regex::new(&pattern.replace(PLACEHOLDER, SEMVER)).map_err(|e| e.to_string())
+}
+
+/// Every version the regex finds in `content`, in order
+pub fn find_versions(content: &str, regex: &Regex) -> Vec<String> {
+    regex
+        .captures_iter(content)
+        .filter_map(|caps| caps.name("version"))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// `content` with every matched version replaced by `version`
+pub fn replace_versions(content: &str, regex: &Regex, version: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for caps in regex.captures_iter(content) {
+        if let Some(m) = caps.name("version") {
+            out.push_str(&content[last..m.start()]);
+            out.push_str(version);
+            last = m.end();
+        }
+    }
+    out.push_str(&content[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_and_replace() {
+        assert_eq!(bump_version("1.4.2", Bump::Major).unwrap(), "2.0.0");
+        assert_eq!(bump_version("1.4.2", Bump::Minor).unwrap(), "1.5.0");
+        assert_eq!(bump_version("2.0.0-rc.1", Bump::Patch).unwrap(), "2.0.1");
+        assert!(bump_version("1.4", Bump::Patch).is_err());
+        assert!(Bump::parse("huge").is_err());
+
+        let cargo = VersionFile {
+            repo: "sdk".to_string(),
+            path: "crates/sdk/Cargo.toml".to_string(),
+            pattern: None,
+        };
+        let regex = version_regex(&file_pattern(&cargo).unwrap()).unwrap();
+        let content = "[package]\nname = \"sdk\"\nversion = \"1.4.2\"\n\n[dependencies]\nserde = { version = \"1.0.0\" }\n";
+        assert_eq!(find_versions(content, &regex), vec!["1.4.2"]);
+        assert_eq!(
+            replace_versions(content, &regex, "1.5.0"),
+            content.replace("\"1.4.2\"", "\"1.5.0\"")
+        );
+
+        let regex = version_regex(r#""@acme/sdk": "\^{version}""#).unwrap();
+        assert_eq!(
+            replace_versions(r#"{"@acme/sdk": "^1.4.2"}"#, &regex, "2.0.0"),
+            r#"{"@acme/sdk": "^2.0.0"}"#
+        );
+        assert!(version_regex("no placeholder").is_err());
+        assert!(file_pattern(&VersionFile {
+            pattern: None,
+            path: "VERSION.txt".to_string(),
+            ..cargo
+        })
+        .is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! C ABI for calling the Rust core from other runtimes
+//!
+//! Built with the `ffi` feature as a shared library:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! Every function takes and returns NUL-terminated UTF-8 strings. Results are
+//! JSON envelopes, either `{"ok": <value>}` or `{"error": "<message>"}`, so
+//! callers (e.g. the Node CLI via `koffi` or `ffi-napi`) only need a JSON
+//! parser. Strings returned by this module must be released with
+//! [`gitgrip_string_free`]. The declarations are in `include/gitgrip.h`.
+
+use crate::core::manifest::Manifest;
+use crate::workspace::Workspace;
+use serde_json::{json, Value};
+use std::ffi::{c_char, CStr, CString};
+
+/// Parse and validate manifest YAML
+///
+/// Returns `{"ok": <manifest>}` with the manifest as JSON.
+///
+/// # Safety
+///
+/// `yaml` must be a valid pointer to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gitgrip_manifest_parse(yaml: *const c_char) -> *mut c_char {
+    respond(|| {
+        let manifest = Manifest::parse(read_str(yaml)?).map_err(|e| e.to_string())?;
+        serde_json::to_value(manifest).map_err(|e| e.to_string())
+    })
+}
+
+/// Status of every repo in the workspace containing `path`
+///
+/// Returns `{"ok": [<status>, ...]}` in repo name order.
+///
+/// # Safety
+///
+/// `path` must be a valid pointer to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gitgrip_status(path: *const c_char) -> *mut c_char {
+    respond(|| {
+        let workspace = Workspace::discover(read_str(path)?).map_err(|e| e.to_string())?;
+        serde_json::to_value(workspace.status()).map_err(|e| e.to_string())
+    })
+}
+
+/// Clone missing repos and pull existing ones in the workspace containing `path`
+///
+/// Returns `{"ok": [{"repo": ..., "result": {"action": ...}} | {"repo": ..., "error": ...}, ...]}`.
+///
+/// # Safety
+///
+/// `path` must be a valid pointer to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gitgrip_sync(path: *const c_char) -> *mut c_char {
+    respond(|| {
+        let workspace = Workspace::discover(read_str(path)?).map_err(|e| e.to_string())?;
+        serde_json::to_value(workspace.sync()).map_err(|e| e.to_string())
+    })
+}
+
+/// Library version, as a static string that must not be freed
+#[no_mangle]
+pub extern "C" fn gitgrip_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Release a string returned by this library
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by a `gitgrip_*` function other
+/// than [`gitgrip_version`], and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn gitgrip_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+unsafe fn read_str<'a>(ptr: *const c_char) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err("null string argument".to_string());
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| "argument is not valid UTF-8".to_string())
+}
+
+/// Run `f` and encode its result as a JSON envelope, never unwinding into C
+fn respond(f: impl FnOnce() -> Result<Value, String>) -> *mut c_char {
+    let envelope = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => json!({ "ok": value }),
+        Ok(Err(message)) => json!({ "error": message }),
+        Err(_) => json!({ "error": "internal panic" }),
+    };
+    // serde_json escapes control characters, so the output has no interior NUL
+    CString::new(envelope.to_string())
+        .unwrap_or_default()
+        .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn call(f: unsafe extern "C" fn(*const c_char) -> *mut c_char, arg: &str) -> Value {
+        let arg = CString::new(arg).unwrap();
+        let out = f(arg.as_ptr());
+        let value = serde_json::from_str(CStr::from_ptr(out).to_str().unwrap()).unwrap();
+        gitgrip_string_free(out);
+        value
+    }
+
+    #[test]
+    fn test_manifest_parse_envelopes() {
+        let ok = unsafe {
+            call(
+                gitgrip_manifest_parse,
+                "repos:\n  app:\n    url: git@github.com:acme/app.git\n    path: app\n",
+            )
+        };
+        assert_eq!(ok["ok"]["repos"]["app"]["path"], "app");
+
+        let err = unsafe { call(gitgrip_manifest_parse, "repos: {}\n") };
+        assert!(err["error"].as_str().unwrap().contains("at least one"));
+    }
+
+    #[test]
+    fn test_status_outside_workspace_is_an_error() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let err = unsafe { call(gitgrip_status, &temp.path().to_string_lossy()) };
+        assert!(err["error"]
+            .as_str()
+            .unwrap()
+            .contains("Not in a gitgrip workspace"));
+    }
+
+    #[test]
+    fn test_version_and_null_handling() {
+        let version = unsafe { CStr::from_ptr(gitgrip_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+
+        let err = unsafe { gitgrip_status(std::ptr::null()) };
+        let value: Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(err) }.to_str().unwrap()).unwrap();
+        assert_eq!(value["error"], "null string argument");
+        unsafe { gitgrip_string_free(err) };
+    }
+}
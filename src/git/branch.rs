@@ -1,6 +1,7 @@
 //! Git branch operations
 
-use git2::Repository;
+use crate::util::profile::ProfiledCommand;
+use git2::{Oid, Repository};
 use std::process::Command;
 
 use super::{get_current_branch, GitError};
@@ -12,7 +13,29 @@ pub fn create_and_checkout_branch(repo: &Repository, branch_name: &str) -> Resul
     let output = Command::new("git")
         .args(["checkout", "-b", branch_name])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Create a new local branch at `start_point` and check it out
+pub fn create_and_checkout_branch_from(
+    repo: &Repository,
+    branch_name: &str,
+    start_point: &str,
+) -> Result<(), GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let output = Command::new("git")
+        .args(["checkout", "--no-track", "-b", branch_name, start_point])
+        .current_dir(repo_path)
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -35,7 +58,7 @@ pub fn checkout_branch(repo: &Repository, branch_name: &str) -> Result<(), GitEr
     let output = Command::new("git")
         .args(["checkout", branch_name])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -57,7 +80,7 @@ pub fn branch_exists(repo: &Repository, branch_name: &str) -> bool {
             &format!("refs/heads/{}", branch_name),
         ])
         .current_dir(repo_path)
-        .output();
+        .timed_output();
 
     output.map(|o| o.status.success()).unwrap_or(false)
 }
@@ -73,11 +96,29 @@ pub fn remote_branch_exists(repo: &Repository, branch_name: &str, remote: &str)
             &format!("refs/remotes/{}/{}", remote, branch_name),
         ])
         .current_dir(repo_path)
-        .output();
+        .timed_output();
 
     output.map(|o| o.status.success()).unwrap_or(false)
 }
 
+/// Rename a local branch (checked out or not)
+pub fn rename_local_branch(repo: &Repository, old: &str, new: &str) -> Result<(), GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let output = Command::new("git")
+        .args(["branch", "-m", old, new])
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    Ok(())
+}
+
 /// Delete a local branch
 pub fn delete_local_branch(
     repo: &Repository,
@@ -98,7 +139,7 @@ pub fn delete_local_branch(
     let output = Command::new("git")
         .args(["branch", flag, branch_name])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -126,7 +167,7 @@ pub fn is_branch_merged(
     let output = Command::new("git")
         .args(["branch", "--merged", target_branch])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -142,7 +183,7 @@ pub fn list_local_branches(repo: &Repository) -> Result<Vec<String>, GitError> {
     let output = Command::new("git")
         .args(["branch", "--format=%(refname:short)"])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -157,7 +198,7 @@ pub fn list_remote_branches(repo: &Repository, remote: &str) -> Result<Vec<Strin
     let output = Command::new("git")
         .args(["branch", "-r", "--format=%(refname:short)"])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -185,7 +226,7 @@ pub fn get_commits_between(
     let output = Command::new("git")
         .args(["rev-list", &range])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -198,6 +239,237 @@ pub fn has_commits_ahead(repo: &Repository, base_branch: &str) -> Result<bool, G
     Ok(!commits.is_empty())
 }
 
+/// Files changed on `head` since it diverged from `base_branch`
+///
+/// Compares against `origin/<base_branch>` when it exists.
+pub fn get_branch_changed_files(
+    repo: &Repository,
+    base_branch: &str,
+    head: &str,
+) -> Result<Vec<String>, GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let base = if remote_branch_exists(repo, base_branch, "origin") {
+        format!("origin/{}", base_branch)
+    } else {
+        base_branch.to_string()
+    };
+
+    let range = format!("{}...{}", base, head);
+    let output = Command::new("git")
+        .args(["diff", "--name-only", &range])
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(|s| s.to_string()).collect())
+}
+
+/// Size of a diff
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct DiffStat {
+    pub files: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStat {
+    /// Lines added plus lines deleted
+    pub fn lines_changed(&self) -> usize {
+        self.additions + self.deletions
+    }
+}
+
+impl std::fmt::Display for DiffStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "+{} -{} in {} file(s)",
+            self.additions, self.deletions, self.files
+        )
+    }
+}
+
+/// Size of `head`'s changes since it diverged from `base_branch`
+///
+/// Compares against `origin/<base_branch>` when it exists. Binary files count
+/// as changed files with no lines.
+pub fn get_branch_diff_stat(
+    repo: &Repository,
+    base_branch: &str,
+    head: &str,
+) -> Result<DiffStat, GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let base = if remote_branch_exists(repo, base_branch, "origin") {
+        format!("origin/{}", base_branch)
+    } else {
+        base_branch.to_string()
+    };
+
+    let range = format!("{}...{}", base, head);
+    let output = Command::new("git")
+        .args(["diff", "--numstat", &range])
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// A commit's message, split into subject and body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMessage {
+    /// Abbreviated SHA
+    pub sha: String,
+    pub subject: String,
+    /// Message body without the subject line; empty if there is none
+    pub body: String,
+}
+
+/// Messages of the commits on `head` since it diverged from `base_branch`, oldest first
+///
+/// Compares against `origin/<base_branch>` when it exists.
+pub fn get_branch_commit_messages(
+    repo: &Repository,
+    base_branch: &str,
+    head: &str,
+) -> Result<Vec<CommitMessage>, GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let base = if remote_branch_exists(repo, base_branch, "origin") {
+        format!("origin/{}", base_branch)
+    } else {
+        base_branch.to_string()
+    };
+
+    let range = format!("{}..{}", base, head);
+    let output = Command::new("git")
+        .args(["log", "--reverse", "--format=%h%x1f%s%x1f%b%x1e", &range])
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    Ok(parse_commit_messages(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// How a branch fared in a trial merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrialMerge {
+    Clean,
+    /// Paths that would conflict
+    Conflicts(Vec<String>),
+}
+
+/// Merge `heads` into `base` one after another, as a merge train would, and
+/// report how each merge goes
+///
+/// Merges happen in memory without touching the working tree or refs; each
+/// head merges into the result of the clean merges before it, so conflicts
+/// between the branches themselves show up too.
+pub fn trial_merge_sequence(
+    repo: &Repository,
+    base: &str,
+    heads: &[&str],
+) -> Result<Vec<TrialMerge>, GitError> {
+    let base_commit = repo.revparse_single(base)?.peel_to_commit()?;
+    let mut tree = base_commit.tree()?;
+    let mut merged = vec![base_commit.id()];
+    let mut results = Vec::new();
+
+    for head in heads {
+        let head_commit = repo.revparse_single(head)?.peel_to_commit()?;
+
+        // The nearest common ancestor with anything merged so far, so a
+        // branch stacked on an earlier one only brings its own changes
+        let mut ancestor: Option<Oid> = None;
+        for &tip in &merged {
+            let Ok(candidate) = repo.merge_base(tip, head_commit.id()) else {
+                continue;
+            };
+            ancestor = match ancestor {
+                Some(current)
+                    if candidate == current || !repo.graph_descendant_of(candidate, current)? =>
+                {
+                    Some(current)
+                }
+                _ => Some(candidate),
+            };
+        }
+        let ancestor_tree = match ancestor {
+            Some(oid) => repo.find_commit(oid)?.tree()?,
+            None => repo.find_tree(repo.treebuilder(None)?.write()?)?,
+        };
+
+        let mut index = repo.merge_trees(&ancestor_tree, &tree, &head_commit.tree()?, None)?;
+        if index.has_conflicts() {
+            let mut paths: Vec<String> = index
+                .conflicts()?
+                .filter_map(|conflict| conflict.ok())
+                .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+                .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+                .collect();
+            paths.sort();
+            paths.dedup();
+            results.push(TrialMerge::Conflicts(paths));
+        } else {
+            tree = repo.find_tree(index.write_tree_to(repo)?)?;
+            merged.push(head_commit.id());
+            results.push(TrialMerge::Clean);
+        }
+    }
+    Ok(results)
+}
+
+fn parse_commit_messages(stdout: &str) -> Vec<CommitMessage> {
+    stdout
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            Some(CommitMessage {
+                sha: fields.next().filter(|s| !s.is_empty())?.to_string(),
+                subject: fields.next()?.to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_numstat(stdout: &str) -> DiffStat {
+    let mut stat = DiffStat::default();
+    for line in stdout.lines() {
+        let mut fields = line.split('\t');
+        let (Some(added), Some(deleted), Some(_path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        stat.files += 1;
+        // Binary files show "-" for both counts
+        stat.additions += added.parse::<usize>().unwrap_or(0);
+        stat.deletions += deleted.parse::<usize>().unwrap_or(0);
+    }
+    stat
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +478,43 @@ mod tests {
     use std::process::Command;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_commit_messages() {
+        let stdout = "abc1234\x1fAdd login\x1fWires up the form.\n\nCloses #3\n\x1e\n\
+                      def5678\x1fFix typo\x1f\x1e\n";
+        let commits = parse_commit_messages(stdout);
+        assert_eq!(
+            commits,
+            vec![
+                CommitMessage {
+                    sha: "abc1234".to_string(),
+                    subject: "Add login".to_string(),
+                    body: "Wires up the form.\n\nCloses #3".to_string(),
+                },
+                CommitMessage {
+                    sha: "def5678".to_string(),
+                    subject: "Fix typo".to_string(),
+                    body: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_numstat() {
+        let stat = parse_numstat("10\t2\tsrc/lib.rs\n-\t-\tlogo.png\n3\t0\tREADME.md\n");
+        assert_eq!(
+            stat,
+            DiffStat {
+                files: 3,
+                additions: 13,
+                deletions: 2,
+            }
+        );
+        assert_eq!(stat.lines_changed(), 15);
+        assert_eq!(stat.to_string(), "+13 -2 in 3 file(s)");
+    }
+
     fn setup_test_repo() -> (TempDir, Repository) {
         let temp = TempDir::new().unwrap();
 
@@ -244,6 +553,47 @@ mod tests {
         (temp, repo)
     }
 
+    #[test]
+    fn test_trial_merge_sequence() {
+        let (temp, repo) = setup_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        let base = get_current_branch(&repo).unwrap();
+        let commit_on = |branch: &str, from: &str, file: &str, content: &str| {
+            git(&["checkout", "-q", "-b", branch, from]);
+            fs::write(temp.path().join(file), content).unwrap();
+            git(&["add", file]);
+            git(&["commit", "-q", "-m", branch]);
+        };
+        commit_on("a", &base, "README.md", "# From a");
+        commit_on("b", &base, "NOTES.md", "notes");
+        // Clean against the base alone, but conflicts with `a`
+        commit_on("c", &base, "README.md", "# From c");
+        // Stacked on `a`, so `a`'s change isn't a conflict
+        commit_on("d", "a", "README.md", "# From d");
+
+        let results = trial_merge_sequence(&repo, &base, &["a", "b", "c", "d"]).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                TrialMerge::Clean,
+                TrialMerge::Clean,
+                TrialMerge::Conflicts(vec!["README.md".to_string()]),
+                TrialMerge::Clean,
+            ]
+        );
+        assert_eq!(
+            trial_merge_sequence(&repo, &base, &["c"]).unwrap(),
+            vec![TrialMerge::Clean]
+        );
+    }
+
     #[test]
     fn test_create_and_checkout_branch() {
         let (temp, repo) = setup_test_repo();
@@ -264,6 +614,18 @@ mod tests {
         assert!(branch_exists(&repo, "feature"));
     }
 
+    #[test]
+    fn test_rename_local_branch() {
+        let (_temp, repo) = setup_test_repo();
+
+        create_and_checkout_branch(&repo, "feature").unwrap();
+        rename_local_branch(&repo, "feature", "feat/renamed").unwrap();
+
+        assert!(!branch_exists(&repo, "feature"));
+        assert_eq!(get_current_branch(&repo).unwrap(), "feat/renamed");
+        assert!(rename_local_branch(&repo, "feature", "other").is_err());
+    }
+
     #[test]
     fn test_checkout_branch() {
         let (temp, repo) = setup_test_repo();
@@ -294,4 +656,27 @@ mod tests {
         assert!(branches.contains(&"feature1".to_string()));
         assert!(branches.contains(&"feature2".to_string()));
     }
+
+    #[test]
+    fn test_get_branch_changed_files() {
+        let (temp, repo) = setup_test_repo();
+        let default = get_current_branch(&repo).unwrap();
+
+        create_and_checkout_branch(&repo, "feature").unwrap();
+        fs::create_dir(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/lib.rs"), "// lib").unwrap();
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "Add lib"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+
+        let changed = get_branch_changed_files(&repo, &default, "feature").unwrap();
+        assert_eq!(changed, vec!["src/lib.rs".to_string()]);
+    }
 }
@@ -1,7 +1,12 @@
 //! Git status cache
 //!
 //! Caches git status calls to avoid redundant operations within a single command execution.
+//! Ahead/behind counts are cached separately, keyed by the pair of commits
+//! compared: a commit pair always has the same counts, so those entries never
+//! go stale and are only dropped by [`GitStatusCache::clear`].
 
+use crate::util::profile::{self, Phase};
+use git2::{Oid, Repository};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -19,6 +24,8 @@ struct CacheEntry {
 /// Git status cache with TTL
 pub struct GitStatusCache {
     cache: Mutex<HashMap<PathBuf, CacheEntry>>,
+    /// Map: (local commit, upstream commit) -> (ahead, behind)
+    ahead_behind: Mutex<HashMap<(Oid, Oid), (usize, usize)>>,
     ttl: Duration,
 }
 
@@ -27,10 +34,28 @@ impl GitStatusCache {
     pub fn new(ttl: Duration) -> Self {
         Self {
             cache: Mutex::new(HashMap::new()),
+            ahead_behind: Mutex::new(HashMap::new()),
             ttl,
         }
     }
 
+    /// Cached `(ahead, behind)` of `local` relative to `upstream`
+    pub fn get_ahead_behind(&self, local: Oid, upstream: Oid) -> Option<(usize, usize)> {
+        self.ahead_behind
+            .lock()
+            .unwrap()
+            .get(&(local, upstream))
+            .copied()
+    }
+
+    /// Cache `(ahead, behind)` of `local` relative to `upstream`
+    pub fn set_ahead_behind(&self, local: Oid, upstream: Oid, counts: (usize, usize)) {
+        self.ahead_behind
+            .lock()
+            .unwrap()
+            .insert((local, upstream), counts);
+    }
+
     /// Check if an entry is expired
     fn is_expired(&self, entry: &CacheEntry) -> bool {
         entry.timestamp.elapsed() > self.ttl
@@ -69,6 +94,7 @@ impl GitStatusCache {
     pub fn clear(&self) {
         let mut cache = self.cache.lock().unwrap();
         cache.clear();
+        self.ahead_behind.lock().unwrap().clear();
     }
 }
 
@@ -86,6 +112,22 @@ pub fn invalidate_status_cache(repo_path: &PathBuf) {
     STATUS_CACHE.invalidate(repo_path);
 }
 
+/// `repo.graph_ahead_behind(local, upstream)`, answered from the cache when
+/// the same commit pair was compared before
+pub fn cached_graph_ahead_behind(
+    repo: &Repository,
+    local: Oid,
+    upstream: Oid,
+) -> Result<(usize, usize), git2::Error> {
+    if let Some(counts) = STATUS_CACHE.get_ahead_behind(local, upstream) {
+        return Ok(counts);
+    }
+    let _span = profile::span(Phase::Git, || "ahead/behind", repo.workdir());
+    let counts = repo.graph_ahead_behind(local, upstream)?;
+    STATUS_CACHE.set_ahead_behind(local, upstream, counts);
+    Ok(counts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,8 +142,12 @@ mod tests {
             staged: vec![],
             modified: vec![],
             untracked: vec![],
+            untracked_count: 0,
+            untracked_truncated: false,
             ahead: 0,
             behind: 0,
+            operation: None,
+            conflicted: vec![],
         };
 
         cache.set(path.clone(), status.clone());
@@ -120,8 +166,12 @@ mod tests {
             staged: vec![],
             modified: vec![],
             untracked: vec![],
+            untracked_count: 0,
+            untracked_truncated: false,
             ahead: 0,
             behind: 0,
+            operation: None,
+            conflicted: vec![],
         };
 
         cache.set(path.clone(), status);
@@ -131,6 +181,24 @@ mod tests {
         assert!(cache.get(&path).is_none());
     }
 
+    #[test]
+    fn test_ahead_behind_keyed_by_oid_pair() {
+        let cache = GitStatusCache::new(Duration::from_millis(10));
+        let a = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+        let b = Oid::from_str("2222222222222222222222222222222222222222").unwrap();
+
+        cache.set_ahead_behind(a, b, (3, 1));
+        assert_eq!(cache.get_ahead_behind(a, b), Some((3, 1)));
+        assert_eq!(cache.get_ahead_behind(b, a), None);
+
+        // Commit pairs don't expire with the status TTL
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get_ahead_behind(a, b), Some((3, 1)));
+
+        cache.clear();
+        assert_eq!(cache.get_ahead_behind(a, b), None);
+    }
+
     #[test]
     fn test_cache_expiry() {
         let cache = GitStatusCache::new(Duration::from_millis(10));
@@ -141,8 +209,12 @@ mod tests {
             staged: vec![],
             modified: vec![],
             untracked: vec![],
+            untracked_count: 0,
+            untracked_truncated: false,
             ahead: 0,
             behind: 0,
+            operation: None,
+            conflicted: vec![],
         };
 
         cache.set(path.clone(), status);
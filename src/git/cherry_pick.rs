@@ -0,0 +1,468 @@
+//! Cherry-pick operations
+//!
+//! Applies commits onto the current branch using libgit2's cherrypick.
+//! libgit2 has no sequencer, so when a pick stops on conflicts the remaining
+//! commits are recorded in `.git/gitgrip-cherry-pick` for `--continue`/`--abort`.
+
+use git2::{Oid, Repository, RepositoryState};
+use std::fs;
+use std::path::PathBuf;
+
+use super::signing::commit_with_signing;
+use super::{get_signature, GitError};
+
+/// Name of the todo file kept in the git directory while a pick is stopped
+const TODO_FILE: &str = "gitgrip-cherry-pick";
+
+/// Result of applying a sequence of commits
+#[derive(Debug, Default)]
+pub struct CherryPickOutcome {
+    /// Commits applied and committed
+    pub picked: usize,
+    /// Commits skipped because they produced no changes
+    pub empty: usize,
+    /// Commit that stopped on conflicts, with the conflicted paths
+    pub conflict: Option<(Oid, Vec<String>)>,
+}
+
+/// Pending state for a stopped cherry-pick
+#[derive(Debug, PartialEq, Eq)]
+struct Todo {
+    orig_head: Oid,
+    record_origin: bool,
+    sign: bool,
+    remaining: Vec<Oid>,
+}
+
+/// Resolve commit specs to commit ids, oldest first
+///
+/// Each spec is either a single revision or an `A..B` range (commits reachable
+/// from `B` but not `A`). Symmetric `A...B` ranges are rejected. Returns
+/// `GitError::Reference` if a revision does not exist in this repository.
+pub fn resolve_commits(repo: &Repository, specs: &[String]) -> Result<Vec<Oid>, GitError> {
+    let mut commits = Vec::new();
+
+    for spec in specs {
+        if spec.contains("...") {
+            return Err(GitError::Reference(format!(
+                "symmetric range '{}' is not supported; use A..B to pick the commits on B that are not on A",
+                spec
+            )));
+        }
+        if let Some((from, to)) = spec.split_once("..") {
+            commits.extend(commits_between(repo, from, to)?);
+        } else {
+            let commit = repo
+                .revparse_single(spec)
+                .and_then(|obj| obj.peel_to_commit())
+                .map_err(|_| GitError::Reference(format!("commit not found: {}", spec)))?;
+            commits.push(commit.id());
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Commits reachable from `to` but not from `from`, oldest first
+///
+/// Merge commits are skipped since they can't be picked without a mainline.
+pub fn commits_between(repo: &Repository, from: &str, to: &str) -> Result<Vec<Oid>, GitError> {
+    let from = resolve_rev(repo, if from.is_empty() { "HEAD" } else { from })?;
+    let to = resolve_rev(repo, if to.is_empty() { "HEAD" } else { to })?;
+    walk_range(repo, from, to)
+}
+
+/// Commits introduced by `branch` relative to `base`, oldest first
+///
+/// Works both before and after the branch is merged. If `base` already
+/// contains the branch through a merge commit on its first-parent history,
+/// the commits from the merged side are returned. Squash- and rebase-merged
+/// branches are not reachable from `base`, so their original commits are used.
+pub fn branch_commits(repo: &Repository, branch: &str, base: &str) -> Result<Vec<Oid>, GitError> {
+    let tip = resolve_rev(repo, branch)?;
+    let base = resolve_rev(repo, base)?;
+
+    let contains = |commit: Oid, ancestor: Oid| -> Result<bool, GitError> {
+        Ok(commit == ancestor || repo.graph_descendant_of(commit, ancestor)?)
+    };
+
+    if !contains(base, tip)? {
+        return walk_range(repo, base, tip);
+    }
+
+    // Find the merge on base's first-parent line that brought the branch in
+    let mut current = repo.find_commit(base)?;
+    loop {
+        if current.parent_count() > 1 {
+            let mainline = current.parent_id(0)?;
+            let side = current.parent_id(1)?;
+            if contains(side, tip)? && !contains(mainline, tip)? {
+                return walk_range(repo, mainline, side);
+            }
+        }
+        if current.parent_count() == 0 {
+            return Ok(Vec::new());
+        }
+        current = current.parent(0)?;
+    }
+}
+
+fn resolve_rev(repo: &Repository, rev: &str) -> Result<Oid, GitError> {
+    repo.revparse_single(rev)
+        .and_then(|obj| obj.peel_to_commit())
+        .map(|c| c.id())
+        .map_err(|_| GitError::Reference(format!("revision not found: {}", rev)))
+}
+
+fn walk_range(repo: &Repository, hide: Oid, push: Oid) -> Result<Vec<Oid>, GitError> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(push)?;
+    revwalk.hide(hide)?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        if repo.find_commit(oid)?.parent_count() <= 1 {
+            commits.push(oid);
+        }
+    }
+    Ok(commits)
+}
+
+/// Cherry-pick `commits` onto HEAD, committing each one
+///
+/// With `record_origin`, appends "(cherry picked from commit ...)" to each
+/// message like `git cherry-pick -x`. Commits are signed when git config asks
+/// for it unless `sign` is false. Stops at the first conflict, leaving the
+/// repository in the cherry-pick state.
+pub fn cherry_pick_commits(
+    repo: &Repository,
+    commits: &[Oid],
+    record_origin: bool,
+    sign: bool,
+) -> Result<CherryPickOutcome, GitError> {
+    if repo.state() != RepositoryState::Clean {
+        return Err(GitError::OperationFailed(
+            "another operation is in progress".to_string(),
+        ));
+    }
+
+    let todo = Todo {
+        orig_head: repo.head()?.peel_to_commit()?.id(),
+        record_origin,
+        sign,
+        remaining: commits.to_vec(),
+    };
+    apply(repo, todo, CherryPickOutcome::default())
+}
+
+/// Whether a cherry-pick started by [`cherry_pick_commits`] is stopped
+pub fn has_pending(repo: &Repository) -> bool {
+    todo_path(repo).exists()
+}
+
+/// Commit the resolved pick and apply the remaining commits
+pub fn continue_cherry_pick(repo: &Repository) -> Result<CherryPickOutcome, GitError> {
+    let todo = read_todo(repo)?;
+    let mut outcome = CherryPickOutcome::default();
+
+    if repo.state() == RepositoryState::CherryPick {
+        let conflicted = conflicted_paths(repo)?;
+        let current = repo.refname_to_id("CHERRY_PICK_HEAD")?;
+        if !conflicted.is_empty() {
+            outcome.conflict = Some((current, conflicted));
+            return Ok(outcome);
+        }
+        if commit_pick(repo, current, &todo)? {
+            outcome.picked += 1;
+        } else {
+            outcome.empty += 1;
+        }
+    }
+
+    apply(repo, todo, outcome)
+}
+
+/// Abort a stopped cherry-pick, restoring HEAD to where it started
+pub fn abort_cherry_pick(repo: &Repository) -> Result<(), GitError> {
+    let todo = read_todo(repo)?;
+    let orig = repo.find_commit(todo.orig_head)?;
+    repo.reset(orig.as_object(), git2::ResetType::Hard, None)?;
+    repo.cleanup_state()?;
+    fs::remove_file(todo_path(repo))?;
+    Ok(())
+}
+
+fn apply(
+    repo: &Repository,
+    mut todo: Todo,
+    mut outcome: CherryPickOutcome,
+) -> Result<CherryPickOutcome, GitError> {
+    let commits = std::mem::take(&mut todo.remaining);
+    for (i, oid) in commits.iter().enumerate() {
+        let commit = repo.find_commit(*oid)?;
+        repo.cherrypick(&commit, None)?;
+
+        let conflicted = conflicted_paths(repo)?;
+        if !conflicted.is_empty() {
+            todo.remaining = commits[i + 1..].to_vec();
+            write_todo(repo, &todo)?;
+            outcome.conflict = Some((*oid, conflicted));
+            return Ok(outcome);
+        }
+
+        if commit_pick(repo, *oid, &todo)? {
+            outcome.picked += 1;
+        } else {
+            outcome.empty += 1;
+        }
+    }
+
+    let todo = todo_path(repo);
+    if todo.exists() {
+        fs::remove_file(todo)?;
+    }
+    Ok(outcome)
+}
+
+/// Commit the index as a pick of `source`; returns false if it was empty
+fn commit_pick(repo: &Repository, source: Oid, todo: &Todo) -> Result<bool, GitError> {
+    let source = repo.find_commit(source)?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    let mut index = repo.index()?;
+    index.read(true)?;
+    let tree_id = index.write_tree()?;
+
+    if tree_id == head.tree_id() {
+        repo.cleanup_state()?;
+        return Ok(false);
+    }
+
+    let tree = repo.find_tree(tree_id)?;
+    let committer = get_signature(repo)?;
+    let mut message = source.message().unwrap_or("").trim_end().to_string();
+    if todo.record_origin {
+        message.push_str(&format!("\n\n(cherry picked from commit {})", source.id()));
+    }
+
+    commit_with_signing(
+        repo,
+        &source.author(),
+        &committer,
+        &message,
+        &tree,
+        &[&head],
+        todo.sign,
+    )?;
+    repo.cleanup_state()?;
+    Ok(true)
+}
+
+fn conflicted_paths(repo: &Repository) -> Result<Vec<String>, GitError> {
+    // Re-read from disk: conflicts may have been resolved with `git add` since
+    let mut index = repo.index()?;
+    index.read(true)?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths)
+}
+
+fn todo_path(repo: &Repository) -> PathBuf {
+    repo.path().join(TODO_FILE)
+}
+
+fn write_todo(repo: &Repository, todo: &Todo) -> Result<(), GitError> {
+    let mut content = format!(
+        "orig {}\nrecord-origin {}\nsign {}\n",
+        todo.orig_head, todo.record_origin, todo.sign
+    );
+    for oid in &todo.remaining {
+        content.push_str(&format!("pick {}\n", oid));
+    }
+    fs::write(todo_path(repo), content)?;
+    Ok(())
+}
+
+fn read_todo(repo: &Repository) -> Result<Todo, GitError> {
+    let content = fs::read_to_string(todo_path(repo))
+        .map_err(|_| GitError::OperationFailed("no cherry-pick in progress".to_string()))?;
+    parse_todo(&content)
+}
+
+fn parse_todo(content: &str) -> Result<Todo, GitError> {
+    let invalid = || GitError::OperationFailed("invalid cherry-pick todo file".to_string());
+    let mut orig_head = None;
+    let mut record_origin = false;
+    let mut sign = true;
+    let mut remaining = Vec::new();
+
+    for line in content.lines() {
+        match line.split_once(' ') {
+            Some(("orig", sha)) => orig_head = Some(Oid::from_str(sha).map_err(|_| invalid())?),
+            Some(("record-origin", flag)) => record_origin = flag == "true",
+            Some(("sign", flag)) => sign = flag == "true",
+            Some(("pick", sha)) => remaining.push(Oid::from_str(sha).map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(Todo {
+        orig_head: orig_head.ok_or_else(invalid)?,
+        record_origin,
+        sign,
+        remaining,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::open_repo;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    fn setup_test_repo() -> (TempDir, Repository) {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        fs::write(temp.path().join("README.md"), "# Test").unwrap();
+        git(temp.path(), &["add", "README.md"]);
+        git(temp.path(), &["commit", "-m", "Initial commit"]);
+        git(temp.path(), &["branch", "release"]);
+
+        // Two fix commits on the main line
+        git(temp.path(), &["checkout", "-b", "fixes"]);
+        fs::write(temp.path().join("a.txt"), "a").unwrap();
+        git(temp.path(), &["add", "a.txt"]);
+        git(temp.path(), &["commit", "-m", "Fix a"]);
+        fs::write(temp.path().join("README.md"), "# Fixed").unwrap();
+        git(temp.path(), &["commit", "-am", "Fix readme"]);
+        git(temp.path(), &["checkout", "release"]);
+
+        let repo = open_repo(temp.path()).unwrap();
+        (temp, repo)
+    }
+
+    #[test]
+    fn test_cherry_pick_range() {
+        let (temp, repo) = setup_test_repo();
+
+        let commits = resolve_commits(&repo, &["release..fixes".to_string()]).unwrap();
+        assert_eq!(commits.len(), 2);
+
+        let outcome = cherry_pick_commits(&repo, &commits, true, false).unwrap();
+        assert_eq!(outcome.picked, 2);
+        assert!(outcome.conflict.is_none());
+        assert!(temp.path().join("a.txt").exists());
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert!(head
+            .message()
+            .unwrap()
+            .contains("cherry picked from commit"));
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict_and_abort() {
+        let (temp, repo) = setup_test_repo();
+
+        fs::write(temp.path().join("README.md"), "# Release").unwrap();
+        git(temp.path(), &["commit", "-am", "Release readme"]);
+        let orig = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        let commits = resolve_commits(&repo, &["release..fixes".to_string()]).unwrap();
+        let outcome = cherry_pick_commits(&repo, &commits, false, false).unwrap();
+        assert_eq!(outcome.picked, 1);
+        let (_, paths) = outcome.conflict.unwrap();
+        assert_eq!(paths, vec!["README.md".to_string()]);
+        assert!(has_pending(&repo));
+
+        abort_cherry_pick(&repo).unwrap();
+        assert!(!has_pending(&repo));
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), orig);
+        assert!(!temp.path().join("a.txt").exists());
+    }
+
+    #[test]
+    fn test_cherry_pick_continue() {
+        let (temp, repo) = setup_test_repo();
+
+        fs::write(temp.path().join("README.md"), "# Release").unwrap();
+        git(temp.path(), &["commit", "-am", "Release readme"]);
+
+        let commits = resolve_commits(&repo, &["release..fixes".to_string()]).unwrap();
+        let outcome = cherry_pick_commits(&repo, &commits, false, false).unwrap();
+        assert!(outcome.conflict.is_some());
+
+        // Still conflicted: continue reports it again
+        let outcome = continue_cherry_pick(&repo).unwrap();
+        assert!(outcome.conflict.is_some());
+
+        fs::write(temp.path().join("README.md"), "# Resolved").unwrap();
+        git(temp.path(), &["add", "README.md"]);
+
+        let outcome = continue_cherry_pick(&repo).unwrap();
+        assert_eq!(outcome.picked, 1);
+        assert!(outcome.conflict.is_none());
+        assert!(!has_pending(&repo));
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_branch_commits_after_merge() {
+        let (temp, repo) = setup_test_repo();
+        git(temp.path(), &["checkout", "-b", "main-line", "release"]);
+        fs::write(temp.path().join("other.txt"), "other").unwrap();
+        git(temp.path(), &["add", "other.txt"]);
+        git(temp.path(), &["commit", "-m", "Unrelated work"]);
+
+        // Before merging: the two fix commits
+        assert_eq!(
+            branch_commits(&repo, "fixes", "main-line").unwrap().len(),
+            2
+        );
+
+        git(
+            temp.path(),
+            &["merge", "--no-ff", "-m", "Merge fixes", "fixes"],
+        );
+        let commits = branch_commits(&repo, "fixes", "main-line").unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[1], repo.revparse_single("fixes").unwrap().id());
+    }
+
+    #[test]
+    fn test_resolve_missing_commit() {
+        let (_temp, repo) = setup_test_repo();
+        assert!(resolve_commits(&repo, &["deadbeef".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_rejects_symmetric_range() {
+        let (_temp, repo) = setup_test_repo();
+        let err = resolve_commits(&repo, &["release...fixes".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("symmetric range"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_todo() {
+        let oid = "1234567890123456789012345678901234567890";
+        let todo = parse_todo(&format!("orig {0}\nrecord-origin true\npick {0}\n", oid)).unwrap();
+        assert!(todo.record_origin);
+        assert_eq!(todo.remaining.len(), 1);
+        assert!(parse_todo("bogus").is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Incremental status for very large repositories
+//!
+//! `git status` stats every tracked file, which takes seconds in repos with
+//! hundreds of thousands of files. For repos above [`INCREMENTAL_MIN_FILES`]
+//! we keep a snapshot in `.git/gitgrip-status.json` holding HEAD, the index
+//! checksum, the mtime of every tracked directory, and the last porcelain
+//! output. On the next call only the subtrees whose directory mtime changed
+//! are re-examined with a pathspec-limited `git status`.
+//!
+//! Directory mtimes only change when entries are added, removed, or renamed,
+//! so a file edited in place (as VS Code and many other editors do) can be
+//! missed until the snapshot expires after [`MAX_SNAPSHOT_AGE`] or HEAD or
+//! the index changes. The incremental result is therefore only used for the
+//! `gr status` table; anything that guards against losing work calls
+//! [`full_porcelain_status`]. Pass `--full-status` to always scan everything.
+
+use crate::util::profile::ProfiledCommand;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::GitError;
+
+/// Repos with fewer tracked files always get a full scan
+pub const INCREMENTAL_MIN_FILES: usize = 50_000;
+
+/// Snapshots older than this are discarded
+pub const MAX_SNAPSHOT_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Directories modified this close to the snapshot are re-examined anyway,
+/// since a change in the same timestamp tick would be invisible
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Above this fraction of changed directories a full scan is cheaper
+const MAX_CHANGED_FRACTION: f64 = 0.25;
+
+const SNAPSHOT_FILE: &str = "gitgrip-status.json";
+
+static FORCE_FULL_SCAN: AtomicBool = AtomicBool::new(false);
+
+/// Disable incremental status for the rest of this process (`--full-status`)
+pub fn force_full_scan(full: bool) {
+    FORCE_FULL_SCAN.store(full, Ordering::Relaxed);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatusSnapshot {
+    head: String,
+    index_checksum: String,
+    taken_at_nanos: u128,
+    /// Tracked directory (relative, "" for the root) -> mtime in nanoseconds
+    dirs: HashMap<String, u128>,
+    /// `git status --porcelain=v1` lines
+    entries: Vec<String>,
+}
+
+/// `git status --porcelain=v1` output from a full scan of the working tree
+///
+/// Safety checks (dirty guards before checkout, reset, prune, ...) must use
+/// this rather than [`porcelain_status`], which can miss in-place edits.
+pub fn full_porcelain_status(repo: &Repository) -> Result<String, GitError> {
+    run_status(super::get_workdir(repo), &[], false)
+}
+
+/// `git status --porcelain=v1` output, computed incrementally when possible
+///
+/// Only suitable for display: in-place edits can go unseen for up to
+/// [`MAX_SNAPSHOT_AGE`] in large repos.
+pub fn porcelain_status(repo: &Repository) -> Result<String, GitError> {
+    porcelain_status_with(
+        repo,
+        FORCE_FULL_SCAN.load(Ordering::Relaxed),
+        INCREMENTAL_MIN_FILES,
+    )
+}
+
+fn porcelain_status_with(
+    repo: &Repository,
+    force_full: bool,
+    min_files: usize,
+) -> Result<String, GitError> {
+    let workdir = super::get_workdir(repo);
+    let snapshot_path = repo.path().join(SNAPSHOT_FILE);
+
+    let tracked = repo.index().map(|i| i.len()).unwrap_or(0);
+    if force_full || tracked < min_files {
+        let _ = std::fs::remove_file(&snapshot_path);
+        return run_status(workdir, &[], false);
+    }
+
+    let head = head_id(repo);
+    let previous = std::fs::read_to_string(&snapshot_path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<StatusSnapshot>(&json).ok())
+        .filter(|s| s.head == head && s.index_checksum == index_checksum(repo));
+
+    let entries = match previous.and_then(|s| incremental_entries(workdir, s)) {
+        Some(entries) => entries,
+        None => parse_lines(&run_status(workdir, &[], false)?),
+    };
+
+    // A full `git status` may refresh the index, so checksum it afterwards
+    let snapshot = StatusSnapshot {
+        head,
+        index_checksum: index_checksum(repo),
+        taken_at_nanos: now_nanos(),
+        dirs: tracked_dir_mtimes(repo, workdir),
+        entries,
+    };
+    if let Ok(json) = serde_json::to_string(&snapshot) {
+        let _ = std::fs::write(&snapshot_path, json);
+    }
+
+    Ok(snapshot
+        .entries
+        .iter()
+        .map(|e| format!("{}\n", e))
+        .collect())
+}
+
+/// Re-examine only the subtrees whose directories changed since `snapshot`
+///
+/// Returns `None` when the snapshot can't be trusted and a full scan is needed.
+fn incremental_entries(workdir: &Path, snapshot: StatusSnapshot) -> Option<Vec<String>> {
+    let now = now_nanos();
+    if now.saturating_sub(snapshot.taken_at_nanos) > MAX_SNAPSHOT_AGE.as_nanos() {
+        return None;
+    }
+
+    let racy_after = snapshot
+        .taken_at_nanos
+        .saturating_sub(RACY_WINDOW.as_nanos());
+    let changed: BTreeSet<&str> = snapshot
+        .dirs
+        .iter()
+        .filter(|(dir, mtime)| {
+            **mtime >= racy_after || dir_mtime(&workdir.join(dir)) != Some(**mtime)
+        })
+        .map(|(dir, _)| dir.as_str())
+        .collect();
+
+    if changed.is_empty() {
+        return Some(snapshot.entries);
+    }
+    // Root changes (a top-level file added or removed) affect everything
+    if changed.contains("")
+        || changed.len() as f64 > snapshot.dirs.len() as f64 * MAX_CHANGED_FRACTION
+    {
+        return None;
+    }
+
+    // Drop directories already covered by a changed ancestor
+    let roots: Vec<&str> = changed
+        .iter()
+        .filter(|dir| {
+            !changed
+                .iter()
+                .any(|other| other != *dir && dir.starts_with(&format!("{}/", other)))
+        })
+        .copied()
+        .collect();
+
+    let pathspecs: Vec<String> = roots.iter().map(|dir| format!("{}/", dir)).collect();
+    let fresh = parse_lines(&run_status(workdir, &pathspecs, true).ok()?);
+
+    let mut entries: Vec<String> = snapshot
+        .entries
+        .into_iter()
+        .filter(|entry| {
+            let path = entry_path(entry);
+            !roots
+                .iter()
+                .any(|dir| path.starts_with(&format!("{}/", dir)))
+        })
+        .chain(fresh)
+        .collect();
+    entries.sort_by(|a, b| entry_path(a).cmp(entry_path(b)));
+    Some(entries)
+}
+
+fn run_status(workdir: &Path, pathspecs: &[String], read_only: bool) -> Result<String, GitError> {
+    let mut command = Command::new("git");
+    if read_only {
+        // Keep the index (and so its checksum) untouched
+        command.arg("--no-optional-locks");
+    }
+    command.args(["status", "--porcelain=v1"]);
+    if !pathspecs.is_empty() {
+        command.arg("--").args(pathspecs);
+    }
+
+    let output = command
+        .current_dir(workdir)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// The path of a porcelain v1 entry (the destination for renames)
+fn entry_path(entry: &str) -> &str {
+    let path = entry.get(3..).unwrap_or("");
+    let path = path.rsplit(" -> ").next().unwrap_or(path);
+    path.trim_matches('"')
+}
+
+fn head_id(repo: &Repository) -> String {
+    repo.head()
+        .ok()
+        .and_then(|h| h.target())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default()
+}
+
+/// The trailing checksum git writes at the end of the index file
+fn index_checksum(repo: &Repository) -> String {
+    let read_tail = || -> std::io::Result<Vec<u8>> {
+        let mut file = File::open(repo.path().join("index"))?;
+        file.seek(SeekFrom::End(-20))?;
+        let mut tail = Vec::with_capacity(20);
+        file.read_to_end(&mut tail)?;
+        Ok(tail)
+    };
+    read_tail()
+        .map(|tail| tail.iter().map(|b| format!("{:02x}", b)).collect())
+        .unwrap_or_default()
+}
+
+/// Mtimes of every directory containing tracked files, plus the root
+fn tracked_dir_mtimes(repo: &Repository, workdir: &Path) -> HashMap<String, u128> {
+    let mut dirs: BTreeSet<String> = BTreeSet::new();
+    dirs.insert(String::new());
+    if let Ok(index) = repo.index() {
+        for entry in index.iter() {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            let mut dir = path.as_str();
+            while let Some((parent, _)) = dir.rsplit_once('/') {
+                if !dirs.insert(parent.to_string()) {
+                    break;
+                }
+                dir = parent;
+            }
+        }
+    }
+
+    dirs.into_iter()
+        .filter_map(|dir| {
+            let mtime = dir_mtime(&workdir.join(&dir))?;
+            Some((dir, mtime))
+        })
+        .collect()
+}
+
+fn dir_mtime(path: &Path) -> Option<u128> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos())
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::open_repo;
+    use crate::git::testing::{git, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        for dir in ["a", "b", "c", "d", "e"] {
+            fs::create_dir(path.join(dir)).unwrap();
+            fs::write(path.join(dir).join("file.txt"), dir).unwrap();
+        }
+        fs::write(path.join("README.md"), "# repo").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Initial commit"]);
+        temp
+    }
+
+    /// Pretend the snapshot was taken long enough ago to be outside the racy window
+    fn age_snapshot(repo: &Repository) {
+        let path = repo.path().join(SNAPSHOT_FILE);
+        let mut snapshot: StatusSnapshot =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let shift = RACY_WINDOW.as_nanos() * 2;
+        snapshot.taken_at_nanos += shift;
+        fs::write(&path, serde_json::to_string(&snapshot).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_small_repo_skips_snapshot() {
+        let temp = setup_repo();
+        let repo = open_repo(temp.path()).unwrap();
+        porcelain_status_with(&repo, false, INCREMENTAL_MIN_FILES).unwrap();
+        assert!(!repo.path().join(SNAPSHOT_FILE).exists());
+    }
+
+    #[test]
+    fn test_incremental_picks_up_new_files() {
+        let temp = setup_repo();
+        let repo = open_repo(temp.path()).unwrap();
+
+        assert_eq!(porcelain_status_with(&repo, false, 1).unwrap(), "");
+        assert!(repo.path().join(SNAPSHOT_FILE).exists());
+        age_snapshot(&repo);
+
+        fs::write(temp.path().join("b/new.txt"), "new").unwrap();
+        assert_eq!(
+            porcelain_status_with(&repo, false, 1).unwrap(),
+            "?? b/new.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_index_change_forces_full_scan() {
+        let temp = setup_repo();
+        let repo = open_repo(temp.path()).unwrap();
+        porcelain_status_with(&repo, false, 1).unwrap();
+        age_snapshot(&repo);
+
+        // An in-place edit is invisible to directory mtimes, but staging it
+        // changes the index and so triggers a full scan
+        fs::write(temp.path().join("a/file.txt"), "changed").unwrap();
+        git(temp.path(), &["add", "a/file.txt"]);
+        assert_eq!(
+            porcelain_status_with(&repo, false, 1).unwrap(),
+            "M  a/file.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_full_scan_sees_in_place_edit() {
+        let temp = setup_repo();
+        let repo = open_repo(temp.path()).unwrap();
+        porcelain_status_with(&repo, false, 1).unwrap();
+        age_snapshot(&repo);
+
+        fs::write(temp.path().join("a/file.txt"), "edited in place").unwrap();
+        assert_eq!(full_porcelain_status(&repo).unwrap(), " M a/file.txt\n");
+    }
+}
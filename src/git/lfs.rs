@@ -0,0 +1,211 @@
+//! Git LFS support
+//!
+//! Repos that track files with LFS end up with pointer files when the LFS
+//! filters aren't installed at clone time. These helpers detect LFS usage,
+//! count objects that are still pointers, and fetch them via the `git lfs` CLI.
+//! They also find large files about to be pushed that belong in LFS.
+
+use crate::util::profile::ProfiledCommand;
+use git2::{Oid, Repository};
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+use super::GitError;
+
+/// Whether the repository tracks any paths with LFS (`filter=lfs` in `.gitattributes`)
+pub fn uses_lfs(repo_path: &Path) -> bool {
+    std::fs::read_to_string(repo_path.join(".gitattributes"))
+        .map(|content| attributes_use_lfs(&content))
+        .unwrap_or(false)
+}
+
+fn attributes_use_lfs(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| line.split_whitespace().any(|attr| attr == "filter=lfs"))
+}
+
+/// Whether the `git lfs` extension is installed
+pub fn lfs_available() -> bool {
+    Command::new("git")
+        .args(["lfs", "version"])
+        .timed_output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Number of LFS-tracked files in the working tree that are still pointers
+///
+/// Returns 0 when the repo doesn't use LFS or `git lfs` isn't installed.
+pub fn missing_lfs_objects(repo_path: &Path) -> usize {
+    if !uses_lfs(repo_path) {
+        return 0;
+    }
+
+    match Command::new("git")
+        .args(["lfs", "ls-files"])
+        .current_dir(repo_path)
+        .timed_output()
+    {
+        Ok(output) if output.status.success() => {
+            count_missing(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => 0,
+    }
+}
+
+/// Count `git lfs ls-files` entries marked `-` (pointer only) rather than `*`
+fn count_missing(ls_files: &str) -> usize {
+    ls_files
+        .lines()
+        .filter(|line| line.split_whitespace().nth(1) == Some("-"))
+        .count()
+}
+
+/// Download and check out LFS objects for the current ref
+///
+/// Installs the LFS filters for this repo first so later checkouts smudge
+/// files automatically.
+pub fn pull_lfs_objects(repo_path: &Path) -> Result<(), GitError> {
+    if !lfs_available() {
+        return Err(GitError::OperationFailed(
+            "repo uses Git LFS but git-lfs is not installed".to_string(),
+        ));
+    }
+
+    let steps: [&[&str]; 2] = [&["lfs", "install", "--local"], &["lfs", "pull"]];
+    for args in steps {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo_path)
+            .timed_output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitError::OperationFailed(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                stderr.trim()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// A file added or changed by an outgoing commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeBlob {
+    pub path: String,
+    pub size: u64,
+    /// Short SHA of the commit that introduces this version
+    pub commit: String,
+}
+
+/// Blobs larger than `limit` introduced by the commits in `head` but not in
+/// `base`, largest first
+///
+/// Sizes are read from object headers, so big blobs aren't loaded.
+pub fn large_blobs(
+    repo: &Repository,
+    base: &str,
+    head: &str,
+    limit: u64,
+) -> Result<Vec<LargeBlob>, GitError> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(repo.revparse_single(head)?.peel_to_commit()?.id())?;
+    if let Ok(base) = repo.revparse_single(base) {
+        revwalk.hide(base.peel_to_commit()?.id())?;
+    }
+
+    let odb = repo.odb()?;
+    let mut seen: HashSet<Oid> = HashSet::new();
+    let mut blobs = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            let file = delta.new_file();
+            if file.id().is_zero() || !seen.insert(file.id()) {
+                continue;
+            }
+            let Ok((size, _)) = odb.read_header(file.id()) else {
+                continue;
+            };
+            if size as u64 > limit {
+                blobs.push(LargeBlob {
+                    path: file
+                        .path()
+                        .map(|p| p.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    size: size as u64,
+                    commit: commit.id().to_string()[..7].to_string(),
+                });
+            }
+        }
+    }
+    blobs.sort_by_key(|blob| std::cmp::Reverse(blob.size));
+    Ok(blobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_use_lfs() {
+        assert!(attributes_use_lfs(
+            "*.psd filter=lfs diff=lfs merge=lfs -text\n"
+        ));
+        assert!(!attributes_use_lfs("*.sh text eol=lf\n"));
+        assert!(!attributes_use_lfs("# *.psd filter=lfs\n"));
+    }
+
+    #[test]
+    fn test_count_missing() {
+        let output = "4d7a214614 * assets/logo.png\n\
+                      1b2c3d4e5f - assets/video.mp4\n\
+                      9f8e7d6c5b - data/model.bin\n";
+        assert_eq!(count_missing(output), 2);
+        assert_eq!(count_missing(""), 0);
+    }
+
+    #[test]
+    fn test_large_blobs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.name", "Test User"]);
+        git(&["config", "user.email", "test@example.com"]);
+        std::fs::write(path.join("old.bin"), vec![1u8; 4096]).unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Initial"]);
+        git(&["checkout", "-q", "-b", "feat"]);
+        std::fs::write(path.join("small.txt"), "hi").unwrap();
+        std::fs::write(path.join("big.bin"), vec![2u8; 2048]).unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Add files"]);
+
+        let repo = Repository::open(path).unwrap();
+        // Blobs already on the base branch aren't outgoing
+        let blobs = large_blobs(&repo, "main", "feat", 1024).unwrap();
+        assert_eq!(blobs.len(), 1);
+        assert_eq!((blobs[0].path.as_str(), blobs[0].size), ("big.bin", 2048));
+        assert!(large_blobs(&repo, "main", "feat", 4096).unwrap().is_empty());
+    }
+}
@@ -6,15 +6,26 @@
 
 pub mod branch;
 pub mod cache;
+pub mod cherry_pick;
+pub mod incremental;
+pub mod lfs;
+pub mod reference;
 pub mod remote;
+pub mod revision;
+pub mod signing;
+pub mod sparse;
 pub mod status;
+#[cfg(test)]
+pub mod testing;
 
 pub use branch::*;
-pub use cache::{invalidate_status_cache, GitStatusCache, STATUS_CACHE};
+pub use cache::{cached_graph_ahead_behind, invalidate_status_cache, GitStatusCache, STATUS_CACHE};
 pub use remote::*;
 pub use status::*;
 
-use git2::Repository;
+use crate::util::profile::{self, Phase, ProfiledCommand};
+use crate::util::redact::redact;
+use git2::{Repository, Signature};
 use std::path::Path;
 use std::process::Command;
 use thiserror::Error;
@@ -22,13 +33,13 @@ use thiserror::Error;
 /// Errors that can occur during git operations
 #[derive(Error, Debug)]
 pub enum GitError {
-    #[error("Git error: {0}")]
+    #[error("Git error: {}", redact(&.0.to_string()))]
     Git(#[from] git2::Error),
 
     #[error("Repository not found: {0}")]
     NotFound(String),
 
-    #[error("Not a git repository: {0}")]
+    #[error("Not a git repository: {}", redact(.0))]
     NotARepo(String),
 
     #[error("Branch not found: {0}")]
@@ -37,7 +48,7 @@ pub enum GitError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
-    #[error("Operation failed: {0}")]
+    #[error("Operation failed: {}", redact(.0))]
     OperationFailed(String),
 
     #[error("Reference error: {0}")]
@@ -45,10 +56,19 @@ pub enum GitError {
 
     #[error("Object error: {0}")]
     Object(String),
+
+    #[error("Remote moved: {remote}/{branch} is at {actual}, expected {expected}; fetch and review the new commits before force pushing")]
+    RemoteMoved {
+        remote: String,
+        branch: String,
+        expected: String,
+        actual: String,
+    },
 }
 
 /// Open a git repository at the given path
 pub fn open_repo<P: AsRef<Path>>(path: P) -> Result<Repository, GitError> {
+    let _span = profile::span(Phase::Git, || "open", Some(path.as_ref()));
     Repository::open(path.as_ref())
         .map_err(|e| GitError::NotARepo(format!("{}: {}", path.as_ref().display(), e)))
 }
@@ -64,24 +84,33 @@ pub fn path_exists<P: AsRef<Path>>(path: P) -> bool {
 }
 
 /// Clone a repository
+///
+/// With `reference`, objects already in that repo are borrowed through
+/// alternates instead of downloaded (see [`reference`]).
 pub fn clone_repo<P: AsRef<Path>>(
     url: &str,
     path: P,
     branch: Option<&str>,
+    reference: Option<&Path>,
 ) -> Result<Repository, GitError> {
     let path = path.as_ref();
 
+    let reference = reference.map(|r| r.to_string_lossy().to_string());
     let mut args = vec!["clone"];
     if let Some(b) = branch {
         args.push("-b");
         args.push(b);
     }
+    if let Some(ref reference) = reference {
+        args.push("--reference");
+        args.push(reference);
+    }
     args.push(url);
     args.push(path.to_str().unwrap_or("."));
 
     let output = Command::new("git")
         .args(&args)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -101,6 +130,27 @@ pub fn get_workdir(repo: &Repository) -> &Path {
     repo.workdir().unwrap_or_else(|| repo.path())
 }
 
+/// Run `git <args>` in `dir` and return its stdout
+///
+/// A non-zero exit becomes [`GitError::OperationFailed`] with git's stderr.
+pub fn run_git(dir: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .timed_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 /// Get the current branch name
 pub fn get_current_branch(repo: &Repository) -> Result<String, GitError> {
     let head = repo
@@ -119,6 +169,58 @@ pub fn get_current_branch(repo: &Repository) -> Result<String, GitError> {
     }
 }
 
+/// Resolve the committer identity for a repository
+///
+/// `user.name`/`user.email` are read through git2's config stack (local,
+/// global, XDG and system scopes, honoring `include`/`includeIf`). As with
+/// git, `GIT_COMMITTER_NAME`/`GIT_COMMITTER_EMAIL` take precedence and
+/// `EMAIL` is used as a last resort for the address.
+pub fn get_signature(repo: &Repository) -> Result<Signature<'static>, GitError> {
+    let config = repo.config()?;
+    let from_env = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+    let name = from_env("GIT_COMMITTER_NAME").or_else(|| config.get_string("user.name").ok());
+    let email = from_env("GIT_COMMITTER_EMAIL")
+        .or_else(|| config.get_string("user.email").ok())
+        .or_else(|| from_env("EMAIL"));
+
+    match (name, email) {
+        (Some(name), Some(email)) => Ok(Signature::now(&name, &email)?),
+        (name, email) => {
+            let mut missing = Vec::new();
+            if name.is_none() {
+                missing.push("user.name");
+            }
+            if email.is_none() {
+                missing.push("user.email");
+            }
+            Err(GitError::OperationFailed(format!(
+                "git identity not configured ({} missing). Set it with:\n  git config --global user.name \"Your Name\"\n  git config --global user.email \"you@example.com\"",
+                missing.join(", ")
+            )))
+        }
+    }
+}
+
+/// Parse an identity in `Name <email>` form (as accepted by `--author`)
+pub fn parse_identity(identity: &str) -> Result<Signature<'static>, GitError> {
+    let invalid = || {
+        GitError::OperationFailed(format!(
+            "invalid identity '{}': expected \"Name <email>\"",
+            identity
+        ))
+    };
+
+    let (name, rest) = identity.split_once('<').ok_or_else(invalid)?;
+    let email = rest.strip_suffix('>').ok_or_else(invalid)?.trim();
+    let name = name.trim();
+    if name.is_empty() || email.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(Signature::now(name, email)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +243,30 @@ mod tests {
         assert!(!path_exists(temp.path().join("nonexistent")));
     }
 
+    #[test]
+    fn test_parse_identity() {
+        let sig = parse_identity("Jane Doe <jane@example.com>").unwrap();
+        assert_eq!(sig.name(), Some("Jane Doe"));
+        assert_eq!(sig.email(), Some("jane@example.com"));
+
+        assert!(parse_identity("Jane Doe").is_err());
+        assert!(parse_identity("<jane@example.com>").is_err());
+    }
+
+    #[test]
+    fn test_get_signature_from_repo_config() {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Repo User").unwrap();
+        config.set_str("user.email", "repo@example.com").unwrap();
+
+        let sig = get_signature(&repo).unwrap();
+        if std::env::var("GIT_COMMITTER_NAME").is_err() {
+            assert_eq!(sig.name(), Some("Repo User"));
+        }
+    }
+
     #[test]
     fn test_open_repo() {
         let temp = TempDir::new().unwrap();
@@ -1,6 +1,7 @@
 //! Git remote operations
 
-use git2::Repository;
+use crate::util::profile::ProfiledCommand;
+use git2::{Oid, Repository};
 use std::process::Command;
 
 use super::cache::invalidate_status_cache;
@@ -13,7 +14,7 @@ pub fn get_remote_url(repo: &Repository, remote: &str) -> Result<Option<String>,
     let output = Command::new("git")
         .args(["remote", "get-url", remote])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if output.status.success() {
@@ -32,13 +33,13 @@ pub fn set_remote_url(repo: &Repository, remote: &str, url: &str) -> Result<(),
         Command::new("git")
             .args(["remote", "add", remote, url])
             .current_dir(repo_path)
-            .output()
+            .timed_output()
             .map_err(|e| GitError::OperationFailed(e.to_string()))?;
     } else {
         Command::new("git")
             .args(["remote", "set-url", remote, url])
             .current_dir(repo_path)
-            .output()
+            .timed_output()
             .map_err(|e| GitError::OperationFailed(e.to_string()))?;
     }
     Ok(())
@@ -51,7 +52,7 @@ pub fn fetch_remote(repo: &Repository, remote: &str) -> Result<(), GitError> {
     let output = Command::new("git")
         .args(["fetch", remote])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -59,9 +60,23 @@ pub fn fetch_remote(repo: &Repository, remote: &str) -> Result<(), GitError> {
         return Err(GitError::OperationFailed(stderr.to_string()));
     }
 
+    // Invalidate cache
+    invalidate_status_cache(&repo_path.to_path_buf());
+
     Ok(())
 }
 
+/// When remote data was last refreshed, from the mtime of `FETCH_HEAD`
+///
+/// A fresh clone has no `FETCH_HEAD`, so `packed-refs` (written by the clone)
+/// stands in. Returns `None` if neither exists.
+pub fn last_fetch_time(repo: &Repository) -> Option<std::time::SystemTime> {
+    let git_dir = repo.path();
+    ["FETCH_HEAD", "packed-refs"]
+        .iter()
+        .find_map(|name| std::fs::metadata(git_dir.join(name)).ok()?.modified().ok())
+}
+
 /// Pull latest changes (fetch + merge)
 pub fn pull_latest(repo: &Repository, remote: &str) -> Result<(), GitError> {
     let repo_path = super::get_workdir(repo);
@@ -69,7 +84,7 @@ pub fn pull_latest(repo: &Repository, remote: &str) -> Result<(), GitError> {
     let output = Command::new("git")
         .args(["pull", remote])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -110,7 +125,7 @@ pub fn push_branch(
     let output = Command::new("git")
         .args(&args)
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -118,6 +133,9 @@ pub fn push_branch(
         return Err(GitError::OperationFailed(stderr.to_string()));
     }
 
+    // Invalidate cache
+    invalidate_status_cache(&repo_path.to_path_buf());
+
     Ok(())
 }
 
@@ -132,17 +150,113 @@ pub fn force_push_branch(
     let output = Command::new("git")
         .args(["push", "--force", remote, branch_name])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    // Invalidate cache
+    invalidate_status_cache(&repo_path.to_path_buf());
+
+    Ok(())
+}
+
+/// Commit a remote-tracking branch (`refs/remotes/<remote>/<branch>`) points at
+pub fn get_remote_tracking_oid(repo: &Repository, remote: &str, branch_name: &str) -> Option<Oid> {
+    repo.find_reference(&format!("refs/remotes/{}/{}", remote, branch_name))
+        .ok()?
+        .target()
+}
+
+/// Commit a branch points at on the remote itself, or `None` if it doesn't exist there
+///
+/// Unlike [`get_remote_tracking_oid`], this asks the remote (`git ls-remote`)
+/// rather than trusting the last fetch.
+pub fn get_remote_branch_oid(
+    repo: &Repository,
+    remote: &str,
+    branch_name: &str,
+) -> Result<Option<Oid>, GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let output = Command::new("git")
+        .args(["ls-remote", remote, &format!("refs/heads/{}", branch_name)])
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.to_string()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.split_whitespace().next() {
+        Some(sha) => Oid::from_str(sha)
+            .map(Some)
+            .map_err(|e| GitError::OperationFailed(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Force push branch to remote, only if the remote branch is still at `expected`
+///
+/// The remote tip is checked right before pushing, and the push itself carries
+/// a `--force-with-lease` so a push landing in between is also refused. Either
+/// way the result is [`GitError::RemoteMoved`].
+pub fn force_push_with_lease(
+    repo: &Repository,
+    branch_name: &str,
+    remote: &str,
+    expected: Oid,
+) -> Result<(), GitError> {
+    let repo_path = super::get_workdir(repo);
+
+    let remote_moved = |actual: Option<Oid>| GitError::RemoteMoved {
+        remote: remote.to_string(),
+        branch: branch_name.to_string(),
+        expected: short_oid(Some(expected)),
+        actual: short_oid(actual),
+    };
+
+    let actual = get_remote_branch_oid(repo, remote, branch_name)?;
+    if actual != Some(expected) {
+        return Err(remote_moved(actual));
+    }
+
+    let lease = format!("--force-with-lease=refs/heads/{}:{}", branch_name, expected);
+    let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch_name);
+    let output = Command::new("git")
+        .args(["push", &lease, remote, &refspec])
+        .current_dir(repo_path)
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("stale info") {
+            let actual = get_remote_branch_oid(repo, remote, branch_name).unwrap_or(None);
+            return Err(remote_moved(actual));
+        }
         return Err(GitError::OperationFailed(stderr.to_string()));
     }
 
+    // Invalidate cache
+    invalidate_status_cache(&repo_path.to_path_buf());
+
     Ok(())
 }
 
+fn short_oid(oid: Option<Oid>) -> String {
+    match oid {
+        Some(oid) => oid.to_string()[..7].to_string(),
+        None => "(deleted)".to_string(),
+    }
+}
+
 /// Delete a remote branch
 pub fn delete_remote_branch(
     repo: &Repository,
@@ -154,7 +268,7 @@ pub fn delete_remote_branch(
     let output = Command::new("git")
         .args(["push", remote, "--delete", branch_name])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -165,6 +279,36 @@ pub fn delete_remote_branch(
     Ok(())
 }
 
+/// Delete a remote branch only if it still points at `expected`, so commits
+/// pushed since it was inspected aren't lost
+pub fn delete_remote_branch_at(
+    repo: &Repository,
+    branch_name: &str,
+    remote: &str,
+    expected: Oid,
+) -> Result<(), GitError> {
+    let repo_path = super::get_workdir(repo);
+    let refname = format!("refs/heads/{}", branch_name);
+
+    let output = Command::new("git")
+        .args([
+            "push",
+            &format!("--force-with-lease={}:{}", refname, expected),
+            remote,
+            &format!(":{}", refname),
+        ])
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.trim().to_string()));
+    }
+
+    Ok(())
+}
+
 /// Get upstream tracking branch name
 pub fn get_upstream_branch(
     repo: &Repository,
@@ -184,7 +328,7 @@ pub fn get_upstream_branch(
             &format!("{}@{{upstream}}", branch),
         ])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if output.status.success() {
@@ -223,7 +367,7 @@ pub fn set_upstream_branch(repo: &Repository, remote: &str) -> Result<(), GitErr
             &format!("{}/{}", remote, branch_name),
         ])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -234,14 +378,51 @@ pub fn set_upstream_branch(repo: &Repository, remote: &str) -> Result<(), GitErr
     Ok(())
 }
 
+/// How much of the working state `git reset` rewinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    /// Move the branch only; changes stay staged
+    Soft,
+    /// Move the branch and reset the index; changes stay in the working tree
+    Mixed,
+    /// Move the branch and discard all changes
+    Hard,
+    /// Move the branch and update the working tree, keeping local changes
+    Keep,
+}
+
+impl ResetMode {
+    /// The `git reset` flag for this mode
+    pub fn flag(self) -> &'static str {
+        match self {
+            ResetMode::Soft => "--soft",
+            ResetMode::Mixed => "--mixed",
+            ResetMode::Hard => "--hard",
+            ResetMode::Keep => "--keep",
+        }
+    }
+
+    /// The mode's name, e.g. `hard`
+    pub fn name(self) -> &'static str {
+        &self.flag()[2..]
+    }
+}
+
 /// Hard reset to a target
 pub fn reset_hard(repo: &Repository, target: &str) -> Result<(), GitError> {
+    reset(repo, target, ResetMode::Hard)
+}
+
+/// Reset the current branch to `target`
+///
+/// Runs `git reset`, so the previous tip is kept in `ORIG_HEAD`.
+pub fn reset(repo: &Repository, target: &str, mode: ResetMode) -> Result<(), GitError> {
     let repo_path = super::get_workdir(repo);
 
     let output = Command::new("git")
-        .args(["reset", "--hard", target])
+        .args(["reset", "--quiet", mode.flag(), target])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .map_err(|e| GitError::OperationFailed(e.to_string()))?;
 
     if !output.status.success() {
@@ -417,6 +598,57 @@ mod tests {
         assert_eq!(url, Some("https://github.com/test/repo.git".to_string()));
     }
 
+    #[test]
+    fn test_force_push_with_lease_detects_moved_remote() {
+        let (temp, repo) = setup_test_repo();
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+
+        let bare = TempDir::new().unwrap();
+        git(bare.path(), &["init", "--bare", "-q"]);
+        let url = bare.path().to_string_lossy().to_string();
+        git(temp.path(), &["remote", "add", "origin", &url]);
+        git(temp.path(), &["checkout", "-q", "-b", "feat"]);
+        git(temp.path(), &["push", "-q", "origin", "feat"]);
+        let expected = get_remote_tracking_oid(&repo, "origin", "feat").unwrap();
+
+        // Rewrite our commit and push under a matching lease
+        git(temp.path(), &["commit", "-q", "--amend", "-m", "Reworded"]);
+        force_push_with_lease(&repo, "feat", "origin", expected).unwrap();
+
+        // The old tip is now stale, so a second lease on it must be refused
+        git(
+            temp.path(),
+            &["commit", "-q", "--amend", "-m", "Reworded again"],
+        );
+        let err = force_push_with_lease(&repo, "feat", "origin", expected).unwrap_err();
+        assert!(matches!(err, GitError::RemoteMoved { .. }), "{}", err);
+
+        // Deleting the remote branch also breaks the lease
+        let current = get_remote_branch_oid(&repo, "origin", "feat")
+            .unwrap()
+            .unwrap();
+        git(temp.path(), &["push", "-q", "origin", "--delete", "feat"]);
+        let err = force_push_with_lease(&repo, "feat", "origin", current).unwrap_err();
+        assert!(err.to_string().contains("is at (deleted)"), "{}", err);
+    }
+
+    #[test]
+    fn test_last_fetch_time() {
+        let (temp, repo) = setup_test_repo();
+        assert!(last_fetch_time(&repo).is_none());
+
+        let before = std::time::SystemTime::now() - std::time::Duration::from_secs(5);
+        fs::write(temp.path().join(".git").join("FETCH_HEAD"), "").unwrap();
+        assert!(last_fetch_time(&repo).unwrap() >= before);
+    }
+
     #[test]
     fn test_set_remote_url() {
         let (temp, repo) = setup_test_repo();
@@ -0,0 +1,205 @@
+//! Manifest revision pins
+//!
+//! A repo's `revision:` names what sync checks out instead of its default
+//! branch. A branch is tracked and pulled like the default branch would be;
+//! a tag or commit is checked out on a detached HEAD.
+
+use crate::util::profile::ProfiledCommand;
+use git2::{Oid, Repository};
+use std::process::Command;
+
+use super::branch::{branch_exists, remote_branch_exists};
+use super::cache::invalidate_status_cache;
+use super::remote::{fetch_remote, safe_pull_latest, SafePullResult};
+use super::status::has_uncommitted_changes;
+use super::{get_current_branch, get_workdir, GitError};
+
+/// What a revision names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Revision {
+    /// A branch on the remote (or a local-only branch)
+    Branch(String),
+    /// A tag or commit
+    Detached(Oid),
+}
+
+/// Work out what `revision` names in `repo`, after a fetch from `remote`
+pub fn resolve_revision(
+    repo: &Repository,
+    revision: &str,
+    remote: &str,
+) -> Result<Revision, GitError> {
+    if remote_branch_exists(repo, revision, remote) || branch_exists(repo, revision) {
+        return Ok(Revision::Branch(revision.to_string()));
+    }
+    for spec in [
+        format!("refs/tags/{}^{{commit}}", revision),
+        format!("{}^{{commit}}", revision),
+    ] {
+        if let Ok(object) = repo.revparse_single(&spec) {
+            return Ok(Revision::Detached(object.id()));
+        }
+    }
+    Err(GitError::Reference(format!(
+        "Revision '{}' not found (not a branch, tag, or commit on {})",
+        revision, remote
+    )))
+}
+
+/// Whether HEAD is already at `revision`
+pub fn at_revision(repo: &Repository, revision: &Revision) -> bool {
+    let Ok(head) = repo.head() else {
+        return false;
+    };
+    match revision {
+        Revision::Branch(name) => head.is_branch() && head.shorthand() == Some(name.as_str()),
+        Revision::Detached(oid) => !head.is_branch() && head.target() == Some(*oid),
+    }
+}
+
+/// Check out `revision`, creating a local branch that tracks `remote` if needed
+pub fn checkout_revision(
+    repo: &Repository,
+    revision: &Revision,
+    remote: &str,
+) -> Result<(), GitError> {
+    let repo_path = get_workdir(repo);
+    let args = match revision {
+        Revision::Branch(name) if branch_exists(repo, name) => {
+            vec!["checkout".to_string(), "--quiet".to_string(), name.clone()]
+        }
+        Revision::Branch(name) => vec![
+            "checkout".to_string(),
+            "--quiet".to_string(),
+            "--track".to_string(),
+            format!("{}/{}", remote, name),
+        ],
+        Revision::Detached(oid) => vec![
+            "checkout".to_string(),
+            "--quiet".to_string(),
+            "--detach".to_string(),
+            oid.to_string(),
+        ],
+    };
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(repo_path)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(stderr.trim().to_string()));
+    }
+
+    invalidate_status_cache(&repo_path.to_path_buf());
+    Ok(())
+}
+
+/// Bring a repo with a manifest `revision:` to that revision
+///
+/// A repo on its default branch, on the revision's branch, or on a detached
+/// HEAD follows the pin: it's moved to the revision (unless it has local
+/// changes), and a branch revision is then pulled. A repo on some other
+/// branch is left there, with a branch revision as its base.
+pub fn sync_revision(
+    git_repo: &Repository,
+    default_branch: &str,
+    revision: &str,
+) -> Result<SafePullResult, GitError> {
+    fetch_remote(git_repo, "origin")?;
+    let target = resolve_revision(git_repo, revision, "origin")?;
+
+    let detached = git_repo.head_detached().unwrap_or(false);
+    let current = get_current_branch(git_repo)?;
+    let following = detached || current == default_branch || current == revision;
+    if !following {
+        return match target {
+            Revision::Branch(_) => safe_pull_latest(git_repo, revision, "origin"),
+            Revision::Detached(_) => Ok(SafePullResult {
+                pulled: false,
+                recovered: false,
+                message: None,
+            }),
+        };
+    }
+
+    let mut moved = false;
+    if !at_revision(git_repo, &target) {
+        if has_uncommitted_changes(git_repo)? {
+            return Ok(SafePullResult {
+                pulled: false,
+                recovered: false,
+                message: Some(format!(
+                    "has uncommitted changes; not moving to '{}'",
+                    revision
+                )),
+            });
+        }
+        checkout_revision(git_repo, &target, "origin")?;
+        moved = true;
+    }
+
+    let mut result = match target {
+        Revision::Branch(_) => safe_pull_latest(git_repo, revision, "origin")?,
+        Revision::Detached(_) => SafePullResult {
+            pulled: moved,
+            recovered: false,
+            message: None,
+        },
+    };
+    if moved && result.pulled {
+        result.message = Some(format!("moved to {}", revision));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::open_repo;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_and_checkout_revision() {
+        let temp = TempDir::new().unwrap();
+        let upstream = temp.path().join("upstream");
+        init_repo(&upstream);
+        git(
+            &upstream,
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        );
+        git(&upstream, &["tag", "v1.0.0"]);
+        git(&upstream, &["branch", "release/1.x"]);
+        git(&upstream, &["commit", "-q", "--allow-empty", "-m", "Next"]);
+
+        let clone = temp.path().join("clone");
+        git(
+            temp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        let repo = open_repo(&clone).unwrap();
+
+        let branch = resolve_revision(&repo, "release/1.x", "origin").unwrap();
+        assert_eq!(branch, Revision::Branch("release/1.x".to_string()));
+        assert!(!at_revision(&repo, &branch));
+        checkout_revision(&repo, &branch, "origin").unwrap();
+        assert!(at_revision(&repo, &branch));
+        let upstream_ref = repo
+            .find_branch("release/1.x", git2::BranchType::Local)
+            .unwrap()
+            .upstream()
+            .unwrap();
+        assert_eq!(upstream_ref.name().unwrap(), Some("origin/release/1.x"));
+
+        let tag = resolve_revision(&repo, "v1.0.0", "origin").unwrap();
+        assert!(matches!(tag, Revision::Detached(_)));
+        checkout_revision(&repo, &tag, "origin").unwrap();
+        assert!(at_revision(&repo, &tag));
+        assert!(repo.head_detached().unwrap());
+
+        assert!(resolve_revision(&repo, "no-such-thing", "origin").is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! Commit signing
+//!
+//! libgit2 never signs commits on its own, so commits created through git2
+//! are signed here according to the same git config that `git commit` honors:
+//! `commit.gpgsign`, `gpg.format` (`openpgp`, `x509`, `ssh`), `user.signingkey`
+//! and the `gpg.program` / `gpg.<format>.program` overrides.
+
+use git2::{Commit, Oid, Repository, Signature, Tree};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::GitError;
+
+/// Signature format from `gpg.format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningFormat {
+    OpenPgp,
+    X509,
+    Ssh,
+}
+
+/// Signing settings resolved from git config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningConfig {
+    pub format: SigningFormat,
+    /// `user.signingkey`, if set
+    pub key: Option<String>,
+    /// Program used to produce the signature
+    pub program: String,
+}
+
+impl SigningConfig {
+    /// Read signing settings; returns `None` unless `commit.gpgsign` is true
+    pub fn from_repo(repo: &Repository) -> Result<Option<Self>, GitError> {
+        let config = repo.config()?;
+        if !config.get_bool("commit.gpgsign").unwrap_or(false) {
+            return Ok(None);
+        }
+        Self::from_config(&config).map(Some)
+    }
+
+    fn from_config(config: &git2::Config) -> Result<Self, GitError> {
+        let format = match config.get_string("gpg.format").ok().as_deref() {
+            None | Some("openpgp") => SigningFormat::OpenPgp,
+            Some("x509") => SigningFormat::X509,
+            Some("ssh") => SigningFormat::Ssh,
+            Some(other) => {
+                return Err(GitError::OperationFailed(format!(
+                    "unsupported gpg.format '{}'",
+                    other
+                )))
+            }
+        };
+
+        let (section, default_program) = match format {
+            SigningFormat::OpenPgp => ("gpg.openpgp.program", "gpg"),
+            SigningFormat::X509 => ("gpg.x509.program", "gpgsm"),
+            SigningFormat::Ssh => ("gpg.ssh.program", "ssh-keygen"),
+        };
+        let program = config
+            .get_string(section)
+            .ok()
+            .or_else(|| {
+                // gpg.program only applies to the OpenPGP format
+                (format == SigningFormat::OpenPgp)
+                    .then(|| config.get_string("gpg.program").ok())
+                    .flatten()
+            })
+            .unwrap_or_else(|| default_program.to_string());
+
+        let key = config
+            .get_string("user.signingkey")
+            .ok()
+            .filter(|k| !k.is_empty());
+
+        if format == SigningFormat::Ssh && key.is_none() {
+            return Err(GitError::OperationFailed(
+                "gpg.format=ssh requires user.signingkey to be set".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            format,
+            key,
+            program,
+        })
+    }
+
+    /// Produce a detached signature for `payload`
+    pub fn sign(&self, payload: &str) -> Result<String, GitError> {
+        match self.format {
+            SigningFormat::OpenPgp | SigningFormat::X509 => self.sign_gpg(payload),
+            SigningFormat::Ssh => self.sign_ssh(payload),
+        }
+    }
+
+    fn sign_gpg(&self, payload: &str) -> Result<String, GitError> {
+        let mut args = vec!["--status-fd=2", "-bsa"];
+        if let Some(key) = &self.key {
+            args.push("-u");
+            args.push(key);
+        }
+        let stdout = run_signer(&self.program, &args, Some(payload))?;
+        Ok(stdout)
+    }
+
+    fn sign_ssh(&self, payload: &str) -> Result<String, GitError> {
+        let key = self.key.as_deref().unwrap_or_default();
+        let scratch = ScratchDir::new()?;
+
+        // A literal public key means the private half lives in ssh-agent
+        let (key_file, use_agent) = match key.strip_prefix("key::") {
+            Some(literal) => (scratch.write("signing_key.pub", literal)?, true),
+            None if key.starts_with("ssh-") => (scratch.write("signing_key.pub", key)?, true),
+            None => (PathBuf::from(expand_home(key)), false),
+        };
+        let payload_file = scratch.write("payload", payload)?;
+
+        let key_arg = key_file.to_string_lossy().to_string();
+        let payload_arg = payload_file.to_string_lossy().to_string();
+        let mut args = vec!["-Y", "sign", "-n", "git", "-f", &key_arg];
+        if use_agent {
+            args.push("-U");
+        }
+        args.push(&payload_arg);
+        run_signer(&self.program, &args, None)?;
+
+        std::fs::read_to_string(payload_file.with_extension("sig")).map_err(|e| {
+            GitError::OperationFailed(format!("ssh-keygen produced no signature: {}", e))
+        })
+    }
+}
+
+/// Create a commit, signing it when the repository config asks for it
+///
+/// Behaves like `Repository::commit` with `update_ref = Some("HEAD")`. Pass
+/// `sign = false` to skip signing regardless of config (`--no-sign`).
+pub fn commit_with_signing(
+    repo: &Repository,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+    sign: bool,
+) -> Result<Oid, GitError> {
+    let config = if sign {
+        SigningConfig::from_repo(repo)?
+    } else {
+        None
+    };
+
+    let Some(config) = config else {
+        return Ok(repo.commit(Some("HEAD"), author, committer, message, tree, parents)?);
+    };
+
+    let oid = signed_commit(repo, &config, author, committer, message, tree, parents)?;
+
+    // commit_signed doesn't move any refs; follow HEAD (even if unborn)
+    let head = repo.find_reference("HEAD")?;
+    match head.symbolic_target() {
+        Some(branch) => {
+            let summary = message.lines().next().unwrap_or("");
+            repo.reference(branch, oid, true, &format!("commit: {}", summary))?;
+        }
+        None => repo.set_head_detached(oid)?,
+    }
+
+    Ok(oid)
+}
+
+/// Create a commit without moving any ref, signing it like [`commit_with_signing`]
+pub fn create_commit_with_signing(
+    repo: &Repository,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+    sign: bool,
+) -> Result<Oid, GitError> {
+    let config = if sign {
+        SigningConfig::from_repo(repo)?
+    } else {
+        None
+    };
+
+    match config {
+        Some(config) => signed_commit(repo, &config, author, committer, message, tree, parents),
+        None => Ok(repo.commit(None, author, committer, message, tree, parents)?),
+    }
+}
+
+fn signed_commit(
+    repo: &Repository,
+    config: &SigningConfig,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) -> Result<Oid, GitError> {
+    let buffer = repo.commit_create_buffer(author, committer, message, tree, parents)?;
+    let payload = std::str::from_utf8(&buffer)
+        .map_err(|e| GitError::OperationFailed(format!("invalid commit buffer: {}", e)))?;
+    let signature = config.sign(payload)?;
+    Ok(repo.commit_signed(payload, &signature, None)?)
+}
+
+fn run_signer(program: &str, args: &[&str], stdin: Option<&str>) -> Result<String, GitError> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            GitError::OperationFailed(format!(
+                "failed to run signing program '{}': {} (use --no-sign to skip signing)",
+                program, e
+            ))
+        })?;
+
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr
+            .lines()
+            .filter(|l| !l.starts_with("[GNUPG:]"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        return Err(GitError::OperationFailed(format!(
+            "signing failed: {} (is the signing key available? use --no-sign to skip signing)",
+            detail.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Temporary directory removed on drop
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new() -> Result<Self, GitError> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let dir =
+            std::env::temp_dir().join(format!("gitgrip-sign-{}-{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self(dir))
+    }
+
+    fn write(&self, name: &str, content: &str) -> Result<PathBuf, GitError> {
+        let path = self.0.join(name);
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config_with(entries: &[(&str, &str)]) -> (TempDir, git2::Config) {
+        let temp = TempDir::new().unwrap();
+        let mut config = git2::Config::open(&temp.path().join("config")).unwrap();
+        for (key, value) in entries {
+            config.set_str(key, value).unwrap();
+        }
+        (temp, config)
+    }
+
+    #[test]
+    fn test_default_openpgp() {
+        let (_temp, config) = config_with(&[]);
+        let signing = SigningConfig::from_config(&config).unwrap();
+        assert_eq!(signing.format, SigningFormat::OpenPgp);
+        assert_eq!(signing.program, "gpg");
+        assert!(signing.key.is_none());
+    }
+
+    #[test]
+    fn test_ssh_format() {
+        let (_temp, config) = config_with(&[
+            ("gpg.format", "ssh"),
+            ("user.signingkey", "~/.ssh/id_ed25519.pub"),
+            ("gpg.program", "ignored-for-ssh"),
+        ]);
+        let signing = SigningConfig::from_config(&config).unwrap();
+        assert_eq!(signing.format, SigningFormat::Ssh);
+        assert_eq!(signing.program, "ssh-keygen");
+    }
+
+    #[test]
+    fn test_ssh_requires_key() {
+        let (_temp, config) = config_with(&[("gpg.format", "ssh")]);
+        assert!(SigningConfig::from_config(&config).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_with_fake_gpg() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+
+        let gpg = temp.path().join("fake-gpg");
+        std::fs::write(
+            &gpg,
+            "#!/bin/sh\ncat > /dev/null\nprintf -- '-----BEGIN PGP SIGNATURE-----\\nfake\\n-----END PGP SIGNATURE-----\\n'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&gpg, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut config = repo.config().unwrap();
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config
+            .set_str("gpg.program", gpg.to_str().unwrap())
+            .unwrap();
+
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+
+        let oid = commit_with_signing(&repo, &sig, &sig, "Signed", &tree, &[], true).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(oid));
+        let (signature, _) = repo.extract_signature(&oid, None).unwrap();
+        assert!(signature.as_str().unwrap().contains("fake"));
+
+        // --no-sign skips signing entirely
+        let parent = repo.find_commit(oid).unwrap();
+        let oid =
+            commit_with_signing(&repo, &sig, &sig, "Unsigned", &tree, &[&parent], false).unwrap();
+        assert!(repo.extract_signature(&oid, None).is_err());
+    }
+
+    #[test]
+    fn test_missing_program_is_clear_error() {
+        let signing = SigningConfig {
+            format: SigningFormat::OpenPgp,
+            key: None,
+            program: "gitgrip-no-such-gpg".to_string(),
+        };
+        let err = signing.sign("payload").unwrap_err().to_string();
+        assert!(err.contains("--no-sign"));
+    }
+}
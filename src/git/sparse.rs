@@ -0,0 +1,127 @@
+//! Sparse checkout support
+//!
+//! Repos with a `sparse:` list in the manifest are checked out in cone mode so
+//! only the listed directories (plus top-level files) are materialized.
+
+use crate::util::profile::ProfiledCommand;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+use super::{open_repo, run_git, GitError};
+
+/// Clone a repository with only the given directories checked out
+pub fn clone_sparse(
+    url: &str,
+    path: &Path,
+    branch: Option<&str>,
+    patterns: &[String],
+    reference: Option<&Path>,
+) -> Result<(), GitError> {
+    let path_str = path.to_string_lossy().to_string();
+    let reference = reference.map(|r| r.to_string_lossy().to_string());
+    let mut args = vec!["clone", "--sparse"];
+    if let Some(b) = branch {
+        args.push("-b");
+        args.push(b);
+    }
+    if let Some(ref reference) = reference {
+        args.push("--reference");
+        args.push(reference);
+    }
+    args.push(url);
+    args.push(&path_str);
+
+    let output = Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::OperationFailed(format!(
+            "git clone failed: {}",
+            stderr
+        )));
+    }
+
+    set_sparse_patterns(path, patterns)
+}
+
+/// Directories currently configured for sparse checkout, or `None` if disabled
+pub fn get_sparse_patterns(repo_path: &Path) -> Result<Option<Vec<String>>, GitError> {
+    let repo = open_repo(repo_path)?;
+    let enabled = repo
+        .config()?
+        .get_bool("core.sparseCheckout")
+        .unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+
+    let output = run_git(repo_path, &["sparse-checkout", "list"])?;
+    Ok(Some(output.lines().map(|l| l.trim().to_string()).collect()))
+}
+
+/// Configure cone-mode sparse checkout with the given directories
+pub fn set_sparse_patterns(repo_path: &Path, patterns: &[String]) -> Result<(), GitError> {
+    let mut args = vec!["sparse-checkout", "set", "--cone", "--"];
+    args.extend(patterns.iter().map(|p| p.as_str()));
+    run_git(repo_path, &args).map(|_| ())
+}
+
+/// Whether the repo's sparse checkout differs from the manifest's `sparse:` list
+pub fn sparse_drifted(current: Option<&[String]>, expected: &[String]) -> bool {
+    match current {
+        Some(current) => normalize(current) != normalize(expected),
+        None => true,
+    }
+}
+
+fn normalize(patterns: &[String]) -> BTreeSet<String> {
+    patterns
+        .iter()
+        .map(|p| p.trim().trim_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::init_repo;
+    use tempfile::TempDir;
+
+    fn patterns(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sparse_drifted() {
+        let expected = patterns(&["services/api", "libs/"]);
+        let current = patterns(&["libs", "services/api"]);
+        assert!(!sparse_drifted(Some(&current), &expected));
+        assert!(sparse_drifted(Some(&patterns(&["libs"])), &expected));
+        assert!(sparse_drifted(None, &expected));
+    }
+
+    #[test]
+    fn test_set_and_get_sparse_patterns() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path();
+        init_repo(path);
+        for dir in ["docs", "src"] {
+            std::fs::create_dir(path.join(dir)).unwrap();
+            std::fs::write(path.join(dir).join("file.txt"), dir).unwrap();
+        }
+        run_git(path, &["add", "."]).unwrap();
+        run_git(path, &["commit", "-m", "Initial commit"]).unwrap();
+
+        assert_eq!(get_sparse_patterns(path).unwrap(), None);
+
+        set_sparse_patterns(path, &patterns(&["src"])).unwrap();
+        assert_eq!(get_sparse_patterns(path).unwrap(), Some(patterns(&["src"])));
+        assert!(path.join("src/file.txt").exists());
+        assert!(!path.join("docs/file.txt").exists());
+    }
+}
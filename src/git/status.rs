@@ -1,12 +1,21 @@
 //! Git status operations
 
-use git2::Repository;
-use std::path::PathBuf;
+use git2::{Oid, Repository, RepositoryState};
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use super::cache::STATUS_CACHE;
+use super::incremental::{full_porcelain_status, porcelain_status};
+use super::lfs::missing_lfs_objects;
 use super::{get_current_branch, open_repo, path_exists, GitError};
 use crate::core::repo::RepoInfo;
+use crate::util::profile::{self, Phase, ProfiledCommand};
+use crate::util::{max_workers, parallel_map};
+
+/// Untracked paths kept per repo by `gr status`; the rest are only counted
+pub const UNTRACKED_LIMIT: usize = 100;
 
 /// Repository status information
 #[derive(Debug, Clone)]
@@ -19,16 +28,80 @@ pub struct RepoStatusInfo {
     pub staged: Vec<String>,
     /// Modified files (not staged)
     pub modified: Vec<String>,
-    /// Untracked files
+    /// Untracked files (the first `untracked_count` paths, or fewer if truncated)
     pub untracked: Vec<String>,
+    /// Total number of untracked files
+    pub untracked_count: usize,
+    /// Whether `untracked` was capped and lists only some of the files
+    pub untracked_truncated: bool,
     /// Commits ahead of remote
     pub ahead: usize,
     /// Commits behind remote
     pub behind: usize,
+    /// Multi-step git operation in progress (merge, rebase, ...)
+    pub operation: Option<GitOperation>,
+    /// Files with unresolved merge conflicts
+    pub conflicted: Vec<String>,
+}
+
+/// A multi-step git operation left in progress in a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+impl GitOperation {
+    /// Detect the operation in progress from the repository state
+    pub fn detect(repo: &Repository) -> Option<Self> {
+        match repo.state() {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(Self::Merge),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(Self::Revert),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(Self::CherryPick)
+            }
+            RepositoryState::Bisect => Some(Self::Bisect),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+            | RepositoryState::ApplyMailbox
+            | RepositoryState::ApplyMailboxOrRebase => Some(Self::Rebase),
+        }
+    }
+
+    /// Git subcommand that drives this operation
+    pub fn git_command(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+            Self::Bisect => "bisect",
+        }
+    }
+}
+
+impl fmt::Display for GitOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Merge => "MERGING",
+            Self::Rebase => "REBASING",
+            Self::CherryPick => "CHERRY-PICKING",
+            Self::Revert => "REVERTING",
+            Self::Bisect => "BISECTING",
+        };
+        write!(f, "{}", label)
+    }
 }
 
 /// Repository status with name
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RepoStatus {
     /// Repository name
     pub name: String,
@@ -50,28 +123,71 @@ pub struct RepoStatus {
     pub ahead_main: usize,
     /// Commits behind default branch (main)
     pub behind_main: usize,
+    /// Whether an explicit comparison base didn't resolve in this repo
+    pub base_missing: bool,
+    /// Multi-step git operation in progress
+    pub operation: Option<GitOperation>,
+    /// Conflicted file count
+    pub conflicted: usize,
+    /// LFS-tracked files that are still pointers
+    pub lfs_missing: usize,
     /// Whether repo exists
     pub exists: bool,
 }
 
+impl RepoStatusInfo {
+    /// Drop untracked paths beyond `limit`, keeping the count
+    fn truncate_untracked(&mut self, limit: usize) {
+        if self.untracked.len() > limit {
+            self.untracked.truncate(limit);
+            self.untracked_truncated = true;
+        }
+    }
+}
+
 /// Get detailed status for a repository using git2
+///
+/// Always scans the full working tree, so it is safe for dirty checks.
 pub fn get_status_info(repo: &Repository) -> Result<RepoStatusInfo, GitError> {
+    get_status_info_limited(repo, None)
+}
+
+/// Get detailed status, keeping at most `untracked_limit` untracked paths
+///
+/// Repos with thousands of untracked files would otherwise allocate a path
+/// for each; the total is still reported in `untracked_count`.
+pub fn get_status_info_limited(
+    repo: &Repository,
+    untracked_limit: Option<usize>,
+) -> Result<RepoStatusInfo, GitError> {
+    scan_status_info(repo, untracked_limit, false)
+}
+
+/// Build status info, using the incremental snapshot when `incremental` is set
+///
+/// The incremental scan can miss in-place edits in very large repos, so it
+/// is only used for the cached status shown in tables.
+fn scan_status_info(
+    repo: &Repository,
+    untracked_limit: Option<usize>,
+    incremental: bool,
+) -> Result<RepoStatusInfo, GitError> {
+    let _span = profile::span(Phase::Status, || "status scan", repo.workdir());
     let current_branch = get_current_branch(repo)?;
 
     // Use git porcelain status for reliable parsing
     let repo_path = super::get_workdir(repo);
-
-    let output = Command::new("git")
-        .args(["status", "--porcelain=v1"])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| GitError::OperationFailed(e.to_string()))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = if incremental {
+        porcelain_status(repo)?
+    } else {
+        full_porcelain_status(repo)?
+    };
 
     let mut staged = Vec::new();
     let mut modified = Vec::new();
     let mut untracked = Vec::new();
+    let mut untracked_count = 0;
+    let mut conflicted = Vec::new();
 
     for line in stdout.lines() {
         if line.len() < 3 {
@@ -79,8 +195,24 @@ pub fn get_status_info(repo: &Repository) -> Result<RepoStatusInfo, GitError> {
         }
         let index_status = line.chars().next().unwrap_or(' ');
         let worktree_status = line.chars().nth(1).unwrap_or(' ');
+
+        // Untracked
+        if index_status == '?' && worktree_status == '?' {
+            untracked_count += 1;
+            if untracked_limit.map_or(true, |limit| untracked.len() < limit) {
+                untracked.push(line[3..].to_string());
+            }
+            continue;
+        }
+
         let path = line[3..].to_string();
 
+        // Unmerged paths (both sides touched, or one deleted)
+        if is_conflict_status(index_status, worktree_status) {
+            conflicted.push(path);
+            continue;
+        }
+
         // Staged changes (index)
         if matches!(index_status, 'A' | 'M' | 'D' | 'R' | 'C') {
             staged.push(path.clone());
@@ -90,14 +222,14 @@ pub fn get_status_info(repo: &Repository) -> Result<RepoStatusInfo, GitError> {
         if matches!(worktree_status, 'M' | 'D') {
             modified.push(path.clone());
         }
-
-        // Untracked
-        if index_status == '?' && worktree_status == '?' {
-            untracked.push(path);
-        }
     }
 
-    let is_clean = staged.is_empty() && modified.is_empty() && untracked.is_empty();
+    let operation = GitOperation::detect(repo);
+    let is_clean = staged.is_empty()
+        && modified.is_empty()
+        && untracked_count == 0
+        && conflicted.is_empty()
+        && operation.is_none();
 
     // Get ahead/behind counts
     let (ahead, behind) = get_ahead_behind_git(repo_path).unwrap_or((0, 0));
@@ -107,80 +239,136 @@ pub fn get_status_info(repo: &Repository) -> Result<RepoStatusInfo, GitError> {
         is_clean,
         staged,
         modified,
+        untracked_truncated: untracked.len() < untracked_count,
         untracked,
+        untracked_count,
         ahead,
         behind,
+        operation,
+        conflicted,
     })
 }
 
+/// Whether a porcelain v1 XY pair denotes an unmerged (conflicted) path
+fn is_conflict_status(x: char, y: char) -> bool {
+    matches!((x, y), ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D'))
+}
+
 /// Get cached status or compute it
+///
+/// Large repos may be scanned incrementally, so use this for display only;
+/// dirty checks go through [`get_status_info`].
 pub fn get_cached_status(repo_path: &PathBuf) -> Result<RepoStatusInfo, GitError> {
+    get_cached_status_limited(repo_path, None)
+}
+
+/// Get cached status or compute it, keeping at most `untracked_limit` untracked paths
+///
+/// A cached status that was capped below the requested limit is recomputed.
+pub fn get_cached_status_limited(
+    repo_path: &PathBuf,
+    untracked_limit: Option<usize>,
+) -> Result<RepoStatusInfo, GitError> {
     // Check cache first
-    if let Some(status) = STATUS_CACHE.get(repo_path) {
-        return Ok(status);
+    if let Some(mut status) = STATUS_CACHE.get(repo_path) {
+        let enough = !status.untracked_truncated
+            || untracked_limit.is_some_and(|limit| limit <= status.untracked.len());
+        if enough {
+            if let Some(limit) = untracked_limit {
+                status.truncate_untracked(limit);
+            }
+            return Ok(status);
+        }
     }
 
     // Compute and cache
     let repo = open_repo(repo_path)?;
-    let status = get_status_info(&repo)?;
+    let status = scan_status_info(&repo, untracked_limit, true)?;
     STATUS_CACHE.set(repo_path.clone(), status.clone());
     Ok(status)
 }
 
-/// Get ahead/behind counts using git rev-list
-fn get_ahead_behind_git(repo_path: &std::path::Path) -> Option<(usize, usize)> {
+/// Get ahead/behind counts against the upstream branch
+fn get_ahead_behind_git(repo_path: &Path) -> Option<(usize, usize)> {
+    get_ahead_behind(repo_path, "@{upstream}").or(Some((0, 0)))
+}
+
+/// Get commits ahead/behind an arbitrary base ref (e.g., `origin/main`, `release/1.x`)
+///
+/// Returns `None` if the ref cannot be resolved. Counts are cached by the
+/// pair of commits compared, so unchanged refs skip the history walk.
+pub fn get_ahead_behind(repo_path: &Path, base_ref: &str) -> Option<(usize, usize)> {
+    let (head, base) = resolve_commit_pair(repo_path, base_ref)?;
+    if let Some(counts) = STATUS_CACHE.get_ahead_behind(head, base) {
+        return Some(counts);
+    }
+
     let output = Command::new("git")
-        .args(["rev-list", "--left-right", "--count", "@{upstream}...HEAD"])
+        .args([
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("{}...{}", base, head),
+        ])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .ok()?;
 
     if !output.status.success() {
-        return Some((0, 0));
+        return None;
     }
 
-    parse_ahead_behind(&output.stdout)
+    let counts = parse_ahead_behind(&output.stdout)?;
+    STATUS_CACHE.set_ahead_behind(head, base, counts);
+    Some(counts)
 }
 
-/// Get commits ahead/behind a specific branch (e.g., main)
-fn get_ahead_behind_branch(
-    repo_path: &std::path::Path,
-    base_branch: &str,
-) -> Option<(usize, usize)> {
-    // Try remote first: origin/{base_branch}
-    let remote_ref = format!("origin/{}", base_branch);
-
+/// Resolve HEAD and `base_ref` to commits, as `(head, base)`
+fn resolve_commit_pair(repo_path: &Path, base_ref: &str) -> Option<(Oid, Oid)> {
     let output = Command::new("git")
         .args([
-            "rev-list",
-            "--left-right",
-            "--count",
-            &format!("{}...HEAD", remote_ref),
+            "rev-parse",
+            "HEAD^{commit}",
+            &format!("{}^{{commit}}", base_ref),
+            "--",
         ])
         .current_dir(repo_path)
-        .output()
+        .timed_output()
         .ok()?;
 
     if !output.status.success() {
-        // Fallback to local branch
-        let output = Command::new("git")
-            .args([
-                "rev-list",
-                "--left-right",
-                "--count",
-                &format!("{}...HEAD", base_branch),
-            ])
-            .current_dir(repo_path)
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            return Some((0, 0));
-        }
-        return parse_ahead_behind(&output.stdout);
+        return None;
     }
 
-    parse_ahead_behind(&output.stdout)
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut oids = stdout.lines().map(|line| Oid::from_str(line.trim()).ok());
+    Some((oids.next()??, oids.next()??))
+}
+
+/// Count commits on HEAD (checked out as `branch`) that no remote has yet
+///
+/// Compares against `origin/<branch>` once the branch has been pushed, and
+/// against the default branch before that. The default branch itself only
+/// counts commits ahead of `origin/<default>`.
+pub fn get_unpushed_count(repo_path: &Path, branch: &str, default_branch: &str) -> usize {
+    let ahead = |base: &str| get_ahead_behind(repo_path, base).map(|(ahead, _)| ahead);
+    ahead(&format!("origin/{}", branch))
+        .or_else(|| {
+            if branch == default_branch {
+                None
+            } else {
+                ahead(&format!("origin/{}", default_branch)).or_else(|| ahead(default_branch))
+            }
+        })
+        .unwrap_or(0)
+}
+
+/// Get commits ahead/behind a specific branch (e.g., main)
+fn get_ahead_behind_branch(repo_path: &Path, base_branch: &str) -> Option<(usize, usize)> {
+    // Try remote first: origin/{base_branch}, then fall back to the local branch
+    get_ahead_behind(repo_path, &format!("origin/{}", base_branch))
+        .or_else(|| get_ahead_behind(repo_path, base_branch))
+        .or(Some((0, 0)))
 }
 
 /// Parse ahead/behind counts from git rev-list output
@@ -199,6 +387,19 @@ fn parse_ahead_behind(stdout: &[u8]) -> Option<(usize, usize)> {
 
 /// Get repository status
 pub fn get_repo_status(repo_info: &RepoInfo) -> RepoStatus {
+    get_repo_status_against(repo_info, None)
+}
+
+/// Get repository status, comparing against `base` instead of the default branch
+///
+/// When `base` is `None`, `ahead_main`/`behind_main` are computed against the
+/// repo's default branch (preferring `origin/<default>`).
+pub fn get_repo_status_against(repo_info: &RepoInfo, base: Option<&str>) -> RepoStatus {
+    let _span = profile::span(
+        Phase::Status,
+        || "repo status",
+        Some(&repo_info.absolute_path),
+    );
     if !path_exists(&repo_info.absolute_path) {
         return RepoStatus {
             name: repo_info.name.clone(),
@@ -211,16 +412,25 @@ pub fn get_repo_status(repo_info: &RepoInfo) -> RepoStatus {
             behind: 0,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: false,
         };
     }
 
-    match get_cached_status(&repo_info.absolute_path) {
+    match get_cached_status_limited(&repo_info.absolute_path, Some(UNTRACKED_LIMIT)) {
         Ok(status) => {
-            // Get ahead/behind counts vs default branch
-            let (ahead_main, behind_main) =
-                get_ahead_behind_branch(&repo_info.absolute_path, &repo_info.default_branch)
-                    .unwrap_or((0, 0));
+            // Get ahead/behind counts vs base (default branch unless overridden)
+            let counts = match base {
+                Some(base_ref) => get_ahead_behind(&repo_info.absolute_path, base_ref),
+                None => {
+                    get_ahead_behind_branch(&repo_info.absolute_path, &repo_info.default_branch)
+                        .or(Some((0, 0)))
+                }
+            };
+            let (ahead_main, behind_main) = counts.unwrap_or((0, 0));
 
             RepoStatus {
                 name: repo_info.name.clone(),
@@ -228,11 +438,15 @@ pub fn get_repo_status(repo_info: &RepoInfo) -> RepoStatus {
                 clean: status.is_clean,
                 staged: status.staged.len(),
                 modified: status.modified.len(),
-                untracked: status.untracked.len(),
+                untracked: status.untracked_count,
                 ahead: status.ahead,
                 behind: status.behind,
                 ahead_main,
                 behind_main,
+                base_missing: counts.is_none(),
+                operation: status.operation,
+                conflicted: status.conflicted.len(),
+                lfs_missing: missing_lfs_objects(&repo_info.absolute_path),
                 exists: true,
             }
         }
@@ -247,14 +461,50 @@ pub fn get_repo_status(repo_info: &RepoInfo) -> RepoStatus {
             behind: 0,
             ahead_main: 0,
             behind_main: 0,
+            base_missing: false,
+            operation: None,
+            conflicted: 0,
+            lfs_missing: 0,
             exists: true,
         },
     }
 }
 
 /// Get status for all repositories
+///
+/// Repos are examined concurrently on a bounded worker pool; results are in
+/// the same order as `repos`.
 pub fn get_all_repo_status(repos: &[RepoInfo]) -> Vec<RepoStatus> {
-    repos.iter().map(get_repo_status).collect()
+    get_all_repo_status_against(repos, None)
+}
+
+/// Get status for all repositories, comparing against `base` instead of
+/// each repo's default branch when given
+pub fn get_all_repo_status_against(repos: &[RepoInfo], base: Option<&str>) -> Vec<RepoStatus> {
+    parallel_map(repos, max_workers(), |repo| {
+        get_repo_status_against(repo, base)
+    })
+}
+
+/// Describe why a repository is blocked by an in-progress operation, if it is
+///
+/// Returns a message with guidance on how to finish or abort the operation.
+pub fn blocking_operation(repo: &Repository) -> Option<String> {
+    let operation = GitOperation::detect(repo)?;
+    let conflicts = get_status_info(repo)
+        .map(|s| s.conflicted.len())
+        .unwrap_or(0);
+
+    let mut msg = format!("{} in progress", operation.git_command());
+    if conflicts > 0 {
+        msg.push_str(&format!(" with {} conflicted file(s)", conflicts));
+    }
+    if operation == GitOperation::Bisect {
+        msg.push_str("; finish with 'gr bisect reset'");
+    } else {
+        msg.push_str("; resolve and run 'gr resolve --continue', or 'gr resolve --abort'");
+    }
+    Some(msg)
 }
 
 /// Get list of changed files (staged, modified, and untracked)
@@ -356,6 +606,113 @@ mod tests {
         assert!(status.untracked.contains(&"new_file.txt".to_string()));
     }
 
+    #[test]
+    fn test_untracked_capped() {
+        let (temp, repo) = setup_test_repo();
+        fs::write(temp.path().join("README.md"), "# Test").unwrap();
+        for args in [
+            &["add", "README.md"][..],
+            &["commit", "-m", "Initial commit"],
+        ] {
+            Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        }
+        for i in 0..5 {
+            fs::write(temp.path().join(format!("file{}.txt", i)), "x").unwrap();
+        }
+
+        let status = get_status_info_limited(&repo, Some(2)).unwrap();
+        assert!(!status.is_clean);
+        assert_eq!(status.untracked.len(), 2);
+        assert_eq!(status.untracked_count, 5);
+        assert!(status.untracked_truncated);
+
+        let path = temp.path().to_path_buf();
+        STATUS_CACHE.set(path.clone(), status);
+        let full = get_cached_status(&path).unwrap();
+        assert_eq!(full.untracked.len(), 5);
+        assert!(!full.untracked_truncated);
+        let capped = get_cached_status_limited(&path, Some(3)).unwrap();
+        assert_eq!(capped.untracked.len(), 3);
+        assert_eq!(capped.untracked_count, 5);
+        assert!(capped.untracked_truncated);
+        STATUS_CACHE.invalidate(&path);
+    }
+
+    #[test]
+    fn test_merge_conflict_detected() {
+        let (temp, repo) = setup_test_repo();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap()
+        };
+
+        fs::write(temp.path().join("file.txt"), "base").unwrap();
+        git(&["add", "file.txt"]);
+        git(&["commit", "-m", "Initial commit"]);
+        git(&["checkout", "-b", "other"]);
+        fs::write(temp.path().join("file.txt"), "other").unwrap();
+        git(&["commit", "-am", "Other change"]);
+        git(&["checkout", "-"]);
+        fs::write(temp.path().join("file.txt"), "mine").unwrap();
+        git(&["commit", "-am", "My change"]);
+        git(&["merge", "other"]);
+
+        let status = get_status_info(&repo).unwrap();
+        assert_eq!(status.operation, Some(GitOperation::Merge));
+        assert_eq!(status.conflicted, vec!["file.txt".to_string()]);
+        assert!(!status.is_clean);
+        assert!(blocking_operation(&repo)
+            .unwrap()
+            .contains("gr resolve --abort"));
+    }
+
+    #[test]
+    fn test_conflict_status_codes() {
+        assert!(is_conflict_status('U', 'U'));
+        assert!(is_conflict_status('A', 'A'));
+        assert!(is_conflict_status('D', 'U'));
+        assert!(!is_conflict_status('M', ' '));
+        assert!(!is_conflict_status('A', ' '));
+    }
+
+    #[test]
+    fn test_get_ahead_behind_against_base() {
+        let (temp, _repo) = setup_test_repo();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap()
+        };
+
+        fs::write(temp.path().join("README.md"), "# Test").unwrap();
+        git(&["add", "README.md"]);
+        git(&["commit", "-m", "Initial commit"]);
+        git(&["branch", "base"]);
+        git(&["checkout", "-b", "feature"]);
+        fs::write(temp.path().join("feature.txt"), "feature").unwrap();
+        git(&["add", "feature.txt"]);
+        git(&["commit", "-m", "Feature commit"]);
+
+        assert_eq!(get_ahead_behind(temp.path(), "base"), Some((1, 0)));
+        assert_eq!(get_ahead_behind(temp.path(), "does-not-exist"), None);
+
+        // Not pushed yet: counted against the default branch
+        assert_eq!(get_unpushed_count(temp.path(), "feature", "base"), 1);
+        git(&["update-ref", "refs/remotes/origin/feature", "HEAD"]);
+        assert_eq!(get_unpushed_count(temp.path(), "feature", "base"), 0);
+        git(&["checkout", "-q", "base"]);
+        assert_eq!(get_unpushed_count(temp.path(), "base", "base"), 0);
+    }
+
     #[test]
     fn test_staged_file() {
         let (temp, repo) = setup_test_repo();
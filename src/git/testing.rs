@@ -0,0 +1,28 @@
+//! Fixtures for unit tests that drive the git CLI
+
+use std::path::Path;
+use std::process::Command;
+
+/// Run `git <args>` in `path` and return its trimmed stdout, panicking on failure
+pub fn git(path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Create an empty repo at `path` on `main`, with a test identity configured
+pub fn init_repo(path: &Path) {
+    std::fs::create_dir_all(path).unwrap();
+    git(path, &["init", "-q", "-b", "main"]);
+    git(path, &["config", "user.name", "Test User"]);
+    git(path, &["config", "user.email", "test@example.com"]);
+}
@@ -5,11 +5,15 @@
 
 pub mod cli;
 pub mod core;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod files;
 pub mod git;
 pub mod platform;
 pub mod util;
+pub mod workspace;
 
 pub use core::manifest::Manifest;
 pub use core::repo::RepoInfo;
 pub use core::state::StateFile;
+pub use workspace::Workspace;
@@ -2,6 +2,9 @@
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
+use gitgrip::core::manifest::resolve_flag;
+use gitgrip::util::notify::{notify, Completion};
+use gitgrip::util::redact::{redact, RedactingWriter};
 
 #[derive(Parser)]
 #[command(name = "gr")]
@@ -9,6 +12,41 @@ use clap_complete::{generate, Shell};
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Only act on repos whose changes (vs default branch) match these globs
+    #[arg(long, global = true, value_name = "GLOB")]
+    paths: Vec<String>,
+    /// Seconds to wait for another gr process to release a repo
+    #[arg(
+        long,
+        global = true,
+        env = "GITGRIP_LOCK_TIMEOUT",
+        default_value_t = 30,
+        value_name = "SECS"
+    )]
+    lock_timeout: u64,
+    /// Run against this registered workspace instead of the current directory's
+    #[arg(
+        short = 'w',
+        long,
+        global = true,
+        env = "GITGRIP_WORKSPACE",
+        value_name = "NAME"
+    )]
+    workspace: Option<String>,
+    /// Don't page output through `$PAGER`
+    #[arg(long, global = true)]
+    no_pager: bool,
+    /// Screen-reader friendly output: words instead of symbols and colors,
+    /// no box drawing, and progress on separate lines (default when TERM=dumb)
+    #[arg(long, global = true, env = "GITGRIP_PLAIN")]
+    plain: bool,
+    /// Report time per repo and phase (git, status, network, processes) on
+    /// stderr, and write a trace to .gitgrip/profile.json
+    ///
+    /// This is the performance profiler; `--profile` on init, sync and env
+    /// selects a manifest profile instead.
+    #[arg(long, global = true)]
+    timings: bool,
 }
 
 #[derive(Subcommand)]
@@ -38,21 +76,71 @@ enum Commands {
         /// Make manifest repository private (default: false)
         #[arg(long, requires = "create_manifest")]
         private: bool,
+        /// Only materialize the repos in this manifest profile
+        #[arg(long, conflicts_with = "from_dirs")]
+        profile: Option<String>,
     },
     /// Sync all repositories
     Sync {
         /// Force sync even with local changes
         #[arg(short, long)]
         force: bool,
+        /// Don't pull the manifest repo before syncing
+        #[arg(long)]
+        no_manifest_update: bool,
+        /// Pick up an interrupted sync, skipping repos it already finished
+        #[arg(long = "continue")]
+        resume: bool,
+        /// Clones to run at once (pulls run one at a time) [default: 4]
+        #[arg(long)]
+        clone_jobs: Option<usize>,
+        /// Borrow objects from bare repos cached in this directory when cloning
+        #[arg(long, env = "GITGRIP_REFERENCE_CACHE", value_name = "DIR")]
+        reference_cache: Option<std::path::PathBuf>,
+        /// Switch the workspace to this manifest profile
+        #[arg(long)]
+        profile: Option<String>,
+        /// Stop using a profile and sync every repo
+        #[arg(long, conflicts_with = "profile")]
+        no_profile: bool,
+        /// Afterwards, delete checkouts of repos no longer in the manifest (see `gr prune`)
+        #[arg(long, overrides_with = "no_prune")]
+        prune: bool,
+        /// Don't prune afterwards, even if the manifest's settings.sync.prune is set
+        #[arg(long, overrides_with = "prune")]
+        no_prune: bool,
+        #[command(flatten)]
+        priority: PriorityArgs,
     },
     /// Show status of all repositories
     Status {
         /// Show detailed status
-        #[arg(short, long)]
+        #[arg(short, long, overrides_with = "no_verbose")]
         verbose: bool,
+        /// Don't show detailed status, even if the manifest's settings.status.verbose is set
+        #[arg(long, overrides_with = "verbose")]
+        no_verbose: bool,
+        /// Compare against this ref instead of each repo's default branch (e.g. origin/main)
+        #[arg(long)]
+        base: Option<String>,
+        /// Also report repos in the workspace that the manifest doesn't manage
+        #[arg(short, long)]
+        all: bool,
+        /// Scan every file instead of using incremental status in large repos
+        #[arg(long)]
+        full_status: bool,
+        /// Only show this repo, listing its changed files (repeatable)
+        #[arg(short, long)]
+        repo: Vec<String>,
+        /// List every untracked file instead of the first 100 (with --repo)
+        #[arg(long, requires = "repo")]
+        full: bool,
     },
     /// Create or switch branches across repos
+    #[command(args_conflicts_with_subcommands = true)]
     Branch {
+        #[command(subcommand)]
+        action: Option<BranchCommands>,
         /// Branch name
         name: Option<String>,
         /// Delete branch
@@ -61,11 +149,17 @@ enum Commands {
         /// Include manifest repo
         #[arg(long)]
         include_manifest: bool,
+        /// Allow deleting protected branches
+        #[arg(long)]
+        allow_protected: bool,
     },
     /// Checkout a branch across repos
     Checkout {
         /// Branch name
         name: String,
+        /// Switch even if repos have unpushed commits or uncommitted changes
+        #[arg(short, long)]
+        force: bool,
     },
     /// Stage changes across repos
     Add {
@@ -78,6 +172,15 @@ enum Commands {
         /// Show staged changes
         #[arg(long)]
         staged: bool,
+        /// Color theme: dark, light, or plain [default: settings.diff.theme, else dark]
+        #[arg(long)]
+        theme: Option<gitgrip::core::manifest::DiffTheme>,
+        /// Show old and new side by side
+        #[arg(long, conflicts_with = "unified")]
+        side_by_side: bool,
+        /// Show a unified diff even on wide terminals
+        #[arg(long)]
+        unified: bool,
     },
     /// Commit changes across repos
     Commit {
@@ -87,6 +190,42 @@ enum Commands {
         /// Amend previous commit
         #[arg(long)]
         amend: bool,
+        /// Don't sign the commit even if commit.gpgsign is set
+        #[arg(long)]
+        no_sign: bool,
+        /// Override the commit author ("Name <email>")
+        #[arg(long)]
+        author: Option<String>,
+        /// Allow committing to protected branches
+        #[arg(long)]
+        allow_protected: bool,
+    },
+    /// Amend the last commit with staged changes and force-push it safely
+    Amend {
+        /// Replace the commit message (default: keep it)
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Amend locally without pushing
+        #[arg(long)]
+        no_push: bool,
+        /// Don't sign the commit even if commit.gpgsign is set
+        #[arg(long)]
+        no_sign: bool,
+        /// Allow amending protected branches
+        #[arg(long)]
+        allow_protected: bool,
+    },
+    /// Fetch remotes in every repo without touching working trees
+    Fetch {
+        /// Only print failures (for scheduled runs)
+        #[arg(short, long)]
+        quiet: bool,
+        /// Print a scheduled-fetch unit instead: systemd or launchd
+        #[arg(long, value_name = "SCHEDULER")]
+        cron: Option<String>,
+        /// Minutes between scheduled fetches
+        #[arg(long, default_value = "30", requires = "cron")]
+        interval: u64,
     },
     /// Push changes across repos
     Push {
@@ -96,6 +235,47 @@ enum Commands {
         /// Force push
         #[arg(short, long)]
         force: bool,
+        /// Force push only if the remote branch hasn't moved since the last fetch
+        #[arg(long, conflicts_with = "force")]
+        force_with_lease: bool,
+        /// Show the commits each repo would push without pushing
+        #[arg(long)]
+        dry_run: bool,
+        /// Allow force pushing protected branches
+        #[arg(long)]
+        allow_protected: bool,
+        /// Skip the pre-push secret scan and large file check
+        #[arg(long)]
+        no_verify: bool,
+    },
+    /// Reset the current branch in each repo, previewing the commits dropped
+    Reset {
+        /// Ref to reset to (default: each branch's upstream)
+        target: Option<String>,
+        /// Discard staged and working tree changes too
+        #[arg(long, conflicts_with_all = ["soft", "mixed"])]
+        hard: bool,
+        /// Keep changes staged
+        #[arg(long, conflicts_with = "mixed")]
+        soft: bool,
+        /// Keep changes in the working tree, unstaged (default)
+        #[arg(long)]
+        mixed: bool,
+        /// Only these repos
+        #[arg(short, long)]
+        repo: Vec<String>,
+        /// Per-repo ref, as repo=ref (repeatable)
+        #[arg(short = 't', long = "target", value_name = "REPO=REF")]
+        repo_targets: Vec<String>,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Show the preview without resetting
+        #[arg(long)]
+        dry_run: bool,
+        /// Allow resetting protected branches
+        #[arg(long)]
+        allow_protected: bool,
     },
     /// Pull request operations
     Pr {
@@ -113,14 +293,43 @@ enum Commands {
         #[arg(short, long)]
         command: String,
         /// Run in parallel
-        #[arg(short, long)]
+        #[arg(short, long, overrides_with = "no_parallel")]
         parallel: bool,
+        /// Run one repo at a time, even if the manifest's settings.forall.parallel is set
+        #[arg(long, overrides_with = "parallel")]
+        no_parallel: bool,
         /// Only run in repos with changes
         #[arg(long)]
         changed: bool,
         /// Disable git command interception (use CLI for all commands)
         #[arg(long)]
         no_intercept: bool,
+        /// Kill the command in a repo after this long (e.g. 30s, 5m)
+        #[arg(long, value_parser = gitgrip::util::process::parse_duration)]
+        timeout: Option<std::time::Duration>,
+        #[command(flatten)]
+        priority: PriorityArgs,
+    },
+    /// Run a command only in repos that changed between two snapshots or revisions
+    ForeachChanged {
+        /// Snapshot file (from --save) or revision (e.g. origin/main) to compare from
+        #[arg(long, required_unless_present = "save")]
+        from: Option<String>,
+        /// Snapshot file or revision to compare to (default: each repo's HEAD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Command to run; {changed_files} expands to the repo's changed files
+        #[arg(short, long)]
+        command: Option<String>,
+        /// Save a snapshot of each repo's current commit to this file
+        #[arg(long, conflicts_with_all = ["from", "to", "command"])]
+        save: Option<std::path::PathBuf>,
+        /// Only list changed repos and files
+        #[arg(long)]
+        dry_run: bool,
+        /// Continue if the command fails in a repo
+        #[arg(long)]
+        continue_on_error: bool,
     },
     /// Rebase branches across repos
     Rebase {
@@ -133,6 +342,209 @@ enum Commands {
         #[arg(long, name = "continue")]
         continue_rebase: bool,
     },
+    /// Cherry-pick commits onto the current branch across repos
+    CherryPick {
+        /// Commits or ranges (A..B) to pick
+        commits: Vec<String>,
+        /// Pick all commits on this branch not on the current branch
+        #[arg(long, conflicts_with = "commits")]
+        from: Option<String>,
+        /// Append "(cherry picked from commit ...)" to commit messages
+        #[arg(short = 'x')]
+        record_origin: bool,
+        /// Continue after resolving conflicts
+        #[arg(long = "continue", conflicts_with = "abort")]
+        continue_pick: bool,
+        /// Abort and restore the original branch state
+        #[arg(long)]
+        abort: bool,
+        /// Don't sign commits even if commit.gpgsign is set
+        #[arg(long)]
+        no_sign: bool,
+    },
+    /// Backport a PR group's branch to a release branch with linked PRs
+    Backport {
+        /// Branch of the PR group to backport
+        source: String,
+        /// Release branch to backport onto
+        #[arg(long)]
+        to: String,
+        /// PR title (default: "[Backport <to>] <source>")
+        #[arg(short, long)]
+        title: Option<String>,
+        /// Create PRs as drafts
+        #[arg(long)]
+        draft: bool,
+        /// Don't sign commits even if commit.gpgsign is set
+        #[arg(long)]
+        no_sign: bool,
+    },
+    /// Revert commits in every repo that has them, on a new branch
+    Revert {
+        /// Commit SHAs to revert (reverted newest first)
+        #[arg(required = true)]
+        commits: Vec<String>,
+        /// Parent number to revert merge commits against
+        #[arg(short, long)]
+        mainline: Option<u32>,
+        /// Branch to create (default: revert/<short sha>)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Push the branches and open linked revert PRs
+        #[arg(long)]
+        pr: bool,
+        /// PR title (default: Revert "<subject>")
+        #[arg(short, long, requires = "pr")]
+        title: Option<String>,
+        /// Create PRs as drafts
+        #[arg(long, requires = "pr")]
+        draft: bool,
+    },
+    /// Bisect one repo while pinning the others to matching points in time
+    Bisect {
+        #[command(subcommand)]
+        action: BisectCommands,
+    },
+    /// Batch changes across repos, one PR per changed repo
+    Campaign {
+        #[command(subcommand)]
+        action: CampaignCommands,
+    },
+    /// CI helpers
+    Ci {
+        #[command(subcommand)]
+        action: CiCommands,
+    },
+    /// Deployment environments
+    Deploy {
+        #[command(subcommand)]
+        action: DeployCommands,
+    },
+    /// Continue or abort in-progress merges/rebases across repos
+    Resolve {
+        /// Continue the operation after resolving conflicts
+        #[arg(
+            long = "continue",
+            conflicts_with = "abort",
+            required_unless_present = "abort"
+        )]
+        continue_op: bool,
+        /// Abort the operation
+        #[arg(long)]
+        abort: bool,
+    },
+    /// Run git maintenance (gc, prune, reflog expiry) across repos
+    Gc {
+        /// Use `git gc --aggressive`
+        #[arg(long)]
+        aggressive: bool,
+        /// Prune unreachable objects older than this
+        #[arg(long, default_value = "2.weeks.ago")]
+        prune: String,
+        /// Expire reflog entries older than this
+        #[arg(long, default_value = "90.days.ago")]
+        reflog_expire: String,
+        /// Register repos for background `git maintenance`
+        #[arg(long)]
+        maintenance: bool,
+        #[command(flatten)]
+        priority: PriorityArgs,
+    },
+    /// Export all repo sources as one tarball
+    Archive {
+        /// Output file (.tar.gz, .tgz, or .tar)
+        output: std::path::PathBuf,
+        /// Revision to export from each repo
+        #[arg(long, default_value = "HEAD", conflicts_with = "lock")]
+        rev: String,
+        /// Export each repo at its commit in this `gr foreach-changed --save` snapshot
+        #[arg(long, value_name = "FILE")]
+        lock: Option<std::path::PathBuf>,
+    },
+    /// Search tracked files across repos
+    Grep {
+        /// Pattern (extended regex)
+        pattern: String,
+        /// Limit the search to these paths (after `--`)
+        #[arg(last = true)]
+        pathspecs: Vec<String>,
+        /// Case-insensitive match
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Treat the pattern as a literal string
+        #[arg(short = 'F', long)]
+        fixed_strings: bool,
+        /// Only list files containing a match
+        #[arg(short = 'l', long)]
+        files_with_matches: bool,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Regex search-and-replace across repos, committed on a new branch
+    Sed {
+        /// Regex to search for
+        pattern: String,
+        /// Replacement (`$1` / `${name}` reference capture groups)
+        replacement: String,
+        /// Limit to these paths (after `--`)
+        #[arg(last = true)]
+        pathspecs: Vec<String>,
+        /// Branch to create in each changed repo
+        #[arg(short, long)]
+        branch: String,
+        /// Commit message
+        #[arg(short, long)]
+        message: String,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Show the diff without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rewrite origin and manifest URLs across repos, e.g. when changing hosts
+    SwitchUrl {
+        /// Regex to match in each URL
+        pattern: String,
+        /// Replacement (`$1` / `${name}` reference capture groups)
+        replacement: String,
+        /// Treat the pattern as a literal string
+        #[arg(short = 'F', long)]
+        fixed_strings: bool,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Show the planned changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip checking that the new URLs are reachable
+        #[arg(long)]
+        no_verify: bool,
+    },
+    /// List dependencies and licenses across repos, for compliance reviews
+    Inventory {
+        /// Output CSV instead of JSON
+        #[arg(long)]
+        csv: bool,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Rescan every repo instead of reusing results for unchanged HEADs
+        #[arg(long)]
+        no_cache: bool,
+    },
+    /// Show disk usage per repo
+    Du {
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show CODEOWNERS for a path or the changes on a branch
+    Owners {
+        /// Path inside a repo, or branch name (default: current branch)
+        target: Option<String>,
+    },
     /// Manage file links
     Link {
         /// Show link status
@@ -149,16 +561,197 @@ enum Commands {
         /// List available scripts
         #[arg(long)]
         list: bool,
+        /// Kill a command or step after this long (e.g. 30s, 5m)
+        #[arg(long, value_parser = gitgrip::util::process::parse_duration)]
+        timeout: Option<std::time::Duration>,
+    },
+    /// Show the resolved workspace environment
+    Env {
+        /// Print as exports for this shell (sh, bash, zsh, fish, powershell)
+        #[arg(long, value_name = "SHELL", conflicts_with = "dotenv")]
+        shell: Option<String>,
+        /// Print in dotenv format
+        #[arg(long)]
+        dotenv: bool,
+        /// Include this repo's REPO_* variables and env
+        #[arg(short, long)]
+        repo: Option<String>,
+        /// Layer this profile's env over the workspace's
+        #[arg(long)]
+        profile: Option<String>,
     },
-    /// Show environment variables
-    Env,
     /// Run benchmarks
     Bench(gitgrip::cli::commands::bench::BenchArgs),
+    /// Check the workspace on disk against the manifest
+    Doctor,
+    /// Add an existing checkout inside the workspace to the manifest
+    Adopt {
+        /// Path to the checkout
+        path: std::path::PathBuf,
+        /// Name in the manifest (default: from the remote URL)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+    /// Export the workspace as a DOT or Mermaid graph
+    Graph {
+        /// Output format (dot, mermaid)
+        #[arg(long, default_value = "dot")]
+        format: String,
+    },
+    /// Render a Markdown or HTML report of the workspace
+    Report {
+        /// Output format (markdown, html)
+        #[arg(long, default_value = "markdown")]
+        format: String,
+        /// Write the report to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Recent commits to list per repo
+        #[arg(long, default_value = "5")]
+        commits: usize,
+        /// Skip looking up PRs on the hosting platform
+        #[arg(long)]
+        no_prs: bool,
+    },
+    /// Summarize your recent commits and PRs across repos as Markdown
+    Standup {
+        /// How far back to look: an age (12h, 1d, 1w) or a date (2024-05-01)
+        #[arg(long, default_value = "1d")]
+        since: String,
+        /// Match commits by this author instead of each repo's user.email
+        #[arg(long)]
+        author: Option<String>,
+        /// Skip looking up PRs on the hosting platform
+        #[arg(long)]
+        no_prs: bool,
+    },
+    /// Show commit, line, contributor, and PR cycle-time stats across repos
+    Stats {
+        /// Start of the window: an age (7d, 4w) or a date (2024-05-01)
+        #[arg(long, default_value = "30d")]
+        since: String,
+        /// End of the window (default: now)
+        #[arg(long)]
+        until: Option<String>,
+        /// Output JSON
+        #[arg(long, conflicts_with = "csv")]
+        json: bool,
+        /// Output CSV
+        #[arg(long)]
+        csv: bool,
+        /// Skip PR cycle times, which need the hosting platform
+        #[arg(long)]
+        no_prs: bool,
+    },
+    /// Print a Markdown changelog of the PR groups merged since a tag
+    Changelog {
+        /// Tag (the newest commit it marks in any repo), age (30d), or date (2024-05-01)
+        #[arg(long)]
+        since: Option<String>,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check or bump the versions kept in step across repos (manifest `versions:`)
+    Version {
+        #[command(subcommand)]
+        action: VersionCommands,
+    },
+    /// Serve JSON-RPC over stdio (or a unix socket) for editor integrations
+    Serve {
+        /// Listen on this unix socket instead of stdio
+        #[arg(long)]
+        socket: Option<std::path::PathBuf>,
+    },
+    /// Create a new repo from a template and add it to the workspace
+    New {
+        /// Repository name
+        name: String,
+        /// Template: local directory or git URL
+        #[arg(short, long)]
+        template: String,
+        /// Owner/org for the new repo (default: most common in manifest)
+        #[arg(long)]
+        owner: Option<String>,
+        /// Local path (default: the repo name)
+        #[arg(short, long)]
+        path: Option<String>,
+        /// Default branch
+        #[arg(short, long, default_value = "main")]
+        branch: String,
+        /// Repository description
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Create a public repository (default: private)
+        #[arg(long)]
+        public: bool,
+        /// Don't protect the default branch
+        #[arg(long)]
+        no_protect: bool,
+    },
+    /// Manifest operations
+    Manifest {
+        #[command(subcommand)]
+        action: ManifestCommands,
+    },
+    /// Review the log of pushes, branch deletions, and PR operations
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommands,
+    },
+    /// Tools for developing gitgrip
+    Dev {
+        #[command(subcommand)]
+        action: DevCommands,
+    },
+    /// Remove checkouts of repos that are no longer in the manifest
+    Prune {
+        /// List what would be pruned without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Move checkouts to .gitgrip/pruned/ instead of deleting them
+        #[arg(long)]
+        archive: bool,
+        /// Also prune repos with unpushed commits, uncommitted changes, or stashes
+        #[arg(long)]
+        force: bool,
+    },
+    /// Enable git's commit-graph, untracked cache, and fsmonitor speedups in every repo
+    Optimize {
+        /// Skip timing `git status` and `git log` before and after
+        #[arg(long)]
+        no_measure: bool,
+    },
+    /// Check repos for corrupt or missing objects, broken refs, and missing pinned revisions
+    Verify {
+        /// Only check that objects are reachable, not their contents
+        #[arg(long)]
+        quick: bool,
+    },
+    /// Show or clear per-repo locks
+    Locks {
+        #[command(subcommand)]
+        action: LocksCommands,
+    },
+    /// Undo the most recent recorded operation where possible
+    Undo {
+        /// List what would be undone without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
     /// Repository operations
     Repo {
         #[command(subcommand)]
         action: RepoCommands,
     },
+    /// List, switch between, and run commands across registered workspaces
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceCommands,
+    },
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
@@ -175,11 +768,41 @@ enum PrCommands {
         #[arg(short, long)]
         title: Option<String>,
         /// Push before creating
-        #[arg(long)]
+        #[arg(long, overrides_with = "no_push")]
         push: bool,
+        /// Don't push first, even if the manifest's settings.pr.push is set
+        #[arg(long, overrides_with = "push")]
+        no_push: bool,
         /// Create as draft
-        #[arg(long)]
+        #[arg(long, overrides_with = "no_draft")]
         draft: bool,
+        /// Create ready for review, even if the manifest's settings.pr.draft is set
+        #[arg(long, overrides_with = "draft")]
+        no_draft: bool,
+        /// Request review from CODEOWNERS of the changed files
+        #[arg(long, overrides_with = "no_auto_reviewers")]
+        auto_reviewers: bool,
+        /// Don't request CODEOWNERS reviews, even if the manifest's settings.pr.auto_reviewers is set
+        #[arg(long, overrides_with = "auto_reviewers")]
+        no_auto_reviewers: bool,
+        /// Open a separate branch and PR per top-level directory in repos whose changes span several
+        #[arg(long)]
+        split_by_path: bool,
+        /// Open a separate branch and PR per CODEOWNERS owner set
+        #[arg(long, conflicts_with = "split_by_path")]
+        split_by_owner: bool,
+        /// Skip the pre-push secret scan and large file check
+        #[arg(long)]
+        no_verify: bool,
+        /// Label for `gr changelog`, recorded with the PR group (repeatable)
+        #[arg(short, long = "label")]
+        labels: Vec<String>,
+        /// Don't sign split branch commits even if commit.gpgsign is set
+        #[arg(long)]
+        no_sign: bool,
+        /// Allow pushing split branches that are protected
+        #[arg(long)]
+        allow_protected: bool,
     },
     /// Show PR status
     Status {
@@ -189,24 +812,66 @@ enum PrCommands {
     },
     /// Merge pull requests
     Merge {
-        /// Merge method (merge, squash, rebase)
+        /// Merge method (merge, squash, rebase) [default: settings.pr.merge_method, else merge]
         #[arg(short, long)]
         method: Option<String>,
         /// Force merge without readiness checks
         #[arg(short, long)]
         force: bool,
+        /// Merge with admin rights despite missing approvals or failing checks (asks first)
+        #[arg(long, conflicts_with = "force")]
+        admin: bool,
+        /// Trial-merge each PR into its freshly fetched base, in order, and stop on conflicts
+        #[arg(long)]
+        predict_conflicts: bool,
+        /// Print the merge order (dependencies first, then least behind base) and stop
+        #[arg(long)]
+        plan: bool,
+        /// Merge each PR's base into its branch and push right before merging it
+        #[arg(long)]
+        resync: bool,
+    },
+    /// Check every PR against the manifest's ready_check policy; exits non-zero unless all are ready
+    ReadyCheck {
+        /// Output JSON verdicts
+        #[arg(long)]
+        json: bool,
     },
     /// Check CI status
     Checks {
         /// Output JSON
         #[arg(long)]
         json: bool,
+        /// Print the end of CI job logs: of the named job, or of every failing job
+        #[arg(long, value_name = "CHECK", num_args = 0..=1, default_missing_value = "")]
+        logs: Option<String>,
+        /// Only show failing checks (and only fetch logs of failing jobs)
+        #[arg(long)]
+        failed_only: bool,
+        /// Lines to print from the end of each log
+        #[arg(long, default_value_t = 50, requires = "logs")]
+        tail: usize,
+        /// Re-run failed CI jobs (GitHub Actions runs, GitLab pipelines) first
+        #[arg(long)]
+        rerun_failed: bool,
+        /// Wait until no checks are pending, then report
+        #[arg(long)]
+        watch: bool,
     },
     /// Show PR diff
     Diff {
         /// Show stat summary only
         #[arg(long)]
         stat: bool,
+        /// Color theme: dark, light, or plain [default: settings.diff.theme, else dark]
+        #[arg(long)]
+        theme: Option<gitgrip::core::manifest::DiffTheme>,
+        /// Show old and new side by side
+        #[arg(long, conflicts_with = "unified")]
+        side_by_side: bool,
+        /// Show a unified diff even on wide terminals
+        #[arg(long)]
+        unified: bool,
     },
 }
 
@@ -242,6 +907,187 @@ enum TreeCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum CampaignCommands {
+    /// Run a script in each repo and open PRs for the resulting changes
+    Run {
+        /// Script file or shell command to run in each repo
+        script: String,
+        /// Branch to create in each changed repo
+        #[arg(short, long)]
+        branch: String,
+        /// PR title (also the commit message)
+        #[arg(short, long)]
+        title: String,
+        /// PR body
+        #[arg(long)]
+        body: Option<String>,
+        /// Open PRs as drafts
+        #[arg(long)]
+        draft: bool,
+    },
+    /// Show merge progress of campaigns
+    Status {
+        /// Campaign branch (default: all campaigns)
+        branch: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BranchCommands {
+    /// Create a branch from the freshly fetched default branch in every repo
+    Start {
+        /// Branch name, or with --ticket a description for the name's {slug}
+        #[arg(required_unless_present = "ticket")]
+        name: Option<String>,
+        /// Only start the branch in this repo (repeatable)
+        #[arg(short, long)]
+        repo: Vec<String>,
+        /// Switch even if repos have unpushed commits or uncommitted changes
+        #[arg(short, long)]
+        force: bool,
+        /// Ticket ID (e.g. PROJ-123); names the branch with settings.tickets.branch_format
+        #[arg(short, long)]
+        ticket: Option<String>,
+    },
+    /// Rename a branch in every repo, on the remote, and in linked PRs
+    Rename {
+        /// Current branch name
+        old: String,
+        /// New branch name
+        new: String,
+        /// Allow renaming protected branches
+        #[arg(long)]
+        allow_protected: bool,
+    },
+    /// List remote branches that are merged, abandoned, or by people who left
+    Audit {
+        /// Days without commits before a branch is abandoned [default: settings.branch_audit.stale_days, else 90]
+        #[arg(long)]
+        days: Option<u64>,
+        /// Name or email of someone who has left (repeatable; adds to settings.branch_audit.departed)
+        #[arg(long, value_name = "WHO")]
+        departed: Vec<String>,
+        /// Only report these findings: merged, abandoned, departed (comma-separated)
+        #[arg(long, value_name = "FINDINGS")]
+        only: Vec<String>,
+        /// Use remote-tracking branches as they are instead of fetching first
+        #[arg(long)]
+        no_fetch: bool,
+        /// Delete the listed branches from the remote, after confirmation
+        #[arg(long, conflicts_with = "json")]
+        delete: bool,
+        /// Don't ask for confirmation
+        #[arg(short, long, requires = "delete")]
+        yes: bool,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CiCommands {
+    /// Print a GitHub Actions matrix of repos changed vs their default branch
+    Matrix {
+        /// Include every cloned repo, changed or not
+        #[arg(long)]
+        all: bool,
+        /// Also write `matrix` and `changed` outputs to $GITHUB_OUTPUT
+        #[arg(long)]
+        github_output: bool,
+    },
+    /// Start a workflow (workflow_dispatch) across repos
+    Run {
+        /// Workflow file name or ID, e.g. deploy.yml (GitLab runs the ref's pipeline)
+        workflow: String,
+        /// Only these repos (default: every repo that has the workflow)
+        #[arg(short, long)]
+        repo: Vec<String>,
+        /// Ref to run on (default: each repo's current branch)
+        #[arg(long = "ref", value_name = "REF")]
+        git_ref: Option<String>,
+        /// Workflow input, as key=value (repeatable)
+        #[arg(short, long = "input", value_name = "KEY=VALUE", value_parser = gitgrip::cli::commands::ci::parse_input)]
+        inputs: Vec<(String, String)>,
+        /// Wait for the runs to finish
+        #[arg(long)]
+        watch: bool,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Download artifacts of the latest successful CI run from each repo
+    Artifacts {
+        /// Artifact names (default: all artifacts of the run)
+        names: Vec<String>,
+        /// Only these repos (default: every repo with a matching artifact)
+        #[arg(short, long)]
+        repo: Vec<String>,
+        /// Branch to take the run from (default: each repo's current branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Only runs of this workflow file or ID (GitHub)
+        #[arg(long)]
+        workflow: Option<String>,
+        /// Directory to download into; artifacts land in <DIR>/<repo>/
+        #[arg(short, long, default_value = "artifacts")]
+        output: std::path::PathBuf,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DeployCommands {
+    /// Show what is live in each environment vs local HEAD and a lock snapshot
+    Status {
+        /// Only this environment (repeatable)
+        #[arg(short, long = "env", value_name = "ENV")]
+        environments: Vec<String>,
+        /// Snapshot from `gr foreach-changed --save` to compare against
+        #[arg(long, value_name = "FILE")]
+        lock: Option<std::path::PathBuf>,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BisectCommands {
+    /// Start bisecting a repository
+    Start {
+        /// Repository to bisect
+        #[arg(long)]
+        repo: String,
+        /// Known bad commit
+        #[arg(long)]
+        bad: Option<String>,
+        /// Known good commit(s)
+        #[arg(long, requires = "bad")]
+        good: Vec<String>,
+    },
+    /// Mark the current (or given) commit as good
+    Good {
+        /// Commit to mark
+        rev: Option<String>,
+    },
+    /// Mark the current (or given) commit as bad
+    Bad {
+        /// Commit to mark
+        rev: Option<String>,
+    },
+    /// Skip the current (or given) commit
+    Skip {
+        /// Commit to skip
+        rev: Option<String>,
+    },
+    /// End the bisect and restore all repos
+    Reset,
+}
+
 #[derive(Subcommand)]
 enum RepoCommands {
     /// List repositories
@@ -265,82 +1111,587 @@ enum RepoCommands {
         #[arg(long)]
         delete: bool,
     },
+    /// Show everything known about one repository, for debugging the workspace
+    Info {
+        /// Repository name
+        name: String,
+        /// Recent commits to show
+        #[arg(long, default_value = "5")]
+        commits: usize,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+        /// Don't look up the current branch's PR on the hosting platform
+        #[arg(long)]
+        no_prs: bool,
+    },
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
+#[derive(Subcommand)]
+enum ManifestCommands {
+    /// Check the manifest for problems, reporting each with its line and column
+    Lint {
+        /// Also check that every repo URL is reachable
+        #[arg(long)]
+        network: bool,
+    },
+    /// Summarize repo and script changes between manifest revisions
+    Diff {
+        /// Revision to compare from (default: HEAD)
+        from: Option<String>,
+        /// Revision to compare to (default: the manifest file on disk)
+        to: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// List recorded operations
+    Show {
+        /// Only operations newer than this age (30m, 12h, 7d, 2w) or date (2024-05-01)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only operations in these repos
+        #[arg(short, long)]
+        repo: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DevCommands {
+    /// Run the benchmarks and fail on regressions against a saved baseline
+    BenchCompare {
+        /// Criterion baseline to compare against (or save)
+        #[arg(default_value = "main")]
+        baseline: String,
+        /// Save the baseline instead of comparing
+        #[arg(long)]
+        save: bool,
+        /// Largest allowed slowdown, in percent
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+        /// Only run benchmarks matching this filter
+        #[arg(long)]
+        filter: Option<String>,
+        /// Compare the latest results without running the benchmarks
+        #[arg(long, conflicts_with = "save")]
+        no_run: bool,
+    },
+}
+
+/// CPU and I/O priority for heavy commands
+#[derive(clap::Args)]
+struct PriorityArgs {
+    /// Run at lower CPU priority (niceness 0-19, default 10)
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "10",
+        value_parser = clap::value_parser!(i32).range(0..=19)
+    )]
+    nice: Option<i32>,
+    /// Only use the disk when nothing else needs it
+    #[arg(long)]
+    io_idle: bool,
+}
+
+impl PriorityArgs {
+    /// Lower this process's priority; failures are warnings
+    fn apply(&self) {
+        use gitgrip::cli::output::Output;
+        if let Some(nice) = self.nice {
+            if let Err(e) = gitgrip::util::priority::set_nice(nice) {
+                Output::warning(&format!("Could not lower CPU priority: {}", e));
+            }
+        }
+        if self.io_idle {
+            if let Err(e) = gitgrip::util::priority::set_io_idle() {
+                Output::warning(&format!("Could not lower I/O priority: {}", e));
+            }
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum LocksCommands {
+    /// List held locks
+    List,
+    /// Remove locks left by processes that have exited
+    Clear {
+        /// Only these repos
+        #[arg(short, long)]
+        repo: Vec<String>,
+        /// Also remove locks held by running processes
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VersionCommands {
+    /// Check that every file of each version agrees; exits non-zero if not
+    Check {
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Bump a version in all of its files and commit on a branch per repo
+    Bump {
+        /// Which part to bump: major, minor, or patch
+        level: String,
+        /// Version to bump (needed when the manifest defines several)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Branch to commit on [default: version/<name>-<new version>]
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommands {
+    /// List registered workspaces
+    List,
+    /// Register the current workspace, optionally under a chosen name
+    Add {
+        /// Name to use (default: the directory name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Forget a workspace without touching its files
+    Remove {
+        /// Workspace name
+        name: String,
+    },
+    /// Act on this workspace when gr runs outside any workspace
+    Switch {
+        /// Workspace name (omit to clear)
+        name: Option<String>,
+    },
+    /// Run a command in the root of every registered workspace
+    Run {
+        /// Command to run
+        #[arg(short, long)]
+        command: String,
+        /// Only these workspaces
+        #[arg(long, value_name = "NAME")]
+        only: Vec<String>,
+        /// Keep going after the command fails in a workspace
+        #[arg(long)]
+        continue_on_error: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> std::process::ExitCode {
+    // Initialize tracing; log lines may quote remote URLs, so mask credentials
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(|| RedactingWriter(std::io::stderr()))
         .init();
 
-    let cli = Cli::parse();
+    let result = run(Cli::parse()).await;
+    if let Some(report) = gitgrip::util::profile::finish() {
+        report.print();
+        match report.save() {
+            Ok(path) => eprintln!("Trace written to {}", path.display()),
+            Err(e) => eprintln!("Couldn't write the profile trace: {}", e),
+        }
+    }
+    match result {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", redact(&format!("{:?}", e)));
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    gitgrip::cli::output::Output::set_plain(
+        cli.plain || std::env::var("TERM").is_ok_and(|term| term == "dumb"),
+    );
+    let fallback = !matches!(
+        cli.command,
+        Some(Commands::Init { .. } | Commands::Workspace { .. } | Commands::Completions { .. })
+    );
+    select_workspace(cli.workspace.as_deref(), fallback)?;
+    if cli.timings {
+        let command: Vec<String> = std::iter::once("gr".to_string())
+            .chain(std::env::args().skip(1))
+            .collect();
+        let root = std::env::current_dir()
+            .ok()
+            .and_then(|dir| gitgrip::Workspace::locate(dir).ok())
+            .map(|(root, _)| root);
+        gitgrip::util::profile::enable(&redact(&command.join(" ")), root);
+    }
 
     match cli.command {
-        Some(Commands::Status { verbose }) => {
-            let (workspace_root, manifest) = load_workspace()?;
-            gitgrip::cli::commands::status::run_status(&workspace_root, &manifest, verbose)?;
+        Some(Commands::Status {
+            verbose,
+            no_verbose,
+            base,
+            all,
+            full_status,
+            repo,
+            full,
+        }) => {
+            // Drift detection needs the whole manifest, so --all ignores --paths
+            let (workspace_root, manifest) = if all {
+                load_workspace()?
+            } else {
+                load_scoped_workspace(&cli.paths)?
+            };
+            let verbose = resolve_flag(verbose, no_verbose, manifest.settings.status.verbose);
+            gitgrip::cli::commands::status::run_status(
+                &workspace_root,
+                &manifest,
+                verbose,
+                base.as_deref(),
+                all,
+                full_status,
+                (!repo.is_empty()).then_some(repo.as_slice()),
+                full,
+            )?;
         }
-        Some(Commands::Sync { force }) => {
-            let (workspace_root, manifest) = load_workspace()?;
-            gitgrip::cli::commands::sync::run_sync(&workspace_root, &manifest, force)?;
+        Some(Commands::Sync {
+            force,
+            no_manifest_update,
+            resume,
+            clone_jobs,
+            reference_cache,
+            profile,
+            no_profile,
+            prune,
+            no_prune,
+            priority,
+        }) => {
+            priority.apply();
+            let (root, manifest_path) = gitgrip::Workspace::locate(std::env::current_dir()?)?;
+            gitgrip::core::registry::remember_workspace(&root);
+            // A continued sync keeps the repo list it started with
+            if !no_manifest_update && !resume {
+                // Update before loading, so the sync sees the new manifest
+                gitgrip::cli::commands::sync::update_manifest_repo(&manifest_path);
+            }
+            if profile.is_some() || no_profile {
+                gitgrip::cli::commands::sync::set_profile(
+                    &root,
+                    &manifest_path,
+                    profile.as_deref(),
+                )?;
+            }
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let started = std::time::Instant::now();
+            let options = gitgrip::cli::commands::sync::SyncOptions {
+                force,
+                resume,
+                clone_jobs: clone_jobs
+                    .or(manifest.settings.sync.clone_jobs)
+                    .unwrap_or(gitgrip::cli::commands::sync::DEFAULT_CLONE_JOBS),
+                reference_cache,
+            };
+            let prune = resolve_flag(prune, no_prune, manifest.settings.sync.prune);
+            let result =
+                gitgrip::cli::commands::sync::run_sync(&workspace_root, &manifest, &options);
+            let completion = Completion::from_result(
+                "sync",
+                &workspace_root,
+                started.elapsed(),
+                &result,
+                |summary| (summary.failed == 0, summary.to_string()),
+            );
+            notify(&manifest.settings.notifications, &completion).await;
+            result?;
+            if prune {
+                // The whole manifest, so --paths scoping can't make repos look orphaned
+                let (workspace_root, manifest) = load_workspace()?;
+                println!();
+                gitgrip::cli::commands::prune::run_prune(
+                    &workspace_root,
+                    &manifest,
+                    &Default::default(),
+                )?;
+            }
+        }
+        Some(Commands::Branch {
+            action:
+                Some(BranchCommands::Start {
+                    name,
+                    repo,
+                    force,
+                    ticket,
+                }),
+            ..
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let ticket = ticket.as_deref().map(str::trim);
+            let name = match ticket {
+                Some(ticket) => gitgrip::cli::commands::branch::ticket_branch_name(
+                    &manifest,
+                    ticket,
+                    name.as_deref(),
+                ),
+                None => name.unwrap_or_default(),
+            };
+            gitgrip::cli::commands::branch::run_branch_start(
+                &workspace_root,
+                &manifest,
+                &name,
+                (!repo.is_empty()).then_some(repo.as_slice()),
+                force,
+                ticket,
+            )?;
+        }
+        Some(Commands::Branch {
+            action:
+                Some(BranchCommands::Rename {
+                    old,
+                    new,
+                    allow_protected,
+                }),
+            ..
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::branch::run_branch_rename(
+                &workspace_root,
+                &manifest,
+                &old,
+                &new,
+                allow_protected,
+            )
+            .await?;
+        }
+        Some(Commands::Branch {
+            action:
+                Some(BranchCommands::Audit {
+                    days,
+                    departed,
+                    only,
+                    no_fetch,
+                    delete,
+                    yes,
+                    json,
+                }),
+            ..
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::branch_audit::run_branch_audit(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::branch_audit::BranchAuditOptions {
+                    days,
+                    departed,
+                    only,
+                    no_fetch,
+                    delete,
+                    yes,
+                    json,
+                },
+            )?;
         }
         Some(Commands::Branch {
+            action: None,
             name,
             delete,
             include_manifest: _,
+            allow_protected,
         }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
             gitgrip::cli::commands::branch::run_branch(
                 &workspace_root,
                 &manifest,
                 name.as_deref(),
                 delete,
                 None,
+                allow_protected,
             )?;
         }
-        Some(Commands::Checkout { name }) => {
-            let (workspace_root, manifest) = load_workspace()?;
-            gitgrip::cli::commands::checkout::run_checkout(&workspace_root, &manifest, &name)?;
+        Some(Commands::Checkout { name, force }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::checkout::run_checkout(
+                &workspace_root,
+                &manifest,
+                &name,
+                force,
+            )?;
         }
         Some(Commands::Add { files }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
             gitgrip::cli::commands::add::run_add(&workspace_root, &manifest, &files)?;
         }
-        Some(Commands::Diff { staged }) => {
-            let (workspace_root, manifest) = load_workspace()?;
-            gitgrip::cli::commands::diff::run_diff(&workspace_root, &manifest, staged)?;
+        Some(Commands::Diff {
+            staged,
+            theme,
+            side_by_side,
+            unified,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let display = diff_settings(&manifest, theme, side_by_side, unified);
+            let _pager = gitgrip::cli::pager::start(&manifest.settings.pager, cli.no_pager);
+            gitgrip::cli::commands::diff::run_diff(&workspace_root, &manifest, staged, &display)?;
         }
-        Some(Commands::Commit { message, amend }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+        Some(Commands::Commit {
+            message,
+            amend,
+            no_sign,
+            author,
+            allow_protected,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
             let msg = message.unwrap_or_else(|| {
                 eprintln!("Error: commit message required (-m)");
                 std::process::exit(1);
             });
-            gitgrip::cli::commands::commit::run_commit(&workspace_root, &manifest, &msg, amend)?;
+            gitgrip::cli::commands::commit::run_commit(
+                &workspace_root,
+                &manifest,
+                &msg,
+                amend,
+                !no_sign,
+                author.as_deref(),
+                allow_protected,
+            )?;
+        }
+        Some(Commands::Amend {
+            message,
+            no_push,
+            no_sign,
+            allow_protected,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::amend::run_amend(
+                &workspace_root,
+                &manifest,
+                message.as_deref(),
+                !no_push,
+                !no_sign,
+                allow_protected,
+            )?;
+        }
+        Some(Commands::Fetch {
+            quiet,
+            cron,
+            interval,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            match cron {
+                Some(scheduler) => gitgrip::cli::commands::fetch::run_fetch_cron(
+                    &workspace_root,
+                    &scheduler,
+                    interval,
+                )?,
+                None => {
+                    gitgrip::cli::commands::fetch::run_fetch(&workspace_root, &manifest, quiet)?
+                }
+            }
         }
         Some(Commands::Push {
             set_upstream,
             force,
+            force_with_lease,
+            dry_run,
+            allow_protected,
+            no_verify,
         }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
             gitgrip::cli::commands::push::run_push(
                 &workspace_root,
                 &manifest,
                 set_upstream,
                 force,
+                force_with_lease,
+                dry_run,
+                allow_protected,
+                !no_verify,
+            )?;
+        }
+        Some(Commands::Reset {
+            target,
+            hard,
+            soft,
+            mixed: _,
+            repo,
+            repo_targets,
+            yes,
+            dry_run,
+            allow_protected,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let mode = if hard {
+                gitgrip::git::remote::ResetMode::Hard
+            } else if soft {
+                gitgrip::git::remote::ResetMode::Soft
+            } else {
+                gitgrip::git::remote::ResetMode::Mixed
+            };
+            let options = gitgrip::cli::commands::reset::ResetOptions {
+                mode,
+                target,
+                repo_targets,
+                yes,
+                dry_run,
+                allow_protected,
+            };
+            gitgrip::cli::commands::reset::run_reset(
+                &workspace_root,
+                &manifest,
+                (!repo.is_empty()).then_some(repo.as_slice()),
+                &options,
             )?;
         }
         Some(Commands::Pr { action }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
             match action {
-                PrCommands::Create { title, push, draft } => {
+                PrCommands::Create {
+                    title,
+                    push,
+                    no_push,
+                    draft,
+                    no_draft,
+                    auto_reviewers,
+                    no_auto_reviewers,
+                    split_by_path,
+                    split_by_owner,
+                    no_verify,
+                    labels,
+                    no_sign,
+                    allow_protected,
+                } => {
+                    use gitgrip::cli::commands::pr::SplitBy;
+                    let defaults = &manifest.settings.pr;
+                    let split = match (split_by_path, split_by_owner) {
+                        (true, _) => Some(SplitBy::Path),
+                        (_, true) => Some(SplitBy::Owner),
+                        _ => None,
+                    };
                     gitgrip::cli::commands::pr::run_pr_create(
                         &workspace_root,
                         &manifest,
                         title.as_deref(),
-                        draft,
-                        push,
+                        resolve_flag(draft, no_draft, defaults.draft),
+                        resolve_flag(push, no_push, defaults.push),
+                        resolve_flag(auto_reviewers, no_auto_reviewers, defaults.auto_reviewers),
+                        split,
+                        !no_verify,
+                        &labels,
+                        !no_sign,
+                        allow_protected,
                     )
                     .await?;
                 }
@@ -348,23 +1699,84 @@ async fn main() -> anyhow::Result<()> {
                     gitgrip::cli::commands::pr::run_pr_status(&workspace_root, &manifest, json)
                         .await?;
                 }
-                PrCommands::Merge { method, force } => {
+                PrCommands::Merge {
+                    method,
+                    force,
+                    admin,
+                    predict_conflicts,
+                    plan,
+                    resync,
+                } => {
+                    // Updating branches touches working trees
+                    let _locks = if resync && !plan {
+                        Some(lock_repos(&workspace_root, &manifest, cli.lock_timeout)?)
+                    } else {
+                        None
+                    };
+                    let method = method.or_else(|| {
+                        manifest
+                            .settings
+                            .pr
+                            .merge_method
+                            .map(|method| method.to_string())
+                    });
                     gitgrip::cli::commands::pr::run_pr_merge(
                         &workspace_root,
                         &manifest,
-                        method.as_deref(),
-                        force,
+                        &gitgrip::cli::commands::pr::MergeOptions {
+                            method,
+                            force,
+                            admin,
+                            predict_conflicts,
+                            plan,
+                            resync,
+                        },
                     )
                     .await?;
                 }
-                PrCommands::Checks { json } => {
-                    gitgrip::cli::commands::pr::run_pr_checks(&workspace_root, &manifest, json)
-                        .await?;
+                PrCommands::ReadyCheck { json } => {
+                    gitgrip::cli::commands::pr::run_pr_ready_check(
+                        &workspace_root,
+                        &manifest,
+                        json,
+                    )
+                    .await?;
                 }
-                PrCommands::Diff { stat } => {
-                    gitgrip::cli::commands::pr::run_pr_diff(&workspace_root, &manifest, stat)
+                PrCommands::Checks {
+                    json,
+                    logs,
+                    failed_only,
+                    tail,
+                    rerun_failed,
+                    watch,
+                } => {
+                    let options = gitgrip::cli::commands::pr::ChecksOptions {
+                        json,
+                        logs,
+                        failed_only,
+                        tail,
+                        rerun_failed,
+                        watch,
+                    };
+                    gitgrip::cli::commands::pr::run_pr_checks(&workspace_root, &manifest, &options)
                         .await?;
                 }
+                PrCommands::Diff {
+                    stat,
+                    theme,
+                    side_by_side,
+                    unified,
+                } => {
+                    let display = diff_settings(&manifest, theme, side_by_side, unified);
+                    let _pager = gitgrip::cli::pager::start(&manifest.settings.pager, cli.no_pager);
+                    gitgrip::cli::commands::pr::run_pr_diff(
+                        &workspace_root,
+                        &manifest,
+                        stat,
+                        &display,
+                    )
+                    .await?;
+                }
             }
         }
         Some(Commands::Init {
@@ -376,6 +1788,7 @@ async fn main() -> anyhow::Result<()> {
             create_manifest,
             manifest_name,
             private,
+            profile,
         }) => {
             gitgrip::cli::commands::init::run_init(
                 url.as_deref(),
@@ -386,6 +1799,7 @@ async fn main() -> anyhow::Result<()> {
                 create_manifest,
                 manifest_name.as_deref(),
                 private,
+                profile.as_deref(),
             )
             .await?;
         }
@@ -420,10 +1834,27 @@ async fn main() -> anyhow::Result<()> {
         Some(Commands::Forall {
             command,
             parallel,
+            no_parallel,
             changed,
             no_intercept,
+            timeout,
+            priority,
         }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+            priority.apply();
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            // Started first so the locks are released while the output is still being read
+            let _pager = gitgrip::cli::pager::start(&manifest.settings.pager, cli.no_pager);
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::util::process::cancel_on_ctrl_c();
+            let parallel = resolve_flag(parallel, no_parallel, manifest.settings.forall.parallel);
+            let timeout = match (timeout, &manifest.settings.forall.timeout) {
+                (Some(timeout), _) => Some(timeout),
+                (None, Some(default)) => Some(
+                    gitgrip::util::process::parse_duration(default)
+                        .map_err(|e| anyhow::anyhow!("settings.forall.timeout: {}", e))?,
+                ),
+                (None, None) => None,
+            };
             gitgrip::cli::commands::forall::run_forall(
                 &workspace_root,
                 &manifest,
@@ -431,6 +1862,31 @@ async fn main() -> anyhow::Result<()> {
                 parallel,
                 changed,
                 no_intercept,
+                timeout,
+            )?;
+        }
+        Some(Commands::ForeachChanged {
+            from,
+            to,
+            command,
+            save,
+            dry_run,
+            continue_on_error,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::util::process::cancel_on_ctrl_c();
+            gitgrip::cli::commands::foreach_changed::run_foreach_changed(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::foreach_changed::ForeachChangedOptions {
+                    from,
+                    to,
+                    command,
+                    save,
+                    dry_run,
+                    continue_on_error,
+                },
             )?;
         }
         Some(Commands::Rebase {
@@ -438,7 +1894,8 @@ async fn main() -> anyhow::Result<()> {
             abort,
             continue_rebase,
         }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
             gitgrip::cli::commands::rebase::run_rebase(
                 &workspace_root,
                 &manifest,
@@ -447,23 +1904,654 @@ async fn main() -> anyhow::Result<()> {
                 continue_rebase,
             )?;
         }
-        Some(Commands::Link { status, apply }) => {
+        Some(Commands::CherryPick {
+            commits,
+            from,
+            record_origin,
+            continue_pick,
+            abort,
+            no_sign,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let resume = if abort {
+                Some(gitgrip::cli::commands::resolve::ResolveAction::Abort)
+            } else if continue_pick {
+                Some(gitgrip::cli::commands::resolve::ResolveAction::Continue)
+            } else {
+                None
+            };
+            gitgrip::cli::commands::cherry_pick::run_cherry_pick(
+                &workspace_root,
+                &manifest,
+                &commits,
+                from.as_deref(),
+                record_origin,
+                !no_sign,
+                resume,
+            )?;
+        }
+        Some(Commands::Backport {
+            source,
+            to,
+            title,
+            draft,
+            no_sign,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::backport::run_backport(
+                &workspace_root,
+                &manifest,
+                &source,
+                &to,
+                title.as_deref(),
+                draft,
+                !no_sign,
+            )
+            .await?;
+        }
+        Some(Commands::Revert {
+            commits,
+            mainline,
+            branch,
+            pr,
+            title,
+            draft,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let options = gitgrip::cli::commands::revert::RevertOptions {
+                mainline,
+                branch,
+                pr,
+                title,
+                draft,
+            };
+            gitgrip::cli::commands::revert::run_revert(
+                &workspace_root,
+                &manifest,
+                &commits,
+                &options,
+            )
+            .await?;
+        }
+        Some(Commands::Ci { action }) => {
+            // No matching repos is an empty matrix, not an error
+            let (workspace_root, mut manifest) = load_workspace()?;
+            if !cli.paths.is_empty() {
+                gitgrip::core::scope::scope_to_paths(&workspace_root, &mut manifest, &cli.paths)?;
+            }
+            match action {
+                CiCommands::Matrix { all, github_output } => {
+                    gitgrip::cli::commands::ci::run_ci_matrix(
+                        &workspace_root,
+                        &manifest,
+                        all,
+                        github_output,
+                    )?;
+                }
+                CiCommands::Run {
+                    workflow,
+                    repo,
+                    git_ref,
+                    inputs,
+                    watch,
+                    json,
+                } => {
+                    let options = gitgrip::cli::commands::ci::CiRunOptions {
+                        workflow,
+                        repos: repo,
+                        git_ref,
+                        inputs,
+                        watch,
+                        json,
+                    };
+                    gitgrip::cli::commands::ci::run_ci_run(&workspace_root, &manifest, &options)
+                        .await?;
+                }
+                CiCommands::Artifacts {
+                    names,
+                    repo,
+                    branch,
+                    workflow,
+                    output,
+                    json,
+                } => {
+                    let options = gitgrip::cli::commands::ci::CiArtifactsOptions {
+                        names,
+                        repos: repo,
+                        branch,
+                        workflow,
+                        output,
+                        json,
+                    };
+                    gitgrip::cli::commands::ci::run_ci_artifacts(
+                        &workspace_root,
+                        &manifest,
+                        &options,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Some(Commands::Deploy { action }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            match action {
+                DeployCommands::Status {
+                    environments,
+                    lock,
+                    json,
+                } => {
+                    let options = gitgrip::cli::commands::deploy::DeployStatusOptions {
+                        environments,
+                        lock,
+                        json,
+                    };
+                    gitgrip::cli::commands::deploy::run_deploy_status(
+                        &workspace_root,
+                        &manifest,
+                        &options,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Some(Commands::Campaign { action }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            match action {
+                CampaignCommands::Run {
+                    script,
+                    branch,
+                    title,
+                    body,
+                    draft,
+                } => {
+                    let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+                    let options = gitgrip::cli::commands::campaign::CampaignOptions {
+                        branch,
+                        title,
+                        body,
+                        draft,
+                    };
+                    let started = std::time::Instant::now();
+                    let result = gitgrip::cli::commands::campaign::run_campaign_run(
+                        &workspace_root,
+                        &manifest,
+                        &script,
+                        &options,
+                    )
+                    .await;
+                    let completion = Completion::from_result(
+                        "campaign run",
+                        &workspace_root,
+                        started.elapsed(),
+                        &result,
+                        |summary| (summary.failed == 0, summary.to_string()),
+                    );
+                    notify(&manifest.settings.notifications, &completion).await;
+                    result?;
+                }
+                CampaignCommands::Status { branch } => {
+                    gitgrip::cli::commands::campaign::run_campaign_status(
+                        &workspace_root,
+                        &manifest,
+                        branch.as_deref(),
+                    )
+                    .await?;
+                }
+            }
+        }
+        Some(Commands::Bisect { action }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            match action {
+                BisectCommands::Start { repo, bad, good } => {
+                    gitgrip::cli::commands::bisect::run_bisect_start(
+                        &workspace_root,
+                        &manifest,
+                        &repo,
+                        bad.as_deref(),
+                        &good,
+                    )?;
+                }
+                BisectCommands::Good { rev } => {
+                    gitgrip::cli::commands::bisect::run_bisect_mark(
+                        &workspace_root,
+                        &manifest,
+                        "good",
+                        rev.as_deref(),
+                    )?;
+                }
+                BisectCommands::Bad { rev } => {
+                    gitgrip::cli::commands::bisect::run_bisect_mark(
+                        &workspace_root,
+                        &manifest,
+                        "bad",
+                        rev.as_deref(),
+                    )?;
+                }
+                BisectCommands::Skip { rev } => {
+                    gitgrip::cli::commands::bisect::run_bisect_mark(
+                        &workspace_root,
+                        &manifest,
+                        "skip",
+                        rev.as_deref(),
+                    )?;
+                }
+                BisectCommands::Reset => {
+                    gitgrip::cli::commands::bisect::run_bisect_reset(&workspace_root, &manifest)?;
+                }
+            }
+        }
+        Some(Commands::Resolve { continue_op, abort }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let action = if abort && !continue_op {
+                gitgrip::cli::commands::resolve::ResolveAction::Abort
+            } else {
+                gitgrip::cli::commands::resolve::ResolveAction::Continue
+            };
+            gitgrip::cli::commands::resolve::run_resolve(&workspace_root, &manifest, action)?;
+        }
+        Some(Commands::Gc {
+            aggressive,
+            prune,
+            reflog_expire,
+            maintenance,
+            priority,
+        }) => {
+            priority.apply();
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let options = gitgrip::cli::commands::gc::GcOptions {
+                aggressive,
+                prune,
+                reflog_expire,
+                maintenance,
+            };
+            gitgrip::cli::commands::gc::run_gc(&workspace_root, &manifest, &options)?;
+        }
+        Some(Commands::Archive { output, rev, lock }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::archive::run_archive(
+                &workspace_root,
+                &manifest,
+                &output,
+                &rev,
+                lock.as_deref(),
+            )?;
+        }
+        Some(Commands::Grep {
+            pattern,
+            pathspecs,
+            ignore_case,
+            fixed_strings,
+            files_with_matches,
+            json,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let options = gitgrip::cli::commands::grep::GrepOptions {
+                ignore_case,
+                fixed_strings,
+                files_with_matches,
+                json,
+            };
+            gitgrip::cli::commands::grep::run_grep(
+                &workspace_root,
+                &manifest,
+                &pattern,
+                &pathspecs,
+                &options,
+            )?;
+        }
+        Some(Commands::Sed {
+            pattern,
+            replacement,
+            pathspecs,
+            branch,
+            message,
+            yes,
+            dry_run,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let options = gitgrip::cli::commands::sed::SedOptions {
+                branch,
+                message,
+                yes,
+                dry_run,
+            };
+            gitgrip::cli::commands::sed::run_sed(
+                &workspace_root,
+                &manifest,
+                &pattern,
+                &replacement,
+                &pathspecs,
+                &options,
+            )?;
+        }
+        Some(Commands::SwitchUrl {
+            pattern,
+            replacement,
+            fixed_strings,
+            yes,
+            dry_run,
+            no_verify,
+        }) => {
             let (workspace_root, manifest) = load_workspace()?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            let options = gitgrip::cli::commands::switch_url::SwitchUrlOptions {
+                fixed_strings,
+                yes,
+                dry_run,
+                verify: !no_verify,
+            };
+            gitgrip::cli::commands::switch_url::run_switch_url(
+                &workspace_root,
+                &manifest,
+                &pattern,
+                &replacement,
+                &options,
+            )?;
+        }
+        Some(Commands::Inventory {
+            csv,
+            output,
+            no_cache,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let options = gitgrip::cli::commands::inventory::InventoryOptions {
+                csv,
+                output,
+                no_cache,
+            };
+            gitgrip::cli::commands::inventory::run_inventory(&workspace_root, &manifest, &options)?;
+        }
+        Some(Commands::Du { json }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::du::run_du(&workspace_root, &manifest, json)?;
+        }
+        Some(Commands::Link { status, apply }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
             gitgrip::cli::commands::link::run_link(&workspace_root, &manifest, status, apply)?;
         }
-        Some(Commands::Run { name, list }) => {
-            let (workspace_root, manifest) = load_workspace()?;
+        Some(Commands::Owners { target }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::owners::run_owners(
+                &workspace_root,
+                &manifest,
+                target.as_deref(),
+            )?;
+        }
+        Some(Commands::Run {
+            name,
+            list,
+            timeout,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::util::process::cancel_on_ctrl_c();
             gitgrip::cli::commands::run::run_run(
                 &workspace_root,
                 &manifest,
                 name.as_deref(),
                 list,
+                timeout,
+            )?;
+        }
+        Some(Commands::Env {
+            shell,
+            dotenv,
+            repo,
+            profile,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::env::run_env(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::env::EnvOptions {
+                    repo,
+                    profile,
+                    shell,
+                    dotenv,
+                },
+            )?;
+        }
+        Some(Commands::Doctor) => {
+            let (workspace_root, manifest) = load_workspace()?;
+            gitgrip::cli::commands::doctor::run_doctor(&workspace_root, &manifest)?;
+        }
+        Some(Commands::Adopt { path, name }) => {
+            let (workspace_root, manifest) = load_workspace()?;
+            gitgrip::cli::commands::adopt::run_adopt(
+                &workspace_root,
+                &manifest,
+                &path,
+                name.as_deref(),
             )?;
         }
-        Some(Commands::Env) => {
+        Some(Commands::Graph { format }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::graph::run_graph(&workspace_root, &manifest, &format)?;
+        }
+        Some(Commands::Report {
+            format,
+            output,
+            commits,
+            no_prs,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::report::run_report(
+                &workspace_root,
+                &manifest,
+                &format,
+                output.as_deref(),
+                commits,
+                no_prs,
+            )
+            .await?;
+        }
+        Some(Commands::Standup {
+            since,
+            author,
+            no_prs,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::standup::run_standup(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::standup::StandupOptions {
+                    since,
+                    author,
+                    no_prs,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Stats {
+            since,
+            until,
+            json,
+            csv,
+            no_prs,
+        }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::stats::run_stats(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::stats::StatsOptions {
+                    since,
+                    until,
+                    json,
+                    csv,
+                    no_prs,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Changelog { since, json }) => {
             let (workspace_root, manifest) = load_workspace()?;
-            gitgrip::cli::commands::env::run_env(&workspace_root, &manifest)?;
+            gitgrip::cli::commands::changelog::run_changelog(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::changelog::ChangelogOptions { since, json },
+            )?;
+        }
+        Some(Commands::Version { action }) => {
+            let (workspace_root, manifest) = load_workspace()?;
+            match action {
+                VersionCommands::Check { json } => {
+                    gitgrip::cli::commands::version::run_version_check(
+                        &workspace_root,
+                        &manifest,
+                        json,
+                    )?
+                }
+                VersionCommands::Bump {
+                    level,
+                    name,
+                    branch,
+                    dry_run,
+                } => {
+                    let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+                    gitgrip::cli::commands::version::run_version_bump(
+                        &workspace_root,
+                        &manifest,
+                        &gitgrip::cli::commands::version::VersionBumpOptions {
+                            level,
+                            name,
+                            branch,
+                            dry_run,
+                        },
+                    )?
+                }
+            }
+        }
+        Some(Commands::Serve { socket }) => {
+            let (workspace_root, _) = load_workspace()?;
+            gitgrip::cli::commands::serve::run_serve(&workspace_root, socket.as_deref()).await?;
         }
+        Some(Commands::New {
+            name,
+            template,
+            owner,
+            path,
+            branch,
+            description,
+            public,
+            no_protect,
+        }) => {
+            let (workspace_root, manifest) = load_workspace()?;
+            let options = gitgrip::cli::commands::new::NewOptions {
+                template,
+                owner,
+                path,
+                branch,
+                description,
+                public,
+                no_protect,
+            };
+            gitgrip::cli::commands::new::run_new(&workspace_root, &manifest, &name, &options)
+                .await?;
+        }
+        Some(Commands::Audit { action }) => match action {
+            AuditCommands::Show { since, repo } => {
+                let (workspace_root, _) = gitgrip::Workspace::locate(std::env::current_dir()?)?;
+                gitgrip::cli::commands::audit::run_audit_show(
+                    &workspace_root,
+                    since.as_deref(),
+                    &repo,
+                )?;
+            }
+        },
+        Some(Commands::Dev { action }) => match action {
+            DevCommands::BenchCompare {
+                baseline,
+                save,
+                threshold,
+                filter,
+                no_run,
+            } => {
+                let options = gitgrip::cli::commands::dev::BenchCompareOptions {
+                    baseline,
+                    save,
+                    threshold,
+                    filter,
+                    no_run,
+                };
+                gitgrip::cli::commands::dev::run_bench_compare(&options)?;
+            }
+        },
+        Some(Commands::Prune {
+            dry_run,
+            archive,
+            force,
+        }) => {
+            let (workspace_root, manifest) = load_workspace()?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::prune::run_prune(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::prune::PruneOptions {
+                    dry_run,
+                    archive,
+                    force,
+                },
+            )?;
+        }
+        Some(Commands::Optimize { no_measure }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::optimize::run_optimize(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::optimize::OptimizeOptions { no_measure },
+            )?;
+        }
+        Some(Commands::Verify { quick }) => {
+            let (workspace_root, manifest) = load_scoped_workspace(&cli.paths)?;
+            gitgrip::cli::commands::verify::run_verify(
+                &workspace_root,
+                &manifest,
+                &gitgrip::cli::commands::verify::VerifyOptions { quick },
+            )?;
+        }
+        Some(Commands::Locks { action }) => {
+            let (workspace_root, _) = gitgrip::Workspace::locate(std::env::current_dir()?)?;
+            match action {
+                LocksCommands::List => {
+                    gitgrip::cli::commands::locks::run_locks_list(&workspace_root)?
+                }
+                LocksCommands::Clear { repo, force } => {
+                    gitgrip::cli::commands::locks::run_locks_clear(&workspace_root, &repo, force)?
+                }
+            }
+        }
+        Some(Commands::Undo { dry_run, yes }) => {
+            let (workspace_root, manifest) = load_workspace()?;
+            let _locks = lock_repos(&workspace_root, &manifest, cli.lock_timeout)?;
+            gitgrip::cli::commands::undo::run_undo(&workspace_root, &manifest, dry_run, yes)?;
+        }
+        Some(Commands::Manifest { action }) => match action {
+            ManifestCommands::Lint { network } => {
+                // Locate without loading, so a manifest that fails to load can be linted
+                let (workspace_root, manifest_path) =
+                    gitgrip::Workspace::locate(std::env::current_dir()?)?;
+                gitgrip::cli::commands::manifest::run_manifest_lint(
+                    &workspace_root,
+                    &manifest_path,
+                    network,
+                )?;
+            }
+            ManifestCommands::Diff { from, to } => {
+                let (_, manifest_path) = gitgrip::Workspace::locate(std::env::current_dir()?)?;
+                gitgrip::cli::commands::manifest::run_manifest_diff(
+                    &manifest_path,
+                    from.as_deref(),
+                    to.as_deref(),
+                )?;
+            }
+        },
         Some(Commands::Repo { action }) => {
             let (workspace_root, manifest) = load_workspace()?;
             match action {
@@ -481,11 +2569,54 @@ async fn main() -> anyhow::Result<()> {
                 RepoCommands::Remove { name, delete } => {
                     gitgrip::cli::commands::repo::run_repo_remove(&workspace_root, &name, delete)?;
                 }
+                RepoCommands::Info {
+                    name,
+                    commits,
+                    json,
+                    no_prs,
+                } => {
+                    gitgrip::cli::commands::repo::run_repo_info(
+                        &workspace_root,
+                        &manifest,
+                        &name,
+                        &gitgrip::cli::commands::repo::RepoInfoOptions {
+                            commits,
+                            json,
+                            no_prs,
+                        },
+                    )
+                    .await?;
+                }
             }
         }
         Some(Commands::Bench(args)) => {
             gitgrip::cli::commands::bench::run(args).await?;
         }
+        Some(Commands::Workspace { action }) => match action {
+            WorkspaceCommands::List => gitgrip::cli::commands::workspace::run_workspace_list()?,
+            WorkspaceCommands::Add { name } => {
+                let (workspace_root, _) = gitgrip::Workspace::locate(std::env::current_dir()?)?;
+                gitgrip::cli::commands::workspace::run_workspace_add(
+                    &workspace_root,
+                    name.as_deref(),
+                )?;
+            }
+            WorkspaceCommands::Remove { name } => {
+                gitgrip::cli::commands::workspace::run_workspace_remove(&name)?
+            }
+            WorkspaceCommands::Switch { name } => {
+                gitgrip::cli::commands::workspace::run_workspace_switch(name.as_deref())?
+            }
+            WorkspaceCommands::Run {
+                command,
+                only,
+                continue_on_error,
+            } => gitgrip::cli::commands::workspace::run_workspace_run(
+                &command,
+                &only,
+                continue_on_error,
+            )?,
+        },
         Some(Commands::Completions { shell }) => {
             let mut cmd = Cli::command();
             generate(shell, &mut cmd, "gr", &mut std::io::stdout());
@@ -499,53 +2630,90 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Load the workspace manifest
-fn load_workspace() -> anyhow::Result<(std::path::PathBuf, gitgrip::core::manifest::Manifest)> {
-    let current = std::env::current_dir()?;
-
-    // First, check if we're in a griptree (has .griptree pointer file)
-    if let Some((griptree_path, pointer)) =
-        gitgrip::core::griptree::GriptreePointer::find_in_ancestors(&current)
-    {
-        // We're in a griptree - read manifest from main workspace but use griptree as workspace root
-        let main_workspace = std::path::PathBuf::from(&pointer.main_workspace);
-        let manifest_path = main_workspace
-            .join(".gitgrip")
-            .join("manifests")
-            .join("manifest.yaml");
-
-        if manifest_path.exists() {
-            let content = std::fs::read_to_string(&manifest_path)?;
-            let manifest = gitgrip::core::manifest::Manifest::parse(&content)?;
-            // Return griptree path as workspace root - repos are located here, not in main workspace
-            return Ok((griptree_path, manifest));
-        } else {
-            anyhow::bail!(
-                "Griptree points to main workspace '{}' but manifest not found at '{}'",
-                pointer.main_workspace,
-                manifest_path.display()
-            );
+/// Load the workspace, narrowed to repos touched by `--paths` globs if given
+fn load_scoped_workspace(
+    paths: &[String],
+) -> anyhow::Result<(std::path::PathBuf, gitgrip::core::manifest::Manifest)> {
+    let (workspace_root, mut manifest) = load_workspace()?;
+    if !paths.is_empty() {
+        let kept = gitgrip::core::scope::scope_to_paths(&workspace_root, &mut manifest, paths)?;
+        if kept.is_empty() {
+            anyhow::bail!("No repos have changes matching {}", paths.join(", "));
         }
     }
+    Ok((workspace_root, manifest))
+}
 
-    // Not in a griptree - find workspace root by looking for .gitgrip directory
-    let mut search_path = current;
-    loop {
-        let gitgrip_dir = search_path.join(".gitgrip");
-        if gitgrip_dir.exists() {
-            let manifest_path = gitgrip_dir.join("manifests").join("manifest.yaml");
-            if manifest_path.exists() {
-                let content = std::fs::read_to_string(&manifest_path)?;
-                let manifest = gitgrip::core::manifest::Manifest::parse(&content)?;
-                return Ok((search_path, manifest));
-            }
-        }
+/// Move into the workspace named by `-w`, or, when `fallback` is set and the
+/// current directory isn't in a workspace, the one `gr workspace switch` chose
+fn select_workspace(name: Option<&str>, fallback: bool) -> anyhow::Result<()> {
+    use gitgrip::core::registry::WorkspaceRegistry;
 
-        match search_path.parent() {
-            Some(parent) => search_path = parent.to_path_buf(),
-            None => {
-                anyhow::bail!("Not in a gitgrip workspace (no .gitgrip directory found)");
+    let root = match name {
+        Some(name) => WorkspaceRegistry::load(WorkspaceRegistry::default_path()?)?
+            .resolve(name)?
+            .to_path_buf(),
+        None if fallback => {
+            if gitgrip::Workspace::locate(std::env::current_dir()?).is_ok() {
+                return Ok(());
+            }
+            let Ok(path) = WorkspaceRegistry::default_path() else {
+                return Ok(());
+            };
+            let registry = WorkspaceRegistry::load(path)?;
+            match registry.current {
+                Some(ref current) => registry.resolve(current)?.to_path_buf(),
+                None => return Ok(()),
             }
         }
+        None => return Ok(()),
+    };
+    std::env::set_current_dir(&root)
+        .map_err(|e| anyhow::anyhow!("Cannot enter workspace {}: {}", root.display(), e))
+}
+
+/// Take the locks for every repo in `manifest`, held until the guard drops
+fn lock_repos(
+    workspace_root: &std::path::Path,
+    manifest: &gitgrip::core::manifest::Manifest,
+    timeout: u64,
+) -> anyhow::Result<Vec<gitgrip::core::lock::RepoLock>> {
+    let locks = gitgrip::core::lock::acquire_all(
+        workspace_root,
+        manifest.repos.keys().map(String::as_str),
+        std::time::Duration::from_secs(timeout),
+        |holder| {
+            gitgrip::cli::output::Output::info(&format!(
+                "Waiting for {} to be released by `{}` (pid {})...",
+                holder.repo, holder.command, holder.pid
+            ))
+        },
+    )?;
+    Ok(locks)
+}
+
+/// The manifest's diff display settings with command-line overrides applied
+fn diff_settings(
+    manifest: &gitgrip::core::manifest::Manifest,
+    theme: Option<gitgrip::core::manifest::DiffTheme>,
+    side_by_side: bool,
+    unified: bool,
+) -> gitgrip::core::manifest::DiffSettings {
+    use gitgrip::core::manifest::SideBySide;
+
+    let mut settings = manifest.settings.diff;
+    if let Some(theme) = theme {
+        settings.theme = theme;
+    }
+    if side_by_side {
+        settings.side_by_side = SideBySide::Always;
+    } else if unified {
+        settings.side_by_side = SideBySide::Never;
     }
+    settings
+}
+
+fn load_workspace() -> anyhow::Result<(std::path::PathBuf, gitgrip::core::manifest::Manifest)> {
+    let workspace = gitgrip::Workspace::discover(std::env::current_dir()?)?;
+    Ok(workspace.into_parts())
 }
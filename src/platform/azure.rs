@@ -68,6 +68,35 @@ struct AzurePullRequestWithReviewers {
     reviewers: Option<Vec<AzureReviewer>>,
 }
 
+/// Azure DevOps pull request comment thread
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureThread {
+    status: Option<String>, // active, pending, fixed, wontFix, closed, byDesign
+    #[serde(default)]
+    is_deleted: bool,
+    #[serde(default)]
+    comments: Vec<AzureThreadComment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureThreadComment {
+    comment_type: Option<String>, // text, codeChange, system
+}
+
+impl AzureThread {
+    /// An open thread with at least one comment from a person
+    fn is_unresolved(&self) -> bool {
+        !self.is_deleted
+            && matches!(self.status.as_deref(), Some("active" | "pending"))
+            && self
+                .comments
+                .iter()
+                .any(|c| c.comment_type.as_deref() != Some("system"))
+    }
+}
+
 /// Azure DevOps API adapter
 pub struct AzureDevOpsAdapter {
     base_url: String,
@@ -502,6 +531,31 @@ impl HostingPlatform for AzureDevOpsAdapter {
         Ok(has_approval && !has_rejection)
     }
 
+    async fn get_unresolved_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<Option<usize>, PlatformError> {
+        let ctx = self.parse_context(owner, repo);
+
+        let threads: ListResponse<AzureThread> = self
+            .api_request(
+                reqwest::Method::GET,
+                &ctx,
+                &format!(
+                    "/git/repositories/{}/pullRequests/{}/threads",
+                    ctx.repository, pull_number
+                ),
+                None::<()>,
+            )
+            .await?;
+
+        Ok(Some(
+            threads.value.iter().filter(|t| t.is_unresolved()).count(),
+        ))
+    }
+
     async fn get_pull_request_reviews(
         &self,
         owner: &str,
@@ -1,6 +1,7 @@
 //! GitHub platform adapter
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use octocrab::Octocrab;
 use std::env;
 
@@ -37,8 +38,280 @@ impl GitHubAdapter {
             .build()
             .map_err(|e| PlatformError::ApiError(format!("Failed to create client: {}", e)))
     }
+
+    /// Run a GraphQL query and return its `data`
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, PlatformError> {
+        let client = self.get_client().await?;
+        let mut response: serde_json::Value = client
+            .graphql(&serde_json::json!({ "query": query, "variables": variables }))
+            .await
+            .map_err(|e| PlatformError::ApiError(format!("GraphQL request failed: {}", e)))?;
+
+        if let Some(message) = response["errors"][0]["message"].as_str() {
+            return Err(PlatformError::ApiError(message.to_string()));
+        }
+        Ok(response["data"].take())
+    }
+
+    /// Send a REST request to `path` (e.g. `/repos/acme/app/...`) with an
+    /// optional JSON body, turning error statuses into errors
+    async fn rest(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<reqwest::Response, PlatformError> {
+        let token = self.get_token().await?;
+        let base_url = self.base_url.as_deref().unwrap_or("https://api.github.com");
+        let mut request = reqwest::Client::new()
+            .request(method, format!("{}{}", base_url, path))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Accept", "application/vnd.github.v3+json")
+            .header("User-Agent", "gitgrip");
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(PlatformError::NotFound(path.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(PlatformError::ApiError(format!(
+                "GitHub API error ({}) for {}",
+                response.status(),
+                path
+            )));
+        }
+        Ok(response)
+    }
+
+    /// GET a REST endpoint's JSON body
+    async fn get_json(&self, path: &str) -> Result<serde_json::Value, PlatformError> {
+        self.rest(reqwest::Method::GET, path, None)
+            .await?
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))
+    }
 }
 
+/// The timestamp in `value` if it is at or after `since`
+fn at_or_after(value: &serde_json::Value, since: DateTime<Utc>) -> Option<String> {
+    let at = value.as_str()?;
+    let time = DateTime::parse_from_rfc3339(at).ok()?;
+    (time >= since).then(|| at.to_string())
+}
+
+/// The PRs `me` opened or had merged since `since`, and the other PRs updated
+/// since then as `(number, title, url)`, from a PR list sorted by update time
+fn parse_pr_activity(
+    pulls: &serde_json::Value,
+    me: &str,
+    since: DateTime<Utc>,
+) -> (Vec<PRActivity>, Vec<(u64, String, String)>) {
+    let mut activity = Vec::new();
+    let mut others = Vec::new();
+    for pull in pulls.as_array().into_iter().flatten() {
+        if at_or_after(&pull["updated_at"], since).is_none() {
+            break;
+        }
+        let Some(number) = pull["number"].as_u64() else {
+            continue;
+        };
+        let title = pull["title"].as_str().unwrap_or_default().to_string();
+        let url = pull["html_url"].as_str().unwrap_or_default().to_string();
+        if pull["user"]["login"].as_str() != Some(me) {
+            others.push((number, title, url));
+            continue;
+        }
+        for (kind, field) in [
+            (PRActivityKind::Opened, "created_at"),
+            (PRActivityKind::Merged, "merged_at"),
+        ] {
+            if let Some(at) = at_or_after(&pull[field], since) {
+                activity.push(PRActivity {
+                    kind,
+                    number,
+                    title: title.clone(),
+                    url: url.clone(),
+                    at,
+                });
+            }
+        }
+    }
+    (activity, others)
+}
+
+/// The PRs merged since `since` in a page of closed PRs sorted by update
+/// time, and whether older pages could still hold some
+fn parse_merged_pulls(pulls: &serde_json::Value, since: DateTime<Utc>) -> (Vec<MergedPR>, bool) {
+    let pulls = pulls.as_array().map(Vec::as_slice).unwrap_or_default();
+    let mut merged = Vec::new();
+    for pull in pulls {
+        // Merging updates a PR, so nothing updated earlier merged in the window
+        if at_or_after(&pull["updated_at"], since).is_none() {
+            return (merged, false);
+        }
+        let (Some(number), Some(merged_at), Some(created_at)) = (
+            pull["number"].as_u64(),
+            at_or_after(&pull["merged_at"], since),
+            pull["created_at"].as_str(),
+        ) else {
+            continue;
+        };
+        merged.push(MergedPR {
+            number,
+            created_at: created_at.to_string(),
+            merged_at,
+        });
+    }
+    (merged, pulls.len() == MERGED_PULLS_PER_PAGE)
+}
+
+/// Parse a `MergeQueueEntry` GraphQL object
+fn parse_merge_queue_entry(entry: &serde_json::Value) -> Option<MergeQueueEntry> {
+    Some(MergeQueueEntry {
+        position: entry["position"].as_u64()? as u32,
+        state: entry["state"].as_str()?.to_string(),
+        estimated_secs: entry["estimatedTimeToMerge"].as_u64(),
+    })
+}
+
+/// Parse a branch protection response
+fn parse_branch_protection(body: &serde_json::Value) -> BranchProtection {
+    let required_checks = body["required_status_checks"]["contexts"]
+        .as_array()
+        .map(|contexts| {
+            contexts
+                .iter()
+                .filter_map(|c| c.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    BranchProtection {
+        required_checks,
+        required_approvals: body["required_pull_request_reviews"]["required_approving_review_count"]
+            .as_u64()
+            .unwrap_or(0) as u32,
+        enforce_admins: body["enforce_admins"]["enabled"].as_bool().unwrap_or(false),
+    }
+}
+
+/// Actions jobs from a check-runs response; other apps' check runs have no
+/// logs to fetch
+fn parse_check_runs(body: &serde_json::Value) -> Vec<CiJob> {
+    let Some(runs) = body["check_runs"].as_array() else {
+        return Vec::new();
+    };
+    runs.iter()
+        .filter(|run| run["app"]["slug"] == "github-actions")
+        .filter_map(|run| {
+            let state = match (run["status"].as_str(), run["conclusion"].as_str()) {
+                (Some("completed"), Some("success" | "neutral" | "skipped")) => CheckState::Success,
+                (Some("completed"), _) => CheckState::Failure,
+                _ => CheckState::Pending,
+            };
+            Some(CiJob {
+                id: run["id"].as_u64()?,
+                name: run["name"].as_str()?.to_string(),
+                state,
+                url: run["html_url"].as_str().map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// IDs of failed runs among the newest run of each workflow, from a
+/// newest-first workflow runs response
+fn latest_failed_runs(body: &serde_json::Value) -> Vec<u64> {
+    let Some(runs) = body["workflow_runs"].as_array() else {
+        return Vec::new();
+    };
+    let mut seen = std::collections::HashSet::new();
+    runs.iter()
+        .filter(|run| seen.insert(run["workflow_id"].as_u64()))
+        .filter(|run| {
+            matches!(
+                run["conclusion"].as_str(),
+                Some("failure" | "timed_out" | "cancelled" | "startup_failure")
+            )
+        })
+        .filter_map(|run| run["id"].as_u64())
+        .collect()
+}
+
+/// A run from the Actions API
+fn parse_workflow_run(run: &serde_json::Value) -> Option<WorkflowRun> {
+    let state = match (run["status"].as_str(), run["conclusion"].as_str()) {
+        (Some("completed"), Some("success" | "neutral" | "skipped")) => CheckState::Success,
+        (Some("completed"), _) => CheckState::Failure,
+        _ => CheckState::Pending,
+    };
+    Some(WorkflowRun {
+        id: run["id"].as_u64()?,
+        state,
+        url: run["html_url"].as_str().map(str::to_string),
+    })
+}
+
+/// Newest run in a workflow runs response with an ID above `after`
+fn newest_run_after(body: &serde_json::Value, after: u64) -> Option<WorkflowRun> {
+    body["workflow_runs"]
+        .as_array()?
+        .iter()
+        .filter_map(parse_workflow_run)
+        .filter(|run| run.id > after)
+        .max_by_key(|run| run.id)
+}
+
+/// Unexpired artifacts in a run artifacts response
+fn parse_artifacts(body: &serde_json::Value, run_id: u64) -> Vec<CiArtifact> {
+    body["artifacts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|artifact| !artifact["expired"].as_bool().unwrap_or(false))
+        .filter_map(|artifact| {
+            Some(CiArtifact {
+                id: artifact["id"].as_u64()?,
+                name: artifact["name"].as_str()?.to_string(),
+                run_id,
+                size: artifact["size_in_bytes"].as_u64(),
+                sha256: artifact["digest"]
+                    .as_str()
+                    .and_then(|digest| digest.strip_prefix("sha256:"))
+                    .map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Deployments checked per environment for one whose latest status is
+/// `success`, before settling for the newest
+const DEPLOYMENTS_PER_ENVIRONMENT: usize = 5;
+
+/// Other people's recently updated PRs checked for the user's reviews
+const ACTIVITY_REVIEW_LOOKUPS: usize = 30;
+
+/// Page size when listing merged PRs
+const MERGED_PULLS_PER_PAGE: usize = 100;
+
+/// Pages of closed PRs read when listing merged PRs
+const MERGED_PULLS_MAX_PAGES: usize = 10;
+
+/// How long to look for the run a dispatch spawned
+const DISPATCH_POLLS: u32 = 15;
+
+const MERGE_QUEUE_ENTRY_FIELDS: &str = "position state estimatedTimeToMerge";
+
 #[async_trait]
 impl HostingPlatform for GitHubAdapter {
     fn platform_type(&self) -> PlatformType {
@@ -366,6 +639,558 @@ impl HostingPlatform for GitHubAdapter {
         })
     }
 
+    async fn request_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+        users: &[String],
+        teams: &[String],
+    ) -> Result<(), PlatformError> {
+        let client = self.get_client().await?;
+
+        // GitHub rejects the whole request if it includes the PR author
+        let me = client.current().user().await.ok().map(|u| u.login);
+        let users: Vec<&String> = users
+            .iter()
+            .filter(|u| me.as_ref().map_or(true, |me| !me.eq_ignore_ascii_case(u)))
+            .collect();
+        if users.is_empty() && teams.is_empty() {
+            return Ok(());
+        }
+
+        let route = format!(
+            "/repos/{}/{}/pulls/{}/requested_reviewers",
+            owner, repo, pull_number
+        );
+        let body = serde_json::json!({
+            "reviewers": users,
+            "team_reviewers": teams,
+        });
+
+        client
+            .post::<_, serde_json::Value>(route, Some(&body))
+            .await
+            .map_err(|e| PlatformError::ApiError(format!("Failed to request reviewers: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn rename_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<(), PlatformError> {
+        let client = self.get_client().await?;
+
+        // GitHub retargets PRs from and into the branch as part of the rename
+        let route = format!("/repos/{}/{}/branches/{}/rename", owner, repo, old);
+        let body = serde_json::json!({ "new_name": new });
+
+        client
+            .post::<_, serde_json::Value>(route, Some(&body))
+            .await
+            .map_err(|e| PlatformError::ApiError(format!("Failed to rename branch: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn protect_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<(), PlatformError> {
+        let client = self.get_client().await?;
+
+        let route = format!("/repos/{}/{}/branches/{}/protection", owner, repo, branch);
+        let body = serde_json::json!({
+            "required_status_checks": null,
+            "enforce_admins": false,
+            "required_pull_request_reviews": {
+                "required_approving_review_count": 1,
+            },
+            "restrictions": null,
+        });
+
+        client
+            .put::<serde_json::Value, _, _>(route, Some(&body))
+            .await
+            .map_err(|e| PlatformError::ApiError(format!("Failed to protect branch: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_branch_protection(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<BranchProtection>, PlatformError> {
+        let token = self.get_token().await?;
+        let base_url = self.base_url.as_deref().unwrap_or("https://api.github.com");
+        let url = format!(
+            "{}/repos/{}/{}/branches/{}/protection",
+            base_url, owner, repo, branch
+        );
+
+        let response = reqwest::Client::new()
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Accept", "application/vnd.github.v3+json")
+            .header("User-Agent", "gitgrip")
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+
+        // 404 means the branch has no protection rules
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(PlatformError::ApiError(format!(
+                "Failed to get branch protection: {}",
+                response.status()
+            )));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))?;
+        Ok(Some(parse_branch_protection(&body)))
+    }
+
+    async fn list_ci_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<CiJob>, PlatformError> {
+        // An Actions job's check run shares its ID
+        let body = self
+            .get_json(&format!(
+                "/repos/{}/{}/commits/{}/check-runs?filter=latest&per_page=100",
+                owner,
+                repo,
+                urlencoding::encode(branch)
+            ))
+            .await?;
+        Ok(parse_check_runs(&body))
+    }
+
+    async fn get_ci_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: u64,
+    ) -> Result<String, PlatformError> {
+        // Redirects to a signed download URL, which reqwest follows without
+        // forwarding the token
+        self.rest(
+            reqwest::Method::GET,
+            &format!("/repos/{}/{}/actions/jobs/{}/logs", owner, repo, job_id),
+            None,
+        )
+        .await?
+        .text()
+        .await
+        .map_err(|e| PlatformError::NetworkError(e.to_string()))
+    }
+
+    async fn list_ci_artifacts(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        workflow: Option<&str>,
+    ) -> Result<Vec<CiArtifact>, PlatformError> {
+        let runs = match workflow {
+            Some(workflow) => format!(
+                "/repos/{}/{}/actions/workflows/{}/runs",
+                owner,
+                repo,
+                urlencoding::encode(workflow)
+            ),
+            None => format!("/repos/{}/{}/actions/runs", owner, repo),
+        };
+        let body = self
+            .get_json(&format!(
+                "{}?branch={}&status=success&per_page=1",
+                runs,
+                urlencoding::encode(branch)
+            ))
+            .await?;
+        let Some(run_id) = body["workflow_runs"][0]["id"].as_u64() else {
+            return Ok(Vec::new());
+        };
+
+        let body = self
+            .get_json(&format!(
+                "/repos/{}/{}/actions/runs/{}/artifacts?per_page=100",
+                owner, repo, run_id
+            ))
+            .await?;
+        Ok(parse_artifacts(&body, run_id))
+    }
+
+    async fn download_ci_artifact(
+        &self,
+        owner: &str,
+        repo: &str,
+        artifact_id: u64,
+    ) -> Result<Vec<u8>, PlatformError> {
+        // Redirects to a signed download URL, like job logs
+        let bytes = self
+            .rest(
+                reqwest::Method::GET,
+                &format!(
+                    "/repos/{}/{}/actions/artifacts/{}/zip",
+                    owner, repo, artifact_id
+                ),
+                None,
+            )
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn rerun_failed_ci_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<usize, PlatformError> {
+        let body = self
+            .get_json(&format!(
+                "/repos/{}/{}/actions/runs?branch={}&per_page=100",
+                owner,
+                repo,
+                urlencoding::encode(branch)
+            ))
+            .await?;
+
+        let failed = latest_failed_runs(&body);
+        for run_id in &failed {
+            self.rest(
+                reqwest::Method::POST,
+                &format!(
+                    "/repos/{}/{}/actions/runs/{}/rerun-failed-jobs",
+                    owner, repo, run_id
+                ),
+                None,
+            )
+            .await?;
+        }
+        Ok(failed.len())
+    }
+
+    async fn dispatch_workflow(
+        &self,
+        owner: &str,
+        repo: &str,
+        workflow: &str,
+        git_ref: &str,
+        inputs: &[(String, String)],
+    ) -> Result<WorkflowRun, PlatformError> {
+        // The dispatch API doesn't return the run, so look for a run newer
+        // than any that existed before it
+        let runs_path = format!(
+            "/repos/{}/{}/actions/workflows/{}/runs?event=workflow_dispatch&branch={}&per_page=20",
+            owner,
+            repo,
+            urlencoding::encode(workflow),
+            urlencoding::encode(git_ref)
+        );
+        let before = self.get_json(&runs_path).await?;
+        let newest_before = newest_run_after(&before, 0).map_or(0, |run| run.id);
+
+        let inputs: serde_json::Map<String, serde_json::Value> = inputs
+            .iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::from(value.as_str())))
+            .collect();
+        self.rest(
+            reqwest::Method::POST,
+            &format!(
+                "/repos/{}/{}/actions/workflows/{}/dispatches",
+                owner,
+                repo,
+                urlencoding::encode(workflow)
+            ),
+            Some(&serde_json::json!({ "ref": git_ref, "inputs": inputs })),
+        )
+        .await?;
+
+        for _ in 0..DISPATCH_POLLS {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            if let Some(run) = newest_run_after(&self.get_json(&runs_path).await?, newest_before) {
+                return Ok(run);
+            }
+        }
+        Err(PlatformError::ApiError(format!(
+            "Workflow {} was dispatched but its run didn't appear",
+            workflow
+        )))
+    }
+
+    async fn get_workflow_run(
+        &self,
+        owner: &str,
+        repo: &str,
+        run_id: u64,
+    ) -> Result<WorkflowRun, PlatformError> {
+        let body = self
+            .get_json(&format!(
+                "/repos/{}/{}/actions/runs/{}",
+                owner, repo, run_id
+            ))
+            .await?;
+        parse_workflow_run(&body)
+            .ok_or_else(|| PlatformError::ParseError(format!("run {} has no ID", run_id)))
+    }
+
+    async fn list_deployments(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<Deployment>, PlatformError> {
+        // Newest first; a deployment's state lives in its latest status
+        let body = self
+            .get_json(&format!(
+                "/repos/{}/{}/deployments?per_page=100",
+                owner, repo
+            ))
+            .await?;
+        let mut by_environment: std::collections::BTreeMap<&str, Vec<&serde_json::Value>> =
+            std::collections::BTreeMap::new();
+        for deployment in body.as_array().into_iter().flatten() {
+            if let Some(environment) = deployment["environment"].as_str() {
+                by_environment
+                    .entry(environment)
+                    .or_default()
+                    .push(deployment);
+            }
+        }
+
+        let mut live = Vec::new();
+        for (environment, deployments) in by_environment {
+            let mut chosen: Option<Deployment> = None;
+            for deployment in deployments.iter().take(DEPLOYMENTS_PER_ENVIRONMENT) {
+                let Some(id) = deployment["id"].as_u64() else {
+                    continue;
+                };
+                let statuses = self
+                    .get_json(&format!(
+                        "/repos/{}/{}/deployments/{}/statuses?per_page=1",
+                        owner, repo, id
+                    ))
+                    .await?;
+                let status = &statuses[0];
+                let candidate = Deployment {
+                    environment: environment.to_string(),
+                    sha: deployment["sha"].as_str().unwrap_or_default().to_string(),
+                    git_ref: deployment["ref"].as_str().unwrap_or_default().to_string(),
+                    state: status["state"].as_str().unwrap_or("pending").to_string(),
+                    created_at: deployment["created_at"].as_str().map(str::to_string),
+                    url: status["environment_url"]
+                        .as_str()
+                        .filter(|url| !url.is_empty())
+                        .or_else(|| status["target_url"].as_str())
+                        .map(str::to_string),
+                };
+                let succeeded = candidate.state == "success";
+                if chosen.is_none() || succeeded {
+                    chosen = Some(candidate);
+                }
+                if succeeded {
+                    break;
+                }
+            }
+            live.extend(chosen);
+        }
+        Ok(live)
+    }
+
+    async fn list_pr_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PRActivity>, PlatformError> {
+        let me = self.get_json("/user").await?["login"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let pulls = self
+            .get_json(&format!(
+                "/repos/{}/{}/pulls?state=all&sort=updated&direction=desc&per_page=100",
+                owner, repo
+            ))
+            .await?;
+        let (mut activity, others) = parse_pr_activity(&pulls, &me, since);
+
+        // Reviews need a request per PR, so only the most recently updated
+        for (number, title, url) in others.into_iter().take(ACTIVITY_REVIEW_LOOKUPS) {
+            let reviews = self
+                .get_json(&format!(
+                    "/repos/{}/{}/pulls/{}/reviews?per_page=100",
+                    owner, repo, number
+                ))
+                .await?;
+            let latest = reviews
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|review| review["user"]["login"].as_str() == Some(me.as_str()))
+                .filter_map(|review| at_or_after(&review["submitted_at"], since))
+                .max();
+            if let Some(at) = latest {
+                activity.push(PRActivity {
+                    kind: PRActivityKind::Reviewed,
+                    number,
+                    title,
+                    url,
+                    at,
+                });
+            }
+        }
+        Ok(activity)
+    }
+
+    async fn list_merged_pull_requests(
+        &self,
+        owner: &str,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<MergedPR>, PlatformError> {
+        let mut merged = Vec::new();
+        for page in 1..=MERGED_PULLS_MAX_PAGES {
+            let pulls = self
+                .get_json(&format!(
+                    "/repos/{}/{}/pulls?state=closed&sort=updated&direction=desc&per_page={}&page={}",
+                    owner, repo, MERGED_PULLS_PER_PAGE, page
+                ))
+                .await?;
+            let (found, more) = parse_merged_pulls(&pulls, since);
+            merged.extend(found);
+            if !more {
+                break;
+            }
+        }
+        merged.sort_by(|a, b| b.merged_at.cmp(&a.merged_at));
+        Ok(merged)
+    }
+
+    async fn get_merge_queue_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<MergeQueueStatus, PlatformError> {
+        let query = format!(
+            "query($owner: String!, $repo: String!, $number: Int!) {{ \
+               repository(owner: $owner, name: $repo) {{ \
+                 pullRequest(number: $number) {{ \
+                   isMergeQueueEnabled mergeQueueEntry {{ {} }} }} }} }}",
+            MERGE_QUEUE_ENTRY_FIELDS
+        );
+        let data = self
+            .graphql(
+                &query,
+                serde_json::json!({ "owner": owner, "repo": repo, "number": pull_number }),
+            )
+            .await?;
+
+        let pr = &data["repository"]["pullRequest"];
+        if pr.is_null() {
+            return Err(PlatformError::NotFound(format!("PR #{}", pull_number)));
+        }
+        Ok(MergeQueueStatus {
+            enabled: pr["isMergeQueueEnabled"].as_bool().unwrap_or(false),
+            entry: parse_merge_queue_entry(&pr["mergeQueueEntry"]),
+        })
+    }
+
+    async fn get_unresolved_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<Option<usize>, PlatformError> {
+        let query = "query($owner: String!, $repo: String!, $number: Int!, $after: String) { \
+                       repository(owner: $owner, name: $repo) { pullRequest(number: $number) { \
+                         reviewThreads(first: 100, after: $after) { \
+                           nodes { isResolved } pageInfo { hasNextPage endCursor } } } } }";
+        let mut unresolved = 0;
+        let mut after: Option<String> = None;
+        loop {
+            let data = self
+                .graphql(
+                    query,
+                    serde_json::json!({
+                        "owner": owner, "repo": repo, "number": pull_number, "after": after
+                    }),
+                )
+                .await?;
+            let threads = &data["repository"]["pullRequest"]["reviewThreads"];
+            if threads.is_null() {
+                return Err(PlatformError::NotFound(format!("PR #{}", pull_number)));
+            }
+            unresolved += threads["nodes"]
+                .as_array()
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .filter(|node| node["isResolved"] == false)
+                        .count()
+                })
+                .unwrap_or(0);
+
+            let page = &threads["pageInfo"];
+            match page["endCursor"].as_str() {
+                Some(cursor) if page["hasNextPage"] == true => after = Some(cursor.to_string()),
+                _ => break,
+            }
+        }
+        Ok(Some(unresolved))
+    }
+
+    async fn enqueue_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<MergeQueueEntry, PlatformError> {
+        // The mutation takes the PR's node ID rather than its number
+        let data = self
+            .graphql(
+                "query($owner: String!, $repo: String!, $number: Int!) { \
+                   repository(owner: $owner, name: $repo) { pullRequest(number: $number) { id } } }",
+                serde_json::json!({ "owner": owner, "repo": repo, "number": pull_number }),
+            )
+            .await?;
+        let id = data["repository"]["pullRequest"]["id"]
+            .as_str()
+            .ok_or_else(|| PlatformError::NotFound(format!("PR #{}", pull_number)))?
+            .to_string();
+
+        let mutation = format!(
+            "mutation($id: ID!) {{ enqueuePullRequest(input: {{ pullRequestId: $id }}) {{ \
+               mergeQueueEntry {{ {} }} }} }}",
+            MERGE_QUEUE_ENTRY_FIELDS
+        );
+        let data = self
+            .graphql(&mutation, serde_json::json!({ "id": id }))
+            .await?;
+        parse_merge_queue_entry(&data["enqueuePullRequest"]["mergeQueueEntry"]).ok_or_else(|| {
+            PlatformError::ParseError("enqueuePullRequest returned no queue entry".to_string())
+        })
+    }
+
     async fn get_pull_request_diff(
         &self,
         owner: &str,
@@ -619,6 +1444,121 @@ impl HostingPlatform for GitHubAdapter {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_newest_run_after() {
+        let body = serde_json::json!({ "workflow_runs": [
+            { "id": 12, "status": "queued", "html_url": "https://github.com/acme/app/actions/runs/12" },
+            { "id": 11, "status": "completed", "conclusion": "success" },
+            { "id": 10, "status": "completed", "conclusion": "failure" }
+        ]});
+        let run = newest_run_after(&body, 11).unwrap();
+        assert_eq!((run.id, run.state), (12, CheckState::Pending));
+        assert!(newest_run_after(&body, 12).is_none());
+        assert_eq!(
+            parse_workflow_run(&body["workflow_runs"][2]).unwrap().state,
+            CheckState::Failure
+        );
+    }
+
+    #[test]
+    fn test_parse_pr_activity() {
+        let since = DateTime::parse_from_rfc3339("2026-05-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let pulls = serde_json::json!([
+            { "number": 3, "title": "Add login", "html_url": "u3", "user": { "login": "me" },
+              "created_at": "2026-05-01T09:00:00Z", "merged_at": "2026-05-01T15:00:00Z",
+              "updated_at": "2026-05-01T15:00:00Z" },
+            { "number": 2, "title": "Fix typo", "html_url": "u2", "user": { "login": "ana" },
+              "created_at": "2026-04-20T09:00:00Z", "merged_at": null,
+              "updated_at": "2026-05-01T10:00:00Z" },
+            { "number": 1, "title": "Old", "html_url": "u1", "user": { "login": "me" },
+              "created_at": "2026-04-01T09:00:00Z", "merged_at": null,
+              "updated_at": "2026-04-02T10:00:00Z" }
+        ]);
+        let (activity, others) = parse_pr_activity(&pulls, "me", since);
+        let kinds: Vec<_> = activity.iter().map(|a| (a.kind, a.number)).collect();
+        assert_eq!(
+            kinds,
+            vec![(PRActivityKind::Opened, 3), (PRActivityKind::Merged, 3)]
+        );
+        assert_eq!(others, vec![(2, "Fix typo".to_string(), "u2".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_merged_pulls() {
+        let since = DateTime::parse_from_rfc3339("2026-05-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let pulls = serde_json::json!([
+            { "number": 3, "created_at": "2026-04-28T09:00:00Z",
+              "merged_at": "2026-05-02T09:00:00Z", "updated_at": "2026-05-02T09:00:00Z" },
+            { "number": 2, "created_at": "2026-04-28T09:00:00Z", "merged_at": null,
+              "updated_at": "2026-05-01T10:00:00Z" },
+            { "number": 1, "created_at": "2026-04-01T09:00:00Z",
+              "merged_at": "2026-04-02T09:00:00Z", "updated_at": "2026-04-02T09:00:00Z" }
+        ]);
+        let (merged, more) = parse_merged_pulls(&pulls, since);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].number, 3);
+        assert_eq!(merged[0].created_at, "2026-04-28T09:00:00Z");
+        assert!(!more);
+    }
+
+    #[test]
+    fn test_parse_artifacts() {
+        let body = serde_json::json!({ "artifacts": [
+            { "id": 5, "name": "dist", "size_in_bytes": 2048, "expired": false,
+              "digest": "sha256:ab12" },
+            { "id": 4, "name": "coverage", "expired": true },
+            { "id": 3, "name": "docs", "expired": false, "digest": null }
+        ]});
+        let artifacts = parse_artifacts(&body, 77);
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts[0].name, "dist");
+        assert_eq!(artifacts[0].run_id, 77);
+        assert_eq!(artifacts[0].size, Some(2048));
+        assert_eq!(artifacts[0].sha256.as_deref(), Some("ab12"));
+        assert_eq!(artifacts[1].sha256, None);
+    }
+
+    #[test]
+    fn test_latest_failed_runs() {
+        let body = serde_json::json!({ "workflow_runs": [
+            { "id": 30, "workflow_id": 1, "conclusion": "success" },
+            { "id": 29, "workflow_id": 2, "conclusion": "failure" },
+            { "id": 28, "workflow_id": 1, "conclusion": "failure" },
+            { "id": 27, "workflow_id": 3, "conclusion": null }
+        ]});
+        // CI already fixed by a newer run isn't re-run
+        assert_eq!(latest_failed_runs(&body), vec![29]);
+    }
+
+    #[test]
+    fn test_parse_check_runs() {
+        let body = serde_json::json!({ "check_runs": [
+            { "id": 1, "name": "build", "status": "completed", "conclusion": "failure",
+              "html_url": "https://github.com/acme/app/actions/runs/9/job/1",
+              "app": { "slug": "github-actions" } },
+            { "id": 2, "name": "lint", "status": "in_progress", "conclusion": null,
+              "app": { "slug": "github-actions" } },
+            { "id": 3, "name": "codecov", "status": "completed", "conclusion": "success",
+              "app": { "slug": "codecov" } }
+        ]});
+        let jobs = parse_check_runs(&body);
+        let summary: Vec<(u64, &str, CheckState)> = jobs
+            .iter()
+            .map(|job| (job.id, job.name.as_str(), job.state))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "build", CheckState::Failure),
+                (2, "lint", CheckState::Pending)
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_github_ssh_url() {
         let adapter = GitHubAdapter::new(None);
@@ -641,6 +1581,37 @@ mod tests {
         assert_eq!(info.repo, "repo");
     }
 
+    #[test]
+    fn test_parse_merge_queue_entry() {
+        let entry = parse_merge_queue_entry(&serde_json::json!({
+            "position": 2,
+            "state": "AWAITING_CHECKS",
+            "estimatedTimeToMerge": 300,
+        }))
+        .unwrap();
+        assert_eq!(entry.position, 2);
+        assert_eq!(entry.to_string(), "#2 awaiting checks (~5m)");
+
+        assert!(parse_merge_queue_entry(&serde_json::Value::Null).is_none());
+    }
+
+    #[test]
+    fn test_parse_branch_protection() {
+        let protection = parse_branch_protection(&serde_json::json!({
+            "required_status_checks": { "strict": true, "contexts": ["ci/build", "lint"] },
+            "required_pull_request_reviews": { "required_approving_review_count": 2 },
+            "enforce_admins": { "enabled": false },
+        }));
+        assert_eq!(protection.required_checks, vec!["ci/build", "lint"]);
+        assert_eq!(protection.required_approvals, 2);
+        assert!(!protection.enforce_admins);
+
+        assert_eq!(
+            parse_branch_protection(&serde_json::json!({})),
+            BranchProtection::default()
+        );
+    }
+
     #[test]
     fn test_matches_url() {
         let adapter = GitHubAdapter::new(None);
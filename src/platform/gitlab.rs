@@ -1,6 +1,7 @@
 //! GitLab platform adapter
 
 use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::env;
@@ -9,6 +10,9 @@ use super::traits::{HostingPlatform, LinkedPRRef, PlatformError};
 use super::types::*;
 use crate::core::manifest::PlatformType;
 
+/// Other people's recently updated MRs checked for the user's approval
+const ACTIVITY_REVIEW_LOOKUPS: usize = 30;
+
 /// GitLab merge request response
 #[derive(Debug, Deserialize)]
 struct GitLabMergeRequest {
@@ -44,7 +48,81 @@ struct ApprovalUser {
 /// GitLab pipeline
 #[derive(Debug, Deserialize)]
 struct GitLabPipeline {
+    id: u64,
     status: String, // success, failed, running, pending, canceled, skipped
+    web_url: Option<String>,
+}
+
+impl GitLabPipeline {
+    fn to_workflow_run(&self) -> WorkflowRun {
+        WorkflowRun {
+            id: self.id,
+            state: match self.status.as_str() {
+                "success" | "skipped" => CheckState::Success,
+                "failed" | "canceled" => CheckState::Failure,
+                _ => CheckState::Pending,
+            },
+            url: self.web_url.clone(),
+        }
+    }
+}
+
+/// GitLab CI job
+#[derive(Debug, Deserialize)]
+struct GitLabJob {
+    id: u64,
+    name: String,
+    status: String, // success, failed, canceled, running, pending, manual, skipped, ...
+    web_url: Option<String>,
+    artifacts_file: Option<GitLabArtifactsFile>,
+}
+
+/// A GitLab job's artifacts archive
+#[derive(Debug, Deserialize)]
+struct GitLabArtifactsFile {
+    size: Option<u64>,
+}
+
+/// GitLab environment
+#[derive(Debug, Deserialize)]
+struct GitLabEnvironment {
+    id: u64,
+    name: String,
+    external_url: Option<String>,
+    last_deployment: Option<GitLabDeployment>,
+}
+
+/// GitLab deployment
+#[derive(Debug, Deserialize)]
+struct GitLabDeployment {
+    sha: String,
+    #[serde(rename = "ref")]
+    git_ref: String,
+    status: String, // created, running, success, failed, canceled, blocked
+    created_at: Option<String>,
+}
+
+/// GitLab merge request discussion (a comment thread)
+#[derive(Debug, Deserialize)]
+struct GitLabDiscussion {
+    notes: Vec<GitLabNote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNote {
+    #[serde(default)]
+    resolvable: bool,
+    #[serde(default)]
+    resolved: bool,
+}
+
+impl GitLabDiscussion {
+    /// A resolvable thread that hasn't been resolved
+    fn is_unresolved(&self) -> bool {
+        self.notes
+            .iter()
+            .any(|note| note.resolvable && !note.resolved)
+    }
 }
 
 /// GitLab API adapter
@@ -179,6 +257,41 @@ impl GitLabAdapter {
 
         Ok(())
     }
+
+    /// Make GET request for a plain-text response
+    async fn api_text(&self, endpoint: &str) -> Result<String, PlatformError> {
+        let bytes = self.api_bytes(endpoint).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// GET an endpoint that returns a raw body (job logs, artifact archives)
+    async fn api_bytes(&self, endpoint: &str) -> Result<Vec<u8>, PlatformError> {
+        let token = self.get_token().await?;
+        let url = format!("{}/api/v4{}", self.base_url, endpoint);
+
+        let response = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(PlatformError::ApiError(format!(
+                "GitLab API error ({}): {}",
+                status, error_text
+            )));
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))
+    }
 }
 
 #[async_trait]
@@ -437,6 +550,35 @@ impl HostingPlatform for GitLabAdapter {
         }
     }
 
+    async fn get_unresolved_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<Option<usize>, PlatformError> {
+        const PER_PAGE: usize = 100;
+        let project_id = self.encode_project(owner, repo);
+
+        let mut unresolved = 0;
+        for page in 1.. {
+            let discussions: Vec<GitLabDiscussion> = self
+                .api_request(
+                    reqwest::Method::GET,
+                    &format!(
+                        "/projects/{}/merge_requests/{}/discussions?per_page={}&page={}",
+                        project_id, pull_number, PER_PAGE, page
+                    ),
+                    None::<()>,
+                )
+                .await?;
+            unresolved += discussions.iter().filter(|d| d.is_unresolved()).count();
+            if discussions.len() < PER_PAGE {
+                break;
+            }
+        }
+        Ok(Some(unresolved))
+    }
+
     async fn get_pull_request_reviews(
         &self,
         owner: &str,
@@ -520,6 +662,374 @@ impl HostingPlatform for GitLabAdapter {
         }
     }
 
+    async fn list_ci_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<CiJob>, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+
+        let pipelines: Vec<GitLabPipeline> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/pipelines?ref={}&per_page=1",
+                    project_id,
+                    urlencoding::encode(branch)
+                ),
+                None::<()>,
+            )
+            .await?;
+        let Some(pipeline) = pipelines.first() else {
+            return Ok(Vec::new());
+        };
+
+        let jobs: Vec<GitLabJob> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/pipelines/{}/jobs?per_page=100",
+                    project_id, pipeline.id
+                ),
+                None::<()>,
+            )
+            .await?;
+        Ok(jobs
+            .into_iter()
+            .map(|job| CiJob {
+                id: job.id,
+                state: match job.status.as_str() {
+                    "success" | "skipped" => CheckState::Success,
+                    "failed" | "canceled" => CheckState::Failure,
+                    _ => CheckState::Pending,
+                },
+                name: job.name,
+                url: job.web_url,
+            })
+            .collect())
+    }
+
+    async fn rerun_failed_ci_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<usize, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+
+        let pipelines: Vec<GitLabPipeline> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/pipelines?ref={}&per_page=1",
+                    project_id,
+                    urlencoding::encode(branch)
+                ),
+                None::<()>,
+            )
+            .await?;
+        let Some(pipeline) = pipelines.first().filter(|p| p.status == "failed") else {
+            return Ok(0);
+        };
+
+        // Retrying a pipeline restarts only its failed and canceled jobs
+        let _: serde_json::Value = self
+            .api_request(
+                reqwest::Method::POST,
+                &format!("/projects/{}/pipelines/{}/retry", project_id, pipeline.id),
+                None::<()>,
+            )
+            .await?;
+        Ok(1)
+    }
+
+    async fn list_deployments(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<Deployment>, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+
+        // The list leaves out each environment's last deployment
+        let environments: Vec<GitLabEnvironment> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/environments?states=available&per_page=100",
+                    project_id
+                ),
+                None::<()>,
+            )
+            .await?;
+        let mut deployments = Vec::new();
+        for environment in environments {
+            let environment: GitLabEnvironment = self
+                .api_request(
+                    reqwest::Method::GET,
+                    &format!("/projects/{}/environments/{}", project_id, environment.id),
+                    None::<()>,
+                )
+                .await?;
+            if let Some(last) = environment.last_deployment {
+                deployments.push(Deployment {
+                    environment: environment.name,
+                    sha: last.sha,
+                    git_ref: last.git_ref,
+                    state: last.status,
+                    created_at: last.created_at,
+                    url: environment.external_url,
+                });
+            }
+        }
+        deployments.sort_by(|a, b| a.environment.cmp(&b.environment));
+        Ok(deployments)
+    }
+
+    async fn list_pr_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PRActivity>, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+        let user: serde_json::Value = self
+            .api_request(reqwest::Method::GET, "/user", None::<()>)
+            .await?;
+        let me = user["username"].as_str().unwrap_or_default();
+        let requests: Vec<serde_json::Value> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/merge_requests?updated_after={}&order_by=updated_at&per_page=100",
+                    project_id,
+                    since.to_rfc3339_opts(SecondsFormat::Secs, true)
+                ),
+                None::<()>,
+            )
+            .await?;
+
+        let after = |value: &serde_json::Value| {
+            let at = value.as_str()?;
+            (DateTime::parse_from_rfc3339(at).ok()? >= since).then(|| at.to_string())
+        };
+        let mut activity = Vec::new();
+        let mut reviews_checked = 0;
+        for request in &requests {
+            let Some(iid) = request["iid"].as_u64() else {
+                continue;
+            };
+            let title = request["title"].as_str().unwrap_or_default().to_string();
+            let url = request["web_url"].as_str().unwrap_or_default().to_string();
+            if request["author"]["username"].as_str() == Some(me) {
+                for (kind, field) in [
+                    (PRActivityKind::Opened, "created_at"),
+                    (PRActivityKind::Merged, "merged_at"),
+                ] {
+                    if let Some(at) = after(&request[field]) {
+                        activity.push(PRActivity {
+                            kind,
+                            number: iid,
+                            title: title.clone(),
+                            url: url.clone(),
+                            at,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            // Approvals carry no timestamp, so the MR's update time stands in
+            if reviews_checked == ACTIVITY_REVIEW_LOOKUPS {
+                continue;
+            }
+            reviews_checked += 1;
+            let approval: GitLabApproval = self
+                .api_request(
+                    reqwest::Method::GET,
+                    &format!("/projects/{}/merge_requests/{}/approvals", project_id, iid),
+                    None::<()>,
+                )
+                .await?;
+            if approval.approved_by.iter().any(|a| a.user.username == me) {
+                if let Some(at) = after(&request["updated_at"]) {
+                    activity.push(PRActivity {
+                        kind: PRActivityKind::Reviewed,
+                        number: iid,
+                        title,
+                        url,
+                        at,
+                    });
+                }
+            }
+        }
+        Ok(activity)
+    }
+
+    async fn list_merged_pull_requests(
+        &self,
+        owner: &str,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<MergedPR>, PlatformError> {
+        const PER_PAGE: usize = 100;
+        let project_id = self.encode_project(owner, repo);
+
+        let mut merged = Vec::new();
+        for page in 1.. {
+            let requests: Vec<serde_json::Value> = self
+                .api_request(
+                    reqwest::Method::GET,
+                    &format!(
+                        "/projects/{}/merge_requests?state=merged&updated_after={}&per_page={}&page={}",
+                        project_id,
+                        since.to_rfc3339_opts(SecondsFormat::Secs, true),
+                        PER_PAGE,
+                        page
+                    ),
+                    None::<()>,
+                )
+                .await?;
+            for request in &requests {
+                let (Some(iid), Some(created_at), Some(merged_at)) = (
+                    request["iid"].as_u64(),
+                    request["created_at"].as_str(),
+                    request["merged_at"].as_str(),
+                ) else {
+                    continue;
+                };
+                let in_window = DateTime::parse_from_rfc3339(merged_at).is_ok_and(|at| at >= since);
+                if in_window {
+                    merged.push(MergedPR {
+                        number: iid,
+                        created_at: created_at.to_string(),
+                        merged_at: merged_at.to_string(),
+                    });
+                }
+            }
+            if requests.len() < PER_PAGE {
+                break;
+            }
+        }
+        merged.sort_by(|a, b| b.merged_at.cmp(&a.merged_at));
+        Ok(merged)
+    }
+
+    async fn dispatch_workflow(
+        &self,
+        owner: &str,
+        repo: &str,
+        _workflow: &str,
+        git_ref: &str,
+        inputs: &[(String, String)],
+    ) -> Result<WorkflowRun, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+        let variables: Vec<serde_json::Value> = inputs
+            .iter()
+            .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+            .collect();
+
+        let pipeline: GitLabPipeline = self
+            .api_request(
+                reqwest::Method::POST,
+                &format!("/projects/{}/pipeline", project_id),
+                Some(serde_json::json!({ "ref": git_ref, "variables": variables })),
+            )
+            .await?;
+        Ok(pipeline.to_workflow_run())
+    }
+
+    async fn get_workflow_run(
+        &self,
+        owner: &str,
+        repo: &str,
+        run_id: u64,
+    ) -> Result<WorkflowRun, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+        let pipeline: GitLabPipeline = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!("/projects/{}/pipelines/{}", project_id, run_id),
+                None::<()>,
+            )
+            .await?;
+        Ok(pipeline.to_workflow_run())
+    }
+
+    async fn get_ci_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: u64,
+    ) -> Result<String, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+        self.api_text(&format!("/projects/{}/jobs/{}/trace", project_id, job_id))
+            .await
+    }
+
+    async fn list_ci_artifacts(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        _workflow: Option<&str>,
+    ) -> Result<Vec<CiArtifact>, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+        let pipelines: Vec<GitLabPipeline> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/pipelines?ref={}&status=success&per_page=1",
+                    project_id,
+                    urlencoding::encode(branch)
+                ),
+                None::<()>,
+            )
+            .await?;
+        let Some(pipeline) = pipelines.first() else {
+            return Ok(Vec::new());
+        };
+
+        // Each job's artifacts archive is one artifact, named after the job
+        let jobs: Vec<GitLabJob> = self
+            .api_request(
+                reqwest::Method::GET,
+                &format!(
+                    "/projects/{}/pipelines/{}/jobs?per_page=100",
+                    project_id, pipeline.id
+                ),
+                None::<()>,
+            )
+            .await?;
+        Ok(jobs
+            .into_iter()
+            .filter_map(|job| {
+                let file = job.artifacts_file?;
+                Some(CiArtifact {
+                    id: job.id,
+                    name: job.name,
+                    run_id: pipeline.id,
+                    size: file.size,
+                    sha256: None,
+                })
+            })
+            .collect())
+    }
+
+    async fn download_ci_artifact(
+        &self,
+        owner: &str,
+        repo: &str,
+        artifact_id: u64,
+    ) -> Result<Vec<u8>, PlatformError> {
+        let project_id = self.encode_project(owner, repo);
+        self.api_bytes(&format!(
+            "/projects/{}/jobs/{}/artifacts",
+            project_id, artifact_id
+        ))
+        .await
+    }
+
     async fn get_allowed_merge_methods(
         &self,
         _owner: &str,
@@ -797,6 +1307,20 @@ impl HostingPlatform for GitLabAdapter {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_discussion_is_unresolved() {
+        let discussions: Vec<GitLabDiscussion> = serde_json::from_str(
+            r#"[
+                {"notes": [{"resolvable": false}]},
+                {"notes": [{"resolvable": true, "resolved": true}]},
+                {"notes": [{"resolvable": true, "resolved": false}, {"resolvable": true, "resolved": true}]}
+            ]"#,
+        )
+        .unwrap();
+        let unresolved: Vec<bool> = discussions.iter().map(|d| d.is_unresolved()).collect();
+        assert_eq!(unresolved, vec![false, false, true]);
+    }
+
     #[test]
     fn test_parse_gitlab_ssh_url() {
         let adapter = GitLabAdapter::new(None);
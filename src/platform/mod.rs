@@ -1,17 +1,21 @@
 //! Hosting platform adapters
 //!
-//! Provides a unified interface for GitHub, GitLab, and Azure DevOps.
+//! Provides a unified interface for GitHub, GitLab, and Azure DevOps, plus
+//! clients for the Jira and Linear issue trackers.
 
 pub mod azure;
 pub mod github;
 pub mod gitlab;
+pub mod tracker;
 pub mod traits;
 pub mod types;
 
 pub use traits::HostingPlatform;
 pub use types::{
-    AllowedMergeMethods, CheckState, CheckStatusDetails, MergeMethod, PRBase, PRCreateResult,
-    PRHead, PRReview, PRState, ParsedRepoInfo, PullRequest, StatusCheck, StatusCheckResult,
+    AllowedMergeMethods, BranchProtection, CheckState, CheckStatusDetails, CiArtifact, CiJob,
+    Deployment, MergeMethod, MergeQueueEntry, MergeQueueStatus, MergedPR, PRActivity,
+    PRActivityKind, PRBase, PRCreateResult, PRHead, PRReview, PRState, ParsedRepoInfo, PullRequest,
+    StatusCheck, StatusCheckResult, WorkflowRun,
 };
 
 use crate::core::manifest::PlatformType;
@@ -0,0 +1,306 @@
+//! Issue tracker clients
+//!
+//! Reads ticket states from Jira (REST) or Linear (GraphQL) and moves tickets
+//! through their workflow, for the tickets that branches carry (see
+//! `core::ticket`).
+
+use async_trait::async_trait;
+use serde::Serialize;
+use std::env;
+use std::sync::Arc;
+
+use super::traits::PlatformError;
+use crate::core::manifest::{TrackerKind, TrackerSettings};
+
+const LINEAR_API: &str = "https://api.linear.app/graphql";
+
+/// A ticket and its workflow state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Issue {
+    pub key: String,
+    pub title: String,
+    /// Workflow state name, e.g. "In Progress"
+    pub state: String,
+    pub url: Option<String>,
+}
+
+/// Interface for issue tracker clients
+#[async_trait]
+pub trait IssueTracker: Send + Sync {
+    /// Get a ticket by its key (e.g. `PROJ-123`)
+    async fn get_issue(&self, key: &str) -> Result<Issue, PlatformError>;
+
+    /// Move a ticket to the workflow state named `state` (case-insensitive);
+    /// returns false if it was already there
+    async fn transition_issue(&self, key: &str, state: &str) -> Result<bool, PlatformError>;
+}
+
+/// Get a client for the configured tracker
+pub fn get_tracker(settings: &TrackerSettings) -> Arc<dyn IssueTracker> {
+    match settings.kind {
+        TrackerKind::Jira => Arc::new(JiraTracker {
+            base_url: settings
+                .base_url
+                .as_deref()
+                .unwrap_or_default()
+                .trim_end_matches('/')
+                .to_string(),
+        }),
+        TrackerKind::Linear => Arc::new(LinearTracker {
+            api_url: settings
+                .base_url
+                .clone()
+                .unwrap_or_else(|| LINEAR_API.to_string()),
+        }),
+    }
+}
+
+/// Jira Cloud or Server/Data Center
+pub struct JiraTracker {
+    base_url: String,
+}
+
+impl JiraTracker {
+    /// Basic auth with `JIRA_EMAIL` and `JIRA_API_TOKEN` (Cloud), or the
+    /// token alone as a bearer personal access token (Server/Data Center)
+    fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder, PlatformError> {
+        let token = env::var("JIRA_API_TOKEN").map_err(|_| {
+            PlatformError::AuthError("No Jira token found. Set JIRA_API_TOKEN".to_string())
+        })?;
+        let request = reqwest::Client::new()
+            .request(method, format!("{}/rest/api/2{}", self.base_url, path))
+            .header("Accept", "application/json")
+            .header("User-Agent", "gitgrip");
+        Ok(match env::var("JIRA_EMAIL") {
+            Ok(email) => request.basic_auth(email, Some(token)),
+            Err(_) => request.bearer_auth(token),
+        })
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        key: &str,
+    ) -> Result<reqwest::Response, PlatformError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            reqwest::StatusCode::NOT_FOUND => Err(PlatformError::NotFound(key.to_string())),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                Err(PlatformError::AuthError(format!(
+                    "Jira rejected the token ({})",
+                    response.status()
+                )))
+            }
+            status => Err(PlatformError::ApiError(format!(
+                "Jira API error ({}) for {}",
+                status, key
+            ))),
+        }
+    }
+
+    async fn get_json(&self, path: &str, key: &str) -> Result<serde_json::Value, PlatformError> {
+        self.send(self.request(reqwest::Method::GET, path)?, key)
+            .await?
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl IssueTracker for JiraTracker {
+    async fn get_issue(&self, key: &str) -> Result<Issue, PlatformError> {
+        let body = self
+            .get_json(&format!("/issue/{}?fields=summary,status", key), key)
+            .await?;
+        Ok(Issue {
+            key: body["key"].as_str().unwrap_or(key).to_string(),
+            title: body["fields"]["summary"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            state: body["fields"]["status"]["name"]
+                .as_str()
+                .ok_or_else(|| PlatformError::ParseError(format!("{}: no status", key)))?
+                .to_string(),
+            url: Some(format!("{}/browse/{}", self.base_url, key)),
+        })
+    }
+
+    async fn transition_issue(&self, key: &str, state: &str) -> Result<bool, PlatformError> {
+        if self.get_issue(key).await?.state.eq_ignore_ascii_case(state) {
+            return Ok(false);
+        }
+        let body = self
+            .get_json(&format!("/issue/{}/transitions", key), key)
+            .await?;
+        let transitions = body["transitions"].as_array().cloned().unwrap_or_default();
+        let id = find_jira_transition(&transitions, state).ok_or_else(|| {
+            PlatformError::ApiError(format!("{} can't move to '{}' from its state", key, state))
+        })?;
+        let request = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/issue/{}/transitions", key),
+            )?
+            .json(&serde_json::json!({ "transition": { "id": id } }));
+        self.send(request, key).await?;
+        Ok(true)
+    }
+}
+
+/// The ID of the transition leading to `state`, matched on the target
+/// status or else the transition's own name
+fn find_jira_transition(transitions: &[serde_json::Value], state: &str) -> Option<String> {
+    let matches = |value: &serde_json::Value| {
+        value
+            .as_str()
+            .is_some_and(|name| name.eq_ignore_ascii_case(state))
+    };
+    transitions
+        .iter()
+        .find(|t| matches(&t["to"]["name"]))
+        .or_else(|| transitions.iter().find(|t| matches(&t["name"])))
+        .and_then(|t| t["id"].as_str())
+        .map(str::to_string)
+}
+
+/// Linear
+pub struct LinearTracker {
+    api_url: String,
+}
+
+impl LinearTracker {
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, PlatformError> {
+        let key = env::var("LINEAR_API_KEY").map_err(|_| {
+            PlatformError::AuthError("No Linear API key found. Set LINEAR_API_KEY".to_string())
+        })?;
+        let response = reqwest::Client::new()
+            .post(&self.api_url)
+            .header("Authorization", key)
+            .header("User-Agent", "gitgrip")
+            .json(&serde_json::json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(PlatformError::AuthError(
+                "Linear rejected the API key".to_string(),
+            ));
+        }
+        let mut body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))?;
+        if let Some(message) = body["errors"][0]["message"].as_str() {
+            return Err(PlatformError::ApiError(message.to_string()));
+        }
+        Ok(body["data"].take())
+    }
+
+    /// The issue, with the workflow states of its team
+    async fn fetch(&self, key: &str) -> Result<serde_json::Value, PlatformError> {
+        let data = self
+            .graphql(
+                "query($id: String!) { issue(id: $id) { id identifier title url \
+                 state { name } team { states { nodes { id name } } } } }",
+                serde_json::json!({ "id": key }),
+            )
+            .await?;
+        match &data["issue"] {
+            serde_json::Value::Null => Err(PlatformError::NotFound(key.to_string())),
+            issue => Ok(issue.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl IssueTracker for LinearTracker {
+    async fn get_issue(&self, key: &str) -> Result<Issue, PlatformError> {
+        let issue = self.fetch(key).await?;
+        Ok(Issue {
+            key: issue["identifier"].as_str().unwrap_or(key).to_string(),
+            title: issue["title"].as_str().unwrap_or_default().to_string(),
+            state: issue["state"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            url: issue["url"].as_str().map(str::to_string),
+        })
+    }
+
+    async fn transition_issue(&self, key: &str, state: &str) -> Result<bool, PlatformError> {
+        let issue = self.fetch(key).await?;
+        if issue["state"]["name"]
+            .as_str()
+            .is_some_and(|name| name.eq_ignore_ascii_case(state))
+        {
+            return Ok(false);
+        }
+        let state_id = issue["team"]["states"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|s| {
+                s["name"]
+                    .as_str()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(state))
+            })
+            .and_then(|s| s["id"].as_str())
+            .ok_or_else(|| {
+                PlatformError::ApiError(format!("{}'s team has no '{}' state", key, state))
+            })?;
+        let data = self
+            .graphql(
+                "mutation($id: String!, $stateId: String!) { \
+                 issueUpdate(id: $id, input: { stateId: $stateId }) { success } }",
+                serde_json::json!({ "id": issue["id"], "stateId": state_id }),
+            )
+            .await?;
+        if data["issueUpdate"]["success"].as_bool() != Some(true) {
+            return Err(PlatformError::ApiError(format!(
+                "Linear didn't update {}",
+                key
+            )));
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_jira_transition() {
+        let transitions: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                {"id": "11", "name": "Start work", "to": {"name": "In Progress"}},
+                {"id": "21", "name": "Review", "to": {"name": "In Review"}},
+                {"id": "31", "name": "Close", "to": {"name": "Done"}}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            find_jira_transition(&transitions, "done"),
+            Some("31".to_string())
+        );
+        assert_eq!(
+            find_jira_transition(&transitions, "Review"),
+            Some("21".to_string())
+        );
+        assert_eq!(find_jira_transition(&transitions, "Won't Do"), None);
+    }
+}
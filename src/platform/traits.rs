@@ -1,18 +1,20 @@
 //! Hosting platform trait definition
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 use super::types::*;
 use crate::core::manifest::PlatformType;
+use crate::util::redact::redact;
 
 /// Errors that can occur during platform operations
 #[derive(Error, Debug)]
 pub enum PlatformError {
-    #[error("Authentication failed: {0}")]
+    #[error("Authentication failed: {}", redact(.0))]
     AuthError(String),
 
-    #[error("API error: {0}")]
+    #[error("API error: {}", redact(.0))]
     ApiError(String),
 
     #[error("Not found: {0}")]
@@ -21,7 +23,7 @@ pub enum PlatformError {
     #[error("Rate limited")]
     RateLimited,
 
-    #[error("Network error: {0}")]
+    #[error("Network error: {}", redact(.0))]
     NetworkError(String),
 
     #[error("Parse error: {0}")]
@@ -167,6 +169,262 @@ pub trait HostingPlatform: Send + Sync {
         ))
     }
 
+    /// Protect a branch: changes must go through a reviewed PR
+    ///
+    /// Optional operation; platforms without branch protection return an error.
+    async fn protect_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<(), PlatformError> {
+        let _ = (owner, repo, branch);
+        Err(PlatformError::ApiError(
+            "Branch protection not supported on this platform".to_string(),
+        ))
+    }
+
+    /// CI jobs that ran (or are running) for the latest commit of a branch
+    ///
+    /// Optional operation; platforms without job APIs return an error.
+    async fn list_ci_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<CiJob>, PlatformError> {
+        let _ = (owner, repo, branch);
+        Err(PlatformError::ApiError(
+            "CI job logs not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Full log of a CI job from `list_ci_jobs`
+    async fn get_ci_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: u64,
+    ) -> Result<String, PlatformError> {
+        let _ = (owner, repo, job_id);
+        Err(PlatformError::ApiError(
+            "CI job logs not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Artifacts of the newest successful CI run on `branch`
+    ///
+    /// On GitHub `workflow` narrows the runs to one workflow file or ID;
+    /// GitLab uses the newest successful pipeline. Empty when no run has
+    /// succeeded. Optional operation; platforms without CI artifacts return
+    /// an error.
+    async fn list_ci_artifacts(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        workflow: Option<&str>,
+    ) -> Result<Vec<CiArtifact>, PlatformError> {
+        let _ = (owner, repo, branch, workflow);
+        Err(PlatformError::ApiError(
+            "CI artifacts not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Download an artifact from `list_ci_artifacts` as a zip archive
+    async fn download_ci_artifact(
+        &self,
+        owner: &str,
+        repo: &str,
+        artifact_id: u64,
+    ) -> Result<Vec<u8>, PlatformError> {
+        let _ = (owner, repo, artifact_id);
+        Err(PlatformError::ApiError(
+            "CI artifacts not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Re-run the failed jobs of a branch's latest CI runs, returning how
+    /// many workflow runs or pipelines were restarted
+    ///
+    /// Optional operation; platforms without job APIs return an error.
+    async fn rerun_failed_ci_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<usize, PlatformError> {
+        let _ = (owner, repo, branch);
+        Err(PlatformError::ApiError(
+            "Re-running CI jobs not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Start a workflow on `git_ref` with `inputs`, returning the run it spawned
+    ///
+    /// On GitHub `workflow` is a workflow file name or ID; GitLab has no named
+    /// workflows, so it starts a pipeline with the inputs as variables.
+    /// Optional operation; platforms without workflow dispatch return an error.
+    async fn dispatch_workflow(
+        &self,
+        owner: &str,
+        repo: &str,
+        workflow: &str,
+        git_ref: &str,
+        inputs: &[(String, String)],
+    ) -> Result<WorkflowRun, PlatformError> {
+        let _ = (owner, repo, workflow, git_ref, inputs);
+        Err(PlatformError::ApiError(
+            "Workflow dispatch not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Current state of a run from `dispatch_workflow`
+    async fn get_workflow_run(
+        &self,
+        owner: &str,
+        repo: &str,
+        run_id: u64,
+    ) -> Result<WorkflowRun, PlatformError> {
+        let _ = (owner, repo, run_id);
+        Err(PlatformError::ApiError(
+            "Workflow runs not supported on this platform".to_string(),
+        ))
+    }
+
+    /// The live deployment of each environment, sorted by environment
+    ///
+    /// Optional operation; platforms without deployments return an error.
+    async fn list_deployments(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<Deployment>, PlatformError> {
+        let _ = (owner, repo);
+        Err(PlatformError::ApiError(
+            "Deployments not supported on this platform".to_string(),
+        ))
+    }
+
+    /// PRs the authenticated user opened, had merged, or reviewed since `since`
+    ///
+    /// Optional operation; platforms without it return an error.
+    async fn list_pr_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PRActivity>, PlatformError> {
+        let _ = (owner, repo, since);
+        Err(PlatformError::ApiError(
+            "PR activity not supported on this platform".to_string(),
+        ))
+    }
+
+    /// PRs merged since `since`, newest first
+    ///
+    /// Optional operation; platforms without it return an error.
+    async fn list_merged_pull_requests(
+        &self,
+        owner: &str,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<MergedPR>, PlatformError> {
+        let _ = (owner, repo, since);
+        Err(PlatformError::ApiError(
+            "Listing merged PRs not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Whether a PR's base branch uses a merge queue, and the PR's place in it
+    ///
+    /// Platforms without merge queues report the queue as disabled, so callers
+    /// fall back to merging directly.
+    async fn get_merge_queue_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<MergeQueueStatus, PlatformError> {
+        let _ = (owner, repo, pull_number);
+        Ok(MergeQueueStatus::default())
+    }
+
+    /// How many of a PR's review threads are still unresolved
+    ///
+    /// Platforms that don't track thread resolution report `None`.
+    async fn get_unresolved_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<Option<usize>, PlatformError> {
+        let _ = (owner, repo, pull_number);
+        Ok(None)
+    }
+
+    /// Add a PR to its base branch's merge queue
+    ///
+    /// Optional operation; platforms without merge queues return an error.
+    async fn enqueue_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+    ) -> Result<MergeQueueEntry, PlatformError> {
+        let _ = (owner, repo, pull_number);
+        Err(PlatformError::ApiError(
+            "Merge queues not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Protection rules on a branch, or `None` if it is unprotected
+    ///
+    /// Platforms that don't expose branch protection report `None`.
+    async fn get_branch_protection(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<BranchProtection>, PlatformError> {
+        let _ = (owner, repo, branch);
+        Ok(None)
+    }
+
+    /// Rename a branch on the remote, carrying its open PRs along
+    ///
+    /// Optional operation; platforms that can't rename branches return an
+    /// error, and callers push the new branch themselves.
+    async fn rename_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<(), PlatformError> {
+        let _ = (owner, repo, old, new);
+        Err(PlatformError::ApiError(
+            "Renaming branches not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Request reviews from users and teams (team slugs, without the org)
+    ///
+    /// Optional operation; platforms without reviewer requests return an error.
+    async fn request_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        pull_number: u64,
+        users: &[String],
+        teams: &[String],
+    ) -> Result<(), PlatformError> {
+        let _ = (owner, repo, pull_number, users, teams);
+        Err(PlatformError::ApiError(
+            "Requesting reviewers not supported on this platform".to_string(),
+        ))
+    }
+
     /// Generate HTML comment for linked PR tracking
     fn generate_linked_pr_comment(&self, links: &[LinkedPRRef]) -> String {
         if links.is_empty() {
@@ -79,7 +79,8 @@ pub struct PRCreateOptions {
 }
 
 /// Merge method for PRs
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MergeMethod {
     #[default]
     Merge,
@@ -162,6 +163,91 @@ pub struct StatusCheckResult {
     pub statuses: Vec<StatusCheck>,
 }
 
+/// A CI job on a ref (a GitHub Actions job or GitLab CI job)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiJob {
+    /// Platform job ID, for fetching its log
+    pub id: u64,
+    /// Job name as shown in the platform UI
+    pub name: String,
+    /// Job state
+    pub state: CheckState,
+    /// Link to the job in the platform UI
+    pub url: Option<String>,
+}
+
+/// A CI workflow run (a GitHub Actions run or GitLab pipeline)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    /// Platform run ID
+    pub id: u64,
+    /// Run state; pending until it finishes
+    pub state: CheckState,
+    /// Link to the run in the platform UI
+    pub url: Option<String>,
+}
+
+/// A build artifact of a CI run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiArtifact {
+    /// Platform artifact ID (the job ID on GitLab)
+    pub id: u64,
+    pub name: String,
+    /// Workflow run or pipeline that produced it
+    pub run_id: u64,
+    /// Size of the download in bytes, if the platform reports it
+    pub size: Option<u64>,
+    /// Hex SHA-256 of the download, if the platform reports it
+    pub sha256: Option<String>,
+}
+
+/// What is deployed to one environment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deployment {
+    /// Environment name, e.g. `production`
+    pub environment: String,
+    /// Deployed commit SHA
+    pub sha: String,
+    /// Branch or tag the deployment was made from
+    pub git_ref: String,
+    /// Platform deployment state, e.g. `success` or `failure`
+    pub state: String,
+    /// When the deployment was created (RFC 3339)
+    pub created_at: Option<String>,
+    /// Link to the environment or deployment
+    pub url: Option<String>,
+}
+
+/// What the authenticated user did on a PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PRActivityKind {
+    Opened,
+    Merged,
+    Reviewed,
+}
+
+/// A PR the authenticated user opened, had merged, or reviewed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PRActivity {
+    pub kind: PRActivityKind,
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    /// When it happened (RFC 3339)
+    pub at: String,
+}
+
+/// A merged PR, for cycle-time stats
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedPR {
+    pub number: u64,
+    /// When it was opened (RFC 3339)
+    pub created_at: String,
+    /// When it was merged (RFC 3339)
+    pub merged_at: String,
+}
+
 /// Detailed check status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckStatusDetails {
@@ -200,6 +286,52 @@ impl Default for AllowedMergeMethods {
     }
 }
 
+/// A PR's place in its base branch's merge queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeQueueEntry {
+    /// 1-based position in the queue
+    pub position: u32,
+    /// Queue state as reported by the platform (e.g. `QUEUED`, `AWAITING_CHECKS`)
+    pub state: String,
+    /// Estimated seconds until the PR merges, if known
+    pub estimated_secs: Option<u64>,
+}
+
+impl std::fmt::Display for MergeQueueEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {}",
+            self.position,
+            self.state.to_lowercase().replace('_', " ")
+        )?;
+        if let Some(secs) = self.estimated_secs {
+            write!(f, " (~{}m)", secs.div_ceil(60))?;
+        }
+        Ok(())
+    }
+}
+
+/// Merge queue status of a PR
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeQueueStatus {
+    /// Whether the PR's base branch merges through a queue
+    pub enabled: bool,
+    /// The PR's queue entry, if it is queued
+    pub entry: Option<MergeQueueEntry>,
+}
+
+/// Protection rules on a branch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchProtection {
+    /// Status check contexts that must pass before merging
+    pub required_checks: Vec<String>,
+    /// Approving reviews required before merging
+    pub required_approvals: u32,
+    /// Whether the rules also apply to administrators
+    pub enforce_admins: bool,
+}
+
 /// Parsed repository information from URL
 #[derive(Debug, Clone)]
 pub struct ParsedRepoInfo {
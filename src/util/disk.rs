@@ -0,0 +1,97 @@
+//! Disk usage helpers
+
+use std::path::Path;
+
+/// Total size in bytes of all files under `path` (symlinks are not followed)
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(ft) if ft.is_dir() => dir_size(&entry.path()),
+            Ok(ft) if ft.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Human-readable size (e.g. `1.5 MiB`)
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Parse a size like `500`, `200KB`, `50M`, or `1GiB` (bare numbers are
+/// bytes; units are binary and case-insensitive)
+pub fn parse_bytes(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (count, unit) = value.split_at(split);
+    let count: u64 = count
+        .parse()
+        .map_err(|_| format!("Invalid size '{}' (use e.g. 500KB, 50MB, 1GB)", value))?;
+    let shift = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        _ => {
+            return Err(format!(
+                "Invalid size unit '{}' (expected B, KB, MB, or GB)",
+                unit
+            ))
+        }
+    };
+    count
+        .checked_mul(1 << shift)
+        .ok_or_else(|| format!("Size '{}' is too large", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("500"), Ok(500));
+        assert_eq!(parse_bytes("50MB"), Ok(50 * 1024 * 1024));
+        assert_eq!(parse_bytes("2 gib"), Ok(2 * 1024 * 1024 * 1024));
+        assert!(parse_bytes("MB").is_err());
+        assert!(parse_bytes("5TB").is_err());
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("a"), vec![0u8; 100]).unwrap();
+        std::fs::create_dir(temp.path().join("sub")).unwrap();
+        std::fs::write(temp.path().join("sub/b"), vec![0u8; 50]).unwrap();
+        assert_eq!(dir_size(temp.path()), 150);
+    }
+}
@@ -0,0 +1,107 @@
+//! Path glob matching
+//!
+//! Globs use `/` as separator: `*` and `?` stay within a path segment, `**`
+//! crosses segments. A glob without `/` matches a file name at any depth.
+
+use regex::Regex;
+
+/// What a glob's last segment may be followed by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobTail {
+    /// Nothing: the glob matches the whole path
+    Exact,
+    /// Optionally a `/` and more, so a directory glob covers its contents
+    OrBeneath,
+    /// A `/` and more: only paths inside the matched directory
+    Beneath,
+}
+
+/// A compiled path glob
+#[derive(Debug, Clone)]
+pub struct PathGlob {
+    regex: Regex,
+}
+
+impl PathGlob {
+    /// Compile a glob such as `proto/**` or `*.proto`
+    pub fn new(glob: &str) -> Result<Self, regex::Error> {
+        let body = glob.trim_start_matches("./").trim_start_matches('/');
+        Self::with_options(body, body.contains('/'), GlobTail::Exact)
+    }
+
+    /// Compile `body` with explicit anchoring and tail rules
+    ///
+    /// An unanchored glob may match below any directory. Used for pattern
+    /// languages such as CODEOWNERS that decide these differently.
+    pub fn with_options(body: &str, anchored: bool, tail: GlobTail) -> Result<Self, regex::Error> {
+        let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push_str(match tail {
+            GlobTail::Exact => "$",
+            GlobTail::OrBeneath => "(?:/.*)?$",
+            GlobTail::Beneath => "/.*$",
+        });
+
+        Ok(Self {
+            regex: Regex::new(&regex)?,
+        })
+    }
+
+    /// Whether a repo-relative path matches
+    pub fn matches(&self, path: &str) -> bool {
+        self.regex.is_match(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_star() {
+        let glob = PathGlob::new("proto/**").unwrap();
+        assert!(glob.matches("proto/api/v1/service.proto"));
+        assert!(!glob.matches("src/proto/x.proto"));
+    }
+
+    #[test]
+    fn test_single_star_stays_in_segment() {
+        let glob = PathGlob::new("src/*.rs").unwrap();
+        assert!(glob.matches("src/lib.rs"));
+        assert!(!glob.matches("src/cli/mod.rs"));
+    }
+
+    #[test]
+    fn test_tail_options() {
+        let beneath = PathGlob::with_options("docs", true, GlobTail::OrBeneath).unwrap();
+        assert!(beneath.matches("docs"));
+        assert!(beneath.matches("docs/guide.md"));
+        let inside = PathGlob::with_options("docs", true, GlobTail::Beneath).unwrap();
+        assert!(!inside.matches("docs"));
+        assert!(inside.matches("docs/guide.md"));
+    }
+
+    #[test]
+    fn test_basename_glob_matches_any_depth() {
+        let glob = PathGlob::new("*.proto").unwrap();
+        assert!(glob.matches("service.proto"));
+        assert!(glob.matches("api/v1/service.proto"));
+        assert!(!glob.matches("api/v1/service.rs"));
+    }
+}
@@ -1,7 +1,18 @@
 //! Utility functions and helpers
 
+pub mod disk;
+pub mod glob;
+pub mod notify;
+pub mod pool;
+pub mod priority;
+pub mod process;
+pub mod profile;
+pub mod redact;
 pub mod retry;
-pub mod timing;
+pub mod shell;
 
+pub use disk::{dir_size, format_bytes};
+pub use pool::{concurrent_map, max_workers, parallel_map};
+pub use redact::redact;
 pub use retry::{retry_with_backoff, RetryOptions};
-pub use timing::{Timer, TimingReport};
+pub use shell::{quote_arg, shell_command};
@@ -0,0 +1,180 @@
+//! Completion notifications for long-running operations
+//!
+//! Configured under `settings.notifications` in the manifest. Desktop
+//! notifications need the `notifications` cargo feature; webhooks (generic
+//! JSON or Slack incoming webhooks) are always available.
+
+use crate::cli::output::Output;
+use crate::core::manifest::NotificationSettings;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long to wait for a webhook before giving up
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The outcome of an operation, as sent to webhooks
+#[derive(Debug, Clone, Serialize)]
+pub struct Completion {
+    /// Operation name, e.g. `sync` or `campaign run`
+    pub operation: String,
+    pub success: bool,
+    /// One-line summary, e.g. `5 synced, 1 failed`
+    pub summary: String,
+    pub duration_secs: f64,
+    /// Workspace root the operation ran in
+    pub workspace: String,
+}
+
+impl Completion {
+    /// Build a completion from an operation's result
+    ///
+    /// `describe` turns a successful result into `(success, summary)`, since an
+    /// operation can return `Ok` while some repos failed.
+    pub fn from_result<T>(
+        operation: &str,
+        workspace_root: &Path,
+        duration: Duration,
+        result: &anyhow::Result<T>,
+        describe: impl FnOnce(&T) -> (bool, String),
+    ) -> Self {
+        let (success, summary) = match result {
+            Ok(value) => describe(value),
+            Err(e) => (false, e.to_string()),
+        };
+        Self {
+            operation: operation.to_string(),
+            success,
+            summary,
+            duration_secs: duration.as_secs_f64(),
+            workspace: workspace_root.display().to_string(),
+        }
+    }
+
+    fn title(&self) -> String {
+        let outcome = if self.success { "finished" } else { "failed" };
+        format!(
+            "gr {} {} in {}",
+            self.operation,
+            outcome,
+            format_duration(self.duration_secs)
+        )
+    }
+
+    fn slack_text(&self) -> String {
+        let icon = if self.success {
+            ":white_check_mark:"
+        } else {
+            ":x:"
+        };
+        format!(
+            "{} *{}*\n{}\n_{}_",
+            icon,
+            self.title(),
+            self.summary,
+            self.workspace
+        )
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
+/// Send `completion` to every configured target
+///
+/// Delivery failures are reported as warnings and never fail the operation.
+pub async fn notify(settings: &NotificationSettings, completion: &Completion) {
+    if settings.desktop {
+        show_desktop(completion);
+    }
+
+    if settings.webhook.is_none() && settings.slack.is_none() {
+        return;
+    }
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            Output::warning(&format!("Could not send notification: {}", e));
+            return;
+        }
+    };
+
+    let targets = [
+        (
+            settings.webhook.as_deref(),
+            serde_json::to_value(completion).unwrap_or_default(),
+        ),
+        (
+            settings.slack.as_deref(),
+            serde_json::json!({ "text": completion.slack_text() }),
+        ),
+    ];
+    for (url, payload) in targets {
+        let Some(url) = url else {
+            continue;
+        };
+        let result = client
+            .post(url)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            Output::warning(&format!("Notification webhook failed: {}", e));
+        }
+    }
+}
+
+#[cfg(feature = "notifications")]
+fn show_desktop(completion: &Completion) {
+    let result = notify_rust::Notification::new()
+        .appname("gitgrip")
+        .summary(&completion.title())
+        .body(&completion.summary)
+        .show();
+    if let Err(e) = result {
+        Output::warning(&format!("Desktop notification failed: {}", e));
+    }
+}
+
+#[cfg(not(feature = "notifications"))]
+fn show_desktop(_completion: &Completion) {
+    Output::warning("Desktop notifications need gitgrip built with the `notifications` feature");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_from_result() {
+        let root = Path::new("/work/space");
+        let ok: anyhow::Result<usize> = Ok(1);
+        let completion =
+            Completion::from_result("sync", root, Duration::from_secs(75), &ok, |failed| {
+                (*failed == 0, format!("4 synced, {} failed", failed))
+            });
+        assert!(!completion.success);
+        assert_eq!(completion.title(), "gr sync failed in 1m 15s");
+        assert!(completion
+            .slack_text()
+            .starts_with(":x: *gr sync failed in 1m 15s*\n4 synced, 1 failed"));
+
+        let err: anyhow::Result<usize> = Err(anyhow::anyhow!("no network"));
+        let completion = Completion::from_result(
+            "sync",
+            root,
+            Duration::from_secs(3),
+            &err,
+            |_| unreachable!(),
+        );
+        assert_eq!(completion.summary, "no network");
+        assert_eq!(completion.title(), "gr sync failed in 3s");
+    }
+}
@@ -0,0 +1,123 @@
+//! Bounded concurrency for per-repo work
+//!
+//! Blocking work (git) runs on a small thread pool via [`parallel_map`];
+//! network-bound work (platform APIs) runs as futures via [`concurrent_map`].
+
+use futures::stream::{self, StreamExt};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Upper bound on workers, so huge workspaces don't spawn a git process per repo
+const MAX_WORKERS: usize = 16;
+
+/// Number of workers to use: one per CPU, capped at [`MAX_WORKERS`]
+pub fn max_workers() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(MAX_WORKERS)
+}
+
+/// Apply `f` to every item on a pool of at most `workers` threads
+///
+/// Results are returned in the same order as `items`.
+pub fn parallel_map<T, R, F>(items: &[T], workers: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = workers.clamp(1, items.len().max(1));
+    if workers == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let slots: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let result = f(item);
+                *slots[i].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    slots
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .unwrap()
+                .expect("every slot is filled once the scope ends")
+        })
+        .collect()
+}
+
+/// Run `f` on every item with at most `limit` futures in flight
+///
+/// Results are returned in the same order as `items`.
+pub async fn concurrent_map<'a, T, R, F, Fut>(items: &'a [T], limit: usize, f: F) -> Vec<R>
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: Future<Output = R>,
+{
+    stream::iter(items)
+        .map(f)
+        .buffered(limit.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parallel_map_preserves_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let results = parallel_map(&items, 8, |n| {
+            // Finish out of order
+            thread::sleep(Duration::from_millis(50 - n));
+            n * 2
+        });
+        assert_eq!(results, (0..50).map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_parallel_map_empty_and_single_worker() {
+        let empty: Vec<u32> = Vec::new();
+        assert!(parallel_map(&empty, 4, |n| *n).is_empty());
+        assert_eq!(parallel_map(&[1, 2, 3], 1, |n| n + 1), vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_map_caps_in_flight() {
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items: Vec<u64> = (0..20).collect();
+
+        let results = concurrent_map(&items, 3, |n| {
+            let in_flight = &in_flight;
+            let peak = &peak;
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20 - n)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                *n
+            }
+        })
+        .await;
+
+        assert_eq!(results, items);
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+}
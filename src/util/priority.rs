@@ -0,0 +1,122 @@
+//! CPU and I/O priority for heavy commands
+//!
+//! Lowers the priority of the calling thread. Worker threads and child
+//! processes started from it afterwards inherit the lower priority, so a
+//! large forall, gc, or sync doesn't starve the developer's editor.
+
+use std::io;
+
+/// Lower the CPU priority to niceness `nice` (0-19)
+///
+/// Already running at a higher niceness is left alone, since raising
+/// priority again needs privileges.
+pub fn set_nice(nice: i32) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        // getpriority can legitimately return -1, so errors are told apart by errno
+        clear_errno();
+        let current = unsafe { libc::getpriority(libc::PRIO_PROCESS as _, 0) };
+        if current == -1 && io::Error::last_os_error().raw_os_error().unwrap_or(0) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if current >= nice {
+            return Ok(());
+        }
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = nice;
+        Err(unsupported("--nice"))
+    }
+}
+
+/// Only do disk I/O when nothing else needs the disk (Linux), or throttle it
+/// (macOS)
+pub fn set_io_idle() -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+        const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+        const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0,
+                IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        const IOPOL_TYPE_DISK: libc::c_int = 0;
+        const IOPOL_SCOPE_PROCESS: libc::c_int = 0;
+        const IOPOL_THROTTLE: libc::c_int = 3;
+        extern "C" {
+            fn setiopolicy_np(
+                iotype: libc::c_int,
+                scope: libc::c_int,
+                policy: libc::c_int,
+            ) -> libc::c_int;
+        }
+        if unsafe { setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_PROCESS, IOPOL_THROTTLE) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        Err(unsupported("--io-idle"))
+    }
+}
+
+#[cfg(unix)]
+fn clear_errno() {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe {
+        *libc::__errno_location() = 0;
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    unsafe {
+        *libc::__error() = 0;
+    }
+}
+
+#[allow(dead_code)]
+fn unsupported(option: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{} isn't supported on this platform", option),
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_child_processes_inherit_nice() {
+        // A fresh thread, so the test runner's other threads keep their priority
+        std::thread::spawn(|| {
+            set_nice(7).unwrap();
+            let output = Command::new("nice").output().unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "7");
+
+            // Never raises priority back
+            set_nice(3).unwrap();
+            let output = Command::new("nice").output().unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "7");
+        })
+        .join()
+        .unwrap();
+    }
+}
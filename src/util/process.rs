@@ -0,0 +1,240 @@
+//! Child processes with timeouts and Ctrl-C cancellation
+//!
+//! A command spawned with [`spawn_in_group`] gets its own process group, so a
+//! timeout or Ctrl-C kills everything it started (a shell's children
+//! included), not just the shell. Such a group is in the background, so it
+//! can't read the terminal: its stdin is null, and a command that prompts
+//! sees end of input instead of hanging until the timeout. Ctrl-C sets a
+//! process-wide flag that long-running loops check between repos; a second
+//! Ctrl-C exits at once.
+
+use crate::util::profile;
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How often a running child is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long a child gets to exit after SIGTERM before it's killed
+const TERM_GRACE: Duration = Duration::from_secs(2);
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// How a child process ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Exited(ExitStatus),
+    /// Killed after running longer than its timeout
+    TimedOut,
+    /// Killed because of Ctrl-C
+    Cancelled,
+}
+
+/// Whether Ctrl-C has been pressed
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Handle Ctrl-C by flagging cancellation instead of exiting
+///
+/// Must be called from within the tokio runtime.
+pub fn cancel_on_ctrl_c() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if CANCELLED.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+            eprintln!("\nCancelling... (press Ctrl-C again to quit immediately)");
+        }
+    });
+}
+
+/// Spawn `command` as the leader of a new process group, with stdin null
+///
+/// A background group reading the terminal would be stopped by SIGTTIN.
+pub fn spawn_in_group(command: &mut Command) -> std::io::Result<Child> {
+    command.stdin(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command.spawn()
+}
+
+/// Wait for `child`, killing it (and its process group) on timeout or Ctrl-C
+pub fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> std::io::Result<Outcome> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Outcome::Exited(status));
+        }
+        if is_cancelled() {
+            kill_group(child);
+            return Ok(Outcome::Cancelled);
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            kill_group(child);
+            return Ok(Outcome::TimedOut);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Run `command` in its own process group, capturing its output
+///
+/// Like [`Command::output`], stdin is null. Output written before a timeout
+/// or cancellation is kept.
+pub fn output_with_timeout(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> std::io::Result<(Outcome, Vec<u8>, Vec<u8>)> {
+    let _span = profile::command_span(command);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = spawn_in_group(command)?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let outcome = wait_with_timeout(&mut child, timeout)?;
+    // A killed command may have left a grandchild holding the pipe open
+    let patience = match outcome {
+        Outcome::Exited(_) => None,
+        _ => Some(Duration::from_secs(1)),
+    };
+    let collect = |rx: mpsc::Receiver<Vec<u8>>| match patience {
+        None => rx.recv().unwrap_or_default(),
+        Some(patience) => rx.recv_timeout(patience).unwrap_or_default(),
+    };
+    Ok((outcome, collect(stdout), collect(stderr)))
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        let _ = tx.send(buffer);
+    });
+    rx
+}
+
+/// Stop `child` and anything else in its process group: SIGTERM first, then
+/// SIGKILL if it hasn't exited after a grace period
+fn kill_group(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let group = format!("-{}", child.id());
+        let signal = |name: &str| {
+            Command::new("kill")
+                .args([name, "--", &group])
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        if signal("-TERM") {
+            let deadline = Instant::now() + TERM_GRACE;
+            while Instant::now() < deadline {
+                if let Ok(Some(_)) = child.try_wait() {
+                    // The leader is gone; make sure the rest of the group is too
+                    signal("-KILL");
+                    return;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            signal("-KILL");
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Parse a duration like `90`, `30s`, `5m`, `1h`, or `500ms` (bare numbers are seconds)
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (count, unit) = value.split_at(split);
+    let invalid = || format!("Invalid duration '{}' (use e.g. 30s, 5m, 1h)", value);
+    let count: u64 = count.parse().map_err(|_| invalid())?;
+    let secs = |scale: u64| {
+        count
+            .checked_mul(scale)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid)
+    };
+    match unit {
+        "ms" => Ok(Duration::from_millis(count)),
+        "" | "s" => secs(1),
+        "m" => secs(60),
+        "h" => secs(3600),
+        _ => Err(format!(
+            "Invalid duration unit '{}' (expected ms, s, m, or h)",
+            unit
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("99999999999999999h").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_process_group() {
+        // The shell's background child must die with it, or the pipe would
+        // stay open and the output never finish
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo started; sleep 30 & wait"]);
+        let started = Instant::now();
+        let (outcome, stdout, _) =
+            output_with_timeout(&mut command, Some(Duration::from_millis(200))).unwrap();
+        assert_eq!(outcome, Outcome::TimedOut);
+        assert_eq!(String::from_utf8_lossy(&stdout).trim(), "started");
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo done"]);
+        let (outcome, stdout, _) = output_with_timeout(&mut command, None).unwrap();
+        assert!(matches!(outcome, Outcome::Exited(status) if status.success()));
+        assert_eq!(String::from_utf8_lossy(&stdout).trim(), "done");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_group_child_does_not_read_terminal() {
+        // A prompt gets end of input instead of stopping on SIGTTIN
+        let mut command = Command::new("sh");
+        command.args(["-c", "read answer || echo no input"]);
+        let mut child = spawn_in_group(command.stdout(Stdio::piped())).unwrap();
+        let outcome = wait_with_timeout(&mut child, Some(Duration::from_secs(5))).unwrap();
+        assert!(matches!(outcome, Outcome::Exited(status) if status.success()));
+        let mut stdout = String::new();
+        child
+            .stdout
+            .take()
+            .unwrap()
+            .read_to_string(&mut stdout)
+            .unwrap();
+        assert_eq!(stdout.trim(), "no input");
+    }
+}
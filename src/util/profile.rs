@@ -0,0 +1,446 @@
+//! Built-in profiler behind `--timings`
+//!
+//! Code that opens repos and walks their objects, scans status, talks to a
+//! remote, or runs a process wraps the work in a [`span`] naming its
+//! [`Phase`] and repo; while profiling is off a span costs one atomic load.
+//! [`finish`] collects the spans into a [`Report`]: a per-repo, per-phase
+//! summary, and a Chrome trace event file whose nested spans show as a flame
+//! graph in Perfetto, speedscope, or `chrome://tracing`.
+
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Git subcommands that talk to a remote
+const NETWORK_COMMANDS: &[&str] = &["clone", "fetch", "ls-remote", "pull", "push"];
+
+/// Where time goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    /// Opening repos and reading objects through libgit2
+    Git,
+    /// Working tree status scans
+    Status,
+    /// Fetches, pushes, clones, and other remote round trips
+    Network,
+    /// Other child processes
+    Process,
+}
+
+impl Phase {
+    const ALL: [Phase; 4] = [Phase::Git, Phase::Status, Phase::Network, Phase::Process];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Git => "git",
+            Phase::Status => "status",
+            Phase::Network => "network",
+            Phase::Process => "process",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Record {
+    phase: Phase,
+    name: String,
+    repo: Option<PathBuf>,
+    thread: u64,
+    /// Since profiling started
+    start: Duration,
+    duration: Duration,
+}
+
+struct Profiler {
+    /// The command line being profiled
+    command: String,
+    /// Workspace root, so repos can be shown by their workspace path
+    root: Option<PathBuf>,
+    started: Instant,
+    main_thread: u64,
+    records: Vec<Record>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILER: Lazy<Mutex<Option<Profiler>>> = Lazy::new(|| Mutex::new(None));
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+fn thread_id() -> u64 {
+    THREAD.with(|id| *id)
+}
+
+/// Start recording spans for `command`
+pub fn enable(command: &str, root: Option<PathBuf>) {
+    *PROFILER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Profiler {
+        command: command.to_string(),
+        root,
+        started: Instant::now(),
+        main_thread: thread_id(),
+        records: Vec::new(),
+    });
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Whether `--timings` is on
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Stop recording and return what was recorded, if profiling was on
+pub fn finish() -> Option<Report> {
+    ENABLED.store(false, Ordering::SeqCst);
+    let profiler = PROFILER.lock().unwrap_or_else(|e| e.into_inner()).take()?;
+    Some(Report {
+        command: profiler.command,
+        root: profiler.root,
+        total: profiler.started.elapsed(),
+        main_thread: profiler.main_thread,
+        records: profiler.records,
+    })
+}
+
+/// Time until the returned guard drops
+///
+/// `name` is only called when profiling is on, so it can format freely.
+pub fn span<N: Into<String>>(phase: Phase, name: impl FnOnce() -> N, repo: Option<&Path>) -> Span {
+    if !is_enabled() {
+        return Span(None);
+    }
+    Span(Some(OpenSpan {
+        phase,
+        name: name().into(),
+        repo: repo.map(Path::to_path_buf),
+        started: Instant::now(),
+    }))
+}
+
+/// A running span, recorded when dropped
+#[must_use = "a span measures until it is dropped"]
+pub struct Span(Option<OpenSpan>);
+
+struct OpenSpan {
+    phase: Phase,
+    name: String,
+    repo: Option<PathBuf>,
+    started: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(open) = self.0.take() else {
+            return;
+        };
+        let duration = open.started.elapsed();
+        let mut profiler = PROFILER.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(profiler) = profiler.as_mut() {
+            profiler.records.push(Record {
+                phase: open.phase,
+                name: open.name,
+                repo: open.repo,
+                thread: thread_id(),
+                start: open.started.saturating_duration_since(profiler.started),
+                duration,
+            });
+        }
+    }
+}
+
+/// A span for running `command`: git's remote subcommands count as network,
+/// `git status` as status, anything else as a process
+pub fn command_span(command: &Command) -> Span {
+    if !is_enabled() {
+        return Span(None);
+    }
+    let program = Path::new(command.get_program())
+        .file_name()
+        .unwrap_or(command.get_program())
+        .to_string_lossy()
+        .to_string();
+    let (phase, name) = if program == "git" {
+        let subcommand = git_subcommand(command.get_args()).unwrap_or_default();
+        let phase = match subcommand.as_str() {
+            sub if NETWORK_COMMANDS.contains(&sub) => Phase::Network,
+            "status" => Phase::Status,
+            _ => Phase::Process,
+        };
+        (phase, format!("git {}", subcommand).trim_end().to_string())
+    } else {
+        (Phase::Process, program)
+    };
+    span(phase, || name, command.get_current_dir())
+}
+
+/// The subcommand in git's arguments, past `-C <dir>` and `-c <key=value>`
+fn git_subcommand<'a>(mut args: impl Iterator<Item = &'a OsStr>) -> Option<String> {
+    while let Some(arg) = args.next() {
+        match arg.to_str()? {
+            "-C" | "-c" => {
+                args.next();
+            }
+            arg if arg.starts_with('-') => {}
+            arg => return Some(arg.to_string()),
+        }
+    }
+    None
+}
+
+/// [`Command::output`], recorded as a span when profiling
+pub trait ProfiledCommand {
+    fn timed_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl ProfiledCommand for Command {
+    fn timed_output(&mut self) -> std::io::Result<Output> {
+        let _span = command_span(self);
+        self.output()
+    }
+}
+
+/// Time spent in one phase
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTotal {
+    pub calls: usize,
+    /// Excluding time in spans nested inside
+    pub time: Duration,
+}
+
+/// Spans recorded for one command
+#[derive(Debug)]
+pub struct Report {
+    command: String,
+    root: Option<PathBuf>,
+    total: Duration,
+    main_thread: u64,
+    records: Vec<Record>,
+}
+
+impl Report {
+    /// Per repo (workspace path, or `(workspace)` for work outside repos),
+    /// time per phase, sorted by total time descending
+    pub fn by_repo(&self) -> Vec<(String, [PhaseTotal; 4])> {
+        let self_times = self.self_times();
+        let mut repos: Vec<(String, [PhaseTotal; 4])> = Vec::new();
+        for (record, time) in self.records.iter().zip(self_times) {
+            let label = self.repo_label(record.repo.as_deref());
+            let index = match repos.iter().position(|(repo, _)| *repo == label) {
+                Some(index) => index,
+                None => {
+                    repos.push((label, Default::default()));
+                    repos.len() - 1
+                }
+            };
+            let total = &mut repos[index].1[record.phase as usize];
+            total.calls += 1;
+            total.time += time;
+        }
+        repos.sort_by_key(|(repo, totals)| {
+            let time: Duration = totals.iter().map(|t| t.time).sum();
+            (std::cmp::Reverse(time), repo.clone())
+        });
+        repos
+    }
+
+    /// Time in each span minus the spans nested directly inside it
+    fn self_times(&self) -> Vec<Duration> {
+        let mut times: Vec<Duration> = self.records.iter().map(|r| r.duration).collect();
+        let mut order: Vec<usize> = (0..self.records.len()).collect();
+        order.sort_by_key(|&i| {
+            let r = &self.records[i];
+            (r.thread, r.start, std::cmp::Reverse(r.duration))
+        });
+        // Enclosing spans on the current thread
+        let mut stack: Vec<usize> = Vec::new();
+        let mut thread = None;
+        for i in order {
+            let record = &self.records[i];
+            if thread != Some(record.thread) {
+                stack.clear();
+                thread = Some(record.thread);
+            }
+            while let Some(&top) = stack.last() {
+                let parent = &self.records[top];
+                if parent.start + parent.duration > record.start {
+                    break;
+                }
+                stack.pop();
+            }
+            if let Some(&parent) = stack.last() {
+                times[parent] = times[parent].saturating_sub(record.duration);
+            }
+            stack.push(i);
+        }
+        times
+    }
+
+    fn repo_label(&self, repo: Option<&Path>) -> String {
+        let relative = match (repo, &self.root) {
+            (Some(repo), Some(root)) => repo.strip_prefix(root).ok(),
+            (Some(repo), None) => Some(repo),
+            (None, _) => None,
+        };
+        match relative {
+            Some(path) if !path.as_os_str().is_empty() => path.display().to_string(),
+            _ => "(workspace)".to_string(),
+        }
+    }
+
+    /// Summary table, to stderr so it never mixes with command output
+    pub fn print(&self) {
+        let repos = self.by_repo();
+        let width = repos
+            .iter()
+            .map(|(repo, _)| repo.len())
+            .max()
+            .unwrap_or(0)
+            .max("repo".len());
+        eprintln!();
+        eprintln!("Profile: {} ({})", self.command, format_ms(self.total));
+        let mut header = format!("{:<width$}", "repo", width = width);
+        for phase in Phase::ALL {
+            header.push_str(&format!("  {:>16}", phase.name()));
+        }
+        eprintln!("{}", header);
+        for (repo, totals) in &repos {
+            let mut line = format!("{:<width$}", repo, width = width);
+            for total in totals {
+                let cell = if total.calls == 0 {
+                    "-".to_string()
+                } else {
+                    format!("{} ({})", format_ms(total.time), total.calls)
+                };
+                line.push_str(&format!("  {:>16}", cell));
+            }
+            eprintln!("{}", line);
+        }
+        if repos.is_empty() {
+            eprintln!("(nothing recorded)");
+        }
+    }
+
+    /// The spans in Chrome's trace event format, under one span for the
+    /// whole command
+    pub fn trace_json(&self) -> serde_json::Value {
+        let micros = |d: Duration| d.as_micros() as u64;
+        let mut events = vec![json!({
+            "name": self.command,
+            "cat": "command",
+            "ph": "X",
+            "ts": 0,
+            "dur": micros(self.total),
+            "pid": 1,
+            "tid": self.main_thread,
+        })];
+        let mut records: Vec<&Record> = self.records.iter().collect();
+        records.sort_by_key(|r| (r.start, std::cmp::Reverse(r.duration)));
+        for record in records {
+            events.push(json!({
+                "name": record.name,
+                "cat": record.phase.name(),
+                "ph": "X",
+                "ts": micros(record.start),
+                "dur": micros(record.duration),
+                "pid": 1,
+                "tid": record.thread,
+                "args": { "repo": self.repo_label(record.repo.as_deref()) },
+            }));
+        }
+        let mut threads: Vec<u64> = self.records.iter().map(|r| r.thread).collect();
+        threads.push(self.main_thread);
+        threads.sort_unstable();
+        threads.dedup();
+        for thread in threads {
+            let name = if thread == self.main_thread {
+                "main".to_string()
+            } else {
+                format!("worker {}", thread)
+            };
+            events.push(json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": 1,
+                "tid": thread,
+                "args": { "name": name },
+            }));
+        }
+        json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+    }
+
+    /// Write the trace to `.gitgrip/profile.json` in the workspace, or the
+    /// temp directory outside one
+    pub fn save(&self) -> std::io::Result<PathBuf> {
+        let path = match self.root {
+            Some(ref root) => root.join(".gitgrip").join("profile.json"),
+            None => std::env::temp_dir().join("gr-profile.json"),
+        };
+        let json = serde_json::to_string(&self.trace_json()).map_err(std::io::Error::other)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(phase: Phase, repo: &str, thread: u64, start: u64, duration: u64) -> Record {
+        Record {
+            phase,
+            name: phase.name().to_string(),
+            repo: Some(PathBuf::from("/ws").join(repo)),
+            thread,
+            start: Duration::from_millis(start),
+            duration: Duration::from_millis(duration),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let mut command = Command::new("git");
+        command.args(["-C", "api", "-c", "a=b", "fetch", "origin"]);
+        assert_eq!(git_subcommand(command.get_args()).as_deref(), Some("fetch"));
+
+        // api: status scan of 10ms running `git status` for 6ms and a 1ms
+        // object lookup; web: a 5ms fetch on another thread
+        let report = Report {
+            command: "gr status".to_string(),
+            root: Some(PathBuf::from("/ws")),
+            total: Duration::from_millis(20),
+            main_thread: 1,
+            records: vec![
+                record(Phase::Process, "api", 2, 1, 6),
+                record(Phase::Status, "api", 2, 0, 10),
+                record(Phase::Git, "api", 2, 8, 1),
+                record(Phase::Network, "web", 3, 0, 5),
+            ],
+        };
+        let repos = report.by_repo();
+        assert_eq!(repos[0].0, "api");
+        let api = repos[0].1;
+        assert_eq!(api[Phase::Status as usize].time, Duration::from_millis(3));
+        assert_eq!(api[Phase::Process as usize].time, Duration::from_millis(6));
+        assert_eq!(api[Phase::Git as usize].calls, 1);
+        assert_eq!(repos[1].0, "web");
+        assert_eq!(repos[1].1[Phase::Network as usize].calls, 1);
+
+        let trace = report.trace_json();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events[0]["name"], "gr status");
+        assert_eq!(events[1]["cat"], "status");
+        assert_eq!(events[1]["args"]["repo"], "api");
+        assert_eq!(events[1]["dur"], 10_000);
+        assert!(events.iter().any(|e| e["ph"] == "M" && e["tid"] == 3));
+    }
+}
@@ -0,0 +1,105 @@
+//! Cross-platform shell command construction
+//!
+//! Builds a `Command` that runs a command string through the configured shell
+//! (`sh -c`, `cmd /C`, PowerShell, ...).
+
+use std::process::Command;
+
+use crate::core::manifest::ShellKind;
+
+/// Build a `Command` that runs `command` through the given shell
+pub fn shell_command(shell: ShellKind, command: &str) -> Command {
+    match shell {
+        ShellKind::Sh => posix_command("sh", command),
+        ShellKind::Bash => posix_command("bash", command),
+        ShellKind::Cmd => cmd_command(command),
+        ShellKind::Powershell => powershell_command("powershell", command),
+        ShellKind::Pwsh => powershell_command("pwsh", command),
+    }
+}
+
+/// Quote `arg` so the given shell passes it through as a single word
+pub fn quote_arg(shell: ShellKind, arg: &str) -> String {
+    match shell {
+        ShellKind::Sh | ShellKind::Bash => format!("'{}'", arg.replace('\'', "'\\''")),
+        ShellKind::Cmd => format!("\"{}\"", arg.replace('"', "\"\"")),
+        ShellKind::Powershell | ShellKind::Pwsh => format!("'{}'", arg.replace('\'', "''")),
+    }
+}
+
+fn posix_command(program: &str, command: &str) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+fn cmd_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+
+    // cmd.exe does its own parsing of the command line, so the standard
+    // MSVC-style argument quoting would mangle embedded quotes.
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.raw_arg("/S /C \"").raw_arg(command).raw_arg("\"");
+    }
+
+    #[cfg(not(windows))]
+    {
+        cmd.arg("/C").arg(command);
+    }
+
+    cmd
+}
+
+fn powershell_command(program: &str, command: &str) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", command]);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args_of(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_sh_command() {
+        let cmd = shell_command(ShellKind::Sh, "echo hi");
+        assert_eq!(cmd.get_program(), "sh");
+        assert_eq!(args_of(&cmd), vec!["-c", "echo hi"]);
+    }
+
+    #[test]
+    fn test_powershell_command() {
+        let cmd = shell_command(ShellKind::Pwsh, "Get-ChildItem");
+        assert_eq!(cmd.get_program(), "pwsh");
+        assert_eq!(args_of(&cmd).last().unwrap(), "Get-ChildItem");
+    }
+
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(quote_arg(ShellKind::Sh, "a b"), "'a b'");
+        assert_eq!(quote_arg(ShellKind::Bash, "it's"), "'it'\\''s'");
+        assert_eq!(quote_arg(ShellKind::Pwsh, "it's"), "'it''s'");
+        assert_eq!(
+            quote_arg(ShellKind::Cmd, "say \"hi\""),
+            "\"say \"\"hi\"\"\""
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sh_env_passing() {
+        let output = shell_command(ShellKind::Sh, "echo \"$REPO_NAME\"")
+            .env("REPO_NAME", "my repo")
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "my repo");
+    }
+}
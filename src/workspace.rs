@@ -0,0 +1,638 @@
+//! Embeddable workspace API
+//!
+//! [`Workspace`] is the stable entry point for driving gitgrip from other
+//! Rust programs. It wraps the manifest and workspace root and exposes the
+//! core operations (status, sync, branches, pull requests) without any
+//! terminal output or CLI types, so callers decide how to present results.
+//!
+//! ```no_run
+//! use gitgrip::workspace::Workspace;
+//!
+//! let workspace = Workspace::discover(std::env::current_dir()?)?;
+//! for status in workspace.status() {
+//!     println!("{}: {} (clean: {})", status.name, status.branch, status.clean);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Operations that touch several repos return one [`RepoOutcome`] per repo,
+//! in repo name order, so a failure in one repo never hides the others.
+
+use crate::core::griptree::GriptreePointer;
+use crate::core::lock::{acquire_all, LockError, RepoLock};
+use crate::core::manifest::{Manifest, ManifestError};
+use crate::core::repo::RepoInfo;
+use crate::core::state::StateFile;
+use crate::git::branch::{
+    branch_exists, checkout_branch, create_and_checkout_branch, has_commits_ahead,
+    list_local_branches,
+};
+use crate::git::remote::safe_pull_latest;
+use crate::git::revision::{checkout_revision, resolve_revision, sync_revision};
+use crate::git::sparse::clone_sparse;
+use crate::git::status::{blocking_operation, get_all_repo_status, RepoStatus};
+use crate::git::{clone_repo, get_current_branch, open_repo, GitError};
+use crate::platform::traits::PlatformError;
+use crate::platform::{get_platform_adapter, HostingPlatform, PRCreateResult, PullRequest};
+use crate::util::concurrent_map;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long mutating operations wait for another process's repo locks
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors returned by [`Workspace`] operations
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("Not in a gitgrip workspace (no .gitgrip directory found)")]
+    NotFound,
+
+    #[error(
+        "Griptree points to main workspace '{workspace}' but manifest not found at '{manifest}'"
+    )]
+    MissingGriptreeManifest { workspace: String, manifest: String },
+
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+
+    #[error(transparent)]
+    Git(#[from] GitError),
+
+    #[error(transparent)]
+    Platform(#[from] PlatformError),
+
+    #[error("{0}")]
+    Blocked(String),
+
+    #[error("Repository '{0}' is not cloned")]
+    NotCloned(String),
+
+    #[error("{0}")]
+    Locked(String),
+}
+
+/// The result of an operation on a single repo
+///
+/// Serializes as `{"repo": ..., "result": ...}` or `{"repo": ..., "error": "..."}`.
+#[derive(Debug)]
+pub struct RepoOutcome<T> {
+    /// Repository name (from manifest)
+    pub repo: String,
+    /// What happened in that repo
+    pub result: Result<T, WorkspaceError>,
+}
+
+impl<T: Serialize> Serialize for RepoOutcome<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("repo", &self.repo)?;
+        match &self.result {
+            Ok(value) => map.serialize_entry("result", value)?,
+            Err(e) => map.serialize_entry("error", &e.to_string())?,
+        }
+        map.end()
+    }
+}
+
+/// What [`Workspace::sync`] did to a repo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", content = "message", rename_all = "lowercase")]
+pub enum SyncAction {
+    /// The repo was missing and has been cloned
+    Cloned,
+    /// The current branch was pulled (or was already up to date)
+    Pulled,
+    /// The repo was switched back to its default branch because its
+    /// upstream branch was deleted, then pulled
+    Recovered(String),
+    /// Nothing was pulled, with the reason
+    Skipped(String),
+}
+
+/// Branches of a single repo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepoBranches {
+    /// Checked-out branch
+    pub current: String,
+    /// All local branches
+    pub local: Vec<String>,
+}
+
+/// A gitgrip workspace: a root directory plus its manifest
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+    manifest: Manifest,
+    lock_timeout: Duration,
+}
+
+impl Workspace {
+    /// Find the workspace containing `start` by walking up to the nearest
+    /// `.gitgrip` directory
+    ///
+    /// Inside a griptree, the manifest comes from the main workspace and the
+    /// griptree directory is the root.
+    pub fn discover(start: impl AsRef<Path>) -> Result<Self, WorkspaceError> {
+        let (root, manifest_path) = Self::locate(start)?;
+        Self::load(root, &manifest_path)
+    }
+
+    /// Find the workspace root and manifest file for `start`, as in
+    /// [`Workspace::discover`], without parsing the manifest
+    pub fn locate(start: impl AsRef<Path>) -> Result<(PathBuf, PathBuf), WorkspaceError> {
+        let start = start.as_ref();
+
+        if let Some((griptree_path, pointer)) = GriptreePointer::find_in_ancestors(start) {
+            let manifest_path = manifest_path(Path::new(&pointer.main_workspace));
+            if !manifest_path.exists() {
+                return Err(WorkspaceError::MissingGriptreeManifest {
+                    workspace: pointer.main_workspace,
+                    manifest: manifest_path.display().to_string(),
+                });
+            }
+            return Ok((griptree_path, manifest_path));
+        }
+
+        start
+            .ancestors()
+            .find(|dir| manifest_path(dir).exists())
+            .map(|dir| (dir.to_path_buf(), manifest_path(dir)))
+            .ok_or(WorkspaceError::NotFound)
+    }
+
+    /// Open the workspace rooted at `root` (the directory holding `.gitgrip`)
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, WorkspaceError> {
+        let root = root.as_ref();
+        let path = manifest_path(root);
+        if !path.exists() {
+            return Err(WorkspaceError::NotFound);
+        }
+        Self::load(root.to_path_buf(), &path)
+    }
+
+    /// Load the manifest, narrowed to the workspace's profile if it has one
+    fn load(root: PathBuf, manifest_path: &Path) -> Result<Self, WorkspaceError> {
+        let mut manifest = Manifest::load_for_workspace(manifest_path, &root)?;
+        let state = StateFile::load(root.join(".gitgrip").join("state.json")).unwrap_or_default();
+        if let Some(ref profile) = state.profile {
+            manifest.apply_profile(profile)?;
+        }
+        Ok(Self::new(root, manifest))
+    }
+
+    /// Wrap an already-loaded manifest
+    pub fn new(root: PathBuf, manifest: Manifest) -> Self {
+        Self {
+            root,
+            manifest,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+        }
+    }
+
+    /// How long [`sync`](Self::sync), [`create_branch`](Self::create_branch)
+    /// and [`checkout`](Self::checkout) wait for repo locks held by another
+    /// process (30 seconds by default)
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// The workspace root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The parsed manifest
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Split into root and manifest
+    pub fn into_parts(self) -> (PathBuf, Manifest) {
+        (self.root, self.manifest)
+    }
+
+    /// Workspace state (PR links, campaigns); empty if none has been saved
+    pub fn state(&self) -> StateFile {
+        StateFile::load(self.root.join(".gitgrip").join("state.json")).unwrap_or_default()
+    }
+
+    /// All repos in the manifest, sorted by name
+    pub fn repos(&self) -> Vec<RepoInfo> {
+        let mut repos: Vec<RepoInfo> = self
+            .manifest
+            .repos
+            .iter()
+            .filter_map(|(name, config)| RepoInfo::from_config(name, config, &self.root))
+            .collect();
+        repos.sort_by(|a, b| a.name.cmp(&b.name));
+        repos
+    }
+
+    /// A single repo by manifest name
+    pub fn repo(&self, name: &str) -> Option<RepoInfo> {
+        let config = self.manifest.repos.get(name)?;
+        RepoInfo::from_config(name, config, &self.root)
+    }
+
+    /// Working tree status of every repo, in name order
+    pub fn status(&self) -> Vec<RepoStatus> {
+        get_all_repo_status(&self.repos())
+    }
+
+    /// Clone missing repos and pull existing ones
+    ///
+    /// Repos with a manifest `revision:` are brought to that revision, as
+    /// `gr sync` does. Repos mid-merge/rebase are reported as
+    /// [`WorkspaceError::Blocked`].
+    pub fn sync(&self) -> Vec<RepoOutcome<SyncAction>> {
+        let _locks = match self.lock_repos() {
+            Ok(locks) => locks,
+            Err(e) => return self.all_failed(&e),
+        };
+        self.repos()
+            .into_iter()
+            .map(|repo| RepoOutcome {
+                result: self.sync_repo(&repo),
+                repo: repo.name,
+            })
+            .collect()
+    }
+
+    fn sync_repo(&self, repo: &RepoInfo) -> Result<SyncAction, WorkspaceError> {
+        let config = self.manifest.repos.get(&repo.name);
+        let revision = config.and_then(|config| config.revision.as_deref());
+
+        if !repo.absolute_path.exists() {
+            let sparse = config.and_then(|config| config.sparse.as_deref());
+            match sparse {
+                Some(patterns) => clone_sparse(
+                    &repo.url,
+                    &repo.absolute_path,
+                    Some(&repo.default_branch),
+                    patterns,
+                    None,
+                )?,
+                None => {
+                    clone_repo(
+                        &repo.url,
+                        &repo.absolute_path,
+                        Some(&repo.default_branch),
+                        None,
+                    )?;
+                }
+            }
+            if let Some(revision) = revision {
+                let git_repo = open_repo(&repo.absolute_path)?;
+                let target = resolve_revision(&git_repo, revision, "origin")?;
+                checkout_revision(&git_repo, &target, "origin")?;
+            }
+            return Ok(SyncAction::Cloned);
+        }
+
+        let git_repo = open_repo(&repo.absolute_path)?;
+        if let Some(reason) = blocking_operation(&git_repo) {
+            return Err(WorkspaceError::Blocked(reason));
+        }
+
+        let pull = match revision {
+            Some(revision) => sync_revision(&git_repo, &repo.default_branch, revision)?,
+            None => safe_pull_latest(&git_repo, &repo.default_branch, "origin")?,
+        };
+        Ok(match (pull.pulled, pull.recovered, pull.message) {
+            (true, true, message) => SyncAction::Recovered(message.unwrap_or_default()),
+            (false, _, Some(message)) => SyncAction::Skipped(message),
+            _ => SyncAction::Pulled,
+        })
+    }
+
+    /// Current and local branches of every cloned repo
+    pub fn branches(&self) -> Vec<RepoOutcome<RepoBranches>> {
+        self.for_each_cloned(|git_repo| {
+            Ok(RepoBranches {
+                current: get_current_branch(git_repo)?,
+                local: list_local_branches(git_repo)?,
+            })
+        })
+    }
+
+    /// Create `branch` from the current HEAD and check it out in every cloned repo
+    pub fn create_branch(&self, branch: &str) -> Vec<RepoOutcome<()>> {
+        let _locks = match self.lock_repos() {
+            Ok(locks) => locks,
+            Err(e) => return self.all_failed(&e),
+        };
+        self.for_each_cloned(|git_repo| Ok(create_and_checkout_branch(git_repo, branch)?))
+    }
+
+    /// Check out an existing `branch` in every cloned repo that has it
+    ///
+    /// Repos without the branch report [`GitError::BranchNotFound`].
+    pub fn checkout(&self, branch: &str) -> Vec<RepoOutcome<()>> {
+        let _locks = match self.lock_repos() {
+            Ok(locks) => locks,
+            Err(e) => return self.all_failed(&e),
+        };
+        self.for_each_cloned(|git_repo| {
+            if !branch_exists(git_repo, branch) {
+                return Err(GitError::BranchNotFound(branch.to_string()).into());
+            }
+            Ok(checkout_branch(git_repo, branch)?)
+        })
+    }
+
+    /// Take every repo's lock, as the CLI's mutating commands do
+    fn lock_repos(&self) -> Result<Vec<RepoLock>, LockError> {
+        acquire_all(
+            &self.root,
+            self.manifest.repos.keys().map(String::as_str),
+            self.lock_timeout,
+            |_| {},
+        )
+    }
+
+    /// Report a failure to take the locks against every repo
+    fn all_failed<T>(&self, error: &LockError) -> Vec<RepoOutcome<T>> {
+        self.repos()
+            .into_iter()
+            .map(|repo| RepoOutcome {
+                repo: repo.name,
+                result: Err(WorkspaceError::Locked(error.to_string())),
+            })
+            .collect()
+    }
+
+    fn for_each_cloned<T>(
+        &self,
+        f: impl Fn(&git2::Repository) -> Result<T, WorkspaceError>,
+    ) -> Vec<RepoOutcome<T>> {
+        self.repos()
+            .into_iter()
+            .map(|repo| {
+                let result = if repo.exists() {
+                    open_repo(&repo.absolute_path)
+                        .map_err(WorkspaceError::from)
+                        .and_then(|git_repo| f(&git_repo))
+                } else {
+                    Err(WorkspaceError::NotCloned(repo.name.clone()))
+                };
+                RepoOutcome {
+                    repo: repo.name,
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    /// The open PR for the current branch of every repo on a feature branch
+    ///
+    /// Requests run concurrently, capped by `settings.api_concurrency`.
+    pub async fn pull_requests(&self) -> Vec<RepoOutcome<Option<PullRequest>>> {
+        let branches = self.feature_branches();
+        let results = concurrent_map(
+            &branches,
+            self.manifest.settings.api_concurrency,
+            |(repo, branch)| async move {
+                let platform = self.platform_for(repo);
+                match platform
+                    .find_pr_by_branch(&repo.owner, &repo.repo, branch)
+                    .await?
+                {
+                    Some(pr) => Ok(Some(
+                        platform
+                            .get_pull_request(&repo.owner, &repo.repo, pr.number)
+                            .await?,
+                    )),
+                    None => Ok(None),
+                }
+            },
+        )
+        .await;
+        outcomes(branches, results)
+    }
+
+    /// Open a PR for every repo whose feature branch has commits ahead of
+    /// its default branch
+    ///
+    /// Branches must already be pushed.
+    pub async fn create_pull_requests(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        draft: bool,
+    ) -> Vec<RepoOutcome<PRCreateResult>> {
+        let branches: Vec<(RepoInfo, String)> = self
+            .feature_branches()
+            .into_iter()
+            .filter(|(repo, _)| {
+                open_repo(&repo.absolute_path)
+                    .and_then(|r| has_commits_ahead(&r, &repo.default_branch))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        let results = concurrent_map(
+            &branches,
+            self.manifest.settings.api_concurrency,
+            |(repo, branch)| async move {
+                Ok(self
+                    .platform_for(repo)
+                    .create_pull_request(
+                        &repo.owner,
+                        &repo.repo,
+                        branch,
+                        &repo.default_branch,
+                        title,
+                        body,
+                        draft,
+                    )
+                    .await?)
+            },
+        )
+        .await;
+        outcomes(branches, results)
+    }
+
+    /// Cloned repos that are not on their default branch, with that branch
+    fn feature_branches(&self) -> Vec<(RepoInfo, String)> {
+        self.repos()
+            .into_iter()
+            .filter(|repo| repo.exists())
+            .filter_map(|repo| {
+                let branch = get_current_branch(&open_repo(&repo.absolute_path).ok()?).ok()?;
+                (branch != repo.default_branch).then_some((repo, branch))
+            })
+            .collect()
+    }
+
+    fn platform_for(&self, repo: &RepoInfo) -> Arc<dyn HostingPlatform> {
+        let base_url = self
+            .manifest
+            .repos
+            .get(&repo.name)
+            .and_then(|config| config.platform.as_ref())
+            .and_then(|platform| platform.base_url.as_deref());
+        get_platform_adapter(repo.platform_type, base_url)
+    }
+}
+
+fn manifest_path(root: &Path) -> PathBuf {
+    root.join(".gitgrip")
+        .join("manifests")
+        .join("manifest.yaml")
+}
+
+fn outcomes<T>(
+    branches: Vec<(RepoInfo, String)>,
+    results: Vec<Result<T, WorkspaceError>>,
+) -> Vec<RepoOutcome<T>> {
+    branches
+        .into_iter()
+        .zip(results)
+        .map(|((repo, _), result)| RepoOutcome {
+            repo: repo.name,
+            result,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::testing::{git, init_repo};
+    use tempfile::TempDir;
+
+    fn setup_workspace() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let manifests = root.join(".gitgrip").join("manifests");
+        std::fs::create_dir_all(&manifests).unwrap();
+        std::fs::write(
+            manifests.join("manifest.yaml"),
+            r#"
+repos:
+  web:
+    url: git@github.com:acme/web.git
+    path: web
+  api:
+    url: git@github.com:acme/api.git
+    path: services/api
+"#,
+        )
+        .unwrap();
+
+        let api = root.join("services/api");
+        init_repo(&api);
+        git(
+            &api,
+            &["commit", "-q", "--allow-empty", "-m", "Initial commit"],
+        );
+        temp
+    }
+
+    #[test]
+    fn test_discover_from_nested_directory() {
+        let temp = setup_workspace();
+        let workspace = Workspace::discover(temp.path().join("services/api")).unwrap();
+        assert_eq!(workspace.root(), temp.path());
+
+        let names: Vec<String> = workspace.repos().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["api", "web"]);
+
+        let outside = TempDir::new().unwrap();
+        assert!(matches!(
+            Workspace::discover(outside.path()),
+            Err(WorkspaceError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_mutations_wait_for_repo_locks() {
+        let temp = setup_workspace();
+        let workspace = Workspace::open(temp.path())
+            .unwrap()
+            .with_lock_timeout(Duration::ZERO);
+
+        let held = acquire_all(temp.path(), ["api"], Duration::ZERO, |_| {}).unwrap();
+        let created = workspace.create_branch("feat/locked");
+        assert!(created
+            .iter()
+            .all(|o| matches!(o.result, Err(WorkspaceError::Locked(_)))));
+        assert_eq!(
+            git(
+                &temp.path().join("services/api"),
+                &["branch", "--show-current"]
+            ),
+            "main"
+        );
+
+        drop(held);
+        let created = workspace.create_branch("feat/locked");
+        assert!(created.iter().any(|o| o.repo == "api" && o.result.is_ok()));
+    }
+
+    #[test]
+    fn test_branch_ops_report_per_repo() {
+        let temp = setup_workspace();
+        let workspace = Workspace::open(temp.path()).unwrap();
+
+        let created = workspace.create_branch("feat/embed");
+        assert_eq!(created.len(), 2);
+        assert!(created[0].result.is_ok());
+        assert!(matches!(
+            created[1].result,
+            Err(WorkspaceError::NotCloned(ref name)) if name == "web"
+        ));
+
+        let statuses = workspace.status();
+        assert_eq!(statuses[0].branch, "feat/embed");
+        assert!(!statuses[1].exists);
+
+        let branches = workspace.branches();
+        let api = branches[0].result.as_ref().unwrap();
+        assert_eq!(api.current, "feat/embed");
+        assert_eq!(api.local, vec!["feat/embed", "main"]);
+
+        assert!(workspace.checkout("main")[0].result.is_ok());
+        assert!(matches!(
+            workspace.checkout("missing")[0].result,
+            Err(WorkspaceError::Git(GitError::BranchNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_sync_follows_revision_pin() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let upstream = root.join("upstream");
+        init_repo(&upstream);
+        git(
+            &upstream,
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        );
+        git(&upstream, &["tag", "v1.0.0"]);
+        git(&upstream, &["commit", "-q", "--allow-empty", "-m", "Next"]);
+        git(root, &["clone", "-q", upstream.to_str().unwrap(), "api"]);
+
+        let manifests = root.join(".gitgrip").join("manifests");
+        std::fs::create_dir_all(&manifests).unwrap();
+        std::fs::write(
+            manifests.join("manifest.yaml"),
+            "repos:\n  api:\n    url: git@github.com:acme/api.git\n    path: api\n    revision: v1.0.0\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::open(root).unwrap();
+        let synced = workspace.sync();
+        assert!(synced[0].result.is_ok(), "{:?}", synced[0].result);
+
+        let repo = open_repo(root.join("api")).unwrap();
+        assert!(repo.head_detached().unwrap());
+        let tag = repo.revparse_single("v1.0.0^{commit}").unwrap().id();
+        assert_eq!(repo.head().unwrap().target(), Some(tag));
+    }
+}
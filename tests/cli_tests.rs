@@ -74,3 +74,30 @@ fn test_bench_json() {
         .success()
         .stdout(predicate::str::starts_with("["));
 }
+
+/// Test that `gr bisect` and `gr prune` refuse to run while a repo is locked
+#[test]
+fn test_bisect_and_prune_respect_repo_locks() {
+    let temp = TempDir::new().unwrap();
+    let manifests = temp.path().join(".gitgrip").join("manifests");
+    std::fs::create_dir_all(&manifests).unwrap();
+    std::fs::write(
+        manifests.join("manifest.yaml"),
+        "repos:\n  app:\n    url: git@github.com:acme/app.git\n    path: app\n",
+    )
+    .unwrap();
+
+    // This test process plays the first `gr` holding the lock
+    let _held = gitgrip::core::lock::acquire(temp.path(), "app", std::time::Duration::ZERO, |_| {})
+        .unwrap();
+
+    for args in [&["bisect", "reset"][..], &["prune", "--dry-run"][..]] {
+        let mut cmd = Command::cargo_bin("gr").unwrap();
+        cmd.current_dir(temp.path())
+            .args(["--lock-timeout", "0"])
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Repo 'app' is locked by"));
+    }
+}
@@ -0,0 +1,211 @@
+//! Git output parity tests
+//!
+//! `gr forall` answers common read-only git commands with git2 instead of
+//! spawning git. These tests build random repo states, run every
+//! intercepted command both ways, and require identical output, so the fast
+//! path can be extended without drifting from the git CLI.
+
+use gitgrip::cli::commands::forall::run_intercepted;
+use proptest::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+const FILES: &[&str] = &[
+    "README.md",
+    "notes.txt",
+    "src/lib.rs",
+    "src/main.rs",
+    "docs/guide.md",
+];
+const CONTENTS: &[&str] = &["", "hello\n", "one\ntwo\nthree\n", "fn main() {}\n"];
+const BRANCHES: &[&str] = &["feature", "fix/login", "release-1.0"];
+const TAGS: &[&str] = &["v1.0.0", "v1.1.0", "beta"];
+
+/// Commands whose fast-path output must match git byte for byte
+const COMMANDS: &[&str] = &[
+    "git status --porcelain",
+    "git status -s",
+    "git branch",
+    "git rev-parse HEAD",
+    "git rev-parse --abbrev-ref HEAD",
+    "git rev-parse --short HEAD",
+    "git log --oneline",
+    "git log --oneline -n 3",
+    "git log -1 --oneline",
+    "git diff --name-only",
+    "git diff --cached --name-only",
+    "git diff --name-status",
+    "git ls-files",
+    "git tag",
+    "git remote",
+    "git remote -v",
+    "git config --get user.name",
+];
+
+/// One change to a repo's state
+#[derive(Debug, Clone)]
+enum Op {
+    Write { file: usize, content: usize },
+    Append { file: usize },
+    Delete { file: usize },
+    Move { from: usize, to: usize },
+    Stage { file: usize },
+    StageAll,
+    Commit,
+    Branch { name: usize },
+    Switch { name: usize },
+    Tag { name: usize },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..FILES.len(), 0..CONTENTS.len())
+            .prop_map(|(file, content)| Op::Write { file, content }),
+        2 => (0..FILES.len()).prop_map(|file| Op::Append { file }),
+        1 => (0..FILES.len()).prop_map(|file| Op::Delete { file }),
+        1 => (0..FILES.len(), 0..FILES.len()).prop_map(|(from, to)| Op::Move { from, to }),
+        2 => (0..FILES.len()).prop_map(|file| Op::Stage { file }),
+        1 => Just(Op::StageAll),
+        2 => Just(Op::Commit),
+        1 => (0..BRANCHES.len()).prop_map(|name| Op::Branch { name }),
+        1 => (0..BRANCHES.len() + 1).prop_map(|name| Op::Switch { name }),
+        1 => (0..TAGS.len()).prop_map(|name| Op::Tag { name }),
+    ]
+}
+
+/// Run git in `dir`, returning whether it succeeded and its stdout
+fn git(dir: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_DATE", "2024-01-01T00:00:00Z")
+        .env("GIT_COMMITTER_DATE", "2024-01-01T00:00:00Z")
+        .output()
+        .expect("failed to run git");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )
+}
+
+/// A repo on `main` with one commit and an origin remote
+fn init_repo(temp: &TempDir) -> PathBuf {
+    let dir = temp.path().join("repo");
+    std::fs::create_dir_all(&dir).unwrap();
+    for args in [
+        &["init", "-q", "-b", "main"][..],
+        &["config", "user.name", "Parity Test"],
+        &["config", "user.email", "parity@example.com"],
+        &["config", "commit.gpgsign", "false"],
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://example.com/org/repo.git",
+        ],
+    ] {
+        assert!(git(&dir, args).0, "git {:?} failed", args);
+    }
+    std::fs::write(dir.join("README.md"), "# Repo\n").unwrap();
+    git(&dir, &["add", "README.md"]);
+    assert!(git(&dir, &["commit", "-q", "-m", "Initial commit"]).0);
+    dir
+}
+
+/// Apply `op` with the git CLI; ops that git refuses (e.g. switching over
+/// local changes) just leave the state as it was
+fn apply(dir: &Path, op: &Op, commits: &mut usize) {
+    match op {
+        Op::Write { file, content } => {
+            let path = dir.join(FILES[*file]);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, CONTENTS[*content]).unwrap();
+        }
+        Op::Append { file } => {
+            let path = dir.join(FILES[*file]);
+            if let Ok(mut content) = std::fs::read_to_string(&path) {
+                content.push_str("more\n");
+                std::fs::write(path, content).unwrap();
+            }
+        }
+        Op::Delete { file } => {
+            let _ = std::fs::remove_file(dir.join(FILES[*file]));
+        }
+        Op::Move { from, to } => {
+            if let Some(parent) = dir.join(FILES[*to]).parent() {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            git(dir, &["mv", FILES[*from], FILES[*to]]);
+        }
+        Op::Stage { file } => {
+            git(dir, &["add", "-A", "--", FILES[*file]]);
+        }
+        Op::StageAll => {
+            git(dir, &["add", "-A"]);
+        }
+        Op::Commit => {
+            *commits += 1;
+            let message = format!("Change {}", commits);
+            git(dir, &["commit", "-q", "--allow-empty", "-m", &message]);
+        }
+        Op::Branch { name } => {
+            git(dir, &["branch", BRANCHES[*name]]);
+        }
+        Op::Switch { name } => {
+            let branch = BRANCHES.get(*name).copied().unwrap_or("main");
+            git(dir, &["switch", "-q", branch]);
+        }
+        Op::Tag { name } => {
+            git(dir, &["tag", TAGS[*name]]);
+        }
+    }
+}
+
+/// Differences between the fast path and git for every command in `COMMANDS`
+fn mismatches(dir: &PathBuf) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for command in COMMANDS {
+        let fast = run_intercepted(dir, command)
+            .unwrap_or_else(|| panic!("'{}' is no longer intercepted", command));
+        let args: Vec<&str> = command.split_whitespace().skip(1).collect();
+        let (ok, expected) = git(dir, &args);
+        match fast {
+            Ok(actual) if ok && actual == expected => {}
+            Err(_) if !ok => {}
+            other => mismatches.push(format!(
+                "{}\n--- git ({}):\n{}--- fast path:\n{:?}",
+                command,
+                if ok { "ok" } else { "failed" },
+                expected,
+                other
+            )),
+        }
+    }
+    mismatches
+}
+
+#[test]
+fn test_parity_on_fresh_repo() {
+    let temp = TempDir::new().unwrap();
+    let dir = init_repo(&temp);
+    let mismatches = mismatches(&dir);
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n\n"));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn test_parity_on_random_repo_states(ops in prop::collection::vec(op(), 1..16)) {
+        let temp = TempDir::new().unwrap();
+        let dir = init_repo(&temp);
+        let mut commits = 0;
+        for op in &ops {
+            apply(&dir, op, &mut commits);
+        }
+        let mismatches = mismatches(&dir);
+        prop_assert!(mismatches.is_empty(), "{}", mismatches.join("\n\n"));
+    }
+}